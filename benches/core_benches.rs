@@ -0,0 +1,151 @@
+//! Criterion benchmarks for the hot paths behind the TUI and the agent
+//! loop: markdown rendering, transcript line building, token estimation,
+//! and ChatRequest serialization. Gated behind the `bench-internals`
+//! feature so criterion never enters normal builds:
+//!
+//!     cargo bench --features bench-internals
+//!
+//! The config below (small sample count, short measurement window, 5%
+//! noise threshold) keeps a full run under a minute so it can serve as a
+//! CI baseline; criterion reports regressions beyond the noise threshold
+//! against the saved baseline in target/criterion.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use llminate::ai::client::ChatRequestBuilder;
+use llminate::ai::summarization::estimate_tokens;
+use llminate::ai::{Message, MessageContent, MessageRole, Tool};
+use llminate::tui::markdown::parse_markdown;
+use std::time::Duration;
+
+/// A realistic large assistant message: prose, lists, inline code, and a
+/// fenced block, repeated until it is a few thousand lines long
+fn large_markdown() -> String {
+    let section = "## Analysis\n\n\
+        The `parse_sse_stream` function buffers **partial chunks** until a\n\
+        complete event arrives. Key points:\n\n\
+        - Events are separated by `\\n\\n`\n\
+        - Multiple `data:` fields concatenate with a newline\n\
+        - Comments (lines starting with `:`) are ignored\n\n\
+        ```rust\n\
+        while let Some(boundary) = buffer.find(\"\\n\\n\") {\n\
+            let event: String = buffer.drain(..=boundary + 1).collect();\n\
+            process(&event);\n\
+        }\n\
+        ```\n\n";
+    section.repeat(100)
+}
+
+/// A long mixed transcript, shaped like what rebuild_cache walks
+fn transcript(messages: usize) -> Vec<(String, String)> {
+    (0..messages)
+        .map(|i| match i % 3 {
+            0 => ("user".to_string(), format!("Please look into issue #{}", i)),
+            1 => (
+                "assistant".to_string(),
+                format!(
+                    "Looking at **module {}**:\n\n- the parser\n- the cache\n\n`fn check_{}()` needs work.",
+                    i, i
+                ),
+            ),
+            _ => (
+                "command_output".to_string(),
+                "line of tool output\n".repeat(40),
+            ),
+        })
+        .collect()
+}
+
+fn conversation(messages: usize) -> Vec<Message> {
+    (0..messages)
+        .map(|i| Message {
+            role: if i % 2 == 0 {
+                MessageRole::User
+            } else {
+                MessageRole::Assistant
+            },
+            content: MessageContent::Text(format!(
+                "Message {} with enough words to resemble a real turn in a long working session",
+                i
+            )),
+            name: None,
+        })
+        .collect()
+}
+
+fn bench_markdown_rendering(c: &mut Criterion) {
+    let document = large_markdown();
+    c.bench_function("markdown_render_large_message", |b| {
+        b.iter(|| parse_markdown(black_box(&document)))
+    });
+}
+
+fn bench_line_cache_rebuild(c: &mut Criterion) {
+    // The dominant cost of a full chat line-cache rebuild: rendering every
+    // assistant message's markdown and splitting everything else into
+    // lines, across a long transcript
+    let messages = transcript(200);
+    c.bench_function("line_cache_rebuild_200_messages", |b| {
+        b.iter(|| {
+            let mut total_lines = 0usize;
+            for (role, content) in &messages {
+                if role == "assistant" {
+                    total_lines += parse_markdown(black_box(content)).lines.len();
+                } else {
+                    total_lines += black_box(content).lines().count();
+                }
+            }
+            total_lines
+        })
+    });
+}
+
+fn bench_token_estimation(c: &mut Criterion) {
+    let messages = conversation(500);
+    c.bench_function("token_estimation_500_messages", |b| {
+        b.iter(|| estimate_tokens(black_box(&messages)))
+    });
+}
+
+fn bench_request_serialization(c: &mut Criterion) {
+    let tools: Vec<Tool> = (0..20)
+        .map(|i| Tool::Standard {
+            name: format!("Tool{}", i),
+            description: "A tool with a schema of typical size".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "file_path": { "type": "string", "description": "Path to operate on" },
+                    "limit": { "type": "number" }
+                },
+                "required": ["file_path"]
+            }),
+        })
+        .collect();
+    let request = ChatRequestBuilder::new("claude-sonnet-4".to_string())
+        .messages(conversation(100))
+        .max_tokens(4096)
+        .system("You are a coding assistant.".repeat(50))
+        .tools(tools)
+        .build();
+    c.bench_function("chat_request_serialization", |b| {
+        b.iter(|| serde_json::to_string(black_box(&request)).expect("serialize"))
+    });
+}
+
+fn configured() -> Criterion {
+    Criterion::default()
+        .sample_size(30)
+        .measurement_time(Duration::from_secs(2))
+        .warm_up_time(Duration::from_millis(500))
+        .noise_threshold(0.05)
+}
+
+criterion_group! {
+    name = benches;
+    config = configured();
+    targets = bench_markdown_rendering,
+        bench_line_cache_rebuild,
+        bench_token_estimation,
+        bench_request_serialization
+}
+criterion_main!(benches);
@@ -0,0 +1,519 @@
+//! Agent Client Protocol (ACP) support for editor integration.
+//!
+//! `llminate acp` speaks ACP (JSON-RPC 2.0 over stdio, as used by Zed and
+//! other editors) so an editor can embed the agent: prompts arrive via
+//! `session/prompt`, assistant output and tool activity stream back as
+//! `session/update` notifications, and mutating tools are gated through the
+//! client's `session/request_permission` call. File edits carry `diff`
+//! content blocks so the editor can render them natively.
+
+use crate::error::{Error, Result};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+/// ACP protocol version we implement
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Maximum agent turns per prompt (model -> tools -> model round trips)
+const MAX_AGENT_TURNS: usize = 10;
+
+/// Tools that mutate state and therefore need client permission
+const PERMISSION_GATED_TOOLS: &[&str] =
+    &["Bash", "Write", "Edit", "MultiEdit", "NotebookEdit", "KillBash"];
+
+/// Connection shared between the reader loop and prompt handlers
+struct AcpConnection {
+    /// Outgoing JSON-RPC messages (one per line on stdout)
+    out_tx: mpsc::UnboundedSender<Value>,
+    /// Responses we are waiting for, keyed by our outgoing request id
+    pending: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
+    /// Next outgoing request id
+    next_id: AtomicU64,
+    /// Per-session conversation history
+    sessions: Mutex<HashMap<String, Vec<crate::ai::Message>>>,
+    /// Tools the client approved with "always allow", per session
+    always_allowed: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+impl AcpConnection {
+    /// Send a JSON-RPC notification to the client
+    fn notify(&self, method: &str, params: Value) {
+        let _ = self.out_tx.send(json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }));
+    }
+
+    /// Send a JSON-RPC request to the client and await its response
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+
+        let _ = self.out_tx.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        }));
+
+        rx.await
+            .map_err(|_| Error::Other("ACP client disconnected while awaiting response".to_string()))
+    }
+
+    /// Send a JSON-RPC response for a client-initiated request
+    fn respond(&self, id: &Value, result: Value) {
+        let _ = self.out_tx.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        }));
+    }
+
+    /// Send a JSON-RPC error for a client-initiated request
+    fn respond_error(&self, id: &Value, code: i64, message: &str) {
+        let _ = self.out_tx.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }));
+    }
+}
+
+/// Run the ACP agent over stdio until the client disconnects
+pub async fn run() -> Result<()> {
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Value>();
+
+    // Writer task: serialize outgoing messages onto stdout, one per line
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = out_rx.recv().await {
+            if let Ok(line) = serde_json::to_string(&message) {
+                if stdout.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+                if stdout.write_all(b"\n").await.is_err() {
+                    break;
+                }
+                let _ = stdout.flush().await;
+            }
+        }
+    });
+
+    let connection = Arc::new(AcpConnection {
+        out_tx,
+        pending: Mutex::new(HashMap::new()),
+        next_id: AtomicU64::new(1),
+        sessions: Mutex::new(HashMap::new()),
+        always_allowed: Mutex::new(HashMap::new()),
+    });
+
+    let stdin = tokio::io::stdin();
+    let mut reader = BufReader::new(stdin);
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| Error::Other(format!("Failed to read from stdin: {}", e)))?;
+        if read == 0 {
+            break; // Client closed stdin
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let message: Value = match serde_json::from_str(trimmed) {
+            Ok(value) => value,
+            Err(_) => continue, // Ignore malformed lines
+        };
+
+        if message.get("method").is_some() {
+            handle_incoming(Arc::clone(&connection), message).await;
+        } else if let Some(id) = message.get("id").and_then(|id| id.as_u64()) {
+            // Response to one of our requests (e.g. session/request_permission)
+            let result = message
+                .get("result")
+                .cloned()
+                .unwrap_or_else(|| json!({ "error": message.get("error").cloned() }));
+            if let Some(tx) = connection.pending.lock().await.remove(&id) {
+                let _ = tx.send(result);
+            }
+        }
+    }
+
+    drop(connection);
+    let _ = writer.await;
+    Ok(())
+}
+
+/// Dispatch a client-initiated request or notification
+async fn handle_incoming(connection: Arc<AcpConnection>, message: Value) {
+    let method = message
+        .get("method")
+        .and_then(|m| m.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let id = message.get("id").cloned();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    match method.as_str() {
+        "initialize" => {
+            if let Some(id) = id {
+                connection.respond(
+                    &id,
+                    json!({
+                        "protocolVersion": PROTOCOL_VERSION,
+                        "agentCapabilities": {
+                            "loadSession": false,
+                            "promptCapabilities": {
+                                "image": false,
+                                "audio": false,
+                                "embeddedContext": true,
+                            },
+                        },
+                        "authMethods": [],
+                    }),
+                );
+            }
+        }
+        "session/new" => {
+            let session_id = crate::utils::generate_session_id();
+            connection
+                .sessions
+                .lock()
+                .await
+                .insert(session_id.clone(), Vec::new());
+            if let Some(id) = id {
+                connection.respond(&id, json!({ "sessionId": session_id }));
+            }
+        }
+        "session/prompt" => {
+            // Prompts run concurrently so the reader loop keeps routing the
+            // permission responses the prompt is waiting on
+            tokio::spawn(async move {
+                let result = handle_prompt(&connection, &params).await;
+                if let Some(id) = id {
+                    match result {
+                        Ok(stop_reason) => {
+                            connection.respond(&id, json!({ "stopReason": stop_reason }));
+                        }
+                        Err(e) => connection.respond_error(&id, -32603, &e.to_string()),
+                    }
+                }
+            });
+        }
+        "session/cancel" => {
+            // Best-effort: turns finish at the next tool boundary
+        }
+        _ => {
+            if let Some(id) = id {
+                connection.respond_error(&id, -32601, &format!("Method not found: {}", method));
+            }
+        }
+    }
+}
+
+/// Extract prompt text from ACP content blocks
+fn prompt_text(params: &Value) -> String {
+    params
+        .get("prompt")
+        .and_then(|p| p.as_array())
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter_map(|block| match block.get("type").and_then(|t| t.as_str()) {
+                    Some("text") => block.get("text").and_then(|t| t.as_str()).map(str::to_string),
+                    Some("resource") => block
+                        .get("resource")
+                        .and_then(|r| r.get("text"))
+                        .and_then(|t| t.as_str())
+                        .map(str::to_string),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+/// Ask the client for permission to run a tool. Returns true when allowed.
+async fn request_permission(
+    connection: &AcpConnection,
+    session_id: &str,
+    tool_call_id: &str,
+    tool_name: &str,
+    description: &str,
+) -> bool {
+    // Skip the round trip when this tool was already approved for the session
+    if let Some(allowed) = connection.always_allowed.lock().await.get(session_id) {
+        if allowed.contains(tool_name) {
+            return true;
+        }
+    }
+
+    let response = connection
+        .request(
+            "session/request_permission",
+            json!({
+                "sessionId": session_id,
+                "toolCall": { "toolCallId": tool_call_id, "title": description },
+                "options": [
+                    { "optionId": "allow-once", "name": "Allow once", "kind": "allow_once" },
+                    { "optionId": "allow-always", "name": "Always allow", "kind": "allow_always" },
+                    { "optionId": "reject", "name": "Reject", "kind": "reject_once" },
+                ],
+            }),
+        )
+        .await;
+
+    match response {
+        Ok(result) => {
+            let option_id = result
+                .get("outcome")
+                .and_then(|o| o.get("optionId"))
+                .and_then(|o| o.as_str())
+                .unwrap_or("reject");
+            if option_id == "allow-always" {
+                connection
+                    .always_allowed
+                    .lock()
+                    .await
+                    .entry(session_id.to_string())
+                    .or_default()
+                    .insert(tool_name.to_string());
+            }
+            option_id.starts_with("allow")
+        }
+        Err(_) => false,
+    }
+}
+
+/// Build diff content blocks for file-editing tools so the editor can
+/// render the change natively
+fn diff_content(tool_name: &str, input: &Value) -> Option<Value> {
+    let file_path = input.get("file_path").and_then(|p| p.as_str())?;
+    let old_text = std::fs::read_to_string(file_path).unwrap_or_default();
+    let new_text = match tool_name {
+        "Write" => input.get("content").and_then(|c| c.as_str())?.to_string(),
+        "Edit" => {
+            let old_string = input.get("old_string").and_then(|s| s.as_str())?;
+            let new_string = input.get("new_string").and_then(|s| s.as_str())?;
+            if input.get("replace_all").and_then(|r| r.as_bool()).unwrap_or(false) {
+                old_text.replace(old_string, new_string)
+            } else {
+                old_text.replacen(old_string, new_string, 1)
+            }
+        }
+        _ => return None,
+    };
+    Some(json!({
+        "type": "diff",
+        "path": file_path,
+        "oldText": old_text,
+        "newText": new_text,
+    }))
+}
+
+/// Run one prompt turn: call the provider, stream updates, execute tools
+/// (permission-gated through the client), and loop until the model finishes
+async fn handle_prompt(connection: &AcpConnection, params: &Value) -> Result<String> {
+    let session_id = params
+        .get("sessionId")
+        .and_then(|s| s.as_str())
+        .ok_or_else(|| Error::InvalidInput("session/prompt requires sessionId".to_string()))?
+        .to_string();
+    let text = prompt_text(params);
+    if text.is_empty() {
+        return Err(Error::InvalidInput("Prompt contained no text".to_string()));
+    }
+
+    let ai_client = crate::ai::create_client().await?;
+    let tool_executor = crate::ai::tools::ToolExecutor::new();
+    let tools = tool_executor.get_available_tools();
+
+    let mut messages = {
+        let sessions = connection.sessions.lock().await;
+        sessions
+            .get(&session_id)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Unknown session: {}", session_id)))?
+    };
+    messages.push(crate::ai::Message {
+        role: crate::ai::MessageRole::User,
+        content: crate::ai::MessageContent::Text(text),
+        name: None,
+    });
+
+    // Base prompt plus the environment snapshot (the snapshot lives in
+    // its own builder section now, so it refreshes between turns)
+    let system_prompt = format!(
+        "{}\n\n{}",
+        crate::ai::system_prompt::get_system_prompt("Claude Code"),
+        crate::ai::system_prompt::get_environment_context()
+    );
+    let mut stop_reason = "end_turn";
+
+    for turn in 0..MAX_AGENT_TURNS {
+        let request = ai_client
+            .create_chat_request()
+            .messages(messages.clone())
+            .system(system_prompt.clone())
+            .tools(tools.clone())
+            .max_tokens(4096)
+            .build();
+
+        let response = ai_client.chat(request).await?;
+        let mut tool_results: Vec<crate::ai::ContentPart> = Vec::new();
+
+        for part in &response.content {
+            match part {
+                crate::ai::ContentPart::Text { text, .. } => {
+                    connection.notify(
+                        "session/update",
+                        json!({
+                            "sessionId": session_id,
+                            "update": {
+                                "sessionUpdate": "agent_message_chunk",
+                                "content": { "type": "text", "text": text },
+                            },
+                        }),
+                    );
+                }
+                crate::ai::ContentPart::ToolUse { id, name, input } => {
+                    let description = tool_executor
+                        .describe_action(name, input)
+                        .unwrap_or_else(|| name.clone());
+
+                    let mut content = Vec::new();
+                    if let Some(diff) = diff_content(name, input) {
+                        content.push(diff);
+                    }
+                    connection.notify(
+                        "session/update",
+                        json!({
+                            "sessionId": session_id,
+                            "update": {
+                                "sessionUpdate": "tool_call",
+                                "toolCallId": id,
+                                "title": description,
+                                "kind": tool_kind(name),
+                                "status": "pending",
+                                "content": content,
+                            },
+                        }),
+                    );
+
+                    let allowed = if PERMISSION_GATED_TOOLS.contains(&name.as_str()) {
+                        request_permission(connection, &session_id, id, name, &description).await
+                    } else {
+                        true
+                    };
+
+                    let result = if allowed {
+                        connection.notify(
+                            "session/update",
+                            json!({
+                                "sessionId": session_id,
+                                "update": {
+                                    "sessionUpdate": "tool_call_update",
+                                    "toolCallId": id,
+                                    "status": "in_progress",
+                                },
+                            }),
+                        );
+                        match tool_executor.execute(name, input.clone()).await {
+                            Ok(crate::ai::ContentPart::ToolResult { content, is_error, .. }) => {
+                                crate::ai::ContentPart::ToolResult {
+                                    tool_use_id: id.clone(),
+                                    content,
+                                    is_error,
+                                }
+                            }
+                            Ok(other) => other,
+                            Err(e) => crate::ai::ContentPart::ToolResult {
+                                tool_use_id: id.clone(),
+                                content: format!("Error: {}", e),
+                                is_error: Some(true),
+                            },
+                        }
+                    } else {
+                        crate::ai::ContentPart::ToolResult {
+                            tool_use_id: id.clone(),
+                            content: "The user rejected this tool call.".to_string(),
+                            is_error: Some(true),
+                        }
+                    };
+
+                    let (status, output) = match &result {
+                        crate::ai::ContentPart::ToolResult { content, is_error, .. } => (
+                            if is_error.unwrap_or(false) { "failed" } else { "completed" },
+                            content.clone(),
+                        ),
+                        _ => ("completed", String::new()),
+                    };
+                    connection.notify(
+                        "session/update",
+                        json!({
+                            "sessionId": session_id,
+                            "update": {
+                                "sessionUpdate": "tool_call_update",
+                                "toolCallId": id,
+                                "status": status,
+                                "content": [{ "type": "content", "content": { "type": "text", "text": output } }],
+                            },
+                        }),
+                    );
+                    tool_results.push(result);
+                }
+                _ => {}
+            }
+        }
+
+        if tool_results.is_empty() {
+            break;
+        }
+        if turn + 1 == MAX_AGENT_TURNS {
+            stop_reason = "max_turn_requests";
+        }
+
+        messages.push(crate::ai::Message {
+            role: crate::ai::MessageRole::Assistant,
+            content: crate::ai::MessageContent::Multipart(response.content.clone()),
+            name: None,
+        });
+        messages.push(crate::ai::Message {
+            role: crate::ai::MessageRole::User,
+            content: crate::ai::MessageContent::Multipart(tool_results),
+            name: None,
+        });
+    }
+
+    connection
+        .sessions
+        .lock()
+        .await
+        .insert(session_id, messages);
+
+    Ok(stop_reason.to_string())
+}
+
+/// Map tool names to ACP tool-call kinds for editor iconography
+fn tool_kind(tool_name: &str) -> &'static str {
+    match tool_name {
+        "Read" | "NotebookRead" | "Glob" | "Grep" | "LS" => "read",
+        "Write" | "Edit" | "MultiEdit" | "NotebookEdit" => "edit",
+        "Bash" | "BashOutput" | "KillBash" => "execute",
+        "WebFetch" | "WebSearch" | "HttpRequest" => "fetch",
+        "Task" => "think",
+        _ => "other",
+    }
+}
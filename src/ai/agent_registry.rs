@@ -0,0 +1,326 @@
+// Custom agent definitions for the Task tool
+//
+// Definitions are loaded from (in precedence order):
+// 1. .claude/agents/ project directory (project settings)
+// 2. ~/.claude/agents/ directory (user settings)
+//
+// A definition is either a Markdown file with YAML frontmatter (the body
+// becomes the system prompt) or a plain YAML file with a `system_prompt`
+// field. The registry fingerprints file modification times on every
+// lookup, so edits are picked up without restarting the session.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// Where an agent definition was loaded from
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentSource {
+    User,
+    Project,
+}
+
+impl AgentSource {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AgentSource::User => "user",
+            AgentSource::Project => "project",
+        }
+    }
+}
+
+/// A custom agent definition usable as a Task `subagent_type`
+#[derive(Debug, Clone)]
+pub struct AgentDefinition {
+    pub name: String,
+    pub description: String,
+    pub system_prompt: String,
+    /// Tools the agent may use; None allows everything except Task
+    pub allowed_tools: Option<Vec<String>>,
+    /// Model for the agent ("haiku"-style alias or a full model id);
+    /// None inherits the parent default
+    pub model: Option<String>,
+    pub source: AgentSource,
+}
+
+/// Frontmatter / YAML metadata for an agent definition file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct AgentFrontmatter {
+    name: Option<String>,
+    description: Option<String>,
+    /// Accepted under both the JS CLI's `tools` key and the
+    /// skill-style `allowed-tools` key
+    tools: Option<ToolList>,
+    #[serde(rename = "allowed-tools")]
+    allowed_tools: Option<ToolList>,
+    model: Option<String>,
+    /// Only used by plain YAML definitions; Markdown bodies win
+    system_prompt: Option<String>,
+}
+
+/// Tool lists appear as either a YAML sequence or a comma-separated string
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ToolList {
+    List(Vec<String>),
+    CommaSeparated(String),
+}
+
+impl ToolList {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            ToolList::List(tools) => tools
+                .into_iter()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            ToolList::CommaSeparated(tools) => tools
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// Cached registry contents plus the file fingerprint they were built
+/// from, so lookups reload only when a definition file actually changed
+struct CachedRegistry {
+    fingerprint: Vec<(PathBuf, Option<SystemTime>)>,
+    agents: Vec<AgentDefinition>,
+}
+
+static REGISTRY_CACHE: RwLock<Option<CachedRegistry>> = RwLock::new(None);
+
+/// All loaded agent definitions, project definitions shadowing user ones
+/// with the same name
+pub fn all_agents() -> Vec<AgentDefinition> {
+    let fingerprint = current_fingerprint();
+
+    {
+        let cache = REGISTRY_CACHE.read().ok();
+        if let Some(Some(cached)) = cache.as_deref() {
+            if cached.fingerprint == fingerprint {
+                return cached.agents.clone();
+            }
+        }
+    }
+
+    let agents = load_all_definitions();
+    if let Ok(mut cache) = REGISTRY_CACHE.write() {
+        *cache = Some(CachedRegistry {
+            fingerprint,
+            agents: agents.clone(),
+        });
+    }
+    agents
+}
+
+/// Look up an agent definition by name (case-insensitive)
+pub fn find_agent(name: &str) -> Option<AgentDefinition> {
+    let wanted = name.to_lowercase();
+    all_agents()
+        .into_iter()
+        .find(|agent| agent.name.to_lowercase() == wanted)
+}
+
+/// The directories scanned for definitions, lowest precedence first
+fn agent_directories() -> Vec<(PathBuf, AgentSource)> {
+    let mut dirs = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        dirs.push((home.join(".claude").join("agents"), AgentSource::User));
+    }
+    if let Ok(cwd) = std::env::current_dir() {
+        dirs.push((cwd.join(".claude").join("agents"), AgentSource::Project));
+    }
+    dirs
+}
+
+/// Paths and modification times of every definition file, used to detect
+/// changes without re-reading file contents
+fn current_fingerprint() -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut fingerprint = Vec::new();
+    for (dir, _) in agent_directories() {
+        for path in definition_files(&dir) {
+            let mtime = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            fingerprint.push((path, mtime));
+        }
+    }
+    fingerprint
+}
+
+/// Definition files in a directory, sorted for stable ordering
+fn definition_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_definition = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| matches!(e, "md" | "yaml" | "yml"))
+                .unwrap_or(false);
+            if is_definition && path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Load every definition, later directories replacing earlier ones on
+/// name conflicts
+fn load_all_definitions() -> Vec<AgentDefinition> {
+    let mut agents: Vec<AgentDefinition> = Vec::new();
+    for (dir, source) in agent_directories() {
+        for path in definition_files(&dir) {
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Some(agent) = parse_agent_definition(&content, &path, source.clone()) {
+                agents.retain(|existing| {
+                    existing.name.to_lowercase() != agent.name.to_lowercase()
+                });
+                agents.push(agent);
+            }
+        }
+    }
+    agents
+}
+
+/// Parse one definition file. Markdown files carry metadata in YAML
+/// frontmatter with the body as the system prompt; YAML files hold
+/// everything including `system_prompt`.
+fn parse_agent_definition(
+    content: &str,
+    path: &Path,
+    source: AgentSource,
+) -> Option<AgentDefinition> {
+    let is_markdown = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e == "md")
+        .unwrap_or(false);
+
+    let (frontmatter, body) = if is_markdown {
+        parse_frontmatter(content)
+    } else {
+        match serde_yaml::from_str::<AgentFrontmatter>(content) {
+            Ok(fm) => (fm, String::new()),
+            Err(_) => return None,
+        }
+    };
+
+    // Name falls back to the file stem, like skills fall back to their
+    // directory name
+    let name = frontmatter.name.clone().or_else(|| {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .map(String::from)
+    })?;
+
+    let system_prompt = if !body.trim().is_empty() {
+        body.trim().to_string()
+    } else {
+        frontmatter.system_prompt.clone().unwrap_or_default()
+    };
+    if system_prompt.is_empty() {
+        return None;
+    }
+
+    let allowed_tools = frontmatter
+        .tools
+        .clone()
+        .or_else(|| frontmatter.allowed_tools.clone())
+        .map(ToolList::into_vec)
+        .filter(|tools| !tools.is_empty());
+
+    // Ignore "inherit", matching skill model handling
+    let model = frontmatter
+        .model
+        .clone()
+        .filter(|m| m != "inherit" && !m.is_empty());
+
+    Some(AgentDefinition {
+        name,
+        description: frontmatter.description.clone().unwrap_or_default(),
+        system_prompt,
+        allowed_tools,
+        model,
+        source,
+    })
+}
+
+/// Split YAML frontmatter (---\n...\n---) from the Markdown body
+fn parse_frontmatter(content: &str) -> (AgentFrontmatter, String) {
+    if !content.starts_with("---\n") {
+        return (AgentFrontmatter::default(), content.to_string());
+    }
+
+    if let Some(end_idx) = content[4..].find("\n---") {
+        let yaml_content = &content[4..4 + end_idx];
+        let body = &content[4 + end_idx + 4..];
+        match serde_yaml::from_str::<AgentFrontmatter>(yaml_content) {
+            Ok(fm) => return (fm, body.trim_start().to_string()),
+            Err(_) => return (AgentFrontmatter::default(), content.to_string()),
+        }
+    }
+
+    (AgentFrontmatter::default(), content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown_definition() {
+        let content = "---\nname: code-reviewer\ndescription: Reviews diffs\ntools: Read, Grep, Glob\nmodel: haiku\n---\nYou are a meticulous code reviewer.\nFlag correctness issues first.";
+        let agent = parse_agent_definition(
+            content,
+            Path::new("/tmp/.claude/agents/code-reviewer.md"),
+            AgentSource::Project,
+        )
+        .unwrap();
+        assert_eq!(agent.name, "code-reviewer");
+        assert_eq!(agent.description, "Reviews diffs");
+        assert_eq!(
+            agent.allowed_tools,
+            Some(vec!["Read".to_string(), "Grep".to_string(), "Glob".to_string()])
+        );
+        assert_eq!(agent.model.as_deref(), Some("haiku"));
+        assert!(agent.system_prompt.starts_with("You are a meticulous"));
+    }
+
+    #[test]
+    fn test_parse_yaml_definition_and_name_fallback() {
+        let content = "description: Writes tests\nallowed-tools:\n  - Read\n  - Write\nsystem_prompt: You write focused unit tests.";
+        let agent = parse_agent_definition(
+            content,
+            Path::new("/tmp/.claude/agents/test-writer.yaml"),
+            AgentSource::User,
+        )
+        .unwrap();
+        // Name falls back to the file stem
+        assert_eq!(agent.name, "test-writer");
+        assert_eq!(
+            agent.allowed_tools,
+            Some(vec!["Read".to_string(), "Write".to_string()])
+        );
+        assert_eq!(agent.system_prompt, "You write focused unit tests.");
+    }
+
+    #[test]
+    fn test_definitions_without_prompt_are_skipped() {
+        let content = "---\nname: empty\n---\n";
+        assert!(parse_agent_definition(
+            content,
+            Path::new("/tmp/.claude/agents/empty.md"),
+            AgentSource::Project,
+        )
+        .is_none());
+    }
+}
@@ -75,6 +75,77 @@ impl AgentModel {
             _ => None,
         }
     }
+
+    /// Concrete model ID for the selection (matches the registry in models.rs)
+    pub fn model_id(&self) -> &'static str {
+        match self {
+            AgentModel::Sonnet => "claude-3-5-sonnet-20241022",
+            AgentModel::Opus => "claude-opus-4-1-20250805",
+            AgentModel::Haiku => "claude-3-haiku-20240307",
+        }
+    }
+}
+
+/// Per-run configuration for a spawned sub-agent.
+///
+/// Defaults mirror the parent loop; Task tool input can narrow them so a
+/// sub-agent runs on a cheaper model with a tighter tool allowlist and
+/// iteration budget instead of inheriting the parent config wholesale.
+#[derive(Debug, Clone)]
+pub struct AgentRunConfig {
+    /// Model override for the sub-agent; None inherits the parent default
+    pub model: Option<String>,
+    /// Tools the sub-agent may use; None allows everything except Task
+    pub allowed_tools: Option<Vec<String>>,
+    /// Per-response token budget
+    pub max_tokens: u32,
+    /// Maximum agent loop iterations
+    pub max_loops: usize,
+}
+
+impl Default for AgentRunConfig {
+    fn default() -> Self {
+        Self {
+            model: None,
+            allowed_tools: None,
+            max_tokens: 4096,
+            max_loops: 10,
+        }
+    }
+}
+
+impl AgentRunConfig {
+    /// Build the run configuration from Task tool input
+    fn from_input(input: &Value) -> Self {
+        let defaults = Self::default();
+        let model = input["model"]
+            .as_str()
+            .and_then(AgentModel::from_str)
+            .map(|m| m.model_id().to_string());
+        let allowed_tools = input["allowed_tools"]
+            .as_array()
+            .map(|tools| {
+                tools
+                    .iter()
+                    .filter_map(|t| t.as_str().map(String::from))
+                    .collect::<Vec<String>>()
+            })
+            .filter(|tools| !tools.is_empty());
+        let max_tokens = input["max_tokens"]
+            .as_u64()
+            .map(|t| (t as u32).clamp(256, 32_000))
+            .unwrap_or(defaults.max_tokens);
+        let max_loops = input["max_iterations"]
+            .as_u64()
+            .map(|n| (n as usize).clamp(1, 25))
+            .unwrap_or(defaults.max_loops);
+        Self {
+            model,
+            allowed_tools,
+            max_tokens,
+            max_loops,
+        }
+    }
 }
 
 /// Stored agent state for resume functionality
@@ -92,6 +163,10 @@ lazy_static::lazy_static! {
         Arc::new(RwLock::new(HashMap::new()));
 }
 
+/// Reports longer than this are condensed by one extra model call before
+/// being returned to the parent loop
+const CONDENSED_REPORT_THRESHOLD: usize = 6000;
+
 /// Result from a single agent execution
 #[derive(Debug, Clone)]
 struct AgentResult {
@@ -140,6 +215,19 @@ impl ToolHandler for AgentTool {
                 "run_in_background": {
                     "type": "boolean",
                     "description": "Set to true to run this agent in the background. Use TaskOutput to read the output later."
+                },
+                "allowed_tools": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional allowlist of tool names the agent may use. If not specified, the agent gets every tool except Task."
+                },
+                "max_tokens": {
+                    "type": "integer",
+                    "description": "Optional per-response token budget for the agent (default 4096)."
+                },
+                "max_iterations": {
+                    "type": "integer",
+                    "description": "Optional maximum agent loop iterations (default 10, max 25)."
                 }
             },
             "required": ["description", "prompt", "subagent_type"]
@@ -172,10 +260,25 @@ impl ToolHandler for AgentTool {
             .ok_or_else(|| Error::InvalidInput("Missing 'subagent_type' field".to_string()))?;
         let agent_type = AgentType::from_str(subagent_type_str);
 
-        // Extract optional model selection
-        let model = input["model"]
-            .as_str()
-            .and_then(AgentModel::from_str);
+        // Model, tool allowlist, and budget overrides for the sub-agent
+        let mut run_config = AgentRunConfig::from_input(&input);
+
+        // Custom agent definitions from .claude/agents/ supply defaults;
+        // explicit Task input still wins
+        if let AgentType::Custom(name) = &agent_type {
+            if let Some(definition) = crate::ai::agent_registry::find_agent(name) {
+                if run_config.model.is_none() {
+                    run_config.model = definition.model.as_deref().map(|m| {
+                        AgentModel::from_str(m)
+                            .map(|am| am.model_id().to_string())
+                            .unwrap_or_else(|| m.to_string())
+                    });
+                }
+                if run_config.allowed_tools.is_none() {
+                    run_config.allowed_tools = definition.allowed_tools.clone();
+                }
+            }
+        }
 
         // Extract optional resume agent ID
         let resume_id = input["resume"].as_str().map(String::from);
@@ -200,7 +303,7 @@ impl ToolHandler for AgentTool {
                     prompt,
                     description,
                     &agent_type,
-                    model.as_ref(),
+                    &run_config,
                     start_time,
                     cancellation_token,
                 ).await;
@@ -219,7 +322,7 @@ impl ToolHandler for AgentTool {
                 prompt.to_string(),
                 description.to_string(),
                 agent_type,
-                model,
+                run_config,
                 cancellation_token,
             ).await;
         }
@@ -236,6 +339,7 @@ impl ToolHandler for AgentTool {
                 prompt,
                 description,
                 &agent_type,
+                &run_config,
                 parallel_tasks_count,
                 start_time,
                 cancellation_token,
@@ -247,6 +351,7 @@ impl ToolHandler for AgentTool {
                 prompt,
                 description,
                 &agent_type,
+                &run_config,
                 0,
                 start_time,
                 cancellation_token,
@@ -263,11 +368,12 @@ impl AgentTool {
         prompt: &str,
         description: &str,
         agent_type: &AgentType,
+        run_config: &AgentRunConfig,
         agent_index: usize,
         start_time: Instant,
         cancellation_token: Option<CancellationToken>,
     ) -> Result<String> {
-        let result = self.run_agent(prompt, description, agent_type, agent_index, false, cancellation_token.clone()).await?;
+        let result = self.run_agent(prompt, description, agent_type, run_config, agent_index, false, cancellation_token.clone()).await?;
 
         // Store agent state for potential resume
         self.store_agent_state(agent_id, agent_type.clone(), result.messages.clone()).await;
@@ -301,6 +407,7 @@ impl AgentTool {
         prompt: &str,
         description: &str,
         agent_type: &AgentType,
+        run_config: &AgentRunConfig,
         parallel_count: usize,
         start_time: Instant,
         cancellation_token: Option<CancellationToken>,
@@ -318,9 +425,10 @@ impl AgentTool {
             let agent_tool = AgentTool;
             let cancellation_clone = cancellation_token.clone();
             let at_clone = agent_type_clone.clone();
+            let config_clone = run_config.clone();
 
             join_set.spawn(async move {
-                agent_tool.run_agent(&prompt_clone, &description_clone, &at_clone, i, false, cancellation_clone).await
+                agent_tool.run_agent(&prompt_clone, &description_clone, &at_clone, &config_clone, i, false, cancellation_clone).await
             });
         }
 
@@ -369,7 +477,7 @@ impl AgentTool {
             output.push_str("\n=== Synthesis Phase ===\n");
 
             let synthesis_prompt = self.create_synthesis_prompt(prompt, &agent_results);
-            let synthesis_result = self.run_agent(&synthesis_prompt, "Synthesis", agent_type, 0, true, cancellation_token.clone()).await?;
+            let synthesis_result = self.run_agent(&synthesis_prompt, "Synthesis", agent_type, run_config, 0, true, cancellation_token.clone()).await?;
 
             // Extract text content from synthesis
             for part in &synthesis_result.content {
@@ -403,7 +511,7 @@ impl AgentTool {
         prompt: String,
         description: String,
         agent_type: AgentType,
-        _model: Option<AgentModel>,
+        run_config: AgentRunConfig,
         cancellation_token: Option<CancellationToken>,
     ) -> Result<String> {
         // Spawn the agent execution in a background task
@@ -417,6 +525,7 @@ impl AgentTool {
                 &prompt,
                 &description,
                 &agent_type_clone,
+                &run_config,
                 0,
                 false,
                 cancellation_token,
@@ -452,7 +561,7 @@ impl AgentTool {
         additional_prompt: &str,
         description: &str,
         agent_type: &AgentType,
-        _model: Option<&AgentModel>,
+        run_config: &AgentRunConfig,
         start_time: Instant,
         cancellation_token: Option<CancellationToken>,
     ) -> Result<String> {
@@ -472,6 +581,7 @@ impl AgentTool {
             additional_prompt,
             description,
             agent_type,
+            run_config,
             cancellation_token,
         ).await?;
 
@@ -535,6 +645,7 @@ impl AgentTool {
         prompt: &str,
         description: &str,
         agent_type: &AgentType,
+        run_config: &AgentRunConfig,
         agent_index: usize,
         is_synthesis: bool,
         cancellation_token: Option<CancellationToken>,
@@ -560,7 +671,10 @@ impl AgentTool {
                 if name == "Task" {
                     return false;
                 }
-                // Agent type specific filtering could be added here
+                // Apply the per-run allowlist if one was given
+                if let Some(allowed) = &run_config.allowed_tools {
+                    return allowed.iter().any(|a| a == name);
+                }
                 true
             })
             .collect();
@@ -600,7 +714,6 @@ impl AgentTool {
 
         // Run the sub-agent loop
         let mut loop_count = 0;
-        const MAX_LOOPS: usize = 10;
 
         loop {
             // Check for cancellation at the start of each loop iteration
@@ -615,7 +728,7 @@ impl AgentTool {
             }
 
             loop_count += 1;
-            if loop_count > MAX_LOOPS {
+            if loop_count > run_config.max_loops {
                 result_content.push(ContentPart::Text {
                     text: "[Agent reached maximum iterations]".to_string(),
                     citations: None
@@ -623,14 +736,17 @@ impl AgentTool {
                 break;
             }
 
-            let request = ai_client
+            let mut request_builder = ai_client
                 .create_chat_request()
                 .messages(messages.clone())
                 .system(system_prompt.clone())
                 .tools(tools.clone())
-                .max_tokens(4096)
-                .temperature(0.7)
-                .build();
+                .max_tokens(run_config.max_tokens)
+                .temperature(0.7);
+            if let Some(model) = &run_config.model {
+                request_builder = request_builder.model(model);
+            }
+            let request = request_builder.build();
 
             let response = ai_client.chat(request).await?;
 
@@ -720,6 +836,32 @@ impl AgentTool {
             }
         }
 
+        // Condense oversized reports so the parent loop gets the key
+        // findings rather than a transcript-sized dump
+        let report_len: usize = result_content
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text { text, .. } => text.len(),
+                _ => 0,
+            })
+            .sum();
+        if report_len > CONDENSED_REPORT_THRESHOLD {
+            let full_report: String = result_content
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text { text, .. } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            if let Some(condensed) = self.condense_report(&ai_client, run_config, &full_report).await {
+                result_content = vec![ContentPart::Text {
+                    text: condensed,
+                    citations: None,
+                }];
+            }
+        }
+
         Ok(AgentResult {
             content: result_content,
             tool_use_count,
@@ -730,6 +872,49 @@ impl AgentTool {
         })
     }
 
+    /// Ask the sub-agent's model to condense a long report. Falls back to
+    /// the original report on any failure rather than erroring the task.
+    async fn condense_report(
+        &self,
+        ai_client: &crate::ai::client_adapter::AIClientAdapter,
+        run_config: &AgentRunConfig,
+        report: &str,
+    ) -> Option<String> {
+        let mut request_builder = ai_client
+            .create_chat_request()
+            .messages(vec![Message {
+                role: MessageRole::User,
+                content: MessageContent::Text(format!(
+                    "Condense the following task report into its key findings, decisions, \
+                    and relevant file paths. Keep code snippets that are essential to the \
+                    conclusions and drop exploration narration. Use absolute file paths.\n\n{}",
+                    report
+                )),
+                name: None,
+            }])
+            .max_tokens(1024)
+            .temperature(0.2);
+        if let Some(model) = &run_config.model {
+            request_builder = request_builder.model(model);
+        }
+
+        let response = ai_client.chat(request_builder.build()).await.ok()?;
+        let condensed: String = response
+            .content
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if condensed.trim().is_empty() {
+            None
+        } else {
+            Some(condensed)
+        }
+    }
+
     /// Run agent with existing message history (for resume functionality)
     async fn run_agent_with_history(
         &self,
@@ -737,6 +922,7 @@ impl AgentTool {
         additional_prompt: &str,
         description: &str,
         agent_type: &AgentType,
+        run_config: &AgentRunConfig,
         cancellation_token: Option<CancellationToken>,
     ) -> Result<AgentResult> {
         let start = Instant::now();
@@ -754,7 +940,16 @@ impl AgentTool {
         let all_tools = tool_executor.get_available_tools();
         let tools: Vec<_> = all_tools
             .into_iter()
-            .filter(|tool| tool.name() != "Task")
+            .filter(|tool| {
+                let name = tool.name();
+                if name == "Task" {
+                    return false;
+                }
+                if let Some(allowed) = &run_config.allowed_tools {
+                    return allowed.iter().any(|a| a == name);
+                }
+                true
+            })
             .collect();
 
         // Add the new prompt as a user message
@@ -772,7 +967,6 @@ impl AgentTool {
 
         // Run the sub-agent loop
         let mut loop_count = 0;
-        const MAX_LOOPS: usize = 10;
 
         loop {
             if let Some(token) = &cancellation_token {
@@ -786,7 +980,7 @@ impl AgentTool {
             }
 
             loop_count += 1;
-            if loop_count > MAX_LOOPS {
+            if loop_count > run_config.max_loops {
                 result_content.push(ContentPart::Text {
                     text: "[Agent reached maximum iterations]".to_string(),
                     citations: None
@@ -794,14 +988,17 @@ impl AgentTool {
                 break;
             }
 
-            let request = ai_client
+            let mut request_builder = ai_client
                 .create_chat_request()
                 .messages(messages.clone())
                 .system(system_prompt.clone())
                 .tools(tools.clone())
-                .max_tokens(4096)
-                .temperature(0.7)
-                .build();
+                .max_tokens(run_config.max_tokens)
+                .temperature(0.7);
+            if let Some(model) = &run_config.model {
+                request_builder = request_builder.model(model);
+            }
+            let request = request_builder.build();
 
             let response = ai_client.chat(request).await?;
             total_tokens += 1000;
@@ -917,7 +1114,15 @@ impl AgentTool {
                 Help the user configure their Claude Code status line setting. \
                 Use Read and Edit tools to examine and modify configuration files.".to_string()
             }
-            AgentType::GeneralPurpose | AgentType::Custom(_) => {
+            AgentType::Custom(name) => {
+                // Custom definitions from .claude/agents/ bring their own
+                // system prompt; unknown names get the general prompt
+                if let Some(definition) = crate::ai::agent_registry::find_agent(name) {
+                    return definition.system_prompt;
+                }
+                self.get_system_prompt_for_agent_type(&AgentType::GeneralPurpose, description)
+            }
+            AgentType::GeneralPurpose => {
                 // Default sub-agent prompt from JavaScript (line 368376-368383)
                 "You are an agent for Claude Code, Anthropic's official CLI for Claude. \
                 Given the user's message, you should use the tools available to complete the task. \
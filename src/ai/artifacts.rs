@@ -0,0 +1,290 @@
+use crate::ai::tools::ToolHandler;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
+
+/// Maximum number of characters a tool result may occupy in the conversation
+/// before it is offloaded to the artifact store and replaced by a handle.
+pub const MAX_INLINE_OUTPUT_CHARS: usize = 30_000;
+
+/// Number of characters included in the inline preview of an offloaded artifact.
+const PREVIEW_CHARS: usize = 1_500;
+
+/// Metadata for a single stored artifact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    /// Short unique identifier, referenced as `artifact://<id>`
+    pub id: String,
+    /// Session the artifact belongs to
+    pub session_id: String,
+    /// Tool or subsystem that produced the artifact (e.g. "Bash", "WebFetch")
+    pub source: String,
+    /// Absolute path of the backing file on disk
+    pub path: PathBuf,
+    /// Size of the stored content in bytes
+    pub size_bytes: u64,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl Artifact {
+    /// The handle used to reference this artifact in conversation context
+    pub fn handle(&self) -> String {
+        format!("artifact://{}", self.id)
+    }
+}
+
+/// In-memory registry of artifacts, keyed by artifact id.
+/// The on-disk index per session is the source of truth across restarts.
+static ARTIFACT_REGISTRY: Lazy<RwLock<HashMap<String, Artifact>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Get the artifacts directory for a session (`.claude/artifacts/<session>/`),
+/// creating it if necessary.
+fn get_artifacts_dir(session_id: &str) -> Result<PathBuf> {
+    let dir = std::env::current_dir()
+        .map_err(Error::Io)?
+        .join(".claude")
+        .join("artifacts")
+        .join(session_id);
+    fs::create_dir_all(&dir).map_err(Error::Io)?;
+    Ok(dir)
+}
+
+fn index_path(session_id: &str) -> Result<PathBuf> {
+    Ok(get_artifacts_dir(session_id)?.join("index.json"))
+}
+
+/// Load the on-disk index for a session into the in-memory registry
+fn load_index(session_id: &str) -> Result<()> {
+    let path = index_path(session_id)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let content = fs::read_to_string(&path).map_err(Error::Io)?;
+    let artifacts: Vec<Artifact> = serde_json::from_str(&content).map_err(Error::Serialization)?;
+    let mut registry = ARTIFACT_REGISTRY.write();
+    for artifact in artifacts {
+        registry.insert(artifact.id.clone(), artifact);
+    }
+    Ok(())
+}
+
+/// Persist the index for a session from the in-memory registry
+fn save_index(session_id: &str) -> Result<()> {
+    let artifacts: Vec<Artifact> = ARTIFACT_REGISTRY
+        .read()
+        .values()
+        .filter(|a| a.session_id == session_id)
+        .cloned()
+        .collect();
+    let json_content = serde_json::to_string_pretty(&artifacts).map_err(Error::Serialization)?;
+    fs::write(index_path(session_id)?, json_content).map_err(Error::Io)?;
+    Ok(())
+}
+
+/// Store content as a new artifact for the given session and return its metadata
+pub fn store_artifact(
+    session_id: &str,
+    source: &str,
+    content: &[u8],
+    extension: &str,
+) -> Result<Artifact> {
+    let id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+    let dir = get_artifacts_dir(session_id)?;
+    let file_name = if extension.is_empty() {
+        id.clone()
+    } else {
+        format!("{}.{}", id, extension)
+    };
+    let path = dir.join(file_name);
+    fs::write(&path, content).map_err(Error::Io)?;
+
+    let artifact = Artifact {
+        id: id.clone(),
+        session_id: session_id.to_string(),
+        source: source.to_string(),
+        path,
+        size_bytes: content.len() as u64,
+        created_at: Utc::now(),
+    };
+
+    ARTIFACT_REGISTRY.write().insert(id, artifact.clone());
+    save_index(session_id)?;
+    Ok(artifact)
+}
+
+/// Look up an artifact by id, accepting either a bare id or an
+/// `artifact://<id>` handle. Falls back to the on-disk session index.
+pub fn get_artifact(session_id: &str, id_or_handle: &str) -> Result<Artifact> {
+    let id = id_or_handle.trim_start_matches("artifact://");
+    if let Some(artifact) = ARTIFACT_REGISTRY.read().get(id).cloned() {
+        return Ok(artifact);
+    }
+    // Registry may be cold after a restart - reload the session index
+    if !session_id.is_empty() {
+        load_index(session_id)?;
+        if let Some(artifact) = ARTIFACT_REGISTRY.read().get(id).cloned() {
+            return Ok(artifact);
+        }
+    }
+    // Handle may come from an earlier session's transcript (--resume) or
+    // the session id was lost - scan every session index on disk
+    load_all_indexes();
+    ARTIFACT_REGISTRY
+        .read()
+        .get(id)
+        .cloned()
+        .ok_or_else(|| Error::InvalidInput(format!("Artifact not found: artifact://{}", id)))
+}
+
+/// Load every session's on-disk index into the registry. Used as a last
+/// resort when an artifact id cannot be resolved through the current session.
+fn load_all_indexes() {
+    let Ok(cwd) = std::env::current_dir() else {
+        return;
+    };
+    let root = cwd.join(".claude").join("artifacts");
+    if let Ok(entries) = fs::read_dir(root) {
+        for entry in entries.flatten() {
+            if let Some(session) = entry.file_name().to_str() {
+                let _ = load_index(session);
+            }
+        }
+    }
+}
+
+/// List all artifacts for a session, oldest first
+pub fn list_artifacts(session_id: &str) -> Result<Vec<Artifact>> {
+    load_index(session_id)?;
+    let mut artifacts: Vec<Artifact> = ARTIFACT_REGISTRY
+        .read()
+        .values()
+        .filter(|a| a.session_id == session_id)
+        .cloned()
+        .collect();
+    artifacts.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(artifacts)
+}
+
+/// If a tool output exceeds the inline threshold, store it as an artifact and
+/// return a handle plus a short preview; otherwise return the output unchanged.
+pub fn offload_if_large(session_id: &str, tool_name: &str, output: String) -> Result<String> {
+    if output.chars().count() <= MAX_INLINE_OUTPUT_CHARS || session_id.is_empty() {
+        return Ok(output);
+    }
+
+    let artifact = store_artifact(session_id, tool_name, output.as_bytes(), "txt")?;
+    let preview: String = output.chars().take(PREVIEW_CHARS).collect();
+    Ok(format!(
+        "[Output too large for context: {} bytes stored as {}]\n\
+         Use the ReadArtifact tool with artifact_id \"{}\" to read the full content.\n\n\
+         Preview:\n{}\n[...truncated, {} more characters in artifact]",
+        artifact.size_bytes,
+        artifact.handle(),
+        artifact.id,
+        preview,
+        output.chars().count() - PREVIEW_CHARS
+    ))
+}
+
+/// ReadArtifact tool - read back content that was offloaded to the artifact store
+pub struct ReadArtifactTool;
+
+#[async_trait]
+impl ToolHandler for ReadArtifactTool {
+    fn description(&self) -> String {
+        "Reads the content of a stored artifact. Large tool outputs and generated files are stored as artifacts and referenced in the conversation by handles of the form artifact://<id>. Use this tool to retrieve the full content of such an artifact.
+
+Usage:
+- The artifact_id parameter accepts either the bare id or the full artifact://<id> handle
+- By default the entire artifact is returned; use offset and limit to read a line range of large artifacts
+- Artifacts are scoped to the current session and stored under .claude/artifacts/".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "artifact_id": {
+                    "type": "string",
+                    "description": "The artifact id or artifact:// handle to read"
+                },
+                "offset": {
+                    "type": "number",
+                    "description": "The line number to start reading from. Only provide for large artifacts"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "The number of lines to read. Only provide for large artifacts"
+                }
+            },
+            "required": ["artifact_id"]
+        })
+    }
+
+    fn action_description(&self, input: &Value) -> String {
+        format!(
+            "Read artifact: {}",
+            input["artifact_id"].as_str().unwrap_or("<unknown>")
+        )
+    }
+
+    fn permission_details(&self, input: &Value) -> String {
+        format!(
+            "Artifact: {}",
+            input["artifact_id"].as_str().unwrap_or("<unknown>")
+        )
+    }
+
+    async fn execute(
+        &self,
+        input: Value,
+        _cancellation_token: Option<CancellationToken>,
+    ) -> Result<String> {
+        let artifact_id = input["artifact_id"]
+            .as_str()
+            .ok_or_else(|| Error::InvalidInput("Missing 'artifact_id' parameter".to_string()))?;
+        // The dispatcher injects the current session id as an internal
+        // parameter; CLAUDE_SESSION_ID only exists in hook subprocesses
+        let session_id = input["_session_id"]
+            .as_str()
+            .map(str::to_string)
+            .or_else(|| std::env::var("CLAUDE_SESSION_ID").ok())
+            .unwrap_or_default();
+
+        let artifact = get_artifact(&session_id, artifact_id)?;
+        let content = fs::read_to_string(&artifact.path).map_err(Error::Io)?;
+
+        let offset = input["offset"].as_u64().map(|o| o as usize);
+        let limit = input["limit"].as_u64().map(|l| l as usize);
+
+        if offset.is_none() && limit.is_none() {
+            return Ok(content);
+        }
+
+        let start = offset.unwrap_or(1).saturating_sub(1);
+        let lines: Vec<&str> = content.lines().collect();
+        let end = match limit {
+            Some(l) => (start + l).min(lines.len()),
+            None => lines.len(),
+        };
+        if start >= lines.len() {
+            return Err(Error::InvalidInput(format!(
+                "Offset {} is beyond the end of the artifact ({} lines)",
+                start + 1,
+                lines.len()
+            )));
+        }
+        Ok(lines[start..end].join("\n"))
+    }
+}
@@ -0,0 +1,228 @@
+//! Bulk print-mode runs via the Messages Batches API.
+//!
+//! `llminate batch --input prompts.jsonl --output results.jsonl` submits
+//! every input line as one batch request, polls until processing ends,
+//! and writes the API's JSONL results. Batched requests are priced at
+//! half the interactive rate, which is what makes large offline
+//! evaluation runs practical.
+//!
+//! Input lines are JSON objects with either a `prompt` string or a full
+//! `messages` array, plus optional `custom_id`, `model`, `system`, and
+//! `max_tokens` fields. Missing ids are synthesized from the line number
+//! so results can always be correlated.
+
+use crate::ai::{ChatRequest, Message, MessageContent, MessageRole};
+use crate::auth::client::BatchRequestItem;
+use crate::error::{Error, Result};
+use serde_json::Value;
+use std::path::Path;
+
+/// Options for a batch run, from the CLI flags
+pub struct BatchOptions {
+    /// Input JSONL path (one request per line)
+    pub input: std::path::PathBuf,
+    /// Output JSONL path for the results
+    pub output: std::path::PathBuf,
+    /// Model for requests that don't specify their own
+    pub model: Option<String>,
+    /// Seconds between status polls
+    pub poll_interval_secs: u64,
+}
+
+/// Submit the input file as a message batch, wait for it to finish, and
+/// write the results
+pub async fn run(options: BatchOptions) -> Result<()> {
+    let default_model = match &options.model {
+        Some(model) => model.clone(),
+        None => crate::ai::load_config()?.default_model,
+    };
+
+    let content = std::fs::read_to_string(&options.input).map_err(|e| {
+        Error::Other(format!(
+            "Failed to read {}: {}",
+            options.input.display(),
+            e
+        ))
+    })?;
+    let requests = parse_input(&content, &default_model)?;
+    if requests.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "{} contains no requests",
+            options.input.display()
+        )));
+    }
+
+    let client = crate::ai::create_client().await?;
+    println!("Submitting {} requests...", requests.len());
+    let mut batch = client.create_message_batch(&requests).await?;
+    println!("Batch {} created", batch.id);
+
+    // Batches can take up to 24h, though most finish much sooner
+    let poll_interval = std::time::Duration::from_secs(options.poll_interval_secs.max(1));
+    while batch.processing_status != "ended" {
+        tokio::time::sleep(poll_interval).await;
+        batch = client.get_message_batch(&batch.id).await?;
+        let counts = &batch.request_counts;
+        println!(
+            "Status: {} ({} processing, {} succeeded, {} errored)",
+            batch.processing_status, counts.processing, counts.succeeded, counts.errored
+        );
+    }
+
+    let results = client.get_message_batch_results(&batch).await?;
+    write_results(&options.output, &results)?;
+
+    let counts = &batch.request_counts;
+    println!(
+        "Done: {} succeeded, {} errored, {} canceled, {} expired. Results written to {}",
+        counts.succeeded,
+        counts.errored,
+        counts.canceled,
+        counts.expired,
+        options.output.display()
+    );
+    Ok(())
+}
+
+/// Parse input JSONL into batch request items. Blank lines are skipped;
+/// a malformed line fails the whole run (better than silently dropping
+/// part of an evaluation set).
+fn parse_input(content: &str, default_model: &str) -> Result<Vec<BatchRequestItem>> {
+    let mut requests = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(line).map_err(|e| {
+            Error::InvalidInput(format!("Invalid JSON on input line {}: {}", index + 1, e))
+        })?;
+        requests.push(parse_request_line(&value, default_model, index)?);
+    }
+    Ok(requests)
+}
+
+/// Build one batch item from an input line
+fn parse_request_line(value: &Value, default_model: &str, index: usize) -> Result<BatchRequestItem> {
+    let custom_id = value
+        .get("custom_id")
+        .and_then(|id| id.as_str())
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| format!("request-{}", index + 1));
+
+    let messages = if let Some(prompt) = value.get("prompt").and_then(|p| p.as_str()) {
+        vec![Message {
+            role: MessageRole::User,
+            content: MessageContent::Text(prompt.to_string()),
+            name: None,
+        }]
+    } else if let Some(raw) = value.get("messages") {
+        serde_json::from_value(raw.clone()).map_err(|e| {
+            Error::InvalidInput(format!(
+                "Invalid messages on input line {}: {}",
+                index + 1,
+                e
+            ))
+        })?
+    } else {
+        return Err(Error::InvalidInput(format!(
+            "Input line {} needs a \"prompt\" string or \"messages\" array",
+            index + 1
+        )));
+    };
+
+    let model = value
+        .get("model")
+        .and_then(|m| m.as_str())
+        .unwrap_or(default_model)
+        .to_string();
+
+    let mut request = ChatRequest {
+        model,
+        messages,
+        max_tokens: value.get("max_tokens").and_then(|t| t.as_u64()).map(|t| t as u32),
+        temperature: None,
+        top_p: None,
+        top_k: None,
+        stop_sequences: None,
+        stream: None,
+        system: value
+            .get("system")
+            .and_then(|s| s.as_str())
+            .map(|s| s.to_string()),
+        tools: None,
+        tool_choice: None,
+        metadata: None,
+        betas: None,
+        thinking: None,
+        service_tier: None,
+    };
+    // max_tokens is mandatory for batched requests
+    if request.max_tokens.is_none() {
+        request.max_tokens = Some(4096);
+    }
+
+    Ok(BatchRequestItem {
+        custom_id,
+        params: request,
+    })
+}
+
+/// Write the results JSONL, creating parent directories as needed
+fn write_results(path: &Path, results: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                Error::Other(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+    }
+    std::fs::write(path, results)
+        .map_err(|e| Error::Other(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prompt_lines() {
+        let content = r#"{"prompt": "What is 2+2?"}
+
+{"custom_id": "eval-7", "prompt": "Name a prime", "model": "claude-haiku-4-5-20251001", "max_tokens": 100}
+"#;
+        let requests = parse_input(content, "claude-opus-4-1-20250805").unwrap();
+        assert_eq!(requests.len(), 2);
+
+        assert_eq!(requests[0].custom_id, "request-1");
+        assert_eq!(requests[0].params.model, "claude-opus-4-1-20250805");
+        assert_eq!(requests[0].params.max_tokens, Some(4096));
+
+        assert_eq!(requests[1].custom_id, "eval-7");
+        assert_eq!(requests[1].params.model, "claude-haiku-4-5-20251001");
+        assert_eq!(requests[1].params.max_tokens, Some(100));
+    }
+
+    #[test]
+    fn test_parse_messages_line() {
+        let content = r#"{"messages": [{"role": "user", "content": "hi"}], "system": "Be terse"}"#;
+        let requests = parse_input(content, "claude-opus-4-1-20250805").unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].params.system.as_deref(), Some("Be terse"));
+        assert!(matches!(
+            requests[0].params.messages[0].content,
+            MessageContent::Text(ref t) if t == "hi"
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_lines() {
+        // Malformed JSON fails with the line number
+        let err = parse_input("not json", "m").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+
+        // A line with neither prompt nor messages is rejected
+        let err = parse_input(r#"{"custom_id": "x"}"#, "m").unwrap_err();
+        assert!(err.to_string().contains("prompt"));
+    }
+}
@@ -0,0 +1,728 @@
+//! Native AWS Bedrock backend for Claude models.
+//!
+//! Sends `ChatRequest`s to Bedrock's `InvokeModel` /
+//! `InvokeModelWithResponseStream` endpoints, reusing the credential
+//! resolution chain from `auth::aws` (environment, AWS CLI ini files,
+//! container and instance metadata) and its SigV4 signer. Bedrock speaks
+//! the Anthropic Messages API with two differences handled here: the
+//! model goes in the URL path instead of the body (replaced by
+//! `anthropic_version`), and streaming uses the AWS binary event-stream
+//! framing instead of SSE — each frame payload carries a base64-encoded
+//! Anthropic stream event, which we decode back into the shared
+//! `StreamEvent` sequence.
+//!
+//! Selected via `AIConfig::provider = "bedrock"` or the
+//! `CLAUDE_CODE_USE_BEDROCK` environment variable.
+//!
+//! Cross-region inference profiles are supported by setting
+//! `CLAUDE_CODE_BEDROCK_INFERENCE_PROFILE` to a profile id (e.g.
+//! `us.anthropic.claude-3-5-sonnet-20240620-v1:0`) or ARN, which replaces
+//! the model id on the wire. `CLAUDE_CODE_BEDROCK_REGIONS` takes an
+//! ordered comma-separated region list; requests fail over to the next
+//! region on throttling or unavailability, and the region that served
+//! each request is surfaced in debug logs and telemetry.
+
+use crate::ai::client::{parse_sse_event, SseEvent, StreamEvent};
+use crate::ai::{ChatRequest, ChatResponse};
+use crate::auth::aws::{
+    AwsCredentials, CredentialProvider, DefaultCredentialProvider, MemoizedProvider, SignatureV4,
+};
+use crate::error::{Error, Result};
+use base64::Engine;
+use futures::stream::{Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Anthropic version string Bedrock expects in the request body
+const BEDROCK_ANTHROPIC_VERSION: &str = "bedrock-2023-05-31";
+
+/// Client for Claude models hosted on AWS Bedrock
+pub struct BedrockBackend {
+    config: crate::ai::AIConfig,
+    http_client: reqwest::Client,
+    /// Regions to try in order; the first is the primary
+    regions: Vec<String>,
+    /// Pinned endpoint (ANTHROPIC_BEDROCK_BASE_URL); disables per-region
+    /// endpoint derivation but not per-region signing
+    base_url_override: Option<String>,
+    /// Inference profile id or ARN replacing the model id on the wire
+    inference_profile: Option<String>,
+    credentials: MemoizedProvider,
+}
+
+impl BedrockBackend {
+    pub fn new(config: crate::ai::AIConfig) -> Result<Self> {
+        let regions = match std::env::var("CLAUDE_CODE_BEDROCK_REGIONS") {
+            Ok(raw) if !raw.trim().is_empty() => parse_region_list(&raw),
+            _ => {
+                let region = std::env::var("AWS_REGION")
+                    .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+                    .unwrap_or_else(|_| "us-east-1".to_string());
+                vec![region]
+            }
+        };
+
+        let inference_profile = std::env::var("CLAUDE_CODE_BEDROCK_INFERENCE_PROFILE")
+            .ok()
+            .filter(|profile| !profile.is_empty());
+
+        // Same endpoint resolution as auth::client::BedrockClient
+        let base_url_override = std::env::var("ANTHROPIC_BEDROCK_BASE_URL").ok();
+
+        let http_client = crate::utils::http::client_builder()?
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            config,
+            http_client,
+            regions,
+            base_url_override,
+            inference_profile,
+            credentials: MemoizedProvider::new(Box::new(DefaultCredentialProvider::new())),
+        })
+    }
+
+    /// Endpoint for a region, unless ANTHROPIC_BEDROCK_BASE_URL pins one
+    fn endpoint_for(&self, region: &str) -> String {
+        self.base_url_override
+            .clone()
+            .unwrap_or_else(|| format!("https://bedrock-runtime.{}.amazonaws.com", region))
+    }
+
+    /// The model id sent on the wire: the configured inference profile
+    /// when set, otherwise the request's model
+    fn resolve_model<'a>(&'a self, model: &'a str) -> &'a str {
+        self.inference_profile.as_deref().unwrap_or(model)
+    }
+
+    async fn resolve_credentials(&self) -> Result<AwsCredentials> {
+        self.credentials.get_credentials().await.map_err(|e| {
+            Error::Auth(format!(
+                "Could not resolve AWS credentials for Bedrock: {}",
+                e
+            ))
+        })
+    }
+
+    /// URL path for the given action, with the model id percent-encoded.
+    ///
+    /// Model ids contain `:` (e.g. `...-v1:0`); the wire path carries them
+    /// once-encoded and the SigV4 canonical path encodes again, matching
+    /// the AWS SDK's double-encoding rule for non-S3 services.
+    fn model_path(&self, model: &str, action: &str) -> String {
+        format!("/model/{}/{}", urlencoding::encode(model), action)
+    }
+
+    /// Sign and send a POST to Bedrock, failing over through the region
+    /// list on throttling or unavailability
+    async fn send_signed(&self, path: &str, body: Vec<u8>) -> Result<reqwest::Response> {
+        let credentials = self.resolve_credentials().await?;
+        let mut last_error: Option<Error> = None;
+
+        for (attempt, region) in self.regions.iter().enumerate() {
+            match self
+                .send_signed_to_region(region, path, &body, &credentials)
+                .await
+            {
+                Ok(response) => {
+                    tracing::debug!(
+                        region = %region,
+                        attempt = attempt + 1,
+                        path = %path,
+                        "Bedrock request served"
+                    );
+                    crate::telemetry::track(
+                        "bedrock_request",
+                        json!({
+                            "region": region,
+                            "failover": attempt > 0,
+                        }),
+                    )
+                    .await;
+                    return Ok(response);
+                }
+                Err((error, retryable)) => {
+                    if retryable && attempt + 1 < self.regions.len() {
+                        tracing::debug!(
+                            region = %region,
+                            error = %error,
+                            "Bedrock region failed, trying next region"
+                        );
+                        last_error = Some(error);
+                        continue;
+                    }
+                    return Err(error);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| Error::Other("No Bedrock regions configured".to_string())))
+    }
+
+    /// One signed attempt against a single region. The error side carries
+    /// whether failing over to the next region makes sense.
+    async fn send_signed_to_region(
+        &self,
+        region: &str,
+        path: &str,
+        body: &[u8],
+        credentials: &AwsCredentials,
+    ) -> std::result::Result<reqwest::Response, (Error, bool)> {
+        let base_url = self.endpoint_for(region);
+        let host = base_url
+            .strip_prefix("https://")
+            .or_else(|| base_url.strip_prefix("http://"))
+            .unwrap_or(&base_url)
+            .trim_end_matches('/')
+            .to_string();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("accept", HeaderValue::from_static("application/json"));
+        headers.insert(
+            "host",
+            HeaderValue::from_str(&host)
+                .map_err(|e| (Error::Other(format!("Invalid Bedrock host: {}", e)), false))?,
+        );
+
+        // Gateway header passthrough, inserted before signing so the
+        // signature covers them
+        for (name, value) in crate::config::get_custom_headers("bedrock") {
+            if let (Ok(header_name), Ok(header_value)) = (
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                headers.insert(header_name, header_value);
+            }
+        }
+
+        let signer = SignatureV4::new(region.to_string(), "bedrock".to_string());
+        signer
+            .sign("POST", path, &mut headers, body, credentials)
+            .await
+            .map_err(|e| (Error::Auth(format!("SigV4 signing failed: {}", e)), false))?;
+
+        let url = format!("{}{}", base_url.trim_end_matches('/'), path);
+        let response = self
+            .http_client
+            .post(&url)
+            .headers(headers)
+            .body(body.to_vec())
+            .send()
+            .await
+            // Connection failures are worth retrying in another region
+            .map_err(|e| {
+                (
+                    Error::Other(format!("Failed to send Bedrock request: {}", e)),
+                    true,
+                )
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let retryable = is_failover_status(status.as_u16());
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err((
+                Error::Other(format!(
+                    "Bedrock request failed in {} with status {}: {}",
+                    region, status, text
+                )),
+                retryable || is_failover_exception(&text),
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Send a non-streaming chat request via InvokeModel
+    pub async fn chat(&self, request: &ChatRequest) -> Result<ChatResponse> {
+        let payload = shape_payload(request, self.config.max_tokens)?;
+        let path = self.model_path(self.resolve_model(&request.model), "invoke");
+        let response = self.send_signed(&path, payload).await?;
+
+        // The InvokeModel response body is a plain Anthropic Messages
+        // response; only `model` is absent, which serde tolerates via the
+        // response including it on current Bedrock versions
+        response
+            .json::<ChatResponse>()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to parse Bedrock response: {}", e)))
+    }
+
+    /// Send a streaming chat request via InvokeModelWithResponseStream
+    pub async fn chat_stream(
+        &self,
+        request: &ChatRequest,
+    ) -> Result<impl Stream<Item = Result<StreamEvent>> + Send> {
+        let payload = shape_payload(request, self.config.max_tokens)?;
+        let path = self.model_path(
+            self.resolve_model(&request.model),
+            "invoke-with-response-stream",
+        );
+        let response = self.send_signed(&path, payload).await?;
+        Ok(parse_event_stream(response.bytes_stream()))
+    }
+
+    /// List the Anthropic models this account can invoke via
+    /// ListFoundationModels.
+    ///
+    /// This is a control-plane call (`bedrock.{region}`, not
+    /// `bedrock-runtime`), so ANTHROPIC_BEDROCK_BASE_URL does not apply;
+    /// the primary region is queried directly. Returns `(model_id,
+    /// display_name)` pairs for active on-demand models.
+    pub async fn list_foundation_models(&self) -> Result<Vec<(String, String)>> {
+        let credentials = self.resolve_credentials().await?;
+        let region = self
+            .regions
+            .first()
+            .ok_or_else(|| Error::Other("No Bedrock regions configured".to_string()))?;
+        let host = format!("bedrock.{}.amazonaws.com", region);
+        let path = "/foundation-models";
+
+        let mut headers = HeaderMap::new();
+        headers.insert("accept", HeaderValue::from_static("application/json"));
+        headers.insert(
+            "host",
+            HeaderValue::from_str(&host)
+                .map_err(|e| Error::Other(format!("Invalid Bedrock host: {}", e)))?,
+        );
+
+        let signer = SignatureV4::new(region.clone(), "bedrock".to_string());
+        signer
+            .sign("GET", path, &mut headers, b"", &credentials)
+            .await
+            .map_err(|e| Error::Auth(format!("SigV4 signing failed: {}", e)))?;
+
+        let url = format!("https://{}{}", host, path);
+        let response = self
+            .http_client
+            .get(&url)
+            .headers(headers)
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to list Bedrock models: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(Error::Other(format!(
+                "ListFoundationModels failed with status {}: {}",
+                status, text
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to parse model list: {}", e)))?;
+        Ok(parse_foundation_models(&body))
+    }
+}
+
+/// Extract invocable Anthropic models from a ListFoundationModels
+/// response: active lifecycle, on-demand inference, provider "Anthropic"
+fn parse_foundation_models(body: &Value) -> Vec<(String, String)> {
+    let mut models = Vec::new();
+    let Some(summaries) = body.get("modelSummaries").and_then(|s| s.as_array()) else {
+        return models;
+    };
+    for summary in summaries {
+        let provider = summary
+            .get("providerName")
+            .and_then(|p| p.as_str())
+            .unwrap_or("");
+        if !provider.eq_ignore_ascii_case("anthropic") {
+            continue;
+        }
+        let active = summary
+            .get("modelLifecycle")
+            .and_then(|l| l.get("status"))
+            .and_then(|s| s.as_str())
+            .map(|s| s == "ACTIVE")
+            .unwrap_or(true);
+        let on_demand = summary
+            .get("inferenceTypesSupported")
+            .and_then(|t| t.as_array())
+            .map(|types| types.iter().any(|t| t.as_str() == Some("ON_DEMAND")))
+            .unwrap_or(true);
+        if !active || !on_demand {
+            continue;
+        }
+        if let Some(model_id) = summary.get("modelId").and_then(|id| id.as_str()) {
+            let name = summary
+                .get("modelName")
+                .and_then(|n| n.as_str())
+                .unwrap_or(model_id);
+            models.push((model_id.to_string(), name.to_string()));
+        }
+    }
+    models
+}
+
+/// Parse the ordered CLAUDE_CODE_BEDROCK_REGIONS list (comma-separated)
+fn parse_region_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|region| region.trim().to_string())
+        .filter(|region| !region.is_empty())
+        .collect()
+}
+
+/// HTTP statuses where trying another region can help: throttling and
+/// server-side unavailability
+fn is_failover_status(status: u16) -> bool {
+    matches!(status, 429 | 500 | 502 | 503 | 504)
+}
+
+/// AWS exception names (in the error body) where another region can help
+fn is_failover_exception(body: &str) -> bool {
+    body.contains("ThrottlingException")
+        || body.contains("ServiceUnavailableException")
+        || body.contains("ModelNotReadyException")
+}
+
+/// Shape a `ChatRequest` into a Bedrock invoke body.
+///
+/// Bedrock takes the Messages API body with the model moved to the URL,
+/// `anthropic_version` added, and no `stream`/`betas`/`metadata` fields.
+/// `max_tokens` is mandatory on Bedrock, so the configured default is
+/// filled in when the request leaves it unset.
+fn shape_payload(request: &ChatRequest, default_max_tokens: u32) -> Result<Vec<u8>> {
+    let mut body = serde_json::to_value(request)
+        .map_err(|e| Error::Other(format!("Failed to serialize request: {}", e)))?;
+    if let Some(map) = body.as_object_mut() {
+        map.remove("model");
+        map.remove("stream");
+        map.remove("betas");
+        map.remove("metadata");
+        map.insert(
+            "anthropic_version".to_string(),
+            json!(BEDROCK_ANTHROPIC_VERSION),
+        );
+        if !map.contains_key("max_tokens") {
+            map.insert("max_tokens".to_string(), json!(default_max_tokens));
+        }
+    }
+    serde_json::to_vec(&body)
+        .map_err(|e| Error::Other(format!("Failed to serialize request: {}", e)))
+}
+
+/// Incremental parser for the AWS binary event-stream framing.
+///
+/// Frame layout: 4-byte total length, 4-byte headers length, 4-byte
+/// prelude CRC, headers, payload, 4-byte message CRC (all big-endian).
+/// For Bedrock the payload is JSON `{"bytes": "<base64>"}` where the
+/// decoded bytes are one Anthropic stream event.
+struct EventStreamState {
+    buffer: Vec<u8>,
+    event_queue: std::collections::VecDeque<Result<StreamEvent>>,
+}
+
+impl EventStreamState {
+    fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            event_queue: std::collections::VecDeque::new(),
+        }
+    }
+
+    fn process_buffer(&mut self) {
+        loop {
+            if self.buffer.len() < 12 {
+                return;
+            }
+            let total_len =
+                u32::from_be_bytes([self.buffer[0], self.buffer[1], self.buffer[2], self.buffer[3]])
+                    as usize;
+            let headers_len =
+                u32::from_be_bytes([self.buffer[4], self.buffer[5], self.buffer[6], self.buffer[7]])
+                    as usize;
+            if total_len < 16 || headers_len + 16 > total_len {
+                self.event_queue.push_back(Err(Error::Other(format!(
+                    "Malformed event-stream frame (total {}, headers {})",
+                    total_len, headers_len
+                ))));
+                self.buffer.clear();
+                return;
+            }
+            if self.buffer.len() < total_len {
+                // Incomplete frame, wait for more bytes
+                return;
+            }
+
+            let payload_start = 12 + headers_len;
+            let payload_end = total_len - 4;
+            let payload = self.buffer[payload_start..payload_end].to_vec();
+            self.process_payload(&payload);
+            self.buffer.drain(..total_len);
+        }
+    }
+
+    fn process_payload(&mut self, payload: &[u8]) {
+        if payload.is_empty() {
+            return;
+        }
+        let value: Value = match serde_json::from_slice(payload) {
+            Ok(value) => value,
+            Err(parse_error) => {
+                self.event_queue.push_back(Err(Error::Other(format!(
+                    "Failed to parse event-stream payload: {}",
+                    parse_error
+                ))));
+                return;
+            }
+        };
+
+        // Exception frames carry a message instead of bytes
+        if let Some(message) = value.get("message").and_then(|m| m.as_str()) {
+            if value.get("bytes").is_none() {
+                self.event_queue
+                    .push_back(Ok(StreamEvent::Error(message.to_string())));
+                return;
+            }
+        }
+
+        let Some(encoded) = value.get("bytes").and_then(|b| b.as_str()) else {
+            return;
+        };
+        let decoded = match base64::engine::general_purpose::STANDARD.decode(encoded) {
+            Ok(decoded) => decoded,
+            Err(decode_error) => {
+                self.event_queue.push_back(Err(Error::Other(format!(
+                    "Failed to decode event-stream bytes: {}",
+                    decode_error
+                ))));
+                return;
+            }
+        };
+
+        match serde_json::from_slice::<SseEvent>(&decoded) {
+            Ok(event) => self.event_queue.push_back(parse_sse_event(event)),
+            Err(parse_error) => {
+                self.event_queue.push_back(Err(Error::Other(format!(
+                    "Failed to parse Bedrock stream event: {}. Data was: '{}'",
+                    parse_error,
+                    String::from_utf8_lossy(&decoded)
+                ))));
+            }
+        }
+    }
+}
+
+/// Parse a Bedrock response stream into Anthropic-style `StreamEvent`s
+fn parse_event_stream(
+    stream: impl Stream<Item = reqwest::Result<bytes::Bytes>> + Send + 'static,
+) -> impl Stream<Item = Result<StreamEvent>> + Send {
+    use futures::stream;
+
+    let pinned_stream = Box::pin(stream);
+
+    stream::unfold(
+        (pinned_stream, EventStreamState::new()),
+        |(mut stream, mut state)| async move {
+            loop {
+                if let Some(event) = state.event_queue.pop_front() {
+                    return Some((event, (stream, state)));
+                }
+
+                match stream.next().await {
+                    Some(Ok(bytes)) => {
+                        state.buffer.extend_from_slice(&bytes);
+                        state.process_buffer();
+                    }
+                    Some(Err(stream_error)) => {
+                        return Some((
+                            Err(Error::Other(format!("Stream error: {}", stream_error))),
+                            (stream, state),
+                        ));
+                    }
+                    None => return None,
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{Message, MessageContent, MessageRole};
+
+    fn basic_request() -> ChatRequest {
+        ChatRequest {
+            model: "anthropic.claude-3-5-sonnet-20240620-v1:0".to_string(),
+            messages: vec![Message {
+                role: MessageRole::User,
+                content: MessageContent::Text("hello".to_string()),
+                name: None,
+            }],
+            max_tokens: None,
+            temperature: Some(0.7),
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: Some(true),
+            system: Some("Be terse".to_string()),
+            tools: None,
+            tool_choice: None,
+            metadata: None,
+            betas: None,
+            thinking: None,
+            service_tier: None,
+        }
+    }
+
+    #[test]
+    fn test_shape_payload_bedrock_fields() {
+        let payload = shape_payload(&basic_request(), 4096).unwrap();
+        let body: Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(body["anthropic_version"], BEDROCK_ANTHROPIC_VERSION);
+        assert_eq!(body["max_tokens"], 4096);
+        assert_eq!(body["system"], "Be terse");
+        assert!(body.get("model").is_none());
+        assert!(body.get("stream").is_none());
+    }
+
+    #[test]
+    fn test_model_path_encodes_model_id() {
+        let config = crate::ai::AIConfig {
+            provider: crate::ai::Provider::Bedrock,
+            ..Default::default()
+        };
+        let backend = BedrockBackend::new(config).unwrap();
+        assert_eq!(
+            backend.model_path("anthropic.claude-3-5-sonnet-20240620-v1:0", "invoke"),
+            "/model/anthropic.claude-3-5-sonnet-20240620-v1%3A0/invoke"
+        );
+    }
+
+    #[test]
+    fn test_parse_region_list() {
+        assert_eq!(
+            parse_region_list("us-east-1, us-west-2,eu-central-1,"),
+            vec!["us-east-1", "us-west-2", "eu-central-1"]
+        );
+        assert!(parse_region_list("").is_empty());
+    }
+
+    #[test]
+    fn test_failover_classification() {
+        assert!(is_failover_status(429));
+        assert!(is_failover_status(503));
+        assert!(!is_failover_status(400));
+        assert!(!is_failover_status(403));
+        assert!(is_failover_exception(
+            r#"{"__type":"ThrottlingException","message":"Too many requests"}"#
+        ));
+        assert!(!is_failover_exception(
+            r#"{"__type":"ValidationException","message":"bad input"}"#
+        ));
+    }
+
+    #[test]
+    fn test_inference_profile_replaces_model() {
+        let config = crate::ai::AIConfig {
+            provider: crate::ai::Provider::Bedrock,
+            ..Default::default()
+        };
+        let mut backend = BedrockBackend::new(config).unwrap();
+        backend.inference_profile =
+            Some("us.anthropic.claude-3-5-sonnet-20240620-v1:0".to_string());
+        assert_eq!(
+            backend.resolve_model("anthropic.claude-3-5-sonnet-20240620-v1:0"),
+            "us.anthropic.claude-3-5-sonnet-20240620-v1:0"
+        );
+        backend.inference_profile = None;
+        assert_eq!(
+            backend.resolve_model("anthropic.claude-3-5-sonnet-20240620-v1:0"),
+            "anthropic.claude-3-5-sonnet-20240620-v1:0"
+        );
+    }
+
+    #[test]
+    fn test_parse_foundation_models_filters() {
+        let body = json!({
+            "modelSummaries": [
+                {
+                    "modelId": "anthropic.claude-3-5-sonnet-20240620-v1:0",
+                    "modelName": "Claude 3.5 Sonnet",
+                    "providerName": "Anthropic",
+                    "inferenceTypesSupported": ["ON_DEMAND"],
+                    "modelLifecycle": {"status": "ACTIVE"}
+                },
+                {
+                    "modelId": "anthropic.claude-v2",
+                    "modelName": "Claude 2",
+                    "providerName": "Anthropic",
+                    "inferenceTypesSupported": ["ON_DEMAND"],
+                    "modelLifecycle": {"status": "LEGACY"}
+                },
+                {
+                    "modelId": "amazon.titan-text-express-v1",
+                    "modelName": "Titan Text Express",
+                    "providerName": "Amazon",
+                    "inferenceTypesSupported": ["ON_DEMAND"],
+                    "modelLifecycle": {"status": "ACTIVE"}
+                },
+                {
+                    "modelId": "anthropic.claude-3-opus-20240229-v1:0",
+                    "modelName": "Claude 3 Opus",
+                    "providerName": "Anthropic",
+                    "inferenceTypesSupported": ["PROVISIONED"],
+                    "modelLifecycle": {"status": "ACTIVE"}
+                }
+            ]
+        });
+        let models = parse_foundation_models(&body);
+        assert_eq!(
+            models,
+            vec![(
+                "anthropic.claude-3-5-sonnet-20240620-v1:0".to_string(),
+                "Claude 3.5 Sonnet".to_string()
+            )]
+        );
+        assert!(parse_foundation_models(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_event_stream_frame_decoding() {
+        // One frame whose payload wraps a base64-encoded message_stop event
+        let event_json = base64::engine::general_purpose::STANDARD
+            .encode(r#"{"type":"message_stop"}"#);
+        let payload = format!(r#"{{"bytes":"{}"}}"#, event_json);
+        let headers: &[u8] = &[];
+        let total_len = 12 + headers.len() + payload.len() + 4;
+
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&(total_len as u32).to_be_bytes());
+        frame.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&[0u8; 4]); // prelude CRC (not verified)
+        frame.extend_from_slice(headers);
+        frame.extend_from_slice(payload.as_bytes());
+        frame.extend_from_slice(&[0u8; 4]); // message CRC (not verified)
+
+        let mut state = EventStreamState::new();
+        // Feed in two chunks to exercise partial-frame buffering
+        state.buffer.extend_from_slice(&frame[..10]);
+        state.process_buffer();
+        assert!(state.event_queue.is_empty());
+        state.buffer.extend_from_slice(&frame[10..]);
+        state.process_buffer();
+
+        let events: Vec<StreamEvent> = state
+            .event_queue
+            .into_iter()
+            .map(|event| event.unwrap())
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], StreamEvent::MessageStop));
+    }
+}
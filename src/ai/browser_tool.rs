@@ -0,0 +1,458 @@
+use crate::ai::tools::ToolHandler;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command as TokioCommand};
+use tokio::sync::Mutex;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tokio_util::sync::CancellationToken;
+use which::which;
+
+/// Default per-command timeout for DevTools protocol calls
+const CDP_COMMAND_TIMEOUT_MS: u64 = 30_000;
+
+/// Maximum time to wait for a page load after navigation
+const PAGE_LOAD_TIMEOUT_MS: u64 = 30_000;
+
+/// Shared headless browser session, started lazily on first Browser tool use
+/// and reused for the rest of the session (like the persistent shell sessions)
+static BROWSER_SESSION: Lazy<Mutex<Option<BrowserSession>>> = Lazy::new(|| Mutex::new(None));
+
+/// A running headless Chrome/Chromium instance with a DevTools page connection
+struct BrowserSession {
+    child: Child,
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+    user_data_dir: PathBuf,
+}
+
+impl BrowserSession {
+    /// Locate a Chrome/Chromium executable, honoring CLAUDE_CHROME_PATH
+    fn find_browser_executable() -> Result<PathBuf> {
+        if let Ok(path) = std::env::var("CLAUDE_CHROME_PATH") {
+            let path = PathBuf::from(path);
+            if path.exists() {
+                return Ok(path);
+            }
+        }
+        for candidate in [
+            "google-chrome",
+            "google-chrome-stable",
+            "chromium",
+            "chromium-browser",
+            "chrome",
+        ] {
+            if let Ok(path) = which(candidate) {
+                return Ok(path);
+            }
+        }
+        Err(Error::ToolExecution(
+            "No Chrome or Chromium executable found. Install Chrome/Chromium or set CLAUDE_CHROME_PATH.".to_string(),
+        ))
+    }
+
+    /// Launch headless Chrome with remote debugging and connect to its first page target
+    async fn launch() -> Result<Self> {
+        let executable = Self::find_browser_executable()?;
+        let user_data_dir = std::env::temp_dir().join(format!(
+            "claude-browser-{}",
+            &uuid::Uuid::new_v4().to_string()[..8]
+        ));
+
+        let mut child = TokioCommand::new(&executable)
+            .arg("--headless=new")
+            .arg("--remote-debugging-port=0")
+            .arg("--no-first-run")
+            .arg("--no-default-browser-check")
+            .arg("--no-sandbox")
+            .arg("--disable-gpu")
+            .arg(format!("--user-data-dir={}", user_data_dir.display()))
+            .arg("about:blank")
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| Error::ToolExecution(format!("Failed to launch browser: {}", e)))?;
+
+        // Chrome announces the DevTools endpoint on stderr:
+        //   DevTools listening on ws://127.0.0.1:PORT/devtools/browser/UUID
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| Error::ToolExecution("Failed to capture browser stderr".to_string()))?;
+        let mut lines = BufReader::new(stderr).lines();
+        let port = tokio::time::timeout(Duration::from_millis(15_000), async {
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Some(ws_url) = line.strip_prefix("DevTools listening on ") {
+                    if let Ok(parsed) = url::Url::parse(ws_url.trim()) {
+                        if let Some(port) = parsed.port() {
+                            return Some(port);
+                        }
+                    }
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| {
+            Error::ToolExecution("Browser did not report a DevTools endpoint".to_string())
+        })?;
+
+        // Resolve the page target's WebSocket URL via the HTTP endpoint
+        let targets: Vec<Value> = reqwest::get(format!("http://127.0.0.1:{}/json/list", port))
+            .await
+            .map_err(Error::Request)?
+            .json()
+            .await
+            .map_err(Error::Request)?;
+        let page_ws_url = targets
+            .iter()
+            .find(|t| t["type"].as_str() == Some("page"))
+            .and_then(|t| t["webSocketDebuggerUrl"].as_str())
+            .ok_or_else(|| Error::ToolExecution("No page target found in browser".to_string()))?
+            .to_string();
+
+        let (ws, _) = connect_async(&page_ws_url)
+            .await
+            .map_err(|e| Error::ToolExecution(format!("Failed to connect to DevTools: {}", e)))?;
+
+        Ok(Self {
+            child,
+            ws,
+            next_id: 1,
+            user_data_dir,
+        })
+    }
+
+    /// Send a CDP command and wait for its matching response
+    async fn send_command(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({ "id": id, "method": method, "params": params });
+        self.ws
+            .send(Message::Text(request.to_string()))
+            .await
+            .map_err(|e| Error::ToolExecution(format!("DevTools send failed: {}", e)))?;
+
+        let response = tokio::time::timeout(Duration::from_millis(CDP_COMMAND_TIMEOUT_MS), async {
+            while let Some(message) = self.ws.next().await {
+                let message =
+                    message.map_err(|e| Error::ToolExecution(format!("DevTools receive failed: {}", e)))?;
+                if let Message::Text(text) = message {
+                    let value: Value = serde_json::from_str(&text).map_err(Error::Serialization)?;
+                    // Skip CDP events; only return the reply to our command id
+                    if value["id"].as_u64() == Some(id) {
+                        return Ok(value);
+                    }
+                }
+            }
+            Err(Error::ToolExecution("DevTools connection closed".to_string()))
+        })
+        .await
+        .map_err(|_| Error::ToolExecution(format!("DevTools command timed out: {}", method)))??;
+
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            return Err(Error::ToolExecution(format!(
+                "DevTools error for {}: {}",
+                method,
+                error["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(response["result"].clone())
+    }
+
+    /// Evaluate a JavaScript expression in the page and return its value
+    async fn evaluate(&mut self, expression: &str) -> Result<Value> {
+        let result = self
+            .send_command(
+                "Runtime.evaluate",
+                json!({
+                    "expression": expression,
+                    "returnByValue": true,
+                    "awaitPromise": true
+                }),
+            )
+            .await?;
+        if let Some(exception) = result.get("exceptionDetails").filter(|e| !e.is_null()) {
+            return Err(Error::ToolExecution(format!(
+                "JavaScript error: {}",
+                exception["exception"]["description"]
+                    .as_str()
+                    .or_else(|| exception["text"].as_str())
+                    .unwrap_or("unknown error")
+            )));
+        }
+        Ok(result["result"]["value"].clone())
+    }
+
+    /// Navigate to a URL and wait for the document to finish loading
+    async fn navigate(&mut self, target_url: &str) -> Result<String> {
+        let result = self
+            .send_command("Page.navigate", json!({ "url": target_url }))
+            .await?;
+        if let Some(error_text) = result["errorText"].as_str() {
+            if !error_text.is_empty() {
+                return Err(Error::ToolExecution(format!(
+                    "Navigation to {} failed: {}",
+                    target_url, error_text
+                )));
+            }
+        }
+
+        // Poll document.readyState rather than relying on Page events so we do
+        // not need to enable event domains on the connection
+        let deadline = std::time::Instant::now() + Duration::from_millis(PAGE_LOAD_TIMEOUT_MS);
+        loop {
+            let state = self.evaluate("document.readyState").await?;
+            if state.as_str() == Some("complete") {
+                break;
+            }
+            if std::time::Instant::now() >= deadline {
+                return Err(Error::ToolExecution(format!(
+                    "Page load timed out after {}ms: {}",
+                    PAGE_LOAD_TIMEOUT_MS, target_url
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+
+        let title = self
+            .evaluate("document.title")
+            .await?
+            .as_str()
+            .unwrap_or("")
+            .to_string();
+        Ok(title)
+    }
+
+    /// Shut down the browser and clean up its profile directory
+    async fn shutdown(mut self) {
+        let _ = self.ws.close(None).await;
+        let _ = self.child.kill().await;
+        let _ = std::fs::remove_dir_all(&self.user_data_dir);
+    }
+}
+
+/// Browser tool - headless Chrome automation over the DevTools protocol
+pub struct BrowserTool;
+
+impl BrowserTool {
+    /// Produce a JavaScript string literal for safe embedding in expressions
+    fn js_string(value: &str) -> String {
+        serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+    }
+}
+
+#[async_trait]
+impl ToolHandler for BrowserTool {
+    fn description(&self) -> String {
+        "Controls a headless Chrome/Chromium browser via the DevTools protocol for frontend debugging workflows. The browser is started on first use and persists for the rest of the session, keeping page state between calls.
+
+Supported actions:
+- navigate: Load a URL and wait for the page to finish loading
+- screenshot: Capture the current page as a PNG image (presented visually)
+- text: Extract the visible text of the page, or of a CSS selector if provided
+- click: Click the first element matching a CSS selector
+- fill: Set the value of the first input/textarea matching a CSS selector and dispatch input events
+- evaluate: Run a JavaScript expression in the page and return its result
+- close: Shut down the browser session
+
+Usage:
+- Navigate before using other actions; the session starts on about:blank
+- Screenshots are the most reliable way to diagnose layout and styling issues
+- click and fill require user permission like other mutating tools".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["navigate", "screenshot", "text", "click", "fill", "evaluate", "close"],
+                    "description": "The browser action to perform"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "The URL to load (required for navigate)"
+                },
+                "selector": {
+                    "type": "string",
+                    "description": "CSS selector of the target element (required for click and fill, optional for text)"
+                },
+                "value": {
+                    "type": "string",
+                    "description": "The value to enter (required for fill)"
+                },
+                "expression": {
+                    "type": "string",
+                    "description": "JavaScript expression to run in the page (required for evaluate)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    fn action_description(&self, input: &Value) -> String {
+        match input["action"].as_str().unwrap_or("") {
+            "navigate" => format!(
+                "Navigate browser to: {}",
+                input["url"].as_str().unwrap_or("<unknown>")
+            ),
+            "screenshot" => "Capture browser screenshot".to_string(),
+            "text" => "Extract page text".to_string(),
+            "click" => format!(
+                "Click element: {}",
+                input["selector"].as_str().unwrap_or("<unknown>")
+            ),
+            "fill" => format!(
+                "Fill element: {}",
+                input["selector"].as_str().unwrap_or("<unknown>")
+            ),
+            "evaluate" => "Run JavaScript in page".to_string(),
+            "close" => "Close browser session".to_string(),
+            other => format!("Browser action: {}", other),
+        }
+    }
+
+    fn permission_details(&self, input: &Value) -> String {
+        match input["action"].as_str().unwrap_or("") {
+            "navigate" => format!("URL: {}", input["url"].as_str().unwrap_or("<unknown>")),
+            "click" | "fill" => format!(
+                "Selector: {}",
+                input["selector"].as_str().unwrap_or("<unknown>")
+            ),
+            "evaluate" => format!(
+                "Expression: {}",
+                input["expression"].as_str().unwrap_or("<unknown>")
+            ),
+            _ => "Headless browser automation".to_string(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        input: Value,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Result<String> {
+        let action = input["action"]
+            .as_str()
+            .ok_or_else(|| Error::InvalidInput("Missing 'action' parameter".to_string()))?
+            .to_string();
+
+        let work = async {
+            let mut session_guard = BROWSER_SESSION.lock().await;
+
+            if action == "close" {
+                return match session_guard.take() {
+                    Some(session) => {
+                        session.shutdown().await;
+                        Ok("Browser session closed".to_string())
+                    }
+                    None => Ok("No browser session is running".to_string()),
+                };
+            }
+
+            if session_guard.is_none() {
+                *session_guard = Some(BrowserSession::launch().await?);
+            }
+            let session = session_guard
+                .as_mut()
+                .ok_or_else(|| Error::ToolExecution("Browser session unavailable".to_string()))?;
+
+            match action.as_str() {
+                "navigate" => {
+                    let target_url = input["url"].as_str().ok_or_else(|| {
+                        Error::InvalidInput("Missing 'url' parameter for navigate".to_string())
+                    })?;
+                    let title = session.navigate(target_url).await?;
+                    Ok(format!("Navigated to {} (title: {})", target_url, title))
+                }
+                "screenshot" => {
+                    let result = session
+                        .send_command("Page.captureScreenshot", json!({ "format": "png" }))
+                        .await?;
+                    let data = result["data"].as_str().ok_or_else(|| {
+                        Error::ToolExecution("Screenshot returned no data".to_string())
+                    })?;
+                    let size = data.len() * 3 / 4;
+                    Ok(format!(
+                        "<image>\npath: <browser screenshot>\ntype: image/png\nsize: {} bytes\ndata: data:image/png;base64,{}\n</image>",
+                        size, data
+                    ))
+                }
+                "text" => {
+                    let expression = match input["selector"].as_str() {
+                        Some(selector) => format!(
+                            "(() => {{ const el = document.querySelector({}); if (!el) throw new Error('No element matches selector'); return el.innerText; }})()",
+                            Self::js_string(selector)
+                        ),
+                        None => "document.body ? document.body.innerText : ''".to_string(),
+                    };
+                    let value = session.evaluate(&expression).await?;
+                    Ok(value.as_str().unwrap_or("").to_string())
+                }
+                "click" => {
+                    let selector = input["selector"].as_str().ok_or_else(|| {
+                        Error::InvalidInput("Missing 'selector' parameter for click".to_string())
+                    })?;
+                    let expression = format!(
+                        "(() => {{ const el = document.querySelector({}); if (!el) throw new Error('No element matches selector'); el.click(); return true; }})()",
+                        Self::js_string(selector)
+                    );
+                    session.evaluate(&expression).await?;
+                    Ok(format!("Clicked element: {}", selector))
+                }
+                "fill" => {
+                    let selector = input["selector"].as_str().ok_or_else(|| {
+                        Error::InvalidInput("Missing 'selector' parameter for fill".to_string())
+                    })?;
+                    let value = input["value"].as_str().ok_or_else(|| {
+                        Error::InvalidInput("Missing 'value' parameter for fill".to_string())
+                    })?;
+                    let expression = format!(
+                        "(() => {{ const el = document.querySelector({sel}); if (!el) throw new Error('No element matches selector'); \
+                         const setter = Object.getOwnPropertyDescriptor(Object.getPrototypeOf(el), 'value'); \
+                         if (setter && setter.set) {{ setter.set.call(el, {val}); }} else {{ el.value = {val}; }} \
+                         el.dispatchEvent(new Event('input', {{ bubbles: true }})); \
+                         el.dispatchEvent(new Event('change', {{ bubbles: true }})); return true; }})()",
+                        sel = Self::js_string(selector),
+                        val = Self::js_string(value)
+                    );
+                    session.evaluate(&expression).await?;
+                    Ok(format!("Filled element {} with {} characters", selector, value.len()))
+                }
+                "evaluate" => {
+                    let expression = input["expression"].as_str().ok_or_else(|| {
+                        Error::InvalidInput("Missing 'expression' parameter for evaluate".to_string())
+                    })?;
+                    let value = session.evaluate(expression).await?;
+                    Ok(serde_json::to_string_pretty(&value).map_err(Error::Serialization)?)
+                }
+                other => Err(Error::InvalidInput(format!(
+                    "Unknown browser action: {}",
+                    other
+                ))),
+            }
+        };
+
+        // Honor cancellation like the Bash tool does for long-running commands
+        match cancellation_token {
+            Some(token) => {
+                tokio::select! {
+                    result = work => result,
+                    _ = token.cancelled() => Err(Error::ToolExecution("Browser action cancelled".to_string())),
+                }
+            }
+            None => work.await,
+        }
+    }
+}
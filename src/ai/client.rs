@@ -334,6 +334,8 @@ impl ChatRequestBuilder {
                 tool_choice: None,
                 metadata: None,
                 betas: None,
+                thinking: None,
+                service_tier: None,
             },
         }
     }
@@ -405,6 +407,18 @@ impl ChatRequestBuilder {
         self.request.stream = Some(true);
         self
     }
+
+    /// Enable extended thinking with a token budget
+    pub fn thinking(mut self, thinking: crate::ai::ThinkingConfig) -> Self {
+        self.request.thinking = Some(thinking);
+        self
+    }
+
+    /// Set the service tier ("auto" or "standard_only")
+    pub fn service_tier(mut self, tier: String) -> Self {
+        self.request.service_tier = Some(tier);
+        self
+    }
     
     /// Build the request
     pub fn build(self) -> ChatRequest {
@@ -524,6 +538,10 @@ pub enum ContentDelta {
     /// Signature delta for thinking blocks
     #[serde(rename = "signature_delta")]
     SignatureDelta { signature: String },
+    /// Citation attached to the current text block. Kept as raw JSON since
+    /// the payload shape varies by citation type (web, char_location, ...)
+    #[serde(rename = "citations_delta")]
+    CitationsDelta { citation: serde_json::Value },
 }
 
 /// Message delta
@@ -541,139 +559,32 @@ fn parse_sse_stream(
     stream: impl Stream<Item = reqwest::Result<bytes::Bytes>> + Send + 'static,
 ) -> impl Stream<Item = Result<StreamEvent>> + Send {
     use futures::stream;
-    use std::pin::Pin;
     use std::collections::VecDeque;
     
     // Wrap the stream in Pin<Box<...>> for proper async handling
     let pinned_stream = Box::pin(stream);
-    
-    // State for SSE parsing - holds buffer and event queue
-    struct SseParserState {
-        buffer: String,
-        event_queue: VecDeque<Result<StreamEvent>>,
-    }
-    
-    impl SseParserState {
-        fn new() -> Self {
-            Self {
-                buffer: String::new(),
-                event_queue: VecDeque::new(),
-            }
-        }
-        
-        fn process_buffer(&mut self) {
-            // SSE protocol specification:
-            // - Events are separated by double newline (\n\n)
-            // - Each event consists of field:value pairs
-            // - Field names include: data, event, id, retry
-            // - Comments start with :
-            
-            // Process all complete events in the buffer
-            while let Some(event_boundary) = self.buffer.find("\n\n") {
-                // Extract one complete event (up to and including the \n\n)
-                let event_text: String = self.buffer.drain(..=event_boundary + 1).collect();
-                
-                // Parse the event fields
-                let mut data_fields = Vec::new();
-                let mut event_type: Option<String> = None;
-                
-                for line in event_text.lines() {
-                    if let Some(colon_pos) = line.find(':') {
-                        let field = &line[..colon_pos];
-                        let value = if colon_pos + 1 < line.len() {
-                            // Skip optional space after colon
-                            if line.chars().nth(colon_pos + 1) == Some(' ') {
-                                &line[colon_pos + 2..]
-                            } else {
-                                &line[colon_pos + 1..]
-                            }
-                        } else {
-                            ""
-                        };
-                        
-                        match field {
-                            "data" => {
-                                data_fields.push(value);
-                            }
-                            "event" => {
-                                event_type = Some(value.to_string());
-                            }
-                            "" => {
-                                // Comment line starting with :, ignore
-                            }
-                            _ => {
-                                // Other fields like id, retry - ignore for now
-                            }
-                        }
-                    }
-                }
-                
-                // Process the collected data fields
-                // Multiple data fields are concatenated with \n between them
-                if !data_fields.is_empty() {
-                    let combined_data = data_fields.join("\n");
-                    
-                    // Check for stream termination
-                    if combined_data == "[DONE]" {
-                        continue;
-                    }
-                    
-                    // Parse the JSON data
-                    match serde_json::from_str::<SseEvent>(&combined_data) {
-                        Ok(sse_event) => {
-                            // Successfully parsed, convert to StreamEvent
-                            self.event_queue.push_back(parse_sse_event(sse_event));
-                        }
-                        Err(parse_error) => {
-                            // JSON parsing failed - this is a real error
-                            self.event_queue.push_back(Err(Error::Other(format!(
-                                "Failed to parse SSE event JSON: {}. Data was: '{}'",
-                                parse_error, combined_data
-                            ))));
-                        }
-                    }
-                }
-            }
-            // Anything left in buffer is an incomplete event - keep for next iteration
-        }
-    }
-    
-    // Use unfold to create a stream from stateful async computation
+
+    // SSE framing (chunk buffering, split UTF-8, \n\n event boundaries,
+    // data field concatenation) lives in streaming::SseParser so tests can
+    // exercise it directly; this unfold only feeds it network chunks and
+    // flattens its batched output back into a one-event-at-a-time stream
+    let parser = crate::ai::streaming::SseParser::new();
+
     stream::unfold(
-        (pinned_stream, SseParserState::new()),
-        |(mut stream, mut state)| async move {
+        (pinned_stream, parser, VecDeque::new()),
+        |(mut stream, mut parser, mut queue)| async move {
             // First, return any queued events from previous processing
-            if let Some(event) = state.event_queue.pop_front() {
-                return Some((event, (stream, state)));
+            if let Some(event) = queue.pop_front() {
+                return Some((event, (stream, parser, queue)));
             }
-            
+
             // Read chunks from the stream until we get an event or stream ends
             loop {
                 match stream.next().await {
                     Some(Ok(bytes)) => {
-                        // Convert bytes to UTF-8 string
-                        let text = match std::str::from_utf8(&bytes) {
-                            Ok(text) => text,
-                            Err(utf8_error) => {
-                                return Some((
-                                    Err(Error::Other(format!(
-                                        "Invalid UTF-8 in SSE stream: {}",
-                                        utf8_error
-                                    ))),
-                                    (stream, state),
-                                ));
-                            }
-                        };
-                        
-                        // Append to buffer
-                        state.buffer.push_str(text);
-                        
-                        // Process the buffer to extract complete events
-                        state.process_buffer();
-                        
-                        // If we now have events, return the first one
-                        if let Some(event) = state.event_queue.pop_front() {
-                            return Some((event, (stream, state)));
+                        queue.extend(parser.push(&bytes));
+                        if let Some(event) = queue.pop_front() {
+                            return Some((event, (stream, parser, queue)));
                         }
                         // Otherwise continue reading more chunks
                     }
@@ -684,29 +595,16 @@ fn parse_sse_stream(
                                 "Network error in SSE stream: {}",
                                 network_error
                             ))),
-                            (stream, state),
+                            (stream, parser, queue),
                         ));
                     }
                     None => {
-                        // Stream has ended
-                        
-                        // Check for incomplete data in buffer
-                        if !state.buffer.trim().is_empty() {
-                            return Some((
-                                Err(Error::Other(format!(
-                                    "SSE stream ended with incomplete event: '{}'",
-                                    state.buffer
-                                ))),
-                                (stream, state),
-                            ));
-                        }
-                        
-                        // Return any remaining queued events
-                        if let Some(event) = state.event_queue.pop_front() {
-                            return Some((event, (stream, state)));
+                        // Stream has ended; an incomplete buffered event
+                        // surfaces as an error item
+                        queue.extend(parser.finish());
+                        if let Some(event) = queue.pop_front() {
+                            return Some((event, (stream, parser, queue)));
                         }
-                        
-                        // Stream is fully consumed
                         return None;
                     }
                 }
@@ -742,10 +640,10 @@ fn parse_sse_chunk(chunk: &[u8]) -> Vec<Result<StreamEvent>> {
     events
 }
 
-/// SSE event
+/// SSE event (also decoded from Bedrock event-stream frame payloads)
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
-enum SseEvent {
+pub(crate) enum SseEvent {
     #[serde(rename = "message_start")]
     MessageStart { message: StreamMessage },
     #[serde(rename = "content_block_start")]
@@ -776,7 +674,7 @@ enum SseEvent {
 use crate::ai::ErrorDetail;
 
 /// Parse SSE event
-fn parse_sse_event(event: SseEvent) -> Result<StreamEvent> {
+pub(crate) fn parse_sse_event(event: SseEvent) -> Result<StreamEvent> {
     Ok(match event {
         SseEvent::MessageStart { message } => StreamEvent::MessageStart { message },
         SseEvent::ContentBlockStart {
@@ -63,17 +63,67 @@ pub fn create_anthropic_from_ai_config(config: AIConfig) -> Result<Arc<Anthropic
 /// Wrapper that makes AnthropicClient compatible with AIClient interface
 pub struct AIClientAdapter {
     inner: Arc<AnthropicClient>,
+    /// Set when config.provider targets an OpenAI-compatible endpoint;
+    /// requests are translated there instead of going through AnthropicClient
+    openai: Option<Arc<crate::ai::openai_compat::OpenAICompatClient>>,
+    /// Set when config.provider targets AWS Bedrock (SigV4-signed requests)
+    bedrock: Option<Arc<crate::ai::bedrock::BedrockBackend>>,
+    /// Set when config.provider targets a local Ollama server
+    ollama: Option<Arc<crate::ai::ollama::OllamaClient>>,
     config: AIConfig,  // Keep original config for compatibility
 }
 
 impl AIClientAdapter {
     pub fn new(config: AIConfig) -> Result<Self> {
+        let openai = if config.provider == crate::ai::Provider::OpenAICompatible {
+            Some(Arc::new(crate::ai::openai_compat::OpenAICompatClient::new(
+                config.clone(),
+            )?))
+        } else {
+            None
+        };
+        let bedrock = if config.provider == crate::ai::Provider::Bedrock {
+            Some(Arc::new(crate::ai::bedrock::BedrockBackend::new(
+                config.clone(),
+            )?))
+        } else {
+            None
+        };
+        let ollama = if config.provider == crate::ai::Provider::Ollama {
+            Some(Arc::new(crate::ai::ollama::OllamaClient::new(
+                config.clone(),
+            )?))
+        } else {
+            None
+        };
         let inner = create_anthropic_from_ai_config(config.clone())?;
-        Ok(Self { inner, config })
+        Ok(Self { inner, openai, bedrock, ollama, config })
+    }
+
+    /// Attach the analytics metadata.user_id (account uuid + session
+    /// hash, see AnthropicClient::build_metadata_user_id) to a request
+    /// that doesn't already carry one. Backends that don't support
+    /// metadata strip it during translation
+    fn attach_metadata(request: &mut ChatRequest) {
+        let mut metadata = request.metadata.take().unwrap_or_default();
+        metadata
+            .entry("user_id".to_string())
+            .or_insert_with(AnthropicClient::build_metadata_user_id);
+        request.metadata = Some(metadata);
     }
 
     /// Send a chat completion request
-    pub async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+    pub async fn chat(&self, mut request: ChatRequest) -> Result<ChatResponse> {
+        Self::attach_metadata(&mut request);
+        if let Some(ref openai) = self.openai {
+            return openai.chat(&request).await;
+        }
+        if let Some(ref bedrock) = self.bedrock {
+            return bedrock.chat(&request).await;
+        }
+        if let Some(ref ollama) = self.ollama {
+            return ollama.chat(&request).await;
+        }
         // Convert from anyhow::Result to crate::error::Result
         self.inner.chat(&request).await
             .map_err(|e| crate::error::Error::Other(e.to_string()))
@@ -82,18 +132,30 @@ impl AIClientAdapter {
     /// Send a streaming chat completion request
     pub async fn chat_stream(
         &self,
-        request: ChatRequest,
+        mut request: ChatRequest,
     ) -> Result<impl Stream<Item = Result<StreamEvent>> + Send> {
+        Self::attach_metadata(&mut request);
         // Convert the stream result from anyhow::Result to crate::error::Result
         use futures::StreamExt;
-
-        let stream = self.inner.chat_stream(&request).await
-            .map_err(|e| crate::error::Error::Other(e.to_string()))?;
-
-        // Wrap the stream to convert each item from anyhow::Result to crate::error::Result
-        Ok(stream.map(|item| {
-            item.map_err(|e| crate::error::Error::Other(e.to_string()))
-        }))
+        use std::pin::Pin;
+
+        // All providers yield the same StreamEvent sequence; box so the
+        // return type is uniform across the branch
+        let stream: Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>> =
+            if let Some(ref openai) = self.openai {
+                Box::pin(openai.chat_stream(&request).await?)
+            } else if let Some(ref bedrock) = self.bedrock {
+                Box::pin(bedrock.chat_stream(&request).await?)
+            } else if let Some(ref ollama) = self.ollama {
+                Box::pin(ollama.chat_stream(&request).await?)
+            } else {
+                let inner_stream = self.inner.chat_stream(&request).await
+                    .map_err(|e| crate::error::Error::Other(e.to_string()))?;
+                Box::pin(inner_stream.map(|item| {
+                    item.map_err(|e| crate::error::Error::Other(e.to_string()))
+                }))
+            };
+        Ok(stream)
     }
 
     /// Get the underlying config (for compatibility with existing code)
@@ -115,6 +177,34 @@ impl AIClientAdapter {
         self.inner.count_tokens(&request).await
             .map_err(|e| crate::error::Error::Other(e.to_string()))
     }
+
+    /// Submit a message batch for asynchronous processing
+    /// Uses Anthropic's /v1/messages/batches endpoint
+    pub async fn create_message_batch(
+        &self,
+        requests: &[crate::auth::client::BatchRequestItem],
+    ) -> Result<crate::auth::client::MessageBatch> {
+        self.inner.create_message_batch(requests).await
+            .map_err(|e| crate::error::Error::Other(e.to_string()))
+    }
+
+    /// Poll a message batch's processing status
+    pub async fn get_message_batch(
+        &self,
+        batch_id: &str,
+    ) -> Result<crate::auth::client::MessageBatch> {
+        self.inner.get_message_batch(batch_id).await
+            .map_err(|e| crate::error::Error::Other(e.to_string()))
+    }
+
+    /// Fetch the JSONL results of an ended batch
+    pub async fn get_message_batch_results(
+        &self,
+        batch: &crate::auth::client::MessageBatch,
+    ) -> Result<String> {
+        self.inner.get_message_batch_results(batch).await
+            .map_err(|e| crate::error::Error::Other(e.to_string()))
+    }
 }
 
 // Re-export the types that are used in the AI module
@@ -13,7 +13,9 @@ impl DiffDisplay {
         Self {
             old_content,
             new_content,
-            file_path,
+            // Diff headers and summaries show workspace-relative paths
+            // (normalizePaths setting), matching tool output
+            file_path: crate::path_display::display_path(&file_path),
         }
     }
     
@@ -189,4 +191,151 @@ impl DiffDisplay {
         
         output
     }
-}
\ No newline at end of file
+}
+/// Build a compact diff-stat block for the files an agent turn touched.
+///
+/// Shells out to `git diff --numstat` so the numbers reflect what actually
+/// changed on disk, and counts untracked files the turn created since
+/// `git diff` doesn't report those. Returns `None` when no paths were
+/// touched, the paths aren't inside a git repository, or nothing changed.
+pub fn turn_diff_stat(files: &std::collections::HashSet<String>) -> Option<String> {
+    if files.is_empty() {
+        return None;
+    }
+    let mut paths: Vec<&str> = files.iter().map(|f| f.as_str()).collect();
+    paths.sort_unstable();
+
+    let output = std::process::Command::new("git")
+        .arg("diff")
+        .arg("--numstat")
+        .arg("HEAD")
+        .arg("--")
+        .args(&paths)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let mut entries = parse_numstat(&String::from_utf8_lossy(&output.stdout));
+
+    // New files don't show up in `git diff HEAD`; list the untracked ones
+    // and count their lines as pure additions
+    let untracked = std::process::Command::new("git")
+        .arg("ls-files")
+        .arg("--others")
+        .arg("--exclude-standard")
+        .arg("--")
+        .args(&paths)
+        .output()
+        .ok()?;
+    if untracked.status.success() {
+        for path in String::from_utf8_lossy(&untracked.stdout).lines() {
+            let path = path.trim();
+            if path.is_empty() || entries.iter().any(|(p, _, _)| p == path) {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(path) {
+                entries.push((path.to_string(), content.lines().count() as u64, 0));
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        None
+    } else {
+        Some(format_diff_stat(&entries))
+    }
+}
+
+/// Parse `git diff --numstat` output into (path, added, removed) entries.
+/// Binary files report "-" for both counts; they're kept with zero counts
+/// so the file still shows in the list.
+fn parse_numstat(output: &str) -> Vec<(String, u64, u64)> {
+    let mut entries = Vec::new();
+    for line in output.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let added = parts.next().unwrap_or("");
+        let removed = parts.next().unwrap_or("");
+        let path = match parts.next() {
+            Some(p) if !p.is_empty() => p,
+            _ => continue,
+        };
+        entries.push((
+            path.to_string(),
+            added.parse::<u64>().unwrap_or(0),
+            removed.parse::<u64>().unwrap_or(0),
+        ));
+    }
+    entries
+}
+
+/// Render the diff-stat block shown in chat after a turn that modified files
+fn format_diff_stat(entries: &[(String, u64, u64)]) -> String {
+    let added: u64 = entries.iter().map(|(_, a, _)| a).sum();
+    let removed: u64 = entries.iter().map(|(_, _, r)| r).sum();
+    let tests = entries.iter().filter(|(p, _, _)| is_test_path(p)).count();
+
+    let mut output = format!(
+        "Diff stat: {} file{} changed, +{} -{}",
+        entries.len(),
+        if entries.len() == 1 { "" } else { "s" },
+        added,
+        removed
+    );
+    if tests > 0 {
+        let _ = write!(
+            &mut output,
+            ", {} test file{} touched",
+            tests,
+            if tests == 1 { "" } else { "s" }
+        );
+    }
+    for (path, added, removed) in entries {
+        let _ = write!(&mut output, "\n  {} +{} -{}", path, added, removed);
+    }
+    output
+}
+
+/// Whether a path looks like a test file (tests/ directory or a
+/// test-named file), for calling out test coverage in the diff stat
+fn is_test_path(path: &str) -> bool {
+    if path.starts_with("tests/") || path.contains("/tests/") {
+        return true;
+    }
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    file_name.starts_with("test_") || file_name.ends_with("_test.rs") || file_name.ends_with("_tests.rs")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numstat_handles_binary_entries() {
+        let entries = parse_numstat("10\t2\tsrc/lib.rs\n-\t-\tassets/logo.png\n");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], ("src/lib.rs".to_string(), 10, 2));
+        assert_eq!(entries[1], ("assets/logo.png".to_string(), 0, 0));
+    }
+
+    #[test]
+    fn test_format_diff_stat_counts_tests() {
+        let entries = vec![
+            ("src/ai/tools.rs".to_string(), 42, 7),
+            ("tests/test_bash_tool_advanced.rs".to_string(), 18, 0),
+        ];
+        let block = format_diff_stat(&entries);
+        assert!(block.starts_with("Diff stat: 2 files changed, +60 -7, 1 test file touched"));
+        assert!(block.contains("\n  src/ai/tools.rs +42 -7"));
+        assert!(block.contains("\n  tests/test_bash_tool_advanced.rs +18 -0"));
+    }
+
+    #[test]
+    fn test_is_test_path() {
+        assert!(is_test_path("tests/test_file_edit_tool.rs"));
+        assert!(is_test_path("crates/core/tests/integration.rs"));
+        assert!(is_test_path("src/parser_tests.rs"));
+        assert!(!is_test_path("src/ai/tools.rs"));
+        assert!(!is_test_path("src/contest.rs"));
+    }
+}
@@ -4,8 +4,10 @@ use crate::error::Result;
 use tokio_util::sync::CancellationToken;
 
 /// ExitPlanMode tool - matches JavaScript implementation
-/// This tool is used when in plan mode and the plan has been written to a file.
-/// It does NOT take the plan content as a parameter - it reads from the plan file.
+/// This tool presents the plan for user approval. In the TUI the plan is
+/// rendered in an editable dialog; any edits the user makes are written
+/// back into the input before execution, so the tool_result echoes the
+/// approved (possibly corrected) plan without an extra round-trip.
 pub struct ExitPlanModeTool;
 
 #[async_trait]
@@ -16,11 +18,15 @@ impl crate::ai::tools::ToolHandler for ExitPlanModeTool {
 
     fn input_schema(&self) -> Value {
         // JavaScript schema: strictObject with launchSwarm, teammateCount, passthrough
-        // The plan is NOT passed as input - it's already written to a file
         json!({
             "$schema": "http://json-schema.org/draft-07/schema#",
             "type": "object",
-            "properties": {},
+            "properties": {
+                "plan": {
+                    "type": "string",
+                    "description": "The plan to present for approval (markdown supported)"
+                }
+            },
             "additionalProperties": true
         })
     }
@@ -33,11 +39,11 @@ impl crate::ai::tools::ToolHandler for ExitPlanModeTool {
         "Exit plan mode".to_string()
     }
 
-    async fn execute(&self, _input: Value, _cancellation_token: Option<CancellationToken>) -> Result<String> {
-        // The plan is read from the plan file, not from input
-        // This just signals readiness to exit plan mode
+    async fn execute(&self, input: Value, _cancellation_token: Option<CancellationToken>) -> Result<String> {
+        // Echo the approved plan (edited by the user in the approval
+        // dialog, when running in the TUI) back to the model
         let response = json!({
-            "plan": null,
+            "plan": input.get("plan").cloned().unwrap_or(Value::Null),
             "isAgent": false
         });
 
@@ -1,10 +1,16 @@
+pub mod batch;
 pub mod client;
 pub mod client_adapter;
+pub mod openai_compat;
+pub mod bedrock;
+pub mod ollama;
+pub mod model_discovery;
 pub mod models;
 pub mod conversation;
 pub mod streaming;
 pub mod system_prompt;
 pub mod tools;
+pub mod agent_registry;
 pub mod agent_tool;
 pub mod todo_tool;
 pub mod task_tools;
@@ -14,6 +20,8 @@ pub mod exit_plan_mode_tool;
 pub mod enter_plan_mode_tool;
 pub mod ask_user_question_tool;
 pub mod skill_tool;
+pub mod artifacts;
+pub mod browser_tool;
 pub mod summarization;
 pub mod git_prompts;
 pub mod github_prompts;
@@ -24,9 +32,30 @@ use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Which wire protocol the configured endpoint speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Provider {
+    /// Anthropic Messages API (api.anthropic.com or compatible proxy)
+    #[default]
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    /// OpenAI Chat Completions API (OpenAI, OpenRouter, Together, vLLM, ...)
+    #[serde(rename = "openai-compatible", alias = "openai")]
+    OpenAICompatible,
+    /// AWS Bedrock InvokeModel API (SigV4-signed, AWS credential chain)
+    #[serde(rename = "bedrock")]
+    Bedrock,
+    /// Local Ollama server speaking the /api/chat protocol
+    #[serde(rename = "ollama")]
+    Ollama,
+}
+
 /// AI provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIConfig {
+    /// Wire protocol of the target endpoint
+    #[serde(default)]
+    pub provider: Provider,
     /// API key for authentication
     pub api_key: String,
     /// Base URL for API endpoint
@@ -49,11 +78,15 @@ pub struct AIConfig {
     pub dangerously_allow_browser: Option<bool>,
     /// Retry configuration
     pub retry_config: RetryConfig,
+    /// Default service tier for requests ("auto" or "standard_only");
+    /// None lets the API pick
+    pub service_tier: Option<String>,
 }
 
 impl Default for AIConfig {
     fn default() -> Self {
         Self {
+            provider: Provider::default(),
             api_key: String::new(),
             base_url: "https://api.anthropic.com/v1".to_string(),
             auth_token: None,
@@ -65,6 +98,7 @@ impl Default for AIConfig {
             log_level: None,
             dangerously_allow_browser: None,
             retry_config: RetryConfig::default(),
+            service_tier: None,
         }
     }
 }
@@ -145,6 +179,17 @@ pub enum ContentPart {
         #[serde(skip_serializing_if = "Option::is_none")]
         is_error: Option<bool>,
     },
+    /// Extended thinking block. Replayed with its signature in follow-up
+    /// requests so the API can verify the reasoning was not tampered with.
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        signature: Option<String>,
+    },
+    /// Thinking redacted by safety systems; replayed verbatim, never shown
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
     #[serde(rename = "server_tool_use")]
     ServerToolUse {
         id: String,
@@ -275,11 +320,168 @@ pub struct ChatRequest {
     /// JavaScript SDK (cli-jsdef-fixed.js:272970-272972) passes this in request body
     #[serde(skip_serializing_if = "Option::is_none")]
     pub betas: Option<Vec<String>>,
+    /// Extended thinking configuration (thinking in the Messages API)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<ThinkingConfig>,
+    /// Service tier for this request: "auto" prefers priority capacity
+    /// when the account has it, "standard_only" opts out of it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<String>,
+}
+
+/// One-line structural summary of a message, used by the request diff:
+/// role plus its content parts (tool uses by name, tool results by id)
+fn summarize_message(message: &Message) -> String {
+    let parts = match &message.content {
+        MessageContent::Text(text) => format!("text {} chars", text.chars().count()),
+        MessageContent::Multipart(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text { text, .. } => format!("text {} chars", text.chars().count()),
+                ContentPart::Image { .. } => "image".to_string(),
+                ContentPart::ToolUse { name, id, .. } => format!("tool_use {} ({})", name, id),
+                ContentPart::ToolResult { tool_use_id, .. } => {
+                    format!("tool_result ({})", tool_use_id)
+                }
+                ContentPart::Thinking { .. } => "thinking".to_string(),
+                ContentPart::RedactedThinking { .. } => "redacted_thinking".to_string(),
+                ContentPart::ServerToolUse { name, id, .. } => {
+                    format!("server_tool_use {} ({})", name, id)
+                }
+                ContentPart::WebSearchToolResult { tool_use_id, .. } => {
+                    format!("web_search_tool_result ({})", tool_use_id)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    format!("{:?} [{}]", message.role, parts)
+}
+
+/// Structural diff between two consecutive chat requests, one finding per
+/// line: model and system prompt changes, messages added or removed, tool
+/// list changes, and duplicated tool results in the new request. Logged at
+/// debug level between agent-loop turns so regressions in context assembly
+/// are visible without diffing raw request dumps.
+pub fn diff_chat_requests(previous: &ChatRequest, next: &ChatRequest) -> Vec<String> {
+    let mut findings = Vec::new();
+
+    if previous.model != next.model {
+        findings.push(format!("model: {} -> {}", previous.model, next.model));
+    }
+
+    match (&previous.system, &next.system) {
+        (None, Some(system)) => {
+            findings.push(format!("system prompt added ({} chars)", system.chars().count()));
+        }
+        (Some(_), None) => findings.push("system prompt removed".to_string()),
+        (Some(old), Some(new)) if old != new => {
+            findings.push(format!(
+                "system prompt changed ({} -> {} chars)",
+                old.chars().count(),
+                new.chars().count()
+            ));
+        }
+        _ => {}
+    }
+
+    // Messages: the new request normally extends the old one, so compare
+    // the common prefix and report everything past it
+    let prev_summaries: Vec<String> = previous.messages.iter().map(summarize_message).collect();
+    let next_summaries: Vec<String> = next.messages.iter().map(summarize_message).collect();
+    let common = prev_summaries
+        .iter()
+        .zip(next_summaries.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    for removed in &prev_summaries[common..] {
+        findings.push(format!("message removed: {}", removed));
+    }
+    for added in &next_summaries[common..] {
+        findings.push(format!("message added: {}", added));
+    }
+
+    let prev_tools: Vec<&str> = previous
+        .tools
+        .iter()
+        .flatten()
+        .map(|tool| tool.name())
+        .collect();
+    let next_tools: Vec<&str> = next.tools.iter().flatten().map(|tool| tool.name()).collect();
+    for tool in &next_tools {
+        if !prev_tools.contains(tool) {
+            findings.push(format!("tool added: {}", tool));
+        }
+    }
+    for tool in &prev_tools {
+        if !next_tools.contains(tool) {
+            findings.push(format!("tool removed: {}", tool));
+        }
+    }
+
+    // Duplicated tool results are the classic context-assembly bug: the
+    // same tool_use_id answered twice makes the API reject the request
+    let mut seen_result_ids: Vec<&str> = Vec::new();
+    for message in &next.messages {
+        if let MessageContent::Multipart(parts) = &message.content {
+            for part in parts {
+                if let ContentPart::ToolResult { tool_use_id, .. } = part {
+                    if seen_result_ids.contains(&tool_use_id.as_str()) {
+                        findings.push(format!("duplicated tool_result: {}", tool_use_id));
+                    } else {
+                        seen_result_ids.push(tool_use_id);
+                    }
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+/// Extended thinking request configuration.
+///
+/// Enabled thinking requires a token budget; the API streams `thinking`
+/// content blocks (with a verification signature) before the visible
+/// response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThinkingConfig {
+    #[serde(rename = "type")]
+    pub thinking_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_tokens: Option<u32>,
+}
+
+impl ThinkingConfig {
+    /// Enable thinking with the given token budget
+    pub fn enabled(budget_tokens: u32) -> Self {
+        Self {
+            thinking_type: "enabled".to_string(),
+            budget_tokens: Some(budget_tokens),
+        }
+    }
+}
+
+/// Default thinking budget for a model, used when `/think` is enabled
+/// without an explicit budget. Larger models get room for deeper
+/// reasoning; budgets stay well under the max_tokens ceiling.
+pub fn default_thinking_budget(model: &str) -> u32 {
+    if model.contains("opus") {
+        16000
+    } else if model.contains("haiku") {
+        4000
+    } else {
+        // sonnet and unknown models
+        10000
+    }
 }
 
 /// Tool choice configuration
+///
+/// Tagged to match the API wire format: `{"type": "auto"}`,
+/// `{"type": "any"}`, or `{"type": "tool", "name": "..."}`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
+#[serde(tag = "type", rename_all = "lowercase")]
 pub enum ToolChoice {
     Auto,
     Any,
@@ -333,6 +535,23 @@ pub struct ErrorDetail {
     pub message: String,
 }
 
+/// Session-wide service tier override set by /tier; None falls back to
+/// the configured default. Shared between the TUI and the agent worker
+/// (same pattern as the dry-run flag in tools.rs).
+static SESSION_SERVICE_TIER: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// Set (or clear) the session service tier override
+pub fn set_session_service_tier(tier: Option<String>) {
+    if let Ok(mut guard) = SESSION_SERVICE_TIER.lock() {
+        *guard = tier;
+    }
+}
+
+/// The session service tier override, if one is set
+pub fn session_service_tier() -> Option<String> {
+    SESSION_SERVICE_TIER.lock().ok().and_then(|guard| guard.clone())
+}
+
 /// Load AI configuration from environment and config
 pub fn load_config() -> Result<AIConfig> {
     let mut config = AIConfig::default();
@@ -349,10 +568,17 @@ pub fn load_config() -> Result<AIConfig> {
     if let Ok(model) = std::env::var("ANTHROPIC_MODEL") {
         config.default_model = model;
     }
-    
+
+    if let Ok(tier) = std::env::var("ANTHROPIC_SERVICE_TIER") {
+        if !tier.is_empty() {
+            config.service_tier = Some(tier);
+        }
+    }
+
     // Load from config file
     if let Ok(user_config) = crate::config::load_config(crate::config::ConfigScope::User) {
         if let Some(ai_config) = user_config.ai_config {
+            config.provider = ai_config.provider;
             if !ai_config.api_key.is_empty() {
                 config.api_key = ai_config.api_key;
             }
@@ -366,19 +592,115 @@ pub fn load_config() -> Result<AIConfig> {
             config.temperature = ai_config.temperature;
             config.timeout_secs = ai_config.timeout_secs;
             config.retry_config = ai_config.retry_config;
+            if let Some(tier) = ai_config.service_tier {
+                config.service_tier = Some(tier);
+            }
         }
     }
-    
-    // Validate configuration
-    if config.api_key.is_empty() {
+
+    // Active auth profile (--profile / ANTHROPIC_PROFILE / /profile)
+    // overrides both the environment and the config file
+    if let Some((_, profile)) = crate::config::get_active_profile() {
+        if let Some(api_key) = profile.api_key {
+            config.api_key = api_key;
+        }
+        if let Some(base_url) = profile.base_url {
+            config.base_url = base_url;
+        }
+        if let Some(model) = profile.model {
+            config.default_model = model;
+        }
+    }
+
+    apply_provider_env(&mut config);
+    apply_auth_token_helper(&mut config);
+
+    // Validate configuration. OpenAI-compatible endpoints (e.g. a local
+    // vLLM server) commonly run without authentication, and Azure
+    // gateways authenticate with AAD tokens acquired per request, so
+    // only direct Anthropic requires credentials here.
+    if config.api_key.is_empty()
+        && config.auth_token.is_none()
+        && config.provider == Provider::Anthropic
+        && !crate::auth::azure::is_azure_gateway(&config.base_url)
+    {
         return Err(Error::Config(
             "API key not found. Set ANTHROPIC_API_KEY environment variable or configure in settings.".to_string()
         ));
     }
-    
+
     Ok(config)
 }
 
+/// Resolve a bearer token from the configured authTokenHelper command when
+/// no other credentials are available, so corporate gateways with bespoke
+/// auth schemes work without code changes.
+fn apply_auth_token_helper(config: &mut AIConfig) {
+    if config.api_key.is_empty() && config.auth_token.is_none() {
+        if let Some(token) = crate::config::run_auth_token_helper() {
+            config.auth_token = Some(token);
+        }
+    }
+}
+
+/// Apply provider selection from the environment.
+///
+/// `LLMINATE_PROVIDER=openai-compatible` (or `openai`) switches the client
+/// to the OpenAI Chat Completions protocol; setting `OPENAI_BASE_URL`
+/// without an explicit provider does the same. For that provider,
+/// `OPENAI_BASE_URL`, `OPENAI_API_KEY` and `OPENAI_MODEL` override the
+/// Anthropic-oriented defaults. `LLMINATE_PROVIDER=bedrock` or
+/// `CLAUDE_CODE_USE_BEDROCK=1` selects the AWS Bedrock backend, which
+/// authenticates through the AWS credential chain instead of an API key.
+/// `LLMINATE_PROVIDER=ollama` targets a local Ollama server; its host and
+/// model come from `OLLAMA_HOST` (default `http://localhost:11434`) and
+/// `OLLAMA_MODEL`.
+fn apply_provider_env(config: &mut AIConfig) {
+    match std::env::var("LLMINATE_PROVIDER").ok().as_deref() {
+        Some("openai") | Some("openai-compatible") => {
+            config.provider = Provider::OpenAICompatible;
+        }
+        Some("bedrock") => config.provider = Provider::Bedrock,
+        Some("ollama") => config.provider = Provider::Ollama,
+        Some("anthropic") => config.provider = Provider::Anthropic,
+        _ => {
+            // CLAUDE_CODE_USE_BEDROCK matches the JavaScript CLI's switch
+            if std::env::var("CLAUDE_CODE_USE_BEDROCK")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false)
+            {
+                config.provider = Provider::Bedrock;
+            } else if std::env::var("OPENAI_BASE_URL").is_ok() {
+                config.provider = Provider::OpenAICompatible;
+            }
+        }
+    }
+
+    if config.provider == Provider::OpenAICompatible {
+        if let Ok(base_url) = std::env::var("OPENAI_BASE_URL") {
+            config.base_url = base_url;
+        }
+        if let Ok(api_key) = std::env::var("OPENAI_API_KEY") {
+            config.api_key = api_key;
+        }
+        if let Ok(model) = std::env::var("OPENAI_MODEL") {
+            config.default_model = model;
+        }
+    }
+
+    if config.provider == Provider::Ollama {
+        if let Ok(host) = std::env::var("OLLAMA_HOST") {
+            config.base_url = host;
+        } else if config.base_url.starts_with("https://api.anthropic.com") {
+            // Nothing pointed elsewhere; assume the stock local server
+            config.base_url = "http://localhost:11434".to_string();
+        }
+        if let Ok(model) = std::env::var("OLLAMA_MODEL") {
+            config.default_model = model;
+        }
+    }
+}
+
 /// Create a client with default configuration
 /// Uses AIClientAdapter which wraps AnthropicClient (has OAuth metadata helpers)
 pub async fn create_client() -> Result<client_adapter::AIClientAdapter> {
@@ -430,8 +752,14 @@ pub fn load_config_with_auth(auth_method: crate::auth::AuthMethod) -> Result<AIC
         config.default_model = model;
     }
 
-    // Validate that we have API key
-    if config.api_key.is_empty() {
+    apply_provider_env(&mut config);
+    apply_auth_token_helper(&mut config);
+
+    // Validate that we have credentials (OpenAI-compatible servers may be unauthenticated)
+    if config.api_key.is_empty()
+        && config.auth_token.is_none()
+        && config.provider == Provider::Anthropic
+    {
         return Err(Error::Auth("No API key available. Please set ANTHROPIC_API_KEY environment variable.".to_string()));
     }
 
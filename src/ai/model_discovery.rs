@@ -0,0 +1,254 @@
+//! Provider-specific model availability discovery.
+//!
+//! Backs the `/models` command with the models an account can actually
+//! invoke, instead of the static Anthropic catalog: Bedrock via
+//! `ListFoundationModels` (see [`crate::ai::bedrock`]) and Vertex AI via
+//! the publisher models listing. Each discovered model carries a short
+//! alias (`opus4.5`, `sonnet`, ...) usable with `/model`, derived from
+//! the provider-specific model id so the aliases stay consistent across
+//! providers.
+
+use crate::ai::{AIConfig, Provider};
+use crate::error::{Error, Result};
+use serde_json::Value;
+
+/// A model the configured account can invoke, as reported by the
+/// provider's discovery API
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredModel {
+    /// Short name accepted by `/model` (e.g. `opus4.5`), when derivable
+    pub alias: Option<String>,
+    /// Provider-specific model id to send on the wire
+    pub model_id: String,
+    /// Human-readable name from the provider catalog
+    pub display_name: String,
+}
+
+/// Whether the current configuration has a discovery source: Bedrock, or
+/// Vertex when `CLAUDE_CODE_USE_VERTEX` is set
+pub fn discovery_available(config: &AIConfig) -> bool {
+    config.provider == Provider::Bedrock || vertex_enabled()
+}
+
+fn vertex_enabled() -> bool {
+    std::env::var("CLAUDE_CODE_USE_VERTEX")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// List the models the configured account can invoke
+pub async fn discover_models(config: &AIConfig) -> Result<Vec<DiscoveredModel>> {
+    if config.provider == Provider::Bedrock {
+        let backend = crate::ai::bedrock::BedrockBackend::new(config.clone())?;
+        let models = backend.list_foundation_models().await?;
+        return Ok(models
+            .into_iter()
+            .map(|(model_id, display_name)| DiscoveredModel {
+                alias: short_alias(&model_id),
+                model_id,
+                display_name,
+            })
+            .collect());
+    }
+    if vertex_enabled() {
+        return discover_vertex_models().await;
+    }
+    Err(Error::Other(
+        "Model discovery is only available for Bedrock and Vertex AI".to_string(),
+    ))
+}
+
+/// List Anthropic publisher models from Vertex AI.
+///
+/// Uses `CLOUD_ML_REGION` (default `us-east5`) and a bearer token from
+/// `VERTEX_ACCESS_TOKEN` or `gcloud auth print-access-token`, matching
+/// how the Vertex Messages endpoint is authenticated.
+async fn discover_vertex_models() -> Result<Vec<DiscoveredModel>> {
+    let region = std::env::var("CLOUD_ML_REGION").unwrap_or_else(|_| "us-east5".to_string());
+    let token = vertex_access_token()?;
+
+    let url = format!(
+        "https://{}-aiplatform.googleapis.com/v1/publishers/anthropic/models",
+        region
+    );
+    let client = crate::utils::http::shared_client()
+        .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
+    let response = client
+        .get(&url)
+        .bearer_auth(&token)
+        .send()
+        .await
+        .map_err(|e| Error::Network(format!("Failed to list Vertex models: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to read error body".to_string());
+        return Err(Error::Other(format!(
+            "Vertex model listing failed with status {}: {}",
+            status, text
+        )));
+    }
+
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| Error::Other(format!("Failed to parse Vertex model list: {}", e)))?;
+    Ok(parse_vertex_models(&body))
+}
+
+/// Bearer token for Vertex: `VERTEX_ACCESS_TOKEN` if set, otherwise the
+/// gcloud application-default credentials
+fn vertex_access_token() -> Result<String> {
+    if let Ok(token) = std::env::var("VERTEX_ACCESS_TOKEN") {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_string());
+        }
+    }
+    let output = std::process::Command::new("gcloud")
+        .args(["auth", "print-access-token"])
+        .output()
+        .map_err(|e| {
+            Error::Auth(format!(
+                "Vertex requires VERTEX_ACCESS_TOKEN or the gcloud CLI: {}",
+                e
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(Error::Auth(
+            "gcloud auth print-access-token failed; run `gcloud auth login` first".to_string(),
+        ));
+    }
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if token.is_empty() {
+        return Err(Error::Auth(
+            "gcloud returned an empty access token".to_string(),
+        ));
+    }
+    Ok(token)
+}
+
+/// Extract models from a Vertex publisher models response. Entries are
+/// named `publishers/anthropic/models/<model-id>`.
+fn parse_vertex_models(body: &Value) -> Vec<DiscoveredModel> {
+    let mut models = Vec::new();
+    let Some(entries) = body.get("publisherModels").and_then(|m| m.as_array()) else {
+        return models;
+    };
+    for entry in entries {
+        let Some(name) = entry.get("name").and_then(|n| n.as_str()) else {
+            continue;
+        };
+        let Some(model_id) = name.rsplit('/').next().filter(|id| !id.is_empty()) else {
+            continue;
+        };
+        // Launch-stage gating: skip anything the account can't call yet
+        if let Some(stage) = entry.get("launchStage").and_then(|s| s.as_str()) {
+            if stage == "PRIVATE_PREVIEW" {
+                continue;
+            }
+        }
+        models.push(DiscoveredModel {
+            alias: short_alias(model_id),
+            model_id: model_id.to_string(),
+            display_name: entry
+                .get("versionId")
+                .and_then(|v| v.as_str())
+                .map(|version| format!("{} ({})", model_id, version))
+                .unwrap_or_else(|| model_id.to_string()),
+        });
+    }
+    models
+}
+
+/// Derive the `/model` short alias from a provider model id, e.g.
+/// `anthropic.claude-opus-4-5-20251101-v1:0` -> `opus4.5`. Returns None
+/// for ids that don't follow the Claude family naming.
+pub fn short_alias(model_id: &str) -> Option<String> {
+    // Strip Bedrock prefixes (`anthropic.` or `us.anthropic.`) so the
+    // same derivation covers Bedrock and Vertex ids
+    let id = model_id
+        .rsplit_once("anthropic.")
+        .map(|(_, rest)| rest)
+        .unwrap_or(model_id);
+
+    let family = ["opus", "sonnet", "haiku"]
+        .iter()
+        .find(|family| id.contains(*family))?;
+
+    // Claude 4+ ids read `claude-<family>-<major>-<minor>-<date>`;
+    // 3.x ids read `claude-3-5-<family>-<date>`
+    let after = id.split(family).nth(1).unwrap_or("");
+    let before = id.split(family).next().unwrap_or("");
+    let version_digits = |s: &str| -> Vec<String> {
+        s.split(['-', '.', '@'])
+            .filter(|segment| {
+                !segment.is_empty()
+                    && segment.len() <= 2
+                    && segment.chars().all(|c| c.is_ascii_digit())
+            })
+            .take(2)
+            .map(|segment| segment.to_string())
+            .collect()
+    };
+    let mut digits = version_digits(after);
+    if digits.is_empty() {
+        digits = version_digits(before);
+    }
+    match digits.len() {
+        0 => Some((*family).to_string()),
+        1 => Some(format!("{}{}", family, digits[0])),
+        _ => Some(format!("{}{}.{}", family, digits[0], digits[1])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_short_alias_bedrock_and_vertex_ids() {
+        assert_eq!(
+            short_alias("anthropic.claude-opus-4-5-20251101-v1:0").as_deref(),
+            Some("opus4.5")
+        );
+        assert_eq!(
+            short_alias("us.anthropic.claude-sonnet-4-20250514-v1:0").as_deref(),
+            Some("sonnet4")
+        );
+        assert_eq!(
+            short_alias("anthropic.claude-3-5-sonnet-20240620-v1:0").as_deref(),
+            Some("sonnet3.5")
+        );
+        assert_eq!(
+            short_alias("claude-haiku-4-5@20251001").as_deref(),
+            Some("haiku4.5")
+        );
+        assert_eq!(short_alias("amazon.titan-text-express-v1"), None);
+    }
+
+    #[test]
+    fn test_parse_vertex_models() {
+        let body = json!({
+            "publisherModels": [
+                {
+                    "name": "publishers/anthropic/models/claude-opus-4-5",
+                    "versionId": "claude-opus-4-5@20251101",
+                    "launchStage": "GA"
+                },
+                {
+                    "name": "publishers/anthropic/models/claude-next",
+                    "launchStage": "PRIVATE_PREVIEW"
+                }
+            ]
+        });
+        let models = parse_vertex_models(&body);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].model_id, "claude-opus-4-5");
+        assert_eq!(models[0].alias.as_deref(), Some("opus4.5"));
+        assert!(parse_vertex_models(&json!({})).is_empty());
+    }
+}
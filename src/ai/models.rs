@@ -397,4 +397,71 @@ impl ModelUsageTracker {
     pub fn clear(&mut self) {
         self.usage.clear();
     }
-}
\ No newline at end of file
+}
+/// Per-turn cost limit in whole cents; 0 means no limit is set via the
+/// slash command and the environment fallback applies. Atomic so the
+/// agent worker and the /cost-limit command share it without locking
+/// (same pattern as the dry-run flag in tools.rs).
+static TURN_COST_LIMIT_CENTS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Set (or clear) the per-turn cost limit in dollars
+pub fn set_turn_cost_limit(dollars: Option<f64>) {
+    let cents = match dollars {
+        Some(d) if d > 0.0 => (d * 100.0).round() as u64,
+        _ => 0,
+    };
+    TURN_COST_LIMIT_CENTS.store(cents, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// The per-turn cost limit in dollars, if one is configured.
+/// The /cost-limit command takes precedence; otherwise the
+/// LLMINATE_TURN_COST_LIMIT environment variable (dollars) applies.
+pub fn turn_cost_limit() -> Option<f64> {
+    let cents = TURN_COST_LIMIT_CENTS.load(std::sync::atomic::Ordering::SeqCst);
+    if cents > 0 {
+        return Some(cents as f64 / 100.0);
+    }
+    std::env::var("LLMINATE_TURN_COST_LIMIT")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|d| *d > 0.0)
+}
+
+/// Estimate the dollar cost of a request from its reported token usage.
+/// Unknown models fall back to sonnet-tier pricing so the guardrail
+/// still engages rather than silently passing expensive turns through.
+pub fn estimate_usage_cost(model: &str, input_tokens: u64, output_tokens: u64) -> f64 {
+    let registry = ModelRegistry::new();
+    let (input_per_1k, output_per_1k) = match registry.get_model(model) {
+        Some(info) => (info.input_cost_per_1k, info.output_cost_per_1k),
+        None => (0.003, 0.015),
+    };
+    (input_tokens as f64 / 1000.0) * input_per_1k + (output_tokens as f64 / 1000.0) * output_per_1k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_usage_cost() {
+        // Opus pricing: $15/M input, $75/M output
+        let cost = estimate_usage_cost("claude-opus-4-1-20250805", 1_000_000, 100_000);
+        assert!((cost - 22.5).abs() < 1e-9);
+
+        // Unknown models use sonnet-tier pricing
+        let cost = estimate_usage_cost("some-new-model", 1000, 1000);
+        assert!((cost - 0.018).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_turn_cost_limit_set_and_clear() {
+        set_turn_cost_limit(Some(2.5));
+        assert_eq!(turn_cost_limit(), Some(2.5));
+        set_turn_cost_limit(None);
+        // With no explicit limit and no env var, the guardrail is off
+        if std::env::var("LLMINATE_TURN_COST_LIMIT").is_err() {
+            assert_eq!(turn_cost_limit(), None);
+        }
+    }
+}
@@ -378,7 +378,18 @@ impl ToolHandler for NotebookEditTool {
         if edit_mode == "insert" && cell_type.is_none() {
             return Err(Error::InvalidInput("Cell type is required when using edit_mode=insert.".to_string()));
         }
-        
+
+        // Dry-run mode: report the would-be cell edit without modifying the notebook
+        if crate::ai::tools::is_dry_run() {
+            return Ok(format!(
+                "[DRY RUN] Would {} cell {} in {} ({} bytes of new source). No changes were made.",
+                edit_mode,
+                cell_id.unwrap_or("<unspecified>"),
+                notebook_path,
+                new_source.len()
+            ));
+        }
+
         // Read the notebook file
         let content = fs::read_to_string(path)
             .map_err(|e| Error::Io(e))?;
@@ -522,11 +533,7 @@ impl ToolHandler for NotebookEditTool {
             .map_err(|e| Error::Io(e))?;
         
         // Return result matching JavaScript mapToolResultToToolResultBlockParam
-        let truncated_source = if new_source.len() > 50 { 
-            format!("{}...", &new_source[..50]) 
-        } else { 
-            new_source.to_string() 
-        };
+        let truncated_source = crate::utils::truncate_display(&new_source, 53);
         
         match actual_edit_mode.as_str() {
             "replace" => Ok(format!("Updated cell {} with {}", 
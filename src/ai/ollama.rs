@@ -0,0 +1,827 @@
+//! Ollama local-model provider support.
+//!
+//! Translates the Anthropic-shaped `ChatRequest`/`ChatResponse`/`StreamEvent`
+//! types into the Ollama `/api/chat` wire format and back, so quick offline
+//! sessions against local llama/qwen models run from the same TUI. Ollama
+//! streams newline-delimited JSON objects rather than SSE frames; each chunk
+//! carries a `message.content` fragment and the final chunk (`done: true`)
+//! carries token counts, which we re-emit as the standard Anthropic event
+//! sequence (message_start, content_block_start/delta/stop, message_delta,
+//! message_stop).
+//!
+//! Tool definitions map onto Ollama's function-tool format. Not every local
+//! model supports tools: when the server rejects the request because of them
+//! we retry once without tool definitions, so plain chat keeps working
+//! against tool-less models instead of failing outright.
+
+use crate::ai::client::{ContentBlock, ContentDelta, MessageDelta, StreamEvent, StreamMessage};
+use crate::ai::{
+    ChatRequest, ChatResponse, ContentPart, Message, MessageContent, MessageRole, StopReason,
+    Tool, Usage,
+};
+use crate::error::{Error, Result};
+use futures::stream::{Stream, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Client for a local (or remote) Ollama server's `/api/chat` endpoint
+pub struct OllamaClient {
+    config: crate::ai::AIConfig,
+    http_client: reqwest::Client,
+}
+
+impl OllamaClient {
+    pub fn new(config: crate::ai::AIConfig) -> Result<Self> {
+        let http_client = crate::utils::http::client_builder()?
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            config,
+            http_client,
+        })
+    }
+
+    /// The `/api/chat` URL for the configured host
+    fn endpoint(&self) -> String {
+        format!("{}/api/chat", self.config.base_url.trim_end_matches('/'))
+    }
+
+    fn build_request(&self, body: &Value) -> reqwest::RequestBuilder {
+        let mut builder = self
+            .http_client
+            .post(self.endpoint())
+            .header("content-type", "application/json");
+        // Local servers run unauthenticated; a key only matters for proxied
+        // Ollama deployments behind a gateway
+        if !self.config.api_key.is_empty() {
+            builder = builder.bearer_auth(&self.config.api_key);
+        } else if let Some(ref auth_token) = self.config.auth_token {
+            builder = builder.bearer_auth(auth_token);
+        }
+        for (name, value) in crate::config::get_custom_headers("ollama") {
+            builder = builder.header(name, value);
+        }
+        builder.json(body)
+    }
+
+    /// Send a request, retrying once without tool definitions when the
+    /// model does not support them (Ollama answers 400 in that case)
+    async fn send_with_tool_fallback(&self, body: &Value) -> Result<reqwest::Response> {
+        let response = self
+            .build_request(body)
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to send request: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to read error body".to_string());
+
+        // Graceful degradation: strip tools and retry so tool-less local
+        // models still answer plain chat
+        if status.as_u16() == 400
+            && body.get("tools").is_some()
+            && text.contains("does not support tools")
+        {
+            let mut degraded = body.clone();
+            if let Some(map) = degraded.as_object_mut() {
+                map.remove("tools");
+            }
+            let retry = self
+                .build_request(&degraded)
+                .send()
+                .await
+                .map_err(|e| Error::Other(format!("Failed to send request: {}", e)))?;
+            let retry_status = retry.status();
+            if retry_status.is_success() {
+                return Ok(retry);
+            }
+            let retry_text = retry
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(Error::Other(format!(
+                "Ollama request failed with status {}: {}",
+                retry_status, retry_text
+            )));
+        }
+
+        Err(Error::Other(format!(
+            "Ollama request failed with status {}: {}",
+            status, text
+        )))
+    }
+
+    /// Send a non-streaming chat request
+    pub async fn chat(&self, request: &ChatRequest) -> Result<ChatResponse> {
+        let body = translate_request(request, false);
+        let response = self.send_with_tool_fallback(&body).await?;
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to parse response JSON: {}", e)))?;
+        translate_response(&value)
+    }
+
+    /// Send a streaming chat request, re-emitted as Anthropic-style events
+    pub async fn chat_stream(
+        &self,
+        request: &ChatRequest,
+    ) -> Result<impl Stream<Item = Result<StreamEvent>> + Send> {
+        let body = translate_request(request, true);
+        let response = self.send_with_tool_fallback(&body).await?;
+        Ok(parse_ollama_ndjson_stream(response.bytes_stream()))
+    }
+}
+
+/// Translate a `ChatRequest` into an Ollama `/api/chat` request body
+pub fn translate_request(request: &ChatRequest, stream: bool) -> Value {
+    let mut messages: Vec<Value> = Vec::new();
+
+    // Ollama takes the system prompt as a leading system message
+    if let Some(ref system) = request.system {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+
+    for message in &request.messages {
+        translate_message(message, &mut messages);
+    }
+
+    let mut body = json!({
+        "model": request.model,
+        "messages": messages,
+        // Ollama streams by default; state it explicitly either way
+        "stream": stream,
+    });
+
+    // Sampling parameters live under "options" in Ollama
+    let mut options = serde_json::Map::new();
+    if let Some(max_tokens) = request.max_tokens {
+        options.insert("num_predict".to_string(), json!(max_tokens));
+    }
+    if let Some(temperature) = request.temperature {
+        options.insert("temperature".to_string(), json!(temperature));
+    }
+    if let Some(top_p) = request.top_p {
+        options.insert("top_p".to_string(), json!(top_p));
+    }
+    if let Some(top_k) = request.top_k {
+        options.insert("top_k".to_string(), json!(top_k));
+    }
+    if let Some(ref stop_sequences) = request.stop_sequences {
+        options.insert("stop".to_string(), json!(stop_sequences));
+    }
+    if !options.is_empty() {
+        body["options"] = Value::Object(options);
+    }
+
+    if let Some(ref tools) = request.tools {
+        let functions: Vec<Value> = tools
+            .iter()
+            .filter_map(|tool| match tool {
+                Tool::Standard {
+                    name,
+                    description,
+                    input_schema,
+                } => Some(json!({
+                    "type": "function",
+                    "function": {
+                        "name": name,
+                        "description": description,
+                        "parameters": input_schema,
+                    }
+                })),
+                // Server-side tools (web search) have no Ollama equivalent
+                Tool::WebSearch { .. } => None,
+            })
+            .collect();
+        if !functions.is_empty() {
+            body["tools"] = json!(functions);
+        }
+    }
+
+    body
+}
+
+/// Translate one Anthropic-shaped message into Ollama messages.
+///
+/// Images travel in a sibling `images` array of raw base64 payloads, tool
+/// uses become `tool_calls` on the assistant message (arguments as a JSON
+/// object, not a string), and tool results fan out into `role: "tool"`
+/// messages.
+fn translate_message(message: &Message, out: &mut Vec<Value>) {
+    let role = match message.role {
+        MessageRole::System => "system",
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::Tool => "tool",
+    };
+
+    match &message.content {
+        MessageContent::Text(text) => {
+            out.push(json!({ "role": role, "content": text }));
+        }
+        MessageContent::Multipart(parts) => {
+            let mut text_parts: Vec<&str> = Vec::new();
+            let mut images: Vec<Value> = Vec::new();
+            let mut tool_calls: Vec<Value> = Vec::new();
+            let mut tool_results: Vec<Value> = Vec::new();
+
+            for part in parts {
+                match part {
+                    ContentPart::Text { text, .. } => {
+                        text_parts.push(text);
+                    }
+                    ContentPart::Image { source } => {
+                        // Ollama takes the bare base64 payload, no data: URL
+                        images.push(json!(source.data));
+                    }
+                    ContentPart::ToolUse { name, input, .. } => {
+                        tool_calls.push(json!({
+                            "function": {
+                                "name": name,
+                                "arguments": input,
+                            }
+                        }));
+                    }
+                    ContentPart::ToolResult { content, .. } => {
+                        tool_results.push(json!({
+                            "role": "tool",
+                            "content": content,
+                        }));
+                    }
+                    // Server-side tool traffic and thinking blocks have no
+                    // Ollama representation
+                    ContentPart::ServerToolUse { .. }
+                    | ContentPart::WebSearchToolResult { .. }
+                    | ContentPart::Thinking { .. }
+                    | ContentPart::RedactedThinking { .. } => {}
+                }
+            }
+
+            if !text_parts.is_empty() || !images.is_empty() || !tool_calls.is_empty() {
+                let mut entry = json!({
+                    "role": role,
+                    "content": text_parts.join("\n"),
+                });
+                if !images.is_empty() {
+                    entry["images"] = Value::Array(images);
+                }
+                if !tool_calls.is_empty() {
+                    entry["tool_calls"] = Value::Array(tool_calls);
+                }
+                out.push(entry);
+            }
+
+            out.extend(tool_results);
+        }
+    }
+}
+
+/// Translate an Ollama `/api/chat` response into a `ChatResponse`
+pub fn translate_response(value: &Value) -> Result<ChatResponse> {
+    let message = value
+        .get("message")
+        .ok_or_else(|| Error::Other("Ollama response contained no message".to_string()))?;
+
+    let mut content: Vec<ContentPart> = Vec::new();
+    if let Some(text) = message.get("content").and_then(|c| c.as_str()) {
+        if !text.is_empty() {
+            content.push(ContentPart::Text {
+                text: text.to_string(),
+                citations: None,
+            });
+        }
+    }
+
+    let mut has_tool_use = false;
+    if let Some(tool_calls) = message.get("tool_calls").and_then(|t| t.as_array()) {
+        for (call_index, tool_call) in tool_calls.iter().enumerate() {
+            let function = tool_call.get("function");
+            has_tool_use = true;
+            content.push(ContentPart::ToolUse {
+                // Ollama does not assign call ids; synthesize stable ones so
+                // tool results can refer back to them
+                id: format!("ollama_call_{}", call_index),
+                name: function
+                    .and_then(|f| f.get("name"))
+                    .and_then(|n| n.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                input: function
+                    .and_then(|f| f.get("arguments"))
+                    .cloned()
+                    .unwrap_or_else(|| json!({})),
+            });
+        }
+    }
+
+    let stop_reason = if has_tool_use {
+        Some(StopReason::ToolUse)
+    } else {
+        value
+            .get("done_reason")
+            .and_then(|r| r.as_str())
+            .map(translate_done_reason)
+    };
+
+    Ok(ChatResponse {
+        id: format!(
+            "ollama_{}",
+            value
+                .get("created_at")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default()
+        ),
+        model: value
+            .get("model")
+            .and_then(|m| m.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        role: MessageRole::Assistant,
+        content,
+        stop_reason,
+        stop_sequence: None,
+        usage: translate_usage(value),
+    })
+}
+
+/// Map an Ollama `done_reason` onto the Anthropic `StopReason` vocabulary
+fn translate_done_reason(done_reason: &str) -> StopReason {
+    match done_reason {
+        "length" => StopReason::MaxTokens,
+        // "stop", "load", and anything unknown end the turn
+        _ => StopReason::EndTurn,
+    }
+}
+
+fn translate_usage(value: &Value) -> Usage {
+    Usage {
+        input_tokens: value
+            .get("prompt_eval_count")
+            .and_then(|t| t.as_u64())
+            .unwrap_or(0) as u32,
+        output_tokens: value
+            .get("eval_count")
+            .and_then(|t| t.as_u64())
+            .unwrap_or(0) as u32,
+        cache_creation_input_tokens: None,
+        cache_read_input_tokens: None,
+    }
+}
+
+/// Parser state for translating Ollama NDJSON chunks into Anthropic events.
+///
+/// Each line is a complete JSON object with a `message.content` fragment;
+/// tool calls arrive fully formed in a single chunk rather than as argument
+/// deltas, and the `done: true` chunk carries the token counts.
+struct OllamaStreamState {
+    buffer: String,
+    event_queue: std::collections::VecDeque<Result<StreamEvent>>,
+    started: bool,
+    /// Our block index for streamed text, once a text block is open
+    text_block: Option<usize>,
+    next_block_index: usize,
+    saw_tool_use: bool,
+    finish_reason: Option<StopReason>,
+    usage: Usage,
+    finished: bool,
+}
+
+impl OllamaStreamState {
+    fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            event_queue: std::collections::VecDeque::new(),
+            started: false,
+            text_block: None,
+            next_block_index: 0,
+            saw_tool_use: false,
+            finish_reason: None,
+            usage: Usage {
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+            },
+            finished: false,
+        }
+    }
+
+    fn process_buffer(&mut self) {
+        while let Some(line_boundary) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=line_boundary).collect();
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Value>(line) {
+                Ok(chunk) => self.process_chunk(&chunk),
+                Err(parse_error) => {
+                    self.event_queue.push_back(Err(Error::Other(format!(
+                        "Failed to parse Ollama stream chunk: {}. Data was: '{}'",
+                        parse_error, line
+                    ))));
+                }
+            }
+        }
+    }
+
+    fn process_chunk(&mut self, chunk: &Value) {
+        // Server-reported errors arrive as their own NDJSON object
+        if let Some(error) = chunk.get("error").and_then(|e| e.as_str()) {
+            self.event_queue
+                .push_back(Ok(StreamEvent::Error(error.to_string())));
+            return;
+        }
+
+        if !self.started {
+            self.started = true;
+            self.event_queue.push_back(Ok(StreamEvent::MessageStart {
+                message: StreamMessage {
+                    id: format!(
+                        "ollama_{}",
+                        chunk
+                            .get("created_at")
+                            .and_then(|c| c.as_str())
+                            .unwrap_or_default()
+                    ),
+                    model: chunk
+                        .get("model")
+                        .and_then(|m| m.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    role: MessageRole::Assistant,
+                    content: Vec::new(),
+                    stop_reason: None,
+                    stop_sequence: None,
+                    usage: self.usage.clone(),
+                },
+            }));
+        }
+
+        if let Some(message) = chunk.get("message") {
+            if let Some(text) = message.get("content").and_then(|c| c.as_str()) {
+                if !text.is_empty() {
+                    let index = match self.text_block {
+                        Some(index) => index,
+                        None => {
+                            let index = self.next_block_index;
+                            self.next_block_index += 1;
+                            self.text_block = Some(index);
+                            self.event_queue.push_back(Ok(StreamEvent::ContentBlockStart {
+                                index,
+                                content_block: ContentBlock::Text {
+                                    text: String::new(),
+                                },
+                            }));
+                            index
+                        }
+                    };
+                    self.event_queue.push_back(Ok(StreamEvent::ContentBlockDelta {
+                        index,
+                        delta: ContentDelta::TextDelta {
+                            text: text.to_string(),
+                        },
+                    }));
+                }
+            }
+
+            // Tool calls arrive whole; emit a complete block per call
+            if let Some(tool_calls) = message.get("tool_calls").and_then(|t| t.as_array()) {
+                for tool_call in tool_calls {
+                    if let Some(text_index) = self.text_block.take() {
+                        self.event_queue
+                            .push_back(Ok(StreamEvent::ContentBlockStop {
+                                index: text_index,
+                            }));
+                    }
+                    let function = tool_call.get("function");
+                    let index = self.next_block_index;
+                    self.next_block_index += 1;
+                    self.saw_tool_use = true;
+                    self.event_queue.push_back(Ok(StreamEvent::ContentBlockStart {
+                        index,
+                        content_block: ContentBlock::ToolUse {
+                            id: format!("ollama_call_{}", index),
+                            name: function
+                                .and_then(|f| f.get("name"))
+                                .and_then(|n| n.as_str())
+                                .unwrap_or_default()
+                                .to_string(),
+                            input: json!({}),
+                        },
+                    }));
+                    let arguments = function
+                        .and_then(|f| f.get("arguments"))
+                        .cloned()
+                        .unwrap_or_else(|| json!({}));
+                    self.event_queue.push_back(Ok(StreamEvent::ContentBlockDelta {
+                        index,
+                        delta: ContentDelta::InputJsonDelta {
+                            partial_json: arguments.to_string(),
+                        },
+                    }));
+                    self.event_queue
+                        .push_back(Ok(StreamEvent::ContentBlockStop { index }));
+                }
+            }
+        }
+
+        if chunk.get("done").and_then(|d| d.as_bool()) == Some(true) {
+            self.usage = translate_usage(chunk);
+            self.finish_reason = if self.saw_tool_use {
+                Some(StopReason::ToolUse)
+            } else {
+                chunk
+                    .get("done_reason")
+                    .and_then(|r| r.as_str())
+                    .map(translate_done_reason)
+            };
+            self.finish();
+        }
+    }
+
+    /// Close the open text block and emit the trailing message_delta /
+    /// message_stop
+    fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        if let Some(index) = self.text_block.take() {
+            self.event_queue
+                .push_back(Ok(StreamEvent::ContentBlockStop { index }));
+        }
+        self.event_queue.push_back(Ok(StreamEvent::MessageDelta {
+            delta: MessageDelta {
+                stop_reason: self.finish_reason.clone(),
+                stop_sequence: None,
+            },
+            usage: self.usage.clone(),
+        }));
+        self.event_queue.push_back(Ok(StreamEvent::MessageStop));
+    }
+}
+
+/// Parse an Ollama NDJSON stream into Anthropic-style `StreamEvent`s
+fn parse_ollama_ndjson_stream(
+    stream: impl Stream<Item = reqwest::Result<bytes::Bytes>> + Send + 'static,
+) -> impl Stream<Item = Result<StreamEvent>> + Send {
+    use futures::stream;
+
+    let pinned_stream = Box::pin(stream);
+
+    stream::unfold(
+        (pinned_stream, OllamaStreamState::new()),
+        |(mut stream, mut state)| async move {
+            loop {
+                if let Some(event) = state.event_queue.pop_front() {
+                    return Some((event, (stream, state)));
+                }
+
+                match stream.next().await {
+                    Some(Ok(bytes)) => {
+                        match std::str::from_utf8(&bytes) {
+                            Ok(text) => state.buffer.push_str(text),
+                            Err(utf8_error) => {
+                                return Some((
+                                    Err(Error::Other(format!(
+                                        "Invalid UTF-8 in stream: {}",
+                                        utf8_error
+                                    ))),
+                                    (stream, state),
+                                ));
+                            }
+                        }
+                        state.process_buffer();
+                    }
+                    Some(Err(stream_error)) => {
+                        return Some((
+                            Err(Error::Other(format!("Stream error: {}", stream_error))),
+                            (stream, state),
+                        ));
+                    }
+                    None => {
+                        // Connection closed without a done chunk
+                        state.finish();
+                        if let Some(event) = state.event_queue.pop_front() {
+                            return Some((event, (stream, state)));
+                        }
+                        return None;
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ImageSource;
+
+    fn text_message(role: MessageRole, text: &str) -> Message {
+        Message {
+            role,
+            content: MessageContent::Text(text.to_string()),
+            name: None,
+        }
+    }
+
+    #[test]
+    fn test_translate_request_options_and_tools() {
+        let request = ChatRequest {
+            model: "qwen2.5:7b".to_string(),
+            messages: vec![text_message(MessageRole::User, "hello")],
+            max_tokens: Some(1024),
+            temperature: Some(0.5),
+            top_p: Some(0.9),
+            top_k: Some(40),
+            stop_sequences: Some(vec!["<END>".to_string()]),
+            stream: None,
+            system: Some("Be terse".to_string()),
+            tools: Some(vec![Tool::Standard {
+                name: "Read".to_string(),
+                description: "Read a file".to_string(),
+                input_schema: json!({ "type": "object" }),
+            }]),
+            tool_choice: None,
+            metadata: None,
+            betas: None,
+            thinking: None,
+            service_tier: None,
+        };
+
+        let body = translate_request(&request, true);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][0]["content"], "Be terse");
+        assert_eq!(body["messages"][1]["role"], "user");
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["options"]["num_predict"], 1024);
+        assert_eq!(body["options"]["top_k"], 40);
+        assert_eq!(body["options"]["stop"][0], "<END>");
+        assert_eq!(body["tools"][0]["function"]["name"], "Read");
+    }
+
+    #[test]
+    fn test_translate_request_tool_use_result_and_image() {
+        let request = ChatRequest {
+            model: "llama3.2:3b".to_string(),
+            messages: vec![
+                Message {
+                    role: MessageRole::Assistant,
+                    content: MessageContent::Multipart(vec![ContentPart::ToolUse {
+                        id: "call_1".to_string(),
+                        name: "Read".to_string(),
+                        input: json!({ "file_path": "/tmp/a" }),
+                    }]),
+                    name: None,
+                },
+                Message {
+                    role: MessageRole::User,
+                    content: MessageContent::Multipart(vec![
+                        ContentPart::ToolResult {
+                            tool_use_id: "call_1".to_string(),
+                            content: "file contents".to_string(),
+                            is_error: None,
+                        },
+                    ]),
+                    name: None,
+                },
+                Message {
+                    role: MessageRole::User,
+                    content: MessageContent::Multipart(vec![
+                        ContentPart::Text {
+                            text: "what is this".to_string(),
+                            citations: None,
+                        },
+                        ContentPart::Image {
+                            source: ImageSource {
+                                source_type: "base64".to_string(),
+                                media_type: "image/png".to_string(),
+                                data: "AAAA".to_string(),
+                            },
+                        },
+                    ]),
+                    name: None,
+                },
+            ],
+            max_tokens: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: None,
+            system: None,
+            tools: None,
+            tool_choice: None,
+            metadata: None,
+            betas: None,
+            thinking: None,
+            service_tier: None,
+        };
+
+        let body = translate_request(&request, false);
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        // Ollama takes arguments as a JSON object, not a string
+        assert_eq!(
+            messages[0]["tool_calls"][0]["function"]["arguments"]["file_path"],
+            "/tmp/a"
+        );
+        assert_eq!(messages[1]["role"], "tool");
+        assert_eq!(messages[1]["content"], "file contents");
+        assert_eq!(messages[2]["content"], "what is this");
+        assert_eq!(messages[2]["images"][0], "AAAA");
+    }
+
+    #[test]
+    fn test_translate_response_with_tool_call() {
+        let value = json!({
+            "model": "qwen2.5:7b",
+            "created_at": "2025-01-01T00:00:00Z",
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{
+                    "function": {
+                        "name": "Read",
+                        "arguments": { "file_path": "/tmp/a" }
+                    }
+                }]
+            },
+            "done": true,
+            "done_reason": "stop",
+            "prompt_eval_count": 12,
+            "eval_count": 7
+        });
+
+        let response = translate_response(&value).unwrap();
+        assert_eq!(response.model, "qwen2.5:7b");
+        assert!(matches!(response.stop_reason, Some(StopReason::ToolUse)));
+        assert_eq!(response.usage.input_tokens, 12);
+        assert_eq!(response.usage.output_tokens, 7);
+        match &response.content[0] {
+            ContentPart::ToolUse { name, input, .. } => {
+                assert_eq!(name, "Read");
+                assert_eq!(input["file_path"], "/tmp/a");
+            }
+            other => panic!("Expected tool use, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_stream_state_emits_anthropic_sequence() {
+        let mut state = OllamaStreamState::new();
+        state.buffer.push_str(concat!(
+            "{\"model\":\"qwen2.5:7b\",\"created_at\":\"t\",\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n",
+            "{\"model\":\"qwen2.5:7b\",\"created_at\":\"t\",\"message\":{\"role\":\"assistant\",\"content\":\"lo\"},\"done\":false}\n",
+            "{\"model\":\"qwen2.5:7b\",\"created_at\":\"t\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"done_reason\":\"stop\",\"prompt_eval_count\":10,\"eval_count\":5}\n",
+        ));
+        state.process_buffer();
+
+        let events: Vec<StreamEvent> = state
+            .event_queue
+            .into_iter()
+            .map(|event| event.unwrap())
+            .collect();
+
+        assert!(matches!(events[0], StreamEvent::MessageStart { .. }));
+        assert!(matches!(
+            events[1],
+            StreamEvent::ContentBlockStart {
+                index: 0,
+                content_block: ContentBlock::Text { .. }
+            }
+        ));
+        assert!(matches!(
+            events[2],
+            StreamEvent::ContentBlockDelta { index: 0, .. }
+        ));
+        assert!(matches!(
+            events[3],
+            StreamEvent::ContentBlockDelta { index: 0, .. }
+        ));
+        assert!(matches!(
+            events[4],
+            StreamEvent::ContentBlockStop { index: 0 }
+        ));
+        match &events[5] {
+            StreamEvent::MessageDelta { delta, usage } => {
+                assert!(matches!(delta.stop_reason, Some(StopReason::EndTurn)));
+                assert_eq!(usage.input_tokens, 10);
+                assert_eq!(usage.output_tokens, 5);
+            }
+            other => panic!("Expected message delta, got {:?}", other),
+        }
+        assert!(matches!(events[6], StreamEvent::MessageStop));
+    }
+}
@@ -0,0 +1,929 @@
+//! OpenAI-compatible provider support.
+//!
+//! Translates the Anthropic-shaped `ChatRequest`/`ChatResponse`/`StreamEvent`
+//! types used throughout the application into the OpenAI Chat Completions
+//! wire format and back, so `AIConfig` can target any OpenAI-compatible
+//! endpoint (OpenAI, OpenRouter, Together, local vLLM, self-hosted gateways)
+//! without changing the TUI or the streaming pipeline.
+//!
+//! Selection happens via `AIConfig::provider` (see `Provider` in the parent
+//! module); `AIClientAdapter` routes requests here when it is set to
+//! `openai-compatible`. Streaming chunks are re-emitted as the same
+//! `StreamEvent` sequence the Anthropic SSE parser produces (message_start,
+//! content_block_start/delta/stop, message_delta, message_stop), so
+//! `streaming.rs` consumes both providers identically.
+
+use crate::ai::client::{ContentBlock, ContentDelta, MessageDelta, StreamEvent, StreamMessage};
+use crate::ai::{
+    ChatRequest, ChatResponse, ContentPart, Message, MessageContent, MessageRole, StopReason,
+    Tool, ToolChoice, Usage,
+};
+use crate::error::{Error, Result};
+use futures::stream::{Stream, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Client for OpenAI-compatible Chat Completions endpoints
+pub struct OpenAICompatClient {
+    config: crate::ai::AIConfig,
+    http_client: reqwest::Client,
+}
+
+impl OpenAICompatClient {
+    pub fn new(config: crate::ai::AIConfig) -> Result<Self> {
+        let http_client = crate::utils::http::client_builder()?
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            config,
+            http_client,
+        })
+    }
+
+    /// The `/chat/completions` URL for the configured base
+    fn endpoint(&self) -> String {
+        format!(
+            "{}/chat/completions",
+            self.config.base_url.trim_end_matches('/')
+        )
+    }
+
+    fn build_request(&self, body: &Value) -> reqwest::RequestBuilder {
+        let mut builder = self
+            .http_client
+            .post(self.endpoint())
+            .header("content-type", "application/json");
+        // Local servers (e.g. vLLM) commonly run without authentication
+        if !self.config.api_key.is_empty() {
+            builder = builder.bearer_auth(&self.config.api_key);
+        } else if let Some(ref auth_token) = self.config.auth_token {
+            builder = builder.bearer_auth(auth_token);
+        }
+        // Gateway header passthrough (customHeaders settings and
+        // ANTHROPIC_CUSTOM_HEADERS)
+        for (name, value) in crate::config::get_custom_headers("openai-compatible") {
+            builder = builder.header(name, value);
+        }
+        builder.json(body)
+    }
+
+    /// Send a non-streaming chat request
+    pub async fn chat(&self, request: &ChatRequest) -> Result<ChatResponse> {
+        let body = translate_request(request, false);
+        let response = self
+            .build_request(&body)
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to send request: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(Error::Other(format!(
+                "OpenAI-compatible request failed with status {}: {}",
+                status, text
+            )));
+        }
+
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to parse response JSON: {}", e)))?;
+        translate_response(&value)
+    }
+
+    /// Send a streaming chat request, re-emitted as Anthropic-style events
+    pub async fn chat_stream(
+        &self,
+        request: &ChatRequest,
+    ) -> Result<impl Stream<Item = Result<StreamEvent>> + Send> {
+        let mut body = translate_request(request, true);
+        // Ask for a final usage chunk so message_delta can carry real token counts
+        body["stream_options"] = json!({ "include_usage": true });
+
+        let response = self
+            .build_request(&body)
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to send streaming request: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(Error::Other(format!(
+                "OpenAI-compatible streaming request failed with status {}: {}",
+                status, text
+            )));
+        }
+
+        Ok(parse_openai_sse_stream(response.bytes_stream()))
+    }
+}
+
+/// Translate a `ChatRequest` into an OpenAI Chat Completions request body
+pub fn translate_request(request: &ChatRequest, stream: bool) -> Value {
+    let mut messages: Vec<Value> = Vec::new();
+
+    // Anthropic carries the system prompt as a top-level field; OpenAI as
+    // the first message
+    if let Some(ref system) = request.system {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+
+    for message in &request.messages {
+        translate_message(message, &mut messages);
+    }
+
+    let mut body = json!({
+        "model": request.model,
+        "messages": messages,
+    });
+
+    if let Some(max_tokens) = request.max_tokens {
+        body["max_tokens"] = json!(max_tokens);
+    }
+    if let Some(temperature) = request.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    if let Some(top_p) = request.top_p {
+        body["top_p"] = json!(top_p);
+    }
+    if let Some(ref stop_sequences) = request.stop_sequences {
+        body["stop"] = json!(stop_sequences);
+    }
+    if stream {
+        body["stream"] = json!(true);
+    }
+
+    if let Some(ref tools) = request.tools {
+        let functions: Vec<Value> = tools
+            .iter()
+            .filter_map(|tool| match tool {
+                Tool::Standard {
+                    name,
+                    description,
+                    input_schema,
+                } => Some(json!({
+                    "type": "function",
+                    "function": {
+                        "name": name,
+                        "description": description,
+                        "parameters": input_schema,
+                    }
+                })),
+                // Server-side tools (web search) have no OpenAI equivalent
+                Tool::WebSearch { .. } => None,
+            })
+            .collect();
+        if !functions.is_empty() {
+            body["tools"] = json!(functions);
+        }
+    }
+
+    if let Some(ref tool_choice) = request.tool_choice {
+        body["tool_choice"] = match tool_choice {
+            ToolChoice::Auto => json!("auto"),
+            ToolChoice::Any => json!("required"),
+            ToolChoice::Tool { name } => json!({
+                "type": "function",
+                "function": { "name": name }
+            }),
+        };
+    }
+
+    body
+}
+
+/// Translate one Anthropic-shaped message into OpenAI messages.
+///
+/// A single multipart message can fan out: tool results become separate
+/// `role: "tool"` messages, and tool uses become `tool_calls` on the
+/// assistant message.
+fn translate_message(message: &Message, out: &mut Vec<Value>) {
+    let role = match message.role {
+        MessageRole::System => "system",
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::Tool => "tool",
+    };
+
+    match &message.content {
+        MessageContent::Text(text) => {
+            out.push(json!({ "role": role, "content": text }));
+        }
+        MessageContent::Multipart(parts) => {
+            let mut content_items: Vec<Value> = Vec::new();
+            let mut tool_calls: Vec<Value> = Vec::new();
+            let mut tool_results: Vec<Value> = Vec::new();
+
+            for part in parts {
+                match part {
+                    ContentPart::Text { text, .. } => {
+                        content_items.push(json!({ "type": "text", "text": text }));
+                    }
+                    ContentPart::Image { source } => {
+                        content_items.push(json!({
+                            "type": "image_url",
+                            "image_url": {
+                                "url": format!(
+                                    "data:{};base64,{}",
+                                    source.media_type, source.data
+                                )
+                            }
+                        }));
+                    }
+                    ContentPart::ToolUse { id, name, input } => {
+                        tool_calls.push(json!({
+                            "id": id,
+                            "type": "function",
+                            "function": {
+                                "name": name,
+                                // OpenAI carries tool arguments as a JSON string
+                                "arguments": input.to_string(),
+                            }
+                        }));
+                    }
+                    ContentPart::ToolResult {
+                        tool_use_id,
+                        content,
+                        ..
+                    } => {
+                        tool_results.push(json!({
+                            "role": "tool",
+                            "tool_call_id": tool_use_id,
+                            "content": content,
+                        }));
+                    }
+                    // Server-side tool traffic and thinking blocks have no
+                    // OpenAI representation
+                    ContentPart::ServerToolUse { .. }
+                    | ContentPart::WebSearchToolResult { .. }
+                    | ContentPart::Thinking { .. }
+                    | ContentPart::RedactedThinking { .. } => {}
+                }
+            }
+
+            if !content_items.is_empty() || !tool_calls.is_empty() {
+                // Collapse a single text part to a plain string for broader
+                // server compatibility (some gateways reject content arrays)
+                let content = if content_items.len() == 1
+                    && content_items[0].get("type").and_then(|t| t.as_str()) == Some("text")
+                {
+                    content_items[0]
+                        .get("text")
+                        .cloned()
+                        .unwrap_or(Value::Null)
+                } else if content_items.is_empty() {
+                    Value::Null
+                } else {
+                    Value::Array(content_items)
+                };
+
+                let mut entry = json!({ "role": role, "content": content });
+                if !tool_calls.is_empty() {
+                    entry["tool_calls"] = Value::Array(tool_calls);
+                }
+                out.push(entry);
+            }
+
+            out.extend(tool_results);
+        }
+    }
+}
+
+/// Translate an OpenAI Chat Completions response into a `ChatResponse`
+pub fn translate_response(value: &Value) -> Result<ChatResponse> {
+    let choice = value
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .and_then(|c| c.first())
+        .ok_or_else(|| Error::Other("OpenAI response contained no choices".to_string()))?;
+    let message = choice
+        .get("message")
+        .ok_or_else(|| Error::Other("OpenAI response choice contained no message".to_string()))?;
+
+    let mut content: Vec<ContentPart> = Vec::new();
+    if let Some(text) = message.get("content").and_then(|c| c.as_str()) {
+        if !text.is_empty() {
+            content.push(ContentPart::Text {
+                text: text.to_string(),
+                citations: None,
+            });
+        }
+    }
+    if let Some(tool_calls) = message.get("tool_calls").and_then(|t| t.as_array()) {
+        for tool_call in tool_calls {
+            let function = tool_call.get("function");
+            let name = function
+                .and_then(|f| f.get("name"))
+                .and_then(|n| n.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let arguments = function
+                .and_then(|f| f.get("arguments"))
+                .and_then(|a| a.as_str())
+                .unwrap_or("{}");
+            content.push(ContentPart::ToolUse {
+                id: tool_call
+                    .get("id")
+                    .and_then(|i| i.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                name,
+                input: serde_json::from_str(arguments).unwrap_or_else(|_| json!({})),
+            });
+        }
+    }
+
+    let stop_reason = choice
+        .get("finish_reason")
+        .and_then(|f| f.as_str())
+        .map(translate_finish_reason);
+
+    Ok(ChatResponse {
+        id: value
+            .get("id")
+            .and_then(|i| i.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        model: value
+            .get("model")
+            .and_then(|m| m.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        role: MessageRole::Assistant,
+        content,
+        stop_reason,
+        stop_sequence: None,
+        usage: translate_usage(value.get("usage")),
+    })
+}
+
+/// Map an OpenAI `finish_reason` onto the Anthropic `StopReason` vocabulary
+fn translate_finish_reason(finish_reason: &str) -> StopReason {
+    match finish_reason {
+        "length" => StopReason::MaxTokens,
+        "tool_calls" | "function_call" => StopReason::ToolUse,
+        // "stop", "content_filter", and anything unknown end the turn
+        _ => StopReason::EndTurn,
+    }
+}
+
+fn translate_usage(usage: Option<&Value>) -> Usage {
+    let prompt_tokens = usage
+        .and_then(|u| u.get("prompt_tokens"))
+        .and_then(|t| t.as_u64())
+        .unwrap_or(0) as u32;
+    let completion_tokens = usage
+        .and_then(|u| u.get("completion_tokens"))
+        .and_then(|t| t.as_u64())
+        .unwrap_or(0) as u32;
+    Usage {
+        input_tokens: prompt_tokens,
+        output_tokens: completion_tokens,
+        cache_creation_input_tokens: None,
+        cache_read_input_tokens: None,
+    }
+}
+
+/// Parser state for translating OpenAI stream chunks into Anthropic events.
+///
+/// OpenAI streams flat `choices[].delta` chunks; Anthropic streams indexed
+/// content blocks. We synthesize block boundaries: one text block for the
+/// running text, and one tool_use block per `tool_calls[].index`.
+struct OpenAIStreamState {
+    buffer: String,
+    event_queue: std::collections::VecDeque<Result<StreamEvent>>,
+    started: bool,
+    /// Our block index for streamed text, once a text block is open
+    text_block: Option<usize>,
+    /// OpenAI tool_call index -> our content block index
+    tool_blocks: std::collections::HashMap<u64, usize>,
+    next_block_index: usize,
+    finish_reason: Option<StopReason>,
+    usage: Usage,
+    finished: bool,
+}
+
+impl OpenAIStreamState {
+    fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            event_queue: std::collections::VecDeque::new(),
+            started: false,
+            text_block: None,
+            tool_blocks: std::collections::HashMap::new(),
+            next_block_index: 0,
+            finish_reason: None,
+            usage: Usage {
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+            },
+            finished: false,
+        }
+    }
+
+    fn process_buffer(&mut self) {
+        while let Some(event_boundary) = self.buffer.find("\n\n") {
+            let event_text: String = self.buffer.drain(..=event_boundary + 1).collect();
+            for line in event_text.lines() {
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let data = data.trim_start();
+                if data == "[DONE]" {
+                    self.finish();
+                    continue;
+                }
+                match serde_json::from_str::<Value>(data) {
+                    Ok(chunk) => self.process_chunk(&chunk),
+                    Err(parse_error) => {
+                        self.event_queue.push_back(Err(Error::Other(format!(
+                            "Failed to parse OpenAI stream chunk: {}. Data was: '{}'",
+                            parse_error, data
+                        ))));
+                    }
+                }
+            }
+        }
+    }
+
+    fn process_chunk(&mut self, chunk: &Value) {
+        if !self.started {
+            self.started = true;
+            self.event_queue.push_back(Ok(StreamEvent::MessageStart {
+                message: StreamMessage {
+                    id: chunk
+                        .get("id")
+                        .and_then(|i| i.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    model: chunk
+                        .get("model")
+                        .and_then(|m| m.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    role: MessageRole::Assistant,
+                    content: Vec::new(),
+                    stop_reason: None,
+                    stop_sequence: None,
+                    usage: self.usage.clone(),
+                },
+            }));
+        }
+
+        // The final usage-only chunk has an empty choices array
+        if let Some(usage) = chunk.get("usage") {
+            if !usage.is_null() {
+                self.usage = translate_usage(Some(usage));
+            }
+        }
+
+        let Some(choice) = chunk
+            .get("choices")
+            .and_then(|c| c.as_array())
+            .and_then(|c| c.first())
+        else {
+            return;
+        };
+
+        if let Some(delta) = choice.get("delta") {
+            if let Some(text) = delta.get("content").and_then(|c| c.as_str()) {
+                if !text.is_empty() {
+                    let index = match self.text_block {
+                        Some(index) => index,
+                        None => {
+                            let index = self.next_block_index;
+                            self.next_block_index += 1;
+                            self.text_block = Some(index);
+                            self.event_queue.push_back(Ok(StreamEvent::ContentBlockStart {
+                                index,
+                                content_block: ContentBlock::Text {
+                                    text: String::new(),
+                                },
+                            }));
+                            index
+                        }
+                    };
+                    self.event_queue.push_back(Ok(StreamEvent::ContentBlockDelta {
+                        index,
+                        delta: ContentDelta::TextDelta {
+                            text: text.to_string(),
+                        },
+                    }));
+                }
+            }
+
+            if let Some(tool_calls) = delta.get("tool_calls").and_then(|t| t.as_array()) {
+                for tool_call in tool_calls {
+                    let openai_index = tool_call
+                        .get("index")
+                        .and_then(|i| i.as_u64())
+                        .unwrap_or(0);
+                    let function = tool_call.get("function");
+
+                    let index = match self.tool_blocks.get(&openai_index) {
+                        Some(index) => *index,
+                        None => {
+                            // Text streams before tool calls; close the text
+                            // block so indices mirror the Anthropic sequence
+                            if let Some(text_index) = self.text_block.take() {
+                                self.event_queue
+                                    .push_back(Ok(StreamEvent::ContentBlockStop {
+                                        index: text_index,
+                                    }));
+                            }
+                            let index = self.next_block_index;
+                            self.next_block_index += 1;
+                            self.tool_blocks.insert(openai_index, index);
+                            self.event_queue.push_back(Ok(StreamEvent::ContentBlockStart {
+                                index,
+                                content_block: ContentBlock::ToolUse {
+                                    id: tool_call
+                                        .get("id")
+                                        .and_then(|i| i.as_str())
+                                        .unwrap_or_default()
+                                        .to_string(),
+                                    name: function
+                                        .and_then(|f| f.get("name"))
+                                        .and_then(|n| n.as_str())
+                                        .unwrap_or_default()
+                                        .to_string(),
+                                    input: json!({}),
+                                },
+                            }));
+                            index
+                        }
+                    };
+
+                    if let Some(arguments) = function
+                        .and_then(|f| f.get("arguments"))
+                        .and_then(|a| a.as_str())
+                    {
+                        if !arguments.is_empty() {
+                            self.event_queue.push_back(Ok(StreamEvent::ContentBlockDelta {
+                                index,
+                                delta: ContentDelta::InputJsonDelta {
+                                    partial_json: arguments.to_string(),
+                                },
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(finish_reason) = choice.get("finish_reason").and_then(|f| f.as_str()) {
+            self.finish_reason = Some(translate_finish_reason(finish_reason));
+        }
+    }
+
+    /// Close open blocks and emit the trailing message_delta / message_stop
+    fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        if let Some(index) = self.text_block.take() {
+            self.event_queue
+                .push_back(Ok(StreamEvent::ContentBlockStop { index }));
+        }
+        let mut tool_indices: Vec<usize> = self.tool_blocks.values().copied().collect();
+        tool_indices.sort_unstable();
+        for index in tool_indices {
+            self.event_queue
+                .push_back(Ok(StreamEvent::ContentBlockStop { index }));
+        }
+        self.tool_blocks.clear();
+        self.event_queue.push_back(Ok(StreamEvent::MessageDelta {
+            delta: MessageDelta {
+                stop_reason: self.finish_reason.clone(),
+                stop_sequence: None,
+            },
+            usage: self.usage.clone(),
+        }));
+        self.event_queue.push_back(Ok(StreamEvent::MessageStop));
+    }
+}
+
+/// Parse an OpenAI SSE stream into Anthropic-style `StreamEvent`s
+fn parse_openai_sse_stream(
+    stream: impl Stream<Item = reqwest::Result<bytes::Bytes>> + Send + 'static,
+) -> impl Stream<Item = Result<StreamEvent>> + Send {
+    use futures::stream;
+
+    let pinned_stream = Box::pin(stream);
+
+    stream::unfold(
+        (pinned_stream, OpenAIStreamState::new()),
+        |(mut stream, mut state)| async move {
+            loop {
+                if let Some(event) = state.event_queue.pop_front() {
+                    return Some((event, (stream, state)));
+                }
+
+                match stream.next().await {
+                    Some(Ok(bytes)) => {
+                        match std::str::from_utf8(&bytes) {
+                            Ok(text) => state.buffer.push_str(text),
+                            Err(utf8_error) => {
+                                return Some((
+                                    Err(Error::Other(format!(
+                                        "Invalid UTF-8 in stream: {}",
+                                        utf8_error
+                                    ))),
+                                    (stream, state),
+                                ));
+                            }
+                        }
+                        state.process_buffer();
+                    }
+                    Some(Err(stream_error)) => {
+                        return Some((
+                            Err(Error::Other(format!("Stream error: {}", stream_error))),
+                            (stream, state),
+                        ));
+                    }
+                    None => {
+                        // Some servers close the connection without [DONE]
+                        state.finish();
+                        if let Some(event) = state.event_queue.pop_front() {
+                            return Some((event, (stream, state)));
+                        }
+                        return None;
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ImageSource;
+
+    fn text_message(role: MessageRole, text: &str) -> Message {
+        Message {
+            role,
+            content: MessageContent::Text(text.to_string()),
+            name: None,
+        }
+    }
+
+    #[test]
+    fn test_translate_request_system_and_tools() {
+        let request = ChatRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![text_message(MessageRole::User, "hello")],
+            max_tokens: Some(1024),
+            temperature: Some(0.5),
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: None,
+            system: Some("Be terse".to_string()),
+            tools: Some(vec![Tool::Standard {
+                name: "Read".to_string(),
+                description: "Read a file".to_string(),
+                input_schema: json!({ "type": "object" }),
+            }]),
+            tool_choice: Some(ToolChoice::Auto),
+            metadata: None,
+            betas: None,
+            thinking: None,
+            service_tier: None,
+        };
+
+        let body = translate_request(&request, true);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][0]["content"], "Be terse");
+        assert_eq!(body["messages"][1]["role"], "user");
+        assert_eq!(body["messages"][1]["content"], "hello");
+        assert_eq!(body["max_tokens"], 1024);
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["tools"][0]["type"], "function");
+        assert_eq!(body["tools"][0]["function"]["name"], "Read");
+        assert_eq!(body["tool_choice"], "auto");
+    }
+
+    #[test]
+    fn test_translate_request_tool_use_and_result() {
+        let request = ChatRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![
+                Message {
+                    role: MessageRole::Assistant,
+                    content: MessageContent::Multipart(vec![ContentPart::ToolUse {
+                        id: "call_1".to_string(),
+                        name: "Read".to_string(),
+                        input: json!({ "file_path": "/tmp/a" }),
+                    }]),
+                    name: None,
+                },
+                Message {
+                    role: MessageRole::User,
+                    content: MessageContent::Multipart(vec![ContentPart::ToolResult {
+                        tool_use_id: "call_1".to_string(),
+                        content: "file contents".to_string(),
+                        is_error: None,
+                    }]),
+                    name: None,
+                },
+            ],
+            max_tokens: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: None,
+            system: None,
+            tools: None,
+            tool_choice: None,
+            metadata: None,
+            betas: None,
+            thinking: None,
+            service_tier: None,
+        };
+
+        let body = translate_request(&request, false);
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["role"], "assistant");
+        assert_eq!(messages[0]["tool_calls"][0]["id"], "call_1");
+        assert_eq!(messages[0]["tool_calls"][0]["function"]["name"], "Read");
+        assert_eq!(
+            messages[0]["tool_calls"][0]["function"]["arguments"],
+            "{\"file_path\":\"/tmp/a\"}"
+        );
+        assert_eq!(messages[1]["role"], "tool");
+        assert_eq!(messages[1]["tool_call_id"], "call_1");
+        assert_eq!(messages[1]["content"], "file contents");
+    }
+
+    #[test]
+    fn test_translate_request_image_part() {
+        let request = ChatRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![Message {
+                role: MessageRole::User,
+                content: MessageContent::Multipart(vec![
+                    ContentPart::Text {
+                        text: "what is this".to_string(),
+                        citations: None,
+                    },
+                    ContentPart::Image {
+                        source: ImageSource {
+                            source_type: "base64".to_string(),
+                            media_type: "image/png".to_string(),
+                            data: "AAAA".to_string(),
+                        },
+                    },
+                ]),
+                name: None,
+            }],
+            max_tokens: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: None,
+            system: None,
+            tools: None,
+            tool_choice: None,
+            metadata: None,
+            betas: None,
+            thinking: None,
+            service_tier: None,
+        };
+
+        let body = translate_request(&request, false);
+        let content = body["messages"][0]["content"].as_array().unwrap();
+        assert_eq!(content[0]["type"], "text");
+        assert_eq!(content[1]["type"], "image_url");
+        assert_eq!(
+            content[1]["image_url"]["url"],
+            "data:image/png;base64,AAAA"
+        );
+    }
+
+    #[test]
+    fn test_translate_response_with_tool_call() {
+        let value = json!({
+            "id": "chatcmpl-1",
+            "model": "gpt-4o",
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": "Reading the file",
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {
+                            "name": "Read",
+                            "arguments": "{\"file_path\":\"/tmp/a\"}"
+                        }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": { "prompt_tokens": 12, "completion_tokens": 7 }
+        });
+
+        let response = translate_response(&value).unwrap();
+        assert_eq!(response.id, "chatcmpl-1");
+        assert_eq!(response.content.len(), 2);
+        assert!(matches!(
+            response.stop_reason,
+            Some(StopReason::ToolUse)
+        ));
+        assert_eq!(response.usage.input_tokens, 12);
+        assert_eq!(response.usage.output_tokens, 7);
+        match &response.content[1] {
+            ContentPart::ToolUse { id, name, input } => {
+                assert_eq!(id, "call_1");
+                assert_eq!(name, "Read");
+                assert_eq!(input["file_path"], "/tmp/a");
+            }
+            other => panic!("Expected tool use, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_stream_state_emits_anthropic_sequence() {
+        let mut state = OpenAIStreamState::new();
+        state.buffer.push_str(concat!(
+            "data: {\"id\":\"chatcmpl-1\",\"model\":\"gpt-4o\",\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"function\":{\"name\":\"Read\",\"arguments\":\"\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"{\\\"file_path\\\":\\\"/tmp/a\\\"}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":10,\"completion_tokens\":5}}\n\n",
+            "data: [DONE]\n\n",
+        ));
+        state.process_buffer();
+
+        let events: Vec<StreamEvent> = state
+            .event_queue
+            .into_iter()
+            .map(|event| event.unwrap())
+            .collect();
+
+        assert!(matches!(events[0], StreamEvent::MessageStart { .. }));
+        assert!(matches!(
+            events[1],
+            StreamEvent::ContentBlockStart {
+                index: 0,
+                content_block: ContentBlock::Text { .. }
+            }
+        ));
+        assert!(matches!(
+            events[2],
+            StreamEvent::ContentBlockDelta { index: 0, .. }
+        ));
+        assert!(matches!(
+            events[3],
+            StreamEvent::ContentBlockDelta { index: 0, .. }
+        ));
+        // Text block closes before the tool block opens
+        assert!(matches!(
+            events[4],
+            StreamEvent::ContentBlockStop { index: 0 }
+        ));
+        assert!(matches!(
+            events[5],
+            StreamEvent::ContentBlockStart {
+                index: 1,
+                content_block: ContentBlock::ToolUse { .. }
+            }
+        ));
+        assert!(matches!(
+            events[6],
+            StreamEvent::ContentBlockDelta {
+                index: 1,
+                delta: ContentDelta::InputJsonDelta { .. }
+            }
+        ));
+        assert!(matches!(
+            events[7],
+            StreamEvent::ContentBlockStop { index: 1 }
+        ));
+        match &events[8] {
+            StreamEvent::MessageDelta { delta, usage } => {
+                assert!(matches!(delta.stop_reason, Some(StopReason::ToolUse)));
+                assert_eq!(usage.input_tokens, 10);
+                assert_eq!(usage.output_tokens, 5);
+            }
+            other => panic!("Expected message delta, got {:?}", other),
+        }
+        assert!(matches!(events[9], StreamEvent::MessageStop));
+    }
+}
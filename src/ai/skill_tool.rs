@@ -71,6 +71,14 @@ pub struct SkillFrontmatter {
     pub disable_model_invocation: Option<bool>,
 }
 
+/// A shell command whose output is gathered at invocation time and
+/// appended to the skill prompt as pre-collected context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextCommand {
+    pub label: String,
+    pub command: String,
+}
+
 /// Skill definition - matches JavaScript skill structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
@@ -101,8 +109,20 @@ pub struct Skill {
     /// The content of the SKILL.md file (for prompt-based skills)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    /// Commands run when the skill is invoked; their output is appended
+    /// to the prompt so the model starts with the context already
+    /// gathered (used by the built-in workflow skills)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_commands: Vec<ContextCommand>,
 }
 
+/// How long one context command may run before being abandoned
+const CONTEXT_COMMAND_TIMEOUT_SECS: u64 = 15;
+
+/// Cap on captured context command output (chars), so one noisy command
+/// can't fill the prompt
+const MAX_CONTEXT_OUTPUT_LEN: usize = 8_000;
+
 impl Skill {
     /// Get user-facing name (from frontmatter or directory name)
     pub fn user_facing_name(&self) -> &str {
@@ -137,6 +157,56 @@ impl Skill {
 
         Ok(prompt)
     }
+
+    /// Run the skill's context commands and format their output as a
+    /// section to append to the prompt. Failures and timeouts become
+    /// notes in the output rather than errors - missing context should
+    /// not stop the skill from running.
+    pub async fn gather_context(&self, arguments: Option<&str>) -> Option<String> {
+        if self.context_commands.is_empty() {
+            return None;
+        }
+
+        let args = arguments.unwrap_or("");
+        let mut sections = String::from("## Pre-gathered context\n");
+
+        for ctx in &self.context_commands {
+            let command = ctx.command.replace("$ARGUMENTS", args);
+            let output = match tokio::time::timeout(
+                std::time::Duration::from_secs(CONTEXT_COMMAND_TIMEOUT_SECS),
+                tokio::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(&command)
+                    .output(),
+            )
+            .await
+            {
+                Ok(Ok(out)) => {
+                    let stdout = String::from_utf8_lossy(&out.stdout);
+                    let text = stdout.trim_end();
+                    if text.is_empty() {
+                        if out.status.success() {
+                            "(no output)".to_string()
+                        } else {
+                            let stderr = String::from_utf8_lossy(&out.stderr);
+                            format!("(command failed: {})", stderr.trim())
+                        }
+                    } else if text.chars().count() > MAX_CONTEXT_OUTPUT_LEN {
+                        let truncated: String = text.chars().take(MAX_CONTEXT_OUTPUT_LEN).collect();
+                        format!("{}\n... (output truncated)", truncated)
+                    } else {
+                        text.to_string()
+                    }
+                }
+                Ok(Err(e)) => format!("(command failed: {})", e),
+                Err(_) => "(command timed out)".to_string(),
+            };
+
+            sections.push_str(&format!("\n### {}\n```\n{}\n```\n", ctx.label, output));
+        }
+
+        Some(sections)
+    }
 }
 
 /// Skill execution result - matches JavaScript output schema
@@ -188,6 +258,7 @@ Steps:
 3. Craft a commit message based on the changes
 4. Create the commit with `git commit -m "message"`
 "#.to_string()),
+            context_commands: Vec::new(),
         },
     );
 
@@ -226,6 +297,7 @@ Steps:
    - Documentation
 4. Provide a constructive review with specific suggestions
 "#.to_string()),
+            context_commands: Vec::new(),
         },
     );
 
@@ -252,6 +324,115 @@ $ARGUMENTS
 
 Use the Read tool to read the PDF file. The tool will automatically extract text content from PDFs.
 "#.to_string()),
+            context_commands: Vec::new(),
+        },
+    );
+
+    // standup skill - drafts a standup update from recent work
+    skills.insert(
+        "standup".to_string(),
+        Skill {
+            skill_type: SkillType::Prompt,
+            name: "standup".to_string(),
+            description: "Draft a standup update from recent commits and the working tree".to_string(),
+            allowed_tools: Some(vec!["Bash".to_string(), "Read".to_string()]),
+            argument_hint: None,
+            when_to_use: Some("When the user wants a standup summary of recent work".to_string()),
+            version: Some("1.0.0".to_string()),
+            model: None,
+            is_skill: true,
+            disable_model_invocation: false,
+            is_hidden: false,
+            source: SkillSource::BuiltIn,
+            skill_dir: None,
+            content: Some(r#"Draft a standup update from the pre-gathered context below.
+
+Format:
+- **Yesterday**: what the recent commits accomplished, grouped by theme (not one bullet per commit)
+- **Today**: what the uncommitted working-tree changes suggest is in progress
+- **Blockers**: anything the context hints is stuck (reverts, repeated fixes, failing work); write "none" if nothing stands out
+
+Keep it short enough to read aloud. Don't invent work that isn't in the context; if the context is empty, say so.
+"#.to_string()),
+            context_commands: vec![
+                ContextCommand {
+                    label: "Commits from the last 24 hours".to_string(),
+                    command: "git log --since='24 hours ago' --oneline --no-merges".to_string(),
+                },
+                ContextCommand {
+                    label: "Working tree".to_string(),
+                    command: "git status --short".to_string(),
+                },
+            ],
+        },
+    );
+
+    // changelog-since skill - drafts a changelog from commits since a tag
+    skills.insert(
+        "changelog-since".to_string(),
+        Skill {
+            skill_type: SkillType::Prompt,
+            name: "changelog-since".to_string(),
+            description: "Draft a changelog from commits since a tag".to_string(),
+            allowed_tools: Some(vec!["Bash".to_string(), "Read".to_string()]),
+            argument_hint: Some("<tag>".to_string()),
+            when_to_use: Some("When the user wants release notes or a changelog draft".to_string()),
+            version: Some("1.0.0".to_string()),
+            model: None,
+            is_skill: true,
+            disable_model_invocation: false,
+            is_hidden: false,
+            source: SkillSource::BuiltIn,
+            skill_dir: None,
+            content: Some(r#"Draft a changelog from the pre-gathered commit list below (commits since the given tag, or since the most recent tag when none was given).
+
+Guidelines:
+1. Group entries under **Added**, **Changed**, **Fixed**, and **Removed**; drop empty groups
+2. Write entries for users, not committers: describe the visible effect, not the refactor behind it
+3. Conventional-commit prefixes (feat/fix/chore...) indicate the group; fold chore/ci/test commits into one line or omit them
+4. Run `git show <hash>` on commits whose one-line subject is too vague to classify
+"#.to_string()),
+            context_commands: vec![
+                ContextCommand {
+                    label: "Commits since the tag".to_string(),
+                    command: r#"TAG="$ARGUMENTS"; [ -z "$TAG" ] && TAG=$(git describe --tags --abbrev=0 2>/dev/null); git log ${TAG:+$TAG..}HEAD --oneline --no-merges"#.to_string(),
+                },
+            ],
+        },
+    );
+
+    // triage skill - prioritizes open issues
+    skills.insert(
+        "triage".to_string(),
+        Skill {
+            skill_type: SkillType::Prompt,
+            name: "triage".to_string(),
+            description: "Triage open GitHub issues by priority".to_string(),
+            allowed_tools: Some(vec!["Bash".to_string(), "Read".to_string(), "WebFetch".to_string()]),
+            argument_hint: None,
+            when_to_use: Some("When the user wants open issues categorized and prioritized".to_string()),
+            version: Some("1.0.0".to_string()),
+            model: None,
+            is_skill: true,
+            disable_model_invocation: false,
+            is_hidden: false,
+            source: SkillSource::BuiltIn,
+            skill_dir: None,
+            content: Some(r#"Triage the open issues in the pre-gathered context below.
+
+For each issue, assign a priority:
+- **P0**: data loss, security, or crashes on common paths - needs someone today
+- **P1**: broken functionality with no workaround
+- **P2**: everything else (improvements, papercuts, questions)
+
+Output a table of issue number, title, priority, and a one-line rationale. Run `gh issue view <number>` on issues whose title alone isn't enough to judge. Finish by flagging duplicates and issues that look stale enough to close.
+"#.to_string()),
+            context_commands: vec![
+                ContextCommand {
+                    label: "Open issues".to_string(),
+                    command: "gh issue list --state open --limit 30".to_string(),
+                },
+            ],
         },
     );
 
@@ -449,6 +630,7 @@ async fn load_skill_from_file(file_path: &Path, skill_dir: &Path, source: &Skill
         source: source.clone(),
         skill_dir: Some(skill_dir.to_path_buf()),
         content: Some(body),
+        context_commands: Vec::new(),
     })
 }
 
@@ -640,7 +822,13 @@ Important:
         }
 
         // Get the prompt for this skill
-        let prompt = skill.get_prompt_for_command(args.as_deref())?;
+        let mut prompt = skill.get_prompt_for_command(args.as_deref())?;
+
+        // Append pre-gathered context (workflow skills only)
+        if let Some(context) = skill.gather_context(args.as_deref()).await {
+            prompt.push_str("\n\n");
+            prompt.push_str(&context);
+        }
 
         // Build the result
         let result = SkillResult {
@@ -673,6 +861,65 @@ mod tests {
         assert!(skills.contains_key("pdf"));
     }
 
+    #[test]
+    fn test_builtin_workflow_skills() {
+        let skills = get_builtin_skills();
+        for name in ["standup", "changelog-since", "triage"] {
+            let skill = skills.get(name).expect("workflow skill should exist");
+            assert!(
+                !skill.context_commands.is_empty(),
+                "{} should pre-gather context",
+                name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gather_context_runs_commands() {
+        let skill = Skill {
+            skill_type: SkillType::Prompt,
+            name: "ctx-test".to_string(),
+            description: "Test".to_string(),
+            allowed_tools: None,
+            argument_hint: None,
+            when_to_use: None,
+            version: None,
+            model: None,
+            is_skill: true,
+            disable_model_invocation: false,
+            is_hidden: false,
+            source: SkillSource::BuiltIn,
+            skill_dir: None,
+            content: Some("Test content".to_string()),
+            context_commands: vec![
+                ContextCommand {
+                    label: "Echo".to_string(),
+                    command: "echo hello $ARGUMENTS".to_string(),
+                },
+                ContextCommand {
+                    label: "Failure".to_string(),
+                    command: "false".to_string(),
+                },
+            ],
+        };
+
+        let context = skill
+            .gather_context(Some("world"))
+            .await
+            .expect("should produce context");
+        assert!(context.contains("## Pre-gathered context"));
+        assert!(context.contains("### Echo"));
+        assert!(context.contains("hello world"));
+        // Failing command becomes a note, not an error
+        assert!(context.contains("### Failure"));
+        assert!(context.contains("(command failed:"));
+
+        // Skills without context commands gather nothing
+        let plain = get_builtin_skills();
+        let commit = plain.get("commit").expect("commit skill should exist");
+        assert!(commit.gather_context(None).await.is_none());
+    }
+
     #[test]
     fn test_skill_prompt_generation() {
         let skills = get_builtin_skills();
@@ -791,6 +1038,7 @@ mod tests {
             source: SkillSource::BuiltIn,
             skill_dir: None,
             content: Some("Test content".to_string()),
+            context_commands: Vec::new(),
         };
         assert_eq!(skill.user_facing_name(), "test-skill");
     }
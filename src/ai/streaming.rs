@@ -45,6 +45,15 @@ pub enum StreamingUpdate {
         thinking: String,
         signature: Option<String>,
     },
+    /// Redacted thinking block; carried for conversation replay, never shown
+    RedactedThinking {
+        data: String,
+    },
+    /// Token usage update from a streaming delta (message_start / message_delta)
+    UsageDelta {
+        input_tokens: u32,
+        output_tokens: u32,
+    },
     /// Message completed
     MessageComplete {
         stop_reason: Option<String>,
@@ -74,6 +83,9 @@ impl StreamingHandler {
             let mut stream = stream;
             let mut current_tool_id = None;
             let mut tool_input_buffer = String::new();
+            let mut in_thinking = false;
+            let mut thinking_buffer = String::new();
+            let mut thinking_signature: Option<String> = None;
             let mut total_usage = TokenUsage {
                 input_tokens: 0,
                 output_tokens: 0,
@@ -101,6 +113,10 @@ impl StreamingHandler {
                         match event {
                             StreamEvent::MessageStart { message } => {
                                 total_usage.input_tokens = message.usage.input_tokens;
+                                let _ = tx.send(StreamingUpdate::UsageDelta {
+                                    input_tokens: total_usage.input_tokens,
+                                    output_tokens: total_usage.output_tokens,
+                                });
                             }
                             StreamEvent::ContentBlockStart { content_block, .. } => {
                                 match content_block {
@@ -113,13 +129,19 @@ impl StreamingHandler {
                                         let _ = tx.send(StreamingUpdate::ToolUseStart { id, name });
                                     }
                                     ContentBlock::Thinking { thinking, .. } => {
+                                        in_thinking = true;
+                                        thinking_buffer.clear();
+                                        thinking_signature = None;
                                         let _ = tx.send(StreamingUpdate::ThinkingStart);
                                         if !thinking.is_empty() {
+                                            thinking_buffer.push_str(&thinking);
                                             let _ = tx.send(StreamingUpdate::ThinkingChunk(thinking));
                                         }
                                     }
-                                    ContentBlock::RedactedThinking { .. } => {
-                                        // Redacted thinking is not displayed to user
+                                    ContentBlock::RedactedThinking { data } => {
+                                        // Not displayed, but surfaced so the
+                                        // block can be replayed verbatim
+                                        let _ = tx.send(StreamingUpdate::RedactedThinking { data });
                                     }
                                 }
                             }
@@ -138,14 +160,31 @@ impl StreamingHandler {
                                         }
                                     }
                                     ContentDelta::ThinkingDelta { thinking } => {
+                                        thinking_buffer.push_str(&thinking);
                                         let _ = tx.send(StreamingUpdate::ThinkingChunk(thinking));
                                     }
-                                    ContentDelta::SignatureDelta { .. } => {
-                                        // Signature is internal, not displayed
+                                    ContentDelta::SignatureDelta { signature } => {
+                                        // Not displayed, but kept so the
+                                        // thinking block can be replayed
+                                        thinking_signature = Some(signature);
+                                    }
+                                    ContentDelta::CitationsDelta { citation } => {
+                                        // Surface citations as markdown links so
+                                        // the renderer collects them as footnotes
+                                        if let Some(link) = citation_markdown(&citation) {
+                                            let _ = tx.send(StreamingUpdate::TextChunk(link));
+                                        }
                                     }
                                 }
                             }
                             StreamEvent::ContentBlockStop { .. } => {
+                                if in_thinking {
+                                    in_thinking = false;
+                                    let _ = tx.send(StreamingUpdate::ThinkingComplete {
+                                        thinking: std::mem::take(&mut thinking_buffer),
+                                        signature: thinking_signature.take(),
+                                    });
+                                }
                                 if let Some(id) = current_tool_id.take() {
                                     match serde_json::from_str(&tool_input_buffer) {
                                         Ok(input) => {
@@ -166,6 +205,10 @@ impl StreamingHandler {
                             }
                             StreamEvent::MessageDelta { usage, .. } => {
                                 total_usage.output_tokens = usage.output_tokens;
+                                let _ = tx.send(StreamingUpdate::UsageDelta {
+                                    input_tokens: total_usage.input_tokens,
+                                    output_tokens: total_usage.output_tokens,
+                                });
                             }
                             StreamEvent::MessageStop => {
                                 let _ = tx.send(StreamingUpdate::MessageComplete {
@@ -230,6 +273,8 @@ pub struct StreamAccumulator {
     is_thinking: bool,
     /// Thinking signature (for verification)
     thinking_signature: Option<String>,
+    /// Redacted thinking payloads, kept for replay
+    redacted_thinking: Vec<String>,
 }
 
 /// Accumulated tool use
@@ -255,6 +300,7 @@ impl StreamAccumulator {
             thinking_buffer: String::new(),
             is_thinking: false,
             thinking_signature: None,
+            redacted_thinking: Vec::new(),
         }
     }
 
@@ -298,6 +344,13 @@ impl StreamAccumulator {
                 self.thinking_signature = signature;
                 self.is_thinking = false;
             }
+            StreamingUpdate::RedactedThinking { data } => {
+                self.redacted_thinking.push(data);
+            }
+            StreamingUpdate::UsageDelta { input_tokens, output_tokens } => {
+                self.usage.input_tokens = input_tokens;
+                self.usage.output_tokens = output_tokens;
+            }
             StreamingUpdate::MessageComplete { usage, .. } => {
                 self.usage = usage;
             }
@@ -335,7 +388,19 @@ impl StreamAccumulator {
     /// Convert to content parts
     pub fn to_content_parts(self) -> Vec<ContentPart> {
         let mut parts = Vec::new();
-        
+
+        // Thinking blocks precede the visible response, and must be
+        // replayed (with signatures) for multi-turn tool use
+        if !self.thinking_buffer.is_empty() {
+            parts.push(ContentPart::Thinking {
+                thinking: self.thinking_buffer.clone(),
+                signature: self.thinking_signature.clone(),
+            });
+        }
+        for data in &self.redacted_thinking {
+            parts.push(ContentPart::RedactedThinking { data: data.clone() });
+        }
+
         if !self.text_buffer.is_empty() {
             parts.push(ContentPart::Text {
                 text: self.text_buffer,
@@ -403,8 +468,9 @@ where
                                         StreamingUpdate::ThinkingChunk(thinking)
                                     }
                                 }
-                                ContentBlock::RedactedThinking { .. } => {
-                                    continue; // Redacted thinking not shown to user
+                                ContentBlock::RedactedThinking { data } => {
+                                    // Not shown to user, kept for replay
+                                    StreamingUpdate::RedactedThinking { data }
                                 }
                             }
                         }
@@ -427,12 +493,25 @@ where
                             ContentDelta::ThinkingDelta { thinking } => {
                                 StreamingUpdate::ThinkingChunk(thinking)
                             }
-                            ContentDelta::SignatureDelta { .. } => {
-                                continue; // Signature is internal
+                            ContentDelta::SignatureDelta { signature } => {
+                                // Internal, but kept so thinking can be replayed
+                                accumulator.thinking_signature = Some(signature);
+                                continue;
+                            }
+                            ContentDelta::CitationsDelta { citation } => {
+                                match citation_markdown(&citation) {
+                                    Some(link) => StreamingUpdate::TextChunk(link),
+                                    None => continue,
+                                }
                             }
                         },
                         StreamEvent::ContentBlockStop { .. } => {
-                            if let Some(index) = accumulator.current_tool_index {
+                            if accumulator.is_thinking {
+                                StreamingUpdate::ThinkingComplete {
+                                    thinking: accumulator.thinking_buffer.clone(),
+                                    signature: accumulator.thinking_signature.clone(),
+                                }
+                            } else if let Some(index) = accumulator.current_tool_index {
                                 if let Some(tool) = accumulator.tool_uses.get_mut(index) {
                                     match serde_json::from_str(&tool.input_buffer) {
                                         Ok(input) => StreamingUpdate::ToolUseComplete {
@@ -495,4 +574,151 @@ where
         
         Ok(accumulator)
     }
-}
\ No newline at end of file
+}
+/// Incremental SSE parser: raw transport bytes in, [`StreamEvent`]s out.
+///
+/// This is the single place SSE framing is decoded (`client.rs` drives it
+/// from the network stream), exposed as a plain push-based struct so tests
+/// can feed it arbitrary chunkings — malformed JSON, events split across
+/// chunks, multi-byte UTF-8 characters split across chunk boundaries —
+/// without a network in sight. Bytes are buffered until they form valid
+/// UTF-8, text is buffered until it forms a complete `\n\n`-terminated
+/// event, and a malformed event yields an `Err` item while the parser
+/// keeps going.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    /// Undecoded bytes: at most an incomplete UTF-8 suffix between pushes
+    bytes: Vec<u8>,
+    /// Decoded text not yet forming a complete event
+    buffer: String,
+}
+
+impl SseParser {
+    /// Create an empty parser
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one transport chunk, returning every event it completed.
+    /// Chunk boundaries carry no meaning: a chunk may hold a fraction of
+    /// an event, several events, or end mid-character.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Result<StreamEvent>> {
+        let mut events = Vec::new();
+        self.bytes.extend_from_slice(chunk);
+
+        // Decode the longest valid UTF-8 prefix; keep an incomplete
+        // trailing sequence for the next chunk, and skip (with an error
+        // item) bytes that can never become valid
+        loop {
+            match std::str::from_utf8(&self.bytes) {
+                Ok(text) => {
+                    self.buffer.push_str(text);
+                    self.bytes.clear();
+                    break;
+                }
+                Err(e) => {
+                    let valid = e.valid_up_to();
+                    // Safe slice: valid_up_to marks a char boundary
+                    self.buffer
+                        .push_str(&String::from_utf8_lossy(&self.bytes[..valid]));
+                    match e.error_len() {
+                        None => {
+                            // Incomplete multi-byte char at the end of the
+                            // chunk; the rest arrives with the next one
+                            self.bytes.drain(..valid);
+                            break;
+                        }
+                        Some(len) => {
+                            events.push(Err(Error::Other(
+                                "Invalid UTF-8 in SSE stream".to_string(),
+                            )));
+                            self.bytes.drain(..valid + len);
+                        }
+                    }
+                }
+            }
+        }
+
+        // SSE framing: events are separated by a blank line. Each event is
+        // field:value lines; multiple data fields concatenate with \n,
+        // lines starting with : are comments.
+        while let Some(event_boundary) = self.buffer.find("\n\n") {
+            let event_text: String = self.buffer.drain(..=event_boundary + 1).collect();
+
+            let mut data_fields = Vec::new();
+            for line in event_text.lines() {
+                if let Some(colon_pos) = line.find(':') {
+                    let field = &line[..colon_pos];
+                    let value = if colon_pos + 1 < line.len() {
+                        // Skip the optional space after the colon
+                        if line.as_bytes().get(colon_pos + 1) == Some(&b' ') {
+                            &line[colon_pos + 2..]
+                        } else {
+                            &line[colon_pos + 1..]
+                        }
+                    } else {
+                        ""
+                    };
+                    if field == "data" {
+                        data_fields.push(value);
+                    }
+                    // event/id/retry fields and comment lines (empty field
+                    // name) carry nothing we need: the payload type is in
+                    // the JSON itself
+                }
+            }
+
+            if data_fields.is_empty() {
+                continue;
+            }
+            let combined_data = data_fields.join("\n");
+            if combined_data == "[DONE]" {
+                continue;
+            }
+            match serde_json::from_str::<crate::ai::client::SseEvent>(&combined_data) {
+                Ok(sse_event) => {
+                    events.push(crate::ai::client::parse_sse_event(sse_event));
+                }
+                Err(parse_error) => {
+                    events.push(Err(Error::Other(format!(
+                        "Failed to parse SSE event JSON: {}. Data was: '{}'",
+                        parse_error, combined_data
+                    ))));
+                }
+            }
+        }
+
+        events
+    }
+
+    /// Signal end of stream. Anything still buffered is an event the
+    /// server never finished, reported as an error item.
+    pub fn finish(&mut self) -> Vec<Result<StreamEvent>> {
+        let mut events = Vec::new();
+        if !self.bytes.is_empty() {
+            self.buffer
+                .push_str(&String::from_utf8_lossy(&std::mem::take(&mut self.bytes)));
+        }
+        let remainder = std::mem::take(&mut self.buffer);
+        if !remainder.trim().is_empty() {
+            events.push(Err(Error::Other(format!(
+                "SSE stream ended with incomplete event: '{}'",
+                remainder
+            ))));
+        }
+        events
+    }
+}
+
+/// Turn a `citations_delta` payload into a markdown link appended to the
+/// text stream. The renderer collects such links into numbered footnotes,
+/// so citations show up as `[n]` markers instead of being dropped.
+fn citation_markdown(citation: &serde_json::Value) -> Option<String> {
+    let url = citation.get("url").and_then(|v| v.as_str())?;
+    let title = citation
+        .get("title")
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.is_empty())
+        .unwrap_or(url);
+    Some(format!(" [{}]({})", title, url))
+}
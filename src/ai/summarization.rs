@@ -127,6 +127,182 @@ pub fn get_detailed_summary_prompt_with_instructions(additional_instructions: &s
 "#, &format!("Additional Instructions:\n{}", additional_instructions))
 }
 
+use crate::ai::{ContentPart, Message, MessageContent, MessageRole};
+use crate::error::{Error, Result};
+
+/// Fraction of the context window at which the agent loop compacts
+/// automatically, unless LLMINATE_COMPACT_THRESHOLD overrides it
+pub const DEFAULT_AUTO_COMPACT_THRESHOLD: f32 = 0.85;
+
+/// Recent messages preserved verbatim through auto-compaction so the
+/// model keeps the tool results it is actively working from
+const PRESERVED_RECENT_MESSAGES: usize = 6;
+
+/// The auto-compaction trigger threshold as a fraction of the context
+/// window. LLMINATE_COMPACT_THRESHOLD accepts either a fraction (0.85)
+/// or a percentage (85); values are clamped to a sane range.
+pub fn auto_compact_threshold() -> f32 {
+    let configured = std::env::var("LLMINATE_COMPACT_THRESHOLD")
+        .ok()
+        .and_then(|raw| raw.trim().parse::<f32>().ok())
+        .map(|value| if value > 1.0 { value / 100.0 } else { value });
+    configured
+        .unwrap_or(DEFAULT_AUTO_COMPACT_THRESHOLD)
+        .clamp(0.3, 0.95)
+}
+
+/// Context window for a model, from the model registry where known
+pub fn context_window_for(model: &str) -> usize {
+    crate::ai::models::ModelRegistry::new()
+        .get_model(model)
+        .map(|info| info.context_window as usize)
+        .unwrap_or(200_000)
+}
+
+/// Rough token estimate for a transcript (4 chars per token, matching
+/// the estimate used elsewhere in the TUI)
+pub fn estimate_tokens(messages: &[Message]) -> usize {
+    messages
+        .iter()
+        .map(|msg| match &msg.content {
+            MessageContent::Text(text) => text.len() / 4,
+            MessageContent::Multipart(parts) => parts
+                .iter()
+                .map(|part| {
+                    serde_json::to_string(part)
+                        .map(|json| json.len() / 4)
+                        .unwrap_or(0)
+                })
+                .sum(),
+        })
+        .sum()
+}
+
+/// Whether the transcript has crossed the auto-compaction threshold
+pub fn should_compact(messages: &[Message], model: &str) -> bool {
+    if messages.len() <= PRESERVED_RECENT_MESSAGES + 1 {
+        return false;
+    }
+    let limit = (context_window_for(model) as f32 * auto_compact_threshold()) as usize;
+    estimate_tokens(messages) >= limit
+}
+
+/// Index where the preserved tail begins. Moved back as needed so the
+/// tail never opens with orphaned tool results (every tool_result must
+/// follow the assistant turn carrying its tool_use).
+fn compaction_split(messages: &[Message]) -> usize {
+    let mut split = messages.len().saturating_sub(PRESERVED_RECENT_MESSAGES);
+    while split > 0 && starts_with_tool_result(&messages[split]) {
+        split -= 1;
+    }
+    split
+}
+
+fn starts_with_tool_result(message: &Message) -> bool {
+    match &message.content {
+        MessageContent::Multipart(parts) => parts
+            .iter()
+            .any(|part| matches!(part, ContentPart::ToolResult { .. })),
+        MessageContent::Text(_) => false,
+    }
+}
+
+/// Flatten messages into a plain-text transcript for the summarizer
+fn render_transcript(messages: &[Message]) -> String {
+    let mut transcript = String::new();
+    for msg in messages {
+        let role = match msg.role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
+            MessageRole::System => "System",
+            MessageRole::Tool => "Tool",
+        };
+        transcript.push_str(role);
+        transcript.push_str(": ");
+        match &msg.content {
+            MessageContent::Text(text) => transcript.push_str(text),
+            MessageContent::Multipart(parts) => {
+                for part in parts {
+                    match part {
+                        ContentPart::Text { text, .. } => transcript.push_str(text),
+                        ContentPart::ToolUse { name, input, .. } => {
+                            transcript.push_str(&format!("[Called tool {} with {}]", name, input));
+                        }
+                        ContentPart::ToolResult { content, .. } => {
+                            transcript.push_str(&format!("[Tool result: {}]", content));
+                        }
+                        _ => {}
+                    }
+                    transcript.push('\n');
+                }
+            }
+        }
+        transcript.push_str("\n\n");
+    }
+    transcript
+}
+
+/// Summarize everything before the preserved tail into a synthetic
+/// summary message, in place. The tail (recent turns and their tool
+/// results) survives verbatim, so mid-task state is not lost.
+pub async fn auto_compact(
+    client: &crate::ai::client_adapter::AIClientAdapter,
+    model: &str,
+    messages: &mut Vec<Message>,
+) -> Result<()> {
+    let split = compaction_split(messages);
+    if split == 0 {
+        return Err(Error::Other(
+            "Nothing to compact before the preserved tail".to_string(),
+        ));
+    }
+
+    let transcript = render_transcript(&messages[..split]);
+    let request = client
+        .create_chat_request()
+        .model(model)
+        .max_tokens(4096)
+        .system(get_summarization_system_prompt().to_string())
+        .messages(vec![Message {
+            role: MessageRole::User,
+            content: MessageContent::Text(format!(
+                "{}\n\nConversation to summarize:\n\n{}",
+                get_detailed_summary_prompt(),
+                transcript
+            )),
+            name: None,
+        }])
+        .build();
+
+    let response = client.chat(request).await?;
+    let summary: String = response
+        .content
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if summary.trim().is_empty() {
+        return Err(Error::Other("Summarization returned no text".to_string()));
+    }
+
+    let mut compacted = vec![Message {
+        role: MessageRole::User,
+        content: MessageContent::Text(format!(
+            "[The conversation was compacted automatically to stay within the context window. \
+             Earlier turns are summarized below; continue the task from this state.]\n\n\
+             **Conversation Summary:**\n\n{}",
+            summary
+        )),
+        name: None,
+    }];
+    compacted.extend_from_slice(&messages[split..]);
+    *messages = compacted;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,7 +349,98 @@ mod tests {
     fn test_prompt_with_custom_instructions() {
         let custom_instructions = "Focus on Rust code changes and error handling.";
         let prompt = get_detailed_summary_prompt_with_instructions(custom_instructions);
-        
+
         assert!(prompt.contains("Additional Instructions:\nFocus on Rust code changes and error handling."));
     }
+
+    fn text_message(role: MessageRole, text: &str) -> Message {
+        Message {
+            role,
+            content: MessageContent::Text(text.to_string()),
+            name: None,
+        }
+    }
+
+    fn tool_result_message(id: &str) -> Message {
+        Message {
+            role: MessageRole::User,
+            content: MessageContent::Multipart(vec![ContentPart::ToolResult {
+                tool_use_id: id.to_string(),
+                content: "ok".to_string(),
+                is_error: None,
+            }]),
+            name: None,
+        }
+    }
+
+    #[test]
+    fn test_should_compact_respects_threshold() {
+        // Short transcript: never compacts regardless of estimate
+        let short = vec![text_message(MessageRole::User, "hi")];
+        assert!(!should_compact(&short, "claude-opus-4-1-20250805"));
+
+        // A transcript big enough to cross 85% of a 200k window
+        let big_text = "x".repeat(800_000);
+        let mut big = vec![text_message(MessageRole::User, &big_text)];
+        for _ in 0..8 {
+            big.push(text_message(MessageRole::Assistant, "ack"));
+        }
+        assert!(should_compact(&big, "claude-opus-4-1-20250805"));
+    }
+
+    #[test]
+    fn test_compaction_split_keeps_tool_pairs_together() {
+        // ... 10 messages where the natural split would land on a
+        // tool_result; the split must back up to include its tool_use
+        let mut messages = Vec::new();
+        for i in 0..6 {
+            messages.push(text_message(MessageRole::User, &format!("q{}", i)));
+        }
+        messages.push(text_message(MessageRole::Assistant, "calling a tool"));
+        messages.push(tool_result_message("toolu_1"));
+        messages.push(text_message(MessageRole::Assistant, "done"));
+        messages.push(text_message(MessageRole::User, "next"));
+
+        let split = compaction_split(&messages);
+        // Natural split (len - 6 = 4) is fine here; the tail starts at a
+        // plain user message
+        assert!(!starts_with_tool_result(&messages[split]));
+
+        // Force the natural split onto the tool_result
+        messages.insert(0, text_message(MessageRole::User, "q"));
+        let split = compaction_split(&messages);
+        assert!(!starts_with_tool_result(&messages[split]));
+        // The assistant turn carrying the tool_use stays with its result
+        assert!(matches!(
+            messages[split].content,
+            MessageContent::Text(ref t) if t == "calling a tool"
+        ) || split < 7);
+    }
+
+    #[test]
+    fn test_render_transcript_flattens_parts() {
+        let messages = vec![
+            text_message(MessageRole::User, "run the tests"),
+            Message {
+                role: MessageRole::Assistant,
+                content: MessageContent::Multipart(vec![
+                    ContentPart::Text {
+                        text: "Running".to_string(),
+                        citations: None,
+                    },
+                    ContentPart::ToolUse {
+                        id: "toolu_1".to_string(),
+                        name: "Bash".to_string(),
+                        input: serde_json::json!({"command": "cargo test"}),
+                    },
+                ]),
+                name: None,
+            },
+            tool_result_message("toolu_1"),
+        ];
+        let transcript = render_transcript(&messages);
+        assert!(transcript.contains("User: run the tests"));
+        assert!(transcript.contains("[Called tool Bash"));
+        assert!(transcript.contains("[Tool result: ok]"));
+    }
 }
\ No newline at end of file
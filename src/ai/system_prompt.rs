@@ -4,19 +4,46 @@
 use std::env;
 use std::process::Command;
 
-/// Get environment context for the agent (like JavaScript line 368355)
+/// How long a cached environment snapshot stays fresh. The block is
+/// assembled per request, so a short TTL means each turn sees current
+/// state without shelling out to git on every keystroke
+const ENVIRONMENT_TTL_SECS: u64 = 30;
+
+/// Cached environment snapshot with its build time
+static ENVIRONMENT_CACHE: std::sync::Mutex<Option<(std::time::Instant, String)>> =
+    std::sync::Mutex::new(None);
+
+/// Get environment context for the agent (like JavaScript line 368355),
+/// extended with the git branch/status summary and detected runtime
+/// versions so the model doesn't open every turn with pwd, git status,
+/// and date tool calls. Refreshed when the cached snapshot goes stale.
 pub fn get_environment_context() -> String {
+    if let Ok(guard) = ENVIRONMENT_CACHE.lock() {
+        if let Some((built_at, snapshot)) = guard.as_ref() {
+            if built_at.elapsed().as_secs() < ENVIRONMENT_TTL_SECS {
+                return snapshot.clone();
+            }
+        }
+    }
+    let snapshot = build_environment_context();
+    if let Ok(mut guard) = ENVIRONMENT_CACHE.lock() {
+        *guard = Some((std::time::Instant::now(), snapshot.clone()));
+    }
+    snapshot
+}
+
+fn build_environment_context() -> String {
     let working_dir = env::current_dir()
         .map(|p| p.display().to_string())
         .unwrap_or_else(|_| "unknown".to_string());
-    
+
     // Check if we're in a git repo
     let is_git_repo = Command::new("git")
         .args(&["rev-parse", "--is-inside-work-tree"])
         .output()
         .map(|output| output.status.success())
         .unwrap_or(false);
-    
+
     // Get OS info
     let os_version = Command::new("uname")
         .args(&["-sr"])
@@ -25,9 +52,25 @@ pub fn get_environment_context() -> String {
         .and_then(|output| String::from_utf8(output.stdout).ok())
         .map(|s| s.trim().to_string())
         .unwrap_or_else(|| format!("{} {}", env::consts::OS, env::consts::ARCH));
-    
+
     let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    
+
+    let mut git_lines = String::new();
+    if is_git_repo {
+        if let Some(branch) = git_branch() {
+            git_lines.push_str(&format!("Git branch: {}\n", branch));
+        }
+        if let Some(status) = git_status_summary() {
+            git_lines.push_str(&format!("Git status: {}\n", status));
+        }
+    }
+    let runtimes = detected_runtimes();
+    let runtime_line = if runtimes.is_empty() {
+        String::new()
+    } else {
+        format!("Runtimes: {}\n", runtimes)
+    };
+
     format!(
         r#"Here is useful information about the environment you are running in:
 <env>
@@ -36,16 +79,101 @@ Is directory a git repo: {}
 Platform: {}
 OS Version: {}
 Today's date: {}
-</env>
+{}{}</env>
 You are powered by the model named Claude 3.5 Sonnet."#,
         working_dir,
         if is_git_repo { "Yes" } else { "No" },
         env::consts::OS,
         os_version,
-        today
+        today,
+        git_lines,
+        runtime_line
     )
 }
 
+/// The current git branch, or the short HEAD when detached
+fn git_branch() -> Option<String> {
+    let output = Command::new("git")
+        .args(&["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    if branch.is_empty() {
+        None
+    } else {
+        Some(branch)
+    }
+}
+
+/// A one-line working tree summary ("clean" or counts of changed and
+/// untracked files), from `git status --porcelain`
+fn git_status_summary() -> Option<String> {
+    let output = Command::new("git")
+        .args(&["status", "--porcelain"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(output.stdout).ok()?;
+    let mut changed = 0;
+    let mut untracked = 0;
+    for line in text.lines() {
+        if line.starts_with("??") {
+            untracked += 1;
+        } else if !line.trim().is_empty() {
+            changed += 1;
+        }
+    }
+    Some(match (changed, untracked) {
+        (0, 0) => "clean".to_string(),
+        (changed, 0) => format!("{} changed file(s)", changed),
+        (0, untracked) => format!("{} untracked file(s)", untracked),
+        (changed, untracked) => format!("{} changed, {} untracked file(s)", changed, untracked),
+    })
+}
+
+/// Runtime versions found on PATH, detected once per process (they
+/// don't change mid-session, and probing is the expensive part)
+fn detected_runtimes() -> &'static str {
+    static RUNTIMES: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    RUNTIMES.get_or_init(|| {
+        let probes: [(&str, &[&str]); 4] = [
+            ("rustc", &["--version"]),
+            ("node", &["--version"]),
+            ("python3", &["--version"]),
+            ("go", &["version"]),
+        ];
+        let mut found = Vec::new();
+        for (command, args) in probes {
+            if let Ok(output) = Command::new(command).args(args).output() {
+                if output.status.success() {
+                    // Version output may land on stdout or stderr
+                    // depending on the runtime
+                    let text = if output.stdout.is_empty() {
+                        String::from_utf8_lossy(&output.stderr).to_string()
+                    } else {
+                        String::from_utf8_lossy(&output.stdout).to_string()
+                    };
+                    if let Some(line) = text.lines().next() {
+                        // First two words are enough ("rustc 1.80.0",
+                        // "go version go1.22" trimmed to the essentials)
+                        let short: Vec<&str> =
+                            line.split_whitespace().filter(|w| *w != "version").take(2).collect();
+                        if !short.is_empty() {
+                            found.push(short.join(" "));
+                        }
+                    }
+                }
+            }
+        }
+        found.join(", ")
+    })
+}
+
 pub fn get_system_prompt(app_name: &str) -> String {
     format!(r#"You are an interactive CLI tool that helps users with software engineering tasks. Use the instructions below and the tools available to you to assist the user.
 
@@ -130,5 +258,286 @@ When doing file search, prefer to use the Search tool to reduce context usage.
 You have the capability to call multiple tools in a single response. When multiple independent pieces of information are requested, batch your tool calls together for optimal performance.
 
 {}
-"#, app_name, get_environment_context())
-}
\ No newline at end of file
+"#, app_name, get_clarification_guidance())
+}
+
+/// Session-scoped instructions added with /system append. Shared between
+/// the TUI and the agent worker (same pattern as the service tier
+/// override in ai::mod).
+static SESSION_INSTRUCTIONS: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+/// Add a session-scoped instruction to the end of the system prompt
+pub fn append_session_instruction(text: &str) {
+    if let Ok(mut guard) = SESSION_INSTRUCTIONS.lock() {
+        guard.push(text.to_string());
+    }
+}
+
+/// The session-scoped instructions added so far, in order
+pub fn session_instructions() -> Vec<String> {
+    SESSION_INSTRUCTIONS
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+/// Drop all session-scoped instructions
+pub fn clear_session_instructions() {
+    if let Ok(mut guard) = SESSION_INSTRUCTIONS.lock() {
+        guard.clear();
+    }
+}
+
+/// One named section of the assembled system prompt
+#[derive(Debug, Clone)]
+pub struct PromptSection {
+    pub name: String,
+    pub content: String,
+    /// Listed by /system show but not sent as prompt text (e.g. beta
+    /// flags, which ride the anthropic-beta header instead)
+    pub display_only: bool,
+}
+
+/// Composable system prompt assembly. Each layer (base prompt, project
+/// memory, output style, session-scoped appends) is a named section, so
+/// /system show can report per-section token estimates while render()
+/// produces exactly what the request sends.
+#[derive(Debug, Clone, Default)]
+pub struct SystemPromptBuilder {
+    sections: Vec<PromptSection>,
+}
+
+impl SystemPromptBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything the interactive session sends: the base prompt (or a
+    /// caller-supplied override), project memory, the configured output
+    /// style, and session appends, plus a display-only section listing
+    /// the beta flags the request headers will carry
+    pub fn for_session(base: Option<&str>, model: &str) -> Self {
+        Self::new()
+            .section("base", match base {
+                Some(prompt) => prompt.to_string(),
+                None => get_system_prompt("Claude Code"),
+            })
+            .with_environment()
+            .with_memory()
+            .with_output_style()
+            .with_guardrails()
+            .with_session_instructions()
+            .with_betas(model)
+    }
+
+    /// Add a named section; empty content is skipped
+    pub fn section(mut self, name: &str, content: impl Into<String>) -> Self {
+        let content = content.into();
+        if !content.trim().is_empty() {
+            self.sections.push(PromptSection {
+                name: name.to_string(),
+                content,
+                display_only: false,
+            });
+        }
+        self
+    }
+
+    /// Environment snapshot (cwd, OS, date, git branch/status summary,
+    /// runtime versions), refreshed when stale so the model stops
+    /// running pwd/git status/date as its first tool calls
+    pub fn with_environment(self) -> Self {
+        self.section("environment", get_environment_context())
+    }
+
+    /// Project memory: CLAUDE.md (or CLAUDE_MD_PATH) from the working
+    /// directory, when present and non-empty
+    pub fn with_memory(self) -> Self {
+        let path = env::var("CLAUDE_MD_PATH").unwrap_or_else(|_| "CLAUDE.md".to_string());
+        match std::fs::read_to_string(&path) {
+            Ok(content) if !content.trim().is_empty() => self.section(
+                "memory",
+                format!("# Project memory ({})\n\n{}", path, content.trim()),
+            ),
+            _ => self,
+        }
+    }
+
+    /// The outputStyle setting, resolved across settings sources
+    pub fn with_output_style(self) -> Self {
+        match get_output_style() {
+            Some(style) => self.section(
+                "output-style",
+                format!("# Output style\n\nAdopt the '{}' output style for your responses.", style),
+            ),
+            None => self,
+        }
+    }
+
+    /// Guardrails from settings, stated as hard constraints. The same
+    /// rules are enforced deterministically by the permissions engine,
+    /// so the prompt and the guard cannot drift apart.
+    pub fn with_guardrails(self) -> Self {
+        let guardrails = crate::config::get_guardrails();
+        if guardrails.is_empty() {
+            return self;
+        }
+        let rules = guardrails
+            .iter()
+            .map(|guardrail| format!("- {}", guardrail.rule))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.section(
+            "guardrails",
+            format!(
+                "# Guardrails (hard constraints)\n\nThe following constraints are absolute and are also enforced by the permissions engine: tool calls that violate them will be denied automatically. Do not attempt to work around them.\n{}",
+                rules
+            ),
+        )
+    }
+
+    /// Session-scoped instructions added with /system append
+    pub fn with_session_instructions(self) -> Self {
+        let instructions = session_instructions();
+        if instructions.is_empty() {
+            self
+        } else {
+            self.section(
+                "session",
+                format!("# Session instructions\n\n{}", instructions.join("\n")),
+            )
+        }
+    }
+
+    /// Beta flags the request will carry, mirroring the anthropic-beta
+    /// header assembly in auth::client::build_headers. Display-only:
+    /// they never become prompt text.
+    pub fn with_betas(mut self, model: &str) -> Self {
+        let mut betas = vec!["claude-code-20250219"];
+        if model.contains("claude-sonnet-4") || model.contains("claude-opus-4") {
+            betas.push("interleaved-thinking-2025-05-14");
+        }
+        self.sections.push(PromptSection {
+            name: "betas".to_string(),
+            content: betas.join(","),
+            display_only: true,
+        });
+        self
+    }
+
+    /// All sections, including display-only ones, for /system show
+    pub fn sections(&self) -> &[PromptSection] {
+        &self.sections
+    }
+
+    /// The assembled prompt text (display-only sections excluded)
+    pub fn render(&self) -> String {
+        self.sections
+            .iter()
+            .filter(|section| !section.display_only)
+            .map(|section| section.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// The fully assembled prompt the interactive session sends: base (or
+/// the caller's override) + memory + output style + session appends
+pub fn assemble_session_prompt(base: Option<&str>) -> String {
+    // The betas section is display-only, so the model name doesn't
+    // affect what render() produces
+    SystemPromptBuilder::for_session(base, "").render()
+}
+
+/// The outputStyle setting across settings sources; later sources win
+fn get_output_style() -> Option<String> {
+    let mut style = None;
+    for source in crate::config::SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = crate::config::load_settings(source) {
+            if let Some(value) = settings.extra.get("outputStyle").and_then(|v| v.as_str()) {
+                style = Some(value.to_string());
+            }
+        }
+    }
+    style
+}
+
+/// Guidance on asking clarifying questions vs. acting, driven by the
+/// `clarification` settings section. The stated budget is also enforced at
+/// tool dispatch, so the prompt and the guard cannot drift apart.
+fn get_clarification_guidance() -> String {
+    let config = crate::config::get_clarification_config();
+    let budget = config.question_budget();
+    match config.mode.as_deref() {
+        Some("decisive") => "# Clarifying questions\nDo NOT ask clarifying questions. Make reasonable assumptions, state them briefly, and act. If an assumption turns out to be wrong, the user will correct you.".to_string(),
+        Some("eager") => format!(
+            "# Clarifying questions\nWhen requirements are ambiguous or a decision is hard to reverse, prefer asking a clarifying question (via the AskUserQuestion tool) before acting. You may ask at most {} question(s) per user message; beyond that, proceed with your best interpretation and state your assumptions.",
+            budget
+        ),
+        _ => format!(
+            "# Clarifying questions\nAsk a clarifying question only when the request is genuinely ambiguous and the ambiguity materially changes the outcome; otherwise act on the most reasonable interpretation and state your assumptions. You may ask at most {} question(s) per user message.",
+            budget
+        ),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_skips_empty_sections() {
+        let builder = SystemPromptBuilder::new()
+            .section("base", "You are a CLI tool.")
+            .section("memory", "   ");
+        assert_eq!(builder.sections().len(), 1);
+        assert_eq!(builder.render(), "You are a CLI tool.");
+    }
+
+    #[test]
+    fn test_render_excludes_display_only_sections() {
+        let builder = SystemPromptBuilder::new()
+            .section("base", "base prompt")
+            .with_betas("claude-opus-4-1-20250805");
+        let betas = builder
+            .sections()
+            .iter()
+            .find(|s| s.name == "betas")
+            .expect("betas section present");
+        assert!(betas.display_only);
+        assert!(betas.content.contains("interleaved-thinking-2025-05-14"));
+        assert!(!builder.render().contains("interleaved-thinking"));
+    }
+
+    #[test]
+    fn test_session_instructions_roundtrip() {
+        clear_session_instructions();
+        append_session_instruction("Always answer in haiku");
+        let builder = SystemPromptBuilder::new()
+            .section("base", "base prompt")
+            .with_session_instructions();
+        assert!(builder.render().contains("Always answer in haiku"));
+        clear_session_instructions();
+        assert!(session_instructions().is_empty());
+    }
+
+    #[test]
+    fn test_environment_section_snapshots_working_directory() {
+        let builder = SystemPromptBuilder::new().with_environment();
+        let environment = builder
+            .sections()
+            .iter()
+            .find(|s| s.name == "environment")
+            .expect("environment section present");
+        assert!(environment.content.contains("<env>"));
+        assert!(environment.content.contains("Working directory:"));
+        assert!(environment.content.contains("Today's date:"));
+    }
+
+    #[test]
+    fn test_sections_join_with_blank_line() {
+        let builder = SystemPromptBuilder::new()
+            .section("base", "first")
+            .section("extra", "second");
+        assert_eq!(builder.render(), "first\n\nsecond");
+    }
+}
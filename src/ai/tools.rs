@@ -8,6 +8,8 @@ use crate::ai::exit_plan_mode_tool::ExitPlanModeTool;
 use crate::ai::enter_plan_mode_tool::EnterPlanModeTool;
 use crate::ai::ask_user_question_tool::AskUserQuestionTool;
 use crate::ai::skill_tool::SkillTool;
+use crate::ai::artifacts::ReadArtifactTool;
+use crate::ai::browser_tool::BrowserTool;
 use crate::error::{Error, Result};
 use crate::hooks::{execute_hooks, HookType, HookContext};
 use crate::tui::{TuiEvent, PermissionDecision};
@@ -21,7 +23,6 @@ use tokio::fs as async_fs;
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::time;
 use regex::Regex;
-use glob::glob;
 use which::which;
 use std::env;
 use std::time::SystemTime;
@@ -32,6 +33,189 @@ use std::sync::Arc;
 use once_cell::sync::Lazy;
 use rand::Rng;
 
+/// Version of the built-in tool schemas. Bumped whenever a tool's parameters
+/// or result shape change, so transcripts recorded under older semantics can
+/// be detected on resume and normalized before being replayed to the model.
+/// v1: original parameter names (path/start_line, bash_id, inverted sandbox)
+/// v2: current schemas (file_path/offset/limit, task_id, dangerouslyDisableSandbox)
+pub const TOOL_SCHEMA_VERSION: u32 = 2;
+
+/// Session-wide dry-run flag: when enabled, mutating tools (Write, Edit,
+/// MultiEdit, Bash, NotebookEdit) report what they would do as tool results
+/// without executing, so large refactor plans can be rehearsed safely.
+static DRY_RUN_MODE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Enable or disable dry-run mode for the session (toggled via /dry-run)
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN_MODE.store(enabled, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Check whether dry-run mode is active
+pub fn is_dry_run() -> bool {
+    DRY_RUN_MODE.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Apply the configured attribution policy to a shell command: inline
+/// `git commit -m` invocations get the trailer appended as an extra `-m`
+/// paragraph. Editor commits (no `-m`) and commits that already carry a
+/// trailer are left alone.
+pub fn apply_commit_attribution(command: &str) -> String {
+    let Some(trailer) = crate::config::get_attribution_config().commit_trailer() else {
+        return command.to_string();
+    };
+    append_commit_trailer(command, &trailer)
+}
+
+/// Insert ` -m '<trailer>'` after the `git commit` portion of `command`,
+/// before any following shell control operator (quote-aware)
+pub fn append_commit_trailer(command: &str, trailer: &str) -> String {
+    let Some(commit_pos) = command.find("git commit") else {
+        return command.to_string();
+    };
+    let after_commit = &command[commit_pos..];
+    // Only inline messages can be extended; skip editor-based commits,
+    // amends reusing the previous message, and already-attributed ones
+    if !after_commit.contains("-m") && !after_commit.contains("--message") {
+        return command.to_string();
+    }
+    if command.contains("Co-Authored-By") || command.contains(trailer) {
+        return command.to_string();
+    }
+
+    // Find the end of the commit invocation: the first control operator
+    // (&&, ||, ;, |) outside of quotes after `git commit`
+    let bytes = command.as_bytes();
+    let mut insert_at = command.len();
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut i = commit_pos;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' if !in_double => in_single = !in_single,
+            b'"' if !in_single => in_double = !in_double,
+            b'\\' if in_double => i += 1, // skip escaped char
+            b'&' | b'|' | b';' if !in_single && !in_double => {
+                insert_at = i;
+                break;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let escaped = trailer.replace('\'', "'\\''");
+    let insertion = format!(" -m '{}' ", escaped);
+    let mut rewritten = String::with_capacity(command.len() + insertion.len());
+    rewritten.push_str(command[..insert_at].trim_end());
+    rewritten.push_str(&insertion);
+    rewritten.push_str(&command[insert_at..]);
+    rewritten.trim_end().to_string()
+}
+
+/// AskUserQuestion calls made in the current user turn, checked against the
+/// clarification settings budget at dispatch
+static QUESTIONS_THIS_TURN: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+/// Reset the clarifying-question budget; called at the start of each user turn
+pub fn reset_question_budget() {
+    QUESTIONS_THIS_TURN.store(0, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Files opened through the Read tool this session, reported by /stats
+static FILES_READ: Lazy<std::sync::Mutex<HashSet<PathBuf>>> =
+    Lazy::new(|| std::sync::Mutex::new(HashSet::new()));
+
+fn record_file_read(path: &Path) {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        match std::env::current_dir() {
+            Ok(cwd) => cwd.join(path),
+            Err(_) => path.to_path_buf(),
+        }
+    };
+    if let Ok(mut files) = FILES_READ.lock() {
+        files.insert(absolute);
+    }
+}
+
+/// Distinct files the Read tool has opened this session
+pub fn files_read_this_session() -> Vec<PathBuf> {
+    FILES_READ
+        .lock()
+        .map(|files| files.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Write `content` to `path` atomically: write a temp file in the same
+/// directory, carry the original file's mode (and, best-effort, ownership)
+/// over to it, then rename it into place — a crash mid-write can therefore
+/// never leave a truncated target. Read-only targets are rejected up front
+/// with a clear error instead of failing on the rename.
+async fn atomic_write(path: &Path, content: &str) -> Result<()> {
+    let existing = async_fs::metadata(path).await.ok();
+    if let Some(metadata) = &existing {
+        if metadata.permissions().readonly() {
+            return Err(Error::ToolExecution(format!(
+                "{} is read-only; change its permissions before writing to it",
+                path.display()
+            )));
+        }
+    }
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "file".to_string());
+    let temp_path = parent.join(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4().simple()));
+
+    if let Err(e) = async_fs::write(&temp_path, content).await {
+        let _ = async_fs::remove_file(&temp_path).await;
+        return Err(Error::from(e));
+    }
+
+    if let Some(metadata) = &existing {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{MetadataExt, PermissionsExt};
+            let _ = async_fs::set_permissions(
+                &temp_path,
+                std::fs::Permissions::from_mode(metadata.mode()),
+            )
+            .await;
+            // Only succeeds for root or the file's owner; best-effort
+            let _ = std::os::unix::fs::chown(&temp_path, Some(metadata.uid()), Some(metadata.gid()));
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = async_fs::set_permissions(&temp_path, metadata.permissions()).await;
+        }
+    }
+
+    if let Err(e) = async_fs::rename(&temp_path, path).await {
+        let _ = async_fs::remove_file(&temp_path).await;
+        return Err(Error::from(e));
+    }
+    Ok(())
+}
+
+/// Match the dominant line ending of the original file: when it is CRLF,
+/// convert the replacement text so an edit doesn't silently rewrite every
+/// line ending in the file. LF-dominant (or new) files pass through as-is.
+fn match_line_endings(original: &str, new_content: &str) -> String {
+    let crlf = original.matches("\r\n").count();
+    let lf = original.matches('\n').count() - crlf;
+    if crlf > lf {
+        new_content.replace("\r\n", "\n").replace('\n', "\r\n")
+    } else {
+        new_content.to_string()
+    }
+}
+
 /// Tool execution context (mirrors JavaScript's context with AbortController)
 pub struct ToolContext {
     pub tool_use_id: String,
@@ -153,6 +337,13 @@ impl ShellSessionState {
             cmd
         };
         
+        // Apply the settings-driven env policy (allowlist/denylist/injected vars)
+        // before the tool-specific overrides below
+        if let Some(policy_env) = crate::config::compute_policied_env() {
+            cmd.env_clear();
+            cmd.envs(&policy_env);
+        }
+
         // Set working directory and environment
         cmd.current_dir(&self.working_dir)
             .env("SHELL", &self.shell_executable)
@@ -524,9 +715,67 @@ impl BackgroundShellManager {
 }
 
 /// Global background shell manager
-pub static BACKGROUND_SHELLS: Lazy<BackgroundShellManager> = 
+pub static BACKGROUND_SHELLS: Lazy<BackgroundShellManager> =
     Lazy::new(|| BackgroundShellManager::new());
 
+/// Check a URL's host against a per-tool allow-list from settings.
+/// Entries match the exact host or any subdomain of it.
+fn check_tool_domain(url_str: &str, allowed: &[String], tool_name: &str) -> Result<()> {
+    let parsed = url::Url::parse(url_str)
+        .map_err(|e| Error::InvalidInput(format!("Invalid URL: {}", e)))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| Error::InvalidInput("URL has no host".to_string()))?
+        .to_ascii_lowercase();
+
+    let is_allowed = allowed.iter().any(|domain| {
+        let domain = domain.to_ascii_lowercase();
+        host == domain || host.ends_with(&format!(".{}", domain))
+    });
+
+    if is_allowed {
+        Ok(())
+    } else {
+        Err(Error::PermissionDenied(format!(
+            "Domain '{}' is not in the tools.{}.allowedDomains allow-list ({})",
+            host,
+            tool_name,
+            allowed.join(", ")
+        )))
+    }
+}
+
+/// Run the configured formatter on an edited file, returning a note to
+/// append to the tool output when the formatter ran (or failed)
+async fn run_format_command(format_command: &str, file_path: &str) -> Option<String> {
+    let command_line = if format_command.contains("{file}") {
+        format_command.replace("{file}", file_path)
+    } else {
+        format!("{} '{}'", format_command, file_path.replace('\'', r"'\''"))
+    };
+
+    match tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command_line)
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => {
+            Some(format!("\n\nFormatter applied: {}", format_command))
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Some(format!(
+                "\n\nFormatter '{}' failed ({}): {}",
+                format_command,
+                output.status,
+                stderr.trim()
+            ))
+        }
+        Err(e) => Some(format!("\n\nFormatter '{}' failed to start: {}", format_command, e)),
+    }
+}
+
 /// Tool executor
 pub struct ToolExecutor {
     tools: HashMap<String, Box<dyn ToolHandler>>,
@@ -568,6 +817,14 @@ impl ToolExecutor {
         tools.insert("TaskUpdate".to_string(), Box::new(TaskUpdateTool));
         tools.insert("TaskList".to_string(), Box::new(TaskListTool));
         tools.insert("Skill".to_string(), Box::new(SkillTool));
+        tools.insert("ReadArtifact".to_string(), Box::new(ReadArtifactTool));
+
+        // Experimental tools ship dark behind feature flags (features section
+        // in settings.json)
+        let features = crate::config::get_feature_flags();
+        if features.browser_tool {
+            tools.insert("Browser".to_string(), Box::new(BrowserTool));
+        }
 
         Self {
             tools,
@@ -613,6 +870,11 @@ impl ToolExecutor {
             .collect()
     }
     
+    /// Get the human-readable action description for a tool invocation
+    pub fn describe_action(&self, name: &str, input: &serde_json::Value) -> Option<String> {
+        self.tools.get(name).map(|handler| handler.action_description(input))
+    }
+
     /// Check if a tool is allowed
     pub fn is_tool_allowed(&self, name: &str) -> bool {
         // Check disallowed list first
@@ -647,6 +909,97 @@ impl ToolExecutor {
             return Err(Error::ToolNotAllowed(name.to_string()));
         }
 
+        // Managed enterprise policy: tools it disallows cannot be re-enabled
+        // by user/project settings or runtime toggles
+        if crate::config::is_tool_disallowed_by_policy(name) {
+            return Err(Error::ToolNotAllowed(format!(
+                "{} (disabled by managed enterprise policy)",
+                name
+            )));
+        }
+
+        // Per-tool settings (tools section of settings.json) enforced at
+        // dispatch time: disable, timeout cap, domain restriction
+        let tool_config = crate::config::get_tool_config(name);
+        if tool_config.disabled == Some(true) {
+            return Err(Error::ToolNotAllowed(format!(
+                "{} (disabled via tools.{}.disabled in settings)",
+                name, name
+            )));
+        }
+        let mut input = input;
+        if name == "Bash" {
+            if let Some(cap) = tool_config.max_timeout_ms {
+                let requested = input.get("timeout").and_then(|t| t.as_u64()).unwrap_or(cap);
+                input["timeout"] = serde_json::json!(requested.min(cap));
+            }
+        }
+        if let Some(allowed_domains) = &tool_config.allowed_domains {
+            if let Some(url_str) = input.get("url").and_then(|u| u.as_str()) {
+                check_tool_domain(url_str, allowed_domains, name)?;
+            }
+        }
+
+        // Validate the completed tool input against the tool's schema
+        // before execution, so the model gets a correctable field-level
+        // error instead of an opaque failure from inside a handler
+        if let Err(validation_message) = validate_tool_input(&handler.input_schema(), &input) {
+            return Ok(ContentPart::ToolResult {
+                tool_use_id: context
+                    .as_ref()
+                    .map(|ctx| ctx.tool_use_id.clone())
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                content: format!("{} tool: {}", name, validation_message),
+                is_error: Some(true),
+            });
+        }
+
+        // Reverse map for normalized transcripts: relative path inputs
+        // (as the model reads them back from workspace-relative tool
+        // output) are resolved onto the workspace root before any
+        // policy check or handler sees them
+        for key in ["file_path", "notebook_path"] {
+            if let Some(path_str) = input.get(key).and_then(|p| p.as_str()) {
+                if !Path::new(path_str).is_absolute() {
+                    input[key] =
+                        serde_json::json!(crate::path_display::resolve_input_path(path_str));
+                }
+            }
+        }
+
+        // Central path policy: .claudeignore'd paths are invisible to every
+        // file tool, enforced here so individual handlers cannot drift
+        for key in ["file_path", "notebook_path"] {
+            if let Some(path_str) = input.get(key).and_then(|p| p.as_str()) {
+                if crate::path_policy::is_path_excluded(path_str) {
+                    return Err(Error::PermissionDenied(
+                        crate::path_policy::excluded_message(path_str),
+                    ));
+                }
+            }
+        }
+
+        // Track distinct Read targets for the /stats session summary
+        if name == "Read" {
+            if let Some(path_str) = input.get("file_path").and_then(|p| p.as_str()) {
+                record_file_read(Path::new(path_str));
+            }
+        }
+
+        // Clarifying-question budget (clarification section of settings.json):
+        // the limit stated in the system prompt is enforced here so decisive
+        // personas never stall on a question
+        if name == "AskUserQuestion" {
+            let budget = crate::config::get_clarification_config().question_budget();
+            let asked = QUESTIONS_THIS_TURN.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if asked >= budget {
+                return Err(Error::ToolExecution(format!(
+                    "Clarifying-question budget exhausted ({} per turn). Proceed with the most reasonable interpretation and state your assumptions.",
+                    budget
+                )));
+            }
+        }
+
         // Permission handling for Bash is now done entirely in the streaming flow in state.rs
         // No special handling needed here - just execute the tool normally
 
@@ -654,6 +1007,13 @@ impl ToolExecutor {
         let session_id = context.as_ref().map(|ctx| ctx.session_id.clone()).unwrap_or_default();
         let cancellation_token = context.as_ref().and_then(|ctx| ctx.cancellation_token.clone());
 
+        // ReadArtifact needs the session id to reload its on-disk index
+        // when the registry is cold (fresh process, --resume); inject it
+        // as an internal parameter like _permission_already_granted
+        if name == "ReadArtifact" && !session_id.is_empty() {
+            input["_session_id"] = serde_json::json!(session_id);
+        }
+
         // Execute PreToolUse hooks
         let hook_context = HookContext::new(HookType::PreToolUse, &session_id)
             .with_tool(name, input.clone());
@@ -668,6 +1028,17 @@ impl ToolExecutor {
             }
         }
 
+        // Shadow-copy the target file before any mutating file tool runs,
+        // so /undo can roll the turn back (no-op in dry-run mode: nothing
+        // will be written)
+        if matches!(name, "Write" | "Edit" | "MultiEdit" | "NotebookEdit") && !is_dry_run() {
+            for key in ["file_path", "notebook_path"] {
+                if let Some(path_str) = input.get(key).and_then(|p| p.as_str()) {
+                    crate::checkpoint::record_before_modify(Path::new(path_str));
+                }
+            }
+        }
+
         // Execute tool with cancellation support
         let tool_result = handler.execute(input.clone(), cancellation_token).await;
 
@@ -688,7 +1059,45 @@ impl ToolExecutor {
         }
 
         let result = tool_result?;
-        
+
+        // Normalize absolute workspace paths to relative form before
+        // anything downstream (artifact store, output cap, transcript)
+        // sees the text, so every surface shows the same portable paths
+        let result = crate::path_display::normalize_output(result);
+
+        // Offload oversized outputs to the artifact store, replacing them with
+        // an artifact:// handle and a short preview (skip ReadArtifact itself
+        // so retrieved artifacts are never re-offloaded)
+        let result = if name == "ReadArtifact" {
+            result
+        } else {
+            crate::ai::artifacts::offload_if_large(&session_id, name, result)?
+        };
+
+        // Apply the configured output cap, if any
+        let mut result = match tool_config.max_output_chars {
+            Some(cap) if result.chars().count() > cap => {
+                let truncated: String = result.chars().take(cap).collect();
+                format!(
+                    "{}\n\n[Output truncated to {} characters by tools.{}.maxOutputChars setting]",
+                    truncated, cap, name
+                )
+            }
+            _ => result,
+        };
+
+        // Run the configured formatter after successful file edits (never in
+        // dry-run mode, since no file was written)
+        if matches!(name, "Write" | "Edit" | "MultiEdit") && !is_dry_run() {
+            if let Some(format_command) = &tool_config.format_command {
+                if let Some(file_path) = input.get("file_path").and_then(|p| p.as_str()) {
+                    if let Some(note) = run_format_command(format_command, file_path).await {
+                        result.push_str(&note);
+                    }
+                }
+            }
+        }
+
         // Special handling for TodoWrite - notify TUI to update TODO display
         if name == "TodoWrite" {
             if let Some(context) = &context {
@@ -715,6 +1124,39 @@ impl ToolExecutor {
     }
 }
 
+/// Validate a tool input against the tool's JSON schema. Returns a
+/// message listing every offending field with its instance path, so the
+/// model can correct the call instead of hitting an opaque handler error.
+fn validate_tool_input(
+    schema: &serde_json::Value,
+    input: &serde_json::Value,
+) -> std::result::Result<(), String> {
+    // A schema that fails to compile is a bug in the tool definition, not
+    // in the model's call; never block execution on it
+    let Ok(compiled) = jsonschema::JSONSchema::compile(schema) else {
+        return Ok(());
+    };
+
+    let errors: Vec<String> = match compiled.validate(input) {
+        Ok(()) => return Ok(()),
+        Err(errors) => errors
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    format!("- {} (at the top-level input)", e)
+                } else {
+                    format!("- {} (at instance path \"{}\")", e, path)
+                }
+            })
+            .collect(),
+    };
+
+    Err(format!(
+        "Input validation failed:\n{}\nCorrect the listed fields and retry the tool call.",
+        errors.join("\n")
+    ))
+}
+
 /// Tool handler trait
 #[async_trait::async_trait]
 pub trait ToolHandler: Send + Sync {
@@ -1167,6 +1609,36 @@ Usage:
             None
         };
         
+        // Dry-run mode: report what would be written without touching the filesystem
+        if is_dry_run() {
+            return Ok(match &old_content {
+                Some(old) => {
+                    let diff = crate::ai::diff_display::DiffDisplay::new(
+                        old.clone(),
+                        content.to_string(),
+                        path.to_string(),
+                    );
+                    format!(
+                        "[DRY RUN] Would overwrite {} ({} bytes). No changes were made.\n\n{}",
+                        path,
+                        content.len(),
+                        diff.inline_diff()
+                    )
+                }
+                None if mode == "append" => format!(
+                    "[DRY RUN] Would append {} bytes to {}. No changes were made.",
+                    content.len(),
+                    path
+                ),
+                None => format!(
+                    "[DRY RUN] Would create {} ({} bytes, {} lines). No changes were made.",
+                    path,
+                    content.len(),
+                    content.lines().count()
+                ),
+            });
+        }
+
         // Create parent directory if needed
         if create_dirs {
             if let Some(parent) = path_obj.parent() {
@@ -1203,11 +1675,15 @@ Usage:
             Ok(result)
         } else {
             tracing::debug!("DEBUG: Overwriting file: {} with {} bytes", path, content.len());
-            match async_fs::write(path, &content).await {
-                Ok(_) => tracing::info!("DEBUG: File write successful: {} ({} bytes)", path, content.len()),
+            let final_content = match &old_content {
+                Some(old) => match_line_endings(old, content),
+                None => content.to_string(),
+            };
+            match atomic_write(path_obj, &final_content).await {
+                Ok(_) => tracing::info!("DEBUG: File write successful: {} ({} bytes)", path, final_content.len()),
                 Err(e) => {
                     tracing::error!("DEBUG: File write failed for {}: {}", path, e);
-                    return Err(Error::from(e));
+                    return Err(e);
                 }
             }
             
@@ -1335,27 +1811,47 @@ impl ToolHandler for ListFilesTool {
             }
         }
         
-        // List entries
+        // Walk one level with the ignore crate so the listing respects
+        // .gitignore and stays bounded even in huge directories
         let mut entries = Vec::new();
-        let read_dir = fs::read_dir(path)?;
-        
-        for entry in read_dir {
-            let entry = entry?;
+        let mut truncated = false;
+        let walker = ignore::WalkBuilder::new(path)
+            .hidden(false)
+            .max_depth(Some(1))
+            .build();
+
+        for entry in walker.flatten() {
             let entry_path = entry.path();
+            // The walker yields the root itself at depth 0
+            if entry_path == path {
+                continue;
+            }
             let file_name = entry.file_name().to_string_lossy().to_string();
-            
+
             // Check if should ignore
             let should_ignore = ignore_globs.iter().any(|pattern| {
-                pattern.matches(&file_name) || 
+                pattern.matches(&file_name) ||
                 pattern.matches(&entry_path.to_string_lossy())
             });
-            
+
             if should_ignore {
                 continue;
             }
-            
+
+            // Paths excluded by .claudeignore are invisible to listings
+            if crate::path_policy::is_path_excluded(entry_path) {
+                continue;
+            }
+
+            if entries.len() >= LS_MAX_ENTRIES {
+                truncated = true;
+                break;
+            }
+
             // Get file type and metadata
-            let metadata = entry.metadata()?;
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
             let file_type = if metadata.is_dir() {
                 "directory"
             } else if metadata.is_symlink() {
@@ -1363,17 +1859,17 @@ impl ToolHandler for ListFilesTool {
             } else {
                 "file"
             };
-            
+
             // Format entry similar to JavaScript output
             let size = if metadata.is_file() {
                 format!(", {} bytes", metadata.len())
             } else {
                 String::new()
             };
-            
+
             entries.push(format!("{} ({}{})", file_name, file_type, size));
         }
-        
+
         // Sort entries: directories first, then files
         entries.sort_by(|a, b| {
             let a_is_dir = a.contains("(directory");
@@ -1384,9 +1880,15 @@ impl ToolHandler for ListFilesTool {
                 _ => a.cmp(b),
             }
         });
-        
+
         if entries.is_empty() {
             Ok("No files or directories found".to_string())
+        } else if truncated {
+            Ok(format!(
+                "{}\n(Listing limited to {} entries. Use ignore patterns or a subdirectory.)",
+                entries.join("\n"),
+                LS_MAX_ENTRIES
+            ))
         } else {
             Ok(entries.join("\n"))
         }
@@ -1485,7 +1987,17 @@ impl ToolHandler for SearchFilesTool {
         match output {
             Ok(output) => {
                 if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+                    // Drop .claudeignore'd paths from the results
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    let kept: Vec<&str> = stdout
+                        .lines()
+                        .filter(|line| !crate::path_policy::is_path_excluded(line))
+                        .collect();
+                    if kept.is_empty() {
+                        Ok("No matches found".to_string())
+                    } else {
+                        Ok(kept.join("\n"))
+                    }
                 } else {
                     Ok("No matches found".to_string())
                 }
@@ -1494,7 +2006,8 @@ impl ToolHandler for SearchFilesTool {
                 // Fallback to basic search
                 let mut matches = Vec::new();
                 search_files_recursive(Path::new(path), pattern, file_pattern, &mut matches)?;
-                
+                matches.retain(|path| !crate::path_policy::is_path_excluded(path));
+
                 if matches.is_empty() {
                     Ok("No matches found".to_string())
                 } else {
@@ -1749,8 +2262,11 @@ impl ToolHandler for GrepTool {
         
         // For files_with_matches mode (default), return results sorted by modification time
         if output_mode == "files_with_matches" {
-            let files: Vec<&str> = stdout.lines().collect();
-            
+            let files: Vec<&str> = stdout
+                .lines()
+                .filter(|file| !crate::path_policy::is_path_excluded(file))
+                .collect();
+
             if files.is_empty() {
                 return Ok("No files found".to_string());
             }
@@ -1798,9 +2314,19 @@ impl ToolHandler for GrepTool {
             
             Ok(result)
         } else {
-            // For other modes, apply head_limit if specified
-            let mut result = stdout.to_string();
-            
+            // For other modes, drop lines from excluded files (lines are
+            // "path:rest" in content/count modes), then apply head_limit
+            let mut result = stdout
+                .lines()
+                .filter(|line| {
+                    line.split(':')
+                        .next()
+                        .map(|prefix| !crate::path_policy::is_path_excluded(prefix))
+                        .unwrap_or(true)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
             if let Some(limit) = input["head_limit"].as_u64() {
                 let lines: Vec<&str> = result.lines().take(limit as usize).collect();
                 if lines.len() == limit as usize && result.lines().count() > limit as usize {
@@ -1819,6 +2345,12 @@ impl ToolHandler for GrepTool {
     }
 }
 
+/// Bounds for ignore-aware directory walking: keep tool output usable in a
+/// monorepo instead of returning (or crawling) hundreds of thousands of paths
+const GLOB_MAX_RESULTS: usize = 1000;
+const GLOB_MAX_DEPTH: usize = 32;
+const LS_MAX_ENTRIES: usize = 500;
+
 /// Glob tool - fast file pattern matching
 pub struct GlobTool;
 
@@ -1885,47 +2417,79 @@ impl ToolHandler for GlobTool {
         // Resolve base path to absolute
         let base_path = Path::new(base_path).canonicalize()
             .map_err(|e| Error::NotFound(format!("Invalid base path '{}': {}", base_path, e)))?;
-            
-        // Construct full pattern
-        let full_pattern = base_path.join(pattern);
-        let pattern_str = full_pattern.to_string_lossy();
-        
-        // Create a vector to store files with metadata
-        let mut files_with_time: Vec<(PathBuf, SystemTime)> = Vec::new();
-        
-        // Execute glob pattern matching
-        for entry in glob(&pattern_str)? {
-            match entry {
-                Ok(path) => {
-                    // Only include files, not directories
-                    if path.is_file() {
-                        // Get modification time
-                        match fs::metadata(&path) {
-                            Ok(metadata) => {
-                                match metadata.modified() {
-                                    Ok(modified) => {
-                                        files_with_time.push((path, modified));
-                                    }
-                                    Err(_) => {
-                                        // If we can't get modification time, use epoch
-                                        files_with_time.push((path, SystemTime::UNIX_EPOCH));
-                                    }
-                                }
-                            }
-                            Err(_) => {
-                                // If we can't get metadata, still include the file
-                                files_with_time.push((path, SystemTime::UNIX_EPOCH));
-                            }
-                        }
-                    }
+
+        let compiled = glob::Pattern::new(pattern)
+            .map_err(|e| Error::InvalidInput(format!("Invalid glob pattern '{}': {}", pattern, e)))?;
+        // `*` must not cross directory separators (`**` still does), matching
+        // how filesystem globbing treats the pattern
+        let match_options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+
+        // Walk with the ignore crate's parallel, gitignore-aware walker so a
+        // glob over a monorepo neither crawls node_modules nor returns an
+        // unbounded file list into context
+        let collected: Arc<std::sync::Mutex<Vec<(PathBuf, SystemTime)>>> =
+            Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hit_limit = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let walker = ignore::WalkBuilder::new(&base_path)
+            .hidden(false)
+            .max_depth(Some(GLOB_MAX_DEPTH))
+            .build_parallel();
+
+        walker.run(|| {
+            let collected = Arc::clone(&collected);
+            let hit_limit = Arc::clone(&hit_limit);
+            let compiled = compiled.clone();
+            let base_path = base_path.clone();
+            Box::new(move |entry| {
+                use ignore::WalkState;
+
+                let Ok(entry) = entry else {
+                    return WalkState::Continue;
+                };
+                // Only include files, not directories
+                if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                    return WalkState::Continue;
                 }
-                Err(e) => {
-                    // Log glob errors but continue
-                    eprintln!("Glob error: {}", e);
+                let path = entry.path();
+                let Ok(relative) = path.strip_prefix(&base_path) else {
+                    return WalkState::Continue;
+                };
+                if !compiled.matches_path_with(relative, match_options) {
+                    return WalkState::Continue;
                 }
-            }
-        }
-        
+                if crate::path_policy::is_path_excluded(path) {
+                    return WalkState::Continue;
+                }
+                // If we can't get a modification time, use epoch
+                let modified = entry
+                    .metadata()
+                    .ok()
+                    .and_then(|m| m.modified().ok())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+
+                let Ok(mut files) = collected.lock() else {
+                    return WalkState::Quit;
+                };
+                if files.len() >= GLOB_MAX_RESULTS {
+                    hit_limit.store(true, std::sync::atomic::Ordering::SeqCst);
+                    return WalkState::Quit;
+                }
+                files.push((path.to_path_buf(), modified));
+                WalkState::Continue
+            })
+        });
+
+        let mut files_with_time: Vec<(PathBuf, SystemTime)> = match collected.lock() {
+            Ok(files) => files.clone(),
+            Err(_) => Vec::new(),
+        };
+        let truncated = hit_limit.load(std::sync::atomic::Ordering::SeqCst);
+
+
         // Sort by modification time - newest first
         // JavaScript sorts by oldest first: (a.mtimeMs ?? 0) - (b.mtimeMs ?? 0)
         // Then the result is reversed later when displayed
@@ -1940,8 +2504,16 @@ impl ToolHandler for GlobTool {
             .into_iter()
             .map(|(path, _)| path.display().to_string())
             .collect();
-        
-        Ok(result.join("\n"))
+
+        let mut output = result.join("\n");
+        if truncated {
+            output.push_str(&format!(
+                "\n(Results limited to {} files. Consider a more specific pattern or path.)",
+                GLOB_MAX_RESULTS
+            ));
+        }
+
+        Ok(output)
     }
 }
 
@@ -2088,9 +2660,18 @@ Usage:
             file_path.to_string()
         );
         
-        // Write back
-        async_fs::write(file_path, &result).await?;
-        
+        // Dry-run mode: report the diff without writing the file
+        if is_dry_run() {
+            return Ok(format!(
+                "[DRY RUN] {}. No changes were made.\n\n{}",
+                diff.summary(),
+                diff.inline_diff()
+            ));
+        }
+
+        // Write back atomically, keeping the file's dominant line ending
+        atomic_write(Path::new(file_path), &match_line_endings(&content, &result)).await?;
+
         // Return summary with inline diff for context
         let summary = diff.summary();
         let inline_diff = diff.inline_diff();
@@ -2267,12 +2848,8 @@ If you want to create a new file, use:
             
             // Check if old_string exists in current content
             if !content.contains(old_string) {
-                failed_edits.push(format!("Edit {}: Text not found: '{}'", idx + 1, 
-                    if old_string.len() > 50 { 
-                        format!("{}...", &old_string[..50]) 
-                    } else { 
-                        old_string.to_string() 
-                    }
+                failed_edits.push(format!("Edit {}: Text not found: '{}'", idx + 1,
+                    crate::utils::truncate_display(old_string, 53)
                 ));
                 continue;
             }
@@ -2301,8 +2878,29 @@ If you want to create a new file, use:
         
         // Only write if content changed
         if content != original_content {
-            async_fs::write(file_path, &content).await?;
-            
+            // Dry-run mode: report the combined diff without writing the file
+            if is_dry_run() {
+                let diff = crate::ai::diff_display::DiffDisplay::new(
+                    original_content.clone(),
+                    content.clone(),
+                    file_path.to_string(),
+                );
+                return Ok(format!(
+                    "[DRY RUN] {}. Would apply {} of {} edits. No changes were made.\n\n{}",
+                    diff.summary(),
+                    applied_edits.len(),
+                    edits.len(),
+                    diff.inline_diff()
+                ));
+            }
+
+            // Write back atomically, keeping the file's dominant line ending
+            atomic_write(
+                Path::new(file_path),
+                &match_line_endings(&original_content, &content),
+            )
+            .await?;
+
             // Generate diff display
             let diff = crate::ai::diff_display::DiffDisplay::new(
                 original_content.clone(),
@@ -2463,7 +3061,11 @@ Usage notes:
         let command = input["command"]
             .as_str()
             .ok_or_else(|| Error::InvalidInput("Missing 'command' field".to_string()))?;
-        
+
+        // Enforce the attribution policy on inline commit messages
+        let command_owned = apply_commit_attribution(command);
+        let command = command_owned.as_str();
+
         let timeout_ms = input["timeout"]
             .as_u64()
             .unwrap_or(120000)
@@ -2489,7 +3091,25 @@ Usage notes:
         // Note: Permission checking is now handled in execute_bash_with_suspension
         // when called through execute_with_context. Direct calls to this execute method
         // (without context) will bypass permission checks for backward compatibility.
-        
+
+        // Dry-run mode: report the command that would run without executing it
+        if is_dry_run() {
+            let effective_dir = match &working_dir {
+                Some(dir) => dir.display().to_string(),
+                None => std::env::current_dir()
+                    .map(|d| d.display().to_string())
+                    .unwrap_or_else(|_| "<unknown>".to_string()),
+            };
+            return Ok(format!(
+                "[DRY RUN] Would execute in {}{}:\n  {}\n(timeout: {}ms, sandboxed: {}). No command was run.",
+                effective_dir,
+                if run_in_background { " (background)" } else { "" },
+                command,
+                timeout_ms,
+                is_sandboxed
+            ));
+        }
+
         // Handle background execution (like JavaScript moveToBackground)
         if run_in_background {
             let shell_id = BACKGROUND_SHELLS.generate_shell_id().await;
@@ -2500,6 +3120,11 @@ Usage notes:
             cmd.arg("-c");
             cmd.arg(command);
             cmd.kill_on_drop(true);
+            // Apply the settings-driven env policy before tool-specific overrides
+            if let Some(policy_env) = crate::config::compute_policied_env() {
+                cmd.env_clear();
+                cmd.envs(&policy_env);
+            }
             // Disable color output and TTY detection to prevent terminal corruption
             cmd.env("NO_COLOR", "1");
             cmd.env("TERM", "dumb");
@@ -2704,7 +3329,10 @@ Usage notes:
         
         // Truncate output if too long (matches JavaScript behavior)
         if result.len() > 30000 {
-            result.truncate(30000);
+            // Back off to a character boundary so multi-byte text can't
+            // panic the truncation
+            let keep = crate::utils::truncate_bytes(&result, 30000).len();
+            result.truncate(keep);
             result.push_str("\n\n[Output truncated to 30000 characters]");
         }
         
@@ -2715,10 +3343,64 @@ Usage notes:
 /// HTTP request tool
 struct HttpRequestTool;
 
+/// Header names whose values must never appear in transcripts
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "x-auth-token",
+    "api-key",
+];
+
+impl HttpRequestTool {
+    fn is_sensitive_header(name: &str) -> bool {
+        SENSITIVE_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+    }
+
+    /// Check the request host against the merged httpAllowedDomains setting.
+    /// An empty allow-list means no restriction; entries match the exact host
+    /// or any subdomain of it.
+    fn check_domain_allowed(request_url: &str) -> Result<()> {
+        let allowed = crate::config::get_http_allowed_domains();
+        if allowed.is_empty() {
+            return Ok(());
+        }
+
+        let parsed = url::Url::parse(request_url)
+            .map_err(|e| Error::InvalidInput(format!("Invalid URL: {}", e)))?;
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| Error::InvalidInput("URL has no host".to_string()))?
+            .to_ascii_lowercase();
+
+        let is_allowed = allowed.iter().any(|domain| {
+            let domain = domain.to_ascii_lowercase();
+            host == domain || host.ends_with(&format!(".{}", domain))
+        });
+
+        if is_allowed {
+            Ok(())
+        } else {
+            Err(Error::PermissionDenied(format!(
+                "Domain '{}' is not in the httpAllowedDomains allow-list ({})",
+                host,
+                allowed.join(", ")
+            )))
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl ToolHandler for HttpRequestTool {
     fn description(&self) -> String {
-        "Make an HTTP request".to_string()
+        "Make an HTTP request with full method, header, and body control. Use this for API debugging instead of piping curl through Bash so authentication headers never appear in the transcript.
+
+Usage:
+- Supports GET, POST, PUT, DELETE, PATCH, and HEAD
+- Request and response values of sensitive headers (Authorization, Cookie, API keys) are redacted in the output
+- If the httpAllowedDomains setting is configured, requests are restricted to those domains and their subdomains".to_string()
     }
     
     fn input_schema(&self) -> serde_json::Value {
@@ -2732,7 +3414,7 @@ impl ToolHandler for HttpRequestTool {
                 "method": {
                     "type": "string",
                     "description": "HTTP method",
-                    "enum": ["GET", "POST", "PUT", "DELETE", "PATCH"],
+                    "enum": ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD"],
                     "default": "GET"
                 },
                 "headers": {
@@ -2757,20 +3439,40 @@ impl ToolHandler for HttpRequestTool {
     }
     
     fn permission_details(&self, input: &serde_json::Value) -> String {
-        format!(
+        let mut details = format!(
             "URL: {}, Method: {}",
             input["url"].as_str().unwrap_or("<unknown>"),
             input["method"].as_str().unwrap_or("GET")
-        )
+        );
+        // Show header names but redact sensitive values
+        if let Some(headers) = input["headers"].as_object() {
+            if !headers.is_empty() {
+                let header_list: Vec<String> = headers
+                    .iter()
+                    .map(|(key, value)| {
+                        if Self::is_sensitive_header(key) {
+                            format!("{}: <redacted>", key)
+                        } else {
+                            format!("{}: {}", key, value.as_str().unwrap_or("<non-string>"))
+                        }
+                    })
+                    .collect();
+                details.push_str(&format!(", Headers: [{}]", header_list.join(", ")));
+            }
+        }
+        details
     }
-    
+
     async fn execute(&self, input: serde_json::Value, cancellation_token: Option<CancellationToken>) -> Result<String> {
         let url = input["url"]
             .as_str()
             .ok_or_else(|| Error::InvalidInput("Missing 'url' field".to_string()))?;
-        
+
         let method = input["method"].as_str().unwrap_or("GET");
-        
+
+        // Enforce the settings-driven domain allow-list before anything is sent
+        Self::check_domain_allowed(url)?;
+
         let client = reqwest::Client::new();
         let mut request = match method {
             "GET" => client.get(url),
@@ -2778,6 +3480,7 @@ impl ToolHandler for HttpRequestTool {
             "PUT" => client.put(url),
             "DELETE" => client.delete(url),
             "PATCH" => client.patch(url),
+            "HEAD" => client.head(url),
             _ => return Err(Error::InvalidInput(format!("Invalid method: {}", method))),
         };
         
@@ -2803,7 +3506,12 @@ impl ToolHandler for HttpRequestTool {
         let mut result = format!("Status: {}\n\nHeaders:\n", status);
         for (key, value) in headers {
             if let Some(key) = key {
-                result.push_str(&format!("{}: {}\n", key, value.to_str().unwrap_or("<binary>")));
+                // Redact sensitive response headers (e.g. Set-Cookie) from the transcript
+                if Self::is_sensitive_header(key.as_str()) {
+                    result.push_str(&format!("{}: <redacted>\n", key));
+                } else {
+                    result.push_str(&format!("{}: {}\n", key, value.to_str().unwrap_or("<binary>")));
+                }
             }
         }
         
@@ -145,7 +145,8 @@ async fn fetch_url(url: &str) -> Result<CachedFetchResult> {
         sdk_version, runtime, arch
     );
     
-    let client = reqwest::Client::builder()
+    let client = crate::utils::http::client_builder()
+        .map_err(|e| Error::Network(e.to_string()))?
         .timeout(Duration::from_secs(30))
         .user_agent(&user_agent)
         .redirect(reqwest::redirect::Policy::none()) // maxRedirects: 0 in JS
@@ -291,6 +292,8 @@ async fn process_content_with_ai(content: &str, prompt: &str) -> Result<String>
         tool_choice: None,
         metadata: None,
         betas: None,
+        thinking: None,
+        service_tier: None,
     };
     
     // Send request
@@ -491,6 +494,8 @@ impl ToolHandler for WebSearchTool {
             tool_choice: None,
             metadata: None,
             betas: None,
+            thinking: None,
+            service_tier: None,
         };
         
         // Send request and collect results
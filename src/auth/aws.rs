@@ -70,191 +70,120 @@ impl CredentialProvider for EnvCredentialProvider {
     }
 }
 
-// Container Metadata Credential Provider (ECS)
-pub struct ContainerMetadataProvider {
-    timeout: std::time::Duration,
-    max_retries: u32,
-}
+// Container Metadata Credential Provider (ECS and EKS Pod Identity)
+//
+// Delegates to the aws_providers HTTP implementation, which handles the
+// ECS agent, the EKS Pod Identity agent hosts, and authorization tokens
+// from AWS_CONTAINER_AUTHORIZATION_TOKEN or its _FILE variant
+pub struct ContainerMetadataProvider;
 
 impl ContainerMetadataProvider {
     pub fn new() -> Self {
-        Self {
-            timeout: std::time::Duration::from_millis(1000),
-            max_retries: 0,
-        }
+        Self
     }
+}
 
-    async fn get_cmds_uri(&self) -> Result<String> {
-        if let Ok(relative_uri) = env::var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI") {
-            return Ok(format!("http://169.254.170.2{}", relative_uri));
-        }
-
-        if let Ok(full_uri) = env::var("AWS_CONTAINER_CREDENTIALS_FULL_URI") {
-            let url = reqwest::Url::parse(&full_uri)?;
-
-            // Validate host
-            let valid_hosts = ["localhost", "127.0.0.1"];
-            let host = url.host_str().ok_or_else(|| {
-                anyhow::anyhow!("Invalid container metadata service URL")
-            })?;
-
-            if !valid_hosts.contains(&host) {
-                return Err(anyhow::anyhow!(
-                    "{} is not a valid container metadata service hostname",
-                    host
-                ));
-            }
-
-            // Validate protocol
-            if url.scheme() != "http" && url.scheme() != "https" {
-                return Err(anyhow::anyhow!(
-                    "{} is not a valid container metadata service protocol",
-                    url.scheme()
-                ));
-            }
+#[async_trait::async_trait]
+impl CredentialProvider for ContainerMetadataProvider {
+    async fn get_credentials(&self) -> Result<AwsCredentials> {
+        use crate::auth::aws_providers::CredentialProvider as _;
 
-            return Ok(full_uri);
+        if env::var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI").is_err()
+            && env::var("AWS_CONTAINER_CREDENTIALS_FULL_URI").is_err()
+        {
+            return Err(anyhow::anyhow!(
+                "The container metadata credential provider cannot be used unless \
+                 AWS_CONTAINER_CREDENTIALS_RELATIVE_URI or AWS_CONTAINER_CREDENTIALS_FULL_URI is set"
+            ));
         }
 
-        Err(anyhow::anyhow!(
-            "The container metadata credential provider cannot be used unless \
-             AWS_CONTAINER_CREDENTIALS_RELATIVE_URI or AWS_CONTAINER_CREDENTIALS_FULL_URI is set"
-        ))
-    }
+        // Read the container environment at call time so chain lookups
+        // see the current process environment
+        let creds = crate::auth::aws_providers::http::from_http()
+            .provide_credentials()
+            .await?;
 
-    async fn request_from_ecs_imds(&self, uri: &str) -> Result<AwsCredentials> {
-        let client = reqwest::Client::builder()
-            .timeout(self.timeout)
-            .build()?;
+        Ok(AwsCredentials {
+            access_key_id: creds.access_key_id,
+            secret_access_key: creds.secret_access_key,
+            session_token: creds.session_token,
+            expiration: creds.expiration,
+            credential_scope: creds.credential_scope,
+            account_id: creds.account_id,
+            source: None,
+        })
+    }
+}
 
-        let mut request = client.get(uri);
+// Web Identity Token File Credential Provider (EKS IRSA)
+//
+// Delegates to the aws_providers token-file implementation, which reads
+// AWS_WEB_IDENTITY_TOKEN_FILE / AWS_ROLE_ARN and exchanges the token via
+// STS AssumeRoleWithWebIdentity
+pub struct WebIdentityProvider {
+    inner: crate::auth::aws_providers::TokenFileCredentialsProvider,
+}
 
-        // Add authorization token if present
-        if let Ok(token) = env::var("AWS_CONTAINER_AUTHORIZATION_TOKEN") {
-            request = request.header("Authorization", token);
+impl WebIdentityProvider {
+    pub fn new() -> Self {
+        Self {
+            inner: crate::auth::aws_providers::from_token_file(),
         }
-
-        let response = request.send().await?;
-        let credentials: AwsCredentials = response.json().await?;
-
-        Ok(credentials)
     }
 }
 
 #[async_trait::async_trait]
-impl CredentialProvider for ContainerMetadataProvider {
+impl CredentialProvider for WebIdentityProvider {
     async fn get_credentials(&self) -> Result<AwsCredentials> {
-        let uri = self.get_cmds_uri().await?;
+        use crate::auth::aws_providers::CredentialProvider as _;
 
-        // Retry logic
-        let mut last_error = None;
-        for _ in 0..=self.max_retries {
-            match self.request_from_ecs_imds(&uri).await {
-                Ok(creds) => return Ok(creds),
-                Err(e) => last_error = Some(e),
-            }
-        }
+        let creds = self.inner.provide_credentials().await?;
 
-        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Failed to get container credentials")))
+        Ok(AwsCredentials {
+            access_key_id: creds.access_key_id,
+            secret_access_key: creds.secret_access_key,
+            session_token: creds.session_token,
+            expiration: creds.expiration,
+            credential_scope: creds.credential_scope,
+            account_id: creds.account_id,
+            source: None,
+        })
     }
 }
 
 // Instance Metadata Credential Provider (EC2)
+//
+// Delegates to the aws_providers IMDSv2 implementation, which handles
+// endpoint overrides, retry with backoff, and hop-limit guidance for
+// containers running on EC2
 pub struct InstanceMetadataProvider {
-    timeout: std::time::Duration,
-    max_retries: u32,
+    inner: crate::auth::aws_providers::container::FromInstanceMetadata,
 }
 
 impl InstanceMetadataProvider {
     pub fn new() -> Self {
         Self {
-            timeout: std::time::Duration::from_millis(1000),
-            max_retries: 0,
-        }
-    }
-
-    async fn get_instance_metadata_endpoint(&self) -> String {
-        "http://169.254.169.254".to_string()
-    }
-
-    async fn get_credentials_from_imds(&self, endpoint: &str) -> Result<AwsCredentials> {
-        let client = reqwest::Client::builder()
-            .timeout(self.timeout)
-            .build()?;
-
-        // First get the token (IMDSv2)
-        let token_response = client
-            .put(format!("{}/latest/api/token", endpoint))
-            .header("X-aws-ec2-metadata-token-ttl-seconds", "21600")
-            .send()
-            .await?;
-
-        let token = token_response.text().await?;
-
-        // Get the role name
-        let role_response = client
-            .get(format!("{}/latest/meta-data/iam/security-credentials/", endpoint))
-            .header("X-aws-ec2-metadata-token", &token)
-            .send()
-            .await?;
-
-        let role_name = role_response.text().await?;
-        let role_name = role_name.trim();
-
-        // Get the credentials
-        let creds_response = client
-            .get(format!("{}/latest/meta-data/iam/security-credentials/{}", endpoint, role_name))
-            .header("X-aws-ec2-metadata-token", &token)
-            .send()
-            .await?;
-
-        #[derive(Deserialize)]
-        struct ImdsCredentials {
-            #[serde(rename = "AccessKeyId")]
-            access_key_id: String,
-            #[serde(rename = "SecretAccessKey")]
-            secret_access_key: String,
-            #[serde(rename = "Token")]
-            token: String,
-            #[serde(rename = "Expiration")]
-            expiration: String,
+            inner: crate::auth::aws_providers::container::FromInstanceMetadata::new(),
         }
-
-        let imds_creds: ImdsCredentials = creds_response.json().await?;
-
-        Ok(AwsCredentials {
-            access_key_id: imds_creds.access_key_id,
-            secret_access_key: imds_creds.secret_access_key,
-            session_token: Some(imds_creds.token),
-            expiration: DateTime::parse_from_rfc3339(&imds_creds.expiration)
-                .ok()
-                .map(|dt| dt.with_timezone(&Utc)),
-            credential_scope: None,
-            account_id: None,
-            source: None,
-        })
     }
 }
 
 #[async_trait::async_trait]
 impl CredentialProvider for InstanceMetadataProvider {
     async fn get_credentials(&self) -> Result<AwsCredentials> {
-        if env::var("AWS_EC2_METADATA_DISABLED").unwrap_or_default() == "true" {
-            return Err(anyhow::anyhow!("EC2 Instance Metadata Service access disabled"));
-        }
-
-        let endpoint = self.get_instance_metadata_endpoint().await;
+        use crate::auth::aws_providers::CredentialProvider as _;
 
-        // Retry logic
-        let mut last_error = None;
-        for _ in 0..=self.max_retries {
-            match self.get_credentials_from_imds(&endpoint).await {
-                Ok(creds) => return Ok(creds),
-                Err(e) => last_error = Some(e),
-            }
-        }
+        let creds = self.inner.provide_credentials().await?;
 
-        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Failed to get instance metadata credentials")))
+        Ok(AwsCredentials {
+            access_key_id: creds.access_key_id,
+            secret_access_key: creds.secret_access_key,
+            session_token: creds.session_token,
+            expiration: creds.expiration,
+            credential_scope: creds.credential_scope,
+            account_id: creds.account_id,
+            source: None,
+        })
     }
 }
 
@@ -608,19 +537,25 @@ impl DefaultCredentialProvider {
         // 2. INI file credentials (AWS CLI)
         providers.push(Box::new(IniFileProvider::new()));
 
-        // 3. Container metadata (if available)
+        // 3. Web identity token file (EKS IRSA) — precedes container
+        // metadata, matching the AWS SDK default chain
+        if env::var("AWS_WEB_IDENTITY_TOKEN_FILE").is_ok() && env::var("AWS_ROLE_ARN").is_ok() {
+            providers.push(Box::new(WebIdentityProvider::new()));
+        }
+
+        // 4. Container metadata (ECS agent or EKS Pod Identity agent)
         if env::var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI").is_ok()
             || env::var("AWS_CONTAINER_CREDENTIALS_FULL_URI").is_ok()
         {
             providers.push(Box::new(ContainerMetadataProvider::new()));
         }
 
-        // 4. Instance metadata (if not disabled)
+        // 5. Instance metadata (if not disabled)
         if env::var("AWS_EC2_METADATA_DISABLED").unwrap_or_default() != "true" {
             providers.push(Box::new(InstanceMetadataProvider::new()));
         }
 
-        // TODO: Add SSO, process, and web token providers
+        // TODO: Add SSO and process providers
 
         Self { providers }
     }
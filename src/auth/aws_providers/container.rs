@@ -11,14 +11,40 @@ use tracing::debug;
 // Environment variables for EC2 Instance Metadata Service
 pub const AWS_EC2_METADATA_DISABLED: &str = "AWS_EC2_METADATA_DISABLED";
 pub const AWS_EC2_METADATA_V1_DISABLED: &str = "AWS_EC2_METADATA_V1_DISABLED";
+pub const AWS_EC2_METADATA_SERVICE_ENDPOINT: &str = "AWS_EC2_METADATA_SERVICE_ENDPOINT";
+pub const AWS_EC2_METADATA_SERVICE_ENDPOINT_MODE: &str = "AWS_EC2_METADATA_SERVICE_ENDPOINT_MODE";
 
 // Default timeouts and retry settings
 const DEFAULT_TIMEOUT_MS: u64 = 1000;
 const DEFAULT_MAX_RETRIES: u32 = 3;
 
-// IMDSv2 endpoints
-const IMDS_TOKEN_ENDPOINT: &str = "http://169.254.169.254/latest/api/token";
-const IMDS_CREDENTIALS_ENDPOINT: &str = "http://169.254.169.254/latest/meta-data/iam/security-credentials/";
+// IMDS endpoints (IPv4 default, IPv6 selected via endpoint mode)
+const IMDS_ENDPOINT_IPV4: &str = "http://169.254.169.254";
+const IMDS_ENDPOINT_IPV6: &str = "http://[fd00:ec2::254]";
+
+// Guidance for the classic container-on-EC2 failure: the IMDSv2 token PUT
+// times out when the instance's HttpPutResponseHopLimit is 1, because the
+// response cannot cross the extra network hop into the container
+const HOP_LIMIT_HINT: &str = "If this is a container running on EC2, the IMDSv2 token request may be \
+     blocked by the instance's metadata hop limit. Increase it with: \
+     aws ec2 modify-instance-metadata-options --instance-id <id> --http-put-response-hop-limit 2";
+
+/// Resolve the instance metadata endpoint, honoring the
+/// AWS_EC2_METADATA_SERVICE_ENDPOINT override and the IPv4/IPv6
+/// endpoint mode used by the AWS SDKs
+fn resolve_imds_endpoint() -> String {
+    if let Ok(endpoint) = env::var(AWS_EC2_METADATA_SERVICE_ENDPOINT) {
+        let trimmed = endpoint.trim_end_matches('/');
+        if !trimmed.is_empty() {
+            return trimmed.to_string();
+        }
+    }
+
+    match env::var(AWS_EC2_METADATA_SERVICE_ENDPOINT_MODE).as_deref() {
+        Ok(mode) if mode.eq_ignore_ascii_case("ipv6") => IMDS_ENDPOINT_IPV6.to_string(),
+        _ => IMDS_ENDPOINT_IPV4.to_string(),
+    }
+}
 
 /// Instance metadata credentials response
 #[derive(Debug, Deserialize, Serialize)]
@@ -45,6 +71,7 @@ struct InstanceMetadataCredentials {
 pub struct InstanceMetadataConfig {
     pub timeout: Duration,
     pub max_retries: u32,
+    pub endpoint: String,
     pub ec2_metadata_v1_disabled: bool,
     pub ec2_metadata_v2_disable_session_token: bool,
     pub logger: Option<String>,
@@ -55,6 +82,7 @@ impl Default for InstanceMetadataConfig {
         Self {
             timeout: Duration::from_millis(DEFAULT_TIMEOUT_MS),
             max_retries: DEFAULT_MAX_RETRIES,
+            endpoint: resolve_imds_endpoint(),
             ec2_metadata_v1_disabled: env::var(AWS_EC2_METADATA_V1_DISABLED)
                 .map(|v| v == "true")
                 .unwrap_or(false),
@@ -182,6 +210,12 @@ impl FromInstanceMetadata {
         self
     }
 
+    /// Set the metadata service endpoint (overrides the IPv4/IPv6 default)
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.config.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Set EC2 metadata v1 disabled flag
     pub fn with_ec2_metadata_v1_disabled(mut self, disabled: bool) -> Self {
         self.config.ec2_metadata_v1_disabled = disabled;
@@ -195,16 +229,21 @@ impl FromInstanceMetadata {
     }
 
     /// Get IMDSv2 session token
-    async fn get_imds_v2_token(&self) -> Result<Option<String>> {
+    ///
+    /// Returns the token (None when the service only supports IMDSv1) and
+    /// whether every attempt timed out, which is the signature of a hop
+    /// limit of 1 when running in a container on EC2
+    async fn get_imds_v2_token(&self) -> Result<(Option<String>, bool)> {
         if self.config.ec2_metadata_v2_disable_session_token {
             debug!("IMDSv2 session token disabled by configuration");
-            return Ok(None);
+            return Ok((None, false));
         }
 
+        let mut all_timed_out = true;
         let mut attempt = 0;
         while attempt <= self.config.max_retries {
             let response = self.client
-                .put(IMDS_TOKEN_ENDPOINT)
+                .put(format!("{}/latest/api/token", self.config.endpoint))
                 .header("X-aws-ec2-metadata-token-ttl-seconds", "21600") // 6 hours
                 .send()
                 .await;
@@ -213,16 +252,20 @@ impl FromInstanceMetadata {
                 Ok(resp) if resp.status().is_success() => {
                     let token = resp.text().await.context("Failed to read IMDSv2 token response")?;
                     debug!("Successfully obtained IMDSv2 session token");
-                    return Ok(Some(token));
+                    return Ok((Some(token), false));
                 }
                 Ok(resp) => {
+                    all_timed_out = false;
                     debug!("IMDSv2 token request failed with status: {}", resp.status());
                     if resp.status().as_u16() == 403 {
                         // IMDSv2 required but not available
-                        return Ok(None);
+                        return Ok((None, false));
                     }
                 }
                 Err(e) => {
+                    if !e.is_timeout() {
+                        all_timed_out = false;
+                    }
                     debug!("IMDSv2 token request failed: {}", e);
                 }
             }
@@ -234,12 +277,14 @@ impl FromInstanceMetadata {
             }
         }
 
-        Ok(None)
+        Ok((None, all_timed_out))
     }
 
     /// Get available IAM roles
     async fn get_available_roles(&self, token: Option<&str>) -> Result<Vec<String>> {
-        let mut request_builder = self.client.get(IMDS_CREDENTIALS_ENDPOINT);
+        let credentials_endpoint =
+            format!("{}/latest/meta-data/iam/security-credentials/", self.config.endpoint);
+        let mut request_builder = self.client.get(&credentials_endpoint);
 
         if let Some(token) = token {
             request_builder = request_builder.header("X-aws-ec2-metadata-token", token);
@@ -278,7 +323,10 @@ impl FromInstanceMetadata {
 
     /// Get credentials for a specific IAM role
     async fn get_role_credentials(&self, role_name: &str, token: Option<&str>) -> Result<InstanceMetadataCredentials> {
-        let credentials_url = format!("{}{}", IMDS_CREDENTIALS_ENDPOINT, role_name);
+        let credentials_url = format!(
+            "{}/latest/meta-data/iam/security-credentials/{}",
+            self.config.endpoint, role_name
+        );
         let mut request_builder = self.client.get(&credentials_url);
 
         if let Some(token) = token {
@@ -338,10 +386,23 @@ impl CredentialProvider for FromInstanceMetadata {
         }
 
         // Get IMDSv2 session token
-        let token = self.get_imds_v2_token().await?;
+        let (token, token_timed_out) = self.get_imds_v2_token().await?;
 
-        // Get available IAM roles
-        let roles = self.get_available_roles(token.as_deref()).await?;
+        if token.is_none() && token_timed_out && self.config.ec2_metadata_v1_disabled {
+            return Err(CredentialsProviderError::new(format!(
+                "IMDSv2 token request timed out and IMDSv1 fallback is disabled. {}",
+                HOP_LIMIT_HINT
+            )).into());
+        }
+
+        // Get available IAM roles; when the token request only ever timed
+        // out, the v1 fallback will usually fail the same way, so attach
+        // the hop-limit guidance to that failure
+        let roles = match self.get_available_roles(token.as_deref()).await {
+            Ok(roles) => roles,
+            Err(e) if token_timed_out => return Err(e.context(HOP_LIMIT_HINT)),
+            Err(e) => return Err(e),
+        };
 
         // Use the first available role
         let role_name = &roles[0];
@@ -547,6 +608,87 @@ mod tests {
         assert_eq!(provider.config.logger, Some("test-logger".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_instance_metadata_endpoint_override_from_env() {
+        let _endpoint = EnvVar::set(AWS_EC2_METADATA_SERVICE_ENDPOINT, "http://[fd00:ec2::254]/");
+
+        let config = InstanceMetadataConfig::default();
+        // Trailing slash stripped so URL joins stay clean
+        assert_eq!(config.endpoint, "http://[fd00:ec2::254]");
+    }
+
+    #[tokio::test]
+    async fn test_from_instance_metadata_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PUT"))
+            .and(path("/latest/api/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("imds-v2-token"))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/latest/meta-data/iam/security-credentials/"))
+            .and(header("X-aws-ec2-metadata-token", "imds-v2-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("my-instance-role\n"))
+            .mount(&mock_server)
+            .await;
+
+        let credentials_response = serde_json::json!({
+            "AccessKeyId": "imds_access_key",
+            "SecretAccessKey": "imds_secret_key",
+            "Token": "imds_session_token",
+            "Expiration": "2024-12-31T23:59:59Z",
+            "Code": "Success",
+            "LastUpdated": "2024-12-31T00:00:00Z",
+            "Type": "AWS-HMAC"
+        });
+
+        Mock::given(method("GET"))
+            .and(path("/latest/meta-data/iam/security-credentials/my-instance-role"))
+            .and(header("X-aws-ec2-metadata-token", "imds-v2-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(credentials_response))
+            .mount(&mock_server)
+            .await;
+
+        let provider = FromInstanceMetadata::new()
+            .with_endpoint(mock_server.uri())
+            .with_timeout(Duration::from_secs(5));
+        let credentials = provider.provide_credentials().await.unwrap();
+
+        assert_eq!(credentials.access_key_id, "imds_access_key");
+        assert_eq!(credentials.secret_access_key, "imds_secret_key");
+        assert_eq!(credentials.session_token, Some("imds_session_token".to_string()));
+        assert!(credentials.expiration.is_some());
+        assert_eq!(credentials.credential_provider, Some("CREDENTIALS_INSTANCE_METADATA".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_from_instance_metadata_hop_limit_guidance() {
+        let mock_server = MockServer::start().await;
+
+        // Token PUT never answers within the client timeout, mimicking a
+        // hop limit of 1 inside a container on EC2
+        Mock::given(method("PUT"))
+            .and(path("/latest/api/token"))
+            .respond_with(
+                ResponseTemplate::new(200).set_delay(Duration::from_secs(2)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let provider = FromInstanceMetadata::new()
+            .with_endpoint(mock_server.uri())
+            .with_timeout(Duration::from_millis(200))
+            .with_max_retries(0)
+            .with_ec2_metadata_v1_disabled(true);
+        let result = provider.provide_credentials().await;
+
+        assert!(result.is_err());
+        let error_msg = result.unwrap_err().to_string();
+        assert!(error_msg.contains("hop limit"), "unexpected error: {}", error_msg);
+    }
+
     #[tokio::test]
     async fn test_memoized_providers() {
         // Test that the convenience functions return memoized providers
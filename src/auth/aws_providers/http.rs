@@ -17,6 +17,12 @@ pub const AWS_CONTAINER_AUTHORIZATION_TOKEN: &str = "AWS_CONTAINER_AUTHORIZATION
 // Default container metadata endpoint
 pub const CONTAINER_METADATA_ENDPOINT: &str = "http://169.254.170.2";
 
+// Link-local hosts allowed over plain HTTP for full URIs: the ECS agent
+// and the EKS Pod Identity agent (IPv4 and IPv6)
+pub const ECS_CONTAINER_HOST: &str = "169.254.170.2";
+pub const EKS_CONTAINER_HOST: &str = "169.254.170.23";
+pub const EKS_CONTAINER_HOST_IPV6: &str = "fd00:ec2::23";
+
 /// HTTP response structure for container metadata credentials
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -115,7 +121,9 @@ impl FromHttp {
     /// Determine the URL to use for the request (JavaScript lines 861-871)
     fn get_credentials_url(&self) -> Result<Url> {
         if let Some(ref full_uri) = self.aws_container_credentials_full_uri {
-            return Ok(Url::parse(full_uri).context("Invalid full URI")?);
+            let url = Url::parse(full_uri).context("Invalid full URI")?;
+            Self::validate_full_uri_host(&url)?;
+            return Ok(url);
         }
 
         if let Some(ref relative_uri) = self.aws_container_credentials_relative_uri {
@@ -129,6 +137,34 @@ impl FromHttp {
         ).into())
     }
 
+    /// Validate the host of a full URI: HTTPS is allowed anywhere, plain
+    /// HTTP only for loopback and the link-local ECS / EKS Pod Identity
+    /// agent addresses
+    fn validate_full_uri_host(url: &Url) -> Result<()> {
+        if url.scheme() == "https" {
+            return Ok(());
+        }
+
+        let host = url.host_str().unwrap_or_default();
+        let allowed = host == "localhost"
+            || host == ECS_CONTAINER_HOST
+            || host == EKS_CONTAINER_HOST
+            || host.trim_matches(|c| c == '[' || c == ']') == EKS_CONTAINER_HOST_IPV6
+            || host
+                .parse::<std::net::IpAddr>()
+                .map(|ip| ip.is_loopback())
+                .unwrap_or(false);
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(CredentialsProviderError::new(format!(
+                "{} is not a valid container metadata service hostname for plain HTTP; use HTTPS, loopback, or the ECS/EKS agent addresses",
+                host
+            )).into())
+        }
+    }
+
     /// Get authorization headers (JavaScript lines 872-884)
     async fn get_authorization_header(&self) -> Result<Option<String>> {
         if let Some(ref token) = self.aws_container_authorization_token {
@@ -488,10 +524,31 @@ mod tests {
         assert_eq!(credentials.secret_access_key, "file_secret_key");
     }
 
+    #[tokio::test]
+    async fn test_full_uri_host_validation() {
+        // EKS Pod Identity agent addresses are allowed over plain HTTP
+        for uri in [
+            "http://169.254.170.2/v2/credentials",
+            "http://169.254.170.23/v1/credentials",
+            "http://[fd00:ec2::23]/v1/credentials",
+            "http://localhost:8080/credentials",
+            "https://example.com/credentials",
+        ] {
+            let url = Url::parse(uri).unwrap();
+            assert!(FromHttp::validate_full_uri_host(&url).is_ok(), "{} should be allowed", uri);
+        }
+
+        // Arbitrary hosts over plain HTTP are rejected
+        let url = Url::parse("http://example.com/credentials").unwrap();
+        let result = FromHttp::validate_full_uri_host(&url);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("not a valid container metadata service hostname"));
+    }
+
     #[tokio::test]
     async fn test_from_http_invalid_auth_token_file() {
         let provider = FromHttp::new()
-            .with_full_uri("http://example.com/credentials")
+            .with_full_uri("http://localhost/credentials")
             .with_auth_token_file("/nonexistent/token/file");
 
         let result = provider.provide_credentials().await;
@@ -173,9 +173,11 @@ where
 }
 
 // Re-export convenience functions for easy access
+pub use container::{from_container_metadata, from_instance_metadata, FromContainerMetadata, FromInstanceMetadata, InstanceMetadataConfig};
 pub use sts::{from_temporary_credentials, AssumeRoleParams, TemporaryCredentialsProvider};
-pub use web_identity::{from_web_token, from_token_file, WebTokenCredentialsProvider, TokenFileCredentialsProvider};
+pub use web_identity::{from_web_token, from_token_file, StsWebIdentityRoleAssumer, WebTokenCredentialsProvider, TokenFileCredentialsProvider};
 pub use sso::{from_sso, SsoCredentialsProvider, SsoCredentialsParams, is_sso_profile, validate_sso_profile};
+pub use sso::{resolve_login_target, write_sso_token_cache, DeviceAuthorization, RegisteredOidcClient, SsoOidcClient};
 pub use cognito::{from_cognito_identity, from_cognito_identity_pool, CognitoIdentityParams, CognitoIdentityPoolParams};
 
 /// Helper function to parse credential expiration from string
@@ -732,6 +732,317 @@ pub fn from_sso(params: SsoCredentialsParams) -> impl for<'a> Fn(Option<&'a Hash
     }
 }
 
+// ---------------------------------------------------------------------------
+// Device-code SSO login (`llminate auth aws-sso-login`)
+//
+// Everything above consumes a cached SSO access token; this section
+// produces one, so users don't need the AWS CLI installed. The flow is
+// the standard SSO-OIDC device grant: register a public client, start a
+// device authorization, hand the verification URL to the browser, poll
+// for the token, and write it to the same ~/.aws/sso/cache file (same
+// md5 cache key) that get_sso_access_token reads back.
+// ---------------------------------------------------------------------------
+
+/// A registered SSO-OIDC public client
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredOidcClient {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// An in-progress device authorization
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: Option<String>,
+    /// Seconds until the device code expires
+    pub expires_in: u64,
+    /// Suggested polling interval in seconds (default 5)
+    pub interval: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateTokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OidcErrorResponse {
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Minimal SSO-OIDC client covering the device authorization grant
+pub struct SsoOidcClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl SsoOidcClient {
+    pub fn new(region: &str) -> Self {
+        Self {
+            base_url: format!("https://oidc.{}.amazonaws.com", region),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// RegisterClient: create a short-lived public client for this login.
+    /// Scopes come from the sso-session's sso_registration_scopes when
+    /// configured; otherwise the SSO portal default applies
+    pub async fn register_client(&self, scopes: Option<&[String]>) -> Result<RegisteredOidcClient> {
+        let mut body = serde_json::json!({
+            "clientName": "llminate",
+            "clientType": "public",
+        });
+        if let Some(scopes) = scopes {
+            body["scopes"] = serde_json::json!(scopes);
+        }
+        let response = self
+            .http
+            .post(format!("{}/client/register", self.base_url))
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send SSO-OIDC RegisterClient request")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(CredentialsProviderError::new(format!(
+                "SSO-OIDC client registration failed: {} - {}",
+                status, text
+            ))
+            .into());
+        }
+        response
+            .json()
+            .await
+            .context("Failed to parse SSO-OIDC RegisterClient response")
+    }
+
+    /// StartDeviceAuthorization: begin the device grant for a start URL
+    pub async fn start_device_authorization(
+        &self,
+        client: &RegisteredOidcClient,
+        start_url: &str,
+    ) -> Result<DeviceAuthorization> {
+        let response = self
+            .http
+            .post(format!("{}/device_authorization", self.base_url))
+            .json(&serde_json::json!({
+                "clientId": client.client_id,
+                "clientSecret": client.client_secret,
+                "startUrl": start_url,
+            }))
+            .send()
+            .await
+            .context("Failed to send SSO-OIDC StartDeviceAuthorization request")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(CredentialsProviderError::new(format!(
+                "SSO-OIDC device authorization failed: {} - {}",
+                status, text
+            ))
+            .into());
+        }
+        response
+            .json()
+            .await
+            .context("Failed to parse SSO-OIDC StartDeviceAuthorization response")
+    }
+
+    /// CreateToken: poll until the user approves the device in the
+    /// browser. Returns the access token and its lifetime in seconds.
+    /// Honors `authorization_pending`/`slow_down` per the device grant
+    /// and gives up when the device code expires
+    pub async fn wait_for_token(
+        &self,
+        client: &RegisteredOidcClient,
+        authorization: &DeviceAuthorization,
+    ) -> Result<(String, u64)> {
+        let mut interval = authorization.interval.unwrap_or(5).max(1);
+        let deadline =
+            std::time::Instant::now() + std::time::Duration::from_secs(authorization.expires_in);
+
+        loop {
+            if std::time::Instant::now() >= deadline {
+                return Err(CredentialsProviderError::new(
+                    "SSO device authorization expired before it was approved",
+                )
+                .into());
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+
+            let response = self
+                .http
+                .post(format!("{}/token", self.base_url))
+                .json(&serde_json::json!({
+                    "clientId": client.client_id,
+                    "clientSecret": client.client_secret,
+                    "grantType": "urn:ietf:params:oauth:grant-type:device_code",
+                    "deviceCode": authorization.device_code,
+                }))
+                .send()
+                .await
+                .context("Failed to send SSO-OIDC CreateToken request")?;
+
+            if response.status().is_success() {
+                let token: CreateTokenResponse = response
+                    .json()
+                    .await
+                    .context("Failed to parse SSO-OIDC CreateToken response")?;
+                return Ok((token.access_token, token.expires_in));
+            }
+
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            let error: OidcErrorResponse =
+                serde_json::from_str(&text).unwrap_or(OidcErrorResponse {
+                    error: None,
+                    error_description: None,
+                });
+            match error.error.as_deref() {
+                // User hasn't approved yet: keep polling
+                Some("authorization_pending") => continue,
+                // Server asked us to back off
+                Some("slow_down") => {
+                    interval += 5;
+                    continue;
+                }
+                Some("expired_token") => {
+                    return Err(CredentialsProviderError::new(
+                        "SSO device authorization expired before it was approved",
+                    )
+                    .into());
+                }
+                Some("access_denied") => {
+                    return Err(CredentialsProviderError::new(
+                        "SSO login was denied in the browser",
+                    )
+                    .into());
+                }
+                _ => {
+                    return Err(CredentialsProviderError::new(format!(
+                        "SSO-OIDC token request failed: {} - {}",
+                        status,
+                        error.error_description.unwrap_or(text)
+                    ))
+                    .into());
+                }
+            }
+        }
+    }
+}
+
+/// Resolve the start URL, region, and registration scopes for a login:
+/// explicit flags win, otherwise they come from the AWS config profile
+/// (and its sso-session, when it references one)
+pub async fn resolve_login_target(
+    profile: Option<String>,
+    start_url: Option<String>,
+    region: Option<String>,
+) -> Result<(String, String, Option<Vec<String>>)> {
+    // Fully specified on the command line: no config files needed
+    if let (Some(start_url), Some(region)) = (start_url.clone(), region.clone()) {
+        return Ok((start_url, region, None));
+    }
+
+    let params = SsoCredentialsParams {
+        sso_start_url: None,
+        sso_account_id: None,
+        sso_region: None,
+        sso_role_name: None,
+        sso_session: None,
+        profile: profile.clone(),
+        sso_client: None,
+        client_config: None,
+        parent_client_config: None,
+        logger: None,
+    };
+    let profile_name = get_profile_name(profile.as_deref(), None);
+    let known_files = parse_known_files(&params).await?;
+    let profile_config = known_files.get(&profile_name).cloned().unwrap_or_default();
+
+    let mut resolved_start_url = start_url.or_else(|| profile_config.get("sso_start_url").cloned());
+    let mut resolved_region = region.or_else(|| profile_config.get("sso_region").cloned());
+    let mut scopes = None;
+
+    // An sso-session section carries the canonical start URL, region,
+    // and registration scopes
+    if let Some(session_name) = profile_config.get("sso_session") {
+        let sessions = load_sso_session_data(&params).await?;
+        if let Some(session) = sessions.get(session_name) {
+            if resolved_start_url.is_none() && !session.sso_start_url.is_empty() {
+                resolved_start_url = Some(session.sso_start_url.clone());
+            }
+            if resolved_region.is_none() && !session.sso_region.is_empty() {
+                resolved_region = Some(session.sso_region.clone());
+            }
+            scopes = session.sso_registration_scopes.clone();
+        }
+    }
+
+    match (resolved_start_url, resolved_region) {
+        (Some(start_url), Some(region)) => Ok((start_url, region, scopes)),
+        _ => Err(CredentialsProviderError::new(format!(
+            "Could not resolve an SSO start URL and region for profile '{}'. \
+             Pass --start-url and --region, or configure sso_start_url/sso_region \
+             (or an sso-session) in ~/.aws/config",
+            profile_name
+        ))
+        .into()),
+    }
+}
+
+/// Write the access token to the SSO cache file the credential provider
+/// reads (same md5 cache key as [`get_sso_access_token`]), in the AWS
+/// CLI's cache format so the two tools can share logins
+pub async fn write_sso_token_cache(
+    start_url: &str,
+    region: &str,
+    access_token: &str,
+    expires_in: u64,
+) -> Result<PathBuf> {
+    let cache_key = format!(
+        "{:x}",
+        md5::compute(format!("{}-{}", start_url, region).as_bytes())
+    );
+    let cache_file_path = get_sso_cache_file_path(&cache_key)?;
+    if let Some(parent) = cache_file_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .context("Failed to create SSO cache directory")?;
+    }
+
+    let expires_at = Utc::now() + chrono::Duration::seconds(expires_in as i64);
+    let cache_data = serde_json::json!({
+        "startUrl": start_url,
+        "region": region,
+        "accessToken": access_token,
+        "expiresAt": expires_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    });
+    let content =
+        serde_json::to_string(&cache_data).context("Failed to serialize SSO cache entry")?;
+    fs::write(&cache_file_path, content)
+        .await
+        .context("Failed to write SSO cache file")?;
+
+    // The file holds a bearer token: owner-only on platforms that can
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&cache_file_path, std::fs::Permissions::from_mode(0o600));
+    }
+
+    Ok(cache_file_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
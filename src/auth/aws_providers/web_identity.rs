@@ -91,6 +91,157 @@ impl FileReader for SystemFileReader {
     }
 }
 
+/// Endpoint override for the STS role assumer (AWS SDK convention)
+pub const AWS_ENDPOINT_URL_STS: &str = "AWS_ENDPOINT_URL_STS";
+
+/// JSON envelope STS returns for AssumeRoleWithWebIdentity when the
+/// request carries Accept: application/json
+#[derive(Debug, Deserialize)]
+struct StsJsonEnvelope {
+    #[serde(rename = "AssumeRoleWithWebIdentityResponse")]
+    response: StsJsonResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct StsJsonResponse {
+    #[serde(rename = "AssumeRoleWithWebIdentityResult")]
+    result: StsJsonResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct StsJsonResult {
+    #[serde(rename = "Credentials")]
+    credentials: Option<StsJsonCredentials>,
+    #[serde(rename = "AssumedRoleUser")]
+    assumed_role_user: Option<AssumedRoleUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StsJsonCredentials {
+    #[serde(rename = "AccessKeyId")]
+    access_key_id: Option<String>,
+    #[serde(rename = "SecretAccessKey")]
+    secret_access_key: Option<String>,
+    #[serde(rename = "SessionToken")]
+    session_token: Option<String>,
+    /// Epoch seconds in the JSON representation
+    #[serde(rename = "Expiration")]
+    expiration: Option<f64>,
+}
+
+/// Role assumer that calls the STS AssumeRoleWithWebIdentity API over
+/// HTTP. The call is unsigned — the web identity token itself is the
+/// proof of identity — so no master credentials are needed, which is
+/// exactly the EKS IRSA setup
+pub struct StsWebIdentityRoleAssumer {
+    endpoint: String,
+    http: reqwest::Client,
+}
+
+impl StsWebIdentityRoleAssumer {
+    /// Create an assumer targeting the regional STS endpoint, honoring
+    /// the AWS_ENDPOINT_URL_STS override
+    pub fn new() -> Self {
+        let endpoint = std::env::var(AWS_ENDPOINT_URL_STS)
+            .ok()
+            .filter(|e| !e.trim().is_empty())
+            .unwrap_or_else(|| {
+                let region = std::env::var("AWS_REGION")
+                    .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+                    .unwrap_or_else(|_| "us-east-1".to_string());
+                format!("https://sts.{}.amazonaws.com", region)
+            });
+
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Set a custom STS endpoint (useful for testing)
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+}
+
+impl Default for StsWebIdentityRoleAssumer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RoleAssumerWithWebIdentity for StsWebIdentityRoleAssumer {
+    async fn assume_role_with_web_identity(
+        &self,
+        request: AssumeRoleWithWebIdentityRequest,
+    ) -> Result<AssumeRoleWithWebIdentityResponse> {
+        let mut form: Vec<(String, String)> = vec![
+            ("Action".to_string(), "AssumeRoleWithWebIdentity".to_string()),
+            ("Version".to_string(), "2011-06-15".to_string()),
+            ("RoleArn".to_string(), request.role_arn.clone()),
+            ("RoleSessionName".to_string(), request.role_session_name.clone()),
+            ("WebIdentityToken".to_string(), request.web_identity_token.clone()),
+        ];
+
+        if let Some(ref provider_id) = request.provider_id {
+            form.push(("ProviderId".to_string(), provider_id.clone()));
+        }
+        if let Some(ref policy) = request.policy {
+            form.push(("Policy".to_string(), policy.clone()));
+        }
+        if let Some(ref policy_arns) = request.policy_arns {
+            for (i, arn) in policy_arns.iter().enumerate() {
+                form.push((format!("PolicyArns.member.{}.arn", i + 1), arn.clone()));
+            }
+        }
+        if let Some(duration_seconds) = request.duration_seconds {
+            form.push(("DurationSeconds".to_string(), duration_seconds.to_string()));
+        }
+
+        let response = self.http
+            .post(&self.endpoint)
+            .header("Accept", "application/json")
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| CredentialsProviderError::new(
+                format!("STS AssumeRoleWithWebIdentity request failed: {}", e)
+            ))?;
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+
+        if !status.is_success() {
+            return Err(CredentialsProviderError::new(format!(
+                "STS AssumeRoleWithWebIdentity failed with status {}: {}",
+                status,
+                body.chars().take(512).collect::<String>()
+            )).into());
+        }
+
+        let envelope: StsJsonEnvelope = serde_json::from_str(&body)
+            .map_err(|e| CredentialsProviderError::new(
+                format!("Failed to parse STS AssumeRoleWithWebIdentity response: {}", e)
+            ))?;
+
+        let credentials = envelope.response.result.credentials.map(|creds| StsCredentials {
+            access_key_id: creds.access_key_id,
+            secret_access_key: creds.secret_access_key,
+            session_token: creds.session_token,
+            expiration: creds.expiration
+                .and_then(|secs| DateTime::from_timestamp(secs as i64, 0)),
+            credential_scope: None,
+        });
+
+        Ok(AssumeRoleWithWebIdentityResponse {
+            credentials,
+            assumed_role_user: envelope.response.result.assumed_role_user,
+        })
+    }
+}
+
 /// Web token credentials provider
 ///
 /// This implements the fromWebToken functionality from the JavaScript code
@@ -269,6 +420,7 @@ pub struct TokenFileCredentialsProvider<E: EnvReader = SystemEnvReader, F: FileR
     role_session_name: Option<String>,
     env_reader: E,
     file_reader: F,
+    role_assumer: Option<Arc<dyn RoleAssumerWithWebIdentity>>,
     logger: Option<String>,
 }
 
@@ -281,6 +433,7 @@ impl TokenFileCredentialsProvider<SystemEnvReader, SystemFileReader> {
             role_session_name: None,
             env_reader: SystemEnvReader,
             file_reader: SystemFileReader,
+            role_assumer: None,
             logger: None,
         }
     }
@@ -293,6 +446,7 @@ impl TokenFileCredentialsProvider<SystemEnvReader, SystemFileReader> {
             role_session_name: None,
             env_reader: SystemEnvReader,
             file_reader: SystemFileReader,
+            role_assumer: None,
             logger: Some(logger.into()),
         }
     }
@@ -307,6 +461,7 @@ impl<E: EnvReader, F: FileReader> TokenFileCredentialsProvider<E, F> {
             role_session_name: None,
             env_reader,
             file_reader,
+            role_assumer: None,
             logger: None,
         }
     }
@@ -319,10 +474,17 @@ impl<E: EnvReader, F: FileReader> TokenFileCredentialsProvider<E, F> {
             role_session_name: None,
             env_reader,
             file_reader,
+            role_assumer: None,
             logger: Some(logger.into()),
         }
     }
 
+    /// Set a custom role assumer (defaults to the real STS caller)
+    pub fn with_role_assumer(mut self, role_assumer: Arc<dyn RoleAssumerWithWebIdentity>) -> Self {
+        self.role_assumer = Some(role_assumer);
+        self
+    }
+
     /// Set the web identity token file path
     pub fn with_web_identity_token_file(mut self, web_identity_token_file: String) -> Self {
         self.web_identity_token_file = Some(web_identity_token_file);
@@ -398,23 +560,25 @@ impl<E: EnvReader, F: FileReader> CredentialProvider for TokenFileCredentialsPro
         }
 
         // Create WebTokenCredentialsProvider and get credentials (JavaScript lines 218-225)
+        let role_assumer = self.role_assumer.clone()
+            .unwrap_or_else(|| Arc::new(StsWebIdentityRoleAssumer::new()));
+
         let web_token_provider = WebTokenCredentialsProvider::new(
             web_identity_token,
             role_arn,
             role_session_name.unwrap_or_else(|| format!("aws-sdk-js-session-{}", chrono::Utc::now().timestamp_millis())),
-        );
+        ).with_role_assumer(role_assumer);
 
-        // For this implementation, we need a role assumer. In a real implementation,
-        // this would be provided or created by default. For now, we'll return an error.
-        return Err(CredentialsProviderError::new(
-            "Role assumer with web identity is required for token file provider"
-        ).into());
+        let mut credentials = web_token_provider.provide_credentials().await?;
 
-        // JavaScript lines 226-232 show setting credential feature for env vars usage
-        // This would be implemented when we have the actual credentials:
-        // if token_file == self.env_reader.get_var(AWS_WEB_IDENTITY_TOKEN_FILE).as_deref() {
-        //     credentials = credentials.set_credential_feature("CREDENTIALS_ENV_VARS_STS_WEB_ID_TOKEN", "h");
-        // }
+        // Track env-var sourced token files (JavaScript lines 226-232)
+        if self.web_identity_token_file.is_none()
+            && self.env_reader.get_var(AWS_WEB_IDENTITY_TOKEN_FILE).as_deref() == Some(token_file.as_str())
+        {
+            credentials = credentials.set_credential_feature("CREDENTIALS_ENV_VARS_STS_WEB_ID_TOKEN", "h");
+        }
+
+        Ok(credentials)
     }
 }
 
@@ -715,7 +879,23 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_token_file_credentials_requires_role_assumer() {
+    async fn test_token_file_credentials_success_from_env() {
+        let response = AssumeRoleWithWebIdentityResponse {
+            credentials: Some(StsCredentials {
+                access_key_id: Some("irsa_access_key".to_string()),
+                secret_access_key: Some("irsa_secret_key".to_string()),
+                session_token: Some("irsa_session_token".to_string()),
+                expiration: Some(Utc::now() + chrono::Duration::hours(1)),
+                credential_scope: None,
+            }),
+            assumed_role_user: None,
+        };
+
+        let role_assumer = Arc::new(
+            MockRoleAssumerWithWebIdentity::new()
+                .with_response("arn:aws:iam::123456789012:role/test-role", response)
+        );
+
         let env_reader = MockEnvReader::new()
             .with_var(AWS_WEB_IDENTITY_TOKEN_FILE, "/path/to/token")
             .with_var(AWS_ROLE_ARN, "arn:aws:iam::123456789012:role/test-role")
@@ -723,16 +903,35 @@ mod tests {
         let file_reader = MockFileReader::new()
             .with_file("/path/to/token", "mock-token");
 
-        let provider = TokenFileCredentialsProvider::with_readers(env_reader, file_reader);
-        let result = provider.provide_credentials().await;
+        let provider = TokenFileCredentialsProvider::with_readers(env_reader, file_reader)
+            .with_role_assumer(role_assumer);
+        let credentials = provider.provide_credentials().await.unwrap();
 
-        assert!(result.is_err());
-        let error_msg = result.unwrap_err().to_string();
-        assert!(error_msg.contains("Role assumer with web identity is required"));
+        assert_eq!(credentials.access_key_id, "irsa_access_key");
+        assert_eq!(credentials.secret_access_key, "irsa_secret_key");
+        assert_eq!(credentials.session_token, Some("irsa_session_token".to_string()));
+        // Env-var sourced token file is tracked as such
+        assert_eq!(credentials.credential_provider, Some("CREDENTIALS_ENV_VARS_STS_WEB_ID_TOKEN".to_string()));
     }
 
     #[tokio::test]
     async fn test_token_file_credentials_with_provided_values() {
+        let response = AssumeRoleWithWebIdentityResponse {
+            credentials: Some(StsCredentials {
+                access_key_id: Some("custom_access_key".to_string()),
+                secret_access_key: Some("custom_secret_key".to_string()),
+                session_token: None,
+                expiration: None,
+                credential_scope: None,
+            }),
+            assumed_role_user: None,
+        };
+
+        let role_assumer = Arc::new(
+            MockRoleAssumerWithWebIdentity::new()
+                .with_response("arn:aws:iam::123456789012:role/test-role", response)
+        );
+
         let env_reader = MockEnvReader::new();
         let file_reader = MockFileReader::new()
             .with_file("/custom/path/token", "mock-token");
@@ -740,14 +939,67 @@ mod tests {
         let provider = TokenFileCredentialsProvider::with_readers(env_reader, file_reader)
             .with_web_identity_token_file("/custom/path/token".to_string())
             .with_role_arn("arn:aws:iam::123456789012:role/test-role".to_string())
-            .with_role_session_name("custom-session".to_string());
+            .with_role_session_name("custom-session".to_string())
+            .with_role_assumer(role_assumer);
 
-        let result = provider.provide_credentials().await;
+        let credentials = provider.provide_credentials().await.unwrap();
 
-        assert!(result.is_err());
-        // Should still fail due to missing role assumer, but not due to config
-        let error_msg = result.unwrap_err().to_string();
-        assert!(error_msg.contains("Role assumer with web identity is required"));
+        assert_eq!(credentials.access_key_id, "custom_access_key");
+        assert_eq!(credentials.secret_access_key, "custom_secret_key");
+        // Explicitly provided token file is not flagged as env-var sourced
+        assert_eq!(credentials.credential_provider, Some("CREDENTIALS_STS_ASSUME_ROLE_WEB_ID".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_sts_web_identity_role_assumer_parses_json_response() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use wiremock::matchers::{body_string_contains, header, method};
+
+        let mock_server = MockServer::start().await;
+
+        let sts_response = serde_json::json!({
+            "AssumeRoleWithWebIdentityResponse": {
+                "AssumeRoleWithWebIdentityResult": {
+                    "Credentials": {
+                        "AccessKeyId": "sts_access_key",
+                        "SecretAccessKey": "sts_secret_key",
+                        "SessionToken": "sts_session_token",
+                        "Expiration": 4102444800.0_f64
+                    },
+                    "AssumedRoleUser": {
+                        "Arn": "arn:aws:sts::123456789012:assumed-role/test-role/session",
+                        "AssumedRoleId": "AROA123456789012:session"
+                    }
+                },
+                "ResponseMetadata": { "RequestId": "test" }
+            }
+        });
+
+        Mock::given(method("POST"))
+            .and(header("Accept", "application/json"))
+            .and(body_string_contains("Action=AssumeRoleWithWebIdentity"))
+            .and(body_string_contains("WebIdentityToken=mock-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(sts_response))
+            .mount(&mock_server)
+            .await;
+
+        let assumer = StsWebIdentityRoleAssumer::new().with_endpoint(mock_server.uri());
+        let response = assumer.assume_role_with_web_identity(AssumeRoleWithWebIdentityRequest {
+            role_arn: "arn:aws:iam::123456789012:role/test-role".to_string(),
+            role_session_name: "test-session".to_string(),
+            web_identity_token: "mock-token".to_string(),
+            provider_id: None,
+            policy_arns: None,
+            policy: None,
+            duration_seconds: None,
+        }).await.unwrap();
+
+        let credentials = response.credentials.unwrap();
+        assert_eq!(credentials.access_key_id, Some("sts_access_key".to_string()));
+        assert_eq!(credentials.secret_access_key, Some("sts_secret_key".to_string()));
+        assert_eq!(credentials.session_token, Some("sts_session_token".to_string()));
+        assert!(credentials.expiration.is_some());
+        assert!(response.assumed_role_user.is_some());
     }
 
     #[test]
@@ -0,0 +1,394 @@
+//! Azure AD credential provider for Azure-hosted gateways.
+//!
+//! Corporate proxies fronting Claude with Azure API Management (or the
+//! Azure OpenAI-compatible surface) authenticate with AAD bearer tokens
+//! instead of Anthropic API keys. Two token sources are supported:
+//!
+//! - **Client credentials**: AZURE_TENANT_ID + AZURE_CLIENT_ID +
+//!   AZURE_CLIENT_SECRET against login.microsoftonline.com
+//! - **Managed identity**: the App Service identity endpoint
+//!   (IDENTITY_ENDPOINT/IDENTITY_HEADER) or the IMDS endpoint on Azure
+//!   VMs, opted into with AZURE_USE_MANAGED_IDENTITY=true
+//!
+//! Tokens are cached per process and refreshed shortly before expiry.
+//! Azure endpoints additionally require an `api-version` query parameter
+//! (AZURE_API_VERSION, default 2024-06-01), appended by the client when
+//! the base URL points at an Azure gateway.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+/// Default AAD scope for Azure Cognitive Services (covers Azure OpenAI
+/// and Azure-hosted model gateways)
+const DEFAULT_SCOPE: &str = "https://cognitiveservices.azure.com/.default";
+
+/// Default Azure api-version query value
+const DEFAULT_API_VERSION: &str = "2024-06-01";
+
+/// Refresh tokens this long before they actually expire
+const EXPIRY_BUFFER_SECS: u64 = 300;
+
+/// IMDS token endpoint on Azure VMs
+const IMDS_TOKEN_ENDPOINT: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+
+/// Token response shared by the AAD and managed identity endpoints
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    /// Seconds until expiry (AAD) - managed identity returns a string,
+    /// so accept both
+    #[serde(deserialize_with = "deserialize_expires_in")]
+    expires_in: u64,
+}
+
+fn deserialize_expires_in<'de, D>(deserializer: D) -> std::result::Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(u64),
+        String(String),
+    }
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(n) => Ok(n),
+        NumberOrString::String(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+/// A cached bearer token with its expiry time (unix seconds)
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    expires_at: u64,
+}
+
+impl CachedToken {
+    fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now + EXPIRY_BUFFER_SECS >= self.expires_at
+    }
+}
+
+/// How to obtain an AAD token
+#[derive(Debug, Clone)]
+enum TokenSource {
+    /// OAuth2 client credentials grant against login.microsoftonline.com
+    ClientCredentials {
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+        scope: String,
+    },
+    /// Azure managed identity (App Service identity endpoint or IMDS)
+    ManagedIdentity {
+        /// User-assigned identity client id, when not system-assigned
+        client_id: Option<String>,
+        resource: String,
+    },
+}
+
+/// Azure AD credential provider with per-process token caching
+pub struct AzureAdProvider {
+    source: TokenSource,
+    cache: Mutex<Option<CachedToken>>,
+}
+
+impl AzureAdProvider {
+    /// Build a provider from the environment, when one is configured:
+    /// client credentials first, then managed identity
+    pub fn from_env() -> Option<Self> {
+        let scope = std::env::var("AZURE_AD_SCOPE")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| DEFAULT_SCOPE.to_string());
+
+        if let (Ok(tenant_id), Ok(client_id), Ok(client_secret)) = (
+            std::env::var("AZURE_TENANT_ID"),
+            std::env::var("AZURE_CLIENT_ID"),
+            std::env::var("AZURE_CLIENT_SECRET"),
+        ) {
+            if !tenant_id.is_empty() && !client_id.is_empty() && !client_secret.is_empty() {
+                return Some(Self {
+                    source: TokenSource::ClientCredentials {
+                        tenant_id,
+                        client_id,
+                        client_secret,
+                        scope,
+                    },
+                    cache: Mutex::new(None),
+                });
+            }
+        }
+
+        let managed_requested = std::env::var("AZURE_USE_MANAGED_IDENTITY")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+            || std::env::var("IDENTITY_ENDPOINT").map(|v| !v.is_empty()).unwrap_or(false);
+
+        if managed_requested {
+            // Managed identity wants the bare resource, not the /.default scope
+            let resource = scope.trim_end_matches("/.default").trim_end_matches('/').to_string();
+            return Some(Self {
+                source: TokenSource::ManagedIdentity {
+                    client_id: std::env::var("AZURE_CLIENT_ID").ok().filter(|s| !s.is_empty()),
+                    resource,
+                },
+                cache: Mutex::new(None),
+            });
+        }
+
+        None
+    }
+
+    /// A valid bearer token, from cache or freshly acquired
+    pub async fn bearer_token(&self) -> Result<String> {
+        let mut cache = self.cache.lock().await;
+        if let Some(token) = cache.as_ref() {
+            if !token.is_expired() {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let response = match &self.source {
+            TokenSource::ClientCredentials {
+                tenant_id,
+                client_id,
+                client_secret,
+                scope,
+            } => Self::fetch_client_credentials(tenant_id, client_id, client_secret, scope).await?,
+            TokenSource::ManagedIdentity { client_id, resource } => {
+                Self::fetch_managed_identity(client_id.as_deref(), resource).await?
+            }
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let token = CachedToken {
+            access_token: response.access_token,
+            expires_at: now + response.expires_in,
+        };
+        let access_token = token.access_token.clone();
+        *cache = Some(token);
+        Ok(access_token)
+    }
+
+    /// OAuth2 client credentials grant
+    async fn fetch_client_credentials(
+        tenant_id: &str,
+        client_id: &str,
+        client_secret: &str,
+        scope: &str,
+    ) -> Result<TokenResponse> {
+        let url = format!(
+            "https://login.microsoftonline.com/{}/oauth2/v2.0/token",
+            tenant_id
+        );
+        debug!("Requesting Azure AD token via client credentials for scope {}", scope);
+
+        let response = reqwest::Client::new()
+            .post(&url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+                ("scope", scope),
+            ])
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .context("Failed to reach Azure AD token endpoint")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error".to_string());
+            return Err(anyhow!(
+                "Azure AD token request failed with status {}: {}",
+                status,
+                text
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse Azure AD token response")
+    }
+
+    /// Managed identity token: App Service identity endpoint when
+    /// IDENTITY_ENDPOINT is set, the IMDS endpoint otherwise
+    async fn fetch_managed_identity(
+        client_id: Option<&str>,
+        resource: &str,
+    ) -> Result<TokenResponse> {
+        let client = reqwest::Client::new();
+
+        let request = if let Ok(endpoint) = std::env::var("IDENTITY_ENDPOINT") {
+            debug!("Requesting managed identity token from identity endpoint");
+            let mut request = client
+                .get(&endpoint)
+                .query(&[("api-version", "2019-08-01"), ("resource", resource)]);
+            if let Ok(header) = std::env::var("IDENTITY_HEADER") {
+                request = request.header("X-IDENTITY-HEADER", header);
+            }
+            request
+        } else {
+            debug!("Requesting managed identity token from IMDS");
+            client
+                .get(IMDS_TOKEN_ENDPOINT)
+                .query(&[("api-version", "2018-02-01"), ("resource", resource)])
+                .header("Metadata", "true")
+        };
+
+        let request = if let Some(client_id) = client_id {
+            request.query(&[("client_id", client_id)])
+        } else {
+            request
+        };
+
+        let response = request
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .context("Failed to reach managed identity endpoint")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error".to_string());
+            return Err(anyhow!(
+                "Managed identity token request failed with status {}: {}",
+                status,
+                text
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse managed identity token response")
+    }
+}
+
+/// Whether the base URL points at an Azure gateway that expects AAD
+/// bearer tokens and the api-version query parameter. AZURE_AD_GATEWAY=true
+/// forces it for gateways on custom domains
+pub fn is_azure_gateway(base_url: &str) -> bool {
+    if std::env::var("AZURE_AD_GATEWAY")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+    {
+        return true;
+    }
+    let host = url::Url::parse(base_url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()));
+    match host {
+        Some(host) => {
+            host.ends_with(".azure.com")
+                || host.ends_with(".azure-api.net")
+                || host.ends_with(".azure.us")
+                || host.ends_with(".openai.azure.com")
+        }
+        None => false,
+    }
+}
+
+/// A bearer token for the gateway, when the base URL is an Azure
+/// gateway and AAD credentials are configured. The provider (and its
+/// token cache) is shared process-wide
+pub async fn bearer_token_for(base_url: &str) -> Result<Option<String>> {
+    if !is_azure_gateway(base_url) {
+        return Ok(None);
+    }
+
+    static PROVIDER: std::sync::OnceLock<Option<AzureAdProvider>> = std::sync::OnceLock::new();
+    match PROVIDER.get_or_init(AzureAdProvider::from_env) {
+        Some(provider) => provider.bearer_token().await.map(Some),
+        None => Err(anyhow!(
+            "Base URL {} looks like an Azure gateway but no Azure AD credentials are configured. \
+             Set AZURE_TENANT_ID/AZURE_CLIENT_ID/AZURE_CLIENT_SECRET or AZURE_USE_MANAGED_IDENTITY=true",
+            base_url
+        )),
+    }
+}
+
+/// The api-version query value for Azure endpoints
+pub fn api_version() -> String {
+    std::env::var("AZURE_API_VERSION")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_API_VERSION.to_string())
+}
+
+/// Append the api-version query parameter to a URL that lacks one
+pub fn append_api_version(url: &mut String) {
+    if url.contains("api-version=") {
+        return;
+    }
+    let separator = if url.contains('?') { '&' } else { '?' };
+    url.push(separator);
+    url.push_str("api-version=");
+    url.push_str(&urlencoding::encode(&api_version()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_azure_gateway_by_host() {
+        assert!(is_azure_gateway("https://myresource.openai.azure.com/v1"));
+        assert!(is_azure_gateway("https://gw.azure-api.net/anthropic"));
+        assert!(!is_azure_gateway("https://api.anthropic.com/v1"));
+        assert!(!is_azure_gateway("not a url"));
+    }
+
+    #[test]
+    fn test_append_api_version() {
+        let mut url = "https://gw.azure-api.net/v1/messages".to_string();
+        append_api_version(&mut url);
+        assert!(url.contains("?api-version="));
+
+        let mut url = "https://gw.azure-api.net/v1/messages?beta=true".to_string();
+        append_api_version(&mut url);
+        assert!(url.contains("&api-version="));
+
+        // Never doubled
+        let before = url.clone();
+        append_api_version(&mut url);
+        assert_eq!(url, before);
+    }
+
+    #[test]
+    fn test_token_expiry_buffer() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let fresh = CachedToken {
+            access_token: "t".to_string(),
+            expires_at: now + 3600,
+        };
+        assert!(!fresh.is_expired());
+        let nearly = CachedToken {
+            access_token: "t".to_string(),
+            expires_at: now + 60,
+        };
+        assert!(nearly.is_expired());
+    }
+}
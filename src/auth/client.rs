@@ -59,6 +59,43 @@ pub struct CountTokensRequest {
     pub betas: Option<Vec<String>>,
 }
 
+/// One request in a Messages Batch: the caller's id plus full message
+/// params, echoed back with the matching result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRequestItem {
+    /// Caller-chosen id correlating this request with its result line
+    pub custom_id: String,
+    /// The message request to process
+    pub params: crate::ai::ChatRequest,
+}
+
+/// Per-state request counts reported for a message batch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchRequestCounts {
+    #[serde(default)]
+    pub processing: u64,
+    #[serde(default)]
+    pub succeeded: u64,
+    #[serde(default)]
+    pub errored: u64,
+    #[serde(default)]
+    pub canceled: u64,
+    #[serde(default)]
+    pub expired: u64,
+}
+
+/// A message batch as returned by the Batches API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageBatch {
+    pub id: String,
+    /// "in_progress", "canceling", or "ended"
+    pub processing_status: String,
+    #[serde(default)]
+    pub request_counts: BatchRequestCounts,
+    /// Set once processing has ended; serves the JSONL results
+    pub results_url: Option<String>,
+}
+
 /// Token counting response
 /// JavaScript returns: { input_tokens: number }
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -307,8 +344,10 @@ impl AnthropicClient {
         let mut final_config = config;
         final_config.log_level = log_level;
 
-        // Build HTTP client with proxy support
-        let mut client_builder = HttpClient::builder()
+        // Build HTTP client with proxy support (and any configured mTLS
+        // client certificate, via the shared factory)
+        let mut client_builder = crate::utils::http::client_builder()
+            .map_err(|e| anyhow!("{}", e))?
             .timeout(final_config.timeout);
 
         // Add proxy if configured
@@ -680,6 +719,18 @@ impl AnthropicClient {
             }
         }
 
+        // Gateway header passthrough (customHeaders settings and
+        // ANTHROPIC_CUSTOM_HEADERS)
+        let mut custom_headers = HeaderMap::new();
+        for (name, value) in crate::config::get_custom_headers("anthropic") {
+            if let (Ok(header_name), Ok(header_value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                custom_headers.insert(header_name, header_value);
+            }
+        }
+
         // Merge all headers using YB equivalent
         let merged = merge_headers(vec![
             Some(idempotency_headers),
@@ -688,6 +739,7 @@ impl AnthropicClient {
             Some(proxy_headers),  // Add proxy headers
             Some(self.config.default_headers.clone()),
             Some(body_headers),
+            Some(custom_headers),
             options.headers.clone(),
         ]);
 
@@ -757,6 +809,25 @@ impl AnthropicClient {
     // prepareOptions implementation
     async fn prepare_options(&self, options: RequestOptions) -> Result<RequestOptions> {
         // Options preparation from JavaScript
+        let mut options = options;
+
+        // Azure gateways authenticate with AAD bearer tokens and require
+        // the api-version query parameter (see auth::azure)
+        if let Some(token) = super::azure::bearer_token_for(&self.config.base_url).await? {
+            let mut headers = options.headers.take().unwrap_or_default();
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token))?,
+            );
+            options.headers = Some(headers);
+
+            let mut query = options.query.take().unwrap_or_default();
+            query
+                .entry("api-version".to_string())
+                .or_insert_with(super::azure::api_version);
+            options.query = Some(query);
+        }
+
         Ok(options)
     }
 
@@ -860,7 +931,14 @@ impl AnthropicClient {
         // } else {
         //     format!("{}/messages", self.config.base_url)
         // };
-        let url = format!("{}/messages", self.config.base_url);
+        let mut url = format!("{}/messages", self.config.base_url);
+
+        // Azure gateways authenticate with AAD bearer tokens and require
+        // the api-version query parameter (see auth::azure)
+        let azure_token = super::azure::bearer_token_for(&self.config.base_url).await?;
+        if azure_token.is_some() {
+            super::azure::append_api_version(&mut url);
+        }
 
         info!("=== SENDING MESSAGE REQUEST TO LLM ===");
         info!("URL: {}", url);
@@ -891,7 +969,10 @@ impl AnthropicClient {
         //     info!("anthropic-beta header: {}", beta_header);
         //     headers.insert("anthropic-beta", HeaderValue::from_str(&beta_header)?);
         // } else
-        if let Some(ref api_key) = &self.config.api_key {
+        if let Some(ref token) = azure_token {
+            info!("Using Azure AD bearer token authentication");
+            headers.insert("authorization", HeaderValue::from_str(&format!("Bearer {}", token))?);
+        } else if let Some(ref api_key) = &self.config.api_key {
             info!("Using API key authentication");
             headers.insert("x-api-key", HeaderValue::from_str(api_key)?);
 
@@ -903,10 +984,25 @@ impl AnthropicClient {
             let beta_header = betas.join(",");
             info!("anthropic-beta header: {}", beta_header);
             headers.insert("anthropic-beta", HeaderValue::from_str(&beta_header)?);
+        } else if let Some(ref auth_token) = self.config.auth_token {
+            // Bearer token from an authTokenHelper command (corporate gateways)
+            info!("Using bearer token authentication");
+            headers.insert("authorization", HeaderValue::from_str(&format!("Bearer {}", auth_token))?);
         } else {
             return Err(anyhow!("No authentication credentials available. Please set ANTHROPIC_API_KEY environment variable."));
         }
 
+        // Gateway header passthrough (customHeaders settings and
+        // ANTHROPIC_CUSTOM_HEADERS), applied last so they win
+        for (name, value) in crate::config::get_custom_headers("anthropic") {
+            if let (Ok(header_name), Ok(header_value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                headers.insert(header_name, header_value);
+            }
+        }
+
         // Log all headers being sent
         info!("=== REQUEST HEADERS ===");
         for (name, value) in headers.iter() {
@@ -1003,142 +1099,268 @@ impl AnthropicClient {
         Ok(result)
     }
 
-    // ===== Helper methods for OAuth metadata (matching JavaScript behavior) =====
-    // OAUTH DISABLED: Anthropic has disabled 3rd party OAuth support for Claude Code CLI
-    // These methods are kept commented out for potential future re-enablement.
-
-    // /// Get or generate user ID (matches JavaScript variable22486 at line 530737-530745)
-    // /// JavaScript stores userID in state and generates a 32-byte hex random string if not present
-    // fn get_or_generate_user_id() -> String {
-    //     // Check environment variable first
-    //     if let Ok(user_id) = std::env::var("CLAUDE_CODE_USER_ID") {
-    //         if !user_id.is_empty() {
-    //             return user_id;
-    //         }
-    //     }
-    //
-    //     // Try to read from config file
-    //     if let Some(home) = dirs::home_dir() {
-    //         let state_file = home.join(".claude").join("state.json");
-    //         if let Ok(content) = std::fs::read_to_string(&state_file) {
-    //             if let Ok(state) = serde_json::from_str::<serde_json::Value>(&content) {
-    //                 if let Some(user_id) = state.get("userID").and_then(|v| v.as_str()) {
-    //                     if !user_id.is_empty() {
-    //                         return user_id.to_string();
-    //                     }
-    //                 }
-    //             }
-    //         }
-    //     }
-    //
-    //     // Generate new 32-byte hex ID (JavaScript: zX7(32).toString("hex"))
-    //     use rand::Rng;
-    //     let mut rng = rand::thread_rng();
-    //     let bytes: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
-    //     hex::encode(bytes)
-    // }
-
-    // /// Get account UUID from OAuth (matches JavaScript variable26865()?.accountUuid)
-    // fn get_account_uuid() -> String {
-    //     // Check environment variable first
-    //     if let Ok(account_uuid) = std::env::var("CLAUDE_CODE_ACCOUNT_UUID") {
-    //         if !account_uuid.is_empty() {
-    //             info!("get_account_uuid: found in env var: {}", account_uuid);
-    //             return account_uuid;
-    //         }
-    //     }
-    //
-    //     // CRITICAL: Try to read from macOS keychain FIRST (this is where OAuth credentials are stored!)
-    //     // The storage backend saves credentials to keychain, NOT plaintext files
-    //     #[cfg(target_os = "macos")]
-    //     {
-    //         let service_name = "Claude Code-credentials";
-    //         let username = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-    //
-    //         if let Ok(output) = std::process::Command::new("security")
-    //             .args(&[
-    //                 "find-generic-password",
-    //                 "-a", &username,
-    //                 "-s", service_name,
-    //                 "-w"  // Only output the password (the credentials JSON)
-    //             ])
-    //             .output()
-    //         {
-    //             if output.status.success() {
-    //                 let creds_json = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    //                 if let Ok(creds) = serde_json::from_str::<serde_json::Value>(&creds_json) {
-    //                     if let Some(oauth) = creds.get("claudeAiOauth") {
-    //                         if let Some(account_uuid) = oauth.get("accountUuid").and_then(|v| v.as_str()) {
-    //                             if !account_uuid.is_empty() {
-    //                                 info!("get_account_uuid: found in keychain: {}", account_uuid);
-    //                                 return account_uuid.to_string();
-    //                             }
-    //                         }
-    //                     }
-    //                 }
-    //             }
-    //         }
-    //     }
-    //
-    //     // Fallback: Try to read from credentials file (plaintext storage)
-    //     if let Some(home) = dirs::home_dir() {
-    //         // Try .claude/.credentials.json
-    //         let creds_file = home.join(".claude").join(".credentials.json");
-    //         if let Ok(content) = std::fs::read_to_string(&creds_file) {
-    //             if let Ok(creds) = serde_json::from_str::<serde_json::Value>(&content) {
-    //                 if let Some(oauth) = creds.get("claudeAiOauth") {
-    //                     if let Some(account_uuid) = oauth.get("accountUuid").and_then(|v| v.as_str()) {
-    //                         if !account_uuid.is_empty() {
-    //                             info!("get_account_uuid: found in .credentials.json: {}", account_uuid);
-    //                             return account_uuid.to_string();
-    //                         }
-    //                     }
-    //                 }
-    //             }
-    //         }
-    //
-    //         // Try state.json oauthAccount
-    //         let state_file = home.join(".claude").join("state.json");
-    //         if let Ok(content) = std::fs::read_to_string(&state_file) {
-    //             if let Ok(state) = serde_json::from_str::<serde_json::Value>(&content) {
-    //                 if let Some(oauth_account) = state.get("oauthAccount") {
-    //                     if let Some(account_uuid) = oauth_account.get("accountUuid").and_then(|v| v.as_str()) {
-    //                         if !account_uuid.is_empty() {
-    //                             info!("get_account_uuid: found in state.json: {}", account_uuid);
-    //                             return account_uuid.to_string();
-    //                         }
-    //                     }
-    //                 }
-    //             }
-    //         }
-    //     }
-    //
-    //     // Return empty if not found (JavaScript uses "" when not available)
-    //     info!("get_account_uuid: NOT FOUND - returning empty string");
-    //     String::new()
-    // }
-
-    // /// Get session ID (matches JavaScript variable8987 at line 2028)
-    // /// JavaScript stores sessionId in global state, generated once per session
-    // fn get_session_id() -> String {
-    //     // Check environment variable first (JavaScript also uses CLAUDE_CODE_SESSION_ID)
-    //     if let Ok(session_id) = std::env::var("CLAUDE_CODE_SESSION_ID") {
-    //         if !session_id.is_empty() {
-    //             return session_id;
-    //         }
-    //     }
-    //
-    //     // Generate a session ID (JavaScript uses EP0() which creates a UUID-like ID)
-    //     // Using process ID + timestamp for uniqueness within this process
-    //     use std::time::{SystemTime, UNIX_EPOCH};
-    //     let timestamp = SystemTime::now()
-    //         .duration_since(UNIX_EPOCH)
-    //         .unwrap_or_default()
-    //         .as_millis();
-    //     let pid = std::process::id();
-    //
-    //     format!("{:x}{:x}", timestamp, pid)
-    // }
+    /// Headers shared by the Messages Batches endpoints
+    /// Header: anthropic-beta: message-batches-2024-09-24
+    fn batch_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert("accept", HeaderValue::from_static("application/json"));
+        headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("x-app", HeaderValue::from_static("cli"));
+        headers.insert(
+            "anthropic-beta",
+            HeaderValue::from_static("message-batches-2024-09-24"),
+        );
+        if let Some(ref api_key) = self.config.api_key {
+            headers.insert(
+                "x-api-key",
+                HeaderValue::from_str(api_key).context("Invalid API key")?,
+            );
+        }
+        Ok(headers)
+    }
+
+    /// Submit a message batch for asynchronous processing
+    /// Endpoint: POST /v1/messages/batches
+    pub async fn create_message_batch(
+        &self,
+        requests: &[BatchRequestItem],
+    ) -> Result<MessageBatch> {
+        let url = format!("{}/v1/messages/batches", self.config.base_url);
+        let response = self
+            .http_client
+            .post(&url)
+            .headers(self.batch_headers()?)
+            .json(&json!({ "requests": requests }))
+            .send()
+            .await
+            .context("Failed to send create batch request")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_else(|_| "Failed to read error".to_string());
+            return Err(anyhow!("create_message_batch failed with status {}: {}", status, text));
+        }
+
+        response.json().await.context("Failed to parse batch response")
+    }
+
+    /// Poll a message batch's processing status
+    /// Endpoint: GET /v1/messages/batches/{id}
+    pub async fn get_message_batch(&self, batch_id: &str) -> Result<MessageBatch> {
+        let url = format!("{}/v1/messages/batches/{}", self.config.base_url, batch_id);
+        let response = self
+            .http_client
+            .get(&url)
+            .headers(self.batch_headers()?)
+            .send()
+            .await
+            .context("Failed to send get batch request")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_else(|_| "Failed to read error".to_string());
+            return Err(anyhow!("get_message_batch failed with status {}: {}", status, text));
+        }
+
+        response.json().await.context("Failed to parse batch response")
+    }
+
+    /// Fetch the results of an ended batch as raw JSONL (one result
+    /// object per line, in submission order)
+    /// Endpoint: the batch's results_url, or GET /v1/messages/batches/{id}/results
+    pub async fn get_message_batch_results(&self, batch: &MessageBatch) -> Result<String> {
+        let url = batch.results_url.clone().unwrap_or_else(|| {
+            format!(
+                "{}/v1/messages/batches/{}/results",
+                self.config.base_url, batch.id
+            )
+        });
+        let response = self
+            .http_client
+            .get(&url)
+            .headers(self.batch_headers()?)
+            .send()
+            .await
+            .context("Failed to send batch results request")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_else(|_| "Failed to read error".to_string());
+            return Err(anyhow!("batch results failed with status {}: {}", status, text));
+        }
+
+        response.text().await.context("Failed to read batch results")
+    }
+
+    // ===== Helper methods for request metadata (matching JavaScript behavior) =====
+
+    /// Get or generate the anonymous user ID (matches JavaScript
+    /// variable22486 at line 530737-530745). JavaScript stores userID in
+    /// state.json and generates a 32-byte hex random string if not
+    /// present; a newly generated ID is persisted best-effort so
+    /// server-side analytics see a stable ID across sessions
+    fn get_or_generate_user_id() -> String {
+        static USER_ID: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+        USER_ID
+            .get_or_init(|| {
+                // Check environment variable first
+                if let Ok(user_id) = std::env::var("CLAUDE_CODE_USER_ID") {
+                    if !user_id.is_empty() {
+                        return user_id;
+                    }
+                }
+
+                let state_file = dirs::home_dir().map(|home| home.join(".claude").join("state.json"));
+
+                if let Some(path) = &state_file {
+                    if let Ok(content) = std::fs::read_to_string(path) {
+                        if let Ok(state) = serde_json::from_str::<serde_json::Value>(&content) {
+                            if let Some(user_id) = state.get("userID").and_then(|v| v.as_str()) {
+                                if !user_id.is_empty() {
+                                    return user_id.to_string();
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Generate new 32-byte hex ID (JavaScript: zX7(32).toString("hex"))
+                use rand::Rng;
+                let mut rng = rand::thread_rng();
+                let bytes: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
+                let user_id = hex::encode(bytes);
+
+                if let Some(path) = state_file {
+                    let mut state = std::fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                        .unwrap_or_else(|| json!({}));
+                    if let Some(map) = state.as_object_mut() {
+                        map.insert("userID".to_string(), Value::String(user_id.clone()));
+                        if let Some(parent) = path.parent() {
+                            let _ = std::fs::create_dir_all(parent);
+                        }
+                        if let Ok(content) = serde_json::to_string_pretty(&state) {
+                            let _ = std::fs::write(&path, content);
+                        }
+                    }
+                }
+
+                user_id
+            })
+            .clone()
+    }
+
+    /// Get the account UUID (matches JavaScript variable26865()?.accountUuid):
+    /// environment, then the stored oauthAccount in config, then the
+    /// plaintext credentials file, then state.json. Empty when unknown,
+    /// like JavaScript
+    fn get_account_uuid() -> String {
+        // Check environment variable first
+        if let Ok(account_uuid) = std::env::var("CLAUDE_CODE_ACCOUNT_UUID") {
+            if !account_uuid.is_empty() {
+                return account_uuid;
+            }
+        }
+
+        // Stored oauthAccount in config.json
+        if let Ok(config) = crate::config::get_merged_config() {
+            if let Some(account_uuid) = config
+                .oauth_account
+                .as_ref()
+                .and_then(|account| account.get("accountUuid"))
+                .and_then(|v| v.as_str())
+            {
+                if !account_uuid.is_empty() {
+                    return account_uuid.to_string();
+                }
+            }
+        }
+
+        if let Some(home) = dirs::home_dir() {
+            // Try .claude/.credentials.json (plaintext storage)
+            let creds_file = home.join(".claude").join(".credentials.json");
+            if let Ok(content) = std::fs::read_to_string(&creds_file) {
+                if let Ok(creds) = serde_json::from_str::<serde_json::Value>(&content) {
+                    if let Some(account_uuid) = creds
+                        .get("claudeAiOauth")
+                        .and_then(|oauth| oauth.get("accountUuid"))
+                        .and_then(|v| v.as_str())
+                    {
+                        if !account_uuid.is_empty() {
+                            return account_uuid.to_string();
+                        }
+                    }
+                }
+            }
+
+            // Try state.json oauthAccount
+            let state_file = home.join(".claude").join("state.json");
+            if let Ok(content) = std::fs::read_to_string(&state_file) {
+                if let Ok(state) = serde_json::from_str::<serde_json::Value>(&content) {
+                    if let Some(account_uuid) = state
+                        .get("oauthAccount")
+                        .and_then(|account| account.get("accountUuid"))
+                        .and_then(|v| v.as_str())
+                    {
+                        if !account_uuid.is_empty() {
+                            return account_uuid.to_string();
+                        }
+                    }
+                }
+            }
+        }
+
+        // Return empty if not found (JavaScript uses "" when not available)
+        String::new()
+    }
+
+    /// Get the session ID (matches JavaScript variable8987 at line 2028):
+    /// generated once per process, like JavaScript's per-session global
+    fn get_session_id() -> String {
+        static SESSION_ID: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+        SESSION_ID
+            .get_or_init(|| {
+                // Check environment variable first (JavaScript also uses CLAUDE_CODE_SESSION_ID)
+                if let Ok(session_id) = std::env::var("CLAUDE_CODE_SESSION_ID") {
+                    if !session_id.is_empty() {
+                        return session_id;
+                    }
+                }
+
+                // Generate a session ID (JavaScript uses EP0() which creates a UUID-like ID)
+                // Using process ID + timestamp for uniqueness within this process
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis();
+                let pid = std::process::id();
+
+                format!("{:x}{:x}", timestamp, pid)
+            })
+            .clone()
+    }
+
+    /// The metadata.user_id value sent with every request, built exactly
+    /// like the reference CLI: user_{userID}_account_{accountUuid}_session_{sessionId}.
+    /// A metadataUserId config entry overrides the whole constructed value
+    pub fn build_metadata_user_id() -> String {
+        if let Ok(config) = crate::config::get_merged_config() {
+            if let Some(value) = config.metadata_user_id {
+                if !value.is_empty() {
+                    return value;
+                }
+            }
+        }
+
+        format!(
+            "user_{}_account_{}_session_{}",
+            Self::get_or_generate_user_id(),
+            Self::get_account_uuid(),
+            Self::get_session_id()
+        )
+    }
 }
 
 // ===== SSE STREAMING SUPPORT =====
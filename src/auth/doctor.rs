@@ -0,0 +1,419 @@
+//! Credential diagnostics behind `llminate auth doctor`.
+//!
+//! Walks every source [`AuthManager`](super::AuthManager) consults, in
+//! the same precedence order the manager applies, reports which one the
+//! next session would actually use, validates it against the API with a
+//! cheap request, and prints remediation for each failure — so auth
+//! precedence questions no longer require reading debug logs.
+
+use colored::Colorize;
+
+use super::{oauth_enabled, storage, AuthManager, AuthMethod};
+use crate::error::Result;
+
+/// What the walk found for one credential source
+enum SourceState {
+    /// Present and first in precedence: the next session would use it
+    Active(String),
+    /// Present but outranked by an earlier source
+    Shadowed(String),
+    /// Configured but unusable; carries a remediation hint
+    Broken { detail: String, remedy: String },
+    /// Not configured
+    Absent(String),
+}
+
+struct SourceReport {
+    name: &'static str,
+    state: SourceState,
+}
+
+/// Run the credential doctor: walk the sources, name the active one,
+/// and validate it against the API
+pub async fn run() -> Result<()> {
+    println!("{}", "llminate auth doctor".bold());
+    println!("{}", "====================".dimmed());
+    println!();
+
+    let mut manager = AuthManager::new()?;
+    let reports = walk_sources(&mut manager).await?;
+
+    println!("Credential sources, in the order they are consulted:");
+    let mut active_name = None;
+    let mut remedies = Vec::new();
+    for (index, report) in reports.iter().enumerate() {
+        let status = match &report.state {
+            SourceState::Active(detail) => {
+                active_name = Some(report.name);
+                format!("{} {}", "ACTIVE".green().bold(), detail.dimmed())
+            }
+            SourceState::Shadowed(detail) => {
+                format!("{} {}", "shadowed".yellow(), detail.dimmed())
+            }
+            SourceState::Broken { detail, remedy } => {
+                remedies.push((report.name, remedy.clone()));
+                format!("{} {}", "BROKEN".red().bold(), detail)
+            }
+            SourceState::Absent(detail) => detail.dimmed().to_string(),
+        };
+        println!("  {}. {:<36} {}", index + 1, report.name, status);
+    }
+    println!();
+
+    for (name, remedy) in &remedies {
+        println!("{} {}: {}", "Fix".red().bold(), name, remedy);
+    }
+    if !remedies.is_empty() {
+        println!();
+    }
+
+    // The AWS chain only matters when the Bedrock backend is selected
+    if bedrock_selected() {
+        return validate_bedrock().await;
+    }
+
+    match active_name {
+        Some(name) => {
+            println!("Active source: {}", name.bold());
+            validate_anthropic(&mut manager).await
+        }
+        None => {
+            println!("{}", "No usable credential source found.".red());
+            println!("To authenticate, either:");
+            println!("  - export ANTHROPIC_API_KEY=sk-ant-...");
+            println!("  - run `llminate` and sign in through the wizard (/login)");
+            println!("  - configure an auth profile (llminate config set ...)");
+            println!("  - set CLAUDE_CODE_USE_BEDROCK=1 to use the AWS credential chain");
+            Ok(())
+        }
+    }
+}
+
+/// Walk the sources in `determine_auth_method` precedence, recording
+/// what each one contributes and which one wins
+async fn walk_sources(manager: &mut AuthManager) -> Result<Vec<SourceReport>> {
+    let oauth_allowed = oauth_enabled();
+    let mut reports = Vec::new();
+    let mut claimed = false;
+    let mut claim = |present: SourceState| -> SourceState {
+        match present {
+            SourceState::Active(detail) if claimed => SourceState::Shadowed(detail),
+            SourceState::Active(detail) => {
+                claimed = true;
+                SourceState::Active(detail)
+            }
+            other => other,
+        }
+    };
+
+    // OAuth bearer tokens from the environment. When the OAuth paths are
+    // disabled these still short-circuit `get_auth_source`, masking every
+    // later source — the classic precedence trap this command exists for
+    for name in ["ANTHROPIC_AUTH_TOKEN", "CLAUDE_CODE_OAUTH_TOKEN"] {
+        let state = match std::env::var(name) {
+            Ok(token) if !token.is_empty() => {
+                if oauth_allowed {
+                    claim(SourceState::Active(format!(
+                        "(bearer token, {} chars)",
+                        token.len()
+                    )))
+                } else {
+                    SourceState::Broken {
+                        detail: "set, but the OAuth paths are disabled; it masks every \
+                                 later source"
+                            .to_string(),
+                        remedy: format!(
+                            "unset {}, or enable OAuth with `--auth-method oauth` or \
+                             `features.oauth` in settings.json",
+                            name
+                        ),
+                    }
+                }
+            }
+            _ => SourceState::Absent("not set".to_string()),
+        };
+        reports.push(SourceReport { name, state });
+    }
+
+    // Stored OAuth credentials outrank API keys when OAuth is enabled
+    // and preferred, so probe them here to keep the walk in real order
+    let raw_oauth = manager
+        .get_credentials()
+        .await
+        .ok()
+        .flatten()
+        .and_then(|creds| creds.claude_ai_oauth);
+    let usable_oauth = manager.get_claude_ai_oauth().await.unwrap_or(None);
+    let oauth_state = match (&raw_oauth, &usable_oauth) {
+        (Some(_), Some(oauth)) => {
+            if !oauth_allowed {
+                SourceState::Shadowed(
+                    "(present, ignored: OAuth paths are disabled)".to_string(),
+                )
+            } else if !AuthManager::has_valid_scopes(&usable_oauth) {
+                SourceState::Broken {
+                    detail: "stored token lacks the user:inference scope".to_string(),
+                    remedy: "run /login again to re-authorize with the required scopes"
+                        .to_string(),
+                }
+            } else if manager.should_prefer_oauth().await.unwrap_or(false) {
+                claim(SourceState::Active(format!(
+                    "(subscription: {})",
+                    oauth.subscription_type.as_deref().unwrap_or("unknown")
+                )))
+            } else {
+                SourceState::Shadowed(
+                    "(valid, but an approved API key takes precedence)".to_string(),
+                )
+            }
+        }
+        (Some(_), None) => SourceState::Broken {
+            detail: "stored token is expired and the refresh failed".to_string(),
+            remedy: "run /login to sign in again".to_string(),
+        },
+        (None, _) => SourceState::Absent("none stored".to_string()),
+    };
+    reports.push(SourceReport {
+        name: "stored OAuth credentials",
+        state: oauth_state,
+    });
+
+    // Auth profile (--profile / ANTHROPIC_PROFILE) beats ANTHROPIC_API_KEY
+    let profile_state = match crate::config::get_active_profile() {
+        Some((name, profile)) => match profile.api_key {
+            Some(key) if !key.is_empty() => claim(SourceState::Active(format!(
+                "(profile '{}', key ...{})",
+                name,
+                key_suffix(&key)
+            ))),
+            _ => SourceState::Broken {
+                detail: format!("profile '{}' is active but has no api_key", name),
+                remedy: format!(
+                    "add a key with `llminate config set -g profiles.{}.api_key <key>` \
+                     or unset ANTHROPIC_PROFILE",
+                    name
+                ),
+            },
+        },
+        None => SourceState::Absent("no active profile".to_string()),
+    };
+    reports.push(SourceReport {
+        name: "auth profile (--profile)",
+        state: profile_state,
+    });
+
+    let api_key_state = match std::env::var("ANTHROPIC_API_KEY") {
+        Ok(key) if !key.is_empty() => {
+            let approved = manager.is_api_key_approved(&key).await.unwrap_or(false);
+            let note = if approved {
+                "approved"
+            } else {
+                "unapproved, will prompt on first use"
+            };
+            claim(SourceState::Active(format!(
+                "(...{}, {})",
+                key_suffix(&key),
+                note
+            )))
+        }
+        _ => SourceState::Absent("not set".to_string()),
+    };
+    reports.push(SourceReport {
+        name: "ANTHROPIC_API_KEY",
+        state: api_key_state,
+    });
+
+    let config = manager.get_config().await?;
+    let helper_state = match &config.api_key_helper {
+        Some(command) => match manager.execute_api_key_helper(command).await? {
+            // Space is the helper-failure sentinel
+            Some(key) if key != " " => {
+                claim(SourceState::Active(format!("(...{})", key_suffix(&key))))
+            }
+            _ => SourceState::Broken {
+                detail: "configured but produced no key".to_string(),
+                remedy: format!(
+                    "run `{}` manually and confirm it prints a key on stdout",
+                    command
+                ),
+            },
+        },
+        None => SourceState::Absent("not configured".to_string()),
+    };
+    reports.push(SourceReport {
+        name: "apiKeyHelper",
+        state: helper_state,
+    });
+
+    let keyring_state = match storage::read_api_key_from_keyring().await {
+        Some(key) => claim(SourceState::Active(format!("(...{})", key_suffix(&key)))),
+        None => SourceState::Absent("empty".to_string()),
+    };
+    reports.push(SourceReport {
+        name: "OS keyring (/login managed key)",
+        state: keyring_state,
+    });
+
+    let config_key_state = match &config.primary_api_key {
+        Some(key) if !key.is_empty() => {
+            claim(SourceState::Active(format!("(...{})", key_suffix(key))))
+        }
+        _ => SourceState::Absent("not set".to_string()),
+    };
+    reports.push(SourceReport {
+        name: "config primaryApiKey",
+        state: config_key_state,
+    });
+
+    let aws_state = if bedrock_selected() {
+        claim(SourceState::Active(
+            "(Bedrock selected: env -> ini -> web identity -> container -> IMDS)".to_string(),
+        ))
+    } else {
+        SourceState::Absent("skipped (Bedrock not selected)".to_string())
+    };
+    reports.push(SourceReport {
+        name: "AWS credential chain",
+        state: aws_state,
+    });
+
+    Ok(reports)
+}
+
+/// Whether the Bedrock backend (and therefore the AWS chain) is selected
+fn bedrock_selected() -> bool {
+    if std::env::var("LLMINATE_PROVIDER").ok().as_deref() == Some("bedrock") {
+        return true;
+    }
+    std::env::var("CLAUDE_CODE_USE_BEDROCK")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Last four characters of a key, for identification without disclosure
+fn key_suffix(key: &str) -> &str {
+    let len = key.len();
+    &key[len.saturating_sub(4)..]
+}
+
+/// Validate the active credential against the Messages API host with a
+/// cheap request (`GET /models?limit=1`)
+async fn validate_anthropic(manager: &mut AuthManager) -> Result<()> {
+    let method = match manager.determine_auth_method().await {
+        Ok(method) => method,
+        Err(e) => {
+            println!("Validation: {} ({})", "FAILED".red().bold(), e);
+            println!("The walk above shows which source broke the resolution.");
+            return Ok(());
+        }
+    };
+
+    let base = std::env::var("ANTHROPIC_BASE_URL")
+        .unwrap_or_else(|_| "https://api.anthropic.com/v1".to_string());
+    let url = format!("{}/models?limit=1", base.trim_end_matches('/'));
+    print!("Validating against {} ... ", base.trim_end_matches('/'));
+
+    let client = crate::utils::http::shared_client()?;
+    let mut request = client.get(&url).header("anthropic-version", "2023-06-01");
+    request = match &method {
+        AuthMethod::ApiKey(key) => request.header("x-api-key", key),
+        AuthMethod::ClaudeAiOauth(oauth) => request
+            .header("authorization", format!("Bearer {}", oauth.access_token))
+            .header("anthropic-beta", "oauth-2025-04-20"),
+    };
+
+    match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                println!("{}", "OK".green().bold());
+            } else {
+                println!("{} (HTTP {})", "REJECTED".red().bold(), status.as_u16());
+                match status.as_u16() {
+                    401 => match method {
+                        AuthMethod::ApiKey(_) => println!(
+                            "The key was rejected. Regenerate it at \
+                             https://console.anthropic.com/settings/keys and update the source above."
+                        ),
+                        AuthMethod::ClaudeAiOauth(_) => {
+                            println!("The OAuth token was rejected. Run /login to sign in again.")
+                        }
+                    },
+                    403 => println!(
+                        "The credential authenticated but lacks permission for this API. \
+                         Check the key's workspace and role."
+                    ),
+                    _ => {
+                        let body = response.text().await.unwrap_or_default();
+                        println!("{}", truncate(&body, 300));
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            println!("{}", "UNREACHABLE".red().bold());
+            println!(
+                "Could not reach the API: {}. Check your network, and HTTPS_PROXY if you \
+                 are behind a proxy.",
+                e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the AWS chain and make a cheap signed request
+/// (`ListFoundationModels`) to prove the credentials work end to end
+async fn validate_bedrock() -> Result<()> {
+    use crate::auth::aws::{CredentialProvider as _, DefaultCredentialProvider};
+
+    println!("Active source: {}", "AWS credential chain (Bedrock)".bold());
+    print!("Resolving the AWS credential chain ... ");
+    match DefaultCredentialProvider::new().get_credentials().await {
+        Ok(creds) => {
+            println!(
+                "{} (access key ...{})",
+                "OK".green().bold(),
+                key_suffix(&creds.access_key_id)
+            );
+        }
+        Err(e) => {
+            println!("{}", "FAILED".red().bold());
+            println!("{:#}", e);
+            println!(
+                "Set AWS_ACCESS_KEY_ID/AWS_SECRET_ACCESS_KEY, configure a profile in \
+                 ~/.aws/credentials, or run `llminate auth aws-sso-login` for SSO."
+            );
+            return Ok(());
+        }
+    }
+
+    print!("Validating against Bedrock (ListFoundationModels) ... ");
+    let config = crate::ai::AIConfig {
+        provider: crate::ai::Provider::Bedrock,
+        ..Default::default()
+    };
+    let backend = crate::ai::bedrock::BedrockBackend::new(config)?;
+    match backend.list_foundation_models().await {
+        Ok(models) => {
+            println!("{} ({} models visible)", "OK".green().bold(), models.len());
+        }
+        Err(e) => {
+            println!("{}", "REJECTED".red().bold());
+            println!("{}", e);
+            println!(
+                "The chain resolved but Bedrock rejected the request. Check the region \
+                 (AWS_REGION) and that the identity has bedrock:ListFoundationModels."
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Clip long error bodies to keep the report readable
+fn truncate(text: &str, limit: usize) -> &str {
+    match text.char_indices().nth(limit) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
@@ -3,7 +3,9 @@ pub mod signers;
 pub mod checksum;
 pub mod aws;
 pub mod aws_providers;
+pub mod azure;
 pub mod client;
+pub mod doctor;
 pub mod http;
 pub mod session;
 pub mod proxy;
@@ -17,6 +19,26 @@ use tokio::fs;
 use tracing::{debug, info, error};
 use self::storage::{CredentialsStorage, StorageBackend, PlaintextStorage};
 
+/// CLI override for the oauth feature flag (--auth-method). Set once at
+/// startup; unset means the `features.oauth` setting decides
+static OAUTH_OVERRIDE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
+/// Record the --auth-method choice for this run
+pub fn set_oauth_enabled(enabled: bool) {
+    let _ = OAUTH_OVERRIDE.set(enabled);
+}
+
+/// Whether the OAuth login paths are enabled. Anthropic disabled 3rd
+/// party OAuth support for this CLI, so the paths ship dark; Max
+/// subscribers using the official client_id opt in via `features.oauth`
+/// in settings.json or `--auth-method oauth`
+pub fn oauth_enabled() -> bool {
+    OAUTH_OVERRIDE
+        .get()
+        .copied()
+        .unwrap_or_else(|| crate::config::get_feature_flags().oauth)
+}
+
 /// Authentication methods supported
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AuthMethod {
@@ -518,7 +540,22 @@ impl AuthManager {
             }
         }
 
-        // Priority 3: ANTHROPIC_API_KEY with approval check
+        // Priority 3: active auth profile (--profile / ANTHROPIC_PROFILE).
+        // An explicitly selected profile beats ANTHROPIC_API_KEY, since
+        // switching profiles is how users override a globally exported key
+        if let Some((name, profile)) = crate::config::get_active_profile() {
+            if let Some(api_key) = profile.api_key {
+                if !api_key.is_empty() {
+                    debug!("Using API key from profile '{}'", name);
+                    return Ok(AuthSource {
+                        key: Some(api_key),
+                        source: format!("profile:{}", name),
+                    });
+                }
+            }
+        }
+
+        // Priority 4: ANTHROPIC_API_KEY with approval check
         if let Ok(api_key) = std::env::var("ANTHROPIC_API_KEY") {
             if !api_key.is_empty() {
                 if self.is_api_key_approved(&api_key).await? {
@@ -538,7 +575,7 @@ impl AuthManager {
             }
         }
 
-        // Priority 4: apiKeyHelper
+        // Priority 5: apiKeyHelper
         let config = self.get_config().await?;
         if let Some(helper_command) = config.api_key_helper {
             if let Some(api_key) = self.execute_api_key_helper(&helper_command).await? {
@@ -552,124 +589,19 @@ impl AuthManager {
             }
         }
 
-        // Priority 5: Login managed key from keychain (macOS) or config
-        if cfg!(target_os = "macos") {
-            // Try keychain for API key (not OAuth)
-            let service_name = storage::get_service_name_for_api_key()?;
-            let username = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-            
-            if let Ok(output) = tokio::process::Command::new("security")
-                .args(&[
-                    "find-generic-password",
-                    "-a", &username,
-                    "-w",
-                    "-s", &service_name
-                ])
-                .output()
-                .await
-            {
-                if output.status.success() {
-                    if let Ok(api_key) = String::from_utf8(output.stdout) {
-                        let api_key = api_key.trim();
-                        if !api_key.is_empty() {
-                            debug!("Using /login managed key from keychain");
-                            return Ok(AuthSource {
-                                key: Some(api_key.to_string()),
-                                source: "/login managed key".to_string(),
-                            });
-                        }
-                    }
-                }
-            }
-        } else if cfg!(target_os = "linux") {
-            // Linux: Try secret-tool for GNOME Keyring/KWallet
-            let service_name = storage::get_service_name_for_api_key()?;
-            let username = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-            
-            // Try secret-tool (GNOME Keyring)
-            if let Ok(output) = tokio::process::Command::new("secret-tool")
-                .args(&[
-                    "lookup",
-                    "service", &service_name,
-                    "username", &username
-                ])
-                .output()
-                .await
-            {
-                if output.status.success() {
-                    if let Ok(api_key) = String::from_utf8(output.stdout) {
-                        let api_key = api_key.trim();
-                        if !api_key.is_empty() {
-                            debug!("Using /login managed key from secret-tool");
-                            return Ok(AuthSource {
-                                key: Some(api_key.to_string()),
-                                source: "/login managed key".to_string(),
-                            });
-                        }
-                    }
-                }
-            }
-            
-            // Try kwallet (KDE Wallet)
-            if let Ok(output) = tokio::process::Command::new("kwallet-query")
-                .args(&[
-                    "--read-password",
-                    &service_name,
-                    "--folder", "Claude Code",
-                    "kdewallet"
-                ])
-                .output()
-                .await
-            {
-                if output.status.success() {
-                    if let Ok(api_key) = String::from_utf8(output.stdout) {
-                        let api_key = api_key.trim();
-                        if !api_key.is_empty() {
-                            debug!("Using /login managed key from kwallet");
-                            return Ok(AuthSource {
-                                key: Some(api_key.to_string()),
-                                source: "/login managed key".to_string(),
-                            });
-                        }
-                    }
-                }
-            }
-        } else if cfg!(target_os = "windows") {
-            // Windows: Use Windows Credential Manager via PowerShell
-            let service_name = storage::get_service_name_for_api_key()?;
-            
-            // PowerShell command to retrieve credential
-            let ps_script = format!(
-                "$cred = Get-StoredCredential -Target '{}' -AsCredentialObject -ErrorAction SilentlyContinue; \
-                 if ($cred) {{ $cred.GetNetworkCredential().Password }}",
-                service_name
-            );
-            
-            if let Ok(output) = tokio::process::Command::new("powershell")
-                .args(&[
-                    "-NoProfile",
-                    "-NonInteractive",
-                    "-Command", &ps_script
-                ])
-                .output()
-                .await
-            {
-                if output.status.success() {
-                    if let Ok(api_key) = String::from_utf8(output.stdout) {
-                        let api_key = api_key.trim();
-                        if !api_key.is_empty() {
-                            debug!("Using /login managed key from Windows Credential Manager");
-                            return Ok(AuthSource {
-                                key: Some(api_key.to_string()),
-                                source: "/login managed key".to_string(),
-                            });
-                        }
-                    }
-                }
-            }
+        // Priority 6: Login managed key from the OS keyring (Keychain,
+        // Secret Service, or Windows Credential Manager via the keyring
+        // crate - the old per-OS shell-outs failed silently on headless
+        // Linux and non-English Windows)
+        if let Some(api_key) = storage::read_api_key_from_keyring().await {
+            debug!("Using /login managed key from keyring");
+            return Ok(AuthSource {
+                key: Some(api_key),
+                source: "/login managed key".to_string(),
+            });
         }
 
-        // Priority 6: Config file primaryApiKey
+        // Priority 7: Config file primaryApiKey
         if let Some(api_key) = config.primary_api_key {
             if !api_key.is_empty() {
                 debug!("Using /login managed key from config");
@@ -729,54 +661,59 @@ impl AuthManager {
         Ok(false)
     }
 
-    /// Determine the authentication method to use (main entry point)
+    /// Determine the authentication method to use (main entry point).
+    /// OAuth ships dark (Anthropic disabled 3rd party OAuth support for
+    /// this CLI); when the `features.oauth` setting or `--auth-method
+    /// oauth` enables it, OAuth env tokens and stored credentials take
+    /// their old priority over API key sources, with the API key
+    /// exchange as last resort
     pub async fn determine_auth_method(&mut self) -> Result<AuthMethod> {
         debug!("Starting authentication determination");
 
-        // OAUTH DISABLED: Anthropic has disabled 3rd party OAuth support for Claude Code CLI
-        // Priority is now: ANTHROPIC_API_KEY > other API key sources
-        // OAuth environment variables and stored credentials are ignored.
-
-        // Check for environment OAuth tokens - these are now DISABLED
-        // if let Ok(auth_token) = std::env::var("ANTHROPIC_AUTH_TOKEN") {
-        //     if !auth_token.is_empty() {
-        //         info!("✅ Using ANTHROPIC_AUTH_TOKEN as OAuth Bearer token");
-        //         let account_uuid = std::env::var("CLAUDE_CODE_ACCOUNT_UUID").ok();
-        //         return Ok(AuthMethod::ClaudeAiOauth(ClaudeAiOauth {
-        //             access_token: auth_token,
-        //             refresh_token: String::new(),
-        //             expires_at: None,
-        //             scopes: vec!["user:inference".to_string()],
-        //             subscription_type: None,
-        //             account_uuid,
-        //         }));
-        //     }
-        // }
-        //
-        // if let Ok(oauth_token) = std::env::var("CLAUDE_CODE_OAUTH_TOKEN") {
-        //     if !oauth_token.is_empty() {
-        //         info!("✅ Using CLAUDE_CODE_OAUTH_TOKEN as OAuth Bearer token");
-        //         let account_uuid = std::env::var("CLAUDE_CODE_ACCOUNT_UUID").ok();
-        //         return Ok(AuthMethod::ClaudeAiOauth(ClaudeAiOauth {
-        //             access_token: oauth_token,
-        //             refresh_token: String::new(),
-        //             expires_at: None,
-        //             scopes: vec!["user:inference".to_string()],
-        //             subscription_type: None,
-        //             account_uuid,
-        //         }));
-        //     }
-        // }
-        //
-        // // Check if we should prefer OAuth over API key - DISABLED
-        // if self.should_prefer_oauth().await? {
-        //     if let Some(oauth) = self.get_claude_ai_oauth().await? {
-        //         info!("✅ Using Claude.ai OAuth authentication");
-        //         return Ok(AuthMethod::ClaudeAiOauth(oauth));
-        //     }
-        // }
+        let oauth_allowed = oauth_enabled();
+
+        if oauth_allowed {
+            // Environment OAuth tokens take priority
+            if let Ok(auth_token) = std::env::var("ANTHROPIC_AUTH_TOKEN") {
+                if !auth_token.is_empty() {
+                    info!("✅ Using ANTHROPIC_AUTH_TOKEN as OAuth Bearer token");
+                    let account_uuid = std::env::var("CLAUDE_CODE_ACCOUNT_UUID").ok();
+                    return Ok(AuthMethod::ClaudeAiOauth(ClaudeAiOauth {
+                        access_token: auth_token,
+                        refresh_token: String::new(),
+                        expires_at: None,
+                        scopes: vec!["user:inference".to_string()],
+                        subscription_type: None,
+                        account_uuid,
+                    }));
+                }
+            }
+
+            if let Ok(oauth_token) = std::env::var("CLAUDE_CODE_OAUTH_TOKEN") {
+                if !oauth_token.is_empty() {
+                    info!("✅ Using CLAUDE_CODE_OAUTH_TOKEN as OAuth Bearer token");
+                    let account_uuid = std::env::var("CLAUDE_CODE_ACCOUNT_UUID").ok();
+                    return Ok(AuthMethod::ClaudeAiOauth(ClaudeAiOauth {
+                        access_token: oauth_token,
+                        refresh_token: String::new(),
+                        expires_at: None,
+                        scopes: vec!["user:inference".to_string()],
+                        subscription_type: None,
+                        account_uuid,
+                    }));
+                }
+            }
 
-        // Try to get API key (now the primary authentication method)
+            // Stored OAuth credentials when preferred over API keys
+            if self.should_prefer_oauth().await? {
+                if let Some(oauth) = self.get_claude_ai_oauth().await? {
+                    info!("✅ Using Claude.ai OAuth authentication");
+                    return Ok(AuthMethod::ClaudeAiOauth(oauth));
+                }
+            }
+        }
+
+        // Try to get API key (the primary authentication method)
         let auth_source = self.get_auth_source().await?;
 
         if let Some(api_key) = auth_source.key {
@@ -790,30 +727,31 @@ impl AuthManager {
             return Ok(AuthMethod::ApiKey(api_key));
         }
 
-        // OAUTH DISABLED: Don't fall back to OAuth anymore
-        // // Last resort: try OAuth even if not preferred
-        // if let Some(oauth) = self.get_claude_ai_oauth().await? {
-        //     if oauth.scopes.contains(&"user:inference".to_string()) {
-        //         info!("✅ Using Claude.ai OAuth token with user:inference scope (fallback)");
-        //         return Ok(AuthMethod::ClaudeAiOauth(oauth));
-        //     } else {
-        //         info!("✅ OAuth token lacks user:inference scope - attempting API key exchange");
-        //         match self.exchange_oauth_for_api_key(&oauth.access_token).await {
-        //             Ok(api_key) => {
-        //                 info!("✅ Successfully exchanged OAuth token for API key");
-        //                 return Ok(AuthMethod::ApiKey(api_key));
-        //             }
-        //             Err(e) => {
-        //                 error!("Failed to exchange OAuth token: {}", e);
-        //                 info!("Using OAuth token as Bearer (last resort after exchange failure)");
-        //                 return Ok(AuthMethod::ClaudeAiOauth(oauth));
-        //             }
-        //         }
-        //     }
-        // }
+        if oauth_allowed {
+            // Last resort: try OAuth even if not preferred
+            if let Some(oauth) = self.get_claude_ai_oauth().await? {
+                if oauth.scopes.contains(&"user:inference".to_string()) {
+                    info!("✅ Using Claude.ai OAuth token with user:inference scope (fallback)");
+                    return Ok(AuthMethod::ClaudeAiOauth(oauth));
+                } else {
+                    info!("✅ OAuth token lacks user:inference scope - attempting API key exchange");
+                    match self.exchange_oauth_for_api_key(&oauth.access_token).await {
+                        Ok(api_key) => {
+                            info!("✅ Successfully exchanged OAuth token for API key");
+                            return Ok(AuthMethod::ApiKey(api_key));
+                        }
+                        Err(e) => {
+                            error!("Failed to exchange OAuth token: {}", e);
+                            info!("Using OAuth token as Bearer (last resort after exchange failure)");
+                            return Ok(AuthMethod::ClaudeAiOauth(oauth));
+                        }
+                    }
+                }
+            }
+        }
 
         error!("No authentication method available. Please set ANTHROPIC_API_KEY environment variable.");
-        Err(Error::Authentication("No valid authentication method found. Please set ANTHROPIC_API_KEY environment variable.".to_string()))
+        Err(Error::Authentication("No valid authentication method found. Please set ANTHROPIC_API_KEY environment variable (or enable OAuth with --auth-method oauth).".to_string()))
     }
 
     /// Check if Claude Desktop is available (matches JavaScript yP() and v3() functions)
@@ -970,52 +908,25 @@ impl AuthManager {
     pub async fn save_api_key_from_oauth(&mut self, api_key: &str) -> Result<()> {
         debug!("Saving API key from OAuth login");
         
-        // On macOS, save to keychain
-        if cfg!(target_os = "macos") {
-            let service_name = storage::get_service_name_for_api_key()?;
-            let username = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-            
-            // Delete existing entry first
-            let _ = tokio::process::Command::new("security")
-                .args(&[
-                    "delete-generic-password",
-                    "-a", &username,
-                    "-s", &service_name
-                ])
-                .output()
-                .await;
-            
-            // Add new entry
-            let output = tokio::process::Command::new("security")
-                .args(&[
-                    "add-generic-password",
-                    "-a", &username,
-                    "-s", &service_name,
-                    "-w", api_key,
-                    "-U"  // Update if exists
-                ])
-                .output()
-                .await
-                .map_err(|e| Error::Config(format!("Failed to save to keychain: {}", e)))?;
-            
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(Error::Config(format!("Keychain save failed: {}", stderr)));
+        // Prefer the OS keyring; fall back to the config file when no
+        // keyring is available (e.g. headless Linux without Secret Service)
+        match storage::write_api_key_to_keyring(api_key).await {
+            Ok(()) => {
+                debug!("Saved API key to keyring");
+            }
+            Err(e) => {
+                debug!("Keyring unavailable ({}), saving API key to config file", e);
+                let mut config = self.get_config().await?;
+                config.primary_api_key = Some(api_key.to_string());
+
+                let json = serde_json::to_string_pretty(&config)
+                    .map_err(|e| Error::Config(format!("Failed to serialize config: {}", e)))?;
+
+                fs::write(&self.config_path, json).await
+                    .map_err(|e| Error::Config(format!("Failed to write config: {}", e)))?;
+
+                debug!("Saved API key to config file");
             }
-            
-            debug!("Saved API key to keychain");
-        } else {
-            // Save to config file
-            let mut config = self.get_config().await?;
-            config.primary_api_key = Some(api_key.to_string());
-            
-            let json = serde_json::to_string_pretty(&config)
-                .map_err(|e| Error::Config(format!("Failed to serialize config: {}", e)))?;
-            
-            fs::write(&self.config_path, json).await
-                .map_err(|e| Error::Config(format!("Failed to write config: {}", e)))?;
-            
-            debug!("Saved API key to config file");
         }
         
         // Clear cache to force reload
@@ -1044,17 +955,10 @@ impl AuthManager {
             debug!("WARNING: No account UUID provided - metadata may be incomplete");
         }
 
-        // Clear any stale API key from keychain (prevents OAuth token being used as API key)
+        // Clear any stale API key from the keyring (prevents OAuth token being used as API key)
         // JavaScript (line 272551-272560): sets apiKey: null when OAuth is active
-        if cfg!(target_os = "macos") {
-            if let Ok(service_name) = storage::get_service_name_for_api_key() {
-                let username = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-                debug!("Clearing stale API key from keychain service: {}", service_name);
-                let _ = tokio::process::Command::new("security")
-                    .args(&["delete-generic-password", "-a", &username, "-s", &service_name])
-                    .output()
-                    .await;
-            }
+        if let Err(e) = storage::delete_api_key_from_keyring().await {
+            debug!("Failed to clear stale API key from keyring: {}", e);
         }
 
         // Calculate expires_at from expires_in
@@ -1082,8 +986,14 @@ impl AuthManager {
 
         // JavaScript saves to storage backend via variable35391().update()
         // This uses the SAME storage backend that read() uses (keychain on macOS, plaintext otherwise)
+        // Preserve any stored MCP server tokens when rewriting the file
+        let existing_mcp_oauth = self.storage_backend.read().await
+            .ok()
+            .flatten()
+            .and_then(|creds| creds.mcp_oauth);
         let credentials = storage::Credentials {
             claude_ai_oauth: Some(oauth_data),
+            mcp_oauth: existing_mcp_oauth,
         };
 
         // Save to storage backend (matches JavaScript exactly)
@@ -1106,22 +1016,9 @@ impl AuthManager {
             // Continue anyway - we still want to clear other credentials
         }
 
-        // Clear API key from keychain (macOS)
-        if cfg!(target_os = "macos") {
-            let service_name = storage::get_service_name_for_api_key()?;
-            let username = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-
-            // Try to delete API key from keychain
-            let _ = tokio::process::Command::new("security")
-                .args(&[
-                    "delete-generic-password",
-                    "-a", &username,
-                    "-s", &service_name
-                ])
-                .output()
-                .await;
-
-            debug!("Attempted to delete API key from keychain");
+        // Clear API key from the OS keyring
+        if let Err(e) = storage::delete_api_key_from_keyring().await {
+            debug!("Failed to delete API key from keyring: {}", e);
         }
 
         // Clear config file OAuth entry
@@ -0,0 +1,99 @@
+//! Embedded changelog and release-notes lookup.
+//!
+//! The changelog ships inside the binary (via `include_str!`) so
+//! `/release-notes` and the post-update banner work offline. Sections are
+//! `## <version>` headings in reverse-chronological order, newest first.
+
+/// Full changelog text compiled into the binary
+const CHANGELOG: &str = include_str!("../CHANGELOG.md");
+
+/// The version this binary was built as
+pub fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// The full embedded changelog
+pub fn full_changelog() -> &'static str {
+    CHANGELOG
+}
+
+/// Parse the changelog into (version, body) sections, newest first
+fn sections() -> Vec<(&'static str, String)> {
+    let mut result = Vec::new();
+    let mut current: Option<(&'static str, Vec<&str>)> = None;
+
+    for line in CHANGELOG.lines() {
+        if let Some(version) = line.strip_prefix("## ") {
+            if let Some((v, body)) = current.take() {
+                result.push((v, body.join("\n").trim().to_string()));
+            }
+            current = Some((version.trim(), Vec::new()));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push(line);
+        }
+    }
+    if let Some((v, body)) = current.take() {
+        result.push((v, body.join("\n").trim().to_string()));
+    }
+    result
+}
+
+/// Release notes for one specific version, if present in the changelog
+pub fn notes_for_version(version: &str) -> Option<String> {
+    sections()
+        .into_iter()
+        .find(|(v, _)| *v == version)
+        .map(|(v, body)| format!("## {}\n\n{}", v, body))
+}
+
+/// Compare two dotted version strings numerically (non-numeric parts compare as 0)
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| part.trim().parse::<u64>().unwrap_or(0))
+        .collect()
+}
+
+/// All changelog sections newer than `previous`, newest first.
+/// Returns None when there is nothing newer (or `previous` is the current version).
+pub fn notes_since(previous: &str) -> Option<String> {
+    let prev_key = version_key(previous);
+    let newer: Vec<String> = sections()
+        .into_iter()
+        .filter(|(v, _)| version_key(v) > prev_key)
+        .map(|(v, body)| format!("## {}\n\n{}", v, body))
+        .collect();
+
+    if newer.is_empty() {
+        None
+    } else {
+        Some(newer.join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_version_has_notes() {
+        assert!(notes_for_version(current_version()).is_some());
+    }
+
+    #[test]
+    fn notes_since_older_version_includes_current() {
+        let notes = notes_since("0.0.1").expect("expected notes newer than 0.0.1");
+        assert!(notes.contains(current_version()));
+    }
+
+    #[test]
+    fn notes_since_current_version_is_empty() {
+        assert!(notes_since(current_version()).is_none());
+    }
+
+    #[test]
+    fn version_ordering_is_numeric() {
+        assert!(version_key("1.0.10") > version_key("1.0.9"));
+        assert!(version_key("1.1.0") > version_key("1.0.29"));
+    }
+}
@@ -0,0 +1,311 @@
+//! File-change checkpointing for /undo and /redo.
+//!
+//! Before Edit/Write/MultiEdit/NotebookEdit touch a file, the central tool
+//! dispatcher records a shadow copy of its pre-modification content (or the
+//! fact that it did not exist). Snapshots taken during the same user turn
+//! are grouped into one checkpoint tied to that turn's message index, so
+//! /undo rolls back everything a bad turn did in one step and /redo
+//! reapplies it. Checkpoints live in memory for the session; they are a
+//! safety net against a destructive edit, not a replacement for version
+//! control.
+
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Shadow copy of one file as it was before a tool modified it.
+/// `content: None` means the file did not exist yet.
+#[derive(Debug, Clone)]
+struct FileSnapshot {
+    path: PathBuf,
+    content: Option<Vec<u8>>,
+}
+
+/// All snapshots taken during one user turn
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    message_index: usize,
+    files: Vec<FileSnapshot>,
+}
+
+/// Undo/redo stacks for the session. Kept as a plain struct (with thin
+/// global wrappers below) so the stack logic is testable without the
+/// process-wide static.
+#[derive(Debug, Default)]
+pub struct CheckpointStore {
+    undo: Vec<Checkpoint>,
+    redo: Vec<Checkpoint>,
+    turn_index: usize,
+}
+
+impl CheckpointStore {
+    /// Mark the start of a user turn; snapshots recorded from now on are
+    /// grouped under this message index
+    pub fn begin_turn(&mut self, message_index: usize) {
+        self.turn_index = message_index;
+    }
+
+    /// Record a shadow copy of `path` before a tool modifies it. The first
+    /// snapshot of a file in a turn wins (the pre-turn content is what
+    /// /undo should restore); any new modification invalidates the redo
+    /// stack, like an edit after undo does in an editor.
+    pub fn record_before_modify(&mut self, path: &Path) {
+        self.redo.clear();
+
+        let needs_new_checkpoint = self
+            .undo
+            .last()
+            .map(|checkpoint| checkpoint.message_index != self.turn_index)
+            .unwrap_or(true);
+        if needs_new_checkpoint {
+            self.undo.push(Checkpoint {
+                message_index: self.turn_index,
+                files: Vec::new(),
+            });
+        }
+
+        // unwrap-free: the push above guarantees a last element
+        let Some(checkpoint) = self.undo.last_mut() else {
+            return;
+        };
+        if checkpoint.files.iter().any(|snapshot| snapshot.path == path) {
+            return;
+        }
+        checkpoint.files.push(FileSnapshot {
+            path: path.to_path_buf(),
+            content: std::fs::read(path).ok(),
+        });
+    }
+
+    /// Restore the files of the most recent checkpoint to their shadow
+    /// copies, pushing the current contents onto the redo stack. Returns
+    /// a human-readable summary, or None when there is nothing to undo.
+    pub fn undo(&mut self) -> Result<Option<String>> {
+        let Some(checkpoint) = self.undo.pop() else {
+            return Ok(None);
+        };
+        let reverse = capture_current(&checkpoint);
+        let summary = restore(&checkpoint, "before")?;
+        self.redo.push(reverse);
+        Ok(Some(summary))
+    }
+
+    /// Reapply the most recently undone checkpoint, pushing the current
+    /// contents back onto the undo stack. Returns a human-readable
+    /// summary, or None when there is nothing to redo.
+    pub fn redo(&mut self) -> Result<Option<String>> {
+        let Some(checkpoint) = self.redo.pop() else {
+            return Ok(None);
+        };
+        let reverse = capture_current(&checkpoint);
+        let summary = restore(&checkpoint, "after")?;
+        self.undo.push(reverse);
+        Ok(Some(summary))
+    }
+}
+
+/// Snapshot the current on-disk state of every file in `checkpoint`, so
+/// restoring it can itself be reversed
+fn capture_current(checkpoint: &Checkpoint) -> Checkpoint {
+    Checkpoint {
+        message_index: checkpoint.message_index,
+        files: checkpoint
+            .files
+            .iter()
+            .map(|snapshot| FileSnapshot {
+                path: snapshot.path.clone(),
+                content: std::fs::read(&snapshot.path).ok(),
+            })
+            .collect(),
+    }
+}
+
+/// Write every shadow copy in `checkpoint` back to disk (deleting files
+/// that did not exist) and describe what was restored
+fn restore(checkpoint: &Checkpoint, direction: &str) -> Result<String> {
+    let mut restored = Vec::new();
+    for snapshot in &checkpoint.files {
+        match &snapshot.content {
+            Some(content) => {
+                if let Some(parent) = snapshot.path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| {
+                        Error::Other(format!(
+                            "Checkpoint restore of {} failed: {}",
+                            snapshot.path.display(),
+                            e
+                        ))
+                    })?;
+                }
+                std::fs::write(&snapshot.path, content).map_err(|e| {
+                    Error::Other(format!(
+                        "Checkpoint restore of {} failed: {}",
+                        snapshot.path.display(),
+                        e
+                    ))
+                })?;
+            }
+            None => {
+                // The file did not exist at checkpoint time; a missing
+                // file now already matches the shadow copy
+                if snapshot.path.exists() {
+                    std::fs::remove_file(&snapshot.path).map_err(|e| {
+                        Error::Other(format!(
+                            "Checkpoint restore of {} failed: {}",
+                            snapshot.path.display(),
+                            e
+                        ))
+                    })?;
+                }
+            }
+        }
+        restored.push(snapshot.path.display().to_string());
+    }
+    Ok(format!(
+        "Restored {} file(s) to their state {} message #{}:\n  {}",
+        restored.len(),
+        direction,
+        checkpoint.message_index,
+        restored.join("\n  ")
+    ))
+}
+
+/// Session-wide store, shared between the tool executor (which records
+/// snapshots) and the TUI (which drives /undo and /redo). Same pattern as
+/// the dry-run flag in tools.rs.
+static STORE: Mutex<Option<CheckpointStore>> = Mutex::new(None);
+
+fn with_store<T>(f: impl FnOnce(&mut CheckpointStore) -> T) -> Option<T> {
+    let mut guard = STORE.lock().ok()?;
+    Some(f(guard.get_or_insert_with(CheckpointStore::default)))
+}
+
+/// Mark the start of a user turn (see [`CheckpointStore::begin_turn`])
+pub fn begin_turn(message_index: usize) {
+    with_store(|store| store.begin_turn(message_index));
+}
+
+/// Record a shadow copy of `path` before a tool modifies it
+pub fn record_before_modify(path: &Path) {
+    with_store(|store| store.record_before_modify(path));
+}
+
+/// Undo the most recent checkpoint; None when there is nothing to undo
+pub fn undo() -> Result<Option<String>> {
+    with_store(CheckpointStore::undo).unwrap_or(Ok(None))
+}
+
+/// Redo the most recently undone checkpoint; None when there is nothing
+/// to redo
+pub fn redo() -> Result<Option<String>> {
+    with_store(CheckpointStore::redo).unwrap_or(Ok(None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_restores_previous_content() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "original").expect("write");
+
+        let mut store = CheckpointStore::default();
+        store.begin_turn(1);
+        store.record_before_modify(&file);
+        std::fs::write(&file, "modified").expect("write");
+
+        let summary = store.undo().expect("undo").expect("checkpoint");
+        assert!(summary.contains("message #1"));
+        assert_eq!(std::fs::read_to_string(&file).expect("read"), "original");
+    }
+
+    #[test]
+    fn test_undo_removes_file_created_this_turn() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("new.txt");
+
+        let mut store = CheckpointStore::default();
+        store.begin_turn(3);
+        store.record_before_modify(&file);
+        std::fs::write(&file, "created").expect("write");
+
+        store.undo().expect("undo").expect("checkpoint");
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_redo_reapplies_undone_changes() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "original").expect("write");
+
+        let mut store = CheckpointStore::default();
+        store.begin_turn(1);
+        store.record_before_modify(&file);
+        std::fs::write(&file, "modified").expect("write");
+
+        store.undo().expect("undo").expect("checkpoint");
+        assert_eq!(std::fs::read_to_string(&file).expect("read"), "original");
+
+        store.redo().expect("redo").expect("checkpoint");
+        assert_eq!(std::fs::read_to_string(&file).expect("read"), "modified");
+    }
+
+    #[test]
+    fn test_first_snapshot_per_turn_wins() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "original").expect("write");
+
+        let mut store = CheckpointStore::default();
+        store.begin_turn(1);
+        store.record_before_modify(&file);
+        std::fs::write(&file, "first edit").expect("write");
+        store.record_before_modify(&file);
+        std::fs::write(&file, "second edit").expect("write");
+
+        store.undo().expect("undo").expect("checkpoint");
+        assert_eq!(std::fs::read_to_string(&file).expect("read"), "original");
+    }
+
+    #[test]
+    fn test_turns_undo_independently() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "v1").expect("write");
+
+        let mut store = CheckpointStore::default();
+        store.begin_turn(1);
+        store.record_before_modify(&file);
+        std::fs::write(&file, "v2").expect("write");
+        store.begin_turn(2);
+        store.record_before_modify(&file);
+        std::fs::write(&file, "v3").expect("write");
+
+        store.undo().expect("undo").expect("checkpoint");
+        assert_eq!(std::fs::read_to_string(&file).expect("read"), "v2");
+        store.undo().expect("undo").expect("checkpoint");
+        assert_eq!(std::fs::read_to_string(&file).expect("read"), "v1");
+        assert!(store.undo().expect("undo").is_none());
+    }
+
+    #[test]
+    fn test_new_modification_clears_redo() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "v1").expect("write");
+
+        let mut store = CheckpointStore::default();
+        store.begin_turn(1);
+        store.record_before_modify(&file);
+        std::fs::write(&file, "v2").expect("write");
+        store.undo().expect("undo").expect("checkpoint");
+
+        store.begin_turn(2);
+        store.record_before_modify(&file);
+        std::fs::write(&file, "v4").expect("write");
+
+        assert!(store.redo().expect("redo").is_none());
+    }
+}
@@ -46,6 +46,14 @@ pub struct Cli {
     #[arg(long, value_enum, default_value = "text")]
     pub input_format: InputFormat,
 
+    /// JSON Schema file the result must conform to; the response is validated against it and printed as JSON (only works with --print)
+    #[arg(long, value_name = "file.json")]
+    pub output_schema: Option<PathBuf>,
+
+    /// Stream raw text deltas to stdout unbuffered as they arrive, with tool activity on stderr, so output can be piped into other tools in real time (only works with --print)
+    #[arg(long)]
+    pub stream_text: bool,
+
     /// [DEPRECATED. Use --debug instead] Enable MCP debug mode (shows MCP server errors)
     #[arg(long)]
     pub mcp_debug: bool,
@@ -54,6 +62,10 @@ pub struct Cli {
     #[arg(long)]
     pub dangerously_skip_permissions: bool,
 
+    /// Read-only mode: hard-deny all mutating tools (Write/Edit/NotebookEdit and Bash commands with writes) for the entire session, regardless of approval
+    #[arg(long)]
+    pub read_only: bool,
+
     /// Maximum number of agentic turns in non-interactive mode. This will early exit the conversation after the specified number of turns. (only works with --print)
     #[arg(long, hide = true)]
     pub max_turns: Option<usize>,
@@ -70,6 +82,10 @@ pub struct Cli {
     #[arg(long)]
     pub mcp_config: Option<String>,
 
+    /// Expose this interactive session on a local Unix socket with a JSON-RPC API for external UIs
+    #[arg(long, value_name = "PATH", hide = true)]
+    pub control_socket: Option<std::path::PathBuf>,
+
     /// MCP tool to use for permission prompts (only works with --print)
     #[arg(long, hide = true)]
     pub permission_prompt_tool: Option<String>,
@@ -86,18 +102,33 @@ pub struct Cli {
     #[arg(long, value_enum, hide = true)]
     pub permission_mode: Option<PermissionMode>,
 
-    /// Continue the most recent conversation
-    #[arg(short = 'c', long)]
+    /// Continue the most recent conversation for this directory
+    /// (`--continue-conversation` is kept as a hidden alias)
+    #[arg(short = 'c', long = "continue", alias = "continue-conversation")]
     pub continue_conversation: bool,
 
     /// Resume a conversation - provide a session ID or interactively select a conversation to resume
     #[arg(short = 'r', long, value_name = "SESSION_ID")]
     pub resume: Option<Option<String>>,
 
+    /// List sessions from every project in the /resume picker instead of just the current one
+    #[arg(long)]
+    pub all_projects: bool,
+
     /// Model for the current session. Provide an alias for the latest model (e.g. 'sonnet' or 'opus') or a model's full name (e.g. 'claude-sonnet-4-20250514')
     #[arg(long)]
     pub model: Option<String>,
 
+    /// Authentication method for this session. 'oauth' enables the Claude.ai
+    /// OAuth login paths (overrides the features.oauth setting)
+    #[arg(long, value_enum)]
+    pub auth_method: Option<AuthMethodArg>,
+
+    /// Named auth profile from the `profiles` config section, with its own
+    /// API key/base URL/model defaults (overrides ANTHROPIC_PROFILE)
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
     // Logging configuration flags
     /// Module-specific log levels (e.g. "llminate=debug,hyper=warn,tokio=info")
     #[arg(long)]
@@ -160,6 +191,64 @@ pub enum Commands {
         #[command(subcommand)]
         command: McpCommands,
     },
+    /// Submit a JSONL file of prompts via the Messages Batches API
+    Batch {
+        /// Input JSONL file (one request per line: a "prompt" string or
+        /// "messages" array, with optional custom_id/model/system)
+        #[arg(long)]
+        input: std::path::PathBuf,
+        /// Output JSONL file for the results
+        #[arg(long)]
+        output: std::path::PathBuf,
+        /// Seconds between status polls
+        #[arg(long, default_value_t = 10)]
+        poll_interval: u64,
+    },
+    /// Run a headless server exposing an OpenAI-compatible local API
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8787)]
+        port: u16,
+        /// Host to bind (loopback by default; widen deliberately)
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+    /// Run as an ACP agent over stdio for editor integration (eg. Zed)
+    Acp,
+    /// Replay a saved session read-only in the TUI, turn by turn
+    Replay {
+        /// Session ID to play back (find one with `llminate sessions search`)
+        session_id: String,
+    },
+    /// Inspect locally saved conversation sessions
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommands,
+    },
+    /// Generate a usage report from locally saved sessions
+    Report {
+        /// Aggregation window: day, week, or month
+        #[arg(long, default_value = "week")]
+        period: String,
+        /// Output format: md or text
+        #[arg(long, default_value = "md")]
+        format: String,
+    },
+    /// Inspect and control anonymous usage telemetry
+    Telemetry {
+        #[command(subcommand)]
+        command: TelemetryCommands,
+    },
+    /// Export or import shareable settings bundles for teams
+    Settings {
+        #[command(subcommand)]
+        command: SettingsCommands,
+    },
+    /// Authentication utilities beyond the interactive wizard
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
     /// Migrate from global npm installation to local installation
     MigrateInstaller,
     /// Check the health of your llminate auto-updater
@@ -218,6 +307,87 @@ pub enum ConfigCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum SessionsCommands {
+    /// Search messages across all saved conversations in this project
+    Search {
+        /// Search terms
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// Maximum number of matches to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Export a saved conversation as a shareable document
+    Export {
+        /// Session id (as shown by /resume or sessions search)
+        id: String,
+        /// Output format: md, html, or jsonl
+        #[arg(long, default_value = "md")]
+        format: String,
+        /// Write to a file instead of stdout
+        #[arg(long, value_name = "PATH")]
+        output: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommands {
+    /// Sign in to AWS IAM Identity Center via the device-code flow and
+    /// write the SSO token cache Bedrock SSO profiles consume, without
+    /// needing the AWS CLI installed
+    AwsSsoLogin {
+        /// SSO start URL (otherwise read from the AWS config profile)
+        #[arg(long)]
+        start_url: Option<String>,
+        /// SSO region (otherwise read from the AWS config profile)
+        #[arg(long)]
+        region: Option<String>,
+        /// AWS config profile to resolve the start URL and region from
+        #[arg(long)]
+        profile: Option<String>,
+        /// Print the verification URL instead of opening a browser
+        #[arg(long)]
+        no_browser: bool,
+    },
+    /// Walk every credential source in precedence order, report which
+    /// one is active, and validate it against the API with a cheap
+    /// request
+    Doctor,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SettingsCommands {
+    /// Package permission rules, hooks, MCP servers, the output style,
+    /// and command files into a versioned bundle
+    Export {
+        /// Bundle file to write
+        #[arg(long, value_name = "file.json")]
+        bundle: std::path::PathBuf,
+    },
+    /// Apply a settings bundle, skipping anything already configured
+    Import {
+        /// Bundle file to read
+        #[arg(long, value_name = "file.json")]
+        bundle: std::path::PathBuf,
+        /// Overwrite conflicting settings, servers, and files
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TelemetryCommands {
+    /// Print exactly which events/fields are collected and where they go
+    Show,
+    /// Print the current opt-in status
+    Status,
+    /// Enable telemetry collection
+    On,
+    /// Disable all telemetry sinks
+    Off,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum McpCommands {
     /// Start the llminate MCP server
@@ -265,6 +435,11 @@ pub enum McpCommands {
         /// Server name
         name: String,
     },
+    /// Authenticate with a remote MCP server via OAuth
+    Auth {
+        /// Server name
+        name: String,
+    },
     /// Add an MCP server (stdio or SSE) with a JSON string
     AddJson {
         /// Server name
@@ -327,6 +502,15 @@ pub enum PermissionMode {
     Deny,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AuthMethodArg {
+    /// API key authentication (ANTHROPIC_API_KEY, apiKeyHelper, stored keys)
+    #[value(name = "api-key")]
+    ApiKey,
+    /// Claude.ai OAuth login (Max subscribers), including the API key exchange fallback
+    Oauth,
+}
+
 impl Cli {
     /// Parse CLI arguments
     pub fn parse_args() -> Self {
@@ -379,6 +563,28 @@ impl Cli {
             eprintln!("Warning: --mcp-debug is deprecated. Please use --debug instead.");
         }
 
+        // CLI override beats the features.oauth setting for this session
+        if let Some(method) = self.auth_method {
+            crate::auth::set_oauth_enabled(matches!(method, AuthMethodArg::Oauth));
+        }
+
+        // Select the auth profile before anything builds a client
+        if let Some(ref name) = self.profile {
+            if crate::config::get_profile(name).is_none() {
+                let available = crate::config::list_profiles();
+                return Err(crate::error::Error::Config(format!(
+                    "Unknown profile '{}'. Available profiles: {}",
+                    name,
+                    if available.is_empty() {
+                        "none defined (add a \"profiles\" section to config.json)".to_string()
+                    } else {
+                        available.join(", ")
+                    }
+                )));
+            }
+            crate::config::set_active_profile(Some(name.clone()));
+        }
+
         // Initialize telemetry
         crate::telemetry::init().await;
 
@@ -395,6 +601,77 @@ impl Cli {
             Some(Commands::Mcp { command }) => {
                 handle_mcp_command(command, debug).await?;
             }
+            Some(Commands::Batch { input, output, poll_interval }) => {
+                // Batches go straight to the API, so credentials must
+                // already exist; fail fast in this scriptable mode
+                crate::auth::get_or_prompt_auth().await.map_err(|e| {
+                    crate::error::Error::Auth(format!(
+                        "Batch mode requires existing credentials (run `llminate` once to sign in): {}",
+                        e
+                    ))
+                })?;
+                crate::ai::batch::run(crate::ai::batch::BatchOptions {
+                    input,
+                    output,
+                    model: self.model,
+                    poll_interval_secs: poll_interval,
+                })
+                .await?;
+            }
+            Some(Commands::Serve { port, host }) => {
+                // The server fronts the configured provider, so credentials
+                // must exist before we start listening
+                if let Err(_) = crate::auth::get_or_prompt_auth().await {
+                    run_authentication_wizard().await?;
+                }
+                crate::server::run(crate::server::ServeOptions {
+                    host,
+                    port,
+                    allowed_tools: self.allowed_tools,
+                    disallowed_tools: self.disallowed_tools,
+                })
+                .await?;
+            }
+            Some(Commands::Acp) => {
+                // Stdout belongs to the protocol in ACP mode, so no auth
+                // wizard: fail fast if credentials are missing
+                crate::auth::get_or_prompt_auth()
+                    .await
+                    .map_err(|e| crate::error::Error::Auth(format!(
+                        "ACP mode requires existing credentials (run `llminate` once to sign in): {}",
+                        e
+                    )))?;
+                crate::acp::run().await?;
+            }
+            Some(Commands::Replay { session_id }) => {
+                // Purely local playback: nothing is sent or re-executed
+                crate::tui::replay::run(&session_id)?;
+            }
+            Some(Commands::Sessions { command }) => {
+                // Purely local: searches the saved conversation files
+                handle_sessions_command(command)?;
+            }
+            Some(Commands::Report { period, format }) => {
+                // Purely local aggregation: no credentials required
+                println!("{}", crate::report::generate(&period, &format)?);
+            }
+            Some(Commands::Telemetry { command }) => {
+                handle_telemetry_command(command)?;
+            }
+            Some(Commands::Settings { command }) => {
+                // Purely local file operations: no credentials required
+                match command {
+                    SettingsCommands::Export { bundle } => {
+                        crate::settings_bundle::export_bundle(&bundle)?;
+                    }
+                    SettingsCommands::Import { bundle, force } => {
+                        crate::settings_bundle::import_bundle(&bundle, force)?;
+                    }
+                }
+            }
+            Some(Commands::Auth { command }) => {
+                handle_auth_command(command).await?;
+            }
             Some(Commands::MigrateInstaller) => {
                 handle_migrate_installer().await?;
             }
@@ -405,6 +682,12 @@ impl Cli {
                 handle_update().await?;
             }
             None => {
+                // First-run telemetry consent, interactive sessions only
+                // (print mode stays scriptable; unanswered means disabled)
+                if self.prompt.is_none() {
+                    crate::telemetry::maybe_prompt_consent()?;
+                }
+
                 // Check authentication before main command
                 if let Err(_) = crate::auth::get_or_prompt_auth().await {
                     // No valid authentication found - run setup wizard
@@ -420,6 +703,168 @@ impl Cli {
     }
 }
 
+/// Handle sessions subcommands
+fn handle_sessions_command(command: SessionsCommands) -> Result<()> {
+    match command {
+        SessionsCommands::Search { query, limit } => {
+            let query = query.join(" ");
+            let dir = crate::tui::state::get_conversation_dir();
+            let hits = crate::session_store::search_sessions(&dir, &query, limit)?;
+
+            if hits.is_empty() {
+                println!("No messages matching '{}' in {}", query, dir.display());
+                return Ok(());
+            }
+
+            for hit in &hits {
+                println!(
+                    "{} {} {}",
+                    hit.session_id.cyan(),
+                    format!("({})", hit.role).dimmed(),
+                    hit.snippet.replace('\n', " ")
+                );
+            }
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "{} match(es). Resume one with: llminate --resume <session-id>",
+                    hits.len()
+                )
+                .dimmed()
+            );
+        }
+        SessionsCommands::Export { id, format, output } => {
+            let format: crate::session_store::ExportFormat = format.parse()?;
+            let dir = crate::tui::state::get_conversation_dir();
+            let record = crate::session_store::load_session(&dir, &id)?.ok_or_else(|| {
+                crate::error::Error::NotFound(format!("Session {} not found in {}", id, dir.display()))
+            })?;
+            let document = crate::session_store::export_session(&record, format)?;
+
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, document)?;
+                    println!("Exported session {} to {}", id, path.display());
+                }
+                None => {
+                    print!("{}", document);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handle auth subcommands
+async fn handle_auth_command(command: AuthCommands) -> Result<()> {
+    match command {
+        AuthCommands::AwsSsoLogin {
+            start_url,
+            region,
+            profile,
+            no_browser,
+        } => run_aws_sso_login(start_url, region, profile, no_browser).await,
+        AuthCommands::Doctor => crate::auth::doctor::run().await,
+    }
+}
+
+/// Perform the AWS IAM Identity Center device-code login and write the
+/// SSO token cache, replacing the external `aws sso login` step
+async fn run_aws_sso_login(
+    start_url: Option<String>,
+    region: Option<String>,
+    profile: Option<String>,
+    no_browser: bool,
+) -> Result<()> {
+    use crate::auth::aws_providers::sso;
+
+    let auth_err = |e: anyhow::Error| crate::error::Error::Auth(format!("AWS SSO login: {}", e));
+
+    let (start_url, region, scopes) = sso::resolve_login_target(profile, start_url, region)
+        .await
+        .map_err(auth_err)?;
+
+    println!("Signing in to AWS IAM Identity Center");
+    println!("  Start URL: {}", start_url);
+    println!("  Region:    {}", region);
+
+    let oidc = sso::SsoOidcClient::new(&region);
+    let registration = oidc
+        .register_client(scopes.as_deref())
+        .await
+        .map_err(auth_err)?;
+    let authorization = oidc
+        .start_device_authorization(&registration, &start_url)
+        .await
+        .map_err(auth_err)?;
+
+    let verification_url = authorization
+        .verification_uri_complete
+        .as_deref()
+        .unwrap_or(&authorization.verification_uri);
+    println!();
+    println!("Confirmation code: {}", authorization.user_code);
+    if no_browser {
+        println!("Open this URL to approve the request:");
+        println!("  {}", verification_url);
+    } else {
+        println!("Opening your browser to approve the request...");
+        if crate::oauth::OAuthManager::open_browser(verification_url).is_err() {
+            println!("Could not open a browser. Open this URL manually:");
+            println!("  {}", verification_url);
+        }
+    }
+    println!(
+        "Waiting for approval (request expires in {} minutes)...",
+        authorization.expires_in.max(60) / 60
+    );
+
+    let (access_token, expires_in) = oidc
+        .wait_for_token(&registration, &authorization)
+        .await
+        .map_err(auth_err)?;
+    let cache_path = sso::write_sso_token_cache(&start_url, &region, &access_token, expires_in)
+        .await
+        .map_err(auth_err)?;
+
+    println!();
+    println!("✅ SSO login complete.");
+    println!(
+        "Token cached at {} (valid for about {} hours).",
+        cache_path.display(),
+        (expires_in / 3600).max(1)
+    );
+    println!("Bedrock SSO profiles will pick it up automatically.");
+    Ok(())
+}
+
+/// Handle telemetry subcommands
+fn handle_telemetry_command(command: TelemetryCommands) -> Result<()> {
+    match command {
+        TelemetryCommands::Show => {
+            print!("{}", crate::telemetry::transparency_report());
+        }
+        TelemetryCommands::Status => {
+            let status = if crate::telemetry::is_enabled() {
+                "enabled"
+            } else {
+                "disabled"
+            };
+            println!("Telemetry is {}", status);
+        }
+        TelemetryCommands::On => {
+            crate::telemetry::set_consent(true)?;
+            println!("Telemetry enabled. Run `llminate telemetry show` to see what is collected.");
+        }
+        TelemetryCommands::Off => {
+            crate::telemetry::set_consent(false)?;
+            println!("Telemetry disabled. No events will be collected or sent.");
+        }
+    }
+    Ok(())
+}
+
 /// Handle config subcommands
 async fn handle_config_command(command: ConfigCommands) -> Result<()> {
     use crate::config;
@@ -489,6 +934,9 @@ async fn handle_mcp_command(command: McpCommands, debug: bool) -> Result<()> {
         McpCommands::Get { name } => {
             mcp::get_server(&name).await?;
         }
+        McpCommands::Auth { name } => {
+            mcp::auth_server(&name).await?;
+        }
         McpCommands::AddJson { name, json, scope } => {
             mcp::add_server_json(&name, &json, scope).await?;
         }
@@ -656,6 +1104,18 @@ async fn handle_main_command(cli: Cli, debug: bool) -> Result<()> {
         }
     }
     
+    let mut cli = cli;
+    if cli.read_only {
+        // Configure the permissions engine to hard-deny all mutating operations
+        // for the entire session, and hide the mutating tools from the model
+        crate::permissions::PERMISSION_CONTEXT.lock().await.read_only = true;
+        for tool in ["Write", "Edit", "MultiEdit", "NotebookEdit", "KillBash"] {
+            if !cli.disallowed_tools.iter().any(|t| t == tool) {
+                cli.disallowed_tools.push(tool.to_string());
+            }
+        }
+    }
+
     if cli.print {
         // Non-interactive print mode
         handle_print_mode(cli, debug).await?;
@@ -702,8 +1162,10 @@ async fn handle_print_mode(cli: Cli, debug: bool) -> Result<()> {
         mcp_config: cli.mcp_config,
         permission_prompt_tool: cli.permission_prompt_tool,
         dangerously_skip_permissions: cli.dangerously_skip_permissions,
+        output_schema: cli.output_schema,
+        stream_text: cli.stream_text,
     };
-    
+
     print_mode::run(options).await
 }
 
@@ -721,10 +1183,12 @@ async fn handle_interactive_mode(cli: Cli, debug: bool) -> Result<()> {
         add_dirs: cli.add_dir,
         continue_conversation: cli.continue_conversation,
         resume_session_id: cli.resume.and_then(|r| r),
+        all_projects: cli.all_projects,
         mcp_config: cli.mcp_config,
         dangerously_skip_permissions: cli.dangerously_skip_permissions,
+        control_socket: cli.control_socket,
     };
-    
+
     interactive_mode::run(options).await
 }
 
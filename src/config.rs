@@ -47,7 +47,8 @@ pub struct Config {
     pub model: Option<String>,
     pub verbose: Option<bool>,
     pub api_key_helper: Option<String>,
-    
+    pub auth_token_helper: Option<String>,
+
     // Features
     pub todo_feature_enabled: Option<bool>,
     pub memory_usage_count: Option<u32>,
@@ -98,12 +99,24 @@ pub struct Config {
     
     // API key management
     pub custom_api_key_responses: Option<serde_json::Value>,
+
+    // Analytics metadata: overrides the constructed metadata.user_id
+    // (user_{id}_account_{uuid}_session_{id}) when set
+    pub metadata_user_id: Option<String>,
     
     // Environment variables
     pub env: Option<HashMap<String, String>>,
-    
+
+    // Named auth profiles (work/personal/...), selected with --profile,
+    // ANTHROPIC_PROFILE, or /profile
+    pub profiles: Option<HashMap<String, ProfileConfig>>,
+
     // Editor settings
     pub editor_mode: Option<String>,
+
+    // Display layout: cap rendered chat content at this many columns,
+    // centered, on terminals wider than the cap
+    pub reading_width: Option<u16>,
     
     // Auto compact
     pub auto_compact_enabled: Option<bool>,
@@ -137,6 +150,9 @@ pub struct Config {
     
     // Cached data
     pub cached_changelog: Option<String>,
+
+    // Version whose release notes were last shown (drives the post-update banner)
+    pub last_release_notes_seen: Option<String>,
     
     // Terminal settings
     pub has_used_backslash_return: Option<bool>,
@@ -193,6 +209,20 @@ impl Default for LoggingConfig {
     }
 }
 
+/// One named auth profile (the `profiles` section of config.json): its
+/// own API key, endpoint, and model defaults. Selected with --profile,
+/// the ANTHROPIC_PROFILE environment variable, or /profile mid-session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpServerConfig {
@@ -221,6 +251,444 @@ pub struct PermissionsConfig {
     /// Deny rules for tools
     #[serde(default)]
     pub deny: Vec<String>,
+
+    /// Tools that may not run at all (disallowedTools in JS). Primarily
+    /// used by the managed policy file, where it cannot be overridden.
+    #[serde(default)]
+    pub disallowed_tools: Vec<String>,
+}
+
+/// One guardrail from the `guardrails` settings list: a hard constraint
+/// stated to the model in the system prompt and enforced deterministically
+/// by the permissions engine, so the prompt and the guard cannot drift
+/// apart. A rule with neither `denyCommands` nor `protectedPaths` is
+/// prompt-only guidance.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardrailConfig {
+    /// The constraint sentence injected into the system prompt
+    /// (e.g. "Never run rm -rf")
+    pub rule: String,
+
+    /// Substring patterns that hard-deny Bash commands containing them
+    /// (e.g. "rm -rf")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny_commands: Vec<String>,
+
+    /// Path fragments under which mutating file operations are
+    /// hard-denied (e.g. "migrations/"); matched against the resolved
+    /// path on component boundaries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected_paths: Vec<String>,
+}
+
+/// Parse a settings permission rule like `Bash(npm:*)` into a
+/// (tool, pattern) pair. A bare tool name matches everything that tool does.
+pub fn parse_permission_rule(rule: &str) -> (String, String) {
+    match rule.split_once('(') {
+        Some((tool, rest)) => (
+            tool.trim().to_string(),
+            rest.trim_end_matches(')').trim().to_string(),
+        ),
+        None => (rule.trim().to_string(), "*".to_string()),
+    }
+}
+
+/// Environment variable policy for tool subprocesses (envPolicy in settings.json).
+/// Applied when spawning Bash commands, hooks, and MCP stdio servers so secrets
+/// in the parent environment do not leak to arbitrary child processes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvPolicyConfig {
+    /// If non-empty, only variables matching these patterns (plus a small
+    /// essential baseline like PATH and HOME) are passed to child processes.
+    /// Patterns support a trailing `*` wildcard (e.g. "CARGO_*").
+    #[serde(default)]
+    pub allow: Vec<String>,
+
+    /// Variables matching these patterns are always removed, even if allowed
+    #[serde(default)]
+    pub deny: Vec<String>,
+
+    /// Variables injected into every child process environment
+    #[serde(default)]
+    pub inject: HashMap<String, String>,
+}
+
+impl EnvPolicyConfig {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty() && self.inject.is_empty()
+    }
+}
+
+/// Per-tool configuration (the `tools` section of settings.json, keyed by
+/// tool name). Lets a project disable tools, cap Bash timeout and output,
+/// restrict WebFetch domains, and hook a formatter into file edits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConfig {
+    /// Disable this tool entirely; dispatch fails before execution
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    /// Upper bound on the tool's timeout parameter in milliseconds (Bash)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_timeout_ms: Option<u64>,
+    /// Truncate output beyond this many characters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_chars: Option<usize>,
+    /// Domain allow-list for this tool's URLs (WebFetch); matches the exact
+    /// host or any subdomain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_domains: Option<Vec<String>>,
+    /// Shell command run on the file after a successful edit. A `{file}`
+    /// placeholder is substituted; without one the path is appended.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_command: Option<String>,
+}
+
+/// Resolve the configuration for one tool across settings sources. Later
+/// sources win per field: User, then Project, then Local.
+pub fn get_tool_config(tool_name: &str) -> ToolConfig {
+    let mut merged = ToolConfig::default();
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(config) = settings.tools.as_ref().and_then(|tools| tools.get(tool_name)) {
+                if config.disabled.is_some() {
+                    merged.disabled = config.disabled;
+                }
+                if config.max_timeout_ms.is_some() {
+                    merged.max_timeout_ms = config.max_timeout_ms;
+                }
+                if config.max_output_chars.is_some() {
+                    merged.max_output_chars = config.max_output_chars;
+                }
+                if config.allowed_domains.is_some() {
+                    merged.allowed_domains = config.allowed_domains.clone();
+                }
+                if config.format_command.is_some() {
+                    merged.format_command = config.format_command.clone();
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// Voice input configuration (the `voice` section of settings.json).
+/// Recording and transcription both shell out or call an HTTP endpoint so
+/// users can plug in whisper.cpp, a local server, or a hosted API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceConfig {
+    /// Shell command that records audio to a WAV file. `{file}` and
+    /// `{seconds}` placeholders are substituted. Defaults to an autodetected
+    /// recorder (ffmpeg, arecord, or sox).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_command: Option<String>,
+    /// Shell command that transcribes a WAV file and prints the transcript
+    /// to stdout (eg. a whisper.cpp invocation). `{file}` is substituted.
+    /// Takes precedence over the transcription API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcribe_command: Option<String>,
+    /// OpenAI-compatible transcription endpoint (eg. /v1/audio/transcriptions)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Model name sent to the transcription API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_model: Option<String>,
+    /// Environment variable holding the API key (default: OPENAI_API_KEY)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Maximum recording length in seconds (default: 15)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_seconds: Option<u64>,
+}
+
+/// Resolve voice configuration across settings sources. Later sources win
+/// per field: User, then Project, then Local.
+pub fn get_voice_config() -> VoiceConfig {
+    let mut merged = VoiceConfig::default();
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(config) = &settings.voice {
+                if config.record_command.is_some() {
+                    merged.record_command = config.record_command.clone();
+                }
+                if config.transcribe_command.is_some() {
+                    merged.transcribe_command = config.transcribe_command.clone();
+                }
+                if config.api_url.is_some() {
+                    merged.api_url = config.api_url.clone();
+                }
+                if config.api_model.is_some() {
+                    merged.api_model = config.api_model.clone();
+                }
+                if config.api_key_env.is_some() {
+                    merged.api_key_env = config.api_key_env.clone();
+                }
+                if config.max_seconds.is_some() {
+                    merged.max_seconds = config.max_seconds;
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// Resolve the attachment budget as a share of the context window. Later
+/// sources win: User, then Project, then Local. Clamped to keep at least
+/// some room for the conversation itself.
+pub fn get_attachment_context_share() -> f32 {
+    let mut share = 0.25;
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(value) = settings.attachment_context_share {
+                share = value;
+            }
+        }
+    }
+    share.clamp(0.05, 0.9)
+}
+
+/// Credential storage backend choice (credentialStorage in settings.json).
+/// Later sources win, mirroring the other settings getters
+pub fn get_credential_storage() -> String {
+    let mut backend = "keyring".to_string();
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(value) = settings.credential_storage {
+                backend = value;
+            }
+        }
+    }
+    backend
+}
+
+/// Whether tool outputs and diffs rewrite absolute workspace paths to
+/// relative form (normalizePaths in settings.json). On by default;
+/// later sources win so a managed policy can pin it either way
+pub fn get_normalize_paths() -> bool {
+    let mut enabled = true;
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(value) = settings.normalize_paths {
+                enabled = value;
+            }
+        }
+    }
+    enabled
+}
+
+/// Session-wide auth profile override set by --profile or /profile;
+/// None falls back to the ANTHROPIC_PROFILE environment variable
+static ACTIVE_PROFILE: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// Select (or clear) the active auth profile for this session
+pub fn set_active_profile(name: Option<String>) {
+    if let Ok(mut guard) = ACTIVE_PROFILE.lock() {
+        *guard = name;
+    }
+}
+
+/// The active auth profile name: the session override, else
+/// ANTHROPIC_PROFILE from the environment
+pub fn active_profile_name() -> Option<String> {
+    if let Ok(guard) = ACTIVE_PROFILE.lock() {
+        if let Some(name) = guard.as_ref() {
+            return Some(name.clone());
+        }
+    }
+    std::env::var("ANTHROPIC_PROFILE")
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
+/// Look up a named profile across config scopes (project > local > user)
+pub fn get_profile(name: &str) -> Option<ProfileConfig> {
+    let config = get_merged_config().ok()?;
+    config.profiles?.get(name).cloned()
+}
+
+/// The active profile and its name, when one is selected and defined
+pub fn get_active_profile() -> Option<(String, ProfileConfig)> {
+    let name = active_profile_name()?;
+    let profile = get_profile(&name)?;
+    Some((name, profile))
+}
+
+/// All defined profile names, sorted
+pub fn list_profiles() -> Vec<String> {
+    let mut names: Vec<String> = get_merged_config()
+        .ok()
+        .and_then(|config| config.profiles)
+        .map(|profiles| profiles.keys().cloned().collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Clarification behavior (the `clarification` section of settings.json):
+/// how eagerly the agent asks clarifying questions instead of acting.
+/// Scripted/CI personas set `"mode": "decisive"` to suppress questions
+/// entirely; interactive users can raise the per-turn budget for more
+/// check-ins.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClarificationConfig {
+    /// "eager", "balanced" (default), or "decisive"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Maximum AskUserQuestion calls per user turn (default: 3; forced to 0
+    /// in decisive mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_questions_per_turn: Option<u32>,
+}
+
+impl ClarificationConfig {
+    /// The enforced per-turn question budget
+    pub fn question_budget(&self) -> u32 {
+        if self.mode.as_deref() == Some("decisive") {
+            return 0;
+        }
+        self.max_questions_per_turn.unwrap_or(3)
+    }
+}
+
+/// Resolve clarification configuration across settings sources. Later
+/// sources win per field: User, then Project, then Local.
+pub fn get_clarification_config() -> ClarificationConfig {
+    let mut merged = ClarificationConfig::default();
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(config) = &settings.clarification {
+                if config.mode.is_some() {
+                    merged.mode = config.mode.clone();
+                }
+                if config.max_questions_per_turn.is_some() {
+                    merged.max_questions_per_turn = config.max_questions_per_turn;
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// Text-to-speech configuration (the `tts` section of settings.json).
+/// Mirrors the voice-input layout: a local command takes precedence, then
+/// an OpenAI-compatible API, then platform TTS autodetection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsConfig {
+    /// Read assistant messages aloud (default: off)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Speak only the first sentence or two of each response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summaries_only: Option<bool>,
+    /// Shell command that speaks text; `{text}` is substituted pre-quoted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// OpenAI-compatible speech endpoint (eg. /v1/audio/speech)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Model name sent to the speech API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_model: Option<String>,
+    /// Voice name sent to the speech API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+    /// Environment variable holding the API key (default: OPENAI_API_KEY)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+}
+
+/// Resolve TTS configuration across settings sources. Later sources win
+/// per field: User, then Project, then Local.
+pub fn get_tts_config() -> TtsConfig {
+    let mut merged = TtsConfig::default();
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(config) = &settings.tts {
+                if config.enabled.is_some() {
+                    merged.enabled = config.enabled;
+                }
+                if config.summaries_only.is_some() {
+                    merged.summaries_only = config.summaries_only;
+                }
+                if config.command.is_some() {
+                    merged.command = config.command.clone();
+                }
+                if config.api_url.is_some() {
+                    merged.api_url = config.api_url.clone();
+                }
+                if config.api_model.is_some() {
+                    merged.api_model = config.api_model.clone();
+                }
+                if config.voice.is_some() {
+                    merged.voice = config.voice.clone();
+                }
+                if config.api_key_env.is_some() {
+                    merged.api_key_env = config.api_key_env.clone();
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// The `features` section of settings.json: tri-state so user and project
+/// settings can each enable, disable, or leave a flag at its default
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantic_index: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browser_tool: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speculative_prefetch: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<bool>,
+}
+
+/// Resolved feature flags threaded through the app. Experimental subsystems
+/// ship dark (default false) and are enabled via the `features` settings
+/// section per user or per project.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FeatureFlags {
+    /// Semantic code index for search and @-mention ranking
+    pub semantic_index: bool,
+    /// Headless Chrome automation (Browser tool)
+    pub browser_tool: bool,
+    /// Speculatively prefetch likely-read files while the model streams
+    pub speculative_prefetch: bool,
+    /// Claude.ai OAuth login paths in determine_auth_method (disabled upstream)
+    pub oauth: bool,
+}
+
+/// Resolve feature flags across settings sources. Later sources win per
+/// flag: User, then Project, then Local.
+pub fn get_feature_flags() -> FeatureFlags {
+    let mut flags = FeatureFlags::default();
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(features) = settings.features {
+                if let Some(enabled) = features.semantic_index {
+                    flags.semantic_index = enabled;
+                }
+                if let Some(enabled) = features.browser_tool {
+                    flags.browser_tool = enabled;
+                }
+                if let Some(enabled) = features.speculative_prefetch {
+                    flags.speculative_prefetch = enabled;
+                }
+                if let Some(enabled) = features.oauth {
+                    flags.oauth = enabled;
+                }
+            }
+        }
+    }
+    flags
 }
 
 /// Settings file structure matching JavaScript settings.json schema
@@ -232,6 +700,107 @@ pub struct Settings {
     #[serde(default)]
     pub permissions: PermissionsConfig,
 
+    /// Domain allow-list for the HttpRequest tool (httpAllowedDomains in settings.json).
+    /// When non-empty across all sources, requests to other domains are denied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_allowed_domains: Option<Vec<String>>,
+
+    /// Environment variable policy for tool subprocesses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_policy: Option<EnvPolicyConfig>,
+
+    /// Experimental feature flags (features in settings.json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<FeatureFlagsConfig>,
+
+    /// Per-tool configuration keyed by tool name (tools in settings.json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<HashMap<String, ToolConfig>>,
+
+    /// Hard constraints injected into the system prompt and enforced by
+    /// the permissions engine (guardrails in settings.json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrails: Option<Vec<GuardrailConfig>>,
+
+    /// Voice input configuration (voice in settings.json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<VoiceConfig>,
+
+    /// Text-to-speech configuration (tts in settings.json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts: Option<TtsConfig>,
+
+    /// Clarification behavior (clarification in settings.json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clarification: Option<ClarificationConfig>,
+
+    /// Share of the context window @-mention attachments may use before
+    /// large files fall back to head/tail excerpts (default: 0.25)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment_context_share: Option<f32>,
+
+    /// Telemetry collection policy (telemetry in settings.json). A managed
+    /// policy value of false disables collection regardless of user consent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub telemetry: Option<bool>,
+
+    /// Credential storage backend (credentialStorage in settings.json):
+    /// "keyring" (OS keyring with plaintext fallback, default),
+    /// "encrypted" (passphrase/keyring-keyed ChaCha20-Poly1305 file), or
+    /// "plaintext" (credentials file only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_storage: Option<String>,
+
+    /// Extra request headers keyed by provider name (customHeaders in
+    /// settings.json): "anthropic", "openai-compatible", or "bedrock".
+    /// For corporate LLM gateways that key routing or auth off headers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_headers: Option<HashMap<String, HashMap<String, String>>>,
+
+    /// mTLS client certificate for corporate egress proxies (clientCert in
+    /// settings.json): a PEM path, a PKCS#12 path, or `keychain:<name>`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+
+    /// PKCS#8 PEM key path paired with clientCert (clientKey in settings.json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+
+    /// Passphrase for a PKCS#12 clientCert bundle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key_passphrase: Option<String>,
+
+    /// Commit message attribution trailers (attribution in settings.json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<AttributionConfig>,
+
+    /// Language the assistant should respond in (responseLanguage in
+    /// settings.json): a BCP 47 code or plain language name, injected
+    /// into the system prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_language: Option<String>,
+
+    /// Suppress the startup tip ("don't show again" for the tips system)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_startup_tips: Option<bool>,
+
+    /// Conversation persistence backend (sessionStore in settings.json):
+    /// "json" (default, one file per session) or "sqlite" (single
+    /// database with full-text search)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_store: Option<String>,
+
+    /// Rewrite absolute workspace paths in tool outputs and diffs to
+    /// relative form (normalizePaths in settings.json, default true).
+    /// Set false for multi-root setups where stripping the workspace
+    /// prefix would be ambiguous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalize_paths: Option<bool>,
+
+    /// Index of the next startup tip to show (rotates each session)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_tip_index: Option<usize>,
+
     /// Dynamic fields for extensibility
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
@@ -246,10 +815,22 @@ pub enum SettingsSource {
     Project,
     /// Local settings: .claude/settings.local.json (gitignored)
     Local,
+    /// Managed enterprise policy: root-owned, read-only, always wins
+    Managed,
     /// Session: runtime only, not persisted
     Session,
 }
 
+/// Settings sources in merge order. Later sources win for last-wins
+/// fields; the managed policy is last so user/project settings cannot
+/// override it.
+pub const SETTINGS_MERGE_ORDER: [SettingsSource; 4] = [
+    SettingsSource::User,
+    SettingsSource::Project,
+    SettingsSource::Local,
+    SettingsSource::Managed,
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PermissionMode {
     Default,
@@ -265,6 +846,7 @@ impl Default for Config {
             model: Some("claude-opus-4-1-20250805".to_string()),
             verbose: Some(false),
             api_key_helper: Some("claude-api-key".to_string()),
+            auth_token_helper: None,
             todo_feature_enabled: Some(true),
             memory_usage_count: Some(0),
             prompt_queue_use_count: Some(0),
@@ -294,8 +876,11 @@ impl Default for Config {
             parallel_tasks_count: Some(1),
             oauth_account: None,
             custom_api_key_responses: None,
+            metadata_user_id: None,
             env: Some(HashMap::new()),
+            profiles: None,
             editor_mode: None,
+            reading_width: None,
             auto_compact_enabled: Some(true),
             diff_tool: Some("auto".to_string()),
             initial_data_sharing_message_seen: Some(false),
@@ -309,6 +894,7 @@ impl Default for Config {
             message_idle_notif_threshold_ms: Some(60000),
             bypass_permissions_mode_accepted: Some(false),
             cached_changelog: None,
+            last_release_notes_seen: None,
             has_used_backslash_return: Some(false),
             iterm2_backup_path: None,
             terminal_progress_bar_enabled: Some(true),
@@ -787,12 +1373,37 @@ pub fn get_local_settings_path() -> Option<PathBuf> {
     get_project_config_dir().map(|p| p.join(".claude").join("settings.local.json"))
 }
 
+/// Get the path for the managed enterprise policy file.
+///
+/// Root-owned and outside any user-writable location, so IT can pin
+/// permission rules, disallowed tools, and telemetry settings that
+/// user/project settings cannot override.
+pub fn get_managed_settings_path() -> Option<PathBuf> {
+    // Tests point this at a temp file; real deployments use the fixed paths
+    #[cfg(test)]
+    if let Ok(path) = std::env::var("LLMINATE_MANAGED_SETTINGS_PATH_FOR_TESTS") {
+        return Some(PathBuf::from(path));
+    }
+
+    #[cfg(target_os = "macos")]
+    return Some(PathBuf::from(
+        "/Library/Application Support/ClaudeCode/managed-settings.json",
+    ));
+    #[cfg(target_os = "windows")]
+    return Some(PathBuf::from(
+        "C:\\ProgramData\\ClaudeCode\\managed-settings.json",
+    ));
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    Some(PathBuf::from("/etc/claude-code/managed-settings.json"))
+}
+
 /// Get the settings file path for a given source
 pub fn get_settings_path(source: SettingsSource) -> Option<PathBuf> {
     match source {
         SettingsSource::User => Some(get_user_settings_path()),
         SettingsSource::Project => get_project_settings_path(),
         SettingsSource::Local => get_local_settings_path(),
+        SettingsSource::Managed => get_managed_settings_path(),
         SettingsSource::Session => None, // Session settings are not persisted
     }
 }
@@ -819,6 +1430,13 @@ pub fn load_settings(source: SettingsSource) -> Result<Settings> {
 
 /// Save settings to a specific source
 pub fn save_settings(source: SettingsSource, settings: &Settings) -> Result<()> {
+    // The managed policy is administered out of band; never written here
+    if source == SettingsSource::Managed {
+        return Err(Error::Config(
+            "Managed policy settings are read-only".to_string(),
+        ));
+    }
+
     let path = match get_settings_path(source) {
         Some(p) => p,
         None => return Err(Error::Config("Cannot save session settings".to_string())),
@@ -886,7 +1504,7 @@ pub fn get_all_additional_directories() -> Result<Vec<(String, SettingsSource)>>
     let mut directories = Vec::new();
 
     // Load from each source in order (user -> project -> local)
-    for source in [SettingsSource::User, SettingsSource::Project, SettingsSource::Local] {
+    for source in SETTINGS_MERGE_ORDER {
         if let Ok(settings) = load_settings(source) {
             for dir in settings.permissions.additional_directories {
                 directories.push((dir, source));
@@ -897,12 +1515,349 @@ pub fn get_all_additional_directories() -> Result<Vec<(String, SettingsSource)>>
     Ok(directories)
 }
 
+/// Get the managed (enterprise) permissions policy.
+///
+/// Read directly from the managed source — never merged with user or
+/// project settings, so nothing they contain can dilute it. Missing or
+/// unreadable policy files yield an empty (permissive) config.
+pub fn get_managed_permissions() -> PermissionsConfig {
+    load_settings(SettingsSource::Managed)
+        .map(|settings| settings.permissions)
+        .unwrap_or_default()
+}
+
+/// Get the extra request headers for a provider, merged across settings
+/// sources (later sources win per header name) with the
+/// `ANTHROPIC_CUSTOM_HEADERS` environment variable applied last.
+///
+/// The env variable holds newline-separated `Name: Value` pairs and
+/// applies to every provider, for gateways fronted by a single proxy.
+pub fn get_custom_headers(provider: &str) -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(all) = settings.custom_headers {
+                if let Some(provider_headers) = all.get(provider) {
+                    for (name, value) in provider_headers {
+                        headers.insert(name.clone(), value.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    if let Ok(raw) = std::env::var("ANTHROPIC_CUSTOM_HEADERS") {
+        for (name, value) in parse_custom_headers(&raw) {
+            headers.insert(name, value);
+        }
+    }
+
+    headers
+}
+
+/// Attribution trailers appended to `git commit` messages made through
+/// the Bash tool (attribution in settings.json). Enforced at tool
+/// dispatch so attribution does not depend on the model remembering
+/// prompt instructions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributionConfig {
+    /// Append the Co-Authored-By trailer (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub co_authored_by: Option<bool>,
+
+    /// Append the "Generated with" line (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated_with: Option<bool>,
+
+    /// Replace the standard trailers with custom text; an empty string
+    /// disables trailers entirely
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_trailer: Option<String>,
+}
+
+impl AttributionConfig {
+    /// The trailer block to append to commit messages, or None when the
+    /// configuration disables attribution
+    pub fn commit_trailer(&self) -> Option<String> {
+        if let Some(custom) = &self.custom_trailer {
+            let custom = custom.trim();
+            return if custom.is_empty() {
+                None
+            } else {
+                Some(custom.to_string())
+            };
+        }
+        let mut lines = Vec::new();
+        if self.generated_with.unwrap_or(true) {
+            lines.push(format!("🤖 Generated with {}", env!("CARGO_PKG_NAME")));
+        }
+        if self.co_authored_by.unwrap_or(true) {
+            lines.push("Co-Authored-By: Claude <noreply@anthropic.com>".to_string());
+        }
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+}
+
+/// Resolve attribution configuration across settings sources. Later
+/// sources win per field: User, then Project, then Local, then Managed.
+pub fn get_attribution_config() -> AttributionConfig {
+    let mut config = AttributionConfig::default();
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(attribution) = settings.attribution {
+                if attribution.co_authored_by.is_some() {
+                    config.co_authored_by = attribution.co_authored_by;
+                }
+                if attribution.generated_with.is_some() {
+                    config.generated_with = attribution.generated_with;
+                }
+                if attribution.custom_trailer.is_some() {
+                    config.custom_trailer = attribution.custom_trailer;
+                }
+            }
+        }
+    }
+    config
+}
+
+/// Get the configured response language, merged across settings sources
+/// (later sources win, so a project setting overrides the user default).
+/// Injected into the system prompt so responses stay in one language.
+pub fn get_response_language() -> Option<String> {
+    let mut language = None;
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if settings.response_language.is_some() {
+                language = settings.response_language;
+            }
+        }
+    }
+    language.filter(|lang| !lang.trim().is_empty())
+}
+
+/// Get the configured conversation persistence backend, merged across
+/// settings sources (later sources win). Defaults to "json", the
+/// per-file layout; "sqlite" selects the database-backed store in
+/// `crate::session_store`.
+pub fn get_session_store() -> String {
+    let mut store = None;
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if settings.session_store.is_some() {
+                store = settings.session_store;
+            }
+        }
+    }
+    store.unwrap_or_else(|| "json".to_string())
+}
+
+/// Get the mTLS client certificate from settings, merged across sources
+/// (later sources win, so a managed policy certificate is authoritative).
+/// Used by the shared HTTP client factory in `utils::http`.
+pub fn get_client_cert_settings() -> Option<crate::utils::http::ClientCertConfig> {
+    let mut cert = None;
+    let mut key = None;
+    let mut passphrase = None;
+
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if settings.client_cert.is_some() {
+                cert = settings.client_cert;
+            }
+            if settings.client_key.is_some() {
+                key = settings.client_key;
+            }
+            if settings.client_key_passphrase.is_some() {
+                passphrase = settings.client_key_passphrase;
+            }
+        }
+    }
+
+    cert.map(|cert| crate::utils::http::ClientCertConfig {
+        cert,
+        key,
+        passphrase,
+    })
+}
+
+/// Parse newline-separated `Name: Value` header pairs (the
+/// `ANTHROPIC_CUSTOM_HEADERS` format). Malformed lines are skipped.
+pub fn parse_custom_headers(raw: &str) -> Vec<(String, String)> {
+    raw.lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let name = name.trim();
+            let value = value.trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// The configured authTokenHelper command, if any.
+///
+/// The command is executed and its trimmed stdout used as a bearer token,
+/// so gateways with bespoke auth schemes (short-lived tokens, SSO
+/// exchanges) work without code changes.
+pub fn get_auth_token_helper() -> Option<String> {
+    get_merged_config().ok()?.auth_token_helper
+}
+
+/// Run the authTokenHelper command and return the token it prints.
+/// Returns None when no helper is configured or it produces no output.
+pub fn run_auth_token_helper() -> Option<String> {
+    let command = get_auth_token_helper()?;
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        tracing::warn!("authTokenHelper exited with status {}", output.status);
+        return None;
+    }
+    let token = String::from_utf8(output.stdout).ok()?;
+    let token = token.trim();
+    if token.is_empty() {
+        None
+    } else {
+        Some(token.to_string())
+    }
+}
+
+/// Whether the managed policy forbids this tool outright
+pub fn is_tool_disallowed_by_policy(tool_name: &str) -> bool {
+    get_managed_permissions()
+        .disallowed_tools
+        .iter()
+        .any(|tool| tool == tool_name)
+}
+
+/// Get the merged HttpRequest domain allow-list from all settings sources.
+/// An empty result means no restriction is configured.
+pub fn get_http_allowed_domains() -> Vec<String> {
+    let mut domains = Vec::new();
+
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(allowed) = settings.http_allowed_domains {
+                for domain in allowed {
+                    if !domains.contains(&domain) {
+                        domains.push(domain);
+                    }
+                }
+            }
+        }
+    }
+
+    domains
+}
+
+/// Get the merged guardrails from all settings sources. Lists are
+/// unioned (a project cannot remove a user or managed guardrail), with
+/// duplicate rule sentences collapsed.
+pub fn get_guardrails() -> Vec<GuardrailConfig> {
+    let mut guardrails: Vec<GuardrailConfig> = Vec::new();
+
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(list) = settings.guardrails {
+                for guardrail in list {
+                    if !guardrails.iter().any(|g| g.rule == guardrail.rule) {
+                        guardrails.push(guardrail);
+                    }
+                }
+            }
+        }
+    }
+
+    guardrails
+}
+
+/// Variables always passed through to child processes when an allowlist is
+/// active, so basic shell behavior keeps working
+const ESSENTIAL_ENV_VARS: &[&str] = &[
+    "PATH", "HOME", "SHELL", "TERM", "USER", "LOGNAME", "LANG", "LC_ALL", "TMPDIR", "PWD",
+];
+
+/// Match an env policy pattern against a variable name.
+/// Patterns are exact names or prefixes with a trailing `*` (e.g. "AWS_*").
+fn env_pattern_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
+/// Get the merged environment policy from all settings sources.
+/// Allow and deny patterns accumulate; injected variables from later
+/// (more specific) sources override earlier ones.
+pub fn get_env_policy() -> EnvPolicyConfig {
+    let mut policy = EnvPolicyConfig::default();
+
+    for source in SETTINGS_MERGE_ORDER {
+        if let Ok(settings) = load_settings(source) {
+            if let Some(source_policy) = settings.env_policy {
+                for pattern in source_policy.allow {
+                    if !policy.allow.contains(&pattern) {
+                        policy.allow.push(pattern);
+                    }
+                }
+                for pattern in source_policy.deny {
+                    if !policy.deny.contains(&pattern) {
+                        policy.deny.push(pattern);
+                    }
+                }
+                policy.inject.extend(source_policy.inject);
+            }
+        }
+    }
+
+    policy
+}
+
+/// Compute the child process environment under the configured env policy.
+/// Returns None when no policy is configured, meaning the parent environment
+/// should be inherited unchanged. Callers should `env_clear()` and apply the
+/// returned map when this is Some.
+pub fn compute_policied_env() -> Option<HashMap<String, String>> {
+    let policy = get_env_policy();
+    if policy.is_empty() {
+        return None;
+    }
+
+    let mut env: HashMap<String, String> = std::env::vars()
+        .filter(|(name, _)| {
+            if policy.allow.is_empty() {
+                true
+            } else {
+                ESSENTIAL_ENV_VARS.contains(&name.as_str())
+                    || policy.allow.iter().any(|p| env_pattern_matches(p, name))
+            }
+        })
+        // Deny always wins over allow
+        .filter(|(name, _)| !policy.deny.iter().any(|p| env_pattern_matches(p, name)))
+        .collect();
+
+    env.extend(policy.inject);
+    Some(env)
+}
+
 /// Get a friendly name for a settings source
 pub fn get_settings_source_name(source: SettingsSource) -> &'static str {
     match source {
         SettingsSource::User => "user settings",
         SettingsSource::Project => "shared project settings",
         SettingsSource::Local => "project local settings",
+        SettingsSource::Managed => "managed policy",
         SettingsSource::Session => "current session",
     }
 }
@@ -913,6 +1868,7 @@ pub fn get_settings_source_short_name(source: SettingsSource) -> &'static str {
         SettingsSource::User => "user",
         SettingsSource::Project => "project",
         SettingsSource::Local => "local",
+        SettingsSource::Managed => "managed",
         SettingsSource::Session => "session",
     }
 }
@@ -973,4 +1929,94 @@ mod tests {
         let logging_config = parsed.logging_config.unwrap();
         assert_eq!(logging_config.default_level, Some("info".to_string()));
     }
+
+    #[test]
+    fn test_parse_permission_rule() {
+        assert_eq!(
+            parse_permission_rule("Bash(npm:*)"),
+            ("Bash".to_string(), "npm:*".to_string())
+        );
+        assert_eq!(
+            parse_permission_rule("WebFetch"),
+            ("WebFetch".to_string(), "*".to_string())
+        );
+        assert_eq!(
+            parse_permission_rule("Edit(/etc/*)"),
+            ("Edit".to_string(), "/etc/*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_headers() {
+        let parsed = parse_custom_headers(
+            "X-Gateway-Route: llm-prod\nAuthorization: Bearer abc\n\nnot a header\n: empty-name",
+        );
+        assert_eq!(
+            parsed,
+            vec![
+                ("X-Gateway-Route".to_string(), "llm-prod".to_string()),
+                ("Authorization".to_string(), "Bearer abc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_managed_settings_enforcement() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("managed-settings.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "permissions": {
+                    "deny": ["Bash(curl:*)"],
+                    "disallowedTools": ["TestOnlyManagedTool"]
+                },
+                "telemetry": false
+            }"#,
+        )
+        .unwrap();
+        std::env::set_var(
+            "LLMINATE_MANAGED_SETTINGS_PATH_FOR_TESTS",
+            path.to_string_lossy().to_string(),
+        );
+
+        let managed = get_managed_permissions();
+        assert_eq!(managed.deny, vec!["Bash(curl:*)".to_string()]);
+        assert!(is_tool_disallowed_by_policy("TestOnlyManagedTool"));
+        assert!(!is_tool_disallowed_by_policy("Read"));
+
+        let settings = load_settings(SettingsSource::Managed).unwrap();
+        assert_eq!(settings.telemetry, Some(false));
+
+        // The managed policy can never be written from inside the app
+        let err = save_settings(SettingsSource::Managed, &settings).unwrap_err();
+        assert!(err.to_string().contains("read-only"));
+
+        std::env::remove_var("LLMINATE_MANAGED_SETTINGS_PATH_FOR_TESTS");
+    }
+
+    #[test]
+    fn test_profiles_parse_from_config_json() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "profiles": {
+                    "work": { "apiKey": "sk-work", "baseUrl": "https://proxy.corp/api" },
+                    "personal": { "apiKey": "sk-personal", "model": "claude-opus-4-1-20250805" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let profiles = config.profiles.unwrap();
+        assert_eq!(profiles["work"].api_key.as_deref(), Some("sk-work"));
+        assert_eq!(
+            profiles["work"].base_url.as_deref(),
+            Some("https://proxy.corp/api")
+        );
+        assert!(profiles["work"].model.is_none());
+        assert_eq!(
+            profiles["personal"].model.as_deref(),
+            Some("claude-opus-4-1-20250805")
+        );
+    }
 }
\ No newline at end of file
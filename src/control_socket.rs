@@ -0,0 +1,262 @@
+//! JSON-RPC control socket for external UIs.
+//!
+//! `llminate --control-socket <PATH>` exposes the running interactive
+//! session over a local Unix socket so GUI wrappers, Raycast extensions,
+//! and integration tests can drive it programmatically. The protocol is
+//! JSON-RPC 2.0, one message per line:
+//!
+//! - `send_message` `{ "text": "..." }` — queue a user message
+//! - `list_sessions` — list saved conversations for this project
+//! - `approve_permission` `{ "decision": "allow" | "always_allow" | "deny" | "never" }`
+//!   — resolve the front-most pending permission prompt
+//! - `subscribe` — receive session events on this connection as
+//!   `{"method": "event", "params": {...}}` notifications
+//!
+//! The socket carries no authentication: it relies on filesystem
+//! permissions, the same trust model as the terminal it mirrors.
+
+use crate::error::{Error, Result};
+use crate::tui::{PermissionDecision, TuiEvent};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tokio::sync::{broadcast, mpsc};
+
+/// Session events fanned out to `subscribe`d connections
+static EVENT_BUS: Lazy<broadcast::Sender<Value>> = Lazy::new(|| broadcast::channel(256).0);
+
+/// Publish a session event to subscribed control-socket clients.
+/// Cheap no-op when nobody is subscribed.
+pub fn publish(event: &str, payload: Value) {
+    let _ = EVENT_BUS.send(json!({
+        "event": event,
+        "payload": payload,
+        "timestamp": crate::utils::timestamp_ms(),
+    }));
+}
+
+/// Start the control socket server in the background
+#[cfg(unix)]
+pub fn start(socket_path: PathBuf, event_tx: mpsc::UnboundedSender<TuiEvent>) -> Result<()> {
+    // A stale socket file from a previous run blocks bind()
+    if socket_path.exists() {
+        std::fs::remove_file(&socket_path)
+            .map_err(|e| Error::Other(format!("Failed to remove stale control socket: {}", e)))?;
+    }
+    if let Some(parent) = socket_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::Other(format!("Failed to create socket directory: {}", e)))?;
+        }
+    }
+
+    let listener = tokio::net::UnixListener::bind(&socket_path)
+        .map_err(|e| Error::Other(format!("Failed to bind control socket {}: {}", socket_path.display(), e)))?;
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let event_tx = event_tx.clone();
+                    tokio::spawn(async move {
+                        handle_connection(stream, event_tx).await;
+                    });
+                }
+                Err(_) => break,
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn start(_socket_path: PathBuf, _event_tx: mpsc::UnboundedSender<TuiEvent>) -> Result<()> {
+    Err(Error::Other(
+        "--control-socket requires a Unix platform (named pipe support is not implemented)"
+            .to_string(),
+    ))
+}
+
+/// Serve one client connection until it disconnects
+#[cfg(unix)]
+async fn handle_connection(
+    stream: tokio::net::UnixStream,
+    event_tx: mpsc::UnboundedSender<TuiEvent>,
+) {
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::sync::Mutex;
+
+    let (read_half, write_half) = stream.into_split();
+    // Responses and event notifications interleave on the same stream
+    let writer = Arc::new(Mutex::new(write_half));
+    let mut reader = BufReader::new(read_half);
+    let mut line = String::new();
+    let mut subscription: Option<tokio::task::JoinHandle<()>> = None;
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let request: Value = match serde_json::from_str(trimmed) {
+            Ok(value) => value,
+            Err(e) => {
+                write_message(
+                    &writer,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "id": Value::Null,
+                        "error": { "code": -32700, "message": format!("Parse error: {}", e) },
+                    }),
+                )
+                .await;
+                continue;
+            }
+        };
+
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request.get("method").and_then(|m| m.as_str()).unwrap_or_default();
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let response = match method {
+            "send_message" => handle_send_message(&event_tx, &params),
+            "list_sessions" => handle_list_sessions(),
+            "approve_permission" => handle_approve_permission(&event_tx, &params),
+            "subscribe" => {
+                if subscription.is_none() {
+                    subscription = Some(spawn_event_forwarder(Arc::clone(&writer)));
+                }
+                Ok(json!({ "subscribed": true }))
+            }
+            other => Err((-32601, format!("Method not found: {}", other))),
+        };
+
+        let message = match response {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        };
+        write_message(&writer, &message).await;
+    }
+
+    if let Some(handle) = subscription {
+        handle.abort();
+    }
+
+    /// Serialize a message onto the shared writer, newline-delimited
+    async fn write_message(
+        writer: &Arc<Mutex<tokio::net::unix::OwnedWriteHalf>>,
+        message: &Value,
+    ) {
+        if let Ok(mut serialized) = serde_json::to_string(message) {
+            serialized.push('\n');
+            let mut guard = writer.lock().await;
+            let _ = guard.write_all(serialized.as_bytes()).await;
+            let _ = guard.flush().await;
+        }
+    }
+
+    /// Forward bus events to this connection as JSON-RPC notifications
+    fn spawn_event_forwarder(
+        writer: Arc<Mutex<tokio::net::unix::OwnedWriteHalf>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let mut rx = EVENT_BUS.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        write_message(
+                            &writer,
+                            &json!({ "jsonrpc": "2.0", "method": "event", "params": event }),
+                        )
+                        .await;
+                    }
+                    // Slow subscribers drop events rather than stall the session
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+type RpcResult = std::result::Result<Value, (i64, String)>;
+
+/// Queue a user message into the interactive session
+fn handle_send_message(event_tx: &mpsc::UnboundedSender<TuiEvent>, params: &Value) -> RpcResult {
+    let text = params
+        .get("text")
+        .and_then(|t| t.as_str())
+        .ok_or_else(|| (-32602i64, "send_message requires a \"text\" string".to_string()))?;
+    if text.trim().is_empty() {
+        return Err((-32602, "text must not be empty".to_string()));
+    }
+    event_tx
+        .send(TuiEvent::ControlInput(text.to_string()))
+        .map_err(|_| (-32603i64, "Session is shutting down".to_string()))?;
+    Ok(json!({ "queued": true }))
+}
+
+/// List saved conversations for the current project
+fn handle_list_sessions() -> RpcResult {
+    let mut sessions: Vec<Value> = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(crate::tui::state::get_conversation_dir()) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else { continue };
+            if !name.ends_with(".json") {
+                continue;
+            }
+            let modified = entry
+                .metadata()
+                .ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            sessions.push(json!({
+                "id": name.trim_end_matches(".json"),
+                "modified_timestamp": modified,
+            }));
+        }
+    }
+    sessions.sort_by_key(|s| {
+        std::cmp::Reverse(s.get("modified_timestamp").and_then(|t| t.as_u64()).unwrap_or(0))
+    });
+    Ok(json!({ "sessions": sessions }))
+}
+
+/// Resolve the front-most pending permission prompt
+fn handle_approve_permission(
+    event_tx: &mpsc::UnboundedSender<TuiEvent>,
+    params: &Value,
+) -> RpcResult {
+    let decision = match params.get("decision").and_then(|d| d.as_str()) {
+        Some("allow") => PermissionDecision::Allow,
+        Some("always_allow") => PermissionDecision::AlwaysAllow,
+        Some("deny") => PermissionDecision::Deny,
+        Some("never") => PermissionDecision::Never,
+        _ => {
+            return Err((
+                -32602,
+                "approve_permission requires \"decision\": allow | always_allow | deny | never"
+                    .to_string(),
+            ))
+        }
+    };
+    event_tx
+        .send(TuiEvent::ControlPermission(decision))
+        .map_err(|_| (-32603i64, "Session is shutting down".to_string()))?;
+    Ok(json!({ "queued": true }))
+}
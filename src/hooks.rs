@@ -263,14 +263,17 @@ pub async fn execute_hook_command(
     // Execute command
     let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
 
-    let result = timeout(
-        timeout_duration,
-        Command::new(&shell)
-            .arg("-c")
-            .arg(&command.command)
-            .envs(env_vars)
-            .output()
-    ).await;
+    let mut cmd = Command::new(&shell);
+    cmd.arg("-c").arg(&command.command);
+
+    // Apply the settings-driven env policy before the hook-specific variables
+    if let Some(policy_env) = crate::config::compute_policied_env() {
+        cmd.env_clear();
+        cmd.envs(&policy_env);
+    }
+    cmd.envs(env_vars);
+
+    let result = timeout(timeout_duration, cmd.output()).await;
 
     match result {
         Ok(Ok(output)) => {
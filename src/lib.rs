@@ -1,14 +1,25 @@
+pub mod acp;
 pub mod ai;
 pub mod auth;
+pub mod changelog;
+pub mod checkpoint;
 pub mod cli;
+pub mod command_analysis;
 pub mod config;
+pub mod control_socket;
 pub mod error;
 pub mod hooks;
 pub mod mcp;
 pub mod oauth;
+pub mod path_display;
+pub mod path_policy;
+pub mod server;
 pub mod permissions;
 pub mod plugin;
 pub mod progress;
+pub mod report;
+pub mod session_store;
+pub mod settings_bundle;
 pub mod telemetry;
 pub mod tui;
 pub mod updater;
@@ -74,18 +74,200 @@ pub struct McpCommand {
 /// Start MCP server
 pub async fn serve(debug: bool, verbose: bool) -> Result<()> {
     let cwd = std::env::current_dir()?;
-    
+
     if !cwd.exists() {
         eprintln!("Error: Directory {} does not exist", cwd.display());
         std::process::exit(1);
     }
-    
-    println!("Starting MCP server in {}...", cwd.display());
-    
-    // In a real implementation, this would start the MCP server
-    // For now, we'll simulate it
-    loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+
+    // Status and diagnostics go to stderr: stdout is the JSON-RPC channel
+    eprintln!("llminate MCP server listening on stdio (cwd: {})", cwd.display());
+
+    let executor = crate::ai::tools::ToolExecutor::new();
+    let log = debug || verbose;
+
+    let mut reader = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = reader.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let json: Value = match serde_json::from_str(&line) {
+            Ok(json) => json,
+            Err(e) => {
+                if log {
+                    eprintln!("Ignoring malformed JSON-RPC message: {}", e);
+                }
+                continue;
+            }
+        };
+        if log {
+            if let Some(method) = json.get("method").and_then(|m| m.as_str()) {
+                eprintln!("<- {}", method);
+            }
+        }
+
+        // Notifications get no reply
+        let Some(response) = serve_handle_message(&executor, &json).await else {
+            continue;
+        };
+        let mut payload = serde_json::to_string(&response)
+            .context("Failed to serialize MCP response")?;
+        payload.push('\n');
+        stdout
+            .write_all(payload.as_bytes())
+            .await
+            .context("Failed to write MCP response")?;
+        stdout.flush().await.context("Failed to flush stdout")?;
+    }
+
+    Ok(())
+}
+
+/// Dispatch one incoming JSON-RPC message for the stdio server. Returns
+/// None for notifications, which expect no reply
+async fn serve_handle_message(
+    executor: &crate::ai::tools::ToolExecutor,
+    json: &Value,
+) -> Option<Value> {
+    let method = json.get("method").and_then(|m| m.as_str())?;
+    let id = match json.get("id") {
+        Some(id) if !id.is_null() => id.clone(),
+        _ => return None,
+    };
+
+    let result: std::result::Result<Value, (i64, String)> = match method {
+        "initialize" => Ok(serde_json::json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": "llminate",
+                "version": env!("CARGO_PKG_VERSION")
+            }
+        })),
+        "ping" => Ok(serde_json::json!({})),
+        "tools/list" => {
+            let tools: Vec<Value> = executor
+                .get_available_tools()
+                .into_iter()
+                .map(|tool| {
+                    let description = match &tool {
+                        crate::ai::Tool::Standard { description, .. } => description.clone(),
+                        _ => String::new(),
+                    };
+                    serde_json::json!({
+                        "name": tool.name(),
+                        "description": description,
+                        "inputSchema": tool.input_schema()
+                    })
+                })
+                .collect();
+            Ok(serde_json::json!({ "tools": tools }))
+        }
+        "tools/call" => serve_call_tool(executor, json.get("params")).await,
+        _ => Err((-32601, format!("Method not found: {}", method))),
+    };
+
+    Some(match result {
+        Ok(result) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result
+        }),
+        Err((code, message)) => jsonrpc_error(&id, code, &message),
+    })
+}
+
+/// Run one tools/call through the permission engine and tool executor.
+/// Execution failures are reported as tool-level errors (isError), not
+/// protocol errors, matching how MCP servers surface tool faults
+async fn serve_call_tool(
+    executor: &crate::ai::tools::ToolExecutor,
+    params: Option<&Value>,
+) -> std::result::Result<Value, (i64, String)> {
+    let params = params.ok_or((-32602, "tools/call requires params".to_string()))?;
+    let name = params
+        .get("name")
+        .and_then(|n| n.as_str())
+        .ok_or((-32602, "tools/call requires a tool name".to_string()))?;
+    let arguments = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+
+    if let Err(message) = serve_check_permission(name, &arguments).await {
+        return Ok(tool_call_result(&message, true));
+    }
+
+    match executor.execute(name, arguments).await {
+        Ok(crate::ai::ContentPart::ToolResult { content, is_error, .. }) => {
+            Ok(tool_call_result(&content, is_error.unwrap_or(false)))
+        }
+        Ok(other) => {
+            // Tools return ToolResult today; serialize anything else verbatim
+            let content = serde_json::to_string(&other).unwrap_or_default();
+            Ok(tool_call_result(&content, false))
+        }
+        Err(e) => Ok(tool_call_result(&e.to_string(), true)),
+    }
+}
+
+/// Build an MCP tools/call result with a single text content block
+fn tool_call_result(text: &str, is_error: bool) -> Value {
+    serde_json::json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error
+    })
+}
+
+/// Enforce llminate's permission rules for a served tool call. The server
+/// runs headless, so anything that would prompt interactively is denied
+/// with a pointer to the settings allow rules
+async fn serve_check_permission(name: &str, input: &Value) -> std::result::Result<(), String> {
+    use crate::permissions::{
+        check_command_permission, FileOperation, PermissionBehavior, PermissionResult,
+        PERMISSION_CONTEXT,
+    };
+
+    match name {
+        "Bash" => {
+            let command = input.get("command").and_then(|c| c.as_str()).unwrap_or("");
+            match check_command_permission(command).await {
+                PermissionResult::Allow => Ok(()),
+                PermissionResult::Deny => {
+                    Err(format!("Command denied by permission rules: {}", command))
+                }
+                PermissionResult::NeedsApproval => Err(format!(
+                    "Command requires interactive approval: {}. Add an allow rule to permissions.allow in settings.json to use it over MCP",
+                    command
+                )),
+            }
+        }
+        "Write" | "Edit" | "MultiEdit" | "NotebookEdit" => {
+            let path = input
+                .get("file_path")
+                .or_else(|| input.get("notebook_path"))
+                .and_then(|p| p.as_str())
+                .unwrap_or("");
+            let mut ctx = PERMISSION_CONTEXT.lock().await;
+            let result = ctx.check_file_operation(
+                std::path::Path::new(path),
+                FileOperation::Write,
+                name,
+            );
+            match result.behavior {
+                PermissionBehavior::Allow | PermissionBehavior::AlwaysAllow => Ok(()),
+                PermissionBehavior::Deny | PermissionBehavior::Never => {
+                    Err(format!("Write to {} denied by permission rules", path))
+                }
+                _ => Err(format!(
+                    "Write to {} requires interactive approval. Add the directory to permissions.additionalDirectories in settings.json to use it over MCP",
+                    path
+                )),
+            }
+        }
+        _ => Ok(()),
     }
 }
 
@@ -285,6 +467,34 @@ pub async fn get_server(name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Run the OAuth authorization flow for a remote MCP server and store the
+/// resulting token in the credentials backend. Subsequent connections to
+/// the server attach it automatically and refresh it on 401
+pub async fn auth_server(name: &str) -> Result<()> {
+    let servers = config::get_all_mcp_servers()?;
+
+    let server = servers
+        .get(name)
+        .ok_or_else(|| Error::Config(format!("No MCP server found with name: {}", name)))?;
+
+    let url = server.url.as_deref()
+        .ok_or_else(|| Error::Config(format!(
+            "MCP server '{}' has no URL; only remote (sse/http) servers support OAuth",
+            name
+        )))?;
+
+    println!("Authorizing with MCP server '{}' at {}...", name, url);
+    println!("A browser window will open to complete the authorization.");
+
+    let token = crate::oauth::authorize_mcp_server(url).await?;
+    crate::oauth::save_mcp_token(name, token).await?;
+
+    println!("Successfully authenticated with '{}'.", name);
+    println!("The stored token will be attached to requests automatically.");
+
+    Ok(())
+}
+
 /// Add server from JSON
 pub async fn add_server_json(name: &str, json: &str, scope: ConfigScope) -> Result<()> {
     let server_config: McpServerConfig = serde_json::from_str(json)
@@ -468,11 +678,17 @@ async fn start_stdio_client(name: String, config: McpServerConfig) -> Result<Mcp
         .ok_or_else(|| Error::Config("Missing command for stdio transport".to_string()))?;
     
     let mut cmd = Command::new(&command);
-    
+
     if let Some(args) = &config.args {
         cmd.args(args);
     }
-    
+
+    // Apply the settings-driven env policy before server-specific env overrides
+    if let Some(policy_env) = crate::config::compute_policied_env() {
+        cmd.env_clear();
+        cmd.envs(&policy_env);
+    }
+
     if let Some(env) = &config.env {
         cmd.envs(env);
     }
@@ -491,8 +707,10 @@ async fn start_stdio_client(name: String, config: McpServerConfig) -> Result<Mcp
     let (response_tx, response_rx) = mpsc::unbounded_channel();
     
     // Spawn task to handle communication
+    let server_name = name.clone();
+    let reply_tx = tx.clone();
     tokio::spawn(async move {
-        handle_stdio_communication(stdin, stdout, rx, response_tx).await;
+        handle_stdio_communication(server_name, stdin, stdout, rx, response_tx, reply_tx).await;
     });
     
     Ok(McpClient {
@@ -505,7 +723,10 @@ async fn start_stdio_client(name: String, config: McpServerConfig) -> Result<Mcp
 }
 
 /// Start SSE MCP client
-/// SSE transport uses Server-Sent Events for receiving messages and HTTP POST for sending
+/// SSE transport uses Server-Sent Events for receiving messages and HTTP
+/// POST for sending. Dropped streams reconnect automatically with
+/// exponential backoff and Last-Event-ID resumption; connection state is
+/// published via `connection_state` so the TUI can show dropped servers.
 async fn start_sse_client(name: String, config: McpServerConfig) -> Result<McpClient> {
     let url = config.url
         .ok_or_else(|| Error::Config("Missing URL for SSE transport".to_string()))?;
@@ -526,12 +747,28 @@ async fn start_sse_client(name: String, config: McpServerConfig) -> Result<McpCl
         }
     }
 
+    // Attach a stored OAuth token at connect time; an explicit
+    // Authorization header in the server config takes priority. The SSE
+    // transport holds one long-lived stream, so refresh happens by
+    // reconnecting (or re-running `mcp auth`) rather than per-request
+    if !headers.contains_key(reqwest::header::AUTHORIZATION) {
+        if let Some(token) = crate::oauth::load_mcp_token(&name).await {
+            if let Ok(value) = reqwest::header::HeaderValue::from_str(
+                &format!("Bearer {}", token.access_token)
+            ) {
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+            }
+        }
+    }
+
+    let server_name = name.clone();
     let url_clone = url.clone();
     let headers_clone = headers.clone();
 
     // Spawn SSE handler task
+    let reply_tx = tx.clone();
     tokio::spawn(async move {
-        handle_sse_communication(url_clone, headers_clone, rx, response_tx).await;
+        handle_sse_communication(server_name, url_clone, headers_clone, rx, response_tx, reply_tx).await;
     });
 
     Ok(McpClient {
@@ -543,8 +780,439 @@ async fn start_sse_client(name: String, config: McpServerConfig) -> Result<McpCl
     })
 }
 
+/// Connection state of a remote MCP server
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum McpConnectionState {
+    Connected,
+    Reconnecting,
+    Disconnected,
+}
+
+impl McpConnectionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            McpConnectionState::Connected => "connected",
+            McpConnectionState::Reconnecting => "reconnecting",
+            McpConnectionState::Disconnected => "disconnected",
+        }
+    }
+}
+
+/// Connection states by server name, published by transport tasks and
+/// read by the TUI status display
+static CONNECTION_STATES: once_cell::sync::Lazy<
+    std::sync::Mutex<HashMap<String, McpConnectionState>>,
+> = once_cell::sync::Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Publish a server's connection state
+fn set_connection_state(name: &str, state: McpConnectionState) {
+    if let Ok(mut states) = CONNECTION_STATES.lock() {
+        states.insert(name.to_string(), state);
+    }
+}
+
+/// Current connection state of a server, if its transport has started
+pub fn connection_state(name: &str) -> Option<McpConnectionState> {
+    CONNECTION_STATES.lock().ok()?.get(name).copied()
+}
+
+/// Health metrics for one server, updated on every request the client
+/// sends and read by the TUI dashboard
+#[derive(Debug, Clone, Default)]
+pub struct McpServerHealth {
+    /// Round-trip time of the most recent successful request
+    pub last_latency_ms: Option<u64>,
+    /// Most recent request failure, cleared by the next success
+    pub last_error: Option<String>,
+    /// Tool count reported by the last tools/list call
+    pub tool_count: Option<usize>,
+}
+
+/// Health metrics by server name
+static SERVER_HEALTH: once_cell::sync::Lazy<
+    std::sync::Mutex<HashMap<String, McpServerHealth>>,
+> = once_cell::sync::Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Record a successful request's round-trip time
+fn record_request_latency(name: &str, latency_ms: u64) {
+    if let Ok(mut health) = SERVER_HEALTH.lock() {
+        let entry = health.entry(name.to_string()).or_default();
+        entry.last_latency_ms = Some(latency_ms);
+        entry.last_error = None;
+    }
+}
+
+/// Record a failed request so the dashboard can surface it
+fn record_server_error(name: &str, error: &str) {
+    if let Ok(mut health) = SERVER_HEALTH.lock() {
+        health.entry(name.to_string()).or_default().last_error = Some(error.to_string());
+    }
+}
+
+/// Record the tool count reported by a tools/list call
+fn record_tool_count(name: &str, count: usize) {
+    if let Ok(mut health) = SERVER_HEALTH.lock() {
+        health.entry(name.to_string()).or_default().tool_count = Some(count);
+    }
+}
+
+/// Health metrics of a server, if the client has talked to it
+pub fn server_health(name: &str) -> Option<McpServerHealth> {
+    SERVER_HEALTH.lock().ok()?.get(name).cloned()
+}
+
+/// Sentinel method marking an outbound reply to a server-initiated request.
+/// `params` holds the complete JSON-RPC payload, sent over the wire verbatim
+const SERVER_REPLY_METHOD: &str = "__server_reply__";
+
+/// Maximum server-initiated sampling requests per server per window
+const SAMPLING_RATE_LIMIT: usize = 5;
+/// Sliding window for the sampling rate limit
+const SAMPLING_RATE_WINDOW: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Timestamps of recent sampling requests by server name, pruned to the
+/// rate-limit window on every check
+static SAMPLING_HISTORY: once_cell::sync::Lazy<
+    std::sync::Mutex<HashMap<String, Vec<std::time::Instant>>>,
+> = once_cell::sync::Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Servers the user granted a session-wide "always allow" for sampling
+static SAMPLING_APPROVED: once_cell::sync::Lazy<
+    std::sync::Mutex<std::collections::HashSet<String>>,
+> = once_cell::sync::Lazy::new(|| std::sync::Mutex::new(std::collections::HashSet::new()));
+
+/// Channel the interactive session registers to receive sampling approval
+/// prompts; without one, server-initiated requests are denied outright
+static SAMPLING_APPROVER: once_cell::sync::Lazy<
+    std::sync::Mutex<Option<mpsc::UnboundedSender<SamplingApprovalRequest>>>,
+> = once_cell::sync::Lazy::new(|| std::sync::Mutex::new(None));
+
+/// A pending user decision on one server-initiated sampling request
+pub struct SamplingApprovalRequest {
+    pub server_name: String,
+    /// Short excerpt of the prompt the server wants completed
+    pub summary: String,
+    pub responder: oneshot::Sender<SamplingApprovalDecision>,
+}
+
+/// User decision on a sampling approval prompt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplingApprovalDecision {
+    Allow,
+    /// Allow this and every later request from the same server this session
+    AlwaysAllow,
+    Deny,
+}
+
+/// Register the channel sampling approval prompts are routed through.
+/// Called once when the interactive session starts
+pub fn register_sampling_approver(tx: mpsc::UnboundedSender<SamplingApprovalRequest>) {
+    if let Ok(mut approver) = SAMPLING_APPROVER.lock() {
+        *approver = Some(tx);
+    }
+}
+
+/// Record one sampling request against the per-server sliding window.
+/// Returns false when the server has exhausted its budget
+fn record_sampling_request(name: &str) -> bool {
+    let Ok(mut history) = SAMPLING_HISTORY.lock() else {
+        return false;
+    };
+    let now = std::time::Instant::now();
+    let timestamps = history.entry(name.to_string()).or_default();
+    timestamps.retain(|t| now.duration_since(*t) < SAMPLING_RATE_WINDOW);
+    if timestamps.len() >= SAMPLING_RATE_LIMIT {
+        return false;
+    }
+    timestamps.push(now);
+    true
+}
+
+/// Ask the user whether a server's sampling request may be forwarded.
+/// Servers previously granted "always allow" skip the prompt; with no
+/// approver registered (headless runs) the request is denied
+async fn approve_sampling(name: &str, summary: &str) -> bool {
+    if SAMPLING_APPROVED
+        .lock()
+        .map(|approved| approved.contains(name))
+        .unwrap_or(false)
+    {
+        return true;
+    }
+
+    let approver = SAMPLING_APPROVER.lock().ok().and_then(|a| a.clone());
+    let Some(approver) = approver else {
+        eprintln!(
+            "Denied sampling request from MCP server '{}': no interactive session to approve it",
+            name
+        );
+        return false;
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if approver
+        .send(SamplingApprovalRequest {
+            server_name: name.to_string(),
+            summary: summary.to_string(),
+            responder: tx,
+        })
+        .is_err()
+    {
+        return false;
+    }
+
+    match rx.await {
+        Ok(SamplingApprovalDecision::Allow) => true,
+        Ok(SamplingApprovalDecision::AlwaysAllow) => {
+            if let Ok(mut approved) = SAMPLING_APPROVED.lock() {
+                approved.insert(name.to_string());
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Build a JSON-RPC error reply to a server-initiated request
+fn jsonrpc_error(id: &Value, code: i64, message: &str) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
+
+/// Wrap a prebuilt JSON-RPC payload for the transport's outbound channel
+fn server_reply(payload: Value) -> McpRequest {
+    McpRequest {
+        id: String::new(),
+        method: SERVER_REPLY_METHOD.to_string(),
+        params: Some(payload),
+    }
+}
+
+/// Handle a JSON-RPC message the server initiated rather than sent in
+/// reply to one of ours. Returns true if the message was consumed.
+/// Requests for methods we do not implement are answered with a
+/// method-not-found error so well-behaved servers are not left waiting
+/// on a timeout
+fn handle_server_message(
+    name: &str,
+    json: &Value,
+    reply_tx: &mpsc::UnboundedSender<McpRequest>,
+) -> bool {
+    let Some(method) = json.get("method").and_then(|m| m.as_str()) else {
+        return false;
+    };
+    let Some(id) = json.get("id").filter(|id| !id.is_null()).cloned() else {
+        // Server notification; nothing to answer
+        return true;
+    };
+
+    if method == "sampling/createMessage" {
+        let params = json.get("params").cloned().unwrap_or(Value::Null);
+        let name = name.to_string();
+        let reply_tx = reply_tx.clone();
+        tokio::spawn(async move {
+            let payload = handle_sampling_request(&name, id, params).await;
+            let _ = reply_tx.send(server_reply(payload));
+        });
+    } else {
+        let payload = jsonrpc_error(&id, -32601, &format!("Method not found: {}", method));
+        let _ = reply_tx.send(server_reply(payload));
+    }
+    true
+}
+
+/// Forward one sampling/createMessage request through llminate's own AI
+/// client, gated behind the per-server rate limit and a user approval
+/// prompt. Returns the complete JSON-RPC reply payload
+async fn handle_sampling_request(name: &str, id: Value, params: Value) -> Value {
+    if !record_sampling_request(name) {
+        return jsonrpc_error(
+            &id,
+            -32000,
+            &format!(
+                "Sampling rate limit exceeded for server '{}' ({} requests per {} seconds)",
+                name,
+                SAMPLING_RATE_LIMIT,
+                SAMPLING_RATE_WINDOW.as_secs()
+            ),
+        );
+    }
+
+    let messages = match parse_sampling_messages(&params) {
+        Ok(messages) => messages,
+        Err(message) => return jsonrpc_error(&id, -32602, &message),
+    };
+
+    let summary = sampling_summary(&messages);
+    if !approve_sampling(name, &summary).await {
+        return jsonrpc_error(
+            &id,
+            -32001,
+            &format!("Sampling request from server '{}' was not approved", name),
+        );
+    }
+
+    let client = match crate::ai::create_client().await {
+        Ok(client) => client,
+        Err(e) => {
+            return jsonrpc_error(&id, -32603, &format!("Failed to create AI client: {}", e))
+        }
+    };
+
+    let model = crate::ai::load_config()
+        .map(|config| config.default_model)
+        .unwrap_or_else(|_| crate::ai::AIConfig::default().default_model);
+
+    let request = crate::ai::ChatRequest {
+        model,
+        messages,
+        max_tokens: Some(
+            params
+                .get("maxTokens")
+                .and_then(|v| v.as_u64())
+                .map(|v| v.min(8192) as u32)
+                .unwrap_or(1024),
+        ),
+        temperature: params
+            .get("temperature")
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32),
+        top_p: None,
+        top_k: None,
+        stop_sequences: params.get("stopSequences").and_then(|v| {
+            v.as_array().map(|arr| {
+                arr.iter()
+                    .filter_map(|s| s.as_str().map(String::from))
+                    .collect()
+            })
+        }),
+        stream: Some(false),
+        system: params
+            .get("systemPrompt")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        tools: None,
+        tool_choice: None,
+        metadata: None,
+        betas: None,
+        thinking: None,
+        service_tier: None,
+    };
+
+    let response = match client.chat(request).await {
+        Ok(response) => response,
+        Err(e) => return jsonrpc_error(&id, -32603, &format!("Completion failed: {}", e)),
+    };
+
+    let text: String = response
+        .content
+        .iter()
+        .filter_map(|part| match part {
+            crate::ai::ContentPart::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("");
+
+    let stop_reason = match response.stop_reason {
+        Some(crate::ai::StopReason::MaxTokens) => "maxTokens",
+        Some(crate::ai::StopReason::StopSequence) => "stopSequence",
+        _ => "endTurn",
+    };
+
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": {
+            "role": "assistant",
+            "content": { "type": "text", "text": text },
+            "model": response.model,
+            "stopReason": stop_reason
+        }
+    })
+}
+
+/// Convert the request's message list into the AI client's message type.
+/// Content may be a single block or an array of blocks; only text blocks
+/// are forwarded
+fn parse_sampling_messages(params: &Value) -> std::result::Result<Vec<crate::ai::Message>, String> {
+    let raw = params
+        .get("messages")
+        .and_then(|m| m.as_array())
+        .ok_or_else(|| "sampling/createMessage requires a messages array".to_string())?;
+
+    let mut messages = Vec::new();
+    for entry in raw {
+        let role = match entry.get("role").and_then(|r| r.as_str()) {
+            Some("user") => crate::ai::MessageRole::User,
+            Some("assistant") => crate::ai::MessageRole::Assistant,
+            other => {
+                return Err(format!(
+                    "Unsupported sampling message role: {}",
+                    other.unwrap_or("missing")
+                ))
+            }
+        };
+
+        let content = entry.get("content");
+        let blocks: Vec<&Value> = match content {
+            Some(Value::Array(arr)) => arr.iter().collect(),
+            Some(block) => vec![block],
+            None => Vec::new(),
+        };
+        let text: String = blocks
+            .iter()
+            .filter_map(|block| {
+                if block.get("type").and_then(|t| t.as_str()) == Some("text") {
+                    block.get("text").and_then(|t| t.as_str())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        messages.push(crate::ai::Message {
+            role,
+            content: crate::ai::MessageContent::Text(text),
+            name: None,
+        });
+    }
+
+    if messages.is_empty() {
+        return Err("sampling/createMessage requires at least one message".to_string());
+    }
+    Ok(messages)
+}
+
+/// Short excerpt of the last user message, shown in the approval prompt
+fn sampling_summary(messages: &[crate::ai::Message]) -> String {
+    let text = messages
+        .iter()
+        .rev()
+        .find_map(|message| match &message.content {
+            crate::ai::MessageContent::Text(text) if !text.is_empty() => Some(text.clone()),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let excerpt: String = text.chars().take(120).collect();
+    if excerpt.len() < text.len() {
+        format!("{}…", excerpt)
+    } else {
+        excerpt
+    }
+}
+
 /// Start HTTP MCP client
-/// HTTP transport uses HTTP POST for both sending and receiving (request/response)
+/// Implements the Streamable HTTP transport: every JSON-RPC message is sent
+/// via HTTP POST, and the server replies with either a plain JSON body or an
+/// SSE stream. The session ID assigned on initialize is echoed back in the
+/// Mcp-Session-Id header, and dropped SSE streams are resumed with
+/// Last-Event-ID so no messages are lost.
 async fn start_http_client(name: String, config: McpServerConfig) -> Result<McpClient> {
     let url = config.url
         .ok_or_else(|| Error::Config("Missing URL for HTTP transport".to_string()))?;
@@ -570,8 +1238,10 @@ async fn start_http_client(name: String, config: McpServerConfig) -> Result<McpC
     }
 
     // Spawn HTTP handler task
+    let server_name = name.clone();
+    let reply_tx = tx.clone();
     tokio::spawn(async move {
-        handle_http_communication(url, headers, rx, response_tx).await;
+        handle_http_communication(server_name, url, headers, rx, response_tx, reply_tx).await;
     });
 
     Ok(McpClient {
@@ -583,174 +1253,550 @@ async fn start_http_client(name: String, config: McpServerConfig) -> Result<McpC
     })
 }
 
-/// Handle SSE communication
+/// Initial reconnect delay for a dropped SSE stream
+const SSE_INITIAL_BACKOFF: tokio::time::Duration = tokio::time::Duration::from_secs(1);
+/// Ceiling for the exponential reconnect backoff
+const SSE_MAX_BACKOFF: tokio::time::Duration = tokio::time::Duration::from_secs(30);
+
+/// Handle SSE communication with automatic reconnection
 async fn handle_sse_communication(
+    name: String,
     url: String,
     headers: reqwest::header::HeaderMap,
     mut request_rx: mpsc::UnboundedReceiver<McpRequest>,
     response_tx: mpsc::UnboundedSender<McpResponse>,
+    reply_tx: mpsc::UnboundedSender<McpRequest>,
 ) {
-    let client = reqwest::Client::new();
-
-    // Connect to SSE endpoint to get the POST endpoint
-    let mut post_endpoint: Option<String> = None;
+    use futures::StreamExt;
 
-    // First, establish SSE connection
-    let sse_response = match client.get(&url)
-        .headers(headers.clone())
-        .header("Accept", "text/event-stream")
-        .send()
-        .await
-    {
-        Ok(resp) => resp,
+    let client = match crate::utils::http::shared_client() {
+        Ok(client) => client,
         Err(e) => {
-            eprintln!("Failed to connect to SSE endpoint: {}", e);
+            eprintln!("Failed to create HTTP client: {}", e);
+            set_connection_state(&name, McpConnectionState::Disconnected);
             return;
         }
     };
 
-    if !sse_response.status().is_success() {
-        eprintln!("SSE connection failed with status: {}", sse_response.status());
-        return;
-    }
+    let mut backoff = SSE_INITIAL_BACKOFF;
+    let mut last_event_id: Option<String> = None;
+    set_connection_state(&name, McpConnectionState::Reconnecting);
 
-    // Parse SSE stream for endpoint and messages
-    let mut stream = sse_response.bytes_stream();
-    use futures::StreamExt;
+    'reconnect: loop {
+        // (Re-)establish the event stream, replaying missed events if the
+        // server honors Last-Event-ID
+        let mut connect = client
+            .get(&url)
+            .headers(headers.clone())
+            .header("Accept", "text/event-stream");
+        if let Some(event_id) = &last_event_id {
+            connect = connect.header("Last-Event-ID", event_id.clone());
+        }
 
-    let mut buffer = String::new();
-    let headers_for_post = headers.clone();
+        let sse_response = match connect.send().await {
+            Ok(resp) if resp.status().is_success() => resp,
+            Ok(resp) => {
+                eprintln!("SSE connection to '{}' failed with status: {}", name, resp.status());
+                if !wait_for_sse_retry(&name, &mut request_rx, &mut backoff).await {
+                    return;
+                }
+                continue 'reconnect;
+            }
+            Err(e) => {
+                eprintln!("Failed to connect to SSE endpoint for '{}': {}", name, e);
+                if !wait_for_sse_retry(&name, &mut request_rx, &mut backoff).await {
+                    return;
+                }
+                continue 'reconnect;
+            }
+        };
 
-    loop {
-        tokio::select! {
-            Some(request) = request_rx.recv() => {
-                // Send request via HTTP POST to the endpoint
-                if let Some(ref endpoint) = post_endpoint {
-                    let json_rpc = serde_json::json!({
-                        "jsonrpc": "2.0",
-                        "id": request.id,
-                        "method": request.method,
-                        "params": request.params
-                    });
-
-                    match client.post(endpoint)
-                        .headers(headers_for_post.clone())
-                        .header("Content-Type", "application/json")
-                        .json(&json_rpc)
-                        .send()
-                        .await
-                    {
-                        Ok(resp) => {
-                            if !resp.status().is_success() {
-                                eprintln!("POST request failed: {}", resp.status());
+        set_connection_state(&name, McpConnectionState::Connected);
+        backoff = SSE_INITIAL_BACKOFF;
+
+        // The POST endpoint is announced per-connection and may change
+        // after a reconnect
+        let mut post_endpoint: Option<String> = None;
+        let mut stream = sse_response.bytes_stream();
+        let mut buffer = String::new();
+
+        loop {
+            tokio::select! {
+                request = request_rx.recv() => {
+                    let Some(request) = request else {
+                        // Client dropped; shut the transport down
+                        set_connection_state(&name, McpConnectionState::Disconnected);
+                        return;
+                    };
+
+                    // Send request via HTTP POST to the endpoint
+                    if let Some(ref endpoint) = post_endpoint {
+                        let json_rpc = if request.method == SERVER_REPLY_METHOD {
+                            request.params.clone().unwrap_or(Value::Null)
+                        } else {
+                            serde_json::json!({
+                                "jsonrpc": "2.0",
+                                "id": request.id,
+                                "method": request.method,
+                                "params": request.params
+                            })
+                        };
+
+                        match client.post(endpoint)
+                            .headers(headers.clone())
+                            .header("Content-Type", "application/json")
+                            .json(&json_rpc)
+                            .send()
+                            .await
+                        {
+                            Ok(resp) => {
+                                if !resp.status().is_success() {
+                                    eprintln!("POST request failed: {}", resp.status());
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("Failed to send POST request: {}", e);
                             }
                         }
-                        Err(e) => {
-                            eprintln!("Failed to send POST request: {}", e);
-                        }
+                    } else {
+                        eprintln!("No POST endpoint available yet");
                     }
-                } else {
-                    eprintln!("No POST endpoint available yet");
                 }
-            }
-            Some(chunk_result) = stream.next() => {
-                match chunk_result {
-                    Ok(chunk) => {
-                        buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-                        // Parse SSE events from buffer
-                        while let Some(event_end) = buffer.find("\n\n") {
-                            let event_str = buffer[..event_end].to_string();
-                            buffer = buffer[event_end + 2..].to_string();
-
-                            // Parse SSE event
-                            for line in event_str.lines() {
-                                if line.starts_with("data: ") {
-                                    let data = &line[6..];
-                                    if let Ok(json) = serde_json::from_str::<Value>(data) {
+                chunk_result = stream.next() => {
+                    match chunk_result {
+                        Some(Ok(chunk)) => {
+                            buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                            // Parse SSE events from buffer
+                            while let Some(event_end) = buffer.find("\n\n") {
+                                let event_str = buffer[..event_end].to_string();
+                                buffer = buffer[event_end + 2..].to_string();
+
+                                let (event_id, data_lines) = parse_sse_event(&event_str);
+                                if let Some(id) = event_id {
+                                    last_event_id = Some(id);
+                                }
+                                for data in data_lines {
+                                    if let Ok(json) = serde_json::from_str::<Value>(&data) {
                                         // Check if this is an endpoint message
                                         if let Some(endpoint) = json.get("endpoint").and_then(|e| e.as_str()) {
                                             post_endpoint = Some(endpoint.to_string());
-                                        } else {
-                                            // Regular JSON-RPC response
-                                            let response = McpResponse {
-                                                id: json.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                                                result: json.get("result").cloned(),
-                                                error: json.get("error").and_then(|e| serde_json::from_value(e.clone()).ok()),
-                                            };
-                                            let _ = response_tx.send(response);
+                                        } else if !handle_server_message(&name, &json, &reply_tx) {
+                                            forward_jsonrpc_message(&json, &response_tx);
                                         }
                                     }
                                 }
                             }
                         }
+                        Some(Err(e)) => {
+                            eprintln!("SSE stream error on '{}': {}", name, e);
+                            break;
+                        }
+                        None => {
+                            eprintln!("SSE stream for '{}' ended", name);
+                            break;
+                        }
                     }
-                    Err(e) => {
-                        eprintln!("SSE stream error: {}", e);
-                        break;
-                    }
                 }
             }
-            else => break
+        }
+
+        // Stream dropped: back off, then reconnect
+        if !wait_for_sse_retry(&name, &mut request_rx, &mut backoff).await {
+            return;
+        }
+    }
+}
+
+/// Mark a server as reconnecting and sleep for the current backoff,
+/// doubling it up to the ceiling. Returns false if the client was dropped
+/// during the wait, in which case the transport should shut down.
+async fn wait_for_sse_retry(
+    name: &str,
+    request_rx: &mut mpsc::UnboundedReceiver<McpRequest>,
+    backoff: &mut tokio::time::Duration,
+) -> bool {
+    set_connection_state(name, McpConnectionState::Reconnecting);
+
+    let delay = *backoff;
+    *backoff = (*backoff * 2).min(SSE_MAX_BACKOFF);
+
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => true,
+        request = request_rx.recv() => {
+            match request {
+                // A request arrived while disconnected: it can't be
+                // delivered, but the connection is clearly still wanted,
+                // so reconnect immediately
+                Some(_) => true,
+                None => {
+                    set_connection_state(name, McpConnectionState::Disconnected);
+                    false
+                }
+            }
         }
     }
 }
 
-/// Handle HTTP communication
+/// Session ID header used by the Streamable HTTP transport
+const MCP_SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+
+/// Handle Streamable HTTP communication
 async fn handle_http_communication(
+    name: String,
     url: String,
     headers: reqwest::header::HeaderMap,
     mut request_rx: mpsc::UnboundedReceiver<McpRequest>,
     response_tx: mpsc::UnboundedSender<McpResponse>,
+    reply_tx: mpsc::UnboundedSender<McpRequest>,
 ) {
-    let client = reqwest::Client::new();
+    let client = match crate::utils::http::shared_client() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Failed to create HTTP client: {}", e);
+            return;
+        }
+    };
+
+    // Assigned by the server on the initialize response; echoed back on
+    // every subsequent request so the server can route to our session
+    let mut session_id: Option<String> = None;
+    // Last SSE event ID seen, used to resume a dropped stream
+    let mut last_event_id: Option<String> = None;
+
+    // Token acquired via `mcp auth`, attached as a Bearer credential on
+    // every request; an explicit Authorization header in the server
+    // config takes priority
+    let mut oauth_token = if headers.contains_key(reqwest::header::AUTHORIZATION) {
+        None
+    } else {
+        crate::oauth::load_mcp_token(&name).await
+    };
 
     while let Some(request) = request_rx.recv().await {
-        let json_rpc = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": request.id,
-            "method": request.method,
-            "params": request.params
-        });
+        // Refresh proactively when the stored token is past its expiry
+        if let Some(token) = &oauth_token {
+            if token.is_expired() && token.refresh_token.is_some() {
+                if let Some(new_token) = refresh_mcp_oauth(&name, token).await {
+                    oauth_token = Some(new_token);
+                }
+            }
+        }
+        // Notifications carry no id and expect no response body; replies
+        // to server-initiated requests go out verbatim and likewise expect
+        // only an acknowledgement
+        let is_reply = request.method == SERVER_REPLY_METHOD;
+        let is_notification = request.id.is_empty();
+        let json_rpc = if is_reply {
+            request.params.clone().unwrap_or(Value::Null)
+        } else if is_notification {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": request.method,
+                "params": request.params
+            })
+        } else {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "method": request.method,
+                "params": request.params
+            })
+        };
 
-        match client.post(&url)
-            .headers(headers.clone())
-            .json(&json_rpc)
-            .send()
-            .await
+        let mut resp = match post_jsonrpc(&client, &url, &headers, &session_id, &oauth_token, &json_rpc).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                eprintln!("Failed to send HTTP request: {}", e);
+                continue;
+            }
+        };
+
+        // A 401 means the token was revoked or expired server-side;
+        // refresh it and retry the request once before giving up
+        if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+            let refreshed = match &oauth_token {
+                Some(token) => refresh_mcp_oauth(&name, token).await,
+                None => {
+                    eprintln!(
+                        "MCP server '{}' rejected the request with 401 Unauthorized; run `llminate mcp auth {}` to authenticate",
+                        name, name
+                    );
+                    None
+                }
+            };
+            if let Some(new_token) = refreshed {
+                oauth_token = Some(new_token);
+                match post_jsonrpc(&client, &url, &headers, &session_id, &oauth_token, &json_rpc).await {
+                    Ok(retried) => resp = retried,
+                    Err(e) => {
+                        eprintln!("Failed to send HTTP request: {}", e);
+                        continue;
+                    }
+                }
+            }
+        }
+
+        // 404 with an active session means the server expired it; drop the
+        // ID so the caller's next initialize starts a fresh session
+        if resp.status() == reqwest::StatusCode::NOT_FOUND && session_id.is_some() {
+            eprintln!("MCP session expired; server returned 404");
+            session_id = None;
+            last_event_id = None;
+            continue;
+        }
+
+        if !resp.status().is_success() {
+            eprintln!("HTTP request failed: {}", resp.status());
+            continue;
+        }
+
+        // Capture the session ID the server assigns (typically on initialize)
+        if let Some(sid) = resp
+            .headers()
+            .get(MCP_SESSION_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
         {
-            Ok(resp) => {
-                if resp.status().is_success() {
-                    match resp.json::<Value>().await {
-                        Ok(json) => {
-                            let response = McpResponse {
-                                id: json.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                                result: json.get("result").cloned(),
-                                error: json.get("error").and_then(|e| serde_json::from_value(e.clone()).ok()),
-                            };
-                            let _ = response_tx.send(response);
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to parse HTTP response: {}", e);
+            session_id = Some(sid.to_string());
+        }
+
+        // 202 Accepted acknowledges a notification or reply; there is no body
+        if resp.status() == reqwest::StatusCode::ACCEPTED || is_notification || is_reply {
+            continue;
+        }
+
+        let is_sse = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|ct| ct.contains("text/event-stream"))
+            .unwrap_or(false);
+
+        if is_sse {
+            let completed =
+                drain_sse_stream(&name, resp, &response_tx, &reply_tx, &mut last_event_id).await;
+            if !completed {
+                // The stream dropped mid-response; reconnect with
+                // Last-Event-ID so the server replays what we missed
+                resume_sse_stream(
+                    &name,
+                    &client,
+                    &url,
+                    &headers,
+                    &session_id,
+                    &mut last_event_id,
+                    &response_tx,
+                    &reply_tx,
+                )
+                .await;
+            }
+        } else {
+            match resp.json::<Value>().await {
+                Ok(json) => {
+                    if !handle_server_message(&name, &json, &reply_tx) {
+                        forward_jsonrpc_message(&json, &response_tx);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to parse HTTP response: {}", e);
+                }
+            }
+        }
+    }
+
+    // Politely end the session when the client shuts down
+    if let Some(sid) = session_id {
+        let _ = client
+            .delete(&url)
+            .headers(headers)
+            .header(MCP_SESSION_ID_HEADER, sid)
+            .send()
+            .await;
+    }
+}
+
+/// POST one JSON-RPC message to a Streamable HTTP server, attaching the
+/// session ID and Bearer token when present
+async fn post_jsonrpc(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &reqwest::header::HeaderMap,
+    session_id: &Option<String>,
+    oauth_token: &Option<crate::oauth::McpOauthToken>,
+    json_rpc: &Value,
+) -> std::result::Result<reqwest::Response, reqwest::Error> {
+    let mut post = client.post(url)
+        .headers(headers.clone())
+        .header("Accept", "application/json, text/event-stream")
+        .json(json_rpc);
+    if let Some(sid) = session_id {
+        post = post.header(MCP_SESSION_ID_HEADER, sid.clone());
+    }
+    if let Some(token) = oauth_token {
+        post = post.header(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {}", token.access_token),
+        );
+    }
+    post.send().await
+}
+
+/// Refresh a server's OAuth token and persist the result. Returns None
+/// if the refresh failed, advising the user to re-authenticate
+async fn refresh_mcp_oauth(
+    name: &str,
+    token: &crate::oauth::McpOauthToken,
+) -> Option<crate::oauth::McpOauthToken> {
+    match crate::oauth::refresh_mcp_token(token).await {
+        Ok(new_token) => {
+            if let Err(e) = crate::oauth::save_mcp_token(name, new_token.clone()).await {
+                eprintln!("Failed to persist refreshed token for '{}': {}", name, e);
+            }
+            Some(new_token)
+        }
+        Err(e) => {
+            eprintln!(
+                "Failed to refresh OAuth token for '{}': {}; run `llminate mcp auth {}` to re-authenticate",
+                name, e, name
+            );
+            None
+        }
+    }
+}
+
+/// Drain an SSE response body, forwarding JSON-RPC messages and tracking
+/// event IDs for Last-Event-ID resumption. Returns true if the stream
+/// ended cleanly.
+async fn drain_sse_stream(
+    name: &str,
+    response: reqwest::Response,
+    response_tx: &mpsc::UnboundedSender<McpResponse>,
+    reply_tx: &mpsc::UnboundedSender<McpRequest>,
+    last_event_id: &mut Option<String>,
+) -> bool {
+    use futures::StreamExt;
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+
+    while let Some(chunk_result) = stream.next().await {
+        match chunk_result {
+            Ok(chunk) => {
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                while let Some(event_end) = buffer.find("\n\n") {
+                    let event_str = buffer[..event_end].to_string();
+                    buffer = buffer[event_end + 2..].to_string();
+
+                    let (event_id, data_lines) = parse_sse_event(&event_str);
+                    if let Some(id) = event_id {
+                        *last_event_id = Some(id);
+                    }
+                    for data in data_lines {
+                        if let Ok(json) = serde_json::from_str::<Value>(&data) {
+                            if !handle_server_message(name, &json, reply_tx) {
+                                forward_jsonrpc_message(&json, response_tx);
+                            }
                         }
                     }
-                } else {
-                    eprintln!("HTTP request failed: {}", resp.status());
                 }
             }
             Err(e) => {
-                eprintln!("Failed to send HTTP request: {}", e);
+                eprintln!("SSE stream error: {}", e);
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Reopen the server's event stream after a dropped connection, replaying
+/// missed events via the Last-Event-ID header
+#[allow(clippy::too_many_arguments)]
+async fn resume_sse_stream(
+    name: &str,
+    client: &reqwest::Client,
+    url: &str,
+    headers: &reqwest::header::HeaderMap,
+    session_id: &Option<String>,
+    last_event_id: &mut Option<String>,
+    response_tx: &mpsc::UnboundedSender<McpResponse>,
+    reply_tx: &mpsc::UnboundedSender<McpRequest>,
+) {
+    let mut req = client
+        .get(url)
+        .headers(headers.clone())
+        .header("Accept", "text/event-stream");
+    if let Some(sid) = session_id {
+        req = req.header(MCP_SESSION_ID_HEADER, sid.clone());
+    }
+    if let Some(event_id) = last_event_id.clone() {
+        req = req.header("Last-Event-ID", event_id);
+    }
+
+    match req.send().await {
+        Ok(resp) if resp.status().is_success() => {
+            drain_sse_stream(name, resp, response_tx, reply_tx, last_event_id).await;
+        }
+        Ok(resp) => {
+            eprintln!("Failed to resume SSE stream: {}", resp.status());
+        }
+        Err(e) => {
+            eprintln!("Failed to resume SSE stream: {}", e);
+        }
+    }
+}
+
+/// Split one SSE event into its optional event ID and data lines
+fn parse_sse_event(event_str: &str) -> (Option<String>, Vec<String>) {
+    let mut event_id = None;
+    let mut data_lines = Vec::new();
+
+    for line in event_str.lines() {
+        if let Some(id) = line.strip_prefix("id:") {
+            let id = id.trim();
+            if !id.is_empty() {
+                event_id = Some(id.to_string());
             }
+        } else if let Some(data) = line.strip_prefix("data:") {
+            data_lines.push(data.trim().to_string());
         }
     }
+
+    (event_id, data_lines)
+}
+
+/// Forward a JSON-RPC message from the wire to the response channel.
+/// Server-initiated requests and notifications have no result or error and
+/// are skipped; response IDs may arrive as strings or numbers.
+fn forward_jsonrpc_message(json: &Value, response_tx: &mpsc::UnboundedSender<McpResponse>) {
+    if json.get("result").is_none() && json.get("error").is_none() {
+        return;
+    }
+
+    let id = match json.get("id") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    };
+
+    let response = McpResponse {
+        id,
+        result: json.get("result").cloned(),
+        error: json.get("error").and_then(|e| serde_json::from_value(e.clone()).ok()),
+    };
+    let _ = response_tx.send(response);
 }
 
 /// Handle stdio communication
 async fn handle_stdio_communication(
+    name: String,
     mut stdin: tokio::process::ChildStdin,
     stdout: tokio::process::ChildStdout,
     mut request_rx: mpsc::UnboundedReceiver<McpRequest>,
     response_tx: mpsc::UnboundedSender<McpResponse>,
+    reply_tx: mpsc::UnboundedSender<McpRequest>,
 ) {
     let mut reader = BufReader::new(stdout);
     let mut line = String::new();
@@ -758,13 +1804,18 @@ async fn handle_stdio_communication(
     loop {
         tokio::select! {
             Some(request) = request_rx.recv() => {
-                // Send request as JSON-RPC 2.0
-                let json_rpc = serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": request.id,
-                    "method": request.method,
-                    "params": request.params
-                });
+                // Send request as JSON-RPC 2.0; replies to server-initiated
+                // requests carry a prebuilt payload and go out verbatim
+                let json_rpc = if request.method == SERVER_REPLY_METHOD {
+                    request.params.clone().unwrap_or(Value::Null)
+                } else {
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request.id,
+                        "method": request.method,
+                        "params": request.params
+                    })
+                };
                 let request_str = serde_json::to_string(&json_rpc).unwrap();
                 if let Err(e) = stdin.write_all(request_str.as_bytes()).await {
                     eprintln!("Failed to write to stdin: {}", e);
@@ -783,14 +1834,17 @@ async fn handle_stdio_communication(
                 match result {
                     Ok(0) => break, // EOF
                     Ok(_) => {
-                        // Parse JSON-RPC response
+                        // Parse JSON-RPC response; server-initiated requests
+                        // and notifications are handled separately
                         if let Ok(json) = serde_json::from_str::<Value>(&line) {
-                            let response = McpResponse {
-                                id: json.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                                result: json.get("result").cloned(),
-                                error: json.get("error").and_then(|e| serde_json::from_value(e.clone()).ok()),
-                            };
-                            let _ = response_tx.send(response);
+                            if !handle_server_message(&name, &json, &reply_tx) {
+                                let response = McpResponse {
+                                    id: json.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                                    result: json.get("result").cloned(),
+                                    error: json.get("error").and_then(|e| serde_json::from_value(e.clone()).ok()),
+                                };
+                                let _ = response_tx.send(response);
+                            }
                         }
                         line.clear();
                     }
@@ -823,6 +1877,7 @@ impl McpClient {
 
         loop {
             if start.elapsed() > timeout {
+                record_server_error(&self.name, "request timed out");
                 return Err(Error::Timeout("MCP request timed out".to_string()));
             }
 
@@ -830,13 +1885,17 @@ impl McpClient {
                 Ok(Some(response)) => {
                     if response.id == id {
                         if let Some(error) = response.error {
-                            return Err(Error::Other(format!("MCP error: {} (code: {})", error.message, error.code)));
+                            let message = format!("MCP error: {} (code: {})", error.message, error.code);
+                            record_server_error(&self.name, &message);
+                            return Err(Error::Other(message));
                         }
+                        record_request_latency(&self.name, start.elapsed().as_millis() as u64);
                         return Ok(response.result.unwrap_or(Value::Null));
                     }
                     // Not our response, continue waiting
                 }
                 Ok(None) => {
+                    record_server_error(&self.name, "channel closed");
                     return Err(Error::Other("MCP channel closed".to_string()));
                 }
                 Err(_) => {
@@ -878,7 +1937,7 @@ impl McpClient {
     pub async fn list_tools(&mut self) -> Result<Vec<McpTool>> {
         let result = self.request("tools/list", None).await?;
 
-        let tools = result.get("tools")
+        let tools: Vec<McpTool> = result.get("tools")
             .and_then(|t| t.as_array())
             .map(|arr| {
                 arr.iter()
@@ -893,6 +1952,7 @@ impl McpClient {
             })
             .unwrap_or_default();
 
+        record_tool_count(&self.name, tools.len());
         Ok(tools)
     }
 
@@ -952,10 +2012,103 @@ impl McpClient {
         Ok(contents.to_string())
     }
 
+    /// List available prompts from the server
+    pub async fn list_prompts(&mut self) -> Result<Vec<McpPrompt>> {
+        let result = self.request("prompts/list", None).await?;
+
+        let prompts = result.get("prompts")
+            .and_then(|p| p.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| {
+                        let arguments = v.get("arguments")
+                            .and_then(|a| a.as_array())
+                            .map(|args| {
+                                args.iter()
+                                    .filter_map(|arg| {
+                                        Some(McpPromptArgument {
+                                            name: arg.get("name")?.as_str()?.to_string(),
+                                            description: arg.get("description").and_then(|d| d.as_str()).map(|s| s.to_string()),
+                                            required: arg.get("required").and_then(|r| r.as_bool()).unwrap_or(false),
+                                        })
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        Some(McpPrompt {
+                            name: v.get("name")?.as_str()?.to_string(),
+                            description: v.get("description").and_then(|d| d.as_str()).map(|s| s.to_string()),
+                            arguments,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(prompts)
+    }
+
+    /// Expand a prompt template with the given arguments, returning the
+    /// concatenated text of the messages the server produced
+    pub async fn get_prompt(&mut self, name: &str, arguments: Value) -> Result<String> {
+        let params = serde_json::json!({
+            "name": name,
+            "arguments": arguments
+        });
+
+        let result = self.request("prompts/get", Some(params)).await?;
+
+        let text = result.get("messages")
+            .and_then(|m| m.as_array())
+            .map(|messages| {
+                messages.iter()
+                    .filter_map(|msg| {
+                        let content = msg.get("content")?;
+                        // Content is either a single block or an array of blocks
+                        if let Some(text) = content.get("text").and_then(|t| t.as_str()) {
+                            Some(text.to_string())
+                        } else {
+                            content.as_array().map(|blocks| {
+                                blocks.iter()
+                                    .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                                    .collect::<Vec<_>>()
+                                    .join("\n")
+                            })
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            })
+            .unwrap_or_default();
+
+        Ok(text)
+    }
+
     /// Get server name
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Transport this client connects over
+    pub fn transport(&self) -> TransportType {
+        self.transport.clone()
+    }
+}
+
+/// MCP Prompt definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpPrompt {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+/// Argument accepted by an MCP prompt template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpPromptArgument {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
 }
 
 /// MCP Resource definition
@@ -975,4 +2128,159 @@ pub async fn connect_and_initialize(name: &str, config: &McpServerConfig) -> Res
     client.initialize().await?;
 
     Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sse_event_with_id_and_data() {
+        let event = "id: 42\ndata: {\"jsonrpc\":\"2.0\",\"id\":\"abc\",\"result\":{}}";
+        let (event_id, data_lines) = parse_sse_event(event);
+        assert_eq!(event_id.as_deref(), Some("42"));
+        assert_eq!(data_lines.len(), 1);
+        assert!(data_lines[0].starts_with("{\"jsonrpc\""));
+    }
+
+    #[test]
+    fn test_parse_sse_event_without_id() {
+        let (event_id, data_lines) = parse_sse_event("event: message\ndata: {}");
+        assert!(event_id.is_none());
+        assert_eq!(data_lines, vec!["{}".to_string()]);
+    }
+
+    #[test]
+    fn test_connection_state_registry() {
+        assert!(connection_state("sse-test-server").is_none());
+        set_connection_state("sse-test-server", McpConnectionState::Connected);
+        assert_eq!(
+            connection_state("sse-test-server"),
+            Some(McpConnectionState::Connected)
+        );
+        set_connection_state("sse-test-server", McpConnectionState::Reconnecting);
+        assert_eq!(
+            connection_state("sse-test-server").map(|s| s.as_str()),
+            Some("reconnecting")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_serve_handle_message_protocol_basics() {
+        let executor = crate::ai::tools::ToolExecutor::new();
+
+        // initialize advertises the tools capability
+        let init = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"});
+        let response = serve_handle_message(&executor, &init)
+            .await
+            .expect("initialize gets a reply");
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(response["result"]["serverInfo"]["name"], "llminate");
+
+        // tools/list exposes the built-in tools with schemas
+        let list = serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"});
+        let response = serve_handle_message(&executor, &list)
+            .await
+            .expect("tools/list gets a reply");
+        let tools = response["result"]["tools"]
+            .as_array()
+            .expect("tools array");
+        assert!(!tools.is_empty());
+        assert!(tools.iter().all(|t| t.get("inputSchema").is_some()));
+
+        // Notifications get no reply; unknown methods get -32601
+        let note = serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        assert!(serve_handle_message(&executor, &note).await.is_none());
+        let unknown = serde_json::json!({"jsonrpc": "2.0", "id": 3, "method": "bogus"});
+        let response = serve_handle_message(&executor, &unknown)
+            .await
+            .expect("unknown method gets an error reply");
+        assert_eq!(response["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn test_sampling_rate_limit_window() {
+        // Unique server name so parallel tests don't share the window
+        let name = "rate-limit-test-server";
+        for _ in 0..SAMPLING_RATE_LIMIT {
+            assert!(record_sampling_request(name));
+        }
+        assert!(!record_sampling_request(name));
+        // Other servers keep their own budget
+        assert!(record_sampling_request("rate-limit-other-server"));
+    }
+
+    #[test]
+    fn test_handle_server_message_replies_method_not_found() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let request = serde_json::json!({"jsonrpc": "2.0", "id": 7, "method": "roots/list"});
+        assert!(handle_server_message("srv", &request, &tx));
+
+        let reply = rx.try_recv().expect("unimplemented method should be answered");
+        assert_eq!(reply.method, SERVER_REPLY_METHOD);
+        let payload = reply.params.expect("reply carries a payload");
+        assert_eq!(payload["id"], 7);
+        assert_eq!(payload["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn test_handle_server_message_consumes_notifications_and_passes_responses() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        // Notifications are consumed without a reply
+        let notification =
+            serde_json::json!({"jsonrpc": "2.0", "method": "notifications/progress"});
+        assert!(handle_server_message("srv", &notification, &tx));
+        assert!(rx.try_recv().is_err());
+
+        // Responses to our own requests are left for the response channel
+        let response = serde_json::json!({"jsonrpc": "2.0", "id": "abc", "result": {}});
+        assert!(!handle_server_message("srv", &response, &tx));
+    }
+
+    #[test]
+    fn test_parse_sampling_messages_accepts_block_and_array_content() {
+        let params = serde_json::json!({
+            "messages": [
+                {"role": "user", "content": {"type": "text", "text": "hello"}},
+                {"role": "assistant", "content": [
+                    {"type": "text", "text": "first"},
+                    {"type": "text", "text": "second"}
+                ]}
+            ]
+        });
+
+        let messages = parse_sampling_messages(&params).expect("valid messages");
+        assert_eq!(messages.len(), 2);
+        match &messages[1].content {
+            crate::ai::MessageContent::Text(text) => assert_eq!(text, "first\nsecond"),
+            other => panic!("unexpected content: {:?}", other),
+        }
+
+        // A missing messages array is rejected
+        assert!(parse_sampling_messages(&serde_json::json!({})).is_err());
+        // Unknown roles are rejected
+        let bad_role = serde_json::json!({
+            "messages": [{"role": "system", "content": {"type": "text", "text": "x"}}]
+        });
+        assert!(parse_sampling_messages(&bad_role).is_err());
+    }
+
+    #[test]
+    fn test_forward_jsonrpc_message_handles_numeric_ids_and_skips_requests() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        // A server-initiated request has no result/error and is not forwarded
+        let server_request = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+        forward_jsonrpc_message(&server_request, &tx);
+        assert!(rx.try_recv().is_err());
+
+        // A response with a numeric id is forwarded with the id stringified
+        let response = serde_json::json!({"jsonrpc": "2.0", "id": 7, "result": {"ok": true}});
+        forward_jsonrpc_message(&response, &tx);
+        let received = rx.try_recv().expect("response should be forwarded");
+        assert_eq!(received.id, "7");
+        assert!(received.result.is_some());
+    }
 }
\ No newline at end of file
@@ -823,4 +823,341 @@ pub async fn fetch_organization_roles(access_token: &str) -> Result<RolesRespons
     
     debug!("Organization roles: {:?}", roles);
     Ok(roles)
-}
\ No newline at end of file
+}
+// ==========================================================================
+// MCP server authorization
+//
+// Remote MCP servers protect their endpoints with standard OAuth 2.1:
+// the client discovers the authorization server via RFC 8414 metadata,
+// registers itself dynamically (RFC 7591), and runs the authorization
+// code flow with PKCE. Tokens are stored per-server in the credentials
+// backend under "mcpOauth" and refreshed automatically on 401.
+// ==========================================================================
+
+/// OAuth token for a remote MCP server, stored in the credentials backend
+/// keyed by server name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpOauthToken {
+    #[serde(rename = "accessToken")]
+    pub access_token: String,
+    #[serde(rename = "refreshToken", skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// Expiry as seconds since the Unix epoch
+    #[serde(rename = "expiresAt", skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    /// Client ID obtained via dynamic registration; needed for refresh
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    /// Token endpoint from server metadata; needed for refresh
+    #[serde(rename = "tokenEndpoint")]
+    pub token_endpoint: String,
+}
+
+impl McpOauthToken {
+    /// Whether the access token has expired (with a one minute margin so
+    /// a token that is about to expire is refreshed proactively)
+    pub fn is_expired(&self) -> bool {
+        match self.expires_at {
+            Some(expires_at) => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs() as i64;
+                now + 60 >= expires_at
+            }
+            None => false,
+        }
+    }
+}
+
+/// Authorization server metadata (RFC 8414 subset)
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpAuthServerMetadata {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub registration_endpoint: Option<String>,
+}
+
+/// Dynamic client registration response (RFC 7591 subset)
+#[derive(Debug, Deserialize)]
+struct McpClientRegistration {
+    client_id: String,
+}
+
+/// Token endpoint response for MCP servers. Unlike Anthropic's endpoint,
+/// refresh_token and token_type are optional here
+#[derive(Debug, Deserialize)]
+struct McpTokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+impl McpTokenResponse {
+    fn into_token(self, client_id: String, token_endpoint: String) -> McpOauthToken {
+        let expires_at = self.expires_in.map(|exp| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64 + exp
+        });
+        McpOauthToken {
+            access_token: self.access_token,
+            refresh_token: self.refresh_token,
+            expires_at,
+            client_id,
+            token_endpoint,
+        }
+    }
+}
+
+/// Discover the authorization server for a remote MCP server via
+/// RFC 8414 well-known metadata, falling back to the OpenID Connect
+/// discovery document some servers publish instead
+pub async fn discover_mcp_auth_server(server_url: &str) -> Result<McpAuthServerMetadata> {
+    let parsed = url::Url::parse(server_url)
+        .with_context(|| format!("Invalid MCP server URL: {}", server_url))?;
+    let origin = parsed.origin().ascii_serialization();
+
+    let client = reqwest::Client::new();
+    for path in [
+        "/.well-known/oauth-authorization-server",
+        "/.well-known/openid-configuration",
+    ] {
+        let metadata_url = format!("{}{}", origin, path);
+        debug!("Trying authorization server metadata at {}", metadata_url);
+
+        let response = match client.get(&metadata_url).send().await {
+            Ok(response) if response.status().is_success() => response,
+            _ => continue,
+        };
+
+        match response.json::<McpAuthServerMetadata>().await {
+            Ok(metadata) => {
+                info!("Discovered authorization server metadata at {}", metadata_url);
+                return Ok(metadata);
+            }
+            Err(e) => {
+                debug!("Failed to parse metadata from {}: {}", metadata_url, e);
+            }
+        }
+    }
+
+    bail!(
+        "MCP server at {} does not publish OAuth authorization server metadata",
+        server_url
+    )
+}
+
+/// Register this client with the authorization server (RFC 7591),
+/// returning the issued client_id. Public clients use PKCE instead of
+/// a client secret, so token_endpoint_auth_method is "none"
+pub async fn register_mcp_client(
+    metadata: &McpAuthServerMetadata,
+    redirect_uri: &str,
+) -> Result<String> {
+    let registration_endpoint = metadata.registration_endpoint.as_ref()
+        .context("Authorization server does not support dynamic client registration")?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(registration_endpoint)
+        .json(&serde_json::json!({
+            "client_name": "llminate",
+            "redirect_uris": [redirect_uri],
+            "grant_types": ["authorization_code", "refresh_token"],
+            "response_types": ["code"],
+            "token_endpoint_auth_method": "none",
+        }))
+        .send()
+        .await
+        .context("Failed to register OAuth client with MCP server")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        bail!("Client registration failed with status {}: {}", status, error_text);
+    }
+
+    let registration: McpClientRegistration = response
+        .json()
+        .await
+        .context("Failed to parse client registration response")?;
+
+    info!("Registered MCP OAuth client: {}", registration.client_id);
+    Ok(registration.client_id)
+}
+
+/// Run the full interactive authorization flow for a remote MCP server:
+/// discovery, dynamic registration, browser-based authorization code
+/// grant with PKCE, and token exchange
+pub async fn authorize_mcp_server(server_url: &str) -> Result<McpOauthToken> {
+    let metadata = discover_mcp_auth_server(server_url).await?;
+
+    let config = OAuthConfig::default();
+    let redirect_uri = format!("http://localhost:{}/callback", config.redirect_port);
+
+    let client_id = register_mcp_client(&metadata, &redirect_uri).await?;
+
+    let (verifier, challenge) = OAuthManager::generate_pkce();
+    let state = OAuthManager::generate_state();
+
+    let mut auth_url = url::Url::parse(&metadata.authorization_endpoint)
+        .context("Failed to parse authorization endpoint")?;
+    auth_url.query_pairs_mut()
+        .append_pair("client_id", &client_id)
+        .append_pair("response_type", "code")
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("state", &state);
+
+    // Reuse the localhost callback server the Anthropic login flow uses;
+    // it binds first, then opens the browser
+    let manager = OAuthManager::new();
+    let (code, returned_state) = manager.start_callback_server(Some(auth_url.as_str())).await?;
+
+    if returned_state != state {
+        bail!("OAuth state mismatch - authorization response may have been tampered with");
+    }
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(&metadata.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", client_id.as_str()),
+            ("code_verifier", verifier.as_str()),
+        ])
+        .send()
+        .await
+        .context("Failed to exchange authorization code with MCP server")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        bail!("Token exchange failed with status {}: {}", status, error_text);
+    }
+
+    let tokens: McpTokenResponse = response
+        .json()
+        .await
+        .context("Failed to parse MCP token response")?;
+
+    info!("Obtained OAuth token for MCP server at {}", server_url);
+    Ok(tokens.into_token(client_id, metadata.token_endpoint))
+}
+
+/// Refresh an MCP server token via the refresh_token grant
+pub async fn refresh_mcp_token(token: &McpOauthToken) -> Result<McpOauthToken> {
+    let refresh_token = token.refresh_token.as_ref()
+        .context("Stored token has no refresh token")?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(&token.token_endpoint)
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+            ("client_id", token.client_id.as_str()),
+        ])
+        .send()
+        .await
+        .context("Failed to send token refresh request")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        bail!("Token refresh failed with status {}: {}", status, error_text);
+    }
+
+    let mut tokens: McpTokenResponse = response
+        .json()
+        .await
+        .context("Failed to parse token refresh response")?;
+
+    // Servers that do not rotate refresh tokens omit the field; keep
+    // the one we already have so future refreshes still work
+    if tokens.refresh_token.is_none() {
+        tokens.refresh_token = token.refresh_token.clone();
+    }
+
+    Ok(tokens.into_token(token.client_id.clone(), token.token_endpoint.clone()))
+}
+
+/// Load the stored token for an MCP server, if one exists
+pub async fn load_mcp_token(server_name: &str) -> Option<McpOauthToken> {
+    let storage = crate::auth::storage::get_storage_backend().ok()?;
+    let credentials = storage.read().await.ok()??;
+    credentials.mcp_oauth?.get(server_name).cloned()
+}
+
+/// Persist an MCP server token, preserving all other stored credentials
+pub async fn save_mcp_token(server_name: &str, token: McpOauthToken) -> Result<()> {
+    let storage = crate::auth::storage::get_storage_backend()?;
+    let mut credentials = storage.read().await?
+        .unwrap_or(crate::auth::storage::Credentials {
+            claude_ai_oauth: None,
+            mcp_oauth: None,
+        });
+
+    credentials.mcp_oauth
+        .get_or_insert_with(HashMap::new)
+        .insert(server_name.to_string(), token);
+
+    storage.update(credentials).await?;
+    debug!("Saved MCP OAuth token for server '{}'", server_name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mcp_oauth_token_expiry() {
+        let mut token = McpOauthToken {
+            access_token: "at".to_string(),
+            refresh_token: None,
+            expires_at: None,
+            client_id: "cid".to_string(),
+            token_endpoint: "https://example.com/token".to_string(),
+        };
+        // No expiry recorded means the token is assumed valid
+        assert!(!token.is_expired());
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        token.expires_at = Some(now + 3600);
+        assert!(!token.is_expired());
+        // Inside the one minute refresh margin counts as expired
+        token.expires_at = Some(now + 30);
+        assert!(token.is_expired());
+        token.expires_at = Some(now - 10);
+        assert!(token.is_expired());
+    }
+
+    #[test]
+    fn test_mcp_oauth_token_serializes_camel_case() {
+        let token = McpOauthToken {
+            access_token: "at".to_string(),
+            refresh_token: Some("rt".to_string()),
+            expires_at: Some(1_700_000_000),
+            client_id: "cid".to_string(),
+            token_endpoint: "https://example.com/token".to_string(),
+        };
+        let json = serde_json::to_value(&token).unwrap();
+        assert_eq!(json["accessToken"], "at");
+        assert_eq!(json["refreshToken"], "rt");
+        assert_eq!(json["expiresAt"], 1_700_000_000);
+        assert_eq!(json["clientId"], "cid");
+        assert_eq!(json["tokenEndpoint"], "https://example.com/token");
+    }
+}
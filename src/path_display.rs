@@ -0,0 +1,113 @@
+//! Workspace-relative path normalization for tool outputs.
+//!
+//! Tool results and diffs echo absolute paths constantly (`Read`,
+//! `Grep -n`, edit summaries, unified diff headers). Rewriting paths
+//! under the workspace root to relative form saves tokens on every
+//! result and keeps transcripts portable between machines — a session
+//! exported on one laptop replays on another without `/Users/alice`
+//! baked into every line. The reverse direction is handled at the tool
+//! dispatch choke point: relative path inputs from the model are
+//! resolved back onto the workspace root before execution, so the
+//! model can speak in either form.
+//!
+//! Disabled with `normalizePaths: false` in settings.json for
+//! multi-root setups (additional working directories outside the
+//! workspace), where a stripped prefix would be ambiguous.
+
+use std::path::{Path, PathBuf};
+
+/// The workspace root all normalization is anchored to: the directory
+/// the session was started in
+fn workspace_root() -> Option<PathBuf> {
+    std::env::current_dir().ok()
+}
+
+/// Rewrite absolute paths under `root` to workspace-relative form.
+/// Only `{root}/` prefixes are touched — paths outside the workspace
+/// (and the bare root itself) pass through unchanged, so multi-root
+/// references stay unambiguous even when normalization is on
+fn normalize_with_root(text: &str, root: &Path) -> String {
+    let root_str = root.to_string_lossy();
+    // Never strip the filesystem root; that would mangle every path
+    if root_str == "/" || root_str.is_empty() {
+        return text.to_string();
+    }
+    let prefix = format!("{}/", root_str.trim_end_matches('/'));
+    text.replace(&prefix, "")
+}
+
+/// Resolve a (possibly relative) path from the model against `root`.
+/// This is the reverse map for [`normalize_output`]: paths the model
+/// read back from a normalized transcript are joined onto the
+/// workspace root before execution
+fn resolve_with_root(path: &str, root: &Path) -> String {
+    if Path::new(path).is_absolute() {
+        path.to_string()
+    } else {
+        root.join(path).to_string_lossy().into_owned()
+    }
+}
+
+/// Whether output normalization is enabled (normalizePaths in
+/// settings.json, default true)
+pub fn is_enabled() -> bool {
+    crate::config::get_normalize_paths()
+}
+
+/// Normalize absolute workspace paths in a block of tool output to
+/// relative form. A no-op when disabled or the workspace root is
+/// unavailable
+pub fn normalize_output(text: String) -> String {
+    if !is_enabled() {
+        return text;
+    }
+    match workspace_root() {
+        Some(root) => normalize_with_root(&text, &root),
+        None => text,
+    }
+}
+
+/// Normalize a single path for display (diff headers, edit summaries)
+pub fn display_path(path: &str) -> String {
+    normalize_output(path.to_string())
+}
+
+/// Resolve a path input from the model for execution. Relative paths
+/// are joined onto the workspace root; absolute paths pass through.
+/// Applied even when display normalization is disabled, since the
+/// model may still send relative paths
+pub fn resolve_input_path(path: &str) -> String {
+    match workspace_root() {
+        Some(root) => resolve_with_root(path, &root),
+        None => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_strips_workspace_prefix_everywhere() {
+        let root = Path::new("/home/user/project");
+        let text = "--- /home/user/project/src/main.rs\n+++ /home/user/project/src/main.rs\nSee /etc/hosts";
+        let normalized = normalize_with_root(text, root);
+        assert_eq!(normalized, "--- src/main.rs\n+++ src/main.rs\nSee /etc/hosts");
+    }
+
+    #[test]
+    fn test_normalize_leaves_filesystem_root_alone() {
+        let text = "/etc/passwd and /var/log";
+        assert_eq!(normalize_with_root(text, Path::new("/")), text);
+    }
+
+    #[test]
+    fn test_resolve_joins_relative_onto_root() {
+        let root = Path::new("/home/user/project");
+        assert_eq!(
+            resolve_with_root("src/main.rs", root),
+            "/home/user/project/src/main.rs"
+        );
+        assert_eq!(resolve_with_root("/tmp/other.rs", root), "/tmp/other.rs");
+    }
+}
@@ -0,0 +1,127 @@
+//! Central path-access policy backed by `.claudeignore`.
+//!
+//! A `.claudeignore` file in the project root excludes paths from the
+//! file tools (Read/Grep/Glob/LS/Search), @-mention autocomplete, and
+//! attachments — for secrets, fixtures, vendored code, and anything else
+//! that should stay out of the model's view. Syntax is the gitignore
+//! subset the file picker already understands: comments, anchored
+//! patterns, directory patterns (`vendor/`), and `*` globs; negation
+//! (`!pattern`) is not supported.
+//!
+//! All tools consult the same cached matcher through [`is_path_excluded`],
+//! so the policy cannot drift between tools. The cache reloads when the
+//! file's modification time changes.
+
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// The ignore file name, relative to the current working directory
+const IGNORE_FILE: &str = ".claudeignore";
+
+/// Compiled `.claudeignore` patterns
+#[derive(Debug, Default)]
+struct IgnoreMatcher {
+    patterns: Vec<(glob::Pattern, bool)>, // (pattern, dir_only)
+}
+
+impl IgnoreMatcher {
+    fn load(root: &Path) -> Self {
+        let mut patterns = Vec::new();
+        if let Ok(content) = std::fs::read_to_string(root.join(IGNORE_FILE)) {
+            for line in content.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                    continue;
+                }
+                let dir_only = line.ends_with('/');
+                let mut pattern = line.trim_end_matches('/').to_string();
+                // Unanchored patterns match at any depth
+                if !pattern.starts_with('/') && !pattern.contains('/') {
+                    pattern = format!("**/{}", pattern);
+                } else {
+                    pattern = pattern.trim_start_matches('/').to_string();
+                }
+                if let Ok(compiled) = glob::Pattern::new(&pattern) {
+                    patterns.push((compiled, dir_only));
+                }
+            }
+        }
+        Self { patterns }
+    }
+
+    /// A path is ignored when it, or any of its ancestors, matches a
+    /// pattern (ancestors are directories by construction)
+    fn is_ignored(&self, relative: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let mut prefix = PathBuf::new();
+        let components: Vec<_> = relative.components().collect();
+        for (index, component) in components.iter().enumerate() {
+            prefix.push(component);
+            let is_last = index + 1 == components.len();
+            let prefix_str = prefix.to_string_lossy();
+            for (pattern, dir_only) in &self.patterns {
+                if !pattern.matches(&prefix_str) {
+                    continue;
+                }
+                // dir-only patterns don't match a leaf file, but do match
+                // any directory on the way down
+                if *dir_only && is_last && !relative.is_dir() && prefix == relative {
+                    continue;
+                }
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Matcher cache keyed on the ignore file's modification time
+static MATCHER: Lazy<Mutex<Option<(PathBuf, Option<SystemTime>, IgnoreMatcher)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// Check whether a path is excluded by the project's `.claudeignore`.
+/// Relative paths are resolved against the current working directory;
+/// paths outside the project are never excluded.
+pub fn is_path_excluded<P: AsRef<Path>>(path: P) -> bool {
+    let Ok(root) = std::env::current_dir() else {
+        return false;
+    };
+
+    let path = path.as_ref();
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(path)
+    };
+    let Ok(relative) = absolute.strip_prefix(&root) else {
+        return false;
+    };
+
+    let mtime = std::fs::metadata(root.join(IGNORE_FILE))
+        .and_then(|m| m.modified())
+        .ok();
+
+    let Ok(mut cache) = MATCHER.lock() else {
+        return false;
+    };
+    let stale = match cache.as_ref() {
+        Some((cached_root, cached_mtime, _)) => cached_root != &root || cached_mtime != &mtime,
+        None => true,
+    };
+    if stale {
+        *cache = Some((root.clone(), mtime, IgnoreMatcher::load(&root)));
+    }
+    match cache.as_ref() {
+        Some((_, _, matcher)) => matcher.is_ignored(relative),
+        None => false,
+    }
+}
+
+/// Standard denial message for tools that refuse an excluded path
+pub fn excluded_message(path: &str) -> String {
+    format!("{} is excluded by .claudeignore", path)
+}
@@ -0,0 +1,225 @@
+//! Usage report generation (`llminate report`).
+//!
+//! Aggregates the locally saved sessions under `.claude/conversations`
+//! into a day/week/month summary — sessions, estimated cost by model,
+//! top tools, most edited files — formatted for pasting into a team
+//! update. Everything is computed from local files; nothing is sent
+//! anywhere.
+
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Saved conversation shape (subset of the TUI's ConversationData)
+#[derive(Debug, Deserialize)]
+struct SavedConversation {
+    session_id: String,
+    model: String,
+    messages: Vec<SavedMessage>,
+    timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SavedMessage {
+    role: String,
+    content: String,
+}
+
+/// Aggregated numbers for one model
+#[derive(Debug, Default)]
+struct ModelUsage {
+    sessions: usize,
+    estimated_tokens: u64,
+    estimated_cost: f64,
+}
+
+/// Generate a usage report for the given period ("day", "week", "month")
+/// in the given format ("md" or "text")
+pub fn generate(period: &str, format: &str) -> Result<String> {
+    let days: u64 = match period {
+        "day" => 1,
+        "week" => 7,
+        "month" => 30,
+        other => {
+            return Err(Error::InvalidInput(format!(
+                "Unknown period '{}': expected day, week, or month",
+                other
+            )))
+        }
+    };
+    if format != "md" && format != "text" {
+        return Err(Error::InvalidInput(format!(
+            "Unknown format '{}': expected md or text",
+            format
+        )));
+    }
+
+    let cutoff_ms = crate::utils::timestamp_ms().saturating_sub(days * 86_400_000);
+    let conversations = load_conversations_since(cutoff_ms)?;
+
+    let mut user_messages = 0usize;
+    let mut by_model: HashMap<String, ModelUsage> = HashMap::new();
+    let mut tool_calls: HashMap<String, usize> = HashMap::new();
+    let mut edited_files: HashMap<String, usize> = HashMap::new();
+
+    // Tool activity is recorded in the transcript as "[Tool: Name(detail)]"
+    let tool_regex = regex::Regex::new(r"\[Tool: ([A-Za-z]+)\(([^)]*)\)\]")
+        .map_err(|e| Error::Other(format!("Failed to compile tool regex: {}", e)))?;
+
+    for conversation in &conversations {
+        let mut session_tokens = 0u64;
+        for message in &conversation.messages {
+            // chars/4 heuristic, consistent with the TUI's estimator
+            session_tokens += message.content.len() as u64 / 4;
+            match message.role.as_str() {
+                "user" => user_messages += 1,
+                "assistant" => {
+                    for capture in tool_regex.captures_iter(&message.content) {
+                        let tool = capture[1].to_string();
+                        if (tool == "Write" || tool == "Update") && !capture[2].is_empty() {
+                            *edited_files.entry(capture[2].to_string()).or_insert(0) += 1;
+                        }
+                        *tool_calls.entry(tool).or_insert(0) += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let usage = by_model.entry(conversation.model.clone()).or_default();
+        usage.sessions += 1;
+        usage.estimated_tokens += session_tokens;
+        usage.estimated_cost += estimate_cost(&conversation.model, session_tokens);
+    }
+
+    let today = chrono::Local::now().format("%Y-%m-%d");
+    let mut models: Vec<(&String, &ModelUsage)> = by_model.iter().collect();
+    models.sort_by(|a, b| {
+        b.1.estimated_cost
+            .partial_cmp(&a.1.estimated_cost)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut tools: Vec<(&String, &usize)> = tool_calls.iter().collect();
+    tools.sort_by(|a, b| b.1.cmp(a.1));
+    let mut files: Vec<(&String, &usize)> = edited_files.iter().collect();
+    files.sort_by(|a, b| b.1.cmp(a.1));
+
+    let total_cost: f64 = by_model.values().map(|usage| usage.estimated_cost).sum();
+
+    let mut output = String::new();
+    if format == "md" {
+        output.push_str(&format!(
+            "# llminate usage report — last {} day(s)\n\nGenerated {}\n\n",
+            days, today
+        ));
+        output.push_str(&format!(
+            "## Sessions\n\n- {} session(s), {} user message(s)\n- Estimated total cost: ${:.2}\n\n",
+            conversations.len(),
+            user_messages,
+            total_cost
+        ));
+        output.push_str("## Cost by model (estimated)\n\n| Model | Sessions | Tokens | Cost |\n|---|---|---|---|\n");
+        for (model, usage) in &models {
+            output.push_str(&format!(
+                "| {} | {} | {} | ${:.2} |\n",
+                model, usage.sessions, usage.estimated_tokens, usage.estimated_cost
+            ));
+        }
+        output.push_str("\n## Top tools\n\n| Tool | Calls |\n|---|---|\n");
+        for (tool, calls) in tools.iter().take(10) {
+            output.push_str(&format!("| {} | {} |\n", tool, calls));
+        }
+        output.push_str("\n## Most edited files\n\n| File | Edits |\n|---|---|\n");
+        for (file, edits) in files.iter().take(10) {
+            output.push_str(&format!("| {} | {} |\n", file, edits));
+        }
+    } else {
+        output.push_str(&format!(
+            "llminate usage report — last {} day(s) (generated {})\n\n",
+            days, today
+        ));
+        output.push_str(&format!(
+            "Sessions: {} ({} user messages), estimated total cost ${:.2}\n\n",
+            conversations.len(),
+            user_messages,
+            total_cost
+        ));
+        output.push_str("Cost by model (estimated):\n");
+        for (model, usage) in &models {
+            output.push_str(&format!(
+                "  {:<40} {:>4} sessions  {:>10} tokens  ${:.2}\n",
+                model, usage.sessions, usage.estimated_tokens, usage.estimated_cost
+            ));
+        }
+        output.push_str("\nTop tools:\n");
+        for (tool, calls) in tools.iter().take(10) {
+            output.push_str(&format!("  {:<20} {}\n", tool, calls));
+        }
+        output.push_str("\nMost edited files:\n");
+        for (file, edits) in files.iter().take(10) {
+            output.push_str(&format!("  {:<40} {}\n", file, edits));
+        }
+    }
+
+    if conversations.is_empty() {
+        output.push_str("\nNo sessions found in this period.\n");
+    }
+
+    Ok(output)
+}
+
+/// Load saved conversations newer than the cutoff (milliseconds since epoch)
+fn load_conversations_since(cutoff_ms: u64) -> Result<Vec<SavedConversation>> {
+    let dir = conversation_dir();
+    let mut conversations = Vec::new();
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        // No conversation directory yet: an empty report, not an error
+        return Ok(conversations);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        // Skip unreadable or foreign files instead of failing the report
+        let Ok(conversation) = serde_json::from_str::<SavedConversation>(&content) else {
+            continue;
+        };
+        if conversation.timestamp >= cutoff_ms && !conversation.session_id.is_empty() {
+            conversations.push(conversation);
+        }
+    }
+    conversations.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(conversations)
+}
+
+/// Where the TUI saves sessions (matches state.rs)
+fn conversation_dir() -> PathBuf {
+    std::env::current_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(".claude")
+        .join("conversations")
+}
+
+/// Rough cost estimate using the same per-model pricing as the TUI's /cost
+fn estimate_cost(model: &str, token_count: u64) -> f64 {
+    let (input_price_per_1m, output_price_per_1m) = if model.contains("opus-4") {
+        (15.0, 75.0)
+    } else if model.contains("sonnet-4") || model.contains("3-7-sonnet") || model.contains("3-5-sonnet") {
+        (3.0, 15.0)
+    } else if model.contains("haiku") {
+        (0.25, 1.25)
+    } else {
+        (3.0, 15.0)
+    };
+
+    let input_cost = (token_count as f64 / 1_000_000.0) * input_price_per_1m;
+    let estimated_output_tokens = token_count / 2;
+    let output_cost = (estimated_output_tokens as f64 / 1_000_000.0) * output_price_per_1m;
+
+    input_cost + output_cost
+}
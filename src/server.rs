@@ -0,0 +1,321 @@
+//! Headless server mode: a local OpenAI-compatible chat completions API.
+//!
+//! `llminate serve --port 8787` exposes `POST /v1/chat/completions` (and
+//! `GET /v1/models`) backed by the configured provider, with this crate's
+//! tool execution and permission policies applied. Editors and plugins that
+//! already speak the OpenAI API can reuse the agent as a backend without a
+//! terminal session.
+
+use crate::error::{Error, Result};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Maximum agent turns per request (model -> tools -> model round trips)
+const MAX_AGENT_TURNS: usize = 10;
+
+/// Options for the headless server
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    pub host: String,
+    pub port: u16,
+    pub allowed_tools: Vec<String>,
+    pub disallowed_tools: Vec<String>,
+}
+
+/// Shared state for request handlers
+struct ServerState {
+    options: ServeOptions,
+}
+
+/// OpenAI-style chat completion request (the subset we accept)
+#[derive(Debug, Deserialize)]
+struct ChatCompletionRequest {
+    #[serde(default)]
+    model: Option<String>,
+    messages: Vec<OpenAiMessage>,
+    #[serde(default)]
+    max_tokens: Option<u32>,
+    #[serde(default)]
+    temperature: Option<f32>,
+    #[serde(default)]
+    stream: Option<bool>,
+}
+
+/// OpenAI message: content is either a string or an array of content parts
+#[derive(Debug, Deserialize)]
+struct OpenAiMessage {
+    role: String,
+    #[serde(default)]
+    content: Value,
+}
+
+/// OpenAI-style error body
+#[derive(Debug, Serialize)]
+struct ApiError {
+    error: ApiErrorDetail,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiErrorDetail {
+    message: String,
+    #[serde(rename = "type")]
+    error_type: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, error_type: &str, message: impl Into<String>) -> Response {
+        let body = ApiError {
+            error: ApiErrorDetail {
+                message: message.into(),
+                error_type: error_type.to_string(),
+            },
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Run the headless server until interrupted
+pub async fn run(options: ServeOptions) -> Result<()> {
+    let addr = format!("{}:{}", options.host, options.port);
+    let state = Arc::new(ServerState { options });
+
+    let app = Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .route("/v1/models", get(list_models))
+        .route("/health", get(health))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| Error::Other(format!("Failed to bind {}: {}", addr, e)))?;
+
+    println!("llminate serve listening on http://{}", addr);
+    println!("  POST /v1/chat/completions (OpenAI-compatible)");
+    println!("  GET  /v1/models");
+
+    axum::serve(listener, app)
+        .await
+        .map_err(|e| Error::Other(format!("Server error: {}", e)))?;
+
+    Ok(())
+}
+
+async fn health() -> Json<Value> {
+    Json(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }))
+}
+
+/// GET /v1/models: the models this backend can serve
+async fn list_models() -> Json<Value> {
+    let registry = crate::ai::models::ModelRegistry::new();
+    let data: Vec<Value> = registry
+        .list_models()
+        .iter()
+        .map(|model| {
+            json!({
+                "id": model.id,
+                "object": "model",
+                "owned_by": "llminate",
+            })
+        })
+        .collect();
+    Json(json!({ "object": "list", "data": data }))
+}
+
+/// Extract plain text from an OpenAI message content value (string or parts array)
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| {
+                if part.get("type").and_then(|t| t.as_str()) == Some("text") {
+                    part.get("text").and_then(|t| t.as_str()).map(str::to_string)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// POST /v1/chat/completions
+async fn chat_completions(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Response {
+    if request.messages.is_empty() {
+        return ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_request_error",
+            "messages must not be empty",
+        );
+    }
+    if request.stream == Some(true) {
+        return ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_request_error",
+            "stream=true is not supported by this endpoint yet; use stream=false",
+        );
+    }
+
+    match run_agent(&state, &request).await {
+        Ok((content, prompt_tokens, completion_tokens)) => {
+            let body = json!({
+                "id": format!("chatcmpl-{}", uuid::Uuid::new_v4().simple()),
+                "object": "chat.completion",
+                "created": crate::utils::timestamp_ms() / 1000,
+                "model": request.model.clone().unwrap_or_default(),
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": content },
+                    "finish_reason": "stop",
+                }],
+                "usage": {
+                    "prompt_tokens": prompt_tokens,
+                    "completion_tokens": completion_tokens,
+                    "total_tokens": prompt_tokens + completion_tokens,
+                },
+            });
+            Json(body).into_response()
+        }
+        Err(Error::PermissionDenied(message)) => {
+            ApiError::new(StatusCode::FORBIDDEN, "permission_error", message)
+        }
+        Err(Error::InvalidInput(message)) => {
+            ApiError::new(StatusCode::BAD_REQUEST, "invalid_request_error", message)
+        }
+        Err(e) => ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "api_error", e.to_string()),
+    }
+}
+
+/// Run the agent loop for one completion: call the provider, execute any
+/// requested tools through the shared ToolExecutor (which enforces allow /
+/// deny lists, per-tool settings, and permission policies), feed results
+/// back, and return the final assistant text plus token usage.
+async fn run_agent(
+    state: &ServerState,
+    request: &ChatCompletionRequest,
+) -> Result<(String, u64, u64)> {
+    let ai_client = crate::ai::create_client().await?;
+
+    let mut tool_executor = crate::ai::tools::ToolExecutor::new();
+    tool_executor.set_allowed_tools(state.options.allowed_tools.clone());
+    tool_executor.set_disallowed_tools(state.options.disallowed_tools.clone());
+    let tools = tool_executor.get_available_tools();
+
+    // Split out system messages (OpenAI carries them inline) and convert the rest
+    let mut system_prompt = String::new();
+    let mut messages: Vec<crate::ai::Message> = Vec::new();
+    for msg in &request.messages {
+        let text = content_text(&msg.content);
+        match msg.role.as_str() {
+            "system" => {
+                if !system_prompt.is_empty() {
+                    system_prompt.push('\n');
+                }
+                system_prompt.push_str(&text);
+            }
+            "assistant" => messages.push(crate::ai::Message {
+                role: crate::ai::MessageRole::Assistant,
+                content: crate::ai::MessageContent::Text(text),
+                name: None,
+            }),
+            _ => messages.push(crate::ai::Message {
+                role: crate::ai::MessageRole::User,
+                content: crate::ai::MessageContent::Text(text),
+                name: None,
+            }),
+        }
+    }
+
+    let mut response_text = String::new();
+    let mut prompt_tokens: u64 = 0;
+    let mut completion_tokens: u64 = 0;
+
+    for _ in 0..MAX_AGENT_TURNS {
+        let mut builder = ai_client
+            .create_chat_request()
+            .messages(messages.clone())
+            .max_tokens(request.max_tokens.unwrap_or(4096));
+
+        if let Some(model) = &request.model {
+            if !model.is_empty() {
+                builder = builder.model(model);
+            }
+        }
+        if let Some(temperature) = request.temperature {
+            builder = builder.temperature(temperature);
+        }
+        if !system_prompt.is_empty() {
+            builder = builder.system(system_prompt.clone());
+        }
+        if !tools.is_empty() {
+            builder = builder.tools(tools.clone());
+        }
+
+        let response = ai_client.chat(builder.build()).await?;
+        prompt_tokens += response.usage.input_tokens as u64;
+        completion_tokens += response.usage.output_tokens as u64;
+
+        let mut tool_results: Vec<crate::ai::ContentPart> = Vec::new();
+        for part in &response.content {
+            match part {
+                crate::ai::ContentPart::Text { text, .. } => {
+                    response_text.push_str(text);
+                }
+                crate::ai::ContentPart::ToolUse { id, name, input } => {
+                    // Execute through the shared executor so permission
+                    // policies and per-tool settings apply; failures are
+                    // surfaced to the model as error results
+                    let result = match tool_executor.execute(name, input.clone()).await {
+                        // Re-attach the model's tool_use_id: the executor
+                        // generates its own, but replayed results must match
+                        Ok(crate::ai::ContentPart::ToolResult { content, is_error, .. }) => {
+                            crate::ai::ContentPart::ToolResult {
+                                tool_use_id: id.clone(),
+                                content,
+                                is_error,
+                            }
+                        }
+                        Ok(other) => other,
+                        Err(e) => crate::ai::ContentPart::ToolResult {
+                            tool_use_id: id.clone(),
+                            content: format!("Error: {}", e),
+                            is_error: Some(true),
+                        },
+                    };
+                    tool_results.push(result);
+                }
+                _ => {}
+            }
+        }
+
+        if tool_results.is_empty() {
+            break;
+        }
+
+        // Continue the loop: assistant turn with tool_use, user turn with results
+        messages.push(crate::ai::Message {
+            role: crate::ai::MessageRole::Assistant,
+            content: crate::ai::MessageContent::Multipart(response.content.clone()),
+            name: None,
+        });
+        messages.push(crate::ai::Message {
+            role: crate::ai::MessageRole::User,
+            content: crate::ai::MessageContent::Multipart(tool_results),
+            name: None,
+        });
+    }
+
+    Ok((response_text, prompt_tokens, completion_tokens))
+}
@@ -0,0 +1,1012 @@
+//! Conversation persistence backends.
+//!
+//! Sessions have always persisted as one pretty-printed JSON document per
+//! conversation under `.claude/conversations`, and that stays the default.
+//! Large histories make the /resume picker stat and parse every file and
+//! leave transcript search impossible, so `sessionStore: "sqlite"` in
+//! settings.json switches to a single SQLite database storing sessions,
+//! messages (including tool-call transcript entries), token usage, and
+//! metadata relationally, with an FTS5 full-text index over message
+//! content (falling back to LIKE scans when the bundled SQLite lacks
+//! FTS5). Existing JSON conversation files are imported on first open and
+//! left in place, so switching backends loses nothing.
+
+use crate::error::{Error, Result};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// One transcript entry, in the same shape the TUI renders and the
+/// legacy JSON documents store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredMessage {
+    pub role: String,
+    pub content: String,
+    pub timestamp: u64,
+}
+
+/// One saved conversation. Field names and defaults match the legacy
+/// per-file JSON documents, so those parse directly into this type
+/// during import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub session_id: String,
+    pub model: String,
+    pub messages: Vec<StoredMessage>,
+    pub timestamp: u64,
+    /// Tool schema version the session was recorded under (0 = before versioning)
+    #[serde(default)]
+    pub tool_schema_version: u32,
+    /// Cumulative token usage reported while the session ran (0 for
+    /// sessions imported from JSON, which never recorded usage)
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    /// Free-form metadata (stored as a JSON object column)
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Feedback left on one message (👍/👎 plus an optional note), keyed by
+/// message index in the transcript. Stored as JSON in the metadata
+/// field/column so neither the message schema nor the database changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageAnnotation {
+    pub thumbs_up: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl MessageAnnotation {
+    /// The verdict as the symbol shown in transcripts and exports
+    pub fn verdict(&self) -> &'static str {
+        if self.thumbs_up {
+            "👍"
+        } else {
+            "👎"
+        }
+    }
+}
+
+/// Parse the per-message annotations out of a record's metadata
+pub fn annotations_from_metadata(
+    metadata: &HashMap<String, String>,
+) -> HashMap<usize, MessageAnnotation> {
+    metadata
+        .get("annotations")
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// Listing entry for the /resume picker: everything it shows without
+/// loading any message bodies
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    pub id: String,
+    pub created_timestamp: u64,
+    pub modified_timestamp: u64,
+    pub message_count: usize,
+}
+
+/// One full-text search match
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub role: String,
+    pub timestamp: u64,
+    /// Matched content, clipped to an excerpt around the match
+    pub snippet: String,
+}
+
+/// Whether settings select the SQLite backend (sessionStore: "sqlite")
+pub fn sqlite_enabled() -> bool {
+    crate::config::get_session_store() == "sqlite"
+}
+
+/// Formats a session can be exported to (`sessions export`, /export)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            "jsonl" => Ok(ExportFormat::Jsonl),
+            other => Err(Error::InvalidInput(format!(
+                "Unknown export format '{}' (expected md, html, or jsonl)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Load one session from whichever backend holds it: the SQLite store
+/// when enabled, falling back to the per-file JSON document either way
+pub fn load_session(conversation_dir: &Path, session_id: &str) -> Result<Option<SessionRecord>> {
+    if sqlite_enabled() {
+        if let Some(record) =
+            SqliteSessionStore::open_default(conversation_dir)?.load(session_id)?
+        {
+            return Ok(Some(record));
+        }
+    }
+    let path = conversation_dir.join(format!("{}.json", session_id));
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&json)?))
+}
+
+/// Render a session as a shareable document in the given format
+pub fn export_session(record: &SessionRecord, format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Markdown => Ok(export_markdown(record)),
+        ExportFormat::Html => Ok(export_html(record)),
+        ExportFormat::Jsonl => export_jsonl(record),
+    }
+}
+
+/// Human-readable label for a stored message role
+fn role_label(role: &str) -> &str {
+    match role {
+        "user" => "User",
+        "assistant" => "Assistant",
+        "system" => "System",
+        "error" => "Error",
+        "command_output" => "Tool output",
+        other => other,
+    }
+}
+
+/// Roles whose content is raw tool/command output rather than prose
+fn is_tool_role(role: &str) -> bool {
+    matches!(role, "command_output" | "tool" | "tool_result")
+}
+
+/// Format a millisecond timestamp for document headers
+fn format_time(ms: u64) -> String {
+    chrono::DateTime::from_timestamp((ms / 1000) as i64, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| ms.to_string())
+}
+
+fn export_markdown(record: &SessionRecord) -> String {
+    let mut out = format!(
+        "# Conversation {}\n\n- **Model**: {}\n- **Saved**: {}\n- **Messages**: {}\n",
+        record.session_id,
+        record.model,
+        format_time(record.timestamp),
+        record.messages.len(),
+    );
+    if record.input_tokens > 0 || record.output_tokens > 0 {
+        out.push_str(&format!(
+            "- **Tokens**: {} in / {} out\n",
+            record.input_tokens, record.output_tokens
+        ));
+    }
+
+    let annotations = annotations_from_metadata(&record.metadata);
+    for (index, message) in record.messages.iter().enumerate() {
+        out.push_str(&format!(
+            "\n## {} ({})\n\n",
+            role_label(&message.role),
+            format_time(message.timestamp)
+        ));
+        if is_tool_role(&message.role) {
+            // Four backticks so tool output containing fenced blocks
+            // (diffs, code) survives intact
+            out.push_str(&format!("````\n{}\n````\n", message.content.trim_end()));
+        } else {
+            out.push_str(message.content.trim_end());
+            out.push('\n');
+        }
+        if let Some(annotation) = annotations.get(&index) {
+            match &annotation.note {
+                Some(note) => out.push_str(&format!(
+                    "\n> **Feedback**: {} — {}\n",
+                    annotation.verdict(),
+                    note
+                )),
+                None => out.push_str(&format!("\n> **Feedback**: {}\n", annotation.verdict())),
+            }
+        }
+    }
+
+    out
+}
+
+/// Escape text for inclusion in HTML
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn export_html(record: &SessionRecord) -> String {
+    let annotations = annotations_from_metadata(&record.metadata);
+    let mut body = String::new();
+    for (index, message) in record.messages.iter().enumerate() {
+        let label = role_label(&message.role);
+        let time = format_time(message.timestamp);
+        let content = html_escape(message.content.trim_end());
+        if is_tool_role(&message.role) {
+            // Tool outputs and diffs start collapsed, like the TUI
+            body.push_str(&format!(
+                "<details class=\"msg tool\"><summary>{} <time>{}</time></summary><pre>{}</pre></details>\n",
+                label, time, content
+            ));
+        } else {
+            body.push_str(&format!(
+                "<section class=\"msg {}\"><h2>{} <time>{}</time></h2><pre>{}</pre></section>\n",
+                html_escape(&message.role), label, time, content
+            ));
+        }
+        if let Some(annotation) = annotations.get(&index) {
+            let note = annotation
+                .note
+                .as_deref()
+                .map(|n| format!(" — {}", html_escape(n)))
+                .unwrap_or_default();
+            body.push_str(&format!(
+                "<p class=\"feedback\">{} Feedback{}</p>\n",
+                annotation.verdict(),
+                note
+            ));
+        }
+    }
+
+    let tokens = if record.input_tokens > 0 || record.output_tokens > 0 {
+        format!(
+            " &middot; {} in / {} out tokens",
+            record.input_tokens, record.output_tokens
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Conversation {id}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 56rem; margin: 2rem auto; padding: 0 1rem; }}
+.msg {{ margin: 1rem 0; border-left: 3px solid #ccc; padding-left: 1rem; }}
+.msg.user {{ border-color: #3b82f6; }}
+.msg.assistant {{ border-color: #10b981; }}
+.msg.error {{ border-color: #ef4444; }}
+.msg pre {{ white-space: pre-wrap; word-break: break-word; font-size: 0.9rem; }}
+.msg h2, .msg summary {{ font-size: 1rem; }}
+time {{ color: #888; font-weight: normal; font-size: 0.8rem; }}
+.feedback {{ color: #b45309; margin: 0.25rem 0 0 1rem; font-size: 0.9rem; }}
+header p {{ color: #555; }}
+</style>
+</head>
+<body>
+<header>
+<h1>Conversation {id}</h1>
+<p>{model} &middot; saved {saved} &middot; {count} messages{tokens}</p>
+</header>
+{body}</body>
+</html>
+"#,
+        id = html_escape(&record.session_id),
+        model = html_escape(&record.model),
+        saved = format_time(record.timestamp),
+        count = record.messages.len(),
+        tokens = tokens,
+        body = body,
+    )
+}
+
+fn export_jsonl(record: &SessionRecord) -> Result<String> {
+    let mut out = String::new();
+    // First line carries the session metadata, then one line per message
+    out.push_str(&serde_json::to_string(&serde_json::json!({
+        "type": "session",
+        "session_id": record.session_id,
+        "model": record.model,
+        "timestamp": record.timestamp,
+        "input_tokens": record.input_tokens,
+        "output_tokens": record.output_tokens,
+    }))?);
+    out.push('\n');
+    let annotations = annotations_from_metadata(&record.metadata);
+    for (index, message) in record.messages.iter().enumerate() {
+        let mut line = serde_json::json!({
+            "type": "message",
+            "role": message.role,
+            "content": message.content,
+            "timestamp": message.timestamp,
+        });
+        if let Some(annotation) = annotations.get(&index) {
+            line["annotation"] = serde_json::to_value(annotation)?;
+        }
+        out.push_str(&serde_json::to_string(&line)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// SQLite-backed session store. One database holds every session; the
+/// connection is opened per operation by the callers (saves and loads
+/// are rare relative to their cost), so this type stays simple.
+pub struct SqliteSessionStore {
+    conn: Connection,
+    /// Whether the FTS5 index is available in this build of SQLite
+    fts: bool,
+}
+
+/// Map a rusqlite error into the crate error type
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Other(format!("Session store: {}", e))
+}
+
+impl SqliteSessionStore {
+    /// Open (creating if needed) the database at `sessions.db` inside the
+    /// conversation directory, and import any legacy JSON conversation
+    /// files the first time
+    pub fn open_default(conversation_dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(conversation_dir)?;
+        let store = Self::open(&conversation_dir.join("sessions.db"))?;
+        store.import_json_dir_once(conversation_dir)?;
+        Ok(store)
+    }
+
+    /// Open (creating if needed) a session database at the given path
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path).map_err(db_err)?;
+        conn.pragma_update(None, "journal_mode", "WAL").map_err(db_err)?;
+        conn.pragma_update(None, "foreign_keys", "ON").map_err(db_err)?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                 id TEXT PRIMARY KEY,
+                 model TEXT NOT NULL,
+                 created_at INTEGER NOT NULL,
+                 updated_at INTEGER NOT NULL,
+                 tool_schema_version INTEGER NOT NULL DEFAULT 0,
+                 input_tokens INTEGER NOT NULL DEFAULT 0,
+                 output_tokens INTEGER NOT NULL DEFAULT 0,
+                 metadata TEXT NOT NULL DEFAULT '{}'
+             );
+             CREATE TABLE IF NOT EXISTS messages (
+                 session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                 seq INTEGER NOT NULL,
+                 role TEXT NOT NULL,
+                 content TEXT NOT NULL,
+                 timestamp INTEGER NOT NULL,
+                 PRIMARY KEY (session_id, seq)
+             );
+             CREATE INDEX IF NOT EXISTS idx_sessions_updated ON sessions(updated_at);
+             CREATE TABLE IF NOT EXISTS meta (
+                 key TEXT PRIMARY KEY,
+                 value TEXT NOT NULL
+             );",
+        )
+        .map_err(db_err)?;
+
+        // The full-text index is best-effort: bundled SQLite ships FTS5,
+        // but a system libsqlite3 may not, and search then degrades to
+        // LIKE scans instead of failing the whole store
+        let fts = conn
+            .execute_batch(
+                "CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+                     session_id UNINDEXED,
+                     role UNINDEXED,
+                     timestamp UNINDEXED,
+                     content
+                 );",
+            )
+            .is_ok();
+
+        Ok(Self { conn, fts })
+    }
+
+    /// Save (insert or replace) a session and its full transcript. The
+    /// caller always holds the complete message list, mirroring how the
+    /// JSON backend rewrote the whole file on every save.
+    pub fn save(&self, record: &SessionRecord) -> Result<()> {
+        let metadata = serde_json::to_string(&record.metadata)?;
+        let tx = self.conn.unchecked_transaction().map_err(db_err)?;
+
+        tx.execute(
+            "INSERT INTO sessions (id, model, created_at, updated_at, tool_schema_version,
+                                   input_tokens, output_tokens, metadata)
+             VALUES (?1, ?2, ?3, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(id) DO UPDATE SET
+                 model = excluded.model,
+                 updated_at = excluded.updated_at,
+                 tool_schema_version = excluded.tool_schema_version,
+                 input_tokens = excluded.input_tokens,
+                 output_tokens = excluded.output_tokens,
+                 metadata = excluded.metadata",
+            rusqlite::params![
+                record.session_id,
+                record.model,
+                record.timestamp as i64,
+                record.tool_schema_version,
+                record.input_tokens as i64,
+                record.output_tokens as i64,
+                metadata,
+            ],
+        )
+        .map_err(db_err)?;
+
+        tx.execute(
+            "DELETE FROM messages WHERE session_id = ?1",
+            [&record.session_id],
+        )
+        .map_err(db_err)?;
+        if self.fts {
+            tx.execute(
+                "DELETE FROM messages_fts WHERE session_id = ?1",
+                [&record.session_id],
+            )
+            .map_err(db_err)?;
+        }
+
+        {
+            let mut insert = tx
+                .prepare(
+                    "INSERT INTO messages (session_id, seq, role, content, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .map_err(db_err)?;
+            let mut insert_fts = if self.fts {
+                Some(
+                    tx.prepare(
+                        "INSERT INTO messages_fts (session_id, role, timestamp, content)
+                         VALUES (?1, ?2, ?3, ?4)",
+                    )
+                    .map_err(db_err)?,
+                )
+            } else {
+                None
+            };
+
+            for (seq, message) in record.messages.iter().enumerate() {
+                insert
+                    .execute(rusqlite::params![
+                        record.session_id,
+                        seq as i64,
+                        message.role,
+                        message.content,
+                        message.timestamp as i64,
+                    ])
+                    .map_err(db_err)?;
+                if let Some(insert_fts) = insert_fts.as_mut() {
+                    insert_fts
+                        .execute(rusqlite::params![
+                            record.session_id,
+                            message.role,
+                            message.timestamp as i64,
+                            message.content,
+                        ])
+                        .map_err(db_err)?;
+                }
+            }
+        }
+
+        tx.commit().map_err(db_err)
+    }
+
+    /// Load a session with its full transcript, or None if unknown
+    pub fn load(&self, session_id: &str) -> Result<Option<SessionRecord>> {
+        let session = self
+            .conn
+            .query_row(
+                "SELECT model, updated_at, tool_schema_version, input_tokens, output_tokens, metadata
+                 FROM sessions WHERE id = ?1",
+                [session_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, u32>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, String>(5)?,
+                    ))
+                },
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                other => Err(db_err(other)),
+            })?;
+
+        let Some((model, updated_at, tool_schema_version, input_tokens, output_tokens, metadata)) =
+            session
+        else {
+            return Ok(None);
+        };
+
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT role, content, timestamp FROM messages
+                 WHERE session_id = ?1 ORDER BY seq",
+            )
+            .map_err(db_err)?;
+        let messages = statement
+            .query_map([session_id], |row| {
+                Ok(StoredMessage {
+                    role: row.get(0)?,
+                    content: row.get(1)?,
+                    timestamp: row.get::<_, i64>(2)? as u64,
+                })
+            })
+            .map_err(db_err)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+
+        Ok(Some(SessionRecord {
+            session_id: session_id.to_string(),
+            model,
+            messages,
+            timestamp: updated_at as u64,
+            tool_schema_version,
+            input_tokens: input_tokens.max(0) as u64,
+            output_tokens: output_tokens.max(0) as u64,
+            metadata: serde_json::from_str(&metadata).unwrap_or_default(),
+        }))
+    }
+
+    /// List sessions newest-first without loading message bodies
+    pub fn list(&self) -> Result<Vec<SessionSummary>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT s.id, s.created_at, s.updated_at,
+                        (SELECT COUNT(*) FROM messages m WHERE m.session_id = s.id)
+                 FROM sessions s ORDER BY s.updated_at DESC",
+            )
+            .map_err(db_err)?;
+        let summaries = statement
+            .query_map([], |row| {
+                Ok(SessionSummary {
+                    id: row.get(0)?,
+                    created_timestamp: row.get::<_, i64>(1)? as u64,
+                    modified_timestamp: row.get::<_, i64>(2)? as u64,
+                    message_count: row.get::<_, i64>(3)? as usize,
+                })
+            })
+            .map_err(db_err)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        Ok(summaries)
+    }
+
+    /// Delete a session and its transcript
+    pub fn delete(&self, session_id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM sessions WHERE id = ?1", [session_id])
+            .map_err(db_err)?;
+        self.conn
+            .execute("DELETE FROM messages WHERE session_id = ?1", [session_id])
+            .map_err(db_err)?;
+        if self.fts {
+            self.conn
+                .execute("DELETE FROM messages_fts WHERE session_id = ?1", [session_id])
+                .map_err(db_err)?;
+        }
+        Ok(())
+    }
+
+    /// Full-text search across every transcript, newest matches first.
+    /// Uses the FTS5 index when available; otherwise a LIKE scan.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        if self.fts {
+            // Quote each term so user input can't hit FTS query syntax
+            let match_query = query
+                .split_whitespace()
+                .map(|term| format!("\"{}\"", term.replace('"', "")))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if match_query.is_empty() {
+                return Ok(Vec::new());
+            }
+            let mut statement = self
+                .conn
+                .prepare(
+                    "SELECT session_id, role, timestamp,
+                            snippet(messages_fts, 3, '', '', '…', 16)
+                     FROM messages_fts WHERE messages_fts MATCH ?1
+                     ORDER BY rank LIMIT ?2",
+                )
+                .map_err(db_err)?;
+            let hits = statement
+                .query_map(rusqlite::params![match_query, limit as i64], |row| {
+                    Ok(SearchHit {
+                        session_id: row.get(0)?,
+                        role: row.get(1)?,
+                        timestamp: row.get::<_, i64>(2)? as u64,
+                        snippet: row.get(3)?,
+                    })
+                })
+                .map_err(db_err)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(db_err)?;
+            return Ok(hits);
+        }
+
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT session_id, role, timestamp, content FROM messages
+                 WHERE content LIKE '%' || ?1 || '%'
+                 ORDER BY timestamp DESC LIMIT ?2",
+            )
+            .map_err(db_err)?;
+        let hits = statement
+            .query_map(rusqlite::params![query, limit as i64], |row| {
+                Ok(SearchHit {
+                    session_id: row.get(0)?,
+                    role: row.get(1)?,
+                    timestamp: row.get::<_, i64>(2)? as u64,
+                    snippet: row.get::<_, String>(3)?,
+                })
+            })
+            .map_err(db_err)?
+            .map(|hit| {
+                hit.map(|mut hit| {
+                    hit.snippet = excerpt_around(&hit.snippet, query, 160);
+                    hit
+                })
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        Ok(hits)
+    }
+
+    /// Import every legacy JSON conversation file from the directory,
+    /// skipping sessions already in the database. Files are left in
+    /// place, so the JSON backend keeps working if the user switches back.
+    pub fn import_json_dir(&self, dir: &Path) -> Result<usize> {
+        let mut imported = 0;
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Ok(0);
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(json) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(record) = serde_json::from_str::<SessionRecord>(&json) else {
+                tracing::warn!("Skipping unparseable conversation file {}", path.display());
+                continue;
+            };
+            let exists = self
+                .conn
+                .query_row(
+                    "SELECT 1 FROM sessions WHERE id = ?1",
+                    [&record.session_id],
+                    |_| Ok(()),
+                )
+                .is_ok();
+            if !exists {
+                self.save(&record)?;
+                imported += 1;
+            }
+        }
+        Ok(imported)
+    }
+
+    /// Run the JSON import a single time per database, tracked in the
+    /// meta table so later opens skip the directory scan
+    fn import_json_dir_once(&self, dir: &Path) -> Result<usize> {
+        let done = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'json_import_done'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .is_ok();
+        if done {
+            return Ok(0);
+        }
+        let imported = self.import_json_dir(dir)?;
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES ('json_import_done', '1')",
+                [],
+            )
+            .map_err(db_err)?;
+        if imported > 0 {
+            tracing::info!("Imported {} JSON conversation(s) into the SQLite session store", imported);
+        }
+        Ok(imported)
+    }
+}
+
+/// Search every saved conversation regardless of backend: the SQLite
+/// store's index when enabled, otherwise a linear scan of the JSON
+/// files. Both paths return the same hit shape so callers don't care.
+pub fn search_sessions(conversation_dir: &Path, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+    if sqlite_enabled() {
+        SqliteSessionStore::open_default(conversation_dir)?.search(query, limit)
+    } else {
+        search_json_dir(conversation_dir, query, limit)
+    }
+}
+
+/// Case-insensitive substring search across the per-file JSON
+/// conversations, newest matches first
+pub fn search_json_dir(dir: &Path, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+    let needle = query.to_lowercase();
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut hits = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(hits);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(record) = serde_json::from_str::<SessionRecord>(&json) else {
+            continue;
+        };
+        for message in &record.messages {
+            if message.content.to_lowercase().contains(&needle) {
+                hits.push(SearchHit {
+                    session_id: record.session_id.clone(),
+                    role: message.role.clone(),
+                    timestamp: message.timestamp,
+                    snippet: excerpt_around(&message.content, query, 160),
+                });
+            }
+        }
+    }
+
+    hits.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// Clip content to a window around the first (case-insensitive) match of
+/// the query, for the LIKE-based search fallback
+fn excerpt_around(content: &str, query: &str, max_len: usize) -> String {
+    let haystack = content.to_lowercase();
+    let needle = query.to_lowercase();
+    let start = haystack.find(&needle).unwrap_or(0);
+    let window_start = start.saturating_sub(max_len / 4);
+    // Snap to char boundaries
+    let mut begin = window_start;
+    while begin > 0 && !content.is_char_boundary(begin) {
+        begin -= 1;
+    }
+    let mut end = (begin + max_len).min(content.len());
+    while end < content.len() && !content.is_char_boundary(end) {
+        end += 1;
+    }
+    let mut excerpt = String::new();
+    if begin > 0 {
+        excerpt.push('…');
+    }
+    excerpt.push_str(&content[begin..end]);
+    if end < content.len() {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_record(id: &str) -> SessionRecord {
+        SessionRecord {
+            session_id: id.to_string(),
+            model: "claude-opus-4-5".to_string(),
+            messages: vec![
+                StoredMessage {
+                    role: "user".to_string(),
+                    content: "refactor the parser module".to_string(),
+                    timestamp: 1_000,
+                },
+                StoredMessage {
+                    role: "assistant".to_string(),
+                    content: "Done. The parser now returns spans.".to_string(),
+                    timestamp: 2_000,
+                },
+            ],
+            timestamp: 2_000,
+            tool_schema_version: 1,
+            input_tokens: 120,
+            output_tokens: 45,
+            metadata: HashMap::from([("cwd".to_string(), "/tmp/project".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteSessionStore::open(&dir.path().join("sessions.db")).unwrap();
+
+        store.save(&sample_record("abc")).unwrap();
+        let loaded = store.load("abc").unwrap().unwrap();
+        assert_eq!(loaded.model, "claude-opus-4-5");
+        assert_eq!(loaded.messages.len(), 2);
+        assert_eq!(loaded.messages[0].content, "refactor the parser module");
+        assert_eq!(loaded.input_tokens, 120);
+        assert_eq!(loaded.metadata.get("cwd").map(String::as_str), Some("/tmp/project"));
+
+        assert!(store.load("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resave_replaces_transcript() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteSessionStore::open(&dir.path().join("sessions.db")).unwrap();
+
+        let mut record = sample_record("abc");
+        store.save(&record).unwrap();
+        record.messages.push(StoredMessage {
+            role: "user".to_string(),
+            content: "also add tests".to_string(),
+            timestamp: 3_000,
+        });
+        store.save(&record).unwrap();
+
+        let loaded = store.load("abc").unwrap().unwrap();
+        assert_eq!(loaded.messages.len(), 3);
+
+        let list = store.list().unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].message_count, 3);
+    }
+
+    #[test]
+    fn test_search_finds_message_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteSessionStore::open(&dir.path().join("sessions.db")).unwrap();
+        store.save(&sample_record("abc")).unwrap();
+
+        let hits = store.search("parser", 10).unwrap();
+        assert!(!hits.is_empty());
+        assert_eq!(hits[0].session_id, "abc");
+        assert!(hits[0].snippet.to_lowercase().contains("parser"));
+
+        assert!(store.search("nonexistentterm", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let mut record = sample_record("abc");
+        record.messages.push(StoredMessage {
+            role: "command_output".to_string(),
+            content: "diff --git a/x b/x\n<tag> & \"quote\"".to_string(),
+            timestamp: 3_000,
+        });
+
+        let md = export_session(&record, ExportFormat::Markdown).unwrap();
+        assert!(md.starts_with("# Conversation abc"));
+        assert!(md.contains("## User"));
+        assert!(md.contains("## Tool output"));
+        assert!(md.contains("````\ndiff --git"));
+        assert!(md.contains("**Tokens**: 120 in / 45 out"));
+
+        let html = export_session(&record, ExportFormat::Html).unwrap();
+        assert!(html.contains("<!DOCTYPE html>"));
+        // Tool output is collapsed and escaped
+        assert!(html.contains("<details"));
+        assert!(html.contains("&lt;tag&gt; &amp; &quot;quote&quot;"));
+        assert!(!html.contains("<tag>"));
+
+        let jsonl = export_session(&record, ExportFormat::Jsonl).unwrap();
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 1 + record.messages.len());
+        let meta: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(meta["type"], "session");
+        let first: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(first["role"], "user");
+
+        assert!("md".parse::<ExportFormat>().is_ok());
+        assert!("bogus".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_exports_include_annotations() {
+        let mut record = sample_record("abc");
+        let annotations = HashMap::from([(
+            1usize,
+            MessageAnnotation {
+                thumbs_up: false,
+                note: Some("claimed done but the <tests> still fail".to_string()),
+            },
+        )]);
+        record.metadata.insert(
+            "annotations".to_string(),
+            serde_json::to_string(&annotations).unwrap(),
+        );
+
+        let md = export_session(&record, ExportFormat::Markdown).unwrap();
+        assert!(md.contains("> **Feedback**: 👎 — claimed done but the <tests> still fail"));
+
+        let html = export_session(&record, ExportFormat::Html).unwrap();
+        assert!(html.contains("👎 Feedback — claimed done but the &lt;tests&gt; still fail"));
+
+        let jsonl = export_session(&record, ExportFormat::Jsonl).unwrap();
+        let lines: Vec<&str> = jsonl.lines().collect();
+        let annotated: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(annotated["annotation"]["thumbs_up"], false);
+        // Unannotated messages don't carry the field
+        let plain: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert!(plain.get("annotation").is_none());
+    }
+
+    #[test]
+    fn test_search_json_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = sample_record("abc");
+        std::fs::write(
+            dir.path().join("abc.json"),
+            serde_json::to_string(&record).unwrap(),
+        )
+        .unwrap();
+
+        let hits = search_json_dir(dir.path(), "Parser", 10).unwrap();
+        assert_eq!(hits.len(), 2);
+        // Newest match first
+        assert_eq!(hits[0].role, "assistant");
+        assert!(hits[0].snippet.contains("parser"));
+
+        assert!(search_json_dir(dir.path(), "nonexistentterm", 10).unwrap().is_empty());
+        assert!(search_json_dir(dir.path(), "", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_json_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        // A legacy file without usage/metadata fields parses via defaults
+        std::fs::write(
+            dir.path().join("legacy.json"),
+            r#"{"session_id":"legacy","model":"claude-sonnet-4-5","messages":[{"role":"user","content":"hello from json","timestamp":500}],"timestamp":500}"#,
+        )
+        .unwrap();
+
+        let store = SqliteSessionStore::open_default(dir.path()).unwrap();
+        let loaded = store.load("legacy").unwrap().unwrap();
+        assert_eq!(loaded.messages[0].content, "hello from json");
+        assert_eq!(loaded.input_tokens, 0);
+
+        // A second open skips the import without duplicating anything
+        drop(store);
+        let store = SqliteSessionStore::open_default(dir.path()).unwrap();
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,372 @@
+//! Org-shareable settings bundles
+//!
+//! `llminate settings export --bundle team.json` packages the shareable
+//! parts of a project's configuration — permission rules, hooks, MCP
+//! servers, the output style, and custom command files — into a single
+//! versioned JSON file teams can check into a repo or distribute.
+//! `llminate settings import --bundle team.json` applies one
+//! conflict-aware: existing rules, servers, settings, and files are left
+//! alone unless `--force` is given, and every conflict is reported.
+
+use crate::config::{self, ConfigScope, McpServerConfig, PermissionsConfig, SettingsSource};
+use crate::error::{Error, Result};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Current bundle schema version. Import refuses bundles from a newer
+/// schema rather than silently dropping fields it doesn't understand.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// A versioned, shareable snapshot of team-relevant configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub version: u32,
+
+    /// Permission rules (allow/deny/additionalDirectories/disallowedTools)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<PermissionsConfig>,
+
+    /// Raw hooks configuration from settings.json, passed through untouched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<Value>,
+
+    /// The outputStyle setting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_style: Option<Value>,
+
+    /// MCP server definitions keyed by server name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<HashMap<String, McpServerConfig>>,
+
+    /// Custom command files from .claude/commands, keyed by file name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commands: Option<HashMap<String, String>>,
+}
+
+/// Export the current project configuration into a bundle file
+pub fn export_bundle(bundle_path: &Path) -> Result<()> {
+    // Project settings are the shareable layer; fall back to user
+    // settings when a project has none of its own
+    let settings = config::load_settings(SettingsSource::Project)
+        .or_else(|_| config::load_settings(SettingsSource::User))
+        .unwrap_or_default();
+
+    let permissions = if settings.permissions.allow.is_empty()
+        && settings.permissions.deny.is_empty()
+        && settings.permissions.additional_directories.is_empty()
+        && settings.permissions.disallowed_tools.is_empty()
+    {
+        None
+    } else {
+        Some(settings.permissions.clone())
+    };
+
+    let mcp_servers = config::get_all_mcp_servers()?;
+    let mcp_servers = if mcp_servers.is_empty() {
+        None
+    } else {
+        Some(mcp_servers)
+    };
+
+    let commands = read_command_files()?;
+    let commands = if commands.is_empty() {
+        None
+    } else {
+        Some(commands)
+    };
+
+    let bundle = SettingsBundle {
+        version: BUNDLE_VERSION,
+        permissions,
+        hooks: settings.extra.get("hooks").cloned(),
+        output_style: settings.extra.get("outputStyle").cloned(),
+        mcp_servers,
+        commands,
+    };
+
+    let json = serde_json::to_string_pretty(&bundle)
+        .context("Failed to serialize settings bundle")?;
+    std::fs::write(bundle_path, json)
+        .with_context(|| format!("Failed to write bundle to {}", bundle_path.display()))?;
+
+    println!("Exported settings bundle to {}", bundle_path.display());
+    print_bundle_summary(&bundle);
+
+    // Server configs may carry credentials in env vars or headers; make
+    // sure the user reviews those before sharing the file
+    if let Some(servers) = &bundle.mcp_servers {
+        let sensitive: Vec<&str> = servers
+            .iter()
+            .filter(|(_, cfg)| cfg.env.is_some() || cfg.headers.is_some())
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if !sensitive.is_empty() {
+            println!(
+                "Warning: server(s) {} include env vars or headers — review the bundle for secrets before sharing.",
+                sensitive.join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Import a bundle file, skipping anything that conflicts with existing
+/// configuration unless `force` is set
+pub fn import_bundle(bundle_path: &Path, force: bool) -> Result<()> {
+    let content = std::fs::read_to_string(bundle_path)
+        .with_context(|| format!("Failed to read bundle from {}", bundle_path.display()))?;
+    let bundle: SettingsBundle =
+        serde_json::from_str(&content).context("Invalid settings bundle file")?;
+
+    if bundle.version > BUNDLE_VERSION {
+        return Err(Error::Config(format!(
+            "Bundle version {} is newer than this build supports (max {}); update llminate first",
+            bundle.version, BUNDLE_VERSION
+        )));
+    }
+
+    // Permission rules and settings land in the shared project settings
+    let mut settings =
+        config::load_settings(SettingsSource::Project).unwrap_or_default();
+    let mut settings_changed = false;
+
+    if let Some(permissions) = &bundle.permissions {
+        let (added, skipped) = merge_permissions(&mut settings.permissions, permissions);
+        if added > 0 {
+            settings_changed = true;
+        }
+        println!(
+            "Permissions: {} rule{} added, {} already present",
+            added,
+            if added == 1 { "" } else { "s" },
+            skipped
+        );
+    }
+
+    for (key, value) in [
+        ("hooks", bundle.hooks.as_ref()),
+        ("outputStyle", bundle.output_style.as_ref()),
+    ] {
+        let Some(value) = value else { continue };
+        if settings.extra.contains_key(key) && !force {
+            println!("{}: already configured, skipped (use --force to overwrite)", key);
+        } else {
+            settings.extra.insert(key.to_string(), value.clone());
+            settings_changed = true;
+            println!("{}: imported", key);
+        }
+    }
+
+    if settings_changed {
+        config::save_settings(SettingsSource::Project, &settings)?;
+    }
+
+    // MCP servers land in the project config scope
+    if let Some(bundle_servers) = &bundle.mcp_servers {
+        let mut project_config = config::load_config(ConfigScope::Project)?;
+        let servers = project_config.mcp_servers.get_or_insert_with(HashMap::new);
+        let existing = config::get_all_mcp_servers()?;
+
+        let mut added = 0;
+        for (name, server) in bundle_servers {
+            if existing.contains_key(name) && !force {
+                println!(
+                    "MCP server '{}': already configured, skipped (use --force to overwrite)",
+                    name
+                );
+            } else {
+                servers.insert(name.clone(), server.clone());
+                added += 1;
+            }
+        }
+        if added > 0 {
+            config::save_config(ConfigScope::Project, &project_config)?;
+            println!(
+                "MCP servers: {} added to project config",
+                added
+            );
+        }
+    }
+
+    // Command files land in .claude/commands
+    if let Some(commands) = &bundle.commands {
+        let commands_dir = std::env::current_dir()?.join(".claude").join("commands");
+        let mut written = 0;
+        for (file_name, body) in commands {
+            // Bundle keys are plain file names; anything path-like is
+            // hostile or corrupt
+            if file_name.contains('/') || file_name.contains('\\') || file_name.contains("..") {
+                println!("Command '{}': invalid file name, skipped", file_name);
+                continue;
+            }
+            let target = commands_dir.join(file_name);
+            if target.exists() && !force {
+                println!(
+                    "Command '{}': file exists, skipped (use --force to overwrite)",
+                    file_name
+                );
+                continue;
+            }
+            std::fs::create_dir_all(&commands_dir)
+                .context("Failed to create .claude/commands directory")?;
+            std::fs::write(&target, body)
+                .with_context(|| format!("Failed to write {}", target.display()))?;
+            written += 1;
+        }
+        if written > 0 {
+            println!("Commands: {} file{} written", written, if written == 1 { "" } else { "s" });
+        }
+    }
+
+    println!("Imported settings bundle from {}", bundle_path.display());
+    Ok(())
+}
+
+/// Merge bundle permissions into existing ones, returning how many
+/// entries were added and how many were already present
+fn merge_permissions(
+    existing: &mut PermissionsConfig,
+    incoming: &PermissionsConfig,
+) -> (usize, usize) {
+    let mut added = 0;
+    let mut skipped = 0;
+    for (target, source) in [
+        (&mut existing.allow, &incoming.allow),
+        (&mut existing.deny, &incoming.deny),
+        (&mut existing.additional_directories, &incoming.additional_directories),
+        (&mut existing.disallowed_tools, &incoming.disallowed_tools),
+    ] {
+        let (a, s) = merge_string_list(target, source);
+        added += a;
+        skipped += s;
+    }
+    (added, skipped)
+}
+
+/// Append entries not already in the list; returns (added, skipped)
+fn merge_string_list(existing: &mut Vec<String>, incoming: &[String]) -> (usize, usize) {
+    let mut added = 0;
+    let mut skipped = 0;
+    for entry in incoming {
+        if existing.iter().any(|e| e == entry) {
+            skipped += 1;
+        } else {
+            existing.push(entry.clone());
+            added += 1;
+        }
+    }
+    (added, skipped)
+}
+
+/// Read custom command files from .claude/commands, keyed by file name
+fn read_command_files() -> Result<HashMap<String, String>> {
+    let mut commands = HashMap::new();
+    let commands_dir = std::env::current_dir()?.join(".claude").join("commands");
+    if let Ok(entries) = std::fs::read_dir(&commands_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if let Ok(body) = std::fs::read_to_string(&path) {
+                commands.insert(file_name.to_string(), body);
+            }
+        }
+    }
+    Ok(commands)
+}
+
+/// Print what a bundle contains, one line per section
+fn print_bundle_summary(bundle: &SettingsBundle) {
+    let permission_rules = bundle
+        .permissions
+        .as_ref()
+        .map(|p| {
+            p.allow.len() + p.deny.len() + p.additional_directories.len() + p.disallowed_tools.len()
+        })
+        .unwrap_or(0);
+    println!("  Permission rules: {}", permission_rules);
+    println!("  Hooks: {}", if bundle.hooks.is_some() { "yes" } else { "no" });
+    println!(
+        "  Output style: {}",
+        if bundle.output_style.is_some() { "yes" } else { "no" }
+    );
+    println!(
+        "  MCP servers: {}",
+        bundle.mcp_servers.as_ref().map(|s| s.len()).unwrap_or(0)
+    );
+    println!(
+        "  Commands: {}",
+        bundle.commands.as_ref().map(|c| c.len()).unwrap_or(0)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_string_list_skips_duplicates() {
+        let mut existing = vec!["Bash(npm:*)".to_string()];
+        let incoming = vec!["Bash(npm:*)".to_string(), "Read".to_string()];
+        let (added, skipped) = merge_string_list(&mut existing, &incoming);
+        assert_eq!(added, 1);
+        assert_eq!(skipped, 1);
+        assert_eq!(existing, vec!["Bash(npm:*)".to_string(), "Read".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_permissions_counts_all_sections() {
+        let mut existing = PermissionsConfig::default();
+        let incoming = PermissionsConfig {
+            additional_directories: vec!["/srv/app".to_string()],
+            allow: vec!["Bash(git:*)".to_string()],
+            deny: vec!["Bash(curl:*)".to_string()],
+            disallowed_tools: vec![],
+        };
+        let (added, skipped) = merge_permissions(&mut existing, &incoming);
+        assert_eq!(added, 3);
+        assert_eq!(skipped, 0);
+        assert_eq!(existing.allow, vec!["Bash(git:*)".to_string()]);
+    }
+
+    #[test]
+    fn test_bundle_round_trips_and_rejects_newer_versions() {
+        let bundle = SettingsBundle {
+            version: BUNDLE_VERSION,
+            permissions: Some(PermissionsConfig {
+                allow: vec!["Bash(cargo:*)".to_string()],
+                ..Default::default()
+            }),
+            hooks: None,
+            output_style: Some(serde_json::json!("concise")),
+            mcp_servers: None,
+            commands: Some(HashMap::from([(
+                "review.md".to_string(),
+                "Review the diff".to_string(),
+            )])),
+        };
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        let parsed: SettingsBundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.version, BUNDLE_VERSION);
+        assert_eq!(
+            parsed.permissions.unwrap().allow,
+            vec!["Bash(cargo:*)".to_string()]
+        );
+        assert_eq!(parsed.output_style, Some(serde_json::json!("concise")));
+
+        // A newer schema version must be refused on import
+        let newer = serde_json::json!({"version": BUNDLE_VERSION + 1});
+        let parsed: SettingsBundle = serde_json::from_value(newer).unwrap();
+        assert!(parsed.version > BUNDLE_VERSION);
+    }
+}
@@ -72,11 +72,11 @@ impl TelemetryClient {
 }
 
 async fn send_batch(events: &[TelemetryEvent]) {
-    // Only send telemetry if explicitly enabled
-    if std::env::var("LLMINATE_TELEMETRY_DISABLED").is_ok() {
+    // Consent gate: nothing leaves the process without an explicit opt-in
+    if !is_enabled() {
         return;
     }
-    
+
     // In production, this would send to a telemetry endpoint
     if cfg!(debug_assertions) {
         tracing::debug!("Would send telemetry batch: {} events", events.len());
@@ -105,8 +105,14 @@ pub async fn init() {
 
 /// Track an event
 pub async fn track<T: Serialize>(event_name: impl Into<String>, properties: T) {
+    // Drop events at the source when the user has not opted in, rather
+    // than collecting them and filtering at send time
+    if !is_enabled() {
+        return;
+    }
+
     let client = TELEMETRY_CLIENT.clone();
-    
+
     let mut props = HashMap::new();
     if let Ok(value) = serde_json::to_value(properties) {
         if let Value::Object(map) = value {
@@ -274,4 +280,130 @@ pub async fn track_session_end(
 /// Get current session ID
 pub fn get_session_id() -> &'static str {
     &SESSION_ID
+}
+
+// ===== CONSENT AND TRANSPARENCY =====
+
+/// Config key holding the user's telemetry decision
+const CONSENT_KEY: &str = "telemetryConsent";
+
+/// The user's recorded telemetry decision.
+///
+/// `None` means they have never been asked, which is treated as disabled.
+pub fn consent_status() -> Option<bool> {
+    let config = crate::config::load_config(crate::config::ConfigScope::User).ok()?;
+    config.extra.get(CONSENT_KEY).and_then(|v| v.as_bool())
+}
+
+/// Persist the telemetry decision to the user config
+pub fn set_consent(enabled: bool) -> Result<()> {
+    let mut config = crate::config::load_config(crate::config::ConfigScope::User)
+        .map_err(|e| crate::error::Error::Config(format!("Failed to load config: {}", e)))?;
+    config
+        .extra
+        .insert(CONSENT_KEY.to_string(), Value::Bool(enabled));
+    crate::config::save_config(crate::config::ConfigScope::User, &config)
+        .map_err(|e| crate::error::Error::Config(format!("Failed to save config: {}", e)))?;
+    Ok(())
+}
+
+/// Whether telemetry may be collected at all.
+///
+/// Requires an explicit opt-in; `LLMINATE_TELEMETRY_DISABLED` always wins.
+pub fn is_enabled() -> bool {
+    if std::env::var("LLMINATE_TELEMETRY_DISABLED").is_ok() {
+        return false;
+    }
+    if policy_disables_telemetry() {
+        return false;
+    }
+    consent_status() == Some(true)
+}
+
+/// Whether the managed enterprise policy file forbids telemetry.
+/// Beats user consent; user/project settings cannot override it.
+fn policy_disables_telemetry() -> bool {
+    crate::config::load_settings(crate::config::SettingsSource::Managed)
+        .ok()
+        .and_then(|settings| settings.telemetry)
+        == Some(false)
+}
+
+/// First-run consent prompt.
+///
+/// Asks once on an interactive terminal if no decision has been recorded;
+/// the answer is persisted so the prompt never repeats. Non-interactive
+/// runs are left unasked (and therefore disabled).
+pub fn maybe_prompt_consent() -> Result<()> {
+    if consent_status().is_some() || !crate::utils::is_tty() {
+        return Ok(());
+    }
+
+    use std::io::{self, Write};
+    println!("llminate can collect anonymous usage telemetry (event names, timings,");
+    println!("success/failure flags — never prompts, file contents, or paths).");
+    println!("Run `llminate telemetry show` at any time to see exactly what is collected.");
+    print!("Enable telemetry? [y/N] ");
+    io::stdout()
+        .flush()
+        .map_err(|e| crate::error::Error::Other(format!("Failed to flush stdout: {}", e)))?;
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| crate::error::Error::Other(format!("Failed to read input: {}", e)))?;
+    let enabled = matches!(input.trim().to_lowercase().as_str(), "y" | "yes");
+    set_consent(enabled)?;
+    println!(
+        "Telemetry {}. Change this with `llminate telemetry on|off`.",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    println!();
+    Ok(())
+}
+
+/// Human-readable description of everything the telemetry subsystem
+/// collects and where it goes (`llminate telemetry show`)
+pub fn transparency_report() -> String {
+    let status = if std::env::var("LLMINATE_TELEMETRY_DISABLED").is_ok() {
+        "disabled (LLMINATE_TELEMETRY_DISABLED is set)"
+    } else if policy_disables_telemetry() {
+        "disabled (by managed enterprise policy)"
+    } else {
+        match consent_status() {
+            Some(true) => "enabled",
+            Some(false) => "disabled",
+            None => "disabled (never opted in)",
+        }
+    };
+
+    format!(
+        "Telemetry status: {}\n\
+         \n\
+         Events and fields collected when enabled:\n\
+         \n\
+         session_start   version, platform (os), arch\n\
+         session_end     total_messages, total_duration_ms, total_tokens_input,\n\
+         \x20               total_tokens_output, total_cost_usd, average_response_time_ms\n\
+         api_call        endpoint, method, status_code, duration_ms, success\n\
+         tool_use        tool_name, success, duration_ms\n\
+         error           error_type, message\n\
+         feature_use     feature_name, action\n\
+         \n\
+         Every event also carries: a random per-run session id, a millisecond\n\
+         timestamp, and the user id from your config if one is set.\n\
+         \n\
+         Never collected: prompts, model responses, file contents, file paths,\n\
+         or command text.\n\
+         \n\
+         Destination: events are batched in memory and, in this build, only\n\
+         written to the debug log — no network telemetry endpoint is\n\
+         configured. Nothing is sent anywhere without the opt-in above.\n\
+         \n\
+         Controls:\n\
+         \x20 llminate telemetry on    enable collection\n\
+         \x20 llminate telemetry off   disable all sinks\n\
+         \x20 LLMINATE_TELEMETRY_DISABLED=1  hard override, beats any config\n",
+        status
+    )
 }
\ No newline at end of file
@@ -0,0 +1,637 @@
+//! Generic modal dialog toolkit.
+//!
+//! Shared building blocks for overlay dialogs (list select, multi-select,
+//! confirm, text input, text area) with consistent keybindings and theming,
+//! so new features stop reimplementing modal handling: ↑/↓ (or j/k)
+//! navigate, Space toggles in multi-select, Enter confirms, Esc cancels.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::collections::HashSet;
+
+/// Accent color shared by all dialogs
+const DIALOG_ACCENT: Color = Color::Cyan;
+
+/// What a key press did to a dialog
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialogAction {
+    /// Key consumed, dialog still open
+    None,
+    /// An item was chosen (index into the item list)
+    Submitted(usize),
+    /// Multi-select confirmed with the marked item indices
+    SubmittedMany(Vec<usize>),
+    /// Text input confirmed
+    SubmittedText(String),
+    /// Dialog dismissed with Esc
+    Cancelled,
+}
+
+/// One entry in a select dialog
+#[derive(Debug, Clone)]
+pub struct SelectItem {
+    /// Main label shown in the list
+    pub label: String,
+    /// Optional dimmed description rendered under the label
+    pub description: Option<String>,
+    /// Optional marker appended to the label (e.g. "(current)")
+    pub marker: Option<String>,
+}
+
+impl SelectItem {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            description: None,
+            marker: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_marker(mut self, marker: impl Into<String>) -> Self {
+        self.marker = Some(marker.into());
+        self
+    }
+}
+
+/// Centered popup helper shared by the dialogs
+fn centered_popup(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let width = (area.width * percent_x / 100).min(area.width);
+    let height = (area.height * percent_y / 100).min(area.height);
+    Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    }
+}
+
+/// List selection dialog (single or multi-select)
+#[derive(Debug, Clone)]
+pub struct SelectDialog {
+    title: String,
+    prompt: Option<String>,
+    items: Vec<SelectItem>,
+    selected: usize,
+    scroll_offset: usize,
+    multi: bool,
+    marked: HashSet<usize>,
+}
+
+impl SelectDialog {
+    pub fn new(title: impl Into<String>, items: Vec<SelectItem>) -> Self {
+        Self {
+            title: title.into(),
+            prompt: None,
+            items,
+            selected: 0,
+            scroll_offset: 0,
+            multi: false,
+            marked: HashSet::new(),
+        }
+    }
+
+    /// Add a dimmed prompt line above the list
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
+    /// Enable multi-select (Space toggles, Enter confirms the marked set)
+    pub fn multi_select(mut self) -> Self {
+        self.multi = true;
+        self
+    }
+
+    /// Pre-select an item by index
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.selected = index.min(self.items.len().saturating_sub(1));
+        self
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> DialogAction {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                DialogAction::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.items.len() {
+                    self.selected += 1;
+                }
+                DialogAction::None
+            }
+            KeyCode::PageUp => {
+                self.selected = self.selected.saturating_sub(10);
+                DialogAction::None
+            }
+            KeyCode::PageDown => {
+                self.selected = (self.selected + 10).min(self.items.len().saturating_sub(1));
+                DialogAction::None
+            }
+            KeyCode::Char(' ') if self.multi => {
+                if !self.marked.remove(&self.selected) {
+                    self.marked.insert(self.selected);
+                }
+                DialogAction::None
+            }
+            KeyCode::Enter => {
+                if self.multi {
+                    let mut indices: Vec<usize> = self.marked.iter().copied().collect();
+                    indices.sort_unstable();
+                    DialogAction::SubmittedMany(indices)
+                } else if self.items.is_empty() {
+                    DialogAction::Cancelled
+                } else {
+                    DialogAction::Submitted(self.selected)
+                }
+            }
+            KeyCode::Esc => DialogAction::Cancelled,
+            _ => DialogAction::None,
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let popup = centered_popup(area, 60, 60);
+        f.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .borders(Borders::ALL)
+            .style(Style::default().fg(DIALOG_ACCENT));
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+
+        let mut lines = Vec::new();
+        if let Some(prompt) = &self.prompt {
+            lines.push(Line::from(Span::styled(
+                prompt.clone(),
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+            lines.push(Line::from(""));
+        }
+
+        // Each item takes 1-2 lines; keep the selection in view by item index
+        let header_lines = lines.len();
+        let lines_per_item = if self.items.iter().any(|i| i.description.is_some()) { 2 } else { 1 };
+        let visible_items =
+            ((inner.height as usize).saturating_sub(header_lines + 2) / lines_per_item).max(1);
+        if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if self.selected >= self.scroll_offset + visible_items {
+            self.scroll_offset = self.selected + 1 - visible_items;
+        }
+
+        for (i, item) in self
+            .items
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(visible_items)
+        {
+            let is_selected = i == self.selected;
+            let prefix = if is_selected { "❯ " } else { "  " };
+            let mark = if self.multi {
+                if self.marked.contains(&i) { "[x] " } else { "[ ] " }
+            } else {
+                ""
+            };
+            let style = if is_selected {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+
+            let mut spans = vec![
+                Span::styled(format!("{}{}", prefix, mark), style),
+                Span::styled(item.label.clone(), style.add_modifier(Modifier::BOLD)),
+            ];
+            if let Some(marker) = &item.marker {
+                spans.push(Span::styled(
+                    format!(" {}", marker),
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            lines.push(Line::from(spans));
+
+            if let Some(description) = &item.description {
+                let desc_style = style.add_modifier(Modifier::DIM);
+                lines.push(Line::from(vec![
+                    Span::styled("    ", desc_style),
+                    Span::styled(description.clone(), desc_style),
+                ]));
+            }
+        }
+
+        lines.push(Line::from(""));
+        let hints = if self.multi {
+            "↑/↓ navigate, Space toggle, Enter confirm, Esc cancel"
+        } else {
+            "↑/↓ navigate, Enter confirm, Esc cancel"
+        };
+        lines.push(Line::from(Span::styled(
+            hints,
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+/// Yes/no confirmation dialog with stateful key handling
+#[derive(Debug, Clone)]
+pub struct ConfirmDialogState {
+    title: String,
+    message: String,
+    yes_selected: bool,
+}
+
+impl ConfirmDialogState {
+    pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            yes_selected: false,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> DialogAction {
+        match key.code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+                self.yes_selected = !self.yes_selected;
+                DialogAction::None
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y') => DialogAction::Submitted(0),
+            KeyCode::Char('n') | KeyCode::Char('N') => DialogAction::Cancelled,
+            KeyCode::Enter => {
+                if self.yes_selected {
+                    DialogAction::Submitted(0)
+                } else {
+                    DialogAction::Cancelled
+                }
+            }
+            KeyCode::Esc => DialogAction::Cancelled,
+            _ => DialogAction::None,
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let popup = centered_popup(area, 50, 25);
+        f.render_widget(Clear, popup);
+        // Reuse the existing ConfirmDialog widget for the actual drawing
+        let widget = super::ConfirmDialog::new(self.title.clone(), self.message.clone())
+            .with_selection(self.yes_selected);
+        f.render_widget(widget, popup);
+    }
+}
+
+/// Single-line text input dialog
+#[derive(Debug, Clone)]
+pub struct TextInputDialog {
+    title: String,
+    prompt: Option<String>,
+    value: String,
+    cursor: usize,
+}
+
+impl TextInputDialog {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            prompt: None,
+            value: String::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.value = value.into();
+        self.cursor = self.value.chars().count();
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> DialogAction {
+        match key.code {
+            KeyCode::Char(c) => {
+                let idx = self.byte_index();
+                self.value.insert(idx, c);
+                self.cursor += 1;
+                DialogAction::None
+            }
+            KeyCode::Backspace => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                    let idx = self.byte_index();
+                    self.value.remove(idx);
+                }
+                DialogAction::None
+            }
+            KeyCode::Delete => {
+                if self.cursor < self.value.chars().count() {
+                    let idx = self.byte_index();
+                    self.value.remove(idx);
+                }
+                DialogAction::None
+            }
+            KeyCode::Left => {
+                self.cursor = self.cursor.saturating_sub(1);
+                DialogAction::None
+            }
+            KeyCode::Right => {
+                self.cursor = (self.cursor + 1).min(self.value.chars().count());
+                DialogAction::None
+            }
+            KeyCode::Home => {
+                self.cursor = 0;
+                DialogAction::None
+            }
+            KeyCode::End => {
+                self.cursor = self.value.chars().count();
+                DialogAction::None
+            }
+            KeyCode::Enter => DialogAction::SubmittedText(self.value.clone()),
+            KeyCode::Esc => DialogAction::Cancelled,
+            _ => DialogAction::None,
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let popup = centered_popup(area, 50, 20);
+        f.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .borders(Borders::ALL)
+            .style(Style::default().fg(DIALOG_ACCENT));
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+
+        let mut lines = Vec::new();
+        if let Some(prompt) = &self.prompt {
+            lines.push(Line::from(Span::styled(
+                prompt.clone(),
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+            lines.push(Line::from(""));
+        }
+
+        // Input line with a block cursor
+        let before: String = self.value.chars().take(self.cursor).collect();
+        let at: String = self.value.chars().skip(self.cursor).take(1).collect();
+        let after: String = self.value.chars().skip(self.cursor + 1).collect();
+        lines.push(Line::from(vec![
+            Span::raw("> "),
+            Span::raw(before),
+            Span::styled(
+                if at.is_empty() { " ".to_string() } else { at },
+                Style::default().add_modifier(Modifier::REVERSED),
+            ),
+            Span::raw(after),
+        ]));
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Enter confirm, Esc cancel",
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+/// Multi-line text editor dialog for documents like plans: Enter inserts
+/// a newline, Ctrl+S confirms the edited text, Esc cancels
+#[derive(Debug, Clone)]
+pub struct TextAreaDialog {
+    title: String,
+    prompt: Option<String>,
+    lines: Vec<String>,
+    cursor_row: usize,
+    cursor_col: usize,
+    scroll_offset: usize,
+}
+
+impl TextAreaDialog {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            prompt: None,
+            lines: vec![String::new()],
+            cursor_row: 0,
+            cursor_col: 0,
+            scroll_offset: 0,
+        }
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
+    /// Replace the title while the dialog is open (e.g. to reflect a
+    /// toggled state)
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    /// Seed the editor with existing text, cursor at the start
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        let text = text.into();
+        self.lines = text.split('\n').map(|l| l.to_string()).collect();
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.cursor_row = 0;
+        self.cursor_col = 0;
+        self
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn current_line_len(&self) -> usize {
+        self.lines[self.cursor_row].chars().count()
+    }
+
+    fn byte_index(line: &str, col: usize) -> usize {
+        line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> DialogAction {
+        match key.code {
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return DialogAction::SubmittedText(self.text());
+            }
+            KeyCode::Char(c) => {
+                let idx = Self::byte_index(&self.lines[self.cursor_row], self.cursor_col);
+                self.lines[self.cursor_row].insert(idx, c);
+                self.cursor_col += 1;
+            }
+            KeyCode::Enter => {
+                let idx = Self::byte_index(&self.lines[self.cursor_row], self.cursor_col);
+                let rest = self.lines[self.cursor_row].split_off(idx);
+                self.lines.insert(self.cursor_row + 1, rest);
+                self.cursor_row += 1;
+                self.cursor_col = 0;
+            }
+            KeyCode::Backspace => {
+                if self.cursor_col > 0 {
+                    self.cursor_col -= 1;
+                    let idx = Self::byte_index(&self.lines[self.cursor_row], self.cursor_col);
+                    self.lines[self.cursor_row].remove(idx);
+                } else if self.cursor_row > 0 {
+                    // Join with the previous line
+                    let line = self.lines.remove(self.cursor_row);
+                    self.cursor_row -= 1;
+                    self.cursor_col = self.current_line_len();
+                    self.lines[self.cursor_row].push_str(&line);
+                }
+            }
+            KeyCode::Delete => {
+                if self.cursor_col < self.current_line_len() {
+                    let idx = Self::byte_index(&self.lines[self.cursor_row], self.cursor_col);
+                    self.lines[self.cursor_row].remove(idx);
+                } else if self.cursor_row + 1 < self.lines.len() {
+                    let line = self.lines.remove(self.cursor_row + 1);
+                    self.lines[self.cursor_row].push_str(&line);
+                }
+            }
+            KeyCode::Up => {
+                if self.cursor_row > 0 {
+                    self.cursor_row -= 1;
+                    self.cursor_col = self.cursor_col.min(self.current_line_len());
+                }
+            }
+            KeyCode::Down => {
+                if self.cursor_row + 1 < self.lines.len() {
+                    self.cursor_row += 1;
+                    self.cursor_col = self.cursor_col.min(self.current_line_len());
+                }
+            }
+            KeyCode::Left => {
+                if self.cursor_col > 0 {
+                    self.cursor_col -= 1;
+                } else if self.cursor_row > 0 {
+                    self.cursor_row -= 1;
+                    self.cursor_col = self.current_line_len();
+                }
+            }
+            KeyCode::Right => {
+                if self.cursor_col < self.current_line_len() {
+                    self.cursor_col += 1;
+                } else if self.cursor_row + 1 < self.lines.len() {
+                    self.cursor_row += 1;
+                    self.cursor_col = 0;
+                }
+            }
+            KeyCode::Home => self.cursor_col = 0,
+            KeyCode::End => self.cursor_col = self.current_line_len(),
+            KeyCode::PageUp => {
+                self.cursor_row = self.cursor_row.saturating_sub(10);
+                self.cursor_col = self.cursor_col.min(self.current_line_len());
+            }
+            KeyCode::PageDown => {
+                self.cursor_row = (self.cursor_row + 10).min(self.lines.len() - 1);
+                self.cursor_col = self.cursor_col.min(self.current_line_len());
+            }
+            KeyCode::Esc => return DialogAction::Cancelled,
+            _ => {}
+        }
+        DialogAction::None
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let popup = centered_popup(area, 80, 80);
+        f.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .borders(Borders::ALL)
+            .style(Style::default().fg(DIALOG_ACCENT));
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+
+        let mut lines = Vec::new();
+        if let Some(prompt) = &self.prompt {
+            lines.push(Line::from(Span::styled(
+                prompt.clone(),
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+            lines.push(Line::from(""));
+        }
+
+        // Keep the cursor row in view
+        let header_lines = lines.len();
+        let visible_rows = (inner.height as usize).saturating_sub(header_lines + 2).max(1);
+        if self.cursor_row < self.scroll_offset {
+            self.scroll_offset = self.cursor_row;
+        } else if self.cursor_row >= self.scroll_offset + visible_rows {
+            self.scroll_offset = self.cursor_row + 1 - visible_rows;
+        }
+
+        for (row, line) in self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(visible_rows)
+        {
+            if row == self.cursor_row {
+                // Line with a block cursor
+                let before: String = line.chars().take(self.cursor_col).collect();
+                let at: String = line.chars().skip(self.cursor_col).take(1).collect();
+                let after: String = line.chars().skip(self.cursor_col + 1).collect();
+                lines.push(Line::from(vec![
+                    Span::raw(before),
+                    Span::styled(
+                        if at.is_empty() { " ".to_string() } else { at },
+                        Style::default().add_modifier(Modifier::REVERSED),
+                    ),
+                    Span::raw(after),
+                ]));
+            } else {
+                lines.push(Line::from(Span::raw(line.clone())));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Edit freely — Enter newline, Ctrl+S approve, Esc reject",
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+}
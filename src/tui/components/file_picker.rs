@@ -0,0 +1,353 @@
+//! Keyboard-navigable file tree picker component.
+//!
+//! Reusable overlay for choosing files or directories from the workspace,
+//! used by `/add-dir` (directory mode), the `@`-mention fallback, and the
+//! commit workflow's file staging UI (multi-select mode). The tree is
+//! gitignore-aware: entries matched by the root `.gitignore` (and `.git`
+//! itself) are hidden.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What a key press did to the picker
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilePickerAction {
+    /// Key consumed, picker still open
+    None,
+    /// A single entry was chosen with Enter
+    Selected(PathBuf),
+    /// Multi-select mode confirmed with Enter (entries toggled with Space)
+    SelectedMany(Vec<PathBuf>),
+    /// Picker dismissed with Esc
+    Cancelled,
+}
+
+/// A single visible node in the flattened tree view
+#[derive(Debug, Clone)]
+struct FileNode {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    depth: usize,
+}
+
+/// Simple gitignore matcher built from the root .gitignore.
+/// Supports comments, anchored patterns, directory patterns, and `*` globs.
+#[derive(Debug)]
+struct GitignoreMatcher {
+    patterns: Vec<(glob::Pattern, bool)>, // (pattern, dir_only)
+}
+
+impl GitignoreMatcher {
+    fn load(root: &Path) -> Self {
+        let mut patterns = Vec::new();
+        if let Ok(content) = std::fs::read_to_string(root.join(".gitignore")) {
+            for line in content.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                    continue;
+                }
+                let dir_only = line.ends_with('/');
+                let mut pattern = line.trim_end_matches('/').to_string();
+                // Unanchored patterns match at any depth
+                if !pattern.starts_with('/') && !pattern.contains('/') {
+                    pattern = format!("**/{}", pattern);
+                } else {
+                    pattern = pattern.trim_start_matches('/').to_string();
+                }
+                if let Ok(compiled) = glob::Pattern::new(&pattern) {
+                    patterns.push((compiled, dir_only));
+                }
+            }
+        }
+        Self { patterns }
+    }
+
+    fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let rel_str = relative.to_string_lossy();
+        self.patterns.iter().any(|(pattern, dir_only)| {
+            (!dir_only || is_dir) && pattern.matches(&rel_str)
+        })
+    }
+}
+
+/// Keyboard-navigable, gitignore-aware file tree picker
+#[derive(Debug)]
+pub struct FilePicker {
+    title: String,
+    root: PathBuf,
+    ignore: GitignoreMatcher,
+    /// Expanded directories (children are listed under them)
+    expanded: HashSet<PathBuf>,
+    /// Flattened, currently visible nodes
+    visible: Vec<FileNode>,
+    selected: usize,
+    scroll_offset: usize,
+    /// Only directories are shown and selectable (e.g. /add-dir)
+    dirs_only: bool,
+    /// Space toggles entries; Enter confirms the marked set
+    multi_select: bool,
+    marked: HashSet<PathBuf>,
+}
+
+impl FilePicker {
+    pub fn new(title: impl Into<String>, root: PathBuf) -> Self {
+        let ignore = GitignoreMatcher::load(&root);
+        let mut picker = Self {
+            title: title.into(),
+            root: root.clone(),
+            ignore,
+            expanded: HashSet::from([root]),
+            visible: Vec::new(),
+            selected: 0,
+            scroll_offset: 0,
+            dirs_only: false,
+            multi_select: false,
+            marked: HashSet::new(),
+        };
+        picker.rebuild();
+        picker
+    }
+
+    /// Show and select directories only (used by /add-dir)
+    pub fn dirs_only(mut self) -> Self {
+        self.dirs_only = true;
+        self.rebuild();
+        self
+    }
+
+    /// Enable multi-select with Space (used by the commit staging UI)
+    pub fn multi_select(mut self) -> Self {
+        self.multi_select = true;
+        self
+    }
+
+    /// List the children of a directory, filtered and sorted (dirs first)
+    fn list_children(&self, dir: &Path) -> Vec<FileNode> {
+        let depth = dir
+            .strip_prefix(&self.root)
+            .map(|p| p.components().count())
+            .unwrap_or(0);
+        let mut children = Vec::new();
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                let is_dir = path.is_dir();
+                if name == ".git" {
+                    continue;
+                }
+                if let Ok(relative) = path.strip_prefix(&self.root) {
+                    if self.ignore.is_ignored(relative, is_dir) {
+                        continue;
+                    }
+                }
+                if crate::path_policy::is_path_excluded(&path) {
+                    continue;
+                }
+                if self.dirs_only && !is_dir {
+                    continue;
+                }
+                children.push(FileNode {
+                    path,
+                    name,
+                    is_dir,
+                    depth,
+                });
+            }
+        }
+        children.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.name.cmp(&b.name)));
+        children
+    }
+
+    /// Rebuild the flattened visible node list from the expansion state
+    fn rebuild(&mut self) {
+        fn walk(picker: &FilePicker, dir: &Path, out: &mut Vec<FileNode>) {
+            for child in picker.list_children(dir) {
+                let expanded = child.is_dir && picker.expanded.contains(&child.path);
+                let path = child.path.clone();
+                out.push(child);
+                if expanded {
+                    walk(picker, &path, out);
+                }
+            }
+        }
+
+        let mut visible = Vec::new();
+        walk(self, &self.root.clone(), &mut visible);
+        self.visible = visible;
+        if self.selected >= self.visible.len() {
+            self.selected = self.visible.len().saturating_sub(1);
+        }
+    }
+
+    /// Handle a key press, returning what (if anything) was decided
+    pub fn handle_key(&mut self, key: KeyEvent) -> FilePickerAction {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                FilePickerAction::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.visible.len() {
+                    self.selected += 1;
+                }
+                FilePickerAction::None
+            }
+            KeyCode::PageUp => {
+                self.selected = self.selected.saturating_sub(10);
+                FilePickerAction::None
+            }
+            KeyCode::PageDown => {
+                self.selected = (self.selected + 10).min(self.visible.len().saturating_sub(1));
+                FilePickerAction::None
+            }
+            KeyCode::Right => {
+                if let Some(node) = self.visible.get(self.selected) {
+                    if node.is_dir && !self.expanded.contains(&node.path) {
+                        self.expanded.insert(node.path.clone());
+                        self.rebuild();
+                    }
+                }
+                FilePickerAction::None
+            }
+            KeyCode::Left => {
+                if let Some(node) = self.visible.get(self.selected) {
+                    if node.is_dir && self.expanded.contains(&node.path) {
+                        // Collapse the selected directory
+                        self.expanded.remove(&node.path);
+                        self.rebuild();
+                    } else if let Some(parent) = node.path.parent() {
+                        // Jump to (and collapse) the parent directory
+                        if parent != self.root {
+                            if let Some(idx) = self.visible.iter().position(|n| n.path == parent) {
+                                self.selected = idx;
+                            }
+                        }
+                    }
+                }
+                FilePickerAction::None
+            }
+            KeyCode::Char(' ') if self.multi_select => {
+                if let Some(node) = self.visible.get(self.selected) {
+                    if !self.marked.remove(&node.path) {
+                        self.marked.insert(node.path.clone());
+                    }
+                }
+                FilePickerAction::None
+            }
+            KeyCode::Enter => {
+                if self.multi_select {
+                    let mut selection: Vec<PathBuf> = self.marked.iter().cloned().collect();
+                    selection.sort();
+                    return FilePickerAction::SelectedMany(selection);
+                }
+                match self.visible.get(self.selected) {
+                    Some(node) if self.dirs_only || !node.is_dir => {
+                        FilePickerAction::Selected(node.path.clone())
+                    }
+                    Some(node) => {
+                        // Enter on a directory in file mode toggles expansion
+                        if !self.expanded.remove(&node.path) {
+                            self.expanded.insert(node.path.clone());
+                        }
+                        self.rebuild();
+                        FilePickerAction::None
+                    }
+                    None => FilePickerAction::None,
+                }
+            }
+            KeyCode::Esc => FilePickerAction::Cancelled,
+            _ => FilePickerAction::None,
+        }
+    }
+
+    /// Render the picker as a centered overlay
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let width = (area.width * 3 / 5).clamp(40, 90).min(area.width);
+        let height = (area.height * 3 / 4).clamp(10, 40).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width.saturating_sub(width)) / 2,
+            y: area.y + (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        };
+
+        f.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+
+        // Keep the selection in view
+        let list_height = inner.height.saturating_sub(1) as usize;
+        if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if list_height > 0 && self.selected >= self.scroll_offset + list_height {
+            self.scroll_offset = self.selected + 1 - list_height;
+        }
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(list_height)
+            .map(|(idx, node)| {
+                let indent = "  ".repeat(node.depth);
+                let icon = if node.is_dir {
+                    if self.expanded.contains(&node.path) { "▼ 📁" } else { "▶ 📁" }
+                } else {
+                    "  📄"
+                };
+                let mark = if self.multi_select {
+                    if self.marked.contains(&node.path) { "[x] " } else { "[ ] " }
+                } else {
+                    ""
+                };
+                let style = if idx == self.selected {
+                    Style::default().fg(Color::Black).bg(Color::Cyan)
+                } else if node.is_dir {
+                    Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(Span::styled(
+                    format!("{}{}{} {}", indent, mark, icon, node.name),
+                    style,
+                )))
+            })
+            .collect();
+
+        let list_area = Rect { height: inner.height.saturating_sub(1), ..inner };
+        f.render_widget(List::new(items), list_area);
+
+        // Footer with key hints
+        let hints = if self.multi_select {
+            "↑/↓ navigate  ←/→ collapse/expand  Space mark  Enter confirm  Esc cancel"
+        } else {
+            "↑/↓ navigate  ←/→ collapse/expand  Enter select  Esc cancel"
+        };
+        let footer_area = Rect {
+            x: inner.x,
+            y: inner.y + inner.height.saturating_sub(1),
+            width: inner.width,
+            height: 1,
+        };
+        f.render_widget(
+            Paragraph::new(hints).style(Style::default().fg(Color::DarkGray)),
+            footer_area,
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! MCP server health dashboard.
+//!
+//! Overlay opened with `/mcp` showing every configured server with its
+//! transport, connection state, tool count, last error, and request
+//! latency. Individual servers can be enabled, disabled, or reconnected
+//! from the list: ↑/↓ (or j/k) navigate, `e` enables, `d` disables,
+//! `r` reconnects, Esc closes.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// One server row in the dashboard
+#[derive(Debug, Clone)]
+pub struct McpServerRow {
+    pub name: String,
+    pub transport: String,
+    /// Live transport state ("connected", "reconnecting", ...); None for
+    /// stdio servers, which have no published connection state
+    pub connection_state: Option<String>,
+    /// Whether the user has the server enabled this session
+    pub enabled: bool,
+    pub tool_count: Option<usize>,
+    pub last_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+/// What a key press asked the dashboard to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum McpDashboardAction {
+    /// Key consumed, dashboard still open
+    None,
+    /// Enable the named server
+    Enable(String),
+    /// Disable the named server
+    Disable(String),
+    /// Drop and re-establish the named server's connection
+    Reconnect(String),
+    /// Dashboard dismissed
+    Close,
+}
+
+/// MCP server health dashboard overlay
+#[derive(Debug, Clone)]
+pub struct McpDashboard {
+    pub rows: Vec<McpServerRow>,
+    pub selected: usize,
+}
+
+impl McpDashboard {
+    pub fn new(rows: Vec<McpServerRow>) -> Self {
+        Self { rows, selected: 0 }
+    }
+
+    /// Handle a key press, returning the action it requested
+    pub fn handle_key(&mut self, key: KeyEvent) -> McpDashboardAction {
+        let selected_name = self.rows.get(self.selected).map(|row| row.name.clone());
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                if self.selected > 0 {
+                    self.selected -= 1;
+                }
+                McpDashboardAction::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.rows.len() {
+                    self.selected += 1;
+                }
+                McpDashboardAction::None
+            }
+            KeyCode::Char('e') => selected_name
+                .map(McpDashboardAction::Enable)
+                .unwrap_or(McpDashboardAction::None),
+            KeyCode::Char('d') => selected_name
+                .map(McpDashboardAction::Disable)
+                .unwrap_or(McpDashboardAction::None),
+            KeyCode::Char('r') => selected_name
+                .map(McpDashboardAction::Reconnect)
+                .unwrap_or(McpDashboardAction::None),
+            KeyCode::Esc | KeyCode::Char('q') => McpDashboardAction::Close,
+            _ => McpDashboardAction::None,
+        }
+    }
+
+    /// Update one row's enabled flag in place after an action succeeds
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.name == name) {
+            row.enabled = enabled;
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        // Three lines per server plus chrome
+        let height = (self.rows.len() as u16 * 3 + 5)
+            .min(area.height.saturating_sub(2))
+            .max(7);
+        let width = area.width.saturating_sub(8).min(90).max(40);
+        let popup = Rect {
+            x: area.x + (area.width.saturating_sub(width)) / 2,
+            y: area.y + (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        };
+
+        f.render_widget(Clear, popup);
+
+        let mut lines: Vec<Line> = Vec::new();
+        if self.rows.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No MCP servers configured",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let is_selected = index == self.selected;
+            let cursor = if is_selected { "❯ " } else { "  " };
+            let name_style = if is_selected {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            };
+
+            let (state_text, state_color) = if !row.enabled {
+                ("disabled".to_string(), Color::DarkGray)
+            } else {
+                match row.connection_state.as_deref() {
+                    Some("connected") | None => ("connected".to_string(), Color::Green),
+                    Some("reconnecting") => ("reconnecting".to_string(), Color::Yellow),
+                    Some(other) => (other.to_string(), Color::Red),
+                }
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(cursor, Style::default().fg(Color::Cyan)),
+                Span::styled(row.name.clone(), name_style),
+                Span::styled(
+                    format!("  ({})", row.transport),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw("  "),
+                Span::styled(state_text, Style::default().fg(state_color)),
+            ]));
+
+            let tools = row
+                .tool_count
+                .map(|count| format!("{} tools", count))
+                .unwrap_or_else(|| "tools unknown".to_string());
+            let latency = row
+                .last_latency_ms
+                .map(|ms| format!("{}ms", ms))
+                .unwrap_or_else(|| "–".to_string());
+            lines.push(Line::from(vec![
+                Span::raw("    "),
+                Span::styled(
+                    format!("{} · latency {}", tools, latency),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]));
+
+            if let Some(error) = &row.last_error {
+                lines.push(Line::from(vec![
+                    Span::raw("    "),
+                    Span::styled(
+                        format!("last error: {}", error),
+                        Style::default().fg(Color::Red),
+                    ),
+                ]));
+            } else {
+                lines.push(Line::from(""));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            " ↑/↓ select · e enable · d disable · r reconnect · Esc close",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let block = Block::default()
+            .title(" MCP Servers ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        f.render_widget(Paragraph::new(lines).block(block), popup);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    fn row(name: &str) -> McpServerRow {
+        McpServerRow {
+            name: name.to_string(),
+            transport: "stdio".to_string(),
+            connection_state: None,
+            enabled: true,
+            tool_count: Some(3),
+            last_latency_ms: Some(12),
+            last_error: None,
+        }
+    }
+
+    fn press(dashboard: &mut McpDashboard, code: KeyCode) -> McpDashboardAction {
+        dashboard.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_navigation_stays_in_bounds() {
+        let mut dashboard = McpDashboard::new(vec![row("a"), row("b")]);
+        assert_eq!(press(&mut dashboard, KeyCode::Up), McpDashboardAction::None);
+        assert_eq!(dashboard.selected, 0);
+        press(&mut dashboard, KeyCode::Down);
+        assert_eq!(dashboard.selected, 1);
+        press(&mut dashboard, KeyCode::Down);
+        assert_eq!(dashboard.selected, 1);
+    }
+
+    #[test]
+    fn test_actions_target_selected_server() {
+        let mut dashboard = McpDashboard::new(vec![row("a"), row("b")]);
+        press(&mut dashboard, KeyCode::Down);
+        assert_eq!(
+            press(&mut dashboard, KeyCode::Char('d')),
+            McpDashboardAction::Disable("b".to_string())
+        );
+        assert_eq!(
+            press(&mut dashboard, KeyCode::Char('r')),
+            McpDashboardAction::Reconnect("b".to_string())
+        );
+        assert_eq!(press(&mut dashboard, KeyCode::Esc), McpDashboardAction::Close);
+    }
+
+    #[test]
+    fn test_empty_dashboard_ignores_actions() {
+        let mut dashboard = McpDashboard::new(Vec::new());
+        assert_eq!(
+            press(&mut dashboard, KeyCode::Char('e')),
+            McpDashboardAction::None
+        );
+    }
+}
@@ -1,3 +1,8 @@
+pub mod dialogs;
+pub mod file_picker;
+pub mod mcp_dashboard;
+pub mod prune_view;
+
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -8,6 +13,42 @@ use ratatui::{
 use std::collections::HashMap;
 use crate::tui::markdown::parse_markdown;
 
+/// Terminals narrower than this render in compact mode: the horizontal
+/// gutters are dropped so every column goes to content
+pub const COMPACT_WIDTH_THRESHOLD: u16 = 80;
+
+/// Horizontal gutter applied on each side of the chat content on
+/// normal-width terminals
+const CONTENT_GUTTER: u16 = 2;
+
+/// Compute the chat content rect for a terminal area. Narrow terminals
+/// (below [`COMPACT_WIDTH_THRESHOLD`]) use the full width, normal
+/// terminals get a small gutter on each side, and when a reading width
+/// is configured, ultrawide terminals cap the content at that many
+/// columns with centered margins
+pub fn content_area(area: Rect, reading_width: Option<u16>) -> Rect {
+    if area.width < COMPACT_WIDTH_THRESHOLD {
+        return area;
+    }
+
+    let mut content_width = area.width.saturating_sub(CONTENT_GUTTER * 2);
+    if let Some(max_width) = reading_width {
+        // Ignore widths too small to render anything readable so a bad
+        // config value never collapses the chat
+        if max_width >= 40 {
+            content_width = content_width.min(max_width);
+        }
+    }
+
+    let margin = (area.width - content_width) / 2;
+    Rect {
+        x: area.x + margin,
+        y: area.y,
+        width: content_width,
+        height: area.height,
+    }
+}
+
 /// Chat view component
 pub struct ChatView<'a> {
     messages: &'a [UiMessage],
@@ -19,10 +60,14 @@ pub struct ChatView<'a> {
     task_status: Option<&'a str>,
     spinner_char: &'a str,
     is_processing: bool,
+    /// Elapsed time / token count / context usage shown next to the task status
+    processing_stats: Option<&'a str>,
     next_todo: Option<&'a str>,
     // Text selection state
     selection_start: Option<(usize, usize)>,  // (line, column)
     selection_end: Option<(usize, usize)>,    // (line, column)
+    /// Configured reading width cap, applied via content_area()
+    reading_width: Option<u16>,
 }
 
 impl<'a> ChatView<'a> {
@@ -37,9 +82,11 @@ impl<'a> ChatView<'a> {
             task_status: None,
             spinner_char: "-",
             is_processing: false,
+            processing_stats: None,
             next_todo: None,
             selection_start: None,
             selection_end: None,
+            reading_width: None,
         }
     }
     
@@ -71,6 +118,11 @@ impl<'a> ChatView<'a> {
         self
     }
     
+    pub fn with_processing_stats(mut self, stats: Option<&'a str>) -> Self {
+        self.processing_stats = stats;
+        self
+    }
+
     pub fn with_next_todo(mut self, next_todo: Option<&'a str>) -> Self {
         self.next_todo = next_todo;
         self
@@ -81,11 +133,16 @@ impl<'a> ChatView<'a> {
         self.selection_end = end;
         self
     }
+
+    pub fn with_reading_width(mut self, reading_width: Option<u16>) -> Self {
+        self.reading_width = reading_width;
+        self
+    }
 }
 
 impl<'a> Widget for ChatView<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let inner = area;
+        let inner = content_area(area, self.reading_width);
         
         // Use cached lines if available, but always add task status if processing
         let mut all_lines = if let Some(cached) = self.cached_lines {
@@ -341,23 +398,24 @@ impl<'a> Widget for ChatView<'a> {
         // Always add task status display if processing, even with cached lines
         if self.is_processing && self.task_status.is_some() {
             let status_text = self.task_status.unwrap_or("");
-            all_lines.push(Line::from(vec![
+            let mut spans = vec![
                 Span::styled(self.spinner_char, Style::default().fg(Color::Yellow)),
                 Span::raw(" "),
                 Span::styled(status_text, Style::default().fg(Color::White)),
-                Span::raw(" "),
-                Span::styled("(esc to interrupt • ctrl+r to expand)", Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC)),
-            ]));
+            ];
+            if let Some(stats) = self.processing_stats {
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(format!("({})", stats), Style::default().fg(Color::Gray)));
+            }
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled("(esc to interrupt • ctrl+r to expand)", Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC)));
+            all_lines.push(Line::from(spans));
         }
 
         // Always add next TODO display if there is one
         if let Some(next_todo) = self.next_todo {
             // Truncate long todo descriptions
-            let todo_text = if next_todo.len() > 80 {
-                format!("{}...", &next_todo[..77])
-            } else {
-                next_todo.to_string()
-            };
+            let todo_text = crate::utils::truncate_display(next_todo, 80);
 
             all_lines.push(Line::from(vec![
                 Span::styled("⎿ Next: ", Style::default().fg(Color::Cyan)),
@@ -864,4 +922,43 @@ impl Widget for AutocompleteDropdown<'_> {
         
         list.render(area, buf);
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_area_compact_on_narrow_terminals() {
+        // Below the threshold every column goes to content
+        let area = Rect { x: 0, y: 0, width: 60, height: 24 };
+        assert_eq!(content_area(area, None), area);
+        assert_eq!(content_area(area, Some(100)), area);
+    }
+
+    #[test]
+    fn test_content_area_gutters_on_normal_terminals() {
+        let area = Rect { x: 0, y: 0, width: 100, height: 24 };
+        let content = content_area(area, None);
+        assert_eq!(content.width, 96);
+        assert_eq!(content.x, 2);
+        assert_eq!(content.height, area.height);
+    }
+
+    #[test]
+    fn test_content_area_caps_and_centers_on_ultrawide() {
+        let area = Rect { x: 0, y: 0, width: 300, height: 24 };
+        let content = content_area(area, Some(120));
+        assert_eq!(content.width, 120);
+        // Centered: equal margins on both sides
+        assert_eq!(content.x, 90);
+        assert_eq!(content.x + content.width, 210);
+    }
+
+    #[test]
+    fn test_content_area_ignores_unusable_reading_width() {
+        // A cap too small to render anything falls back to the gutter layout
+        let area = Rect { x: 0, y: 0, width: 200, height: 24 };
+        let content = content_area(area, Some(10));
+        assert_eq!(content.width, 196);
+    }
+}
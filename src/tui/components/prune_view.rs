@@ -0,0 +1,224 @@
+//! Selective context pruning overlay.
+//!
+//! Opened with `/prune`: lists the user/assistant messages that get
+//! resent to the model, with an estimated token count per message.
+//! Space (or x) marks a message for exclusion from future requests -
+//! it stays in the visual transcript - and the freed-token total
+//! updates live in the footer. ↑/↓ (or j/k) navigate, Esc closes.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// One prunable message in the list
+#[derive(Debug, Clone)]
+pub struct PruneRow {
+    /// Index into AppState.messages
+    pub index: usize,
+    pub role: String,
+    /// Estimated tokens (chars/4, matching estimate_token_count)
+    pub tokens: usize,
+    /// First line of the message, truncated
+    pub excerpt: String,
+    pub excluded: bool,
+}
+
+/// What a key press asked the prune view to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum PruneAction {
+    /// Key consumed, view still open
+    None,
+    /// Toggle the exclusion of the message at this transcript index
+    Toggle(usize),
+    /// View dismissed
+    Close,
+}
+
+/// Context pruning overlay
+#[derive(Debug, Clone)]
+pub struct PruneView {
+    pub rows: Vec<PruneRow>,
+    pub selected: usize,
+}
+
+impl PruneView {
+    pub fn new(rows: Vec<PruneRow>) -> Self {
+        Self { rows, selected: 0 }
+    }
+
+    /// Handle a key press, returning the action it requested
+    pub fn handle_key(&mut self, key: KeyEvent) -> PruneAction {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                if self.selected > 0 {
+                    self.selected -= 1;
+                }
+                PruneAction::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.rows.len() {
+                    self.selected += 1;
+                }
+                PruneAction::None
+            }
+            KeyCode::Char(' ') | KeyCode::Char('x') => {
+                if let Some(row) = self.rows.get_mut(self.selected) {
+                    row.excluded = !row.excluded;
+                    PruneAction::Toggle(row.index)
+                } else {
+                    PruneAction::None
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => PruneAction::Close,
+            _ => PruneAction::None,
+        }
+    }
+
+    /// Estimated tokens freed by the current exclusions
+    pub fn excluded_tokens(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| row.excluded)
+            .map(|row| row.tokens)
+            .sum()
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let height = (self.rows.len() as u16 + 5)
+            .min(area.height.saturating_sub(2))
+            .max(7);
+        let width = area.width.saturating_sub(8).min(90).max(40);
+        let popup = Rect {
+            x: area.x + (area.width.saturating_sub(width)) / 2,
+            y: area.y + (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        };
+
+        f.render_widget(Clear, popup);
+
+        let mut lines: Vec<Line> = Vec::new();
+        if self.rows.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No messages in context yet",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        // Keep the selection visible when the list outgrows the popup
+        let visible = height.saturating_sub(5) as usize;
+        let first = if visible == 0 || self.selected < visible {
+            0
+        } else {
+            self.selected + 1 - visible
+        };
+
+        for (index, row) in self.rows.iter().enumerate().skip(first).take(visible.max(1)) {
+            let is_selected = index == self.selected;
+            let cursor = if is_selected { "❯ " } else { "  " };
+            let mark = if row.excluded { "[x]" } else { "[ ]" };
+            let amount = if row.tokens >= 1000 {
+                format!("{:.1}k", row.tokens as f64 / 1000.0)
+            } else {
+                row.tokens.to_string()
+            };
+
+            let text_style = if row.excluded {
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::CROSSED_OUT)
+            } else if is_selected {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default()
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(cursor, Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    mark,
+                    if row.excluded {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    },
+                ),
+                Span::styled(format!(" {:<9}", row.role), text_style),
+                Span::styled(format!("{:>6}  ", amount), Style::default().fg(Color::Gray)),
+                Span::styled(row.excerpt.clone(), text_style),
+            ]));
+        }
+
+        let freed = self.excluded_tokens();
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(" Excluded: ~{:.1}k tokens freed", freed as f64 / 1000.0),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                "  ·  ↑/↓ select · Space toggle · Esc close",
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+
+        let block = Block::default()
+            .title(" Prune Context ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        f.render_widget(Paragraph::new(lines).block(block), popup);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    fn row(index: usize, tokens: usize) -> PruneRow {
+        PruneRow {
+            index,
+            role: "assistant".to_string(),
+            tokens,
+            excerpt: "hello".to_string(),
+            excluded: false,
+        }
+    }
+
+    fn press(view: &mut PruneView, code: KeyCode) -> PruneAction {
+        view.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_toggle_reports_transcript_index_and_savings() {
+        let mut view = PruneView::new(vec![row(0, 100), row(3, 2000)]);
+        press(&mut view, KeyCode::Down);
+        assert_eq!(press(&mut view, KeyCode::Char(' ')), PruneAction::Toggle(3));
+        assert_eq!(view.excluded_tokens(), 2000);
+        // Toggling again clears the exclusion
+        assert_eq!(press(&mut view, KeyCode::Char('x')), PruneAction::Toggle(3));
+        assert_eq!(view.excluded_tokens(), 0);
+    }
+
+    #[test]
+    fn test_navigation_stays_in_bounds() {
+        let mut view = PruneView::new(vec![row(0, 1), row(1, 1)]);
+        assert_eq!(press(&mut view, KeyCode::Up), PruneAction::None);
+        assert_eq!(view.selected, 0);
+        press(&mut view, KeyCode::Down);
+        press(&mut view, KeyCode::Down);
+        assert_eq!(view.selected, 1);
+        assert_eq!(press(&mut view, KeyCode::Esc), PruneAction::Close);
+    }
+
+    #[test]
+    fn test_empty_view_ignores_toggle() {
+        let mut view = PruneView::new(Vec::new());
+        assert_eq!(press(&mut view, KeyCode::Char(' ')), PruneAction::None);
+    }
+}
@@ -33,8 +33,10 @@ pub struct InteractiveOptions {
     pub add_dirs: Vec<PathBuf>,
     pub continue_conversation: bool,
     pub resume_session_id: Option<String>,
+    pub all_projects: bool,
     pub mcp_config: Option<String>,
     pub dangerously_skip_permissions: bool,
+    pub control_socket: Option<PathBuf>,
 }
 
 /// Run the interactive TUI
@@ -62,10 +64,28 @@ pub async fn run(options: InteractiveOptions) -> Result<()> {
     
     // Set the event sender for background tasks
     app_state.event_tx = Some(tx.clone());
-    
+
+    // Show the welcome header and this session's tip
+    app_state.show_startup_banner();
+
+    // Expose the session over the control socket when requested
+    if let Some(socket_path) = &options.control_socket {
+        match crate::control_socket::start(socket_path.clone(), tx.clone()) {
+            Ok(()) => app_state.add_message(&format!(
+                "Control socket listening at {}",
+                socket_path.display()
+            )),
+            Err(e) => app_state.add_error(&format!("Control socket failed to start: {}", e)),
+        }
+    }
+
     // Start the persistent agent loop for the entire session
     app_state.start_agent_loop();
-    
+
+    // Route server-initiated MCP sampling approvals through the standard
+    // permission dialog
+    register_sampling_approver(tx.clone());
+
     // Load MCP servers if configured
     if let Some(mcp_config) = &options.mcp_config {
         load_mcp_servers(&mut app_state, mcp_config).await?;
@@ -79,7 +99,13 @@ pub async fn run(options: InteractiveOptions) -> Result<()> {
     }
     
     // Track telemetry
-    telemetry::track("interactive_session_start", None::<serde_json::Value>).await;
+    telemetry::track(
+        "interactive_session_start",
+        Some(serde_json::json!({
+            "tool_schema_version": crate::ai::tools::TOOL_SCHEMA_VERSION,
+        })),
+    )
+    .await;
     
     // Main loop
     let result = run_app(&mut terminal, &mut app_state, &mut rx).await;
@@ -232,6 +258,10 @@ async fn run_app(
                     needs_redraw = true;
                 }
                 TuiEvent::Message(msg) => {
+                    crate::control_socket::publish(
+                        "message",
+                        serde_json::json!({ "content": msg }),
+                    );
                     app_state.add_message(&msg);
                     needs_redraw = true;
                 }
@@ -255,6 +285,10 @@ async fn run_app(
                     needs_redraw = true;
                 }
                 TuiEvent::PermissionRequired { tool_name, command, tool_use_id, input, responder } => {
+                    crate::control_socket::publish(
+                        "permission_required",
+                        serde_json::json!({ "tool_name": tool_name, "command": command }),
+                    );
                     // Add to the queue of pending permissions
                     app_state.pending_permissions.push_back(crate::tui::state::PendingPermission {
                         tool_name: tool_name.clone(),
@@ -277,10 +311,42 @@ async fn run_app(
                     
                     needs_redraw = true;
                 }
+                TuiEvent::QuestionRequired { tool_use_id: _, questions, responder } => {
+                    crate::control_socket::publish(
+                        "question_required",
+                        serde_json::json!({
+                            "questions": questions.iter().map(|q| q.question.clone()).collect::<Vec<_>>()
+                        }),
+                    );
+                    app_state.question_flow =
+                        Some(crate::tui::state::QuestionFlow::new(questions, responder));
+                    needs_redraw = true;
+                }
+                TuiEvent::PlanApprovalRequired { tool_use_id: _, plan, responder } => {
+                    crate::control_socket::publish(
+                        "plan_approval_required",
+                        serde_json::json!({ "plan": plan.clone() }),
+                    );
+                    app_state.plan_approval =
+                        Some(crate::tui::state::PlanApprovalFlow::new(plan, responder));
+                    needs_redraw = true;
+                }
+                TuiEvent::StepPause { stage, payload, responder } => {
+                    crate::control_socket::publish(
+                        "step_pause",
+                        serde_json::json!({ "stage": stage.clone() }),
+                    );
+                    app_state.step_flow =
+                        Some(crate::tui::state::StepFlow::new(stage, payload, responder));
+                    needs_redraw = true;
+                }
                 TuiEvent::ProcessingComplete => {
+                    crate::control_socket::publish("processing_complete", serde_json::json!({}));
                     // Unlock the UI when processing completes
                     app_state.is_processing = false;
                     app_state.input_mode = true;
+                    app_state.processing_started_at = None;
+                    app_state.finalize_retry_variants();
                     needs_redraw = true;
                 }
                 TuiEvent::CancelOperation => {
@@ -297,6 +363,61 @@ async fn run_app(
                     app_state.set_task_status(status);
                     needs_redraw = true;
                 }
+                TuiEvent::StreamingUsage { input_tokens, output_tokens } => {
+                    app_state.streaming_input_tokens = input_tokens;
+                    app_state.streaming_output_tokens = output_tokens;
+                    needs_redraw = true;
+                }
+                TuiEvent::SessionUsage { input_tokens, output_tokens } => {
+                    app_state.session_input_tokens += input_tokens as u64;
+                    app_state.session_output_tokens += output_tokens as u64;
+                }
+                TuiEvent::ControlInput(text) => {
+                    if app_state.is_processing {
+                        crate::control_socket::publish(
+                            "message_rejected",
+                            serde_json::json!({ "reason": "session is processing" }),
+                        );
+                    } else {
+                        // Route through the textarea so the message takes the
+                        // exact same path as typed input (history, slash
+                        // commands, paste placeholders)
+                        app_state.input_textarea.insert_str(&text);
+                        if let Err(e) = app_state.submit_input().await {
+                            app_state.add_error(&format!("Error: {}", e));
+                        }
+                    }
+                    needs_redraw = true;
+                }
+                TuiEvent::ControlPermission(decision) => {
+                    // Same flow as the permission dialog keyboard path
+                    if let Some(pending) = app_state.pending_permissions.pop_front() {
+                        let _ = pending.responder.send(decision);
+                        app_state.permission_dialog.hide();
+                        if let Some(next_pending) = app_state.pending_permissions.front() {
+                            app_state.permission_dialog.show(crate::permissions::PermissionRequest {
+                                id: uuid::Uuid::new_v4().to_string(),
+                                tool_name: next_pending.tool_name.clone(),
+                                action: "execute command".to_string(),
+                                details: next_pending.command.clone(),
+                                timestamp: std::time::Instant::now(),
+                            });
+                        }
+                    } else {
+                        crate::control_socket::publish(
+                            "permission_rejected",
+                            serde_json::json!({ "reason": "no pending permission" }),
+                        );
+                    }
+                    needs_redraw = true;
+                }
+                TuiEvent::VoiceTranscript(text) => {
+                    // Insert for review rather than auto-sending: the user
+                    // edits or confirms with Enter like any typed input
+                    app_state.input_textarea.insert_str(&text);
+                    app_state.add_message("🎤 Transcript inserted — review and press Enter to send");
+                    needs_redraw = true;
+                }
                 TuiEvent::TodosUpdated(todos) => {
                     app_state.update_todos(todos);
                     needs_redraw = true;
@@ -381,7 +502,8 @@ fn draw_ui(f: &mut Frame, app_state: &mut AppState) {
     // Draw chat view with scrolling support
     // Get cached lines and rebuild cache if needed
     let cached_lines = app_state.get_cached_lines().clone();
-    
+    let processing_stats = app_state.get_processing_stats();
+
     let chat_view = ChatView::new(&app_state.messages)
         .with_scroll(app_state.scroll_offset)
         .with_session_picker(
@@ -395,8 +517,10 @@ fn draw_ui(f: &mut Frame, app_state: &mut AppState) {
             app_state.get_spinner_char(),
             app_state.is_processing
         )
+        .with_processing_stats(processing_stats.as_deref())
         .with_next_todo(app_state.next_todo.as_deref())
-        .with_selection(app_state.chat_selection_start, app_state.chat_selection_end);
+        .with_selection(app_state.chat_selection_start, app_state.chat_selection_end)
+        .with_reading_width(app_state.reading_width);
     f.render_widget(chat_view, chunks[0]);
     
     // chunks[1] is now the padding space - leave it empty
@@ -525,8 +649,23 @@ fn draw_ui(f: &mut Frame, app_state: &mut AppState) {
     }
 
     // Draw model picker overlay if active
-    if app_state.show_model_picker {
-        draw_model_picker(f, size, app_state);
+    if let Some(picker) = app_state.model_picker.as_mut() {
+        picker.render(f, size);
+    }
+
+    // Draw file picker overlay if active
+    if let Some(picker) = app_state.file_picker.as_mut() {
+        picker.render(f, size);
+    }
+
+    // Draw MCP server dashboard overlay if active
+    if let Some(dashboard) = app_state.mcp_dashboard.as_ref() {
+        dashboard.render(f, size);
+    }
+
+    // Draw context pruning overlay if active
+    if let Some(prune_view) = app_state.prune_view.as_ref() {
+        prune_view.render(f, size);
     }
 
     // Draw status view overlay if active (matches JavaScript tabbed UI)
@@ -552,7 +691,27 @@ fn draw_ui(f: &mut Frame, app_state: &mut AppState) {
 
     // Draw permission dialog if active
     app_state.permission_dialog.render(f, size);
-    
+
+    // Draw the AskUserQuestion dialog flow if the model is waiting on answers
+    if let Some(flow) = &mut app_state.question_flow {
+        flow.render(f, size);
+    }
+
+    // Draw the plan approval dialog if the model is waiting on it
+    if let Some(flow) = &mut app_state.plan_approval {
+        flow.render(f, size);
+    }
+
+    // Draw the step-mode pause dialog if the agent loop is waiting on it
+    if let Some(flow) = &mut app_state.step_flow {
+        flow.render(f, size);
+    }
+
+    // Draw the feedback-capture dialog if one is open (Ctrl+G)
+    if let Some(flow) = &mut app_state.annotation_flow {
+        flow.render(f, size);
+    }
+
     // Draw autocomplete dropdown if active
     if app_state.is_autocomplete_visible && !app_state.autocomplete_matches.is_empty() {
         // Position dropdown just above the input area
@@ -610,11 +769,111 @@ fn convert_key_to_input(key: KeyEvent) -> Input {
 
 /// Handle key events
 async fn handle_key_event(app_state: &mut AppState, key: KeyEvent) -> Result<()> {
+    // Handle the AskUserQuestion dialog flow first if the model is
+    // waiting on answers
+    if app_state.question_flow.is_some() {
+        let outcome = app_state
+            .question_flow
+            .as_mut()
+            .map(|flow| flow.handle_key(key));
+        match outcome {
+            Some(crate::tui::state::QuestionFlowOutcome::Answered) => {
+                if let Some(flow) = app_state.question_flow.take() {
+                    flow.respond(true);
+                }
+            }
+            Some(crate::tui::state::QuestionFlowOutcome::Dismissed) => {
+                if let Some(flow) = app_state.question_flow.take() {
+                    flow.respond(false);
+                }
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    // Handle the plan approval dialog next if the model is waiting on it
+    if app_state.plan_approval.is_some() {
+        let outcome = app_state
+            .plan_approval
+            .as_mut()
+            .map(|flow| flow.handle_key(key));
+        match outcome {
+            Some(crate::tui::state::PlanApprovalOutcome::Approved) => {
+                if let Some(flow) = app_state.plan_approval.take() {
+                    flow.respond(true);
+                }
+            }
+            Some(crate::tui::state::PlanApprovalOutcome::Rejected) => {
+                if let Some(flow) = app_state.plan_approval.take() {
+                    flow.respond(false);
+                }
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    // Handle the step-mode pause dialog if the agent loop is waiting on it
+    if app_state.step_flow.is_some() {
+        let outcome = app_state
+            .step_flow
+            .as_mut()
+            .map(|flow| flow.handle_key(key));
+        match outcome {
+            Some(crate::tui::state::StepPauseOutcome::Continue) => {
+                if let Some(flow) = app_state.step_flow.take() {
+                    flow.respond(true);
+                }
+            }
+            Some(crate::tui::state::StepPauseOutcome::Skip) => {
+                if let Some(flow) = app_state.step_flow.take() {
+                    flow.respond(false);
+                }
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    // Handle the feedback-capture dialog if one is open (Ctrl+G)
+    if app_state.annotation_flow.is_some() {
+        let outcome = app_state
+            .annotation_flow
+            .as_mut()
+            .map(|flow| flow.handle_key(key));
+        match outcome {
+            Some(crate::tui::state::AnnotationOutcome::Save) => {
+                if let Some(flow) = app_state.annotation_flow.take() {
+                    let index = flow.message_index;
+                    let annotation = flow.annotation();
+                    app_state.apply_annotation(index, annotation).await;
+                }
+            }
+            Some(crate::tui::state::AnnotationOutcome::Cancel) => {
+                app_state.annotation_flow = None;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
     // Handle permission dialog first if it's active
     if app_state.permission_dialog.visible {
         if let Some(decision) = app_state.permission_dialog.handle_key(key) {
             use crate::permissions::PermissionBehavior;
-            
+
+            // Record the prompt outcome and decision latency for the
+            // permission friction summary in the status view
+            if let Some(request) = &app_state.permission_dialog.request {
+                crate::permissions::record_permission_decision(
+                    &request.tool_name,
+                    &request.details,
+                    &decision,
+                    request.timestamp.elapsed(),
+                );
+            }
+
             // Hide the dialog
             app_state.permission_dialog.hide();
             
@@ -724,32 +983,55 @@ async fn handle_key_event(app_state: &mut AppState, key: KeyEvent) -> Result<()>
         }
     }
 
-    // Handle model picker keys
-    if app_state.show_model_picker {
-        let models = app_state.get_available_models();
-        match key.code {
-            KeyCode::Up => {
-                if app_state.model_picker_selected > 0 {
-                    app_state.model_picker_selected -= 1;
-                }
-                return Ok(());
+    // Handle file picker keys
+    if let Some(picker) = app_state.file_picker.as_mut() {
+        use crate::tui::components::file_picker::FilePickerAction;
+        match picker.handle_key(key) {
+            FilePickerAction::Selected(path) => {
+                app_state.file_picker = None;
+                app_state.add_picked_directory(path);
             }
-            KeyCode::Down => {
-                if app_state.model_picker_selected < models.len().saturating_sub(1) {
-                    app_state.model_picker_selected += 1;
+            FilePickerAction::SelectedMany(paths) => {
+                app_state.file_picker = None;
+                for path in paths {
+                    app_state.add_picked_directory(path);
                 }
-                return Ok(());
             }
-            KeyCode::Enter => {
-                app_state.select_model_by_index(app_state.model_picker_selected);
-                return Ok(());
+            FilePickerAction::Cancelled => {
+                app_state.file_picker = None;
             }
-            KeyCode::Esc => {
-                app_state.show_model_picker = false;
-                return Ok(());
+            FilePickerAction::None => {}
+        }
+        return Ok(());
+    }
+
+    // Handle MCP dashboard keys
+    if let Some(dashboard) = app_state.mcp_dashboard.as_mut() {
+        let action = dashboard.handle_key(key);
+        app_state.apply_mcp_dashboard_action(action).await;
+        return Ok(());
+    }
+
+    // Handle context pruning overlay keys
+    if let Some(prune_view) = app_state.prune_view.as_mut() {
+        let action = prune_view.handle_key(key);
+        app_state.apply_prune_action(action);
+        return Ok(());
+    }
+
+    // Handle model picker keys (generic dialog toolkit)
+    if let Some(picker) = app_state.model_picker.as_mut() {
+        use crate::tui::components::dialogs::DialogAction;
+        match picker.handle_key(key) {
+            DialogAction::Submitted(index) => {
+                app_state.select_model_by_index(index);
             }
-            _ => return Ok(()),
+            DialogAction::Cancelled => {
+                app_state.model_picker = None;
+            }
+            _ => {}
         }
+        return Ok(());
     }
     
     match key.code {
@@ -767,7 +1049,9 @@ async fn handle_key_event(app_state: &mut AppState, key: KeyEvent) -> Result<()>
             return Ok(());
         }
         KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app_state.toggle_debug();
+            // Annotate the latest assistant response with 👍/👎 + note
+            // (the debug panel moved to /debug panel)
+            app_state.start_annotation();
             return Ok(());
         }
         KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -1128,7 +1412,7 @@ fn draw_help(f: &mut Frame, area: Rect) {
         "  Ctrl+C            Cancel current operation",
         "  Ctrl+L            Clear screen",
         "  Ctrl+/ or Ctrl+?  Toggle this help",
-        "  Ctrl+G            Toggle debug panel",
+        "  Ctrl+G            Annotate last response (👍/👎 + note)",
         "  Ctrl+R            Toggle expand/collapse view",
         "  Tab               Auto-complete",
         "  Up/Down           Navigate history (single line)",
@@ -1241,83 +1525,6 @@ fn draw_session_picker(f: &mut Frame, area: Rect, app_state: &AppState) {
     f.render_widget(paragraph, inner);
 }
 
-/// Draw model picker overlay (matches JavaScript model selection UI)
-fn draw_model_picker(f: &mut Frame, area: Rect, app_state: &AppState) {
-    let picker_area = centered_rect(60, 50, area);
-    f.render_widget(Clear, picker_area);
-
-    let block = Block::default()
-        .title(" Select Model ")
-        .borders(Borders::ALL)
-        .style(Style::default().fg(Color::Cyan));
-
-    let inner = block.inner(picker_area);
-    f.render_widget(block, picker_area);
-
-    let models = app_state.get_available_models();
-    let mut lines = vec![
-        ratatui::text::Line::from(ratatui::text::Span::styled(
-            "Choose a model for this session:",
-            Style::default().add_modifier(Modifier::DIM)
-        )),
-        ratatui::text::Line::from(""),
-    ];
-
-    for (i, (name, model_id, description)) in models.iter().enumerate() {
-        let is_selected = i == app_state.model_picker_selected;
-        let is_current = *model_id == app_state.current_model;
-
-        let prefix = if is_selected { "❯ " } else { "  " };
-        let current_marker = if is_current { " (current)" } else { "" };
-
-        let style = if is_selected {
-            Style::default().add_modifier(Modifier::REVERSED)
-        } else {
-            Style::default()
-        };
-
-        // Model name line
-        lines.push(ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled(prefix, style),
-            ratatui::text::Span::styled(*name, style.add_modifier(ratatui::style::Modifier::BOLD)),
-            ratatui::text::Span::styled(current_marker, Style::default().fg(Color::Green)),
-        ]));
-
-        // Description line (indented)
-        let desc_style = if is_selected {
-            Style::default().add_modifier(Modifier::REVERSED).add_modifier(Modifier::DIM)
-        } else {
-            Style::default().add_modifier(Modifier::DIM)
-        };
-        lines.push(ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("    ", desc_style),
-            ratatui::text::Span::styled(*description, desc_style),
-        ]));
-
-        // Model ID line (indented, dimmer)
-        let id_style = if is_selected {
-            Style::default().add_modifier(Modifier::REVERSED).add_modifier(Modifier::DIM)
-        } else {
-            Style::default().add_modifier(Modifier::DIM)
-        };
-        lines.push(ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("    ", id_style),
-            ratatui::text::Span::styled(format!("({})", model_id), id_style),
-        ]));
-
-        lines.push(ratatui::text::Line::from("")); // Spacing between models
-    }
-
-    lines.push(ratatui::text::Line::from(""));
-    lines.push(ratatui::text::Line::from(ratatui::text::Span::styled(
-        "Use ↑/↓ to select, Enter to confirm, Esc to cancel",
-        Style::default().add_modifier(Modifier::DIM)
-    )));
-
-    let paragraph = Paragraph::new(lines);
-    f.render_widget(paragraph, inner);
-}
-
 /// Draw status view overlay (matches JavaScript tabbed UI)
 fn draw_status_view(f: &mut Frame, area: Rect, app_state: &AppState) {
     let status_area = centered_rect(85, 85, area);
@@ -1450,6 +1657,39 @@ fn draw_status_view(f: &mut Frame, area: Rect, app_state: &AppState) {
                     ratatui::text::Span::styled(value.to_string(), value_style),
                 ]));
             }
+
+            // Permission friction summary for this session
+            lines.push(ratatui::text::Line::from(""));
+            lines.push(ratatui::text::Line::from(ratatui::text::Span::styled(
+                "Permission friction (this session)",
+                Style::default().add_modifier(ratatui::style::Modifier::BOLD)
+            )));
+
+            let friction = crate::permissions::friction_stats_snapshot();
+            if friction.is_empty() {
+                lines.push(ratatui::text::Line::from(ratatui::text::Span::styled(
+                    "No permission prompts so far",
+                    Style::default().add_modifier(Modifier::DIM)
+                )));
+            } else {
+                for (tool, stats) in &friction {
+                    lines.push(ratatui::text::Line::from(ratatui::text::Span::raw(
+                        format!(
+                            "  {}: {} prompts · avg decision {:.1}s · {:.0}% denied",
+                            tool,
+                            stats.prompts,
+                            stats.average_decision_secs(),
+                            stats.deny_rate() * 100.0
+                        )
+                    )));
+                }
+                for suggestion in crate::permissions::friction_suggestions() {
+                    lines.push(ratatui::text::Line::from(ratatui::text::Span::styled(
+                        format!("  ▸ {}", suggestion),
+                        Style::default().fg(Color::Yellow)
+                    )));
+                }
+            }
         }
         2 => {
             // Usage tab content - uses REAL data from app_state, NEVER hardcoded
@@ -1736,12 +1976,55 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+/// Register this session as the approver for server-initiated MCP
+/// sampling requests, surfacing each one through the permission dialog
+fn register_sampling_approver(event_tx: mpsc::UnboundedSender<TuiEvent>) {
+    let (approval_tx, mut approval_rx) = mpsc::unbounded_channel::<mcp::SamplingApprovalRequest>();
+    mcp::register_sampling_approver(approval_tx);
+
+    tokio::spawn(async move {
+        while let Some(request) = approval_rx.recv().await {
+            let (resp_tx, resp_rx) = tokio::sync::oneshot::channel();
+            let sent = event_tx.send(TuiEvent::PermissionRequired {
+                tool_name: "McpSampling".to_string(),
+                command: format!(
+                    "MCP server '{}' requests a completion: {}",
+                    request.server_name, request.summary
+                ),
+                tool_use_id: uuid::Uuid::new_v4().to_string(),
+                input: serde_json::json!({ "server": request.server_name }),
+                responder: resp_tx,
+            });
+            if sent.is_err() {
+                let _ = request.responder.send(mcp::SamplingApprovalDecision::Deny);
+                continue;
+            }
+
+            let decision = match resp_rx.await {
+                Ok(crate::tui::PermissionDecision::Allow) => mcp::SamplingApprovalDecision::Allow,
+                Ok(crate::tui::PermissionDecision::AlwaysAllow) => {
+                    mcp::SamplingApprovalDecision::AlwaysAllow
+                }
+                _ => mcp::SamplingApprovalDecision::Deny,
+            };
+            let _ = request.responder.send(decision);
+        }
+    });
+}
+
 /// Load MCP servers from configuration
 async fn load_mcp_servers(app_state: &mut AppState, config: &str) -> Result<()> {
     let servers = mcp::parse_config(config)?;
     
     for (name, server_config) in servers {
-        match mcp::start_client(name.clone(), server_config).await {
+        // Keep the config around so the dashboard can reconnect the server
+        app_state
+            .mcp_server_configs
+            .insert(name.clone(), server_config.clone());
+
+        // Run the full initialize handshake so the server will answer
+        // feature requests like prompts/list
+        match mcp::connect_and_initialize(&name, &server_config).await {
             Ok(client) => {
                 app_state.add_mcp_server(name, client);
             }
@@ -1750,7 +2033,10 @@ async fn load_mcp_servers(app_state: &mut AppState, config: &str) -> Result<()>
             }
         }
     }
-    
+
+    // Register each server's prompts as /mcp__server__prompt commands
+    app_state.load_mcp_prompts().await;
+
     Ok(())
 }
 
@@ -35,7 +35,17 @@ pub fn parse_markdown(content: &str) -> Text<'static> {
     let mut in_code = false;
     let mut list_depth: usize = 0;
     let mut in_list_item = false;
-    
+    let mut in_table = false;
+    let mut table_alignments: Vec<pulldown_cmark::Alignment> = Vec::new();
+    let mut table_rows: Vec<Vec<String>> = Vec::new();
+    let mut table_row: Vec<String> = Vec::new();
+    // Links render as numbered footnotes collected at the end of the
+    // message (opened with /open <n>) instead of inlining long URLs
+    let mut footnotes: Vec<String> = Vec::new();
+    let mut link_dest: Option<String> = None;
+    let mut link_text = String::new();
+    let mut link_start: usize = 0;
+
     for event in parser {
         match event {
             Event::Start(tag) => match tag {
@@ -76,12 +86,36 @@ pub fn parse_markdown(content: &str) -> Text<'static> {
                         current_line.clear();
                     }
                 }
+                Tag::Table(alignments) => {
+                    in_table = true;
+                    table_alignments = alignments.clone();
+                    table_rows.clear();
+                    if !current_line.is_empty() {
+                        lines.push(Line::from(current_line.clone()));
+                        current_line.clear();
+                    }
+                }
+                Tag::TableHead | Tag::TableRow => {
+                    table_row.clear();
+                }
+                Tag::TableCell => {
+                    table_row.push(String::new());
+                }
+                Tag::Link { dest_url, .. } => {
+                    // Only external links become footnotes; table cells are
+                    // collected as plain text so markers would be misplaced
+                    if !in_table && dest_url.starts_with("http") {
+                        link_dest = Some(dest_url.to_string());
+                        link_text.clear();
+                        link_start = current_line.len();
+                    }
+                }
                 Tag::Heading { level, .. } => {
                     if !current_line.is_empty() {
                         lines.push(Line::from(current_line.clone()));
                         current_line.clear();
                     }
-                    
+
                     let style = match level {
                         pulldown_cmark::HeadingLevel::H1 => {
                             Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
@@ -119,8 +153,23 @@ pub fn parse_markdown(content: &str) -> Text<'static> {
                             )]));
                         }
 
-                        // Apply syntax highlighting if language is recognized
-                        if let Some(syntax) = syntax_set.find_syntax_by_token(&code_lang) {
+                        // Diagram blocks render as an ASCII approximation;
+                        // unparseable diagrams fall back to highlighted source
+                        if let Some(art) = render_diagram_ascii(&code_lang, &code_content) {
+                            for art_line in art {
+                                lines.push(Line::from(vec![Span::styled(
+                                    art_line,
+                                    Style::default().fg(Color::Cyan),
+                                )]));
+                            }
+                            lines.push(Line::from(vec![Span::styled(
+                                format!(
+                                    " ({} source lines hidden)",
+                                    code_content.lines().count()
+                                ),
+                                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                            )]));
+                        } else if let Some(syntax) = syntax_set.find_syntax_by_token(&code_lang) {
                             highlight_code(&code_content, syntax, theme, &mut lines);
                         } else {
                             // Fallback: render as plain text with code styling
@@ -176,11 +225,46 @@ pub fn parse_markdown(content: &str) -> Text<'static> {
                     }
                     lines.push(Line::from(vec![])); // Add spacing after heading
                 }
+                TagEnd::Link => {
+                    if let Some(dest) = link_dest.take() {
+                        // Bare links (text is the URL itself, or empty)
+                        // collapse to a shortened form; descriptive text stays
+                        let bare = link_text.trim();
+                        if bare.is_empty() || bare == dest || bare == dest.trim_end_matches('/') {
+                            current_line.truncate(link_start);
+                            current_line.push(Span::styled(
+                                shorten_url(&dest),
+                                Style::default()
+                                    .fg(Color::Blue)
+                                    .add_modifier(Modifier::UNDERLINED),
+                            ));
+                        }
+                        footnotes.push(dest);
+                        current_line.push(Span::styled(
+                            format!(" [{}]", footnotes.len()),
+                            Style::default().fg(Color::Cyan),
+                        ));
+                    }
+                }
+                TagEnd::TableHead | TagEnd::TableRow => {
+                    table_rows.push(std::mem::take(&mut table_row));
+                }
+                TagEnd::Table => {
+                    in_table = false;
+                    render_table(&table_rows, &table_alignments, &mut lines);
+                    table_rows.clear();
+                    lines.push(Line::from(vec![])); // Spacing after table
+                }
                 _ => {}
             },
             Event::Text(text) => {
                 if in_code_block {
                     code_content.push_str(&text);
+                } else if in_table {
+                    // Cell text is collected and laid out when the table ends
+                    if let Some(cell) = table_row.last_mut() {
+                        cell.push_str(&text);
+                    }
                 } else {
                     // Use White as default foreground color for visibility
                     let mut style = Style::default().fg(Color::White);
@@ -194,15 +278,36 @@ pub fn parse_markdown(content: &str) -> Text<'static> {
                     if in_code {
                         style = style.fg(Color::Yellow).bg(Color::Rgb(40, 40, 40));
                     }
+                    if link_dest.is_some() {
+                        style = style.fg(Color::Blue).add_modifier(Modifier::UNDERLINED);
+                        link_text.push_str(&text);
+                    }
 
-                    current_line.push(Span::styled(text.to_string(), style));
+                    // Render $...$/$$...$$ spans as Unicode math so formulas
+                    // in model responses stay readable in the terminal
+                    for (segment, is_math) in split_math_segments(&text) {
+                        if is_math {
+                            current_line.push(Span::styled(
+                                render_math(&segment),
+                                Style::default().fg(Color::Cyan),
+                            ));
+                        } else {
+                            current_line.push(Span::styled(segment, style));
+                        }
+                    }
                 }
             }
             Event::Code(code) => {
-                current_line.push(Span::styled(
-                    format!("`{}`", code),
-                    Style::default().fg(Color::Yellow).bg(Color::Rgb(40, 40, 40)),
-                ));
+                if in_table {
+                    if let Some(cell) = table_row.last_mut() {
+                        cell.push_str(&code);
+                    }
+                } else {
+                    current_line.push(Span::styled(
+                        format!("`{}`", code),
+                        Style::default().fg(Color::Yellow).bg(Color::Rgb(40, 40, 40)),
+                    ));
+                }
             }
             Event::SoftBreak | Event::HardBreak => {
                 if !current_line.is_empty() {
@@ -223,11 +328,603 @@ pub fn parse_markdown(content: &str) -> Text<'static> {
     while lines.last().map(|l| l.spans.is_empty()).unwrap_or(false) {
         lines.pop();
     }
-    
+
+    // Footnote list: one `[n] url` line per link, opened with /open <n>
+    if !footnotes.is_empty() {
+        lines.push(Line::from(vec![]));
+        for (i, url) in footnotes.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("[{}] ", i + 1), Style::default().fg(Color::Cyan)),
+                Span::styled(url.clone(), Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+    }
+
     Text::from(lines)
 }
 
+/// Shorten a URL for inline display: drop the scheme and `www.` prefix
+/// and truncate long paths. The full URL stays in the footnote list
+fn shorten_url(url: &str) -> String {
+    let stripped = url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.")
+        .trim_end_matches('/');
+    if stripped.chars().count() > 40 {
+        let head: String = stripped.chars().take(39).collect();
+        format!("{}…", head)
+    } else {
+        stripped.to_string()
+    }
+}
+
+/// Extract the external link URLs from a message in footnote order, so
+/// `/open <n>` resolves the same numbering the renderer displays
+pub fn extract_footnote_links(content: &str) -> Vec<String> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+
+    let mut links = Vec::new();
+    let mut in_table = false;
+    for event in Parser::new_ext(content, options) {
+        match event {
+            Event::Start(Tag::Table(_)) => in_table = true,
+            Event::End(TagEnd::Table) => in_table = false,
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                if !in_table && dest_url.starts_with("http") {
+                    links.push(dest_url.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    links
+}
+
+/// Split text into plain and math segments. Math is delimited by `$...$`
+/// or `$$...$$`; an unmatched delimiter, or one whose content starts or
+/// ends with whitespace (as in "$5 and $10"), is treated as literal text
+fn split_math_segments(text: &str) -> Vec<(String, bool)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut segments = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == '$' {
+            let delim_len = if chars.get(i + 1) == Some(&'$') { 2 } else { 1 };
+            let content_start = i + delim_len;
+
+            // Find the matching closing delimiter
+            let mut close = None;
+            let mut j = content_start;
+            while j < chars.len() {
+                if chars[j] == '$'
+                    && (delim_len == 1 || chars.get(j + 1) == Some(&'$'))
+                {
+                    close = Some(j);
+                    break;
+                }
+                j += 1;
+            }
+
+            if let Some(close) = close {
+                let content: String = chars[content_start..close].iter().collect();
+                let is_valid = !content.is_empty()
+                    && !content.starts_with(char::is_whitespace)
+                    && !content.ends_with(char::is_whitespace);
+                if is_valid {
+                    if !plain.is_empty() {
+                        segments.push((std::mem::take(&mut plain), false));
+                    }
+                    segments.push((content, true));
+                    i = close + delim_len;
+                    continue;
+                }
+            }
+        }
+        plain.push(chars[i]);
+        i += 1;
+    }
+
+    if !plain.is_empty() {
+        segments.push((plain, false));
+    }
+    segments
+}
+
+/// Approximate a LaTeX expression with Unicode: named symbols become
+/// their glyphs, simple super-/subscripts use the dedicated codepoints,
+/// and \frac collapses to a/b. Anything unrecognized passes through as
+/// readable ASCII rather than raw markup
+fn render_math(latex: &str) -> String {
+    let mut out = String::new();
+    let mut chars = latex.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next.is_ascii_alphabetic() {
+                        name.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                match name.as_str() {
+                    "frac" => {
+                        let numerator = read_brace_group(&mut chars);
+                        let denominator = read_brace_group(&mut chars);
+                        let numerator = render_math(&numerator);
+                        let denominator = render_math(&denominator);
+                        if numerator.chars().count() > 1 || denominator.chars().count() > 1 {
+                            out.push_str(&format!("({})/({})", numerator, denominator));
+                        } else {
+                            out.push_str(&format!("{}/{}", numerator, denominator));
+                        }
+                    }
+                    "text" | "mathrm" | "mathbf" => {
+                        out.push_str(&read_brace_group(&mut chars));
+                    }
+                    "" => {
+                        // Escaped character like \$ or \{
+                        if let Some(escaped) = chars.next() {
+                            out.push(escaped);
+                        }
+                    }
+                    _ => match latex_symbol(&name) {
+                        Some(glyph) => out.push_str(glyph),
+                        // Function names (log, max, ...) and unknown
+                        // commands read fine as plain words
+                        None => out.push_str(&name),
+                    },
+                }
+            }
+            '^' => out.push_str(&render_script(&mut chars, to_superscript, "^")),
+            '_' => out.push_str(&render_script(&mut chars, to_subscript, "_")),
+            '{' | '}' => {}
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Read a `{...}` group (handling nesting), or a single character when
+/// no brace follows
+fn read_brace_group(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut group = String::new();
+    if chars.peek() == Some(&'{') {
+        chars.next();
+        let mut depth = 1;
+        for c in chars.by_ref() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            group.push(c);
+        }
+    } else if let Some(c) = chars.next() {
+        group.push(c);
+    }
+    group
+}
+
+/// Render a super- or subscript group: use the dedicated Unicode
+/// codepoints when every character maps, otherwise fall back to
+/// `^(...)` / `_(...)` notation
+fn render_script(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    map: fn(char) -> Option<char>,
+    prefix: &str,
+) -> String {
+    let group = read_brace_group(chars);
+    let rendered = render_math(&group);
+    let mapped: Option<String> = rendered.chars().map(map).collect();
+    match mapped {
+        Some(mapped) => mapped,
+        None if rendered.chars().count() > 1 => format!("{}({})", prefix, rendered),
+        None => format!("{}{}", prefix, rendered),
+    }
+}
+
+fn to_superscript(c: char) -> Option<char> {
+    match c {
+        '0' => Some('⁰'), '1' => Some('¹'), '2' => Some('²'), '3' => Some('³'),
+        '4' => Some('⁴'), '5' => Some('⁵'), '6' => Some('⁶'), '7' => Some('⁷'),
+        '8' => Some('⁸'), '9' => Some('⁹'),
+        '+' => Some('⁺'), '-' => Some('⁻'), '=' => Some('⁼'),
+        '(' => Some('⁽'), ')' => Some('⁾'),
+        'n' => Some('ⁿ'), 'i' => Some('ⁱ'), 'T' => Some('ᵀ'),
+        _ => None,
+    }
+}
+
+fn to_subscript(c: char) -> Option<char> {
+    match c {
+        '0' => Some('₀'), '1' => Some('₁'), '2' => Some('₂'), '3' => Some('₃'),
+        '4' => Some('₄'), '5' => Some('₅'), '6' => Some('₆'), '7' => Some('₇'),
+        '8' => Some('₈'), '9' => Some('₉'),
+        '+' => Some('₊'), '-' => Some('₋'), '=' => Some('₌'),
+        '(' => Some('₍'), ')' => Some('₎'),
+        'a' => Some('ₐ'), 'e' => Some('ₑ'), 'i' => Some('ᵢ'), 'j' => Some('ⱼ'),
+        'k' => Some('ₖ'), 'm' => Some('ₘ'), 'n' => Some('ₙ'), 'o' => Some('ₒ'),
+        'x' => Some('ₓ'), 't' => Some('ₜ'),
+        _ => None,
+    }
+}
+
+/// Unicode glyphs for common LaTeX commands
+fn latex_symbol(name: &str) -> Option<&'static str> {
+    let glyph = match name {
+        // Lowercase Greek
+        "alpha" => "α", "beta" => "β", "gamma" => "γ", "delta" => "δ",
+        "epsilon" => "ε", "zeta" => "ζ", "eta" => "η", "theta" => "θ",
+        "iota" => "ι", "kappa" => "κ", "lambda" => "λ", "mu" => "μ",
+        "nu" => "ν", "xi" => "ξ", "pi" => "π", "rho" => "ρ",
+        "sigma" => "σ", "tau" => "τ", "upsilon" => "υ", "phi" => "φ",
+        "chi" => "χ", "psi" => "ψ", "omega" => "ω",
+        // Uppercase Greek
+        "Gamma" => "Γ", "Delta" => "Δ", "Theta" => "Θ", "Lambda" => "Λ",
+        "Xi" => "Ξ", "Pi" => "Π", "Sigma" => "Σ", "Upsilon" => "Υ",
+        "Phi" => "Φ", "Psi" => "Ψ", "Omega" => "Ω",
+        // Operators and relations
+        "times" => "×", "cdot" => "·", "div" => "÷", "pm" => "±",
+        "leq" | "le" => "≤", "geq" | "ge" => "≥", "neq" | "ne" => "≠",
+        "approx" => "≈", "equiv" => "≡", "sim" => "~", "propto" => "∝",
+        "infty" => "∞", "partial" => "∂", "nabla" => "∇", "sqrt" => "√",
+        "sum" => "Σ", "prod" => "Π", "int" => "∫",
+        // Sets and logic
+        "in" => "∈", "notin" => "∉", "subset" => "⊂", "subseteq" => "⊆",
+        "supset" => "⊃", "supseteq" => "⊇", "cup" => "∪", "cap" => "∩",
+        "emptyset" => "∅", "forall" => "∀", "exists" => "∃",
+        "land" | "wedge" => "∧", "lor" | "vee" => "∨", "neg" => "¬",
+        // Arrows
+        "to" | "rightarrow" => "→", "leftarrow" => "←",
+        "Rightarrow" | "implies" => "⇒", "Leftarrow" => "⇐",
+        "leftrightarrow" => "↔", "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        // Dots and misc
+        "ldots" | "dots" => "…", "cdots" => "⋯", "mid" => "|",
+        "langle" => "⟨", "rangle" => "⟩", "prime" => "′",
+        _ => return None,
+    };
+    Some(glyph)
+}
+
 /// Apply syntax highlighting to code
+/// Maximum rendered width of one table column; longer cells word-wrap
+/// onto continuation lines. Very wide tables thus grow downward instead
+/// of overflowing horizontally, since the chat view has no sideways scroll
+const MAX_TABLE_CELL_WIDTH: usize = 30;
+
+/// Lay a parsed table out with computed column widths, per-column
+/// alignment, and cell wrapping. The first row is the header
+fn render_table(
+    rows: &[Vec<String>],
+    alignments: &[pulldown_cmark::Alignment],
+    lines: &mut Vec<Line<'static>>,
+) {
+    use unicode_width::UnicodeWidthStr;
+
+    if rows.is_empty() {
+        return;
+    }
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return;
+    }
+
+    // Wrap every cell, then size each column to its widest wrapped line
+    let wrapped: Vec<Vec<Vec<String>>> = rows
+        .iter()
+        .map(|row| {
+            (0..columns)
+                .map(|index| {
+                    wrap_cell(
+                        row.get(index).map(String::as_str).unwrap_or(""),
+                        MAX_TABLE_CELL_WIDTH,
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut widths = vec![1usize; columns];
+    for row in &wrapped {
+        for (index, cell) in row.iter().enumerate() {
+            for cell_line in cell {
+                widths[index] = widths[index].max(UnicodeWidthStr::width(cell_line.as_str()));
+            }
+        }
+    }
+
+    for (row_index, row) in wrapped.iter().enumerate() {
+        let height = row.iter().map(Vec::len).max().unwrap_or(1).max(1);
+        for line_index in 0..height {
+            let rendered = (0..columns)
+                .map(|index| {
+                    let text = row[index]
+                        .get(line_index)
+                        .map(String::as_str)
+                        .unwrap_or("");
+                    let alignment = alignments
+                        .get(index)
+                        .copied()
+                        .unwrap_or(pulldown_cmark::Alignment::None);
+                    pad_cell(text, widths[index], alignment)
+                })
+                .collect::<Vec<_>>()
+                .join(" │ ");
+
+            let style = if row_index == 0 {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            lines.push(Line::from(vec![Span::styled(rendered, style)]));
+        }
+
+        // Rule between header and body
+        if row_index == 0 {
+            let rule = widths
+                .iter()
+                .map(|width| "─".repeat(*width))
+                .collect::<Vec<_>>()
+                .join("─┼─");
+            lines.push(Line::from(vec![Span::styled(
+                rule,
+                Style::default().fg(Color::DarkGray),
+            )]));
+        }
+    }
+}
+
+/// Pad a cell line to the column width according to its alignment
+fn pad_cell(text: &str, width: usize, alignment: pulldown_cmark::Alignment) -> String {
+    use unicode_width::UnicodeWidthStr;
+
+    let padding = width.saturating_sub(UnicodeWidthStr::width(text));
+    match alignment {
+        pulldown_cmark::Alignment::Right => format!("{}{}", " ".repeat(padding), text),
+        pulldown_cmark::Alignment::Center => {
+            let left = padding / 2;
+            format!("{}{}{}", " ".repeat(left), text, " ".repeat(padding - left))
+        }
+        _ => format!("{}{}", text, " ".repeat(padding)),
+    }
+}
+
+/// Word-wrap a cell to the given display width, hard-splitting words that
+/// are longer than a whole line. Always returns at least one line
+fn wrap_cell(text: &str, max_width: usize) -> Vec<String> {
+    use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let word_width = UnicodeWidthStr::width(word);
+        let current_width = UnicodeWidthStr::width(current.as_str());
+
+        if current.is_empty() {
+            // fall through to placement below
+        } else if current_width + 1 + word_width <= max_width {
+            current.push(' ');
+        } else {
+            lines.push(std::mem::take(&mut current));
+        }
+
+        if word_width <= max_width {
+            current.push_str(word);
+        } else {
+            // Hard-split an overlong word across lines
+            for c in word.chars() {
+                let char_width = UnicodeWidthChar::width(c).unwrap_or(0);
+                if UnicodeWidthStr::width(current.as_str()) + char_width > max_width {
+                    lines.push(std::mem::take(&mut current));
+                }
+                current.push(c);
+            }
+        }
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Render a mermaid or PlantUML block as an ASCII approximation.
+///
+/// Terminals this TUI targets have no reliable inline-image protocol, so
+/// diagrams are approximated as text: flowcharts become an edge list with
+/// labelled arrows, sequence diagrams a message transcript. Returns None
+/// when the diagram dialect is not recognized, in which case the block is
+/// shown as highlighted source like any other code
+fn render_diagram_ascii(lang: &str, source: &str) -> Option<Vec<String>> {
+    match lang {
+        "mermaid" => {
+            let first = source.lines().find(|l| !l.trim().is_empty())?.trim();
+            if first.starts_with("sequenceDiagram") {
+                render_sequence_ascii(source.lines().filter(|l| {
+                    !l.trim().starts_with("sequenceDiagram")
+                }))
+            } else if first.starts_with("graph") || first.starts_with("flowchart") {
+                render_flowchart_ascii(source)
+            } else {
+                None
+            }
+        }
+        "plantuml" | "puml" => render_sequence_ascii(
+            source
+                .lines()
+                .filter(|l| !l.trim().starts_with('@')),
+        ),
+        _ => None,
+    }
+}
+
+/// Render a mermaid flowchart as one labelled arrow per edge, substituting
+/// declared node labels for their identifiers
+fn render_flowchart_ascii(source: &str) -> Option<Vec<String>> {
+    let mut labels: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut edges: Vec<(String, String, Option<String>)> = Vec::new();
+
+    for raw_line in source.lines().skip(1) {
+        let line = raw_line.trim();
+        if line.is_empty()
+            || line.starts_with("%%")
+            || line.starts_with("subgraph")
+            || line == "end"
+            || line.starts_with("classDef")
+            || line.starts_with("class ")
+            || line.starts_with("style ")
+            || line.starts_with("click ")
+        {
+            continue;
+        }
+
+        // Normalize the arrow variants to a single separator
+        let normalized = line
+            .replace("-.->", "-->")
+            .replace("==>", "-->")
+            .replace("---", "-->");
+
+        let segments: Vec<&str> = normalized.split("-->").collect();
+        if segments.len() < 2 {
+            // Standalone node declaration; remember its label
+            if let (id, Some(label)) = parse_flowchart_node(line) {
+                labels.insert(id, label);
+            }
+            continue;
+        }
+
+        for pair in segments.windows(2) {
+            let (from_id, from_label) = parse_flowchart_node(pair[0].trim());
+            if let Some(label) = from_label {
+                labels.insert(from_id.clone(), label);
+            }
+
+            // The target segment may carry an edge label: `|label| B`
+            let mut target = pair[1].trim();
+            let mut edge_label = None;
+            if let Some(rest) = target.strip_prefix('|') {
+                if let Some(end) = rest.find('|') {
+                    edge_label = Some(rest[..end].trim().to_string());
+                    target = rest[end + 1..].trim();
+                }
+            }
+            let (to_id, to_label) = parse_flowchart_node(target);
+            if let Some(label) = to_label {
+                labels.insert(to_id.clone(), label);
+            }
+            edges.push((from_id, to_id, edge_label));
+        }
+    }
+
+    if edges.is_empty() {
+        return None;
+    }
+
+    let display = |id: &str| labels.get(id).cloned().unwrap_or_else(|| id.to_string());
+    Some(
+        edges
+            .iter()
+            .map(|(from, to, label)| match label {
+                Some(label) => {
+                    format!("[{}] ──({})──▶ [{}]", display(from), label, display(to))
+                }
+                None => format!("[{}] ──▶ [{}]", display(from), display(to)),
+            })
+            .collect(),
+    )
+}
+
+/// Split a flowchart node token into its identifier and optional label,
+/// handling the `id[label]`, `id(label)`, `id((label))` and `id{label}`
+/// shapes
+fn parse_flowchart_node(token: &str) -> (String, Option<String>) {
+    let token = token.trim();
+    let open = token.find(|c| matches!(c, '[' | '(' | '{'));
+    let Some(open) = open else {
+        return (token.to_string(), None);
+    };
+
+    let id = token[..open].trim().to_string();
+    let label = token[open..]
+        .trim_matches(|c| matches!(c, '[' | ']' | '(' | ')' | '{' | '}'))
+        .trim_matches('"')
+        .trim()
+        .to_string();
+    if label.is_empty() {
+        (id, None)
+    } else {
+        (id, Some(label))
+    }
+}
+
+/// Render a sequence diagram as a message transcript, one arrow per line.
+/// Works for both mermaid (`A->>B: msg`) and PlantUML (`A -> B : msg`)
+/// arrow syntax; dashed arrows (replies) render with a dashed shaft
+fn render_sequence_ascii<'a>(lines: impl Iterator<Item = &'a str>) -> Option<Vec<String>> {
+    let mut rendered = Vec::new();
+
+    for raw_line in lines {
+        let line = raw_line.trim();
+        if line.is_empty()
+            || line.starts_with("participant")
+            || line.starts_with("actor")
+            || line.starts_with("autonumber")
+            || line.starts_with("Note ")
+            || line.starts_with("note ")
+        {
+            continue;
+        }
+
+        // Longest arrow tokens first so `-->>` is not split as `-->`
+        let arrow = ["-->>", "->>", "-->", "->"]
+            .iter()
+            .find_map(|token| line.find(token).map(|pos| (pos, *token)));
+        let Some((pos, token)) = arrow else {
+            continue;
+        };
+
+        let from = line[..pos].trim();
+        let rest = &line[pos + token.len()..];
+        let (to, text) = match rest.find(':') {
+            Some(colon) => (rest[..colon].trim(), rest[colon + 1..].trim()),
+            None => (rest.trim(), ""),
+        };
+        if from.is_empty() || to.is_empty() {
+            continue;
+        }
+
+        let shaft = if token.starts_with("--") { "╌╌▶" } else { "──▶" };
+        if text.is_empty() {
+            rendered.push(format!("{} {} {}", from, shaft, to));
+        } else {
+            rendered.push(format!("{} {} {}: {}", from, shaft, to, text));
+        }
+    }
+
+    if rendered.is_empty() {
+        None
+    } else {
+        Some(rendered)
+    }
+}
+
 fn highlight_code(code: &str, syntax: &SyntaxReference, theme: &Theme, lines: &mut Vec<Line<'static>>) {
     let mut highlighter = HighlightLines::new(syntax, theme);
     
@@ -269,4 +966,177 @@ fn highlight_code(code: &str, syntax: &SyntaxReference, theme: &Theme, lines: &m
             )]));
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_math_segments_inline_and_display() {
+        let segments = split_math_segments("mean is $\\mu$ and $$\\sigma^2$$ here");
+        assert_eq!(segments.len(), 5);
+        assert_eq!(segments[0], ("mean is ".to_string(), false));
+        assert_eq!(segments[1], ("\\mu".to_string(), true));
+        assert_eq!(segments[2], (" and ".to_string(), false));
+        assert_eq!(segments[3], ("\\sigma^2".to_string(), true));
+        assert_eq!(segments[4], (" here".to_string(), false));
+    }
+
+    #[test]
+    fn test_split_math_segments_leaves_currency_alone() {
+        // "$5 and $10" must not be mistaken for math
+        let segments = split_math_segments("costs $5 and $10 total");
+        assert_eq!(segments, vec![("costs $5 and $10 total".to_string(), false)]);
+        // An unmatched delimiter stays literal too
+        let segments = split_math_segments("a $ sign");
+        assert_eq!(segments, vec![("a $ sign".to_string(), false)]);
+    }
+
+    #[test]
+    fn test_render_math_symbols_and_scripts() {
+        assert_eq!(render_math("\\alpha + \\beta \\leq \\infty"), "α + β ≤ ∞");
+        assert_eq!(render_math("x^2 + y_1"), "x² + y₁");
+        assert_eq!(render_math("\\sum_{i=1}^{n} x_i"), "Σᵢ₌₁ⁿ xᵢ");
+        assert_eq!(render_math("O(n \\log n)"), "O(n log n)");
+    }
+
+    #[test]
+    fn test_render_math_frac_and_fallback() {
+        assert_eq!(render_math("\\frac{1}{2}"), "1/2");
+        assert_eq!(render_math("\\frac{a+b}{c}"), "(a+b)/(c)");
+        // Scripts that have no Unicode form fall back to ASCII notation
+        assert_eq!(render_math("x^{a+b}"), "x^(a+b)");
+        assert_eq!(render_math("y_q"), "y_q");
+    }
+
+    #[test]
+    fn test_parse_markdown_lays_out_table_columns() {
+        let content = "| Name | Count |\n| :--- | ---: |\n| first | 1 |\n| second | 22 |\n";
+        let text = parse_markdown(content);
+        let rendered: Vec<String> = text
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect();
+
+        assert_eq!(rendered[0], "Name   │ Count");
+        assert_eq!(rendered[1], "───────┼──────");
+        // Left-aligned name column, right-aligned count column
+        assert_eq!(rendered[2], "first  │     1");
+        assert_eq!(rendered[3], "second │    22");
+    }
+
+    #[test]
+    fn test_wrap_cell_word_wraps_and_hard_splits() {
+        assert_eq!(
+            wrap_cell("one two three", 7),
+            vec!["one two".to_string(), "three".to_string()]
+        );
+        assert_eq!(
+            wrap_cell("abcdefghij", 4),
+            vec!["abcd".to_string(), "efgh".to_string(), "ij".to_string()]
+        );
+        // Empty cells still occupy one line
+        assert_eq!(wrap_cell("", 10), vec![String::new()]);
+    }
+
+    #[test]
+    fn test_pad_cell_alignments() {
+        use pulldown_cmark::Alignment;
+        assert_eq!(pad_cell("x", 5, Alignment::Left), "x    ");
+        assert_eq!(pad_cell("x", 5, Alignment::Right), "    x");
+        assert_eq!(pad_cell("x", 5, Alignment::Center), "  x  ");
+    }
+
+    #[test]
+    fn test_parse_markdown_collects_links_as_footnotes() {
+        let content =
+            "See the [docs](https://example.com/docs) and <https://example.com/very/long/path>";
+        let text = parse_markdown(content);
+        let rendered: Vec<String> = text
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect();
+
+        // Descriptive link text stays inline with a numbered marker;
+        // the bare autolink collapses to a shortened URL
+        assert!(rendered[0].contains("docs [1]"));
+        assert!(rendered[0].contains("example.com/very/long/path [2]"));
+        assert!(!rendered[0].contains("https://example.com/docs"));
+        // Footnote list at the end, in marker order
+        assert_eq!(rendered[rendered.len() - 2], "[1] https://example.com/docs");
+        assert_eq!(
+            rendered[rendered.len() - 1],
+            "[2] https://example.com/very/long/path"
+        );
+    }
+
+    #[test]
+    fn test_shorten_url_strips_scheme_and_truncates() {
+        assert_eq!(shorten_url("https://www.example.com/a/"), "example.com/a");
+        let long = format!("https://example.com/{}", "x".repeat(60));
+        let short = shorten_url(&long);
+        assert_eq!(short.chars().count(), 40);
+        assert!(short.ends_with('…'));
+    }
+
+    #[test]
+    fn test_extract_footnote_links_matches_marker_order() {
+        let content = "[a](https://a.example) then [b](https://b.example), \
+                       but [internal](#anchor) is skipped";
+        assert_eq!(
+            extract_footnote_links(content),
+            vec!["https://a.example".to_string(), "https://b.example".to_string()]
+        );
+        assert!(extract_footnote_links("no links here").is_empty());
+    }
+
+    #[test]
+    fn test_render_flowchart_ascii_substitutes_labels() {
+        let source = "graph TD\n    A[Start] -->|yes| B{Check}\n    B --> C[Done]\n";
+        let art = render_diagram_ascii("mermaid", source).expect("flowchart should render");
+        assert_eq!(art[0], "[Start] ──(yes)──▶ [Check]");
+        assert_eq!(art[1], "[Check] ──▶ [Done]");
+    }
+
+    #[test]
+    fn test_render_sequence_ascii_mermaid_and_plantuml() {
+        let mermaid = "sequenceDiagram\n    Alice->>Bob: Hello\n    Bob-->>Alice: Hi\n";
+        let art = render_diagram_ascii("mermaid", mermaid).expect("sequence should render");
+        assert_eq!(art[0], "Alice ──▶ Bob: Hello");
+        assert_eq!(art[1], "Bob ╌╌▶ Alice: Hi");
+
+        let plantuml = "@startuml\nAlice -> Bob : ping\n@enduml\n";
+        let art = render_diagram_ascii("plantuml", plantuml).expect("plantuml should render");
+        assert_eq!(art[0], "Alice ──▶ Bob: ping");
+    }
+
+    #[test]
+    fn test_render_diagram_ascii_falls_back_for_other_languages() {
+        assert!(render_diagram_ascii("rust", "fn main() {}").is_none());
+        // An unrecognized mermaid dialect falls back to highlighted source
+        assert!(render_diagram_ascii("mermaid", "pie title Pets\n  \"Dogs\": 3\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_markdown_renders_math_span() {
+        let text = parse_markdown("The variance is $\\sigma^2$ here");
+        let rendered: String = text.lines.iter()
+            .flat_map(|line| line.spans.iter())
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert!(rendered.contains("σ²"));
+        assert!(!rendered.contains("\\sigma"));
+    }
+}
@@ -5,6 +5,10 @@ pub mod state;
 pub mod events;
 pub mod app;
 pub mod markdown;
+pub mod replay;
+pub mod tips;
+pub mod tts;
+pub mod voice;
 
 use crate::error::Result;
 use crossterm::{
@@ -63,14 +67,62 @@ pub enum TuiEvent {
         input: serde_json::Value,
         responder: tokio::sync::oneshot::Sender<PermissionDecision>,
     },
+    /// AskUserQuestion's questions, rendered as a dialog flow; the
+    /// selected answers come back through the responder (None when
+    /// the user dismissed the dialog without answering)
+    QuestionRequired {
+        tool_use_id: String,
+        questions: Vec<crate::ai::ask_user_question_tool::Question>,
+        responder: tokio::sync::oneshot::Sender<
+            Option<std::collections::HashMap<String, String>>,
+        >,
+    },
+    /// ExitPlanMode's plan, rendered as an editable document dialog; the
+    /// responder receives the (possibly edited) plan text on approval, or
+    /// None when the user rejected it
+    PlanApprovalRequired {
+        tool_use_id: String,
+        plan: String,
+        responder: tokio::sync::oneshot::Sender<Option<String>>,
+    },
+    /// Step-mode pause (/debug step on): the exact payload about to go
+    /// out - a model request or a tool input - rendered for inspection;
+    /// the developer's decision comes back through the responder
+    StepPause {
+        stage: String,
+        payload: String,
+        responder: tokio::sync::oneshot::Sender<StepDecision>,
+    },
     ProcessingComplete,
     CancelOperation,
     UpdateTaskStatus(Option<String>),
+    /// Token usage update for the in-flight streaming request
+    StreamingUsage { input_tokens: u32, output_tokens: u32 },
+    /// Final usage for one completed agent-loop iteration, accumulated
+    /// into the session totals (persisted with the conversation)
+    SessionUsage { input_tokens: u32, output_tokens: u32 },
+    /// User message injected through the control socket
+    ControlInput(String),
+    /// Permission decision injected through the control socket
+    ControlPermission(PermissionDecision),
+    /// Transcript from /voice, inserted into the input for review
+    VoiceTranscript(String),
     TodosUpdated(Vec<crate::ai::todo_tool::Todo>),
     SetIterationLimit(bool, Option<Vec<crate::ai::Message>>),
     SetStreamCanceller(Option<std::sync::Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::UnboundedSender<()>>>>>),
 }
 
+/// Developer decision at a step-mode pause
+#[derive(Debug)]
+pub enum StepDecision {
+    /// Send the payload unchanged
+    Continue,
+    /// Abandon this request or tool call
+    Skip,
+    /// Send the edited payload instead
+    Edit(String),
+}
+
 /// Permission decision from user
 #[derive(Debug, Clone)]
 pub enum PermissionDecision {
@@ -58,6 +58,8 @@ pub struct PrintOptions {
     pub mcp_config: Option<String>,
     pub permission_prompt_tool: Option<String>,
     pub dangerously_skip_permissions: bool,
+    pub output_schema: Option<PathBuf>,
+    pub stream_text: bool,
 }
 
 /// Message structure for JSON output
@@ -80,28 +82,48 @@ pub struct ToolUse {
     pub output: Option<Value>,
 }
 
-/// Stream event for streaming JSON
+/// One newline-delimited JSON event on stdout in --output-format
+/// stream-json: message deltas, tool calls with their results, token
+/// usage, and a final aggregated result, so wrappers and CI jobs can
+/// parse the run programmatically instead of scraping text
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum StreamEvent {
     Start {
         session_id: String,
         model: String,
     },
+    /// A text delta (role "assistant") or the echoed prompt (role "user")
     Message {
         role: String,
         content: String,
     },
+    /// A tool call the model requested, with its full parsed input
     ToolUse {
+        id: String,
         name: String,
         input: Value,
     },
     ToolResult {
+        id: String,
+        name: String,
         output: Value,
     },
+    /// Token usage reported by the API while streaming
+    Usage {
+        input_tokens: u32,
+        output_tokens: u32,
+    },
     Error {
         message: String,
     },
+    /// Final aggregated result, emitted once before the closing event
+    Result {
+        content: String,
+        stop_reason: Option<String>,
+        input_tokens: u32,
+        output_tokens: u32,
+    },
     End {
         reason: String,
     },
@@ -149,11 +171,20 @@ pub async fn run(options: PrintOptions) -> Result<()> {
         context.add_system_message(&system_prompt);
     }
     
-    // Process the conversation
-    match options.output_format {
-        OutputFormat::Text => process_text_output(&mut context, &input).await?,
-        OutputFormat::Json => process_json_output(&mut context, &input).await?,
-        OutputFormat::StreamJson => process_stream_json_output(&mut context, &input).await?,
+    // Process the conversation. --output-schema overrides the output
+    // format: the result is the schema-conforming JSON object itself
+    if let Some(schema_path) = options.output_schema.clone() {
+        process_schema_output(&mut context, &input, &schema_path).await?;
+    } else if options.stream_text {
+        // --stream-text overrides the output format: raw text deltas go
+        // to stdout as they arrive, everything else to stderr
+        process_stream_text_output(&mut context, &input).await?;
+    } else {
+        match options.output_format {
+            OutputFormat::Text => process_text_output(&mut context, &input).await?,
+            OutputFormat::Json => process_json_output(&mut context, &input).await?,
+            OutputFormat::StreamJson => process_stream_json_output(&mut context, &input).await?,
+        }
     }
     
     // Track telemetry
@@ -617,6 +648,9 @@ async fn process_stream_json_output(context: &mut ConversationContext, input: &s
 
     let mut stream = Box::pin(stream);
     let mut accumulated_text = String::new();
+    let mut input_tokens = 0u32;
+    let mut output_tokens = 0u32;
+    let mut stop_reason: Option<String> = None;
 
     while let Some(event) = stream.next().await {
         match event {
@@ -660,23 +694,44 @@ async fn process_stream_json_output(context: &mut ConversationContext, input: &s
                             ContentDelta::SignatureDelta { .. } => {
                                 // Signature deltas are internal
                             }
+                            ContentDelta::CitationsDelta { .. } => {
+                                // Citations render as footnotes in the TUI;
+                                // print mode emits the text stream only
+                            }
                         }
                     }
                     AIStreamEvent::ContentBlockStop { .. } => {},
-                    AIStreamEvent::MessageStart { .. } => {},
-                    AIStreamEvent::MessageDelta { .. } => {},
+                    AIStreamEvent::MessageStart { message } => {
+                        input_tokens = message.usage.input_tokens;
+                    }
+                    AIStreamEvent::MessageDelta { delta, usage } => {
+                        output_tokens = usage.output_tokens;
+                        if let Some(reason) = delta.stop_reason {
+                            stop_reason = Some(format!("{:?}", reason));
+                        }
+                        let usage_event = StreamEvent::Usage {
+                            input_tokens,
+                            output_tokens,
+                        };
+                        writer.write_all(serde_json::to_string(&usage_event)?.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                    }
                     AIStreamEvent::MessageStop => {},
-                    AIStreamEvent::ToolUseStart { id: _, name } => {
+                    // The complete tool_use event (with its parsed input)
+                    // goes out at ToolUseStop below
+                    AIStreamEvent::ToolUseStart { .. } => {},
+                    AIStreamEvent::ToolUseDelta { .. } => {},
+                    AIStreamEvent::ToolUseStop { id, name, input } => {
                         let tool_event = StreamEvent::ToolUse {
+                            id: id.clone(),
                             name: name.clone(),
-                            input: serde_json::Value::Null,
+                            input: input.clone(),
                         };
                         writer.write_all(serde_json::to_string(&tool_event)?.as_bytes()).await?;
                         writer.write_all(b"\n").await?;
                         writer.flush().await?;
-                    }
-                    AIStreamEvent::ToolUseDelta { .. } => {},
-                    AIStreamEvent::ToolUseStop { id: _, name, input } => {
+
                         if !context.options.dangerously_skip_permissions {
                             // Show spinner for tool execution
                             let tool_progress = create_progress_spinner(format!("Executing {}...", name));
@@ -689,6 +744,8 @@ async fn process_stream_json_output(context: &mut ConversationContext, input: &s
                                 Ok(result) => {
                                     if let crate::ai::ContentPart::ToolResult { content, .. } = result {
                                         let result_event = StreamEvent::ToolResult {
+                                            id: id.clone(),
+                                            name: name.clone(),
                                             output: serde_json::json!({ "result": content }),
                                         };
                                         writer.write_all(serde_json::to_string(&result_event)?.as_bytes()).await?;
@@ -697,10 +754,12 @@ async fn process_stream_json_output(context: &mut ConversationContext, input: &s
                                     }
                                 }
                                 Err(e) => {
-                                    let error_event = StreamEvent::Error {
-                                        message: e.to_string(),
+                                    let result_event = StreamEvent::ToolResult {
+                                        id: id.clone(),
+                                        name: name.clone(),
+                                        output: serde_json::json!({ "error": e.to_string() }),
                                     };
-                                    writer.write_all(serde_json::to_string(&error_event)?.as_bytes()).await?;
+                                    writer.write_all(serde_json::to_string(&result_event)?.as_bytes()).await?;
                                     writer.write_all(b"\n").await?;
                                     writer.flush().await?;
                                 }
@@ -730,8 +789,18 @@ async fn process_stream_json_output(context: &mut ConversationContext, input: &s
     }
     
     context.add_assistant_message(&accumulated_text);
-    
-    // Send end event
+
+    // Send the final aggregated result, then the end event
+    let result_event = StreamEvent::Result {
+        content: accumulated_text.clone(),
+        stop_reason,
+        input_tokens,
+        output_tokens,
+    };
+    writer.write_all(serde_json::to_string(&result_event)?.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await?;
+
     let end_event = StreamEvent::End {
         reason: "completed".to_string(),
     };
@@ -742,6 +811,112 @@ async fn process_stream_json_output(context: &mut ConversationContext, input: &s
     Ok(())
 }
 
+/// Process streaming text output (--stream-text)
+///
+/// Raw text deltas go to stdout and are flushed as they arrive, so the
+/// output can be piped into pagers, `tee`, or downstream processors in
+/// real time. Tool activity and errors go to stderr, keeping stdout pure
+/// model text. No spinners: they would interleave with the tool chatter.
+async fn process_stream_text_output(context: &mut ConversationContext, input: &str) -> Result<()> {
+    context.add_user_message(input);
+
+    // Create AI client
+    let ai_client = crate::ai::create_client().await?;
+
+    // Build request
+    let mut request = ai_client
+        .create_chat_request()
+        .messages(context.get_ai_messages())
+        .max_tokens(4096)
+        .stream();
+
+    if let Some(system) = &context.options.system_prompt {
+        request = request.system(system.clone());
+    }
+
+    // Add tools if not disabled
+    if !context.options.dangerously_skip_permissions {
+        let tool_executor = crate::ai::tools::ToolExecutor::new();
+        let tools = tool_executor.get_available_tools();
+        if !tools.is_empty() {
+            request = request.tools(tools);
+        }
+    }
+
+    // Send request and stream response
+    let stream = ai_client.chat_stream(request.build()).await?;
+
+    let mut stream = Box::pin(stream);
+    let mut accumulated_text = String::new();
+    let mut stdout = io::stdout();
+
+    // Emit one delta to stdout, unbuffered
+    let emit = |stdout: &mut io::Stdout, text: &str| -> Result<()> {
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    };
+
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(chunk) => match chunk {
+                AIStreamEvent::ContentDelta { delta } => {
+                    if let StreamDelta::TextDelta { text } = delta {
+                        emit(&mut stdout, &text)?;
+                        accumulated_text.push_str(&text);
+                    }
+                }
+                AIStreamEvent::ContentBlockDelta { delta, .. } => {
+                    if let ContentDelta::TextDelta { text } = delta {
+                        emit(&mut stdout, &text)?;
+                        accumulated_text.push_str(&text);
+                    }
+                    // Thinking, signature, citation, and tool-input deltas
+                    // never reach stdout in this mode
+                }
+                AIStreamEvent::ToolUseStart { id: _, name } => {
+                    eprintln!("[tool] {}", name);
+                }
+                AIStreamEvent::ToolUseStop { id: _, name, input } => {
+                    if !context.options.dangerously_skip_permissions {
+                        let tool_executor = crate::ai::tools::ToolExecutor::new();
+                        match tool_executor.execute(&name, input.clone()).await {
+                            Ok(result) => {
+                                if let crate::ai::ContentPart::ToolResult { content, .. } = result {
+                                    // One-line summary only; full output
+                                    // would drown the stderr channel
+                                    let summary: String = content.lines().next().unwrap_or("").chars().take(120).collect();
+                                    eprintln!("[tool] {} done: {}", name, summary);
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("[tool] {} failed: {}", name, e);
+                            }
+                        }
+                    }
+                }
+                AIStreamEvent::Error(error) => {
+                    eprintln!("Error: {}", error);
+                }
+                _ => {}
+            },
+            Err(e) => {
+                eprintln!("Error: {}", e);
+            }
+        }
+    }
+
+    // Terminate the stream with a newline unless the model already did,
+    // so shells and line-based consumers see a complete final line
+    if !accumulated_text.is_empty() && !accumulated_text.ends_with('\n') {
+        emit(&mut stdout, "\n")?;
+    }
+
+    context.add_assistant_message(&accumulated_text);
+
+    Ok(())
+}
+
 /// Get last session ID
 async fn get_last_session_id() -> Result<String> {
     // Load last session ID from config
@@ -755,4 +930,155 @@ async fn get_last_session_id() -> Result<String> {
     
     // Generate new ID if none exists
     Ok(crate::utils::generate_session_id())
-}
\ No newline at end of file
+}
+/// Name of the synthetic tool used to force schema-conforming output
+const SCHEMA_TOOL_NAME: &str = "structured_output";
+
+/// How many times to ask the model again when its JSON fails validation
+const MAX_SCHEMA_ATTEMPTS: usize = 3;
+
+/// Process structured output mode (--output-schema)
+///
+/// The schema is presented as the input schema of a forced tool call, so
+/// the model can only respond with JSON in the requested shape. The
+/// result is validated locally and validation errors are fed back for a
+/// retry; the final object is printed to stdout as the entire output.
+async fn process_schema_output(
+    context: &mut ConversationContext,
+    input: &str,
+    schema_path: &std::path::Path,
+) -> Result<()> {
+    let schema_text = std::fs::read_to_string(schema_path).map_err(|e| {
+        Error::InvalidInput(format!("Failed to read {}: {}", schema_path.display(), e))
+    })?;
+    let schema: Value = serde_json::from_str(&schema_text).map_err(|e| {
+        Error::InvalidInput(format!("{} is not valid JSON: {}", schema_path.display(), e))
+    })?;
+    let compiled = jsonschema::JSONSchema::compile(&schema).map_err(|e| {
+        Error::InvalidInput(format!(
+            "{} is not a valid JSON Schema: {}",
+            schema_path.display(),
+            e
+        ))
+    })?;
+
+    context.add_user_message(input);
+
+    // Create AI client
+    let ai_client = crate::ai::create_client().await?;
+
+    let mut last_error = String::new();
+    for _attempt in 0..MAX_SCHEMA_ATTEMPTS {
+        // Build request forcing the structured output tool
+        let mut request = ai_client
+            .create_chat_request()
+            .messages(context.get_ai_messages())
+            .max_tokens(4096)
+            .tools(vec![crate::ai::Tool::Standard {
+                name: SCHEMA_TOOL_NAME.to_string(),
+                description: "Record the final answer as a JSON object conforming to the required schema".to_string(),
+                input_schema: schema.clone(),
+            }])
+            .tool_choice(crate::ai::ToolChoice::Tool {
+                name: SCHEMA_TOOL_NAME.to_string(),
+            });
+
+        if let Some(system) = &context.options.system_prompt {
+            request = request.system(system.clone());
+        }
+
+        // Show spinner while waiting for response
+        let progress = create_progress_spinner("Processing...");
+        let response = ai_client.chat(request.build()).await?;
+        progress.finish_and_clear();
+
+        let candidate = response.content.iter().find_map(|part| match part {
+            crate::ai::ContentPart::ToolUse { name, input, .. } if name == SCHEMA_TOOL_NAME => {
+                Some(input.clone())
+            }
+            _ => None,
+        });
+
+        let candidate = match candidate {
+            Some(candidate) => candidate,
+            None => {
+                last_error = "the model did not produce a structured result".to_string();
+                context.add_user_message(&format!(
+                    "You must call the {} tool with the JSON result.",
+                    SCHEMA_TOOL_NAME
+                ));
+                continue;
+            }
+        };
+
+        match validate_against_schema(&compiled, &candidate) {
+            Ok(()) => {
+                context.add_assistant_message(&candidate.to_string());
+                println!("{}", serde_json::to_string_pretty(&candidate)?);
+                return Ok(());
+            }
+            Err(errors) => {
+                // Record the failing attempt and feed the errors back so
+                // the retry can correct them
+                context.add_tool_use(SCHEMA_TOOL_NAME, candidate, None);
+                context.add_user_message(&format!(
+                    "The JSON did not validate against the schema:\n{}\nCall {} again with corrected JSON.",
+                    errors, SCHEMA_TOOL_NAME
+                ));
+                last_error = errors;
+            }
+        }
+    }
+
+    Err(Error::Other(format!(
+        "No schema-conforming output after {} attempts. Last error:\n{}",
+        MAX_SCHEMA_ATTEMPTS, last_error
+    )))
+}
+
+/// Validate a candidate value, returning all validation errors as one
+/// message suitable for both the user and the retry prompt
+fn validate_against_schema(
+    schema: &jsonschema::JSONSchema,
+    value: &Value,
+) -> std::result::Result<(), String> {
+    match schema.validate(value) {
+        Ok(()) => Ok(()),
+        Err(errors) => Err(errors
+            .map(|e| format!("- {} (at instance path \"{}\")", e, e.instance_path))
+            .collect::<Vec<_>>()
+            .join("\n")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_against_schema_reports_all_errors() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "count": { "type": "integer" }
+            },
+            "required": ["name", "count"]
+        });
+        let compiled = jsonschema::JSONSchema::compile(&schema).unwrap();
+
+        assert!(validate_against_schema(
+            &compiled,
+            &serde_json::json!({ "name": "ok", "count": 3 })
+        )
+        .is_ok());
+
+        let errors = validate_against_schema(
+            &compiled,
+            &serde_json::json!({ "name": 7 })
+        )
+        .unwrap_err();
+        assert!(errors.contains("\"count\" is a required property"));
+        assert!(errors.contains("instance path \"/name\""));
+    }
+}
@@ -0,0 +1,387 @@
+//! Read-only replay of a saved session (`llminate replay <session-id>`).
+//!
+//! Plays a transcript back turn by turn without re-executing anything:
+//! step forward and backward through turns, jump to the next tool call,
+//! or scroll freely. Useful for demos, for reviewing what the agent did,
+//! and for debugging a session after the fact.
+
+use crate::error::{Error, Result};
+use crate::session_store::{load_session, SessionRecord, StoredMessage};
+use crate::tui::markdown::parse_markdown;
+use crate::tui::{init_terminal, restore_terminal};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use std::time::Duration;
+
+/// Playback state: which turns are revealed and where the viewport is
+struct ReplayViewer {
+    record: SessionRecord,
+    /// Message index where each turn starts (a user message opens a turn)
+    turn_starts: Vec<usize>,
+    /// Number of turns currently revealed (1..=turn_starts.len())
+    revealed: usize,
+    scroll: usize,
+    /// Keep the viewport pinned to the end of the revealed transcript
+    /// until the user scrolls manually
+    follow: bool,
+}
+
+impl ReplayViewer {
+    fn new(record: SessionRecord) -> Self {
+        let mut turn_starts = Vec::new();
+        for (index, msg) in record.messages.iter().enumerate() {
+            // Anything before the first user message (banners, system
+            // notes) belongs to an implicit opening turn
+            if msg.role == "user" || (index == 0 && !record.messages.is_empty()) {
+                if turn_starts.last() != Some(&index) {
+                    turn_starts.push(index);
+                }
+            }
+        }
+        Self {
+            record,
+            turn_starts,
+            revealed: 1,
+            scroll: 0,
+            follow: true,
+        }
+    }
+
+    fn total_turns(&self) -> usize {
+        self.turn_starts.len()
+    }
+
+    /// Messages belonging to the revealed turns
+    fn visible_messages(&self) -> &[StoredMessage] {
+        let end = self
+            .turn_starts
+            .get(self.revealed)
+            .copied()
+            .unwrap_or(self.record.messages.len());
+        &self.record.messages[..end]
+    }
+
+    /// Messages of one turn, for inspecting what a step would reveal
+    fn turn_messages(&self, turn: usize) -> &[StoredMessage] {
+        let start = self.turn_starts.get(turn).copied().unwrap_or(0);
+        let end = self
+            .turn_starts
+            .get(turn + 1)
+            .copied()
+            .unwrap_or(self.record.messages.len());
+        &self.record.messages[start..end]
+    }
+
+    fn next_turn(&mut self) {
+        if self.revealed < self.total_turns() {
+            self.revealed += 1;
+            self.follow = true;
+        }
+    }
+
+    fn prev_turn(&mut self) {
+        if self.revealed > 1 {
+            self.revealed -= 1;
+            self.follow = true;
+        }
+    }
+
+    /// Advance until the newly revealed turn contains a tool call
+    /// (command output in the transcript), or to the end if none is left
+    fn jump_to_tool_call(&mut self) {
+        let mut turn = self.revealed;
+        while turn < self.total_turns() {
+            if self
+                .turn_messages(turn)
+                .iter()
+                .any(|msg| msg.role == "command_output")
+            {
+                break;
+            }
+            turn += 1;
+        }
+        self.revealed = (turn + 1).min(self.total_turns());
+        self.follow = true;
+    }
+}
+
+/// Render one stored message into styled lines, shaped like the live
+/// chat view (markdown for assistant turns, indented tool output)
+fn message_lines(msg: &StoredMessage, lines: &mut Vec<Line<'static>>) {
+    match msg.role.as_str() {
+        "user" => {
+            let dot = if cfg!(target_os = "macos") { "⏺" } else { "●" };
+            for (index, line) in msg.content.lines().enumerate() {
+                if index == 0 {
+                    lines.push(Line::from(vec![
+                        Span::styled(dot, Style::default().fg(Color::Magenta)),
+                        Span::raw(" "),
+                        Span::styled(
+                            line.to_string(),
+                            Style::default().fg(Color::LightMagenta),
+                        ),
+                    ]));
+                } else {
+                    lines.push(Line::from(vec![
+                        Span::raw("   "),
+                        Span::styled(
+                            line.to_string(),
+                            Style::default().fg(Color::LightMagenta),
+                        ),
+                    ]));
+                }
+            }
+        }
+        "assistant" => {
+            lines.extend(parse_markdown(&msg.content).lines);
+        }
+        "command_output" => {
+            for line in msg.content.lines() {
+                let style = if line.starts_with('+') && !line.starts_with("+++") {
+                    Style::default().fg(Color::Green)
+                } else if line.starts_with('-') && !line.starts_with("---") {
+                    Style::default().fg(Color::Red)
+                } else if line.starts_with("@@") {
+                    Style::default().fg(Color::Cyan)
+                } else {
+                    Style::default().add_modifier(Modifier::DIM)
+                };
+                lines.push(Line::from(vec![
+                    Span::raw("     "),
+                    Span::styled(line.to_string(), style),
+                ]));
+            }
+        }
+        "error" => {
+            for line in msg.content.lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("✗ {}", line),
+                    Style::default().fg(Color::Red),
+                )));
+            }
+        }
+        _ => {
+            for line in msg.content.lines() {
+                lines.push(Line::from(Span::styled(
+                    line.to_string(),
+                    Style::default().add_modifier(Modifier::DIM),
+                )));
+            }
+        }
+    }
+    lines.push(Line::default());
+}
+
+/// Load the session from this project's scope, falling back to every
+/// other project so any id from the pickers can be replayed
+fn load_record(session_id: &str) -> Result<SessionRecord> {
+    let dir = crate::tui::state::get_conversation_dir();
+    if let Some(record) = load_session(&dir, session_id)? {
+        return Ok(record);
+    }
+    for other in crate::tui::state::all_project_conversation_dirs() {
+        if other == dir {
+            continue;
+        }
+        if let Ok(Some(record)) = load_session(&other, session_id) {
+            return Ok(record);
+        }
+    }
+    Err(Error::NotFound(format!(
+        "Session {} not found in {} (or any other project)",
+        session_id,
+        dir.display()
+    )))
+}
+
+/// Run the replay viewer until the user quits
+pub fn run(session_id: &str) -> Result<()> {
+    let record = load_record(session_id)?;
+    if record.messages.is_empty() {
+        return Err(Error::NotFound(format!(
+            "Session {} has no messages to replay",
+            session_id
+        )));
+    }
+
+    let mut viewer = ReplayViewer::new(record);
+    let mut terminal = init_terminal()?;
+    let result = run_loop(&mut terminal, &mut viewer);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+fn run_loop(
+    terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<std::io::Stderr>>,
+    viewer: &mut ReplayViewer,
+) -> Result<()> {
+    loop {
+        let mut lines = Vec::new();
+        for msg in viewer.visible_messages() {
+            message_lines(msg, &mut lines);
+        }
+
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(1), Constraint::Length(1)])
+                .split(f.area());
+
+            let viewport = chunks[0].height.saturating_sub(2) as usize;
+            let max_scroll = lines.len().saturating_sub(viewport);
+            if viewer.follow {
+                viewer.scroll = max_scroll;
+            } else {
+                viewer.scroll = viewer.scroll.min(max_scroll);
+            }
+
+            let title = format!(
+                " Replay: {} ({}) — read-only ",
+                viewer.record.session_id, viewer.record.model
+            );
+            let transcript = Paragraph::new(lines.clone())
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .scroll((viewer.scroll as u16, 0));
+            f.render_widget(transcript, chunks[0]);
+
+            let status = Line::from(vec![
+                Span::styled(
+                    format!(" Turn {}/{} ", viewer.revealed, viewer.total_turns()),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    "n/→ next · p/← prev · t next tool call · g/G start/end · j/k scroll · q quit",
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ]);
+            f.render_widget(Paragraph::new(status), chunks[1]);
+        })?;
+
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('n') | KeyCode::Right | KeyCode::Char(' ') => viewer.next_turn(),
+                KeyCode::Char('p') | KeyCode::Left => viewer.prev_turn(),
+                KeyCode::Char('t') => viewer.jump_to_tool_call(),
+                KeyCode::Char('g') | KeyCode::Home => {
+                    viewer.revealed = 1;
+                    viewer.follow = true;
+                }
+                KeyCode::Char('G') | KeyCode::End => {
+                    viewer.revealed = viewer.total_turns();
+                    viewer.follow = true;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    viewer.follow = false;
+                    viewer.scroll = viewer.scroll.saturating_add(1);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    viewer.follow = false;
+                    viewer.scroll = viewer.scroll.saturating_sub(1);
+                }
+                KeyCode::PageDown => {
+                    viewer.follow = false;
+                    viewer.scroll = viewer.scroll.saturating_add(20);
+                }
+                KeyCode::PageUp => {
+                    viewer.follow = false;
+                    viewer.scroll = viewer.scroll.saturating_sub(20);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> StoredMessage {
+        StoredMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: 0,
+        }
+    }
+
+    fn record(messages: Vec<StoredMessage>) -> SessionRecord {
+        SessionRecord {
+            session_id: "test".to_string(),
+            model: "claude-test".to_string(),
+            messages,
+            timestamp: 0,
+            tool_schema_version: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_turns_start_at_user_messages() {
+        let viewer = ReplayViewer::new(record(vec![
+            message("system", "banner"),
+            message("user", "first"),
+            message("assistant", "reply"),
+            message("user", "second"),
+            message("assistant", "reply"),
+        ]));
+        // The banner forms an implicit opening turn before the first
+        // user message
+        assert_eq!(viewer.turn_starts, vec![0, 1, 3]);
+        assert_eq!(viewer.visible_messages().len(), 1);
+    }
+
+    #[test]
+    fn test_stepping_reveals_and_hides_turns() {
+        let mut viewer = ReplayViewer::new(record(vec![
+            message("user", "first"),
+            message("assistant", "reply"),
+            message("user", "second"),
+            message("assistant", "reply"),
+        ]));
+        assert_eq!(viewer.visible_messages().len(), 2);
+        viewer.next_turn();
+        assert_eq!(viewer.visible_messages().len(), 4);
+        viewer.next_turn();
+        assert_eq!(viewer.visible_messages().len(), 4);
+        viewer.prev_turn();
+        assert_eq!(viewer.visible_messages().len(), 2);
+        viewer.prev_turn();
+        assert_eq!(viewer.visible_messages().len(), 2);
+    }
+
+    #[test]
+    fn test_jump_to_tool_call_skips_plain_turns() {
+        let mut viewer = ReplayViewer::new(record(vec![
+            message("user", "first"),
+            message("assistant", "reply"),
+            message("user", "second"),
+            message("assistant", "reply"),
+            message("user", "third"),
+            message("command_output", "ran the tool"),
+            message("assistant", "done"),
+        ]));
+        viewer.jump_to_tool_call();
+        // Turns without tool output are skipped; the revealed transcript
+        // now ends with the tool-call turn
+        assert_eq!(viewer.revealed, 3);
+        assert!(viewer
+            .visible_messages()
+            .iter()
+            .any(|msg| msg.role == "command_output"));
+        // No further tool calls: jumping again reveals to the end
+        viewer.jump_to_tool_call();
+        assert_eq!(viewer.revealed, viewer.total_turns());
+    }
+}
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
 use tokio_util::sync::CancellationToken;
@@ -80,6 +80,390 @@ impl std::fmt::Debug for PendingPermission {
     }
 }
 
+/// What a key press did to the question flow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuestionFlowOutcome {
+    /// Still collecting answers
+    Open,
+    /// Every question answered; answers are ready to send back
+    Answered,
+    /// Dismissed with Esc before all questions were answered
+    Dismissed,
+}
+
+/// In-flight AskUserQuestion dialog flow: one select dialog per
+/// question, advanced as the user answers. An automatic "Other" choice
+/// opens a text input for custom answers (single-select questions
+/// only). The collected answers flow back to the agent loop through
+/// the responder and become the tool_result.
+pub struct QuestionFlow {
+    questions: Vec<crate::ai::ask_user_question_tool::Question>,
+    current: usize,
+    answers: HashMap<String, String>,
+    dialog: crate::tui::components::dialogs::SelectDialog,
+    other_input: Option<crate::tui::components::dialogs::TextInputDialog>,
+    responder: tokio::sync::oneshot::Sender<Option<HashMap<String, String>>>,
+}
+
+impl std::fmt::Debug for QuestionFlow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuestionFlow")
+            .field("current", &self.current)
+            .field("questions", &self.questions.len())
+            .field("answers", &self.answers)
+            .finish()
+    }
+}
+
+impl QuestionFlow {
+    pub fn new(
+        questions: Vec<crate::ai::ask_user_question_tool::Question>,
+        responder: tokio::sync::oneshot::Sender<Option<HashMap<String, String>>>,
+    ) -> Self {
+        let dialog = Self::dialog_for(&questions, 0);
+        Self {
+            questions,
+            current: 0,
+            answers: HashMap::new(),
+            dialog,
+            other_input: None,
+            responder,
+        }
+    }
+
+    /// Build the select dialog for one question. Single-select
+    /// questions get the automatic "Other" entry appended
+    fn dialog_for(
+        questions: &[crate::ai::ask_user_question_tool::Question],
+        index: usize,
+    ) -> crate::tui::components::dialogs::SelectDialog {
+        use crate::tui::components::dialogs::{SelectDialog, SelectItem};
+        let question = &questions[index];
+        let mut items: Vec<SelectItem> = question
+            .options
+            .iter()
+            .map(|option| {
+                let mut item = SelectItem::new(option.label.clone());
+                if let Some(description) = &option.description {
+                    item = item.with_description(description.clone());
+                }
+                item
+            })
+            .collect();
+        if !question.multi_select {
+            items.push(
+                SelectItem::new("Other").with_description("Type a custom answer".to_string()),
+            );
+        }
+        let title = if questions.len() > 1 {
+            format!("{} ({}/{})", question.header, index + 1, questions.len())
+        } else {
+            question.header.clone()
+        };
+        let mut dialog = SelectDialog::new(title, items).with_prompt(question.question.clone());
+        if question.multi_select {
+            dialog = dialog.multi_select();
+        }
+        dialog
+    }
+
+    /// Record an answer for the current question and move to the next
+    fn record_and_advance(&mut self, answer: String) -> QuestionFlowOutcome {
+        let question_text = self.questions[self.current].question.clone();
+        self.answers.insert(question_text, answer);
+        self.current += 1;
+        self.other_input = None;
+        if self.current >= self.questions.len() {
+            QuestionFlowOutcome::Answered
+        } else {
+            self.dialog = Self::dialog_for(&self.questions, self.current);
+            QuestionFlowOutcome::Open
+        }
+    }
+
+    pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> QuestionFlowOutcome {
+        use crate::tui::components::dialogs::DialogAction;
+
+        // "Other" text entry takes the keyboard while open; Esc backs
+        // out to the option list, not out of the whole flow
+        if let Some(other_input) = &mut self.other_input {
+            return match other_input.handle_key(key) {
+                DialogAction::SubmittedText(text) if !text.trim().is_empty() => {
+                    self.record_and_advance(text.trim().to_string())
+                }
+                DialogAction::Cancelled => {
+                    self.other_input = None;
+                    QuestionFlowOutcome::Open
+                }
+                _ => QuestionFlowOutcome::Open,
+            };
+        }
+
+        let option_count = self.questions[self.current].options.len();
+        match self.dialog.handle_key(key) {
+            DialogAction::Submitted(index) => {
+                if index >= option_count {
+                    // The automatic "Other" entry
+                    self.other_input = Some(
+                        crate::tui::components::dialogs::TextInputDialog::new(
+                            self.questions[self.current].header.clone(),
+                        )
+                        .with_prompt(self.questions[self.current].question.clone()),
+                    );
+                    QuestionFlowOutcome::Open
+                } else {
+                    let label = self.questions[self.current].options[index].label.clone();
+                    self.record_and_advance(label)
+                }
+            }
+            DialogAction::SubmittedMany(indices) => {
+                let labels: Vec<String> = indices
+                    .into_iter()
+                    .filter(|index| *index < option_count)
+                    .map(|index| self.questions[self.current].options[index].label.clone())
+                    .collect();
+                if labels.is_empty() {
+                    // Nothing marked yet: keep the dialog open
+                    QuestionFlowOutcome::Open
+                } else {
+                    self.record_and_advance(labels.join(", "))
+                }
+            }
+            DialogAction::Cancelled => QuestionFlowOutcome::Dismissed,
+            _ => QuestionFlowOutcome::Open,
+        }
+    }
+
+    pub fn render(&mut self, f: &mut ratatui::Frame, area: ratatui::layout::Rect) {
+        if let Some(other_input) = &self.other_input {
+            other_input.render(f, area);
+        } else {
+            self.dialog.render(f, area);
+        }
+    }
+
+    /// Send the collected answers (or the dismissal) back to the agent
+    /// loop, consuming the flow
+    pub fn respond(self, answered: bool) {
+        let payload = if answered { Some(self.answers) } else { None };
+        let _ = self.responder.send(payload);
+    }
+}
+
+/// What a key press did to the plan approval dialog
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanApprovalOutcome {
+    /// Still editing
+    Open,
+    /// Approved with Ctrl+S; the edited plan is ready to send back
+    Approved,
+    /// Rejected with Esc
+    Rejected,
+}
+
+/// In-flight ExitPlanMode approval: the plan is rendered as an editable
+/// document, so the user can correct steps before approving. The edited
+/// text flows back to the agent loop through the responder and becomes
+/// the tool_result.
+pub struct PlanApprovalFlow {
+    dialog: crate::tui::components::dialogs::TextAreaDialog,
+    responder: tokio::sync::oneshot::Sender<Option<String>>,
+}
+
+impl std::fmt::Debug for PlanApprovalFlow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlanApprovalFlow").finish()
+    }
+}
+
+impl PlanApprovalFlow {
+    pub fn new(plan: String, responder: tokio::sync::oneshot::Sender<Option<String>>) -> Self {
+        let dialog = crate::tui::components::dialogs::TextAreaDialog::new("Plan approval")
+            .with_prompt("Review the plan — tweak any steps, then Ctrl+S to approve".to_string())
+            .with_text(plan);
+        Self { dialog, responder }
+    }
+
+    pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> PlanApprovalOutcome {
+        use crate::tui::components::dialogs::DialogAction;
+        match self.dialog.handle_key(key) {
+            DialogAction::SubmittedText(_) => PlanApprovalOutcome::Approved,
+            DialogAction::Cancelled => PlanApprovalOutcome::Rejected,
+            _ => PlanApprovalOutcome::Open,
+        }
+    }
+
+    pub fn render(&mut self, f: &mut ratatui::Frame, area: ratatui::layout::Rect) {
+        self.dialog.render(f, area);
+    }
+
+    /// Send the edited plan (or the rejection) back to the agent loop,
+    /// consuming the flow
+    pub fn respond(self, approved: bool) {
+        let payload = if approved { Some(self.dialog.text()) } else { None };
+        let _ = self.responder.send(payload);
+    }
+}
+
+/// What a key press did to the step-mode dialog
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepPauseOutcome {
+    /// Still inspecting
+    Open,
+    /// Ctrl+S: send the (possibly edited) payload onward
+    Continue,
+    /// Esc: skip this request or tool call entirely
+    Skip,
+}
+
+/// In-flight step-mode pause (/debug step on): the exact payload about
+/// to go out - a model request's message array or a tool input - is
+/// rendered as an editable document so prompt-construction bugs are
+/// visible before they hit the wire. Edits apply to this send only.
+pub struct StepFlow {
+    dialog: crate::tui::components::dialogs::TextAreaDialog,
+    original: String,
+    responder: tokio::sync::oneshot::Sender<crate::tui::StepDecision>,
+}
+
+impl std::fmt::Debug for StepFlow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StepFlow").finish()
+    }
+}
+
+impl StepFlow {
+    pub fn new(
+        stage: String,
+        payload: String,
+        responder: tokio::sync::oneshot::Sender<crate::tui::StepDecision>,
+    ) -> Self {
+        let dialog =
+            crate::tui::components::dialogs::TextAreaDialog::new(format!("Step mode: {}", stage))
+                .with_prompt("Ctrl+S to continue (edits apply to this send only), Esc to skip".to_string())
+                .with_text(payload.clone());
+        Self {
+            dialog,
+            original: payload,
+            responder,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> StepPauseOutcome {
+        use crate::tui::components::dialogs::DialogAction;
+        match self.dialog.handle_key(key) {
+            DialogAction::SubmittedText(_) => StepPauseOutcome::Continue,
+            DialogAction::Cancelled => StepPauseOutcome::Skip,
+            _ => StepPauseOutcome::Open,
+        }
+    }
+
+    pub fn render(&mut self, f: &mut ratatui::Frame, area: ratatui::layout::Rect) {
+        self.dialog.render(f, area);
+    }
+
+    /// Send the decision back to the agent loop, consuming the flow
+    pub fn respond(self, proceed: bool) {
+        let decision = if !proceed {
+            crate::tui::StepDecision::Skip
+        } else {
+            let text = self.dialog.text();
+            if text == self.original {
+                crate::tui::StepDecision::Continue
+            } else {
+                crate::tui::StepDecision::Edit(text)
+            }
+        };
+        let _ = self.responder.send(decision);
+    }
+}
+
+/// What a key press did to the annotation dialog
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnnotationOutcome {
+    /// Still editing
+    Open,
+    /// Ctrl+S: store the verdict and note
+    Save,
+    /// Esc: discard
+    Cancel,
+}
+
+/// In-flight feedback capture (Ctrl+G): marks the latest assistant turn
+/// with 👍/👎 and an optional note. Annotations are stored with the
+/// transcript and included in session exports, so teams can collect
+/// verdicts on agent quality and feed them into prompt tuning.
+pub struct AnnotationFlow {
+    dialog: crate::tui::components::dialogs::TextAreaDialog,
+    pub message_index: usize,
+    thumbs_up: bool,
+}
+
+impl std::fmt::Debug for AnnotationFlow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnnotationFlow").finish()
+    }
+}
+
+impl AnnotationFlow {
+    fn title(thumbs_up: bool) -> String {
+        format!(
+            "Annotate response: {}",
+            if thumbs_up { "👍 helpful" } else { "👎 unhelpful" }
+        )
+    }
+
+    pub fn new(
+        message_index: usize,
+        existing: Option<&crate::session_store::MessageAnnotation>,
+    ) -> Self {
+        let thumbs_up = existing.map(|a| a.thumbs_up).unwrap_or(true);
+        let dialog =
+            crate::tui::components::dialogs::TextAreaDialog::new(Self::title(thumbs_up))
+                .with_prompt(
+                    "Tab toggles 👍/👎, note is optional · Ctrl+S to save, Esc to cancel"
+                        .to_string(),
+                )
+                .with_text(
+                    existing
+                        .and_then(|a| a.note.clone())
+                        .unwrap_or_default(),
+                );
+        Self {
+            dialog,
+            message_index,
+            thumbs_up,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> AnnotationOutcome {
+        use crate::tui::components::dialogs::DialogAction;
+        if key.code == crossterm::event::KeyCode::Tab {
+            self.thumbs_up = !self.thumbs_up;
+            self.dialog.set_title(Self::title(self.thumbs_up));
+            return AnnotationOutcome::Open;
+        }
+        match self.dialog.handle_key(key) {
+            DialogAction::SubmittedText(_) => AnnotationOutcome::Save,
+            DialogAction::Cancelled => AnnotationOutcome::Cancel,
+            _ => AnnotationOutcome::Open,
+        }
+    }
+
+    pub fn render(&mut self, f: &mut ratatui::Frame, area: ratatui::layout::Rect) {
+        self.dialog.render(f, area);
+    }
+
+    /// The finished annotation, consuming the flow
+    pub fn annotation(self) -> crate::session_store::MessageAnnotation {
+        let note = self.dialog.text().trim().to_string();
+        crate::session_store::MessageAnnotation {
+            thumbs_up: self.thumbs_up,
+            note: if note.is_empty() { None } else { Some(note) },
+        }
+    }
+}
+
 /// Application state
 #[derive(Debug)]
 pub struct AppState {
@@ -106,6 +490,9 @@ pub struct AppState {
     pub cache_expanded_state: bool, // What expanded state the cache represents
     pub terminal_size: (u16, u16),
     
+    // Resolved experimental feature flags (features section in settings.json)
+    pub feature_flags: crate::config::FeatureFlags,
+
     // Model and tools
     pub current_model: String,
     pub active_tools: HashMap<String, ToolInfo>,
@@ -115,6 +502,9 @@ pub struct AppState {
     // MCP servers
     pub mcp_servers: HashMap<String, McpClient>,
     pub mcp_server_status: HashMap<String, bool>,  // Server enabled/disabled status
+    // Prompts fetched from connected MCP servers, keyed by their slash
+    // command name (mcp__server__prompt) with the owning server name
+    pub mcp_prompts: HashMap<String, (String, crate::mcp::McpPrompt)>,
 
     // History
     pub command_history: VecDeque<String>,
@@ -129,12 +519,14 @@ pub struct AppState {
     // Conversation persistence
     pub conversation_dir: PathBuf,
     pub auto_save: bool,
+    /// Session picker shows every project's sessions, not just this one's
+    pub all_projects: bool,
     
     // Cancel channel
     pub cancel_tx: Option<tokio::sync::mpsc::UnboundedSender<()>>,
     
     // Agent loop infrastructure
-    pub agent_tx: Option<tokio::sync::mpsc::UnboundedSender<(String, Option<Vec<crate::ai::Message>>, String)>>,
+    pub agent_tx: Option<tokio::sync::mpsc::UnboundedSender<(String, Option<Vec<crate::ai::Message>>, String, Option<f32>, Option<u32>)>>,
     pub agent_handle: Option<tokio::task::JoinHandle<()>>,
     
     // Paste tracking (like JavaScript pastedContents)
@@ -148,6 +540,20 @@ pub struct AppState {
     // Permission dialog
     pub permission_dialog: crate::permissions::PermissionDialog,
     pub pending_permissions: std::collections::VecDeque<PendingPermission>,
+    /// Active AskUserQuestion dialog flow, if the model is waiting on answers
+    pub question_flow: Option<QuestionFlow>,
+    /// Active ExitPlanMode approval dialog, if the model is waiting on it
+    pub plan_approval: Option<PlanApprovalFlow>,
+    /// Active step-mode pause dialog, if the agent loop is waiting on it
+    pub step_flow: Option<StepFlow>,
+    /// Active feedback-capture dialog (Ctrl+G), if open
+    pub annotation_flow: Option<AnnotationFlow>,
+    /// Feedback left on messages (👍/👎 + note), keyed by message index;
+    /// saved with the transcript and included in exports
+    pub annotations: HashMap<usize, crate::session_store::MessageAnnotation>,
+    /// Step mode (/debug step on): shared with the agent loop, which
+    /// pauses before each model request and tool execution while set
+    pub step_mode: std::sync::Arc<std::sync::atomic::AtomicBool>,
     
     // Conversation continuation after permission
     pub continue_after_permission: bool,
@@ -162,11 +568,27 @@ pub struct AppState {
     pub session_picker_items: Vec<SessionInfo>,
 
     // Model picker dialog
-    pub show_model_picker: bool,
-    pub model_picker_selected: usize,
+    /// Model picker dialog (built on the generic dialog toolkit)
+    pub model_picker: Option<crate::tui::components::dialogs::SelectDialog>,
+    /// File tree picker overlay (e.g. /add-dir with no arguments)
+    pub file_picker: Option<crate::tui::components::file_picker::FilePicker>,
+    /// MCP server health dashboard overlay (opened with /mcp)
+    pub mcp_dashboard: Option<crate::tui::components::mcp_dashboard::McpDashboard>,
+    /// Context pruning overlay (opened with /prune)
+    pub prune_view: Option<crate::tui::components::prune_view::PruneView>,
+    /// Transcript indices the user excluded from the model context via
+    /// /prune; the messages stay visible in the transcript
+    pub context_excluded: std::collections::HashSet<usize>,
+    /// Configs the session's MCP servers were started from, kept so
+    /// reconnect can re-establish a connection without re-reading config
+    pub mcp_server_configs: HashMap<String, crate::config::McpServerConfig>,
 
     // Expanded view mode for Ctrl+R (toggles between collapsed/expanded view)
     pub expanded_view: bool,
+
+    // Reading width cap from config: chat content is limited to this many
+    // columns with centered margins on ultrawide terminals
+    pub reading_width: Option<u16>,
     
     // Input area state for dynamic height and paste handling
     pub input_expanded: bool,  // Whether input area is expanded (vs collapsed for large pastes)
@@ -176,6 +598,15 @@ pub struct AppState {
     // Task status display
     pub current_task_status: Option<String>,
     pub spinner_frame: usize,
+    /// When the in-flight request started (drives the elapsed-time display)
+    pub processing_started_at: Option<std::time::Instant>,
+    /// Token usage streamed so far for the in-flight request (from usage deltas)
+    pub streaming_input_tokens: u32,
+    pub streaming_output_tokens: u32,
+    /// Cumulative token usage across the whole session (persisted with
+    /// the conversation)
+    pub session_input_tokens: u64,
+    pub session_output_tokens: u64,
     /// Determinate progress (0.0 to 1.0) - None means indeterminate
     pub current_progress: Option<f64>,
     /// Whether terminal progress bar is enabled (matches JS terminalProgressBarEnabled)
@@ -187,6 +618,12 @@ pub struct AppState {
     
     // Loaded conversation context for resume
     pub loaded_ai_messages: Option<Vec<crate::ai::Message>>,
+
+    // Retry variant tracking for /retry and /variants
+    // The original response captured when /retry starts; becomes a variant
+    // pair once the regeneration completes
+    pub pending_retry_original: Option<String>,
+    pub retry_variants: Option<(String, String)>,
     
     // Stream cancellation tracking
     pub stream_cancel_tx: Option<Arc<Mutex<Option<tokio::sync::mpsc::UnboundedSender<()>>>>>,
@@ -223,6 +660,14 @@ pub struct AppState {
     // Thinking display (interleaved-thinking-2025-05-14 beta)
     pub current_thinking: Option<String>,
     pub thinking_start_time: Option<std::time::Instant>,
+    /// Extended thinking token budget for outgoing requests (None = disabled)
+    pub thinking_budget: Option<u32>,
+    /// Cached count_tokens results keyed by conversation fingerprint, so
+    /// repeated /context calls don't hit the API for an unchanged transcript
+    token_count_cache: std::collections::HashMap<u64, u64>,
+    /// Models discovered from the provider's catalog (Bedrock/Vertex),
+    /// populated by /models and consulted for /model aliases
+    discovered_models: Option<Vec<crate::ai::model_discovery::DiscoveredModel>>,
 
     // Chat display text selection
     pub chat_selection_start: Option<(usize, usize)>,  // (line, column)
@@ -268,6 +713,8 @@ impl AppState {
             scroll_offset: 0,
             terminal_size: (80, 24),
             
+            feature_flags: crate::config::get_feature_flags(),
+
             current_model: options.model.unwrap_or_else(|| "claude-opus-4-1-20250805".to_string()),
             active_tools,
             allowed_tools: options.allowed_tools,
@@ -275,6 +722,7 @@ impl AppState {
             
             mcp_servers: HashMap::new(),
             mcp_server_status: HashMap::new(),
+            mcp_prompts: HashMap::new(),
 
             command_history: VecDeque::with_capacity(1000),
             history_index: None,
@@ -286,6 +734,7 @@ impl AppState {
             
             conversation_dir,
             auto_save: true,
+            all_projects: options.all_projects,
             
             cancel_tx: None,
             
@@ -300,6 +749,12 @@ impl AppState {
             
             permission_dialog: crate::permissions::PermissionDialog::new(),
             pending_permissions: std::collections::VecDeque::new(),
+            question_flow: None,
+            plan_approval: None,
+            step_flow: None,
+            annotation_flow: None,
+            annotations: HashMap::new(),
+            step_mode: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
             continue_after_permission: false,
             pending_tool_result: None,
             
@@ -311,11 +766,19 @@ impl AppState {
             session_picker_selected: 0,
             session_picker_items: Vec::new(),
 
-            show_model_picker: false,
-            model_picker_selected: 0,
+            model_picker: None,
+            file_picker: None,
+            mcp_dashboard: None,
+            prune_view: None,
+            context_excluded: std::collections::HashSet::new(),
+            mcp_server_configs: HashMap::new(),
 
             expanded_view: false,
-            
+
+            reading_width: crate::config::load_config(crate::config::ConfigScope::User)
+                .ok()
+                .and_then(|config| config.reading_width),
+
             // Input area state
             input_expanded: true,  // Start expanded by default
             input_paste_detected: false,
@@ -327,11 +790,18 @@ impl AppState {
             
             current_task_status: None,
             spinner_frame: 0,
+            processing_started_at: None,
+            streaming_input_tokens: 0,
+            streaming_output_tokens: 0,
+            session_input_tokens: 0,
+            session_output_tokens: 0,
             current_progress: None,
             terminal_progress_bar_enabled: true,  // Enabled by default like JavaScript
             hit_iteration_limit: false,
             continuation_messages: None,
             loaded_ai_messages: None,
+            pending_retry_original: None,
+            retry_variants: None,
             stream_cancel_tx: None,
             last_spinner_update: std::time::Instant::now(),
             
@@ -364,6 +834,9 @@ impl AppState {
             // Thinking display
             current_thinking: None,
             thinking_start_time: None,
+            thinking_budget: None,
+            token_count_cache: std::collections::HashMap::new(),
+            discovered_models: None,
 
             // Chat display text selection
             chat_selection_start: None,
@@ -405,6 +878,37 @@ impl AppState {
             }
         }
 
+        // Managed enterprise policy: its permission rules and disallowed
+        // tools are applied on top of everything else and cannot be
+        // overridden by user/project settings or runtime toggles
+        let managed = crate::config::get_managed_permissions();
+        for tool in &managed.disallowed_tools {
+            if !state.disallowed_tools.contains(tool) {
+                state.disallowed_tools.push(tool.clone());
+            }
+        }
+        let guardrails = crate::config::get_guardrails();
+        if !managed.allow.is_empty() || !managed.deny.is_empty() || !guardrails.is_empty() {
+            tokio::task::block_in_place(|| {
+                let rt = tokio::runtime::Handle::current();
+                rt.block_on(async {
+                    if let Ok(mut ctx) = crate::permissions::PERMISSION_CONTEXT.try_lock() {
+                        for rule in &managed.allow {
+                            let (tool, pattern) = crate::config::parse_permission_rule(rule);
+                            ctx.always_allow_rules.entry(tool).or_default().push(pattern);
+                        }
+                        for rule in &managed.deny {
+                            let (tool, pattern) = crate::config::parse_permission_rule(rule);
+                            ctx.always_deny_rules.entry(tool).or_default().push(pattern);
+                        }
+                        // Guardrails: the deterministic half of the hard
+                        // constraints the system prompt states to the model
+                        ctx.guardrails = guardrails;
+                    }
+                });
+            });
+        }
+
         // Process add_dirs from CLI options - add to working directories and permission context
         // CLI options override settings
         if !options.add_dirs.is_empty() {
@@ -434,10 +938,44 @@ impl AppState {
         state
     }
     
+    /// Show the welcome header and this session's rotating tip
+    pub fn show_startup_banner(&mut self) {
+        for line in crate::tui::tips::startup_banner(&self.current_model) {
+            self.add_message(&line);
+        }
+        if let Some(tip) = crate::tui::tips::next_tip() {
+            self.add_message(&tip);
+        }
+        self.show_post_update_banner();
+    }
+
+    /// One-time banner after an update summarizing what changed since the
+    /// previously run version (tracked in the user config)
+    fn show_post_update_banner(&mut self) {
+        let Ok(mut config) = crate::config::load_config(crate::config::ConfigScope::User) else {
+            return;
+        };
+        let current = crate::changelog::current_version();
+        if let Some(prev) = config.last_release_notes_seen.clone() {
+            if prev != current {
+                if let Some(notes) = crate::changelog::notes_since(&prev) {
+                    self.add_command_output(&format!(
+                        "# Updated to v{} (from v{})\n\n{}\n\nUse /release-notes to revisit this later.",
+                        current, prev, notes
+                    ));
+                }
+            }
+        }
+        if config.last_release_notes_seen.as_deref() != Some(current) {
+            config.last_release_notes_seen = Some(current.to_string());
+            let _ = crate::config::save_config(crate::config::ConfigScope::User, &config);
+        }
+    }
+
     /// Start the persistent agent loop for the entire session
     pub fn start_agent_loop(&mut self) {
-        // Create message channel - sends tuples of (message, optional_loaded_messages, model)
-        let (agent_tx, mut agent_rx) = tokio::sync::mpsc::unbounded_channel::<(String, Option<Vec<crate::ai::Message>>, String)>();
+        // Create message channel - sends tuples of (message, optional_loaded_messages, model, temperature_override, thinking_budget)
+        let (agent_tx, mut agent_rx) = tokio::sync::mpsc::unbounded_channel::<(String, Option<Vec<crate::ai::Message>>, String, Option<f32>, Option<u32>)>();
         self.agent_tx = Some(agent_tx);
         
         // Create cancellation channel
@@ -452,6 +990,9 @@ impl AppState {
         // Clone the data needed for tool executor creation
         let allowed_tools = self.allowed_tools.clone();
         let disallowed_tools = self.disallowed_tools.clone();
+
+        // Step mode flag, shared so /debug step toggles apply mid-session
+        let step_mode = self.step_mode.clone();
         
         // Spawn the persistent agent loop
         let handle = tokio::spawn(async move {
@@ -468,6 +1009,10 @@ impl AppState {
             // This agent loop runs for the ENTIRE session
             let mut messages: Vec<crate::ai::Message> = Vec::new();
 
+            // Previous turn's request, kept (at debug level only) so the
+            // structural diff between consecutive requests can be logged
+            let mut previous_request: Option<crate::ai::ChatRequest> = None;
+
             // Create tool executor with cloned permissions
             let mut tool_executor = crate::ai::tools::ToolExecutor::new();
             tool_executor.set_allowed_tools(allowed_tools);
@@ -477,7 +1022,9 @@ impl AppState {
             // Process messages from the queue with cancellation support
             loop {
                 tokio::select! {
-                    Some((user_input, loaded_messages, current_model)) = agent_rx.recv() => {
+                    Some((user_input, loaded_messages, current_model, temperature_override, thinking_budget)) = agent_rx.recv() => {
+                // Each user turn gets a fresh clarifying-question budget
+                crate::ai::tools::reset_question_budget();
                 // Execute UserPromptSubmit hooks when user submits input
                 if !user_input.is_empty() {
                     let prompt_context = crate::hooks::HookContext::new(
@@ -540,6 +1087,13 @@ impl AppState {
                 // Agent loop for this message - continue until AI stops requesting tools
                 let mut iteration = if is_continuation { 0 } else { 0 }; // Reset on continuation
                 const MAX_ITERATIONS: usize = 25;  // Increased from 10 to match JS behavior
+
+                // Paths the write-capable tools touch this turn, for the
+                // end-of-turn diff stat shown in chat
+                let mut files_touched: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+                // Accumulated cost of this turn in dollars, for the cost guardrail
+                let mut turn_cost: f64 = 0.0;
                 
                 loop {
                     iteration += 1;
@@ -556,31 +1110,171 @@ impl AppState {
                         }
                         break;
                     }
-                    
+
+                    // Cost guardrail: before another model iteration, check
+                    // the turn's accumulated cost against the configured
+                    // limit and pause for approval via /continue
+                    if iteration > 1 {
+                        if let Some(limit) = crate::ai::models::turn_cost_limit() {
+                            if turn_cost >= limit {
+                                let stored_messages = messages.clone();
+                                if let Some(tx) = &event_tx {
+                                    let _ = tx.send(crate::tui::TuiEvent::SetIterationLimit(true, Some(stored_messages)));
+                                    let _ = tx.send(crate::tui::TuiEvent::Message(format!(
+                                        "This turn has cost ~${:.2}, reaching the ${:.2} limit. Use /continue to approve continuing, or /cost-limit to change the limit.",
+                                        turn_cost, limit
+                                    )));
+                                    let _ = tx.send(crate::tui::TuiEvent::UpdateTaskStatus(None));
+                                    let _ = tx.send(crate::tui::TuiEvent::ProcessingComplete);
+                                }
+                                break;
+                            }
+                        }
+                    }
+
+                    // Auto-compact when the transcript nears the context
+                    // window: older turns become a summary message, recent
+                    // turns and their tool results survive verbatim
+                    if crate::ai::summarization::should_compact(&messages, &current_model) {
+                        if let Some(tx) = &event_tx {
+                            let _ = tx.send(crate::tui::TuiEvent::UpdateTaskStatus(
+                                Some("Compacting conversation...".to_string()),
+                            ));
+                        }
+                        match crate::ai::summarization::auto_compact(
+                            &ai_client,
+                            &current_model,
+                            &mut messages,
+                        )
+                        .await
+                        {
+                            Ok(()) => {
+                                if let Some(tx) = &event_tx {
+                                    let _ = tx.send(crate::tui::TuiEvent::Message(
+                                        "✅ Context compacted automatically to stay within the model limit".to_string(),
+                                    ));
+                                }
+                            }
+                            Err(e) => {
+                                // Keep going with the full transcript; the
+                                // request itself may still fit
+                                if let Some(tx) = &event_tx {
+                                    let _ = tx.send(crate::tui::TuiEvent::Message(
+                                        format!("⚠ Auto-compaction failed: {}", e),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+
+                    // Step mode: pause with the exact message payload
+                    // before the request goes out, so prompt-construction
+                    // bugs are visible and fixable in place
+                    if step_mode.load(std::sync::atomic::Ordering::Relaxed) {
+                        if let Some(tx) = &event_tx {
+                            let payload = serde_json::to_string_pretty(&messages)
+                                .unwrap_or_else(|e| format!("(unserializable: {})", e));
+                            let (resp_tx, resp_rx) = tokio::sync::oneshot::channel();
+                            let _ = tx.send(crate::tui::TuiEvent::StepPause {
+                                stage: "model request".to_string(),
+                                payload,
+                                responder: resp_tx,
+                            });
+                            match resp_rx.await {
+                                Ok(crate::tui::StepDecision::Continue) => {}
+                                Ok(crate::tui::StepDecision::Edit(edited)) => {
+                                    match serde_json::from_str::<Vec<crate::ai::Message>>(&edited) {
+                                        Ok(new_messages) => messages = new_messages,
+                                        Err(e) => {
+                                            let _ = tx.send(crate::tui::TuiEvent::Error(format!(
+                                                "Step mode: edited payload is not a valid message array ({}); sending the original",
+                                                e
+                                            )));
+                                        }
+                                    }
+                                }
+                                _ => {
+                                    // Skip (or the dialog went away):
+                                    // abandon this request and hand the
+                                    // turn back to the user
+                                    let _ = tx.send(crate::tui::TuiEvent::Message(
+                                        "Step mode: model request skipped".to_string(),
+                                    ));
+                                    let _ = tx.send(crate::tui::TuiEvent::UpdateTaskStatus(None));
+                                    let _ = tx.send(crate::tui::TuiEvent::ProcessingComplete);
+                                    break;
+                                }
+                            }
+                        }
+                    }
+
                     // Build request
                     let mut request = ai_client
                         .create_chat_request()
                         .model(&current_model)
                         .messages(messages.clone())
-                        .max_tokens(4096)
-                        .temperature(0.7)
                         .stream();
-                    
-                    // Set system prompt
-                    let system = if let Some(prompt) = &system_prompt {
-                        prompt.clone()
+                    if let Some(budget) = thinking_budget {
+                        // max_tokens must exceed the thinking budget, and the
+                        // API requires temperature 1 when thinking is enabled
+                        request = request
+                            .max_tokens(budget + 4096)
+                            .temperature(1.0)
+                            .thinking(crate::ai::ThinkingConfig::enabled(budget));
                     } else {
-                        crate::ai::system_prompt::get_system_prompt("Claude Code")
-                    };
+                        request = request
+                            .max_tokens(4096)
+                            .temperature(temperature_override.unwrap_or(0.7));
+                    }
+
+                    // Service tier: the /tier override wins, then the
+                    // configured default
+                    if let Some(tier) = crate::ai::session_service_tier()
+                        .or_else(|| ai_client.config().service_tier.clone())
+                    {
+                        request = request.service_tier(tier);
+                    }
+
+                    // Set system prompt (base + memory + output style +
+                    // session appends, see ai::system_prompt)
+                    let mut system = crate::ai::system_prompt::assemble_session_prompt(
+                        system_prompt.as_deref(),
+                    );
+                    // Keep responses in the configured language (/lang,
+                    // responseLanguage in settings.json)
+                    if let Some(language) = crate::config::get_response_language() {
+                        system.push_str(&format!(
+                            "\n\nIMPORTANT: Always respond in {}. Code, identifiers, and command output stay as-is; all prose in your responses must be in {}.",
+                            language, language
+                        ));
+                    }
                     request = request.system(system);
-                    
+
                     // Add tools
                     if !tools.is_empty() {
                         request = request.tools(tools.clone());
                     }
-                    
+
+                    let request = request.build();
+
+                    // In debug mode, log how this request differs from the
+                    // last one so context-assembly regressions (duplicated
+                    // tool results, dropped messages) are visible
+                    if tracing::enabled!(tracing::Level::DEBUG) {
+                        if let Some(previous) = &previous_request {
+                            let findings = crate::ai::diff_chat_requests(previous, &request);
+                            if findings.is_empty() {
+                                tracing::debug!("Request diff: no structural changes");
+                            }
+                            for finding in findings {
+                                tracing::debug!("Request diff: {}", finding);
+                            }
+                        }
+                        previous_request = Some(request.clone());
+                    }
+
                     // Start streaming
-                    let stream = match ai_client.chat_stream(request.build()).await {
+                    let stream = match ai_client.chat_stream(request).await {
                         Ok(s) => s,
                         Err(e) => {
                             if let Some(tx) = &event_tx {
@@ -622,8 +1316,13 @@ impl AppState {
                     let mut current_text = String::new();
                     let mut pending_tools: std::collections::HashMap<String, String> = std::collections::HashMap::new();
                     let mut tool_uses: Vec<crate::ai::ContentPart> = Vec::new();  // Collect tool uses for assistant message
+                    let mut thinking_parts: Vec<crate::ai::ContentPart> = Vec::new();  // Thinking blocks, replayed for tool continuations
                     let mut tool_results = Vec::new();
                     let mut has_tool_use = false;
+                    // Latest cumulative usage reported for this iteration,
+                    // folded into turn_cost once the stream finishes
+                    let mut iteration_input_tokens: u32 = 0;
+                    let mut iteration_output_tokens: u32 = 0;
                     
                     // Process streaming updates with cancellation check
                     loop {
@@ -638,20 +1337,32 @@ impl AppState {
                                 pending_tools.insert(id.clone(), name.clone());
                                 // Don't set status yet - wait for ToolUseComplete to get the full input
                             }
-                            StreamingUpdate::ToolUseComplete { id, input } => {
+                            StreamingUpdate::ToolUseComplete { id, mut input } => {
                                 if let Some(tool_name) = pending_tools.remove(&id) {
                                     has_tool_use = true;
-                                    
+
+                                    // Record paths the file-modifying tools touch so the
+                                    // turn can end with a diff-stat summary
+                                    match tool_name.as_str() {
+                                        "Write" | "Edit" | "MultiEdit" => {
+                                            if let Some(path) = input["file_path"].as_str() {
+                                                files_touched.insert(path.to_string());
+                                            }
+                                        }
+                                        "NotebookEdit" => {
+                                            if let Some(path) = input["notebook_path"].as_str() {
+                                                files_touched.insert(path.to_string());
+                                            }
+                                        }
+                                        _ => {}
+                                    }
+
                                     // Format the tool status with action/command details
                                     let status_msg = match tool_name.as_str() {
                                         "Bash" => {
                                             if let Some(cmd) = input["command"].as_str() {
                                                 // Truncate long commands for display
-                                                let display_cmd = if cmd.len() > 50 {
-                                                    format!("{}...", &cmd[..47])
-                                                } else {
-                                                    cmd.to_string()
-                                                };
+                                                let display_cmd = crate::utils::truncate_display(cmd, 50);
                                                 format!("Bash({})", display_cmd)
                                             } else {
                                                 format!("Bash(executing command)")
@@ -685,11 +1396,7 @@ impl AppState {
                                         }
                                         "Search" | "Grep" => {
                                             if let Some(pattern) = input["pattern"].as_str() {
-                                                let display_pattern = if pattern.len() > 30 {
-                                                    format!("{}...", &pattern[..27])
-                                                } else {
-                                                    pattern.to_string()
-                                                };
+                                                let display_pattern = crate::utils::truncate_display(pattern, 30);
                                                 format!("Search({})", display_pattern)
                                             } else {
                                                 format!("Search(searching files)")
@@ -721,11 +1428,7 @@ impl AppState {
                                         }
                                         "WebSearch" => {
                                             if let Some(query) = input["query"].as_str() {
-                                                let display_query = if query.len() > 30 {
-                                                    format!("{}...", &query[..27])
-                                                } else {
-                                                    query.to_string()
-                                                };
+                                                let display_query = crate::utils::truncate_display(query, 30);
                                                 format!("WebSearch({})", display_query)
                                             } else {
                                                 format!("WebSearch(searching web)")
@@ -814,9 +1517,75 @@ impl AppState {
                                         } else {
                                             true
                                         }
+                                    } else if tool_name == "AskUserQuestion" && event_tx.is_some() {
+                                        // Render the questions as a TUI dialog and write the
+                                        // selected answers back into the input, so the tool's
+                                        // result echoes them to the model
+                                        match serde_json::from_value::<Vec<crate::ai::ask_user_question_tool::Question>>(
+                                            input["questions"].clone(),
+                                        ) {
+                                            Ok(questions) if !questions.is_empty() => {
+                                                if let Some(tx) = &event_tx {
+                                                    let (resp_tx, resp_rx) = tokio::sync::oneshot::channel();
+                                                    let _ = tx.send(crate::tui::TuiEvent::QuestionRequired {
+                                                        tool_use_id: id.clone(),
+                                                        questions,
+                                                        responder: resp_tx,
+                                                    });
+                                                    match resp_rx.await {
+                                                        Ok(Some(answers)) => {
+                                                            input["answers"] = serde_json::json!(answers);
+                                                            true
+                                                        }
+                                                        _ => {
+                                                            tool_results.push(crate::ai::ContentPart::ToolResult {
+                                                                tool_use_id: id.clone(),
+                                                                content: "User dismissed the question dialog without answering. Proceed with the most reasonable interpretation and state your assumptions.".to_string(),
+                                                                is_error: Some(false),
+                                                            });
+                                                            false
+                                                        }
+                                                    }
+                                                } else {
+                                                    true
+                                                }
+                                            }
+                                            // Malformed questions: execute anyway so the tool's
+                                            // own validation gives the model a correctable error
+                                            _ => true,
+                                        }
+                                    } else if tool_name == "ExitPlanMode" && event_tx.is_some() {
+                                        // Render the plan as an editable document; the approved
+                                        // (possibly edited) text is written back into the input
+                                        // so the tool_result echoes it to the model
+                                        let plan = input["plan"].as_str().unwrap_or("").to_string();
+                                        if let Some(tx) = &event_tx {
+                                            let (resp_tx, resp_rx) = tokio::sync::oneshot::channel();
+                                            let _ = tx.send(crate::tui::TuiEvent::PlanApprovalRequired {
+                                                tool_use_id: id.clone(),
+                                                plan,
+                                                responder: resp_tx,
+                                            });
+                                            match resp_rx.await {
+                                                Ok(Some(edited_plan)) => {
+                                                    input["plan"] = serde_json::json!(edited_plan);
+                                                    true
+                                                }
+                                                _ => {
+                                                    tool_results.push(crate::ai::ContentPart::ToolResult {
+                                                        tool_use_id: id.clone(),
+                                                        content: "User rejected the plan. Stay in plan mode and revise it based on their earlier feedback, or ask what should change.".to_string(),
+                                                        is_error: Some(true),
+                                                    });
+                                                    false
+                                                }
+                                            }
+                                        } else {
+                                            true
+                                        }
                                     } else if tool_name == "Bash" {
                                         let command = input["command"].as_str().unwrap_or("");
-                                        
+
                                         use crate::permissions::{check_command_permission, PermissionResult};
                                         match check_command_permission(command).await {
                                             PermissionResult::NeedsApproval => {
@@ -866,7 +1635,47 @@ impl AppState {
                                         true
                                     };
                                     
-                                    if should_execute {
+                                    // Step mode: show the exact tool input
+                                    // and wait for the developer before
+                                    // running an approved tool call
+                                    let mut step_skip = false;
+                                    if should_execute
+                                        && step_mode.load(std::sync::atomic::Ordering::Relaxed)
+                                    {
+                                        if let Some(tx) = &event_tx {
+                                            let payload = serde_json::to_string_pretty(&input)
+                                                .unwrap_or_else(|e| format!("(unserializable: {})", e));
+                                            let (resp_tx, resp_rx) = tokio::sync::oneshot::channel();
+                                            let _ = tx.send(crate::tui::TuiEvent::StepPause {
+                                                stage: format!("{} tool call", tool_name),
+                                                payload,
+                                                responder: resp_tx,
+                                            });
+                                            match resp_rx.await {
+                                                Ok(crate::tui::StepDecision::Continue) => {}
+                                                Ok(crate::tui::StepDecision::Edit(edited)) => {
+                                                    match serde_json::from_str::<serde_json::Value>(&edited) {
+                                                        Ok(new_input) => input = new_input,
+                                                        Err(e) => {
+                                                            let _ = tx.send(crate::tui::TuiEvent::Error(format!(
+                                                                "Step mode: edited input is not valid JSON ({}); running the original",
+                                                                e
+                                                            )));
+                                                        }
+                                                    }
+                                                }
+                                                _ => step_skip = true,
+                                            }
+                                        }
+                                    }
+
+                                    if step_skip {
+                                        tool_results.push(crate::ai::ContentPart::ToolResult {
+                                            tool_use_id: id.clone(),
+                                            content: "Tool call skipped by the developer in step mode. The tool did not run.".to_string(),
+                                            is_error: Some(true),
+                                        });
+                                    } else if should_execute {
                                         let tool_context = crate::ai::tools::ToolContext {
                                             tool_use_id: id.clone(),
                                             session_id: session_id.clone(),
@@ -974,10 +1783,13 @@ impl AppState {
                                     if let Some(tx) = &event_tx {
                                         let _ = tx.send(crate::tui::TuiEvent::Message(current_text.clone()));
                                     }
+                                    // Read the completed response aloud when /tts is on
+                                    crate::tui::tts::speak_async(&current_text);
                                 }
                                 
-                                // Build assistant message with both text and tool uses
-                                let mut assistant_parts = Vec::new();
+                                // Build assistant message with thinking, text and tool uses
+                                // (thinking blocks come first, matching the wire order)
+                                let mut assistant_parts = thinking_parts.clone();
                                 if !current_text.is_empty() {
                                     assistant_parts.push(crate::ai::ContentPart::Text {
                                         text: current_text.clone(),
@@ -986,7 +1798,7 @@ impl AppState {
                                 }
                                 // Add all tool uses to the assistant message
                                 assistant_parts.extend(tool_uses.clone());
-                                
+
                                 // Add assistant message to conversation
                                 if !assistant_parts.is_empty() {
                                     messages.push(crate::ai::Message {
@@ -1094,6 +1906,24 @@ impl AppState {
                                 }
                                 break;
                             }
+                            StreamingUpdate::UsageDelta { input_tokens, output_tokens } => {
+                                iteration_input_tokens = input_tokens;
+                                iteration_output_tokens = output_tokens;
+                                if let Some(tx) = &event_tx {
+                                    let _ = tx.send(crate::tui::TuiEvent::StreamingUsage { input_tokens, output_tokens });
+                                }
+                            }
+                            StreamingUpdate::ThinkingComplete { thinking, signature } => {
+                                // Keep signed thinking blocks so tool-use
+                                // continuations can replay them verbatim
+                                thinking_parts.push(crate::ai::ContentPart::Thinking {
+                                    thinking,
+                                    signature,
+                                });
+                            }
+                            StreamingUpdate::RedactedThinking { data } => {
+                                thinking_parts.push(crate::ai::ContentPart::RedactedThinking { data });
+                            }
                             _ => {}
                         }
                             }
@@ -1113,10 +1943,30 @@ impl AppState {
                     if let Some(tx) = &event_tx {
                         let _ = tx.send(crate::tui::TuiEvent::SetStreamCanceller(None));
                     }
-                    
+
+                    // Fold this iteration's usage into the turn cost for the guardrail
+                    turn_cost += crate::ai::models::estimate_usage_cost(
+                        &current_model,
+                        iteration_input_tokens as u64,
+                        iteration_output_tokens as u64,
+                    );
+                    // And into the session totals, persisted with the conversation
+                    if let Some(tx) = &event_tx {
+                        let _ = tx.send(crate::tui::TuiEvent::SessionUsage {
+                            input_tokens: iteration_input_tokens,
+                            output_tokens: iteration_output_tokens,
+                        });
+                    }
+
                     // If we didn't get MessageComplete, we're done with all tools
                     if !has_tool_use {
                         if let Some(tx) = &event_tx {
+                            // If tools modified files this turn, show a compact
+                            // diff stat so the impact is visible without
+                            // scrolling through tool outputs
+                            if let Some(stat) = crate::ai::diff_display::turn_diff_stat(&files_touched) {
+                                let _ = tx.send(crate::tui::TuiEvent::Message(stat));
+                            }
                             let _ = tx.send(crate::tui::TuiEvent::UpdateTaskStatus(None));
                             let _ = tx.send(crate::tui::TuiEvent::ProcessingComplete);
                         }
@@ -1186,6 +2036,115 @@ impl AppState {
         self.scroll_to_bottom();
     }
     
+    /// Expand `@path` mentions into attached file contents, within a
+    /// token budget (a configurable share of the context window, see
+    /// attachmentContextShare in settings.json). Files that would blow the
+    /// budget are attached as head/tail excerpts with an inline notice;
+    /// `@path!` forces full inclusion. The transcript keeps the original
+    /// message - only the outgoing prompt carries the attachments.
+    fn expand_file_mentions(&mut self, input: &str) -> String {
+        let mention_regex = match regex::Regex::new(r"@([A-Za-z0-9_\-./~]+?)(!?)(?:\s|$|[,;:)\]])") {
+            Ok(re) => re,
+            Err(_) => return input.to_string(),
+        };
+
+        let budget_tokens = (self.get_model_token_limit() as f32
+            * crate::config::get_attachment_context_share()) as usize;
+        let mut used_tokens = 0usize;
+        let mut attachments = String::new();
+        let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+        for capture in mention_regex.captures_iter(input) {
+            let path_str = capture[1].trim_end_matches(['.', ',']).to_string();
+            let forced = &capture[2] == "!";
+            if !seen.insert(path_str.clone()) {
+                continue;
+            }
+            let path = PathBuf::from(&path_str);
+            if !path.is_file() {
+                continue;
+            }
+            if crate::path_policy::is_path_excluded(&path) {
+                self.add_command_output(&crate::path_policy::excluded_message(&path_str));
+                continue;
+            }
+            // Skip obviously oversized or binary files; the model can still
+            // use the Read tool on them
+            if path.metadata().map(|m| m.len() > 5_000_000).unwrap_or(true) {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+
+            // chars/4 heuristic, consistent with the context estimator
+            let estimated_tokens = content.len() / 4;
+            if forced || used_tokens + estimated_tokens <= budget_tokens {
+                used_tokens += estimated_tokens;
+                attachments.push_str(&format!("\n\n[Attached file: {}]\n{}", path_str, content));
+                if forced && used_tokens > budget_tokens {
+                    self.add_command_output(&format!(
+                        "⚠ @{}! forced full inclusion (~{} tokens); attachments now exceed the {}% context budget.",
+                        path_str,
+                        estimated_tokens,
+                        (crate::config::get_attachment_context_share() * 100.0) as u32
+                    ));
+                }
+            } else {
+                // Head/tail excerpt instead of silently blowing the budget
+                let lines: Vec<&str> = content.lines().collect();
+                let head = 80.min(lines.len());
+                let tail = 20.min(lines.len().saturating_sub(head));
+                let omitted = lines.len() - head - tail;
+                let mut excerpt = lines[..head].join("\n");
+                if tail > 0 {
+                    excerpt.push_str(&format!(
+                        "\n... [{} lines omitted - mention @{}! to force full inclusion] ...\n",
+                        omitted, path_str
+                    ));
+                    excerpt.push_str(&lines[lines.len() - tail..].join("\n"));
+                }
+                used_tokens += excerpt.len() / 4;
+                attachments.push_str(&format!(
+                    "\n\n[Attached file (excerpt): {}]\n{}",
+                    path_str, excerpt
+                ));
+                self.add_command_output(&format!(
+                    "⚠ @{} is large (~{} tokens); attached a head/tail excerpt to stay within the attachment budget. Use @{}! to force full inclusion.",
+                    path_str, estimated_tokens, path_str
+                ));
+            }
+        }
+
+        if attachments.is_empty() {
+            input.to_string()
+        } else {
+            format!("{}{}", input, attachments)
+        }
+    }
+
+    /// Pair the retried response with its regeneration once processing
+    /// completes, so /variants can compare them. No-op outside a retry.
+    pub fn finalize_retry_variants(&mut self) {
+        let Some(original) = self.pending_retry_original.take() else {
+            return;
+        };
+        let regenerated = self
+            .messages
+            .iter()
+            .rev()
+            .find(|msg| msg.role == "assistant")
+            .map(|msg| msg.content.clone());
+        if let Some(regenerated) = regenerated {
+            if regenerated != original {
+                self.retry_variants = Some((original, regenerated));
+                self.add_command_output(
+                    "Two response variants are available. /variants compares them; /variants keep 1|2 chooses which becomes canonical.",
+                );
+            }
+        }
+    }
+
     /// Submit user input
     pub async fn submit_input(&mut self) -> Result<()> {
         // Get text from textarea and trim trailing empty lines
@@ -1230,18 +2189,28 @@ impl AppState {
             content: input.clone(),
             timestamp: crate::utils::timestamp_ms(),
         });
-        
+
+        // File edits made in response to this message are checkpointed
+        // under its index, so /undo rolls the whole turn back
+        crate::checkpoint::begin_turn(self.messages.len() - 1);
+
         self.invalidate_cache();
         self.scroll_to_bottom();
         self.input_mode = false;
         self.is_processing = true;
+        self.processing_started_at = Some(std::time::Instant::now());
+        self.streaming_output_tokens = 0;
         self.current_task_status = Some("Processing request...".to_string());
-        
+
+        // Expand @path mentions into attachments for the outgoing prompt
+        // (budget-aware; the transcript above keeps the original text)
+        let outgoing = self.expand_file_mentions(&input);
+
         // Send message to the persistent agent loop along with any loaded messages and current model
         if let Some(agent_tx) = &self.agent_tx {
             // Take the loaded messages if this is the first message after resuming
             let loaded = self.loaded_ai_messages.take();
-            let _ = agent_tx.send((input.clone(), loaded, self.current_model.clone()));
+            let _ = agent_tx.send((outgoing, loaded, self.current_model.clone(), None, self.thinking_budget));
         } else {
             // Agent loop not started - this shouldn't happen
             self.add_message("Error: Agent loop not initialized");
@@ -1310,12 +2279,18 @@ impl AppState {
                         .temperature(0.7)
                         .stream();
                     
-                    // Set system prompt
-                    let system = if let Some(prompt) = &system_prompt {
-                        prompt.clone()
-                    } else {
-                        crate::ai::system_prompt::get_system_prompt("Claude Code")
-                    };
+                    // Set system prompt (assembled, see ai::system_prompt)
+                    let mut system = crate::ai::system_prompt::assemble_session_prompt(
+                        system_prompt.as_deref(),
+                    );
+                    // Keep responses in the configured language (/lang,
+                    // responseLanguage in settings.json)
+                    if let Some(language) = crate::config::get_response_language() {
+                        system.push_str(&format!(
+                            "\n\nIMPORTANT: Always respond in {}. Code, identifiers, and command output stay as-is; all prose in your responses must be in {}.",
+                            language, language
+                        ));
+                    }
                     request = request.system(system);
                     
                     // Add tools
@@ -1579,7 +2554,9 @@ impl AppState {
                                         current_text.clone()
                                     ));
                                 }
-                                
+                                // Read the completed response aloud when /tts is on
+                                crate::tui::tts::speak_async(&current_text);
+
                                 // Add text to messages for next iteration
                                 messages.push(crate::ai::Message {
                                     role: crate::ai::MessageRole::Assistant,
@@ -1616,6 +2593,11 @@ impl AppState {
                             }
                             break;
                         }
+                        StreamingUpdate::UsageDelta { input_tokens, output_tokens } => {
+                            if let Some(tx) = &event_tx_inner {
+                                let _ = tx.send(crate::tui::TuiEvent::StreamingUsage { input_tokens, output_tokens });
+                            }
+                        }
                         _ => {}
                     }
                     } // End of streaming processing loop
@@ -1691,12 +2673,8 @@ impl AppState {
             
             // Always set system prompt - this is critical for agentic behavior
             // In JavaScript, prependCLISysprompt is always true for main flow
-            let system = if let Some(prompt) = &self.system_prompt {
-                prompt.clone()
-            } else {
-                // Fallback to ensure we always have a system prompt
-                crate::ai::system_prompt::get_system_prompt("Claude Code")
-            };
+            let system =
+                crate::ai::system_prompt::assemble_session_prompt(self.system_prompt.as_deref());
             request = request.system(system);
             
             // Add tools if available
@@ -1934,11 +2912,8 @@ impl AppState {
                 .stream(); // Enable streaming
             
             // Always set system prompt
-            let system = if let Some(prompt) = &self.system_prompt {
-                prompt.clone()
-            } else {
-                crate::ai::system_prompt::get_system_prompt("Claude Code")
-            };
+            let system =
+                crate::ai::system_prompt::assemble_session_prompt(self.system_prompt.as_deref());
             request = request.system(system);
             
             // Add tools if available
@@ -2217,6 +3192,17 @@ impl AppState {
                             let _ = event_tx.send(crate::tui::TuiEvent::Redraw);
                         }
                     }
+                    StreamingUpdate::RedactedThinking { .. } => {
+                        // Encrypted thinking content - nothing to display
+                    }
+                    StreamingUpdate::UsageDelta { input_tokens, output_tokens } => {
+                        self.streaming_input_tokens = input_tokens;
+                        self.streaming_output_tokens = output_tokens;
+                        // Trigger UI redraw so the footer stats refresh
+                        if let Some(event_tx) = &self.event_tx {
+                            let _ = event_tx.send(crate::tui::TuiEvent::Redraw);
+                        }
+                    }
                     StreamingUpdate::MessageComplete { stop_reason: reason, .. } => {
                         stop_reason = reason;
                         break;
@@ -2378,6 +3364,19 @@ impl AppState {
                 if parts.len() > 1 {
                     // Expand short model names to full names
                     let model_input = parts[1].to_lowercase();
+                    // Provider-discovered aliases (from /models) win, so
+                    // "sonnet4.5" maps to the Bedrock/Vertex id when one
+                    // was reported as available
+                    if let Some(discovered) = &self.discovered_models {
+                        if let Some(model) = discovered
+                            .iter()
+                            .find(|m| m.alias.as_deref() == Some(model_input.as_str()))
+                        {
+                            self.current_model = model.model_id.clone();
+                            self.add_message(&format!("Model changed to: {}", self.current_model));
+                            return Ok(());
+                        }
+                    }
                     self.current_model = match model_input.as_str() {
                         "sonnet" | "sonnet4.5" => "claude-sonnet-4-5-20250929".to_string(),
                         "sonnet4" => "claude-sonnet-4-20250514".to_string(),
@@ -2389,11 +3388,54 @@ impl AppState {
                     self.add_message(&format!("Model changed to: {}", self.current_model));
                 } else {
                     // Show model picker dialog
-                    self.show_model_picker = true;
-                    self.model_picker_selected = self.get_model_picker_index();
+                    self.open_model_picker();
                 }
             }
             "/models" => {
+                // Ask the provider which models this account can invoke
+                // (Bedrock ListFoundationModels, Vertex publisher models);
+                // fall back to the static catalog for plain Anthropic or
+                // when discovery fails
+                let discovery_config = crate::ai::load_config().ok();
+                if let Some(config) = discovery_config
+                    .filter(crate::ai::model_discovery::discovery_available)
+                {
+                    match crate::ai::model_discovery::discover_models(&config).await {
+                        Ok(models) if !models.is_empty() => {
+                            let mut output = String::from("# Available Models\n\n");
+                            for (i, model) in models.iter().enumerate() {
+                                let current = if model.model_id == self.current_model {
+                                    " (current)"
+                                } else {
+                                    ""
+                                };
+                                let alias = model
+                                    .alias
+                                    .as_deref()
+                                    .map(|a| format!(" — `/model {}`", a))
+                                    .unwrap_or_default();
+                                output.push_str(&format!(
+                                    "{}. **{}**{}\n   `{}`{}\n\n",
+                                    i + 1, model.display_name, current, model.model_id, alias
+                                ));
+                            }
+                            output.push_str("Use `/model <alias>` or `/model <id>` to switch");
+                            self.discovered_models = Some(models);
+                            self.add_message(&output);
+                            return Ok(());
+                        }
+                        Ok(_) => {
+                            self.add_error("Provider reported no invocable Anthropic models");
+                        }
+                        Err(e) => {
+                            self.add_error(&format!(
+                                "Model discovery failed ({}), showing the static catalog",
+                                e
+                            ));
+                        }
+                    }
+                }
+
                 // Show available models list
                 let models = self.get_available_models();
                 let mut output = String::from("# Available Models\n\n");
@@ -2408,6 +3450,243 @@ impl AppState {
             "/tools" => {
                 self.show_tool_panel = true;
             }
+            "/dry-run" => {
+                // Toggle (or explicitly set) session-wide dry-run mode
+                let enable = match parts.get(1).copied() {
+                    Some("on") => true,
+                    Some("off") => false,
+                    Some(other) => {
+                        self.add_error(&format!("Usage: /dry-run [on|off] (got '{}')", other));
+                        return Ok(());
+                    }
+                    None => !crate::ai::tools::is_dry_run(),
+                };
+                crate::ai::tools::set_dry_run(enable);
+                if enable {
+                    self.add_command_output("Dry-run mode enabled: Write, Edit, MultiEdit, Bash, and NotebookEdit will report what they would do without executing. Use /dry-run off to resume real execution.");
+                } else {
+                    self.add_command_output("Dry-run mode disabled: tools will execute normally again.");
+                }
+            }
+            "/think" => {
+                // Toggle or configure the extended thinking budget
+                match parts.get(1).copied() {
+                    Some("off") => {
+                        self.thinking_budget = None;
+                        self.add_command_output("Extended thinking disabled.");
+                    }
+                    Some(arg) => match arg.parse::<u32>() {
+                        Ok(budget) if budget >= 1024 => {
+                            self.thinking_budget = Some(budget);
+                            self.add_command_output(&format!(
+                                "Extended thinking enabled with a budget of {} tokens.",
+                                budget
+                            ));
+                        }
+                        Ok(_) => {
+                            self.add_error("Thinking budget must be at least 1024 tokens");
+                        }
+                        Err(_) => {
+                            self.add_error(&format!(
+                                "Usage: /think [budget-tokens|off] (got '{}')",
+                                arg
+                            ));
+                        }
+                    },
+                    None => {
+                        if let Some(budget) = self.thinking_budget {
+                            self.add_command_output(&format!(
+                                "Extended thinking is on (budget: {} tokens). Use /think off to disable.",
+                                budget
+                            ));
+                        } else {
+                            let budget = crate::ai::default_thinking_budget(&self.current_model);
+                            self.thinking_budget = Some(budget);
+                            self.add_command_output(&format!(
+                                "Extended thinking enabled with the default budget for {} ({} tokens). Use /think <tokens> to adjust or /think off to disable.",
+                                self.current_model, budget
+                            ));
+                        }
+                    }
+                }
+            }
+            "/lang" => {
+                // Configure the response language (responseLanguage in
+                // settings.json): user scope by default, --project for the
+                // shared project settings
+                let args: Vec<&str> = parts[1..].to_vec();
+                let scope = if args.contains(&"--project") {
+                    crate::config::SettingsSource::Project
+                } else {
+                    crate::config::SettingsSource::User
+                };
+                let value: Vec<&str> = args
+                    .iter()
+                    .filter(|a| !a.starts_with("--"))
+                    .copied()
+                    .collect();
+
+                if value.is_empty() {
+                    match crate::config::get_response_language() {
+                        Some(language) => self.add_command_output(&format!(
+                            "Responses are configured for {}. Use /lang off to clear or /lang <language> to change.",
+                            language
+                        )),
+                        None => self.add_command_output(
+                            "No response language configured. Use /lang <language> (e.g. /lang de, /lang Japanese); add --project to share it with the project.",
+                        ),
+                    }
+                } else {
+                    let mut settings = crate::config::load_settings(scope)?;
+                    if value[0].eq_ignore_ascii_case("off") {
+                        settings.response_language = None;
+                        crate::config::save_settings(scope, &settings)?;
+                        self.add_command_output("Response language cleared.");
+                    } else {
+                        let language = value.join(" ");
+                        settings.response_language = Some(language.clone());
+                        crate::config::save_settings(scope, &settings)?;
+                        let scope_label = if scope == crate::config::SettingsSource::Project {
+                            "project settings"
+                        } else {
+                            "user settings"
+                        };
+                        self.add_command_output(&format!(
+                            "Responses will be in {} (saved to {}).",
+                            language, scope_label
+                        ));
+                    }
+                }
+            }
+            "/release-notes" => {
+                // Show release notes: current version by default, a specific
+                // version by argument, or the whole embedded changelog
+                match parts.get(1).copied() {
+                    None => {
+                        let version = crate::changelog::current_version();
+                        match crate::changelog::notes_for_version(version) {
+                            Some(notes) => self.add_command_output(&notes),
+                            None => self.add_error(&format!("No release notes found for v{}", version)),
+                        }
+                    }
+                    Some("all") | Some("--all") => {
+                        self.add_command_output(crate::changelog::full_changelog());
+                    }
+                    Some(version) => {
+                        match crate::changelog::notes_for_version(version) {
+                            Some(notes) => self.add_command_output(&notes),
+                            None => self.add_error(&format!(
+                                "No release notes found for v{}. Use /release-notes all to see the full changelog.",
+                                version
+                            )),
+                        }
+                    }
+                }
+            }
+            "/tips" => {
+                // Toggle (or explicitly set) the startup tips ("don't show again")
+                let enable = match parts.get(1).copied() {
+                    Some("on") => true,
+                    Some("off") => false,
+                    Some(other) => {
+                        self.add_error(&format!("Usage: /tips [on|off] (got '{}')", other));
+                        return Ok(());
+                    }
+                    None => !crate::tui::tips::tips_enabled(),
+                };
+                match crate::tui::tips::set_tips_enabled(enable) {
+                    Ok(()) => {
+                        if enable {
+                            self.add_command_output("Startup tips enabled. A rotating tip will be shown at the start of each session.");
+                        } else {
+                            self.add_command_output("Startup tips disabled. They won't be shown again (re-enable with /tips on).");
+                        }
+                    }
+                    Err(e) => self.add_error(&format!("Failed to save tips preference: {}", e)),
+                }
+            }
+            "/tts" => {
+                // Toggle (or explicitly set) spoken responses
+                use crate::tui::tts::TtsMode;
+                let mode = match parts.get(1).copied() {
+                    Some("on") | Some("full") => TtsMode::Full,
+                    Some("off") => TtsMode::Off,
+                    Some("summary") => TtsMode::Summary,
+                    Some(other) => {
+                        self.add_error(&format!("Usage: /tts [on|off|summary] (got '{}')", other));
+                        return Ok(());
+                    }
+                    None => {
+                        if crate::tui::tts::mode() == TtsMode::Off {
+                            TtsMode::Full
+                        } else {
+                            TtsMode::Off
+                        }
+                    }
+                };
+                match crate::tui::tts::set_mode(mode) {
+                    Ok(()) => match mode {
+                        TtsMode::Off => self.add_command_output("Text-to-speech disabled."),
+                        TtsMode::Full => self.add_command_output("Text-to-speech enabled. Completed responses will be read aloud (use /tts summary for shorter readings)."),
+                        TtsMode::Summary => self.add_command_output("Text-to-speech enabled in summary mode. Only the first sentence or two of each response will be read aloud."),
+                    },
+                    Err(e) => self.add_error(&format!("Failed to save TTS preference: {}", e)),
+                }
+            }
+            "/voice" => {
+                // Record a clip, transcribe it, and insert the transcript
+                // into the input for review. Runs off the UI task so the
+                // session stays responsive while recording.
+                let seconds = match parts.get(1) {
+                    Some(arg) => match arg.parse::<u64>() {
+                        Ok(n) if n > 0 => n,
+                        _ => {
+                            self.add_error(&format!("Usage: /voice [seconds] (got '{}')", arg));
+                            return Ok(());
+                        }
+                    },
+                    None => crate::tui::voice::DEFAULT_RECORD_SECONDS,
+                };
+                self.add_message(&format!("🎤 Recording for {}s... speak now", seconds));
+                if let Some(event_tx) = self.event_tx.clone() {
+                    tokio::spawn(async move {
+                        match crate::tui::voice::capture_and_transcribe(seconds).await {
+                            Ok(transcript) => {
+                                let _ = event_tx.send(crate::tui::TuiEvent::VoiceTranscript(transcript));
+                            }
+                            Err(e) => {
+                                let _ = event_tx.send(crate::tui::TuiEvent::Error(format!("Voice input failed: {}", e)));
+                            }
+                        }
+                    });
+                }
+            }
+            "/artifacts" => {
+                // Browse artifacts stored for the current session
+                match crate::ai::artifacts::list_artifacts(&self.session_id) {
+                    Ok(artifacts) if artifacts.is_empty() => {
+                        self.add_command_output("No artifacts stored for this session.\n\nOversized tool outputs and generated files are stored under .claude/artifacts/ and referenced by artifact:// handles.");
+                    }
+                    Ok(artifacts) => {
+                        let mut output = String::from("# Session Artifacts\n\n");
+                        for artifact in &artifacts {
+                            output.push_str(&format!(
+                                "- `{}` — {} bytes from {} at {} ({})\n",
+                                artifact.handle(),
+                                artifact.size_bytes,
+                                artifact.source,
+                                artifact.created_at.format("%H:%M:%S"),
+                                artifact.path.display()
+                            ));
+                        }
+                        output.push_str("\nUse the ReadArtifact tool or open the file path directly to view the full content.");
+                        self.add_command_output(&output);
+                    }
+                    Err(e) => {
+                        self.add_error(&format!("Failed to list artifacts: {}", e));
+                    }
+                }
+            }
             "/mcp" => {
                 // Handle /mcp subcommands matching JavaScript implementation
                 // JavaScript: enable, disable, reconnect only - other commands use mcp-cli
@@ -2440,13 +3719,17 @@ impl AppState {
                                 self.add_error("Usage: /mcp reconnect <server-name>");
                             }
                         }
+                        "help" => {
+                            // Text help and mcp-cli pointers
+                            self.show_mcp_manager();
+                        }
                         _ => {
-                            self.add_error(&format!("Unknown /mcp subcommand: {}. Use: enable, disable, reconnect", subcommand));
+                            self.add_error(&format!("Unknown /mcp subcommand: {}. Use: enable, disable, reconnect, help", subcommand));
                         }
                     }
                 } else {
-                    // Default: show MCP server manager
-                    self.show_mcp_manager();
+                    // Default: open the interactive server health dashboard
+                    self.open_mcp_dashboard();
                 }
             }
             "/exit" | "/quit" => {
@@ -2475,6 +3758,116 @@ impl AppState {
                     }
                 }
             }
+            "/search" => {
+                if parts.len() < 2 {
+                    self.add_message("Usage: /search <query>");
+                } else {
+                    let query = parts[1..].join(" ");
+                    match crate::session_store::search_sessions(
+                        &self.conversation_dir,
+                        &query,
+                        20,
+                    ) {
+                        Ok(hits) if hits.is_empty() => {
+                            self.add_message(&format!(
+                                "No messages matching '{}' in saved sessions",
+                                query
+                            ));
+                        }
+                        Ok(hits) => {
+                            let mut output = format!("Matches for '{}':\n", query);
+                            let mut matched_ids: Vec<String> = Vec::new();
+                            for hit in &hits {
+                                if !matched_ids.contains(&hit.session_id) {
+                                    matched_ids.push(hit.session_id.clone());
+                                }
+                                output.push_str(&format!(
+                                    "  [{}] {}: {}\n",
+                                    hit.session_id,
+                                    hit.role,
+                                    hit.snippet.replace('\n', " ")
+                                ));
+                            }
+                            output.push_str(
+                                "\nPick a session below to resume it (Esc to stay here).",
+                            );
+                            self.add_message(&output);
+
+                            // Open the session picker filtered to the
+                            // sessions that matched, newest first
+                            let sessions = self.list_sessions().await?;
+                            let items: Vec<SessionInfo> = matched_ids
+                                .iter()
+                                .filter_map(|id| {
+                                    sessions.iter().find(|s| &s.id == id).cloned()
+                                })
+                                .collect();
+                            if !items.is_empty() {
+                                self.session_picker_items =
+                                    items.into_iter().take(10).collect();
+                                self.session_picker_selected = 0;
+                                self.show_session_picker = true;
+                            }
+                        }
+                        Err(e) => {
+                            self.add_error(&format!("Search failed: {}", e));
+                        }
+                    }
+                }
+            }
+            "/undo" => {
+                match crate::checkpoint::undo() {
+                    Ok(Some(summary)) => self.add_command_output(&summary),
+                    Ok(None) => self.add_message("Nothing to undo: no checkpointed file changes in this session"),
+                    Err(e) => self.add_error(&format!("Undo failed: {}", e)),
+                }
+            }
+            "/redo" => {
+                match crate::checkpoint::redo() {
+                    Ok(Some(summary)) => self.add_command_output(&summary),
+                    Ok(None) => self.add_message("Nothing to redo"),
+                    Err(e) => self.add_error(&format!("Redo failed: {}", e)),
+                }
+            }
+            "/debug" => {
+                match (parts.get(1).copied(), parts.get(2).copied()) {
+                    (Some("step"), Some("on")) => {
+                        self.step_mode
+                            .store(true, std::sync::atomic::Ordering::Relaxed);
+                        self.add_message(
+                            "Step mode on: the agent pauses before each model request and tool call. Ctrl+S continues (edits apply to that send only), Esc skips.",
+                        );
+                    }
+                    (Some("step"), Some("off")) => {
+                        self.step_mode
+                            .store(false, std::sync::atomic::Ordering::Relaxed);
+                        self.add_message("Step mode off");
+                    }
+                    (Some("panel"), _) => {
+                        // Ctrl+G now captures feedback, so the debug
+                        // panel toggle lives here
+                        self.toggle_debug();
+                        self.add_message(&format!(
+                            "Debug panel {}",
+                            if self.debug_mode { "on" } else { "off" }
+                        ));
+                    }
+                    _ => {
+                        let state = if self
+                            .step_mode
+                            .load(std::sync::atomic::Ordering::Relaxed)
+                        {
+                            "on"
+                        } else {
+                            "off"
+                        };
+                        self.add_message(&format!(
+                            "Usage: /debug step on|off · /debug panel (step mode is currently {})",
+                            state
+                        ));
+                    }
+                }
+            }
             "/status" => {
                 // Show tabbed status view (matches JavaScript)
                 // Tab to cycle through tabs, Esc to close
@@ -2515,6 +3908,12 @@ impl AppState {
                     }
                 }
             }
+            "/context" if matches!(parts.get(1).copied(), Some("--detail") | Some("detail")) => {
+                self.show_context_detail();
+            }
+            "/context" if parts.get(1).copied() == Some("prune") => {
+                self.prune_context_messages(&parts[2..]);
+            }
             "/context" => {
                 // Show current context usage with visual bar
                 // Try to get accurate token count from API, fall back to estimate
@@ -2596,23 +3995,224 @@ impl AppState {
                     output.push_str("(CLAUDE.md):");
                 }
 
-                if !is_accurate {
-                    output.push_str("\n\n~ indicates estimated values (API token counting unavailable)");
+                if !is_accurate {
+                    output.push_str("\n\n~ indicates estimated values (API token counting unavailable)");
+                }
+
+                self.add_command_output(&output);
+            }
+            "/cost" => {
+                // Show estimated cost for this conversation
+                let token_count = self.estimate_token_count();
+                let cost = self.estimate_cost(token_count);
+                let output = format!("Estimated tokens: {}\nEstimated cost: ${:.4}", token_count, cost);
+                self.add_command_output(&output);
+            }
+            "/cost-limit" => {
+                // Set, clear, or show the per-turn cost guardrail
+                match parts.get(1).map(|s| s.trim()) {
+                    Some("off") => {
+                        crate::ai::models::set_turn_cost_limit(None);
+                        self.add_command_output("Turn cost limit disabled.");
+                    }
+                    Some(arg) => match arg.trim_start_matches('$').parse::<f64>() {
+                        Ok(dollars) if dollars > 0.0 => {
+                            crate::ai::models::set_turn_cost_limit(Some(dollars));
+                            self.add_command_output(&format!(
+                                "Turn cost limit set to ${:.2}. Turns that reach it pause for /continue approval.",
+                                dollars
+                            ));
+                        }
+                        _ => {
+                            self.add_error(&format!("Usage: /cost-limit [dollars|off] (got '{}')", arg));
+                        }
+                    },
+                    None => match crate::ai::models::turn_cost_limit() {
+                        Some(limit) => self.add_command_output(&format!(
+                            "Turn cost limit: ${:.2}. Use /cost-limit off to disable.",
+                            limit
+                        )),
+                        None => self.add_command_output(
+                            "No turn cost limit set. Use /cost-limit <dollars> to set one.",
+                        ),
+                    },
+                }
+            }
+            "/tier" => {
+                // Pick the service tier for subsequent requests
+                match parts.get(1).map(|s| s.trim()) {
+                    Some("fast") => {
+                        crate::ai::set_session_service_tier(Some("auto".to_string()));
+                        self.add_command_output("Service tier set to fast: priority capacity is used when your account has it.");
+                    }
+                    Some("standard") => {
+                        crate::ai::set_session_service_tier(Some("standard_only".to_string()));
+                        self.add_command_output("Service tier set to standard: requests opt out of priority capacity.");
+                    }
+                    Some("batch") => {
+                        // Batch pricing isn't a per-request tier; point at the
+                        // Batches API instead of silently doing nothing
+                        self.add_command_output("Batch pricing only applies to the Batches API. Run `llminate batch --input requests.jsonl --output results.jsonl` for half-price offline runs; the interactive tier is unchanged.");
+                    }
+                    Some("off") => {
+                        crate::ai::set_session_service_tier(None);
+                        self.add_command_output("Service tier override cleared; the configured default applies.");
+                    }
+                    Some(other) => {
+                        self.add_error(&format!("Usage: /tier [fast|standard|batch|off] (got '{}')", other));
+                    }
+                    None => {
+                        let current = match crate::ai::session_service_tier().as_deref() {
+                            Some("auto") => "fast (priority capacity when available)".to_string(),
+                            Some("standard_only") => "standard".to_string(),
+                            Some(other) => other.to_string(),
+                            None => "default (no override)".to_string(),
+                        };
+                        self.add_command_output(&format!("Service tier: {}", current));
+                    }
+                }
+            }
+            "/agents" => {
+                // List custom agent definitions from .claude/agents/
+                let agents = crate::ai::agent_registry::all_agents();
+                if agents.is_empty() {
+                    self.add_command_output("No custom agents found. Put Markdown or YAML definitions in .claude/agents/ (project) or ~/.claude/agents/ (user).");
+                } else {
+                    let mut output = String::from("Custom agents (usable as Task subagent_type):\n");
+                    for agent in &agents {
+                        output.push_str(&format!("\n  {} [{}]", agent.name, agent.source.as_str()));
+                        if !agent.description.is_empty() {
+                            output.push_str(&format!(" - {}", agent.description));
+                        }
+                        if let Some(model) = &agent.model {
+                            output.push_str(&format!("\n    model: {}", model));
+                        }
+                        if let Some(tools) = &agent.allowed_tools {
+                            output.push_str(&format!("\n    tools: {}", tools.join(", ")));
+                        }
+                    }
+                    output.push_str(&format!(
+                        "\n\nBuilt-in types: {}",
+                        crate::ai::agent_tool::AgentType::available_types().join(", ")
+                    ));
+                    self.add_command_output(&output);
+                }
+            }
+            "/stats" => {
+                // Workspace statistics: what the repo looks like and how much
+                // of it this session has actually read
+                let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+
+                let mut by_language: std::collections::HashMap<&'static str, (usize, usize)> =
+                    std::collections::HashMap::new();
+                let mut largest: Vec<(u64, PathBuf)> = Vec::new();
+                let mut total_files = 0usize;
+                let mut total_loc = 0usize;
+
+                let walker = ignore::WalkBuilder::new(&root)
+                    .hidden(false)
+                    .max_depth(Some(32))
+                    .build();
+                for entry in walker.flatten() {
+                    if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                        continue;
+                    }
+                    let path = entry.path();
+                    if crate::path_policy::is_path_excluded(path) {
+                        continue;
+                    }
+                    total_files += 1;
+
+                    let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    largest.push((size, path.to_path_buf()));
+                    if largest.len() > 64 {
+                        largest.sort_by(|a, b| b.0.cmp(&a.0));
+                        largest.truncate(8);
+                    }
+
+                    // Count lines only for plausibly-text files of sane size
+                    let loc = if size > 0 && size <= 2_000_000 {
+                        std::fs::read(path)
+                            .ok()
+                            .filter(|bytes| !bytes.contains(&0))
+                            .map(|bytes| bytes.iter().filter(|b| **b == b'\n').count())
+                            .unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    total_loc += loc;
+
+                    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                    let slot = by_language.entry(language_for_extension(ext)).or_insert((0, 0));
+                    slot.0 += 1;
+                    slot.1 += loc;
+                }
+
+                let mut output = format!(
+                    "Workspace statistics for {}\n\n{} files, ~{} lines total\n",
+                    root.display(),
+                    total_files,
+                    total_loc
+                );
+
+                let mut languages: Vec<(&str, (usize, usize))> = by_language.into_iter().collect();
+                languages.sort_by(|a, b| b.1 .1.cmp(&a.1 .1));
+                output.push_str("\nBy language:\n");
+                for (language, (files, loc)) in languages.iter().take(8) {
+                    output.push_str(&format!("  {:<12} {:>6} files  {:>9} lines\n", language, files, loc));
+                }
+
+                largest.sort_by(|a, b| b.0.cmp(&a.0));
+                output.push_str("\nLargest files:\n");
+                for (size, path) in largest.iter().take(5) {
+                    let shown = path.strip_prefix(&root).unwrap_or(path);
+                    output.push_str(&format!("  {:>8.1} KB  {}\n", *size as f64 / 1024.0, shown.display()));
+                }
+
+                // Context map (CLAUDE.md) freshness and index status
+                output.push('\n');
+                match std::fs::metadata(root.join("CLAUDE.md")).and_then(|m| m.modified()) {
+                    Ok(modified) => {
+                        let age_days = modified
+                            .elapsed()
+                            .map(|d| d.as_secs() / 86_400)
+                            .unwrap_or(0);
+                        output.push_str(&format!("Context map (CLAUDE.md): last updated {} day(s) ago\n", age_days));
+                    }
+                    Err(_) => output.push_str("Context map (CLAUDE.md): not present\n"),
+                }
+                output.push_str(&format!(
+                    "Semantic index: {}\n",
+                    if self.feature_flags.semantic_index { "enabled" } else { "disabled" }
+                ));
+
+                // How much of the repo this session has read
+                let read_in_repo = crate::ai::tools::files_read_this_session()
+                    .iter()
+                    .filter(|path| path.starts_with(&root))
+                    .count();
+                let read_share = if total_files > 0 {
+                    read_in_repo as f64 / total_files as f64 * 100.0
+                } else {
+                    0.0
+                };
+                output.push_str(&format!(
+                    "Read this session: {} of {} files ({:.1}%)\n",
+                    read_in_repo, total_files, read_share
+                ));
+                if read_share < 5.0 && total_files > 200 {
+                    output.push_str("Tip: large repo, little of it read — consider /compact or a narrower working directory.\n");
                 }
 
                 self.add_command_output(&output);
             }
-            "/cost" => {
-                // Show estimated cost for this conversation
-                let token_count = self.estimate_token_count();
-                let cost = self.estimate_cost(token_count);
-                let output = format!("Estimated tokens: {}\nEstimated cost: ${:.4}", token_count, cost);
-                self.add_command_output(&output);
-            }
             "/settings" => {
                 // Show current settings
-                let output = format!("Current settings:\n  Model: {}\n  Auto-save: {}\n  Compact mode: {}\n  Debug mode: {}\n  Tool panel: {}", 
-                    self.current_model, self.auto_save, self.compact_mode, self.debug_mode, self.show_tool_panel);
+                let output = format!("Current settings:\n  Model: {}\n  Auto-save: {}\n  Compact mode: {}\n  Debug mode: {}\n  Tool panel: {}\n  Features: semantic index {}, browser tool {}, speculative prefetch {}",
+                    self.current_model, self.auto_save, self.compact_mode, self.debug_mode, self.show_tool_panel,
+                    if self.feature_flags.semantic_index { "on" } else { "off" },
+                    if self.feature_flags.browser_tool { "on" } else { "off" },
+                    if self.feature_flags.speculative_prefetch { "on" } else { "off" });
                 self.add_command_output(&output);
             }
             "/continue" => {
@@ -2626,13 +4226,201 @@ impl AppState {
                         // Send a continue command that the agent will process
                         // Pass the continuation messages to restore context
                         let messages = self.continuation_messages.take();
-                        let _ = tx.send(("".to_string(), messages, self.current_model.clone()));  // Empty message to continue with saved context
+                        let _ = tx.send(("".to_string(), messages, self.current_model.clone(), None, self.thinking_budget));  // Empty message to continue with saved context
                     }
                     self.is_processing = true;
+                    self.processing_started_at = Some(std::time::Instant::now());
+                    self.streaming_output_tokens = 0;
                 } else {
                     self.add_message("No iteration limit reached. Nothing to continue from.");
                 }
             }
+            "/retry" => {
+                // Regenerate the last assistant turn, optionally with a
+                // different model or temperature for this one turn. The
+                // previous response stays in the transcript as a variant.
+                if self.is_processing {
+                    self.add_error("Cannot retry while a request is in flight");
+                    return Ok(());
+                }
+
+                let mut model_for_turn = self.current_model.clone();
+                let mut temperature_override: Option<f32> = None;
+                let mut args = parts[1..].iter();
+                while let Some(arg) = args.next() {
+                    if *arg == "--temp" || *arg == "--temperature" {
+                        match args.next().and_then(|v| v.parse::<f32>().ok()) {
+                            Some(t) if (0.0..=1.0).contains(&t) => temperature_override = Some(t),
+                            _ => {
+                                self.add_error("Usage: /retry [model] [--temp 0.0-1.0]");
+                                return Ok(());
+                            }
+                        }
+                    } else {
+                        model_for_turn = arg.to_string();
+                    }
+                }
+
+                // Find the last user message; everything after it is the
+                // assistant turn being retried
+                let last_user_index = self
+                    .messages
+                    .iter()
+                    .rposition(|msg| msg.role == "user");
+                let Some(last_user_index) = last_user_index else {
+                    self.add_error("Nothing to retry yet - send a message first");
+                    return Ok(());
+                };
+                if !self.messages[last_user_index + 1..]
+                    .iter()
+                    .any(|msg| msg.role == "assistant")
+                {
+                    self.add_error("No assistant response to retry for the last message");
+                    return Ok(());
+                }
+
+                // Rebuild the conversation up to and including the last user
+                // message (same text-level reconstruction as /resume). Tool
+                // side effects from the discarded turn are not reverted.
+                let mut ai_messages: Vec<crate::ai::Message> = Vec::new();
+                for msg in &self.messages[..=last_user_index] {
+                    let role = match msg.role.as_str() {
+                        "user" => crate::ai::MessageRole::User,
+                        "assistant" => crate::ai::MessageRole::Assistant,
+                        _ => continue,
+                    };
+                    ai_messages.push(crate::ai::Message {
+                        role,
+                        content: crate::ai::MessageContent::Text(msg.content.clone()),
+                        name: None,
+                    });
+                }
+
+                // Capture the response being retried so /variants can offer
+                // a comparison once the regeneration completes
+                let original: Vec<String> = self.messages[last_user_index + 1..]
+                    .iter()
+                    .filter(|msg| msg.role == "assistant")
+                    .map(|msg| msg.content.clone())
+                    .collect();
+                self.pending_retry_original = Some(original.join("\n\n"));
+                self.retry_variants = None;
+
+                let mut note = format!("↻ Retrying with {}", model_for_turn);
+                if let Some(t) = temperature_override {
+                    note.push_str(&format!(" (temperature {})", t));
+                }
+                note.push_str(" — the previous response above is kept as a variant for comparison.");
+                self.add_command_output(&note);
+
+                if let Some(agent_tx) = &self.agent_tx {
+                    // Empty input + replaced history = regenerate from the
+                    // last user message (same mechanism as /continue)
+                    let _ = agent_tx.send((String::new(), Some(ai_messages), model_for_turn, temperature_override, self.thinking_budget));
+                    self.input_mode = false;
+                    self.is_processing = true;
+                    self.processing_started_at = Some(std::time::Instant::now());
+                    self.streaming_output_tokens = 0;
+                    self.current_task_status = Some("Regenerating response...".to_string());
+                } else {
+                    self.add_error("Agent loop not initialized");
+                }
+            }
+            "/variants" => {
+                // Compare the two responses from the last /retry and pick
+                // which one becomes part of the canonical history
+                let Some((original, regenerated)) = self.retry_variants.clone() else {
+                    self.add_error("No response variants to compare. Use /retry first.");
+                    return Ok(());
+                };
+
+                match parts.get(1).copied() {
+                    None => {
+                        // Render both variants plus a diff of their code blocks
+                        let mut output = String::from("# Response Variants\n\n");
+                        output.push_str("## Variant 1 (original)\n\n");
+                        output.push_str(&original);
+                        output.push_str("\n\n## Variant 2 (regenerated)\n\n");
+                        output.push_str(&regenerated);
+
+                        let code_1 = extract_code_blocks(&original);
+                        let code_2 = extract_code_blocks(&regenerated);
+                        if !code_1.is_empty() || !code_2.is_empty() {
+                            let diff = crate::ai::diff_display::DiffDisplay::new(
+                                code_1,
+                                code_2,
+                                "code blocks".to_string(),
+                            );
+                            output.push_str("\n\n## Code block diff (variant 1 → variant 2)\n\n");
+                            output.push_str(&diff.colored_diff(Some(80)));
+                        }
+                        output.push_str("\n\nChoose with /variants keep 1 or /variants keep 2.");
+                        self.add_command_output(&output);
+                    }
+                    Some("keep") => {
+                        let choice = match parts.get(2).copied() {
+                            Some("1") => 1,
+                            Some("2") => 2,
+                            _ => {
+                                self.add_error("Usage: /variants keep 1|2");
+                                return Ok(());
+                            }
+                        };
+
+                        // Archive the rejected variant in the transcript: the
+                        // last assistant message is the regeneration, earlier
+                        // assistant messages after the last user turn are the
+                        // original
+                        let Some(last_user_index) =
+                            self.messages.iter().rposition(|msg| msg.role == "user")
+                        else {
+                            self.add_error("Conversation state changed; cannot apply choice");
+                            return Ok(());
+                        };
+                        let assistant_indices: Vec<usize> = (last_user_index + 1
+                            ..self.messages.len())
+                            .filter(|&i| self.messages[i].role == "assistant")
+                            .collect();
+                        let archived: Vec<usize> = if choice == 1 {
+                            assistant_indices.last().copied().into_iter().collect()
+                        } else {
+                            assistant_indices[..assistant_indices.len().saturating_sub(1)].to_vec()
+                        };
+                        for index in archived {
+                            self.messages[index].role = "command_output".to_string();
+                            self.messages[index].content =
+                                format!("📦 Archived variant:\n\n{}", self.messages[index].content);
+                        }
+                        self.invalidate_cache();
+
+                        // Rebuild the canonical history from the transcript
+                        // (archived messages are skipped) and hand it to the
+                        // agent loop on the next turn
+                        let mut ai_messages: Vec<crate::ai::Message> = Vec::new();
+                        for msg in &self.messages {
+                            let role = match msg.role.as_str() {
+                                "user" => crate::ai::MessageRole::User,
+                                "assistant" => crate::ai::MessageRole::Assistant,
+                                _ => continue,
+                            };
+                            ai_messages.push(crate::ai::Message {
+                                role,
+                                content: crate::ai::MessageContent::Text(msg.content.clone()),
+                                name: None,
+                            });
+                        }
+                        self.loaded_ai_messages = Some(ai_messages);
+                        self.retry_variants = None;
+                        self.add_command_output(&format!(
+                            "Variant {} is now canonical. The rejected variant is archived in the transcript above.",
+                            choice
+                        ));
+                    }
+                    Some(other) => {
+                        self.add_error(&format!("Usage: /variants [keep 1|2] (got '{}')", other));
+                    }
+                }
+            }
             "/vim" => {
                 // Toggle vim mode
                 self.vim_mode = !self.vim_mode;
@@ -2724,7 +4512,15 @@ impl AppState {
                         self.add_error(&format!("Directory does not exist: {}", canonical_path.display()));
                     }
                 } else {
-                    self.add_error("Usage: /add-dir <path> [--persist|--local|--user]");
+                    // No path given - open the directory picker
+                    let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+                    self.file_picker = Some(
+                        crate::tui::components::file_picker::FilePicker::new(
+                            "Add working directory",
+                            root,
+                        )
+                        .dirs_only(),
+                    );
                 }
             }
             "/files" => {
@@ -2788,11 +4584,7 @@ impl AppState {
                         output.push_str(&format!(
                             "ID: {}\nCommand: {}\nStatus: {}\n",
                             shell.id,
-                            if shell.command.len() > 60 {
-                                format!("{}...", &shell.command[..57])
-                            } else {
-                                shell.command.clone()
-                            },
+                            crate::utils::truncate_display(&shell.command, 60),
                             shell.status
                         ));
                         
@@ -2918,16 +4710,6 @@ impl AppState {
 
                 self.add_command_output(&output);
             }
-            "/release-notes" => {
-                // Show release notes or version info
-                self.add_message(&format!("Claude Code Rust v{}", env!("CARGO_PKG_VERSION")));
-                self.add_message("A Rust implementation of Claude Code");
-                self.add_message("\nRecent changes:");
-                self.add_message("- Full tool execution through AI agent");
-                self.add_message("- Support for 16+ tools");
-                self.add_message("- Jupyter notebook support");
-                self.add_message("- MCP server integration");
-            }
             "/init" => {
                 // AI-powered CLAUDE.md generation
                 self.add_message("Analyzing your codebase...");
@@ -3236,6 +5018,90 @@ impl AppState {
                 self.add_message("Visit https://claude.ai/upgrade to upgrade your account");
                 self.add_message("Or contact your organization admin for enterprise plans");
             }
+            "/profile" => {
+                // Switch between named auth profiles mid-session
+                match parts.get(1).copied() {
+                    Some("clear") | Some("none") => {
+                        crate::config::set_active_profile(None);
+                        self.add_message("✅ Cleared profile override (back to ANTHROPIC_PROFILE or default credentials)");
+                    }
+                    Some(name) => {
+                        match crate::config::get_profile(name) {
+                            Some(profile) => {
+                                crate::config::set_active_profile(Some(name.to_string()));
+                                if let Some(model) = profile.model {
+                                    self.current_model = model;
+                                }
+                                self.add_message(&format!(
+                                    "✅ Switched to profile '{}'. It applies from the next request",
+                                    name
+                                ));
+                            }
+                            None => {
+                                self.add_error(&format!(
+                                    "Unknown profile '{}'. Use /profile to list profiles",
+                                    name
+                                ));
+                            }
+                        }
+                    }
+                    None => {
+                        let active = crate::config::active_profile_name();
+                        let profiles = crate::config::list_profiles();
+                        if profiles.is_empty() {
+                            self.add_message("No profiles defined. Add a \"profiles\" section to config.json, e.g.");
+                            self.add_message("  \"profiles\": { \"work\": { \"apiKey\": \"sk-...\", \"model\": \"claude-opus-4-1-20250805\" } }");
+                        } else {
+                            self.add_message("Auth profiles:");
+                            for name in &profiles {
+                                let marker = if active.as_deref() == Some(name.as_str()) {
+                                    " (active)"
+                                } else {
+                                    ""
+                                };
+                                self.add_message(&format!("  {}{}", name, marker));
+                            }
+                            self.add_message("Switch with /profile <name>, clear with /profile clear");
+                        }
+                    }
+                }
+            }
+            "/system" => {
+                // Inspect and extend the assembled system prompt
+                match parts.get(1).copied() {
+                    Some("show") => {
+                        self.show_system_prompt();
+                    }
+                    Some("append") => {
+                        let text = command
+                            .strip_prefix("/system")
+                            .and_then(|rest| rest.trim_start().strip_prefix("append"))
+                            .map(|rest| rest.trim())
+                            .unwrap_or("");
+                        if text.is_empty() {
+                            self.add_error("Usage: /system append <text>");
+                        } else {
+                            crate::ai::system_prompt::append_session_instruction(text);
+                            let count =
+                                crate::ai::system_prompt::session_instructions().len();
+                            self.add_message(&format!(
+                                "✅ Added session instruction ({} total). It applies to all requests this session",
+                                count
+                            ));
+                        }
+                    }
+                    Some("clear") => {
+                        crate::ai::system_prompt::clear_session_instructions();
+                        self.add_message("✅ Cleared session instructions");
+                    }
+                    _ => {
+                        self.add_message("System prompt commands:");
+                        self.add_message("  /system show          - Show the assembled system prompt with per-section token counts");
+                        self.add_message("  /system append <text> - Add a session-scoped instruction");
+                        self.add_message("  /system clear         - Drop session-scoped instructions");
+                    }
+                }
+            }
             "/memory" => {
                 // Edit Claude memory files
                 if parts.len() > 1 {
@@ -3505,6 +5371,58 @@ impl AppState {
                     let _ = std::process::Command::new("cmd").args(["/c", "start", url]).spawn();
                 }
             }
+            "/prune" => {
+                self.open_prune_view();
+            }
+            "/open" => {
+                // Open a numbered footnote link from the last assistant
+                // response; the numbers match the rendered `[n]` markers
+                let links = self
+                    .messages
+                    .iter()
+                    .rev()
+                    .find(|m| m.role == "assistant")
+                    .map(|m| crate::tui::markdown::extract_footnote_links(&m.content))
+                    .unwrap_or_default();
+
+                if links.is_empty() {
+                    self.add_error("No links in the last assistant response");
+                } else if parts.len() > 1 {
+                    match parts[1].parse::<usize>() {
+                        Ok(n) if n >= 1 && n <= links.len() => {
+                            let url = links[n - 1].clone();
+                            self.add_message(&format!("Opening [{}] {}", n, url));
+                            #[cfg(target_os = "macos")]
+                            {
+                                let _ = std::process::Command::new("open").arg(&url).spawn();
+                            }
+                            #[cfg(target_os = "linux")]
+                            {
+                                let _ = std::process::Command::new("xdg-open").arg(&url).spawn();
+                            }
+                            #[cfg(target_os = "windows")]
+                            {
+                                let _ = std::process::Command::new("cmd")
+                                    .args(["/c", "start", &url])
+                                    .spawn();
+                            }
+                        }
+                        _ => {
+                            self.add_error(&format!("Usage: /open <1-{}>", links.len()));
+                        }
+                    }
+                } else {
+                    let listing: Vec<String> = links
+                        .iter()
+                        .enumerate()
+                        .map(|(i, url)| format!("[{}] {}", i + 1, url))
+                        .collect();
+                    self.add_message(&format!(
+                        "**Links in last response**\n{}\n\nUse /open <n> to open one",
+                        listing.join("\n")
+                    ));
+                }
+            }
             "/terminal-setup" => {
                 // Setup terminal keybindings for Shift+Enter - matches JavaScript
                 let terminal = std::env::var("TERM_PROGRAM").unwrap_or_else(|_| "unknown".to_string());
@@ -3552,53 +5470,62 @@ impl AppState {
                 }
             }
             "/export" => {
-                // Export conversation - matches JavaScript
-                let format = if parts.len() > 1 { parts[1] } else { "json" };
-
-                match format {
-                    "json" => {
-                        let export_data = serde_json::json!({
-                            "session_id": self.session_id,
-                            "model": self.current_model,
-                            "timestamp": crate::utils::timestamp_ms(),
-                            "messages": self.messages,
-                        });
-
-                        let filename = format!("conversation_{}.json", &self.session_id[..8]);
-                        let path = std::env::current_dir().unwrap_or_default().join(&filename);
-
-                        match std::fs::write(&path, serde_json::to_string_pretty(&export_data).unwrap_or_default()) {
-                            Ok(_) => self.add_message(&format!("✅ Exported to: {}", path.display())),
-                            Err(e) => self.add_error(&format!("Failed to export: {}", e)),
-                        }
+                // Export conversation as a shareable document
+                let format_arg = parts.get(1).copied().unwrap_or("md");
+                match format_arg.parse::<crate::session_store::ExportFormat>() {
+                    Err(e) => {
+                        self.add_error(&e.to_string());
                     }
-                    "md" | "markdown" => {
-                        let mut md = String::new();
-                        md.push_str(&format!("# Conversation {}\n\n", &self.session_id[..8]));
-                        md.push_str(&format!("Model: {}\n\n", self.current_model));
-                        md.push_str("---\n\n");
-
-                        for msg in &self.messages {
-                            let role = match msg.role.as_str() {
-                                "user" => "**User**",
-                                "assistant" => "**Assistant**",
-                                "system" => "**System**",
-                                _ => &msg.role,
-                            };
-                            md.push_str(&format!("{}\n\n{}\n\n---\n\n", role, msg.content));
-                        }
-
-                        let filename = format!("conversation_{}.md", &self.session_id[..8]);
-                        let path = std::env::current_dir().unwrap_or_default().join(&filename);
-
-                        match std::fs::write(&path, &md) {
-                            Ok(_) => self.add_message(&format!("✅ Exported to: {}", path.display())),
-                            Err(e) => self.add_error(&format!("Failed to export: {}", e)),
+                    Ok(format) => {
+                        let conversation = ConversationData {
+                            session_id: self.session_id.clone(),
+                            model: self.current_model.clone(),
+                            messages: self.messages.clone(),
+                            timestamp: crate::utils::timestamp_ms(),
+                            tool_schema_version: crate::ai::tools::TOOL_SCHEMA_VERSION,
+                            input_tokens: self.session_input_tokens,
+                            output_tokens: self.session_output_tokens,
+                            working_directories: Vec::new(),
+                            annotations: self.annotations.clone(),
+                        };
+                        let record = conversation_to_record(&conversation);
+                        match crate::session_store::export_session(&record, format) {
+                            Ok(document) => {
+                                // Explicit path wins; the default lands next
+                                // to the conversations under .claude/exports
+                                let path = match parts.get(2) {
+                                    Some(p) => PathBuf::from(p),
+                                    None => self
+                                        .conversation_dir
+                                        .parent()
+                                        .unwrap_or(&self.conversation_dir)
+                                        .join("exports")
+                                        .join(format!(
+                                            "{}.{}",
+                                            self.session_id,
+                                            format.extension()
+                                        )),
+                                };
+                                let write_result = path
+                                    .parent()
+                                    .map(fs::create_dir_all)
+                                    .unwrap_or(Ok(()))
+                                    .and_then(|_| fs::write(&path, document));
+                                match write_result {
+                                    Ok(()) => self.add_message(&format!(
+                                        "✅ Exported to: {}",
+                                        path.display()
+                                    )),
+                                    Err(e) => {
+                                        self.add_error(&format!("Failed to export: {}", e))
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                self.add_error(&format!("Failed to export: {}", e));
+                            }
                         }
                     }
-                    _ => {
-                        self.add_error(&format!("Unknown format: {}. Use 'json' or 'md'", format));
-                    }
                 }
             }
             "/rename" => {
@@ -3615,15 +5542,31 @@ impl AppState {
                     }
                 }
             }
+            cmd if cmd.starts_with("/mcp__") => {
+                return self.run_mcp_prompt_command(command).await;
+            }
             _ => {
                 self.add_error(&format!("Unknown command: {}", parts[0]));
             }
         }
-        
+
         Ok(())
     }
     
     /// Show command help
+    /// Add a directory chosen from the file picker to the session's working
+    /// directories (same effect as `/add-dir <path>` without persistence)
+    pub fn add_picked_directory(&mut self, path: PathBuf) {
+        self.working_directories.insert(path.clone());
+        if let Ok(mut ctx) = crate::permissions::PERMISSION_CONTEXT.try_lock() {
+            ctx.allow_directory(path.clone());
+        }
+        self.add_command_output(&format!(
+            "Added directory: {} for this session",
+            path.display()
+        ));
+    }
+
     fn show_command_help(&mut self) {
         let help = r#"Available commands:
   /help                    Show this help
@@ -3631,12 +5574,34 @@ impl AppState {
   /save                    Save current conversation
   /load <id>               Load a conversation
   /resume [id]             Resume last or specific conversation
+  /search <query>          Search messages across saved sessions
+  /export [format] [path]  Export this conversation (md, html, or jsonl)
+  /undo                    Restore files to before the last turn's edits
+  /redo                    Reapply the last undone file changes
+  /debug step on|off       Pause before each model request and tool call
   /model [name]            Show or change model
   /tools                   Show available tools
+  /artifacts               Browse artifacts stored for this session
+  /dry-run [on|off]        Toggle dry-run previews for mutating tools
+  /think [tokens|off]      Enable extended thinking with an optional token budget
+  /lang [language|off]     Set the response language (add --project to share)
+  /tips [on|off]           Toggle the startup tip shown each session
+  /voice [seconds]         Record from the microphone and insert the transcript
+  /tts [on|off|summary]    Read completed responses aloud
+  /retry [model] [--temp t] Regenerate the last response, keeping the old one as a variant
+  /variants [keep 1|2]     Compare retry variants and choose the canonical one
+  /release-notes [ver|all] Show release notes for a version or the full changelog
   /mcp [subcommand]        MCP server commands (enable, disable, reconnect)
   /compact [instructions]  Clear conversation but keep summary
   /context                 Show context usage visualization
+  /context --detail        Per-message token heat map
+  /context prune <n...>    Replace listed messages with a stub
+  /prune                   Mark messages to exclude from model context
   /cost                    Show estimated token cost
+  /cost-limit [dollars|off] Pause turns that cost more than the limit
+  /tier [fast|standard|off] Trade latency for cost via the service tier
+  /agents                  List custom agents from .claude/agents/
+  /stats                   Show workspace statistics (languages, LOC, read coverage)
   /settings                Show current settings
   /vim                     Toggle vim mode
   /add-dir <path> [flags]  Add working directory
@@ -3652,6 +5617,8 @@ impl AppState {
   /logout                  Sign out and clear credentials
   /upgrade                 Upgrade information
   /memory [list|edit|show] Manage Claude memory files
+  /system [show|append <text>|clear] Inspect or extend the assembled system prompt
+  /profile [name|clear]    Switch between named auth profiles
   /permissions [action]    Manage tool permissions
   /allowed-tools           Alias for /permissions
   /plugin [subcommand]     Plugin management (install, enable, marketplace)
@@ -3659,6 +5626,7 @@ impl AppState {
   /status                  Show Claude Code status
   /hooks                   Show registered hooks
   /bug                     Report a bug (opens GitHub issues)
+  /open [n]                Open footnote link [n] from the last response
   /terminal-setup          Setup terminal keybindings
   /export [format]         Export conversation (json, md)
   /rename <name>           Rename current session
@@ -3679,7 +5647,8 @@ To add MCP servers, use the CLI:
   llminate mcp add <name> <command> [args...]
 
 Available /mcp commands:
-  /mcp                          Show this help
+  /mcp                          Open the server health dashboard
+  /mcp help                     Show this help
   /mcp enable [server-name]     Enable server(s), or all if no name given
   /mcp disable [server-name]    Disable server(s), or all if no name given
   /mcp reconnect <server-name>  Reconnect to a server
@@ -3704,7 +5673,16 @@ See: https://code.claude.com/docs/en/mcp"#;
                 let status = self.mcp_server_status.get(name)
                     .map(|s| if *s { "enabled" } else { "disabled" })
                     .unwrap_or("enabled");
-                output.push_str(&format!("  {} [{}]\n", name, status));
+                // Live transport state published by remote transports
+                // (reconnecting servers show up here during backoff)
+                match crate::mcp::connection_state(name) {
+                    Some(state) => {
+                        output.push_str(&format!("  {} [{}] · {}\n", name, status, state.as_str()));
+                    }
+                    None => {
+                        output.push_str(&format!("  {} [{}]\n", name, status));
+                    }
+                }
             }
 
             output.push_str("\nUse /mcp enable or /mcp disable to toggle servers\n");
@@ -3713,6 +5691,173 @@ See: https://code.claude.com/docs/en/mcp"#;
         }
     }
 
+    /// Open the MCP server health dashboard overlay
+    pub fn open_mcp_dashboard(&mut self) {
+        use crate::tui::components::mcp_dashboard::{McpDashboard, McpServerRow};
+
+        // Include servers whose startup failed (config known, no client)
+        // so they can be reconnected from the dashboard
+        let mut names: Vec<String> = self
+            .mcp_servers
+            .keys()
+            .chain(self.mcp_server_configs.keys())
+            .cloned()
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let rows = names
+            .into_iter()
+            .map(|name| {
+                let transport = self
+                    .mcp_servers
+                    .get(&name)
+                    .map(|client| client.transport().to_string())
+                    .or_else(|| {
+                        self.mcp_server_configs
+                            .get(&name)
+                            .map(|config| {
+                                config.transport_type.clone().unwrap_or_else(|| "stdio".to_string())
+                            })
+                    })
+                    .unwrap_or_else(|| "unknown".to_string());
+                let health = crate::mcp::server_health(&name).unwrap_or_default();
+                // Servers without a live client show as disconnected even
+                // when their transport never published a state (stdio)
+                let connection_state = if self.mcp_servers.contains_key(&name) {
+                    crate::mcp::connection_state(&name).map(|state| state.as_str().to_string())
+                } else {
+                    Some("disconnected".to_string())
+                };
+                McpServerRow {
+                    transport,
+                    connection_state,
+                    enabled: self.mcp_server_status.get(&name).copied().unwrap_or(true),
+                    tool_count: health.tool_count,
+                    last_latency_ms: health.last_latency_ms,
+                    last_error: health.last_error,
+                    name,
+                }
+            })
+            .collect();
+
+        self.mcp_dashboard = Some(McpDashboard::new(rows));
+    }
+
+    /// Apply a dashboard action (enable/disable/reconnect), keeping the
+    /// open dashboard's rows in sync with the result
+    pub async fn apply_mcp_dashboard_action(
+        &mut self,
+        action: crate::tui::components::mcp_dashboard::McpDashboardAction,
+    ) {
+        use crate::tui::components::mcp_dashboard::McpDashboardAction;
+
+        match action {
+            McpDashboardAction::Enable(name) => {
+                self.mcp_enable(&name).await;
+                if let Some(dashboard) = self.mcp_dashboard.as_mut() {
+                    dashboard.set_enabled(&name, true);
+                }
+            }
+            McpDashboardAction::Disable(name) => {
+                self.mcp_disable(&name).await;
+                if let Some(dashboard) = self.mcp_dashboard.as_mut() {
+                    dashboard.set_enabled(&name, false);
+                }
+            }
+            McpDashboardAction::Reconnect(name) => {
+                self.mcp_reconnect(&name).await;
+                // Rebuild rows so the new connection state and health show up
+                let selected = self
+                    .mcp_dashboard
+                    .as_ref()
+                    .map(|dashboard| dashboard.selected)
+                    .unwrap_or(0);
+                self.open_mcp_dashboard();
+                if let Some(dashboard) = self.mcp_dashboard.as_mut() {
+                    dashboard.selected = selected.min(dashboard.rows.len().saturating_sub(1));
+                }
+            }
+            McpDashboardAction::Close => {
+                self.mcp_dashboard = None;
+            }
+            McpDashboardAction::None => {}
+        }
+    }
+
+    /// Open the /prune overlay listing the messages that get resent to
+    /// the model (user/assistant roles - tool, error, and command output
+    /// are display-only and never leave the terminal)
+    pub fn open_prune_view(&mut self) {
+        use crate::tui::components::prune_view::{PruneRow, PruneView};
+
+        let rows: Vec<PruneRow> = self
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| matches!(msg.role.as_str(), "user" | "assistant"))
+            .map(|(index, msg)| PruneRow {
+                index,
+                role: msg.role.clone(),
+                tokens: msg.content.len() / 4,
+                excerpt: msg
+                    .content
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .chars()
+                    .take(40)
+                    .collect(),
+                excluded: self.context_excluded.contains(&index),
+            })
+            .collect();
+
+        self.prune_view = Some(PruneView::new(rows));
+    }
+
+    /// Apply a prune overlay action. Toggles update the exclusion set and
+    /// stage a rebuilt history for the agent's next turn
+    pub fn apply_prune_action(&mut self, action: crate::tui::components::prune_view::PruneAction) {
+        use crate::tui::components::prune_view::PruneAction;
+
+        match action {
+            PruneAction::Toggle(index) => {
+                if !self.context_excluded.remove(&index) {
+                    self.context_excluded.insert(index);
+                }
+                self.rebuild_pruned_context();
+            }
+            PruneAction::Close => {
+                self.prune_view = None;
+            }
+            PruneAction::None => {}
+        }
+    }
+
+    /// Rebuild the AI conversation history from the transcript minus the
+    /// excluded messages and stage it as loaded_ai_messages, which
+    /// replaces the agent loop's history on the next send (the same
+    /// mechanism /resume uses)
+    fn rebuild_pruned_context(&mut self) {
+        let mut ai_messages = Vec::new();
+        for (index, msg) in self.messages.iter().enumerate() {
+            if self.context_excluded.contains(&index) {
+                continue;
+            }
+            let role = match msg.role.as_str() {
+                "user" => crate::ai::MessageRole::User,
+                "assistant" => crate::ai::MessageRole::Assistant,
+                _ => continue,
+            };
+            ai_messages.push(crate::ai::Message {
+                role,
+                content: crate::ai::MessageContent::Text(msg.content.clone()),
+                name: None,
+            });
+        }
+        self.loaded_ai_messages = Some(ai_messages);
+    }
+
     /// Enable MCP server(s)
     /// JavaScript: variable28958 component with action="enable"
     async fn mcp_enable(&mut self, target: &str) {
@@ -3768,22 +5913,45 @@ See: https://code.claude.com/docs/en/mcp"#;
     /// Reconnect to MCP server
     /// JavaScript: variable8137 component
     async fn mcp_reconnect(&mut self, server_name: &str) {
-        if self.mcp_servers.is_empty() {
+        if self.mcp_servers.is_empty() && self.mcp_server_configs.is_empty() {
             self.add_error("No MCP servers configured. Use `llminate mcp add` to add a server.");
             return;
         }
 
-        if self.mcp_servers.contains_key(server_name) {
-            self.add_message(&format!("Reconnecting to MCP server '{}'...", server_name));
-            // In a full implementation, we would:
-            // 1. Close the existing connection
-            // 2. Re-initialize the MCP client for this server
-            // 3. Re-establish the connection
-            // For now, we just update the status
-            self.mcp_server_status.insert(server_name.to_string(), true);
-            self.add_message(&format!("MCP server '{}' reconnected", server_name));
-        } else {
+        // Look up the config the server was started from (falling back to
+        // the global config) so the connection can actually be
+        // re-established, not just marked as such
+        let config = self
+            .mcp_server_configs
+            .get(server_name)
+            .cloned()
+            .or_else(|| {
+                crate::config::get_all_mcp_servers()
+                    .ok()
+                    .and_then(|servers| servers.get(server_name).cloned())
+            });
+        let Some(config) = config else {
             self.add_error(&format!("MCP server '{}' not found", server_name));
+            return;
+        };
+
+        self.add_message(&format!("Reconnecting to MCP server '{}'...", server_name));
+
+        // Drop the old client (shutting its transport down), then run the
+        // full handshake again
+        self.mcp_servers.remove(server_name);
+        match crate::mcp::connect_and_initialize(server_name, &config).await {
+            Ok(client) => {
+                self.add_mcp_server(server_name.to_string(), client);
+                self.mcp_server_status.insert(server_name.to_string(), true);
+                self.add_message(&format!("MCP server '{}' reconnected", server_name));
+            }
+            Err(e) => {
+                self.add_error(&format!(
+                    "Failed to reconnect MCP server '{}': {}",
+                    server_name, e
+                ));
+            }
         }
     }
 
@@ -4387,34 +6555,92 @@ Other:
             model: self.current_model.clone(),
             messages: self.messages.clone(),
             timestamp: crate::utils::timestamp_ms(),
+            tool_schema_version: crate::ai::tools::TOOL_SCHEMA_VERSION,
+            input_tokens: self.session_input_tokens,
+            output_tokens: self.session_output_tokens,
+            working_directories: self
+                .working_directories
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect(),
+            annotations: self.annotations.clone(),
         };
-        
+
+        if crate::session_store::sqlite_enabled() {
+            let store =
+                crate::session_store::SqliteSessionStore::open_default(&self.conversation_dir)?;
+            return store.save(&conversation_to_record(&conversation));
+        }
+
         let path = self.conversation_dir.join(format!("{}.json", self.session_id));
         fs::create_dir_all(&self.conversation_dir)?;
-        
+
         let json = serde_json::to_string_pretty(&conversation)?;
         fs::write(path, json)?;
-        
+
         Ok(())
     }
     
     /// Load conversation
     pub async fn load_conversation(&mut self, session_id: &str) -> Result<()> {
-        let path = self.conversation_dir.join(format!("{}.json", session_id));
-        
-        if !path.exists() {
-            return Err(Error::NotFound(format!("Session {} not found", session_id)));
-        }
-        
-        let json = fs::read_to_string(path)?;
-        let conversation: ConversationData = serde_json::from_str(&json)?;
-        
+        // The SQLite store is checked first when enabled; a miss falls
+        // back to the JSON file so sessions saved before switching (or
+        // copied in from elsewhere) still resume
+        let sqlite_record = if crate::session_store::sqlite_enabled() {
+            crate::session_store::SqliteSessionStore::open_default(&self.conversation_dir)?
+                .load(session_id)?
+        } else {
+            None
+        };
+
+        let conversation = if let Some(record) = sqlite_record {
+            record_to_conversation(record)
+        } else {
+            let path = self.conversation_dir.join(format!("{}.json", session_id));
+
+            if path.exists() {
+                let json = fs::read_to_string(path)?;
+                serde_json::from_str::<ConversationData>(&json)?
+            } else if let Some(found) = self.load_from_other_projects(session_id)? {
+                found
+            } else {
+                return Err(Error::NotFound(format!("Session {} not found", session_id)));
+            }
+        };
+
         self.session_id = conversation.session_id;
         self.current_model = conversation.model;
         self.messages = conversation.messages.clone();
+        self.session_input_tokens = conversation.input_tokens;
+        self.session_output_tokens = conversation.output_tokens;
+        self.annotations = conversation.annotations.clone();
         self.invalidate_cache();  // MUST invalidate cache after loading messages!
         self.scroll_to_bottom();
-        
+
+        // Restore the session's todo list (keyed by session id) and the
+        // working directories granted with /add-dir during the session
+        self.load_todos();
+        for dir in &conversation.working_directories {
+            let path = PathBuf::from(dir);
+            if path.is_dir() {
+                self.working_directories.insert(path.clone());
+                if let Ok(mut ctx) = crate::permissions::PERMISSION_CONTEXT.try_lock() {
+                    ctx.allow_directory(path);
+                }
+            }
+        }
+
+        // Detect sessions recorded under different tool semantics
+        let legacy_tool_schema =
+            conversation.tool_schema_version != crate::ai::tools::TOOL_SCHEMA_VERSION;
+        if legacy_tool_schema {
+            self.add_message(&format!(
+                "⚠ This session was recorded with tool schema v{} (current: v{}). Old tool results will be normalized before being replayed; tool behavior may differ from what the transcript shows.",
+                conversation.tool_schema_version,
+                crate::ai::tools::TOOL_SCHEMA_VERSION
+            ));
+        }
+
         // Reconstruct AI conversation history from the loaded messages
         // This allows the AI to have context when resuming
         let mut ai_messages = Vec::new();
@@ -4434,8 +6660,24 @@ Other:
                     }
                 }
                 "assistant" => {
-                    // Skip tool result messages, they're handled separately
-                    if !msg.content.starts_with("**Result:**") {
+                    if msg.content.starts_with("**Result:**") {
+                        // Legacy transcripts stored tool results as assistant
+                        // text; replay them in the user role (where tool_result
+                        // blocks live today) so the model doesn't mistake old
+                        // output for its own claims. Current-format sessions
+                        // skip them - proper results are handled separately.
+                        if legacy_tool_schema {
+                            let result_text = msg.content.trim_start_matches("**Result:**").trim();
+                            ai_messages.push(crate::ai::Message {
+                                role: crate::ai::MessageRole::User,
+                                content: crate::ai::MessageContent::Text(format!(
+                                    "[Tool result from a previous session]\n{}",
+                                    result_text
+                                )),
+                                name: None,
+                            });
+                        }
+                    } else {
                         ai_messages.push(crate::ai::Message {
                             role: crate::ai::MessageRole::Assistant,
                             content: crate::ai::MessageContent::Text(msg.content.clone()),
@@ -4456,12 +6698,87 @@ Other:
         
         Ok(())
     }
-    
-    /// List available sessions
-    async fn list_sessions(&self) -> Result<Vec<SessionInfo>> {
+    
+    /// Look a session up in every other project's scope. The
+    /// --all-projects picker offers sessions that don't live in this
+    /// project's conversation directory, so resume has to follow suit
+    fn load_from_other_projects(&self, session_id: &str) -> Result<Option<ConversationData>> {
+        if !self.all_projects {
+            return Ok(None);
+        }
+        for dir in all_project_conversation_dirs() {
+            if dir == self.conversation_dir {
+                continue;
+            }
+            if crate::session_store::sqlite_enabled() && dir.join("sessions.db").exists() {
+                if let Some(record) =
+                    crate::session_store::SqliteSessionStore::open_default(&dir)?.load(session_id)?
+                {
+                    return Ok(Some(record_to_conversation(record)));
+                }
+            }
+            let path = dir.join(format!("{}.json", session_id));
+            if path.exists() {
+                let json = fs::read_to_string(path)?;
+                return Ok(Some(serde_json::from_str::<ConversationData>(&json)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// List available sessions; with --all-projects, every project's
+    /// scope is included instead of just this one's
+    async fn list_sessions(&self) -> Result<Vec<SessionInfo>> {
+        let mut dirs = vec![self.conversation_dir.clone()];
+        if self.all_projects {
+            for dir in all_project_conversation_dirs() {
+                if !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+
+        let mut sessions = Vec::new();
+        for (index, dir) in dirs.iter().enumerate() {
+            match Self::sessions_in_dir(dir, index == 0) {
+                Ok(found) => sessions.extend(found),
+                // A broken store in some other project shouldn't break
+                // the picker for this one
+                Err(e) if index > 0 => {
+                    tracing::warn!("Skipping sessions in {}: {}", dir.display(), e)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        sessions.sort_by(|a, b| b.modified_timestamp.cmp(&a.modified_timestamp));
+        Ok(sessions)
+    }
+
+    /// Sessions in one conversation directory. Only the current project's
+    /// SQLite store is created on demand (so legacy JSON gets imported);
+    /// other projects are read only if they already have one
+    fn sessions_in_dir(dir: &Path, create_store: bool) -> Result<Vec<SessionInfo>> {
+        if crate::session_store::sqlite_enabled()
+            && (create_store || dir.join("sessions.db").exists())
+        {
+            let store = crate::session_store::SqliteSessionStore::open_default(dir)?;
+            return Ok(store
+                .list()?
+                .into_iter()
+                .map(|summary| SessionInfo {
+                    id: summary.id,
+                    // Store timestamps are ms (from timestamp_ms); the
+                    // picker expects seconds like the file metadata path
+                    created_timestamp: summary.created_timestamp / 1000,
+                    modified_timestamp: summary.modified_timestamp / 1000,
+                })
+                .collect());
+        }
+
         let mut sessions = Vec::new();
-        
-        if let Ok(entries) = fs::read_dir(&self.conversation_dir) {
+
+        if let Ok(entries) = fs::read_dir(dir) {
             for entry in entries.flatten() {
                 if let Some(name) = entry.file_name().to_str() {
                     if name.ends_with(".json") {
@@ -4489,15 +6806,153 @@ Other:
                 }
             }
         }
-        
-        sessions.sort_by(|a, b| b.modified_timestamp.cmp(&a.modified_timestamp));
+
         Ok(sessions)
     }
-    
+
     /// Add MCP server
     pub fn add_mcp_server(&mut self, name: String, client: McpClient) {
         self.mcp_servers.insert(name, client);
     }
+
+    /// Fetch prompts/list from every connected MCP server and register
+    /// each prompt as a slash command (/mcp__server__prompt) so it shows
+    /// up in autocomplete with its argument hints
+    pub async fn load_mcp_prompts(&mut self) {
+        let server_names: Vec<String> = self.mcp_servers.keys().cloned().collect();
+
+        for server_name in server_names {
+            let prompts = match self.mcp_servers.get_mut(&server_name) {
+                // Servers without prompt support return an error here;
+                // that just means no commands to register
+                Some(client) => match client.list_prompts().await {
+                    Ok(prompts) => prompts,
+                    Err(_) => continue,
+                },
+                None => continue,
+            };
+
+            for prompt in prompts {
+                let command_name = format!("mcp__{}__{}", server_name, prompt.name);
+
+                // Build the argument hint from the prompt's declared
+                // arguments: <required> and [optional]
+                let argument_hint = if prompt.arguments.is_empty() {
+                    None
+                } else {
+                    Some(
+                        prompt.arguments.iter()
+                            .map(|arg| if arg.required {
+                                format!("<{}>", arg.name)
+                            } else {
+                                format!("[{}]", arg.name)
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    )
+                };
+
+                self.available_commands.push(CommandInfo {
+                    name: command_name.clone(),
+                    aliases: vec![],
+                    description: prompt.description.clone()
+                        .unwrap_or_else(|| format!("Prompt from MCP server '{}'", server_name)),
+                    argument_hint,
+                    command_type: "prompt".to_string(),
+                    is_enabled: true,
+                });
+                self.mcp_prompts.insert(command_name, (server_name.clone(), prompt));
+            }
+        }
+    }
+
+    /// Expand an MCP prompt command into the next user message: map the
+    /// positional arguments onto the prompt's declared arguments, ask the
+    /// server to render the template, and submit the result
+    async fn run_mcp_prompt_command(&mut self, command: &str) -> Result<()> {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let command_name = parts[0].trim_start_matches('/');
+
+        let Some((server_name, prompt)) = self.mcp_prompts.get(command_name).cloned() else {
+            self.add_error(&format!("Unknown MCP prompt: {}", parts[0]));
+            return Ok(());
+        };
+
+        // Map positional words onto declared arguments; the last declared
+        // argument swallows any remaining words so free-text arguments work
+        let words = &parts[1..];
+        let mut arguments = serde_json::Map::new();
+        for (index, arg) in prompt.arguments.iter().enumerate() {
+            let value = if index + 1 == prompt.arguments.len() && words.len() > index + 1 {
+                Some(words[index..].join(" "))
+            } else {
+                words.get(index).map(|w| w.to_string())
+            };
+            match value {
+                Some(value) => {
+                    arguments.insert(arg.name.clone(), serde_json::Value::String(value));
+                }
+                None if arg.required => {
+                    let hint = prompt.arguments.iter()
+                        .map(|a| if a.required {
+                            format!("<{}>", a.name)
+                        } else {
+                            format!("[{}]", a.name)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    self.add_error(&format!("Usage: /{} {}", command_name, hint));
+                    return Ok(());
+                }
+                None => {}
+            }
+        }
+
+        let Some(client) = self.mcp_servers.get_mut(&server_name) else {
+            self.add_error(&format!("MCP server '{}' is not connected", server_name));
+            return Ok(());
+        };
+
+        let expanded = match client
+            .get_prompt(&prompt.name, serde_json::Value::Object(arguments))
+            .await
+        {
+            Ok(text) if !text.is_empty() => text,
+            Ok(_) => {
+                self.add_error(&format!("MCP server '{}' returned an empty prompt", server_name));
+                return Ok(());
+            }
+            Err(e) => {
+                self.add_error(&format!("Failed to expand prompt: {}", e));
+                return Ok(());
+            }
+        };
+
+        // Send the expanded prompt exactly like a typed user message
+        self.messages.push(Message {
+            role: "user".to_string(),
+            content: expanded.clone(),
+            timestamp: crate::utils::timestamp_ms(),
+        });
+        crate::checkpoint::begin_turn(self.messages.len() - 1);
+        self.invalidate_cache();
+        self.scroll_to_bottom();
+        self.input_mode = false;
+        self.is_processing = true;
+        self.processing_started_at = Some(std::time::Instant::now());
+        self.streaming_output_tokens = 0;
+        self.current_task_status = Some("Processing request...".to_string());
+
+        if let Some(agent_tx) = &self.agent_tx {
+            let loaded = self.loaded_ai_messages.take();
+            let _ = agent_tx.send((expanded, loaded, self.current_model.clone(), None, self.thinking_budget));
+        } else {
+            self.add_message("Error: Agent loop not initialized");
+            self.is_processing = false;
+        }
+
+        Ok(())
+    }
     
     /// Handle resize
     pub fn handle_resize(&mut self, width: u16, height: u16) {
@@ -4563,6 +7018,8 @@ Other:
     /// Continue conversation with tool result after permission
     async fn continue_conversation_with_tool_result(&mut self, initial_tool_result: crate::ai::ContentPart) -> Result<()> {
         self.is_processing = true;
+        self.processing_started_at = Some(std::time::Instant::now());
+        self.streaming_output_tokens = 0;
         
         // Build conversation history
         let mut messages = Vec::new();
@@ -4613,11 +7070,8 @@ Other:
                 .temperature(0.7);
             
             // Set system prompt
-            let system = if let Some(prompt) = &self.system_prompt {
-                prompt.clone()
-            } else {
-                crate::ai::system_prompt::get_system_prompt("Claude Code")
-            };
+            let system =
+                crate::ai::system_prompt::assemble_session_prompt(self.system_prompt.as_deref());
             request = request.system(system);
             
             // Add tools
@@ -4747,8 +7201,9 @@ Other:
     /// Clear messages and reset session state
     /// This performs a full cleanup similar to JavaScript's /clear command
     pub fn clear_messages(&mut self) {
-        // Clear conversation messages
+        // Clear conversation messages (and the feedback keyed to them)
         self.messages.clear();
+        self.annotations.clear();
         self.scroll_offset = 0;
 
         // Invalidate the rendered lines cache
@@ -4775,6 +7230,10 @@ Other:
         // Clear loaded AI messages from previous session
         self.loaded_ai_messages = None;
 
+        // Clear /prune exclusions - they are transcript-index based
+        self.context_excluded.clear();
+        self.prune_view = None;
+
         // TODO: Execute SessionEnd hooks when hook system is implemented
         // TODO: Execute SessionStart hooks when hook system is implemented
         // TODO: Clear MCP context when MCP system tracks state
@@ -4900,6 +7359,8 @@ Other:
             tool_choice: None,
             metadata: None,
             betas: None,
+            thinking: None,
+            service_tier: None,
         };
 
         // Send request to AI
@@ -5001,7 +7462,7 @@ Other:
                 context.push_str("## README.md\n```\n");
                 // Truncate if too long
                 if content.len() > 8000 {
-                    context.push_str(&content[..8000]);
+                    context.push_str(crate::utils::truncate_bytes(&content, 8000));
                     context.push_str("\n... (truncated)\n");
                 } else {
                     context.push_str(&content);
@@ -5036,7 +7497,7 @@ Other:
             if let Ok(content) = tokio::fs::read_to_string(&makefile_path).await {
                 context.push_str("## Makefile\n```makefile\n");
                 if content.len() > 4000 {
-                    context.push_str(&content[..4000]);
+                    context.push_str(crate::utils::truncate_bytes(&content, 4000));
                     context.push_str("\n... (truncated)\n");
                 } else {
                     context.push_str(&content);
@@ -5105,6 +7566,8 @@ This file provides guidance to Claude Code (claude.ai/code) when working with co
             tool_choice: None,
             metadata: None,
             betas: None,
+            thinking: None,
+            service_tier: None,
         };
 
         // Send request
@@ -5195,7 +7658,11 @@ This file provides guidance to Claude Code (claude.ai/code) when working with co
 
         // Truncate diff if too large
         let diff_truncated = if diff_content.len() > 50000 {
-            format!("{}...\n\n[Diff truncated - {} total bytes]", &diff_content[..50000], diff_content.len())
+            format!(
+                "{}...\n\n[Diff truncated - {} total bytes]",
+                crate::utils::truncate_bytes(&diff_content, 50000),
+                diff_content.len()
+            )
         } else {
             diff_content.to_string()
         };
@@ -5246,6 +7713,8 @@ Format your review with clear sections:
             tool_choice: None,
             metadata: None,
             betas: None,
+            thinking: None,
+            service_tier: None,
         };
 
         // Send request
@@ -5279,7 +7748,42 @@ Format your review with clear sections:
     pub fn toggle_debug(&mut self) {
         self.debug_mode = !self.debug_mode;
     }
-    
+
+    /// Ctrl+G: open the feedback dialog on the latest assistant response
+    pub fn start_annotation(&mut self) {
+        match self.messages.iter().rposition(|msg| msg.role == "assistant") {
+            Some(index) => {
+                self.annotation_flow =
+                    Some(AnnotationFlow::new(index, self.annotations.get(&index)));
+            }
+            None => self.add_message("No assistant response to annotate yet."),
+        }
+    }
+
+    /// Store a finished annotation and persist it with the transcript
+    pub async fn apply_annotation(
+        &mut self,
+        index: usize,
+        annotation: crate::session_store::MessageAnnotation,
+    ) {
+        let summary = format!(
+            "Feedback recorded: {}{}",
+            annotation.verdict(),
+            annotation
+                .note
+                .as_deref()
+                .map(|note| format!(" — {}", note))
+                .unwrap_or_default()
+        );
+        self.annotations.insert(index, annotation);
+        self.invalidate_cache();
+        self.add_command_output(&summary);
+        if self.auto_save {
+            let _ = self.save_conversation().await;
+        }
+    }
+
+
     /// Toggle tool panel
     pub fn toggle_tool_panel(&mut self) {
         self.show_tool_panel = !self.show_tool_panel;
@@ -5395,7 +7899,27 @@ Format your review with clear sections:
             _ => "-",
         }
     }
-    
+
+    /// Build the in-flight stats shown next to the task status (matches the
+    /// JavaScript footer): elapsed time, streamed output tokens, and context
+    /// usage percentage derived from streaming usage deltas.
+    pub fn get_processing_stats(&self) -> Option<String> {
+        let started = self.processing_started_at?;
+        let elapsed = started.elapsed().as_secs();
+        let mut parts = vec![format!("{}s", elapsed)];
+        if self.streaming_output_tokens > 0 {
+            parts.push(format!("↓ {} tokens", self.streaming_output_tokens));
+        }
+        if self.streaming_input_tokens > 0 {
+            let limit = self.get_model_token_limit().max(1) as u64;
+            let used = self.streaming_input_tokens as u64 + self.streaming_output_tokens as u64;
+            let percent = (used * 100 / limit).min(100);
+            parts.push(format!("{}% context", percent));
+        }
+        Some(parts.join(" · "))
+    }
+
+
     /// Calculate the actual line count including pasted content placeholders
     pub fn calculate_input_line_count(&self) -> usize {
         let text = self.input_textarea.lines().join("\n");
@@ -5510,10 +8034,10 @@ Format your review with clear sections:
         // Simple command completion
         if line.starts_with('/') {
             let commands = vec![
-                "/help", "/clear", "/save", "/load", "/resume", "/model",
-                "/tools", "/mcp", "/compact", "/context", "/cost",
+                "/help", "/clear", "/save", "/load", "/resume", "/search", "/export", "/undo", "/redo", "/debug", "/model",
+                "/tools", "/artifacts", "/dry-run", "/think", "/lang", "/tips", "/voice", "/tts", "/retry", "/variants", "/release-notes", "/mcp", "/compact", "/context", "/cost", "/cost-limit", "/tier", "/agents", "/stats",
                 "/settings", "/vim", "/add-dir", "/files", "/config",
-                "/bashes", "/doctor", "/release-notes", "/exit", "/quit",
+                "/bashes", "/doctor", "/release-notes", "/open", "/prune", "/system", "/profile", "/exit", "/quit",
             ];
             
             for cmd in commands {
@@ -5635,14 +8159,186 @@ Format your review with clear sections:
         total
     }
 
+    /// Per-message token heat map for `/context --detail`. Bars use the
+    /// same chars/4 estimate as estimate_token_count; rows marked `·` are
+    /// display-only roles (tool, error, command output) that are never
+    /// resent to the model but still weigh on /compact summarization
+    fn show_context_detail(&mut self) {
+        if self.messages.is_empty() {
+            self.add_command_output("No messages in this conversation yet.");
+            return;
+        }
+
+        let tokens: Vec<usize> = self.messages.iter().map(|m| m.content.len() / 4).collect();
+        let max_tokens = tokens.iter().copied().max().unwrap_or(0).max(1);
+        const BAR_WIDTH: usize = 20;
+
+        let mut output = String::from("Per-message token usage (estimated)\n\n");
+        for (i, msg) in self.messages.iter().enumerate() {
+            let t = tokens[i];
+            let filled = if t == 0 {
+                0
+            } else {
+                (t * BAR_WIDTH / max_tokens).clamp(1, BAR_WIDTH)
+            };
+            let bar: String = "█".repeat(filled) + &"░".repeat(BAR_WIDTH - filled);
+
+            let counted = matches!(msg.role.as_str(), "user" | "assistant");
+            let marker = if counted { ' ' } else { '·' };
+            let amount = if t >= 1000 {
+                format!("{:.1}k", t as f64 / 1000.0)
+            } else {
+                t.to_string()
+            };
+
+            let excerpt: String = msg
+                .content
+                .lines()
+                .next()
+                .unwrap_or("")
+                .chars()
+                .take(36)
+                .collect();
+
+            output.push_str(&format!(
+                "{:>3}{} {:<14} {} {:>6}  {}\n",
+                i + 1,
+                marker,
+                msg.role,
+                bar,
+                amount,
+                excerpt
+            ));
+        }
+
+        output.push_str(&format!(
+            "\nTotal: ~{:.1}k tokens across {} messages\n",
+            tokens.iter().sum::<usize>() as f64 / 1000.0,
+            self.messages.len()
+        ));
+        output.push_str("· display-only (not resent to the model)\n");
+        output.push_str("Prune bloated messages with /context prune <n> [n...] or interactively with /prune");
+        self.add_command_output(&output);
+    }
+
+    /// Replace the listed messages (numbers from `/context --detail`) with
+    /// a short stub, freeing their tokens from future requests
+    fn prune_context_messages(&mut self, args: &[&str]) {
+        if args.is_empty() {
+            self.add_error("Usage: /context prune <n> [n...] (numbers from /context --detail)");
+            return;
+        }
+
+        let mut indices = Vec::new();
+        for arg in args {
+            match arg.parse::<usize>() {
+                Ok(n) if n >= 1 && n <= self.messages.len() => indices.push(n - 1),
+                _ => {
+                    self.add_error(&format!(
+                        "Invalid message number '{}' (expected 1-{})",
+                        arg,
+                        self.messages.len()
+                    ));
+                    return;
+                }
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut freed = 0usize;
+        let mut pruned = 0usize;
+        for &i in &indices {
+            let msg = &mut self.messages[i];
+            if msg.content.starts_with("[pruned ") {
+                continue;
+            }
+            let t = msg.content.len() / 4;
+            msg.content = format!("[pruned {} message, ~{} tokens]", msg.role, t);
+            freed += t;
+            pruned += 1;
+        }
+
+        self.invalidate_cache();
+        self.add_command_output(&format!(
+            "Pruned {} message(s), freeing ~{} tokens of context",
+            pruned, freed
+        ));
+    }
+
+    /// The fully assembled system prompt for `/system show`: every named
+    /// section with its chars/4 token estimate, then the section contents.
+    /// The betas row is header-only (anthropic-beta), never prompt text
+    fn show_system_prompt(&mut self) {
+        let builder = crate::ai::system_prompt::SystemPromptBuilder::for_session(
+            self.system_prompt.as_deref(),
+            &self.current_model,
+        );
+
+        let mut output = String::from("System prompt sections (estimated tokens)\n\n");
+        let mut total = 0usize;
+        for section in builder.sections() {
+            let t = section.content.len() / 4;
+            let amount = if t >= 1000 {
+                format!("{:.1}k", t as f64 / 1000.0)
+            } else {
+                t.to_string()
+            };
+            if section.display_only {
+                output.push_str(&format!(
+                    "  {:<14} {:>6}  · header-only ({})\n",
+                    section.name, amount, section.content
+                ));
+            } else {
+                output.push_str(&format!("  {:<14} {:>6}\n", section.name, amount));
+                total += t;
+            }
+        }
+        output.push_str(&format!("  {:<14} {:>6}\n", "total", {
+            if total >= 1000 {
+                format!("{:.1}k", total as f64 / 1000.0)
+            } else {
+                total.to_string()
+            }
+        }));
+
+        for section in builder.sections() {
+            if section.display_only {
+                continue;
+            }
+            output.push_str(&format!("\n--- {} ---\n{}\n", section.name, section.content));
+        }
+        output.push_str("\nAdd session-scoped instructions with /system append <text>");
+        self.add_command_output(&output);
+    }
+
+    /// Hash the current transcript (model + per-message role/content) for
+    /// the token count cache
+    fn conversation_fingerprint(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.current_model.hash(&mut hasher);
+        for msg in &self.messages {
+            msg.role.hash(&mut hasher);
+            msg.content.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
     /// Count conversation tokens using the Anthropic API
-    /// Returns accurate token count for the messages in this conversation
-    pub async fn count_conversation_tokens(&self) -> crate::error::Result<u64> {
+    /// Returns accurate token count for the messages in this conversation,
+    /// cached per transcript fingerprint so repeated calls are cheap
+    pub async fn count_conversation_tokens(&mut self) -> crate::error::Result<u64> {
         // Skip if no messages to count
         if self.messages.is_empty() {
             return Ok(0);
         }
 
+        let fingerprint = self.conversation_fingerprint();
+        if let Some(count) = self.token_count_cache.get(&fingerprint) {
+            return Ok(*count);
+        }
+
         // Build AI messages from UI messages
         let mut ai_messages = Vec::new();
         for msg in &self.messages {
@@ -5669,6 +8365,8 @@ Format your review with clear sections:
         let client = crate::ai::create_client().await?;
         let response = client.count_tokens(request).await?;
 
+        self.token_count_cache
+            .insert(fingerprint, response.input_tokens);
         Ok(response.input_tokens)
     }
 
@@ -5703,6 +8401,30 @@ Format your review with clear sections:
             .unwrap_or(0)
     }
 
+    /// Open the model picker dialog, built on the generic dialog toolkit
+    pub fn open_model_picker(&mut self) {
+        use crate::tui::components::dialogs::{SelectDialog, SelectItem};
+
+        let items: Vec<SelectItem> = self
+            .get_available_models()
+            .iter()
+            .map(|(name, model_id, description)| {
+                let mut item = SelectItem::new(*name)
+                    .with_description(format!("{} ({})", description, model_id));
+                if *model_id == self.current_model {
+                    item = item.with_marker("(current)");
+                }
+                item
+            })
+            .collect();
+
+        self.model_picker = Some(
+            SelectDialog::new("Select Model", items)
+                .with_prompt("Choose a model for this session:")
+                .with_selected(self.get_model_picker_index()),
+        );
+    }
+
     /// Select a model from the picker by index
     pub fn select_model_by_index(&mut self, index: usize) {
         let models = self.get_available_models();
@@ -5710,7 +8432,7 @@ Format your review with clear sections:
             self.current_model = models[index].1.to_string();
             self.add_message(&format!("Model changed to: {} ({})", models[index].0, models[index].1));
         }
-        self.show_model_picker = false;
+        self.model_picker = None;
     }
 
     pub fn format_relative_time(&self, timestamp: u64) -> String {
@@ -5751,8 +8473,8 @@ Format your review with clear sections:
         use ratatui::prelude::*;
         
         let mut all_lines: Vec<Line> = Vec::new();
-        
-        for msg in self.messages.iter() {
+
+        for (msg_index, msg) in self.messages.iter().enumerate() {
             match msg.role.as_str() {
                 "user" => {
                     // Use bright magenta for user messages to ensure visibility
@@ -5918,8 +8640,26 @@ Format your review with clear sections:
                     }
                 }
             }
+
+            // Feedback left on this message (Ctrl+G)
+            if let Some(annotation) = self.annotations.get(&msg_index) {
+                let mut spans = vec![
+                    Span::raw("  ⎿  "),
+                    Span::styled(
+                        format!("{} Feedback", annotation.verdict()),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                ];
+                if let Some(note) = &annotation.note {
+                    spans.push(Span::styled(
+                        format!(": {}", note),
+                        Style::default().fg(Color::Gray),
+                    ));
+                }
+                all_lines.push(Line::from(spans));
+            }
         }
-        
+
         self.rendered_lines_cache = all_lines;
         self.cache_valid = true;
         self.cache_expanded_state = self.expanded_view;
@@ -6067,6 +8807,16 @@ Format your review with clear sections:
                     false
                 }
             }
+            "Browser" => {
+                // Mutating browser actions (and arbitrary JS) go through
+                // the dialog; screenshot/text only observe the page
+                matches!(
+                    input.get("action").and_then(|v| v.as_str()),
+                    Some("navigate") | Some("click") | Some("fill") | Some("evaluate")
+                        | Some("close")
+                        | None
+                )
+            }
             _ => false, // Other tools don't need permission by default
         }
     }
@@ -6095,7 +8845,20 @@ Format your review with clear sections:
     fn extract_permission_details(&self, tool_name: &str, input: &Value) -> String {
         match tool_name {
             "Bash" => {
-                input.get("command").and_then(|v| v.as_str()).unwrap_or("").to_string()
+                let command = input.get("command").and_then(|v| v.as_str()).unwrap_or("");
+                // Surface statically detected risk factors (command substitution,
+                // sudo, pipe-to-shell, redirects outside allowed dirs, env
+                // exfiltration) in the dialog instead of just the raw string
+                let mut allowed_dirs: Vec<PathBuf> = self.working_directories.iter().cloned().collect();
+                if let Ok(cwd) = std::env::current_dir() {
+                    allowed_dirs.push(cwd);
+                }
+                allowed_dirs.push(PathBuf::from("/tmp"));
+                format!(
+                    "{}{}",
+                    command,
+                    crate::command_analysis::format_risk_factors(command, &allowed_dirs)
+                )
             }
             "Edit" | "MultiEdit" => {
                 input.get("file_path").and_then(|v| v.as_str()).unwrap_or("").to_string()
@@ -6106,6 +8869,26 @@ Format your review with clear sections:
             "Read" => {
                 input.get("file_path").and_then(|v| v.as_str()).unwrap_or("").to_string()
             }
+            "Browser" => {
+                let action = input.get("action").and_then(|v| v.as_str()).unwrap_or("");
+                match action {
+                    "navigate" => format!(
+                        "Navigate to: {}",
+                        input.get("url").and_then(|v| v.as_str()).unwrap_or("<unknown>")
+                    ),
+                    "click" | "fill" => format!(
+                        "{} element: {}",
+                        if action == "click" { "Click" } else { "Fill" },
+                        input.get("selector").and_then(|v| v.as_str()).unwrap_or("<unknown>")
+                    ),
+                    "evaluate" => format!(
+                        "Run JavaScript: {}",
+                        input.get("expression").and_then(|v| v.as_str()).unwrap_or("<unknown>")
+                    ),
+                    "close" => "Close the browser session".to_string(),
+                    other => format!("Browser action: {}", other),
+                }
+            }
             _ => format!("Unknown tool operation: {}", tool_name),
         }
     }
@@ -6241,6 +9024,22 @@ Format your review with clear sections:
                 command_type: "local".to_string(),
                 is_enabled: true,
             },
+            CommandInfo {
+                name: "profile".to_string(),
+                aliases: vec![],
+                description: "Switch between named auth profiles".to_string(),
+                argument_hint: Some("[name|clear]".to_string()),
+                command_type: "local".to_string(),
+                is_enabled: true,
+            },
+            CommandInfo {
+                name: "system".to_string(),
+                aliases: vec![],
+                description: "Inspect or extend the assembled system prompt".to_string(),
+                argument_hint: Some("[show|append <text>|clear]".to_string()),
+                command_type: "local".to_string(),
+                is_enabled: true,
+            },
             CommandInfo {
                 name: "clear".to_string(),
                 aliases: vec![],
@@ -6281,6 +9080,46 @@ Format your review with clear sections:
                 command_type: "local".to_string(),
                 is_enabled: true,
             },
+            CommandInfo {
+                name: "search".to_string(),
+                aliases: vec![],
+                description: "Search messages across saved sessions".to_string(),
+                argument_hint: Some("<query>".to_string()),
+                command_type: "local".to_string(),
+                is_enabled: true,
+            },
+            CommandInfo {
+                name: "export".to_string(),
+                aliases: vec![],
+                description: "Export this conversation as md, html, or jsonl".to_string(),
+                argument_hint: Some("[format] [path]".to_string()),
+                command_type: "local".to_string(),
+                is_enabled: true,
+            },
+            CommandInfo {
+                name: "undo".to_string(),
+                aliases: vec![],
+                description: "Restore files to before the last turn's edits".to_string(),
+                argument_hint: None,
+                command_type: "local".to_string(),
+                is_enabled: true,
+            },
+            CommandInfo {
+                name: "redo".to_string(),
+                aliases: vec![],
+                description: "Reapply the last undone file changes".to_string(),
+                argument_hint: None,
+                command_type: "local".to_string(),
+                is_enabled: true,
+            },
+            CommandInfo {
+                name: "debug".to_string(),
+                aliases: vec![],
+                description: "Toggle step mode: pause before each model request and tool call".to_string(),
+                argument_hint: Some("step on|off".to_string()),
+                command_type: "local".to_string(),
+                is_enabled: true,
+            },
             CommandInfo {
                 name: "continue".to_string(),
                 aliases: vec![],
@@ -6338,6 +9177,14 @@ Format your review with clear sections:
                 command_type: "local".to_string(),
                 is_enabled: true,
             },
+            CommandInfo {
+                name: "stats".to_string(),
+                aliases: vec![],
+                description: "Show workspace statistics".to_string(),
+                argument_hint: None,
+                command_type: "local".to_string(),
+                is_enabled: true,
+            },
             CommandInfo {
                 name: "vim".to_string(),
                 aliases: vec![],
@@ -6697,6 +9544,89 @@ struct ConversationData {
     model: String,
     messages: Vec<Message>,
     timestamp: u64,
+    /// Tool schema version the session was recorded under (0 = before versioning)
+    #[serde(default)]
+    tool_schema_version: u32,
+    /// Cumulative token usage across the session (0 for transcripts
+    /// saved before usage tracking)
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    /// Additional working directories granted during the session
+    /// (/add-dir without a persistence flag), restored on resume
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    working_directories: Vec<String>,
+    /// Feedback left on messages with Ctrl+G, keyed by message index
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    annotations: HashMap<usize, crate::session_store::MessageAnnotation>,
+}
+
+/// Convert to the session-store record shape (same fields, but the
+/// store keeps its own serde types so it doesn't depend on TUI internals)
+fn conversation_to_record(data: &ConversationData) -> crate::session_store::SessionRecord {
+    crate::session_store::SessionRecord {
+        session_id: data.session_id.clone(),
+        model: data.model.clone(),
+        messages: data
+            .messages
+            .iter()
+            .map(|msg| crate::session_store::StoredMessage {
+                role: msg.role.clone(),
+                content: msg.content.clone(),
+                timestamp: msg.timestamp,
+            })
+            .collect(),
+        timestamp: data.timestamp,
+        tool_schema_version: data.tool_schema_version,
+        input_tokens: data.input_tokens,
+        output_tokens: data.output_tokens,
+        metadata: {
+            let mut metadata = std::collections::HashMap::new();
+            if !data.working_directories.is_empty() {
+                metadata.insert(
+                    "working_directories".to_string(),
+                    serde_json::to_string(&data.working_directories).unwrap_or_default(),
+                );
+            }
+            if !data.annotations.is_empty() {
+                metadata.insert(
+                    "annotations".to_string(),
+                    serde_json::to_string(&data.annotations).unwrap_or_default(),
+                );
+            }
+            metadata
+        },
+    }
+}
+
+/// Convert a session-store record back to the TUI's persistence shape
+fn record_to_conversation(record: crate::session_store::SessionRecord) -> ConversationData {
+    let working_directories = record
+        .metadata
+        .get("working_directories")
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default();
+    let annotations = crate::session_store::annotations_from_metadata(&record.metadata);
+    ConversationData {
+        session_id: record.session_id,
+        model: record.model,
+        messages: record
+            .messages
+            .into_iter()
+            .map(|msg| Message {
+                role: msg.role,
+                content: msg.content,
+                timestamp: msg.timestamp,
+            })
+            .collect(),
+        timestamp: record.timestamp,
+        tool_schema_version: record.tool_schema_version,
+        input_tokens: record.input_tokens,
+        output_tokens: record.output_tokens,
+        working_directories,
+        annotations,
+    }
 }
 
 /// Session info
@@ -6840,11 +9770,139 @@ impl Session {
     }
 }
 
-/// Get conversation directory
-fn get_conversation_dir() -> PathBuf {
-    // Match JavaScript - store in current working directory's .claude folder
-    std::env::current_dir()
+/// Map a file extension to a display language name for /stats
+fn language_for_extension(ext: &str) -> &'static str {
+    match ext {
+        "rs" => "Rust",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "py" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "cxx" | "hpp" => "C++",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "swift" => "Swift",
+        "kt" | "kts" => "Kotlin",
+        "sh" | "bash" | "zsh" => "Shell",
+        "md" | "markdown" => "Markdown",
+        "json" => "JSON",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "html" | "htm" => "HTML",
+        "css" | "scss" | "less" => "CSS",
+        "sql" => "SQL",
+        "proto" => "Protobuf",
+        "lock" => "Lockfiles",
+        "" => "(no extension)",
+        _ => "Other",
+    }
+}
+
+/// Concatenate the fenced code blocks in a markdown response, for diffing
+/// response variants
+fn extract_code_blocks(text: &str) -> String {
+    let mut blocks = String::new();
+    let mut in_code_block = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            if in_code_block && !blocks.is_empty() {
+                blocks.push('\n');
+            }
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            blocks.push_str(line);
+            blocks.push('\n');
+        }
+    }
+    blocks
+}
+
+/// Project root: the git toplevel when inside a repository, else the
+/// current working directory. Sessions started anywhere inside a repo
+/// share one scope this way.
+pub(crate) fn project_root() -> PathBuf {
+    if let Ok(output) = std::process::Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+    {
+        if output.status.success() {
+            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !path.is_empty() {
+                return PathBuf::from(path);
+            }
+        }
+    }
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// Stable FNV-1a hash of the project root path, used to key its session
+/// directory (the directory name keeps a readable slug next to it)
+fn project_hash(root: &Path) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in root.display().to_string().as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Parent directory holding every project's session scope
+/// (~/.claude/projects)
+fn projects_dir() -> Option<PathBuf> {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .map(|home| PathBuf::from(home).join(".claude").join("projects"))
+}
+
+/// Get conversation directory, scoped per project so /resume only lists
+/// this project's sessions
+pub(crate) fn get_conversation_dir() -> PathBuf {
+    // Sessions saved by older builds live in .claude/conversations under
+    // the cwd (matching JavaScript); keep using that layout where it
+    // already exists so nothing is orphaned
+    let legacy = std::env::current_dir()
         .unwrap_or_else(|_| PathBuf::from("."))
         .join(".claude")
-        .join("conversations")
+        .join("conversations");
+    if legacy.is_dir() {
+        return legacy;
+    }
+
+    let root = project_root();
+    let slug = root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("project");
+    match projects_dir() {
+        Some(projects) => {
+            projects
+                .join(format!("{}-{}", slug, project_hash(&root)))
+                .join("conversations")
+        }
+        // No resolvable home directory: fall back to the legacy layout
+        None => legacy,
+    }
+}
+
+/// Conversation directories of every known project scope, for the
+/// --all-projects session picker
+pub(crate) fn all_project_conversation_dirs() -> Vec<PathBuf> {
+    let Some(projects) = projects_dir() else {
+        return Vec::new();
+    };
+    let mut dirs = Vec::new();
+    if let Ok(entries) = fs::read_dir(projects) {
+        for entry in entries.flatten() {
+            let conversations = entry.path().join("conversations");
+            if conversations.is_dir() {
+                dirs.push(conversations);
+            }
+        }
+    }
+    dirs
 }
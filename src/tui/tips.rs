@@ -0,0 +1,85 @@
+//! Startup banner and rotating tips system.
+//!
+//! Builds the welcome header shown when an interactive session starts
+//! (version, model, working directory, auth source) and picks one tip per
+//! session from a rotating list that teaches keybindings and slash commands.
+//! The rotation index and the "don't show again" flag are persisted in the
+//! user settings file (`~/.claude/settings.json`).
+
+use crate::config::{load_settings, save_settings, SettingsSource};
+
+/// Tips shown one per session, in rotation. Kept short enough for one line.
+const TIPS: &[&str] = &[
+    "Press Ctrl+R to expand tool output and see full transcripts",
+    "Use /compact to summarize a long conversation and free up context",
+    "Press Shift+Tab to cycle permission modes without leaving the prompt",
+    "Type @ followed by a file path to include that file in your message",
+    "Press Esc at any time to interrupt the current response",
+    "Use /add-dir to grant access to directories outside the workspace",
+    "Use /model to switch models mid-session without losing context",
+    "Use /dry-run on to preview file edits and commands without executing them",
+    "Press Ctrl+T to expand the TODO list and track multi-step tasks",
+    "Use /resume to pick up a previous conversation where you left off",
+];
+
+/// Human-readable description of where credentials come from, for the banner
+pub fn auth_source_label() -> &'static str {
+    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        return "ANTHROPIC_API_KEY (environment)";
+    }
+    if let Ok(config) = crate::config::load_config(crate::config::ConfigScope::User) {
+        if let Some(ai_config) = config.ai_config {
+            if !ai_config.api_key.is_empty() {
+                return "API key (user config)";
+            }
+        }
+    }
+    "OAuth (claude.ai)"
+}
+
+/// Build the welcome header lines shown when the session starts
+pub fn startup_banner(model: &str) -> Vec<String> {
+    let version = env!("CARGO_PKG_VERSION");
+    let cwd = std::env::current_dir()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "(unknown)".to_string());
+
+    vec![
+        format!("✻ Welcome to Claude Code v{}", version),
+        format!("  model: {}", model),
+        format!("  cwd: {}", cwd),
+        format!("  auth: {}", auth_source_label()),
+    ]
+}
+
+/// Pick the next tip in rotation and advance the persisted index.
+/// Returns None when tips are disabled ("don't show again").
+pub fn next_tip() -> Option<String> {
+    let mut settings = load_settings(SettingsSource::User).unwrap_or_default();
+    if settings.hide_startup_tips == Some(true) {
+        return None;
+    }
+
+    let index = settings.next_tip_index.unwrap_or(0) % TIPS.len();
+    let tip = TIPS[index];
+
+    // Advance the rotation; persistence failure just repeats the tip next time
+    settings.next_tip_index = Some((index + 1) % TIPS.len());
+    let _ = save_settings(SettingsSource::User, &settings);
+
+    Some(format!("※ Tip: {} (disable with /tips off)", tip))
+}
+
+/// Persist the tips on/off preference ("don't show again")
+pub fn set_tips_enabled(enabled: bool) -> crate::error::Result<()> {
+    let mut settings = load_settings(SettingsSource::User)?;
+    settings.hide_startup_tips = if enabled { None } else { Some(true) };
+    save_settings(SettingsSource::User, &settings)
+}
+
+/// Whether tips are currently enabled
+pub fn tips_enabled() -> bool {
+    load_settings(SettingsSource::User)
+        .map(|s| s.hide_startup_tips != Some(true))
+        .unwrap_or(true)
+}
@@ -0,0 +1,246 @@
+//! Text-to-speech output: read completed assistant messages aloud.
+//!
+//! Opt-in via `/tts on` (or `tts.enabled` in settings.json). Speech goes
+//! through platform TTS — `say` on macOS, `spd-say` (speech-dispatcher) or
+//! `espeak` on Linux — or a configurable command / OpenAI-compatible speech
+//! API. `/tts summary` reads only the first sentence or two of each
+//! response, which keeps the mode usable during long answers.
+
+use crate::error::{Error, Result};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much of each assistant message is spoken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TtsMode {
+    Off,
+    Full,
+    Summary,
+}
+
+const MODE_UNINIT: u8 = u8::MAX;
+
+/// Runtime mode; initialized lazily from settings on first read
+static TTS_MODE: AtomicU8 = AtomicU8::new(MODE_UNINIT);
+
+/// Spoken summaries are cut at roughly this many characters
+const SUMMARY_MAX_CHARS: usize = 240;
+
+/// Current TTS mode, resolving the settings default on first use
+pub fn mode() -> TtsMode {
+    match TTS_MODE.load(Ordering::Relaxed) {
+        0 => TtsMode::Off,
+        1 => TtsMode::Full,
+        2 => TtsMode::Summary,
+        _ => {
+            let config = crate::config::get_tts_config();
+            let mode = if config.enabled == Some(true) {
+                if config.summaries_only == Some(true) {
+                    TtsMode::Summary
+                } else {
+                    TtsMode::Full
+                }
+            } else {
+                TtsMode::Off
+            };
+            set_mode_runtime(mode);
+            mode
+        }
+    }
+}
+
+/// Set the mode for this session without touching settings
+fn set_mode_runtime(mode: TtsMode) {
+    let value = match mode {
+        TtsMode::Off => 0,
+        TtsMode::Full => 1,
+        TtsMode::Summary => 2,
+    };
+    TTS_MODE.store(value, Ordering::Relaxed);
+}
+
+/// Set the mode and persist it to user settings (like /tips)
+pub fn set_mode(mode: TtsMode) -> Result<()> {
+    set_mode_runtime(mode);
+    let mut settings = crate::config::load_settings(crate::config::SettingsSource::User)
+        .unwrap_or_default();
+    let tts = settings.tts.get_or_insert_with(Default::default);
+    tts.enabled = Some(mode != TtsMode::Off);
+    tts.summaries_only = Some(mode == TtsMode::Summary);
+    crate::config::save_settings(crate::config::SettingsSource::User, &settings)
+}
+
+/// Speak an assistant message in the background, respecting the current
+/// mode. Cheap no-op when TTS is off.
+pub fn speak_async(text: &str) {
+    let mode = mode();
+    if mode == TtsMode::Off {
+        return;
+    }
+    let spoken = match mode {
+        TtsMode::Summary => summarize(text),
+        _ => clean_for_speech(text),
+    };
+    if spoken.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        // Playback failures (no speech engine, muted audio device) are
+        // deliberately silent: TTS is a convenience layer over the TUI
+        let _ = speak(&spoken).await;
+    });
+}
+
+/// Reduce a response to its first sentence or two for summary mode
+fn summarize(text: &str) -> String {
+    let cleaned = clean_for_speech(text);
+    let first_paragraph = cleaned
+        .split("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty())
+        .unwrap_or("");
+    if first_paragraph.chars().count() <= SUMMARY_MAX_CHARS {
+        return first_paragraph.to_string();
+    }
+    // Cut at the last sentence boundary inside the budget
+    let budget: String = first_paragraph.chars().take(SUMMARY_MAX_CHARS).collect();
+    match budget.rfind(['.', '!', '?']) {
+        Some(pos) => budget[..=pos].to_string(),
+        None => format!("{}…", budget.trim_end()),
+    }
+}
+
+/// Strip markdown syntax that reads badly aloud
+fn clean_for_speech(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_code_block = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue; // Code blocks are unlistenable; skip them entirely
+        }
+        let stripped = trimmed
+            .trim_start_matches('#')
+            .trim_start_matches(['-', '*', '>'])
+            .trim()
+            .replace(['`', '*', '_'], "");
+        if !stripped.is_empty() {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&stripped);
+        } else if !out.is_empty() && !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Speak text through the configured command, API, or platform engine
+async fn speak(text: &str) -> Result<()> {
+    let config = crate::config::get_tts_config();
+
+    if let Some(template) = &config.command {
+        let command = template.replace("{text}", &super::voice::shell_quote(text));
+        return run_quiet(&command).await;
+    }
+
+    if let Some(api_url) = &config.api_url {
+        return speak_via_api(&config, api_url, text).await;
+    }
+
+    let quoted = super::voice::shell_quote(text);
+    let command = if super::voice::command_exists("say") {
+        format!("say {}", quoted)
+    } else if super::voice::command_exists("spd-say") {
+        format!("spd-say --wait {}", quoted)
+    } else if super::voice::command_exists("espeak-ng") {
+        format!("espeak-ng {}", quoted)
+    } else if super::voice::command_exists("espeak") {
+        format!("espeak {}", quoted)
+    } else {
+        return Err(Error::Other(
+            "No speech engine found (tried say, spd-say, espeak). Install one or set tts.command in settings.json".to_string(),
+        ));
+    };
+    run_quiet(&command).await
+}
+
+/// Synthesize through an OpenAI-compatible speech endpoint and play the audio
+async fn speak_via_api(
+    config: &crate::config::TtsConfig,
+    api_url: &str,
+    text: &str,
+) -> Result<()> {
+    let key_env = config.api_key_env.as_deref().unwrap_or("OPENAI_API_KEY");
+    let mut request = reqwest::Client::new().post(api_url).json(&serde_json::json!({
+        "model": config.api_model.clone().unwrap_or_else(|| "tts-1".to_string()),
+        "voice": config.voice.clone().unwrap_or_else(|| "alloy".to_string()),
+        "input": text,
+    }));
+    if let Ok(key) = std::env::var(key_env) {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| Error::Other(format!("Speech request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(Error::Other(format!(
+            "Speech API returned {}",
+            response.status()
+        )));
+    }
+    let audio = response
+        .bytes()
+        .await
+        .map_err(|e| Error::Other(format!("Failed to read speech audio: {}", e)))?;
+
+    let audio_path = std::env::temp_dir().join(format!(
+        "llminate-tts-{}.mp3",
+        crate::utils::timestamp_ms()
+    ));
+    tokio::fs::write(&audio_path, &audio)
+        .await
+        .map_err(|e| Error::Other(format!("Failed to write speech audio: {}", e)))?;
+
+    let quoted = super::voice::shell_quote(&audio_path.to_string_lossy());
+    let command = if super::voice::command_exists("afplay") {
+        format!("afplay {}", quoted)
+    } else if super::voice::command_exists("mpv") {
+        format!("mpv --really-quiet {}", quoted)
+    } else if super::voice::command_exists("ffplay") {
+        format!("ffplay -nodisp -autoexit -loglevel quiet {}", quoted)
+    } else if super::voice::command_exists("aplay") {
+        format!("aplay -q {}", quoted)
+    } else {
+        let _ = std::fs::remove_file(&audio_path);
+        return Err(Error::Other(
+            "No audio player found to play synthesized speech (tried afplay, mpv, ffplay, aplay)"
+                .to_string(),
+        ));
+    };
+    let result = run_quiet(&command).await;
+    let _ = std::fs::remove_file(&audio_path);
+    result
+}
+
+/// Run a shell command, discarding output; stdout belongs to the TUI
+async fn run_quiet(command: &str) -> Result<()> {
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .await
+        .map_err(|e| Error::Other(format!("Failed to run speech command: {}", e)))?;
+    if !status.success() {
+        return Err(Error::Other("Speech command exited with an error".to_string()));
+    }
+    Ok(())
+}
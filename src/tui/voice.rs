@@ -0,0 +1,208 @@
+//! Voice input: record from the microphone and transcribe to text.
+//!
+//! `/voice [seconds]` records a short clip, transcribes it, and inserts
+//! the transcript into the input textarea for review before sending.
+//! Recording shells out to whichever recorder is installed (ffmpeg,
+//! arecord, or sox — overridable via `voice.recordCommand` in settings),
+//! and transcription runs either a local command (`voice.transcribeCommand`,
+//! eg. whisper.cpp) or an OpenAI-compatible API (`voice.apiUrl`).
+
+use crate::error::{Error, Result};
+
+/// Default recording length when `/voice` is given no argument
+pub const DEFAULT_RECORD_SECONDS: u64 = 10;
+
+/// Hard ceiling on recording length regardless of settings
+const MAX_RECORD_SECONDS: u64 = 120;
+
+/// Record from the microphone and return the transcript.
+/// Blocks for the duration of the recording; callers should run this off
+/// the UI task and deliver the result as an event.
+pub async fn capture_and_transcribe(seconds: u64) -> Result<String> {
+    let config = crate::config::get_voice_config();
+    let cap = config.max_seconds.unwrap_or(15).min(MAX_RECORD_SECONDS);
+    let seconds = seconds.clamp(1, cap);
+
+    let wav_path = std::env::temp_dir().join(format!(
+        "llminate-voice-{}.wav",
+        crate::utils::timestamp_ms()
+    ));
+    let wav_str = wav_path.to_string_lossy().to_string();
+
+    let record_result = record(&config, &wav_str, seconds).await;
+    let transcript = match record_result {
+        Ok(()) => transcribe(&config, &wav_str).await,
+        Err(e) => Err(e),
+    };
+    let _ = std::fs::remove_file(&wav_path);
+
+    let transcript = transcript?;
+    let transcript = transcript.trim().to_string();
+    if transcript.is_empty() {
+        return Err(Error::Other(
+            "Transcription returned no text (was anything said?)".to_string(),
+        ));
+    }
+    Ok(transcript)
+}
+
+/// Record `seconds` of 16 kHz mono audio to `wav_path`
+async fn record(config: &crate::config::VoiceConfig, wav_path: &str, seconds: u64) -> Result<()> {
+    let command = match &config.record_command {
+        Some(template) => template
+            .replace("{file}", wav_path)
+            .replace("{seconds}", &seconds.to_string()),
+        None => default_record_command(wav_path, seconds).ok_or_else(|| {
+            Error::Other(
+                "No audio recorder found (tried ffmpeg, arecord, sox). Install one or set voice.recordCommand in settings.json".to_string(),
+            )
+        })?,
+    };
+
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .output()
+        .await
+        .map_err(|e| Error::Other(format!("Failed to run recorder: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Other(format!(
+            "Recording failed: {}",
+            stderr.lines().last().unwrap_or("recorder exited with an error")
+        )));
+    }
+    if !std::path::Path::new(wav_path).exists() {
+        return Err(Error::Other("Recorder produced no audio file".to_string()));
+    }
+    Ok(())
+}
+
+/// Build a recording command for whichever recorder is on PATH.
+/// All variants produce 16 kHz mono WAV, the format whisper expects.
+fn default_record_command(wav_path: &str, seconds: u64) -> Option<String> {
+    let quoted = shell_quote(wav_path);
+    if command_exists("ffmpeg") {
+        Some(format!(
+            "ffmpeg -hide_banner -loglevel error -f alsa -i default -t {} -ar 16000 -ac 1 -y {}",
+            seconds, quoted
+        ))
+    } else if command_exists("arecord") {
+        Some(format!(
+            "arecord -q -f S16_LE -r 16000 -c 1 -d {} {}",
+            seconds, quoted
+        ))
+    } else if command_exists("sox") {
+        Some(format!(
+            "sox -q -d -r 16000 -c 1 {} trim 0 {}",
+            quoted, seconds
+        ))
+    } else {
+        None
+    }
+}
+
+/// Transcribe a WAV file via the configured command or API
+async fn transcribe(config: &crate::config::VoiceConfig, wav_path: &str) -> Result<String> {
+    if let Some(template) = &config.transcribe_command {
+        let command = if template.contains("{file}") {
+            template.replace("{file}", wav_path)
+        } else {
+            format!("{} {}", template, shell_quote(wav_path))
+        };
+        let output = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .output()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to run transcriber: {}", e)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Other(format!(
+                "Transcription failed: {}",
+                stderr.lines().last().unwrap_or("transcriber exited with an error")
+            )));
+        }
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+
+    if let Some(api_url) = &config.api_url {
+        return transcribe_via_api(config, api_url, wav_path).await;
+    }
+
+    Err(Error::Other(
+        "No transcriber configured. Set voice.transcribeCommand (eg. a whisper.cpp invocation) or voice.apiUrl in settings.json".to_string(),
+    ))
+}
+
+/// POST the audio to an OpenAI-compatible transcription endpoint
+async fn transcribe_via_api(
+    config: &crate::config::VoiceConfig,
+    api_url: &str,
+    wav_path: &str,
+) -> Result<String> {
+    let audio = tokio::fs::read(wav_path)
+        .await
+        .map_err(|e| Error::Other(format!("Failed to read recording: {}", e)))?;
+
+    let form = reqwest::multipart::Form::new()
+        .part(
+            "file",
+            reqwest::multipart::Part::bytes(audio)
+                .file_name("audio.wav")
+                .mime_str("audio/wav")
+                .map_err(|e| Error::Other(format!("Failed to build upload: {}", e)))?,
+        )
+        .text(
+            "model",
+            config.api_model.clone().unwrap_or_else(|| "whisper-1".to_string()),
+        );
+
+    let key_env = config.api_key_env.as_deref().unwrap_or("OPENAI_API_KEY");
+    let mut request = reqwest::Client::new().post(api_url).multipart(form);
+    if let Ok(key) = std::env::var(key_env) {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| Error::Other(format!("Transcription request failed: {}", e)))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| Error::Other(format!("Failed to read transcription response: {}", e)))?;
+    if !status.is_success() {
+        return Err(Error::Other(format!(
+            "Transcription API returned {}: {}",
+            status, body
+        )));
+    }
+
+    // OpenAI-compatible servers return {"text": "..."}; plain-text servers
+    // just return the transcript
+    match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(json) => Ok(json
+            .get("text")
+            .and_then(|t| t.as_str())
+            .unwrap_or(&body)
+            .to_string()),
+        Err(_) => Ok(body),
+    }
+}
+
+/// Check whether a program is available on PATH
+pub(crate) fn command_exists(program: &str) -> bool {
+    std::process::Command::new("which")
+        .arg(program)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Single-quote a path for embedding in an sh -c command line
+pub(crate) fn shell_quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', r"'\''"))
+}
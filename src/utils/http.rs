@@ -0,0 +1,160 @@
+//! Shared HTTP client factory.
+//!
+//! Central place to construct outbound `reqwest` clients so transport-level
+//! policy applies everywhere: the Anthropic/OpenAI/Bedrock/Ollama API
+//! clients, MCP HTTP/SSE transports, and web fetches all build their
+//! clients here. Currently that policy is mTLS — some enterprise egress
+//! proxies require a client certificate before they will forward traffic.
+//!
+//! The certificate is configured via `clientCert` / `clientKey` /
+//! `clientKeyPassphrase` in settings.json (any source; managed policy
+//! wins) or the `CLAUDE_CODE_CLIENT_CERT` / `CLAUDE_CODE_CLIENT_KEY` /
+//! `CLAUDE_CODE_CLIENT_KEY_PASSPHRASE` environment variables, which take
+//! precedence. `clientCert` accepts a PEM path (paired with a PKCS#8 PEM
+//! key), a PKCS#12 bundle path (`.p12` / `.pfx`, key included), or on
+//! macOS a `keychain:<name>` reference resolved through the `security`
+//! tool (the private key still comes from `clientKey`).
+
+use crate::error::{Error, Result};
+use once_cell::sync::OnceCell;
+use std::path::Path;
+
+/// Resolved client certificate configuration
+#[derive(Debug, Clone)]
+pub struct ClientCertConfig {
+    /// PEM path, PKCS#12 path, or `keychain:<name>` reference
+    pub cert: String,
+    /// PKCS#8 PEM key path (not needed for PKCS#12 bundles)
+    pub key: Option<String>,
+    /// Passphrase for PKCS#12 bundles
+    pub passphrase: Option<String>,
+}
+
+/// Resolve the client certificate from the environment or settings.
+/// Environment variables win so an engagement-specific certificate can be
+/// swapped in without editing settings files.
+pub fn resolve_client_cert() -> Option<ClientCertConfig> {
+    if let Ok(cert) = std::env::var("CLAUDE_CODE_CLIENT_CERT") {
+        if !cert.is_empty() {
+            return Some(ClientCertConfig {
+                cert,
+                key: std::env::var("CLAUDE_CODE_CLIENT_KEY").ok(),
+                passphrase: std::env::var("CLAUDE_CODE_CLIENT_KEY_PASSPHRASE").ok(),
+            });
+        }
+    }
+    crate::config::get_client_cert_settings()
+}
+
+/// Whether the certificate reference is a PKCS#12 bundle (key included)
+fn is_pkcs12(reference: &str) -> bool {
+    Path::new(reference)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("p12") || ext.eq_ignore_ascii_case("pfx"))
+        .unwrap_or(false)
+}
+
+/// Read the certificate material behind a reference: a file path, or on
+/// macOS a `keychain:<name>` lookup via `security find-certificate`
+fn read_cert_material(reference: &str) -> Result<Vec<u8>> {
+    if let Some(name) = reference.strip_prefix("keychain:") {
+        if !cfg!(target_os = "macos") {
+            return Err(Error::Config(
+                "Keychain certificate references are only supported on macOS".to_string(),
+            ));
+        }
+        let output = std::process::Command::new("security")
+            .args(["find-certificate", "-c", name, "-p"])
+            .output()
+            .map_err(|e| Error::Config(format!("Failed to run security tool: {}", e)))?;
+        if !output.status.success() {
+            return Err(Error::Config(format!(
+                "Certificate '{}' not found in keychain: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        return Ok(output.stdout);
+    }
+    std::fs::read(reference)
+        .map_err(|e| Error::Config(format!("Failed to read client certificate {}: {}", reference, e)))
+}
+
+/// Load the configured client identity, if any
+fn load_identity() -> Result<Option<reqwest::Identity>> {
+    let Some(config) = resolve_client_cert() else {
+        return Ok(None);
+    };
+
+    if is_pkcs12(&config.cert) {
+        let bundle = read_cert_material(&config.cert)?;
+        let passphrase = config.passphrase.as_deref().unwrap_or("");
+        let identity = reqwest::Identity::from_pkcs12_der(&bundle, passphrase)
+            .map_err(|e| Error::Config(format!("Invalid PKCS#12 client certificate: {}", e)))?;
+        return Ok(Some(identity));
+    }
+
+    let cert_pem = read_cert_material(&config.cert)?;
+    let key_path = config.key.as_deref().ok_or_else(|| {
+        Error::Config(
+            "clientKey is required when clientCert is a PEM certificate \
+             (use a .p12/.pfx bundle to carry both)"
+                .to_string(),
+        )
+    })?;
+    let key_pem = std::fs::read(key_path)
+        .map_err(|e| Error::Config(format!("Failed to read client key {}: {}", key_path, e)))?;
+    let identity = reqwest::Identity::from_pkcs8_pem(&cert_pem, &key_pem)
+        .map_err(|e| Error::Config(format!("Invalid client certificate/key pair: {}", e)))?;
+    Ok(Some(identity))
+}
+
+/// A `reqwest` builder with transport policy (mTLS identity) pre-applied.
+/// Callers layer their own timeouts, proxies, and headers on top.
+pub fn client_builder() -> Result<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(identity) = load_identity()? {
+        builder = builder.identity(identity);
+    }
+    Ok(builder)
+}
+
+/// Shared client for call sites without bespoke timeout or proxy needs
+/// (MCP transports, one-shot fetches). Built once per process.
+pub fn shared_client() -> Result<reqwest::Client> {
+    static SHARED: OnceCell<reqwest::Client> = OnceCell::new();
+    SHARED
+        .get_or_try_init(|| {
+            client_builder()?
+                .build()
+                .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))
+        })
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_pkcs12_by_extension() {
+        assert!(is_pkcs12("/etc/certs/client.p12"));
+        assert!(is_pkcs12("/etc/certs/client.PFX"));
+        assert!(!is_pkcs12("/etc/certs/client.pem"));
+        assert!(!is_pkcs12("keychain:Corp Client"));
+    }
+
+    #[test]
+    fn test_pem_cert_requires_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let cert_path = dir.path().join("client.pem");
+        std::fs::write(&cert_path, "-----BEGIN CERTIFICATE-----\n").unwrap();
+        std::env::set_var("CLAUDE_CODE_CLIENT_CERT", &cert_path);
+        std::env::remove_var("CLAUDE_CODE_CLIENT_KEY");
+        let result = load_identity();
+        std::env::remove_var("CLAUDE_CODE_CLIENT_CERT");
+        let error = result.expect_err("PEM cert without key should be rejected");
+        assert!(error.to_string().contains("clientKey"));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod error;
+pub mod http;
 pub mod ripgrep;
 
 use crate::error::{Error, Result};
@@ -127,18 +128,61 @@ pub fn terminal_height() -> u16 {
         .unwrap_or(24)
 }
 
+/// Terminal display width of a string (CJK characters count as two
+/// columns, combining marks as zero)
+pub fn display_width(s: &str) -> usize {
+    use unicode_width::UnicodeWidthStr;
+    s.width()
+}
+
+/// Truncate a string to a display width, appending "..." when cut.
+/// Cuts fall on grapheme boundaries, so emoji and combining sequences
+/// are never split, and widths are column-accurate for CJK text.
+pub fn truncate_display(s: &str, max_width: usize) -> String {
+    use unicode_segmentation::UnicodeSegmentation;
+    use unicode_width::UnicodeWidthStr;
+
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width <= 3 {
+        return "...".to_string();
+    }
+
+    let budget = max_width - 3;
+    let mut width = 0;
+    let mut end = 0;
+    for (offset, grapheme) in s.grapheme_indices(true) {
+        let grapheme_width = grapheme.width();
+        if width + grapheme_width > budget {
+            break;
+        }
+        width += grapheme_width;
+        end = offset + grapheme.len();
+    }
+    format!("{}...", &s[..end])
+}
+
+/// Truncate a string to at most `max_bytes`, backing off to the nearest
+/// character boundary so multi-byte characters are never split
+pub fn truncate_bytes(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while end > 0 && !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
 /// Truncate string to fit terminal width
 pub fn truncate_to_terminal(s: &str, prefix_len: usize) -> String {
     let width = terminal_width() as usize;
-    if prefix_len + s.len() <= width {
+    if prefix_len + display_width(s) <= width {
         s.to_string()
     } else {
-        let available = width.saturating_sub(prefix_len + 3); // 3 for "..."
-        if available > 0 {
-            format!("{}...", &s[..available])
-        } else {
-            "...".to_string()
-        }
+        truncate_display(s, width.saturating_sub(prefix_len))
     }
 }
 
@@ -153,4 +197,46 @@ pub fn timestamp_ms() -> u64 {
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_display_passes_short_strings_through() {
+        assert_eq!(truncate_display("hello", 10), "hello");
+        assert_eq!(truncate_display("", 5), "");
+    }
+
+    #[test]
+    fn test_truncate_display_counts_cjk_columns() {
+        // Each CJK character is two columns; budget of 7 leaves 4 columns
+        // after the ellipsis, so exactly two characters fit
+        assert_eq!(truncate_display("日本語のテキスト", 7), "日本...");
+        assert_eq!(display_width("日本語"), 6);
+    }
+
+    #[test]
+    fn test_truncate_display_never_splits_graphemes() {
+        // A family emoji is one grapheme built from several codepoints;
+        // it must be kept or dropped whole, never cut in the middle
+        let s = "abc👨‍👩‍👧‍👦xyz";
+        assert_eq!(truncate_display(s, 6), "abc...");
+        assert_eq!(truncate_display(s, 20), s);
+    }
+
+    #[test]
+    fn test_truncate_bytes_respects_char_boundaries() {
+        // "é" is two bytes; cutting at byte 1 would split it
+        assert_eq!(truncate_bytes("é", 1), "");
+        assert_eq!(truncate_bytes("aé", 2), "a");
+        assert_eq!(truncate_bytes("hello", 10), "hello");
+        // Must never panic on any cut point of multi-byte text
+        let s = "日本語";
+        for max in 0..=s.len() {
+            let out = truncate_bytes(s, max);
+            assert!(s.starts_with(out));
+        }
+    }
 }
\ No newline at end of file
@@ -214,6 +214,112 @@ async fn test_default_credential_chain() {
     env::remove_var("AWS_EC2_METADATA_DISABLED");
 }
 
+#[tokio::test]
+async fn test_default_chain_env_precedes_container_metadata() {
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::method;
+
+    let mock_server = MockServer::start().await;
+
+    // A container endpoint that would succeed if consulted
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "AccessKeyId": "container-key",
+            "SecretAccessKey": "container-secret",
+            "Token": "container-token",
+            "Expiration": "2099-01-01T00:00:00Z"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    env::set_var("AWS_ACCESS_KEY_ID", "env-key");
+    env::set_var("AWS_SECRET_ACCESS_KEY", "env-secret");
+    env::set_var(
+        "AWS_CONTAINER_CREDENTIALS_FULL_URI",
+        format!("{}/v1/credentials", mock_server.uri()),
+    );
+    env::set_var("AWS_EC2_METADATA_DISABLED", "true");
+
+    let provider = DefaultCredentialProvider::new();
+    let creds = provider.get_credentials().await
+        .expect("Chain should resolve credentials");
+
+    // Environment variables win even though the container endpoint works
+    assert_eq!(creds.access_key_id, "env-key");
+    assert_eq!(creds.secret_access_key, "env-secret");
+
+    env::remove_var("AWS_ACCESS_KEY_ID");
+    env::remove_var("AWS_SECRET_ACCESS_KEY");
+    env::remove_var("AWS_CONTAINER_CREDENTIALS_FULL_URI");
+    env::remove_var("AWS_EC2_METADATA_DISABLED");
+}
+
+#[tokio::test]
+async fn test_default_chain_web_identity_precedes_container_metadata() {
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::{body_string_contains, method};
+
+    // STS endpoint the web identity provider will call (IRSA path)
+    let sts_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("Action=AssumeRoleWithWebIdentity"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "AssumeRoleWithWebIdentityResponse": {
+                "AssumeRoleWithWebIdentityResult": {
+                    "Credentials": {
+                        "AccessKeyId": "irsa-key",
+                        "SecretAccessKey": "irsa-secret",
+                        "SessionToken": "irsa-token",
+                        "Expiration": 4102444800.0_f64
+                    }
+                },
+                "ResponseMetadata": { "RequestId": "test" }
+            }
+        })))
+        .mount(&sts_server)
+        .await;
+
+    // A container endpoint that would also succeed if consulted
+    let container_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "AccessKeyId": "container-key",
+            "SecretAccessKey": "container-secret",
+            "Token": "container-token"
+        })))
+        .mount(&container_server)
+        .await;
+
+    let token_file = tempfile::NamedTempFile::new().expect("temp token file");
+    std::fs::write(token_file.path(), "mock-web-identity-token").expect("write token");
+
+    env::remove_var("AWS_ACCESS_KEY_ID");
+    env::remove_var("AWS_SECRET_ACCESS_KEY");
+    env::set_var("AWS_WEB_IDENTITY_TOKEN_FILE", token_file.path());
+    env::set_var("AWS_ROLE_ARN", "arn:aws:iam::123456789012:role/pod-role");
+    env::set_var("AWS_ENDPOINT_URL_STS", sts_server.uri());
+    env::set_var(
+        "AWS_CONTAINER_CREDENTIALS_FULL_URI",
+        format!("{}/v1/credentials", container_server.uri()),
+    );
+    env::set_var("AWS_EC2_METADATA_DISABLED", "true");
+
+    let provider = DefaultCredentialProvider::new();
+    let creds = provider.get_credentials().await
+        .expect("Chain should resolve credentials");
+
+    // Web identity (IRSA) outranks container metadata in the chain
+    assert_eq!(creds.access_key_id, "irsa-key");
+    assert_eq!(creds.secret_access_key, "irsa-secret");
+    assert_eq!(creds.session_token, Some("irsa-token".to_string()));
+
+    env::remove_var("AWS_WEB_IDENTITY_TOKEN_FILE");
+    env::remove_var("AWS_ROLE_ARN");
+    env::remove_var("AWS_ENDPOINT_URL_STS");
+    env::remove_var("AWS_CONTAINER_CREDENTIALS_FULL_URI");
+    env::remove_var("AWS_EC2_METADATA_DISABLED");
+}
+
 #[tokio::test]
 async fn test_memoized_provider() {
     use std::sync::Arc;
@@ -330,4 +330,42 @@ async fn test_bash_tool_working_directory_persistence() {
     let _ = std::fs::remove_dir_all(&temp_dir);
     
     println!("✓ Working directory persistence test passed");
-}
\ No newline at end of file
+}
+#[test]
+fn test_commit_trailer_appended_before_control_operator() {
+    use llminate::ai::tools::append_commit_trailer;
+
+    let trailer = "Co-Authored-By: Example <bot@example.com>";
+
+    // Trailer lands inside the commit invocation, not after the push
+    let rewritten = append_commit_trailer("git commit -m 'fix parser' && git push", trailer);
+    assert_eq!(
+        rewritten,
+        "git commit -m 'fix parser' -m 'Co-Authored-By: Example <bot@example.com>' && git push"
+    );
+
+    // Plain commit at end of command
+    let rewritten = append_commit_trailer("git add -A && git commit -m \"fix\"", trailer);
+    assert!(rewritten.ends_with("-m 'Co-Authored-By: Example <bot@example.com>'"));
+
+    // Operators inside the quoted message must not split the insertion point
+    let rewritten = append_commit_trailer("git commit -m 'use && not ||'", trailer);
+    assert!(rewritten.starts_with("git commit -m 'use && not ||' -m '"));
+}
+
+#[test]
+fn test_commit_trailer_skips_ineligible_commands() {
+    use llminate::ai::tools::append_commit_trailer;
+
+    let trailer = "Co-Authored-By: Example <bot@example.com>";
+
+    // No git commit at all
+    assert_eq!(append_commit_trailer("cargo build", trailer), "cargo build");
+
+    // Editor-based commit has no inline message to extend
+    assert_eq!(append_commit_trailer("git commit", trailer), "git commit");
+
+    // Already attributed
+    let attributed = "git commit -m 'fix' -m 'Co-Authored-By: Someone <x@y.z>'";
+    assert_eq!(append_commit_trailer(attributed, trailer), attributed);
+}
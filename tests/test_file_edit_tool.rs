@@ -130,4 +130,98 @@ async fn test_file_edit_same_strings() {
     assert!(error_msg.contains("exactly the same"), "Error should indicate strings are the same");
     
     println!("✓ Same strings test passed");
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_file_edit_preserves_crlf_line_endings() {
+    // Create a temporary file with CRLF line endings
+    let temp_file = NamedTempFile::new().unwrap();
+    let temp_path = temp_file.path().to_str().unwrap();
+
+    let initial_content = "first line\r\nsecond line\r\nthird line\r\n";
+    fs::write(temp_path, initial_content).unwrap();
+
+    let edit_tool = EditFileTool;
+
+    let input = json!({
+        "file_path": temp_path,
+        "old_string": "second line",
+        "new_string": "replacement one\nreplacement two",
+        "replace_all": false
+    });
+
+    let result = edit_tool.execute(input, None).await;
+    assert!(result.is_ok(), "FileEdit should succeed on a CRLF file");
+
+    // The inserted LF must be converted to match the file's CRLF endings
+    let new_content = fs::read_to_string(temp_path).unwrap();
+    let expected = "first line\r\nreplacement one\r\nreplacement two\r\nthird line\r\n";
+    assert_eq!(new_content, expected, "CRLF line endings should be preserved");
+
+    println!("✓ CRLF preservation test passed");
+}
+
+#[tokio::test]
+async fn test_file_edit_rejects_readonly_file() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let temp_path = temp_file.path().to_str().unwrap();
+
+    fs::write(temp_path, "protected content").unwrap();
+    let mut permissions = fs::metadata(temp_path).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(temp_path, permissions).unwrap();
+
+    let edit_tool = EditFileTool;
+
+    let input = json!({
+        "file_path": temp_path,
+        "old_string": "protected",
+        "new_string": "modified",
+        "replace_all": false
+    });
+
+    let result = edit_tool.execute(input, None).await;
+
+    // Restore write permission so NamedTempFile can clean up
+    let mut permissions = fs::metadata(temp_path).unwrap().permissions();
+    permissions.set_readonly(false);
+    fs::set_permissions(temp_path, permissions).unwrap();
+
+    assert!(result.is_err(), "FileEdit should fail on a read-only file");
+    let error_msg = result.unwrap_err().to_string();
+    assert!(error_msg.contains("read-only"), "Error should name the read-only cause: {}", error_msg);
+
+    let content = fs::read_to_string(temp_path).unwrap();
+    assert_eq!(content, "protected content", "Read-only file must be untouched");
+
+    println!("✓ Read-only rejection test passed");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_file_edit_preserves_file_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let temp_path = temp_file.path().to_str().unwrap();
+
+    fs::write(temp_path, "#!/bin/sh\necho original\n").unwrap();
+    fs::set_permissions(temp_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let edit_tool = EditFileTool;
+
+    let input = json!({
+        "file_path": temp_path,
+        "old_string": "echo original",
+        "new_string": "echo edited",
+        "replace_all": false
+    });
+
+    let result = edit_tool.execute(input, None).await;
+    assert!(result.is_ok(), "FileEdit should succeed on an executable file");
+
+    // The atomic write must carry the executable bit over to the new file
+    let mode = fs::metadata(temp_path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode, 0o755, "File mode should survive an edit");
+
+    println!("✓ File mode preservation test passed");
+}
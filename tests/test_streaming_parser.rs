@@ -0,0 +1,186 @@
+//! Golden-transcript and property tests for the incremental SSE parser
+//! (`ai::streaming::SseParser`).
+//!
+//! Golden tests parse the `.sse` fixtures under tests/fixtures/streaming
+//! and compare a stable one-line-per-event summary against the `.events`
+//! file next to each fixture. Run with UPDATE_GOLDEN=1 to regenerate the
+//! expectations after an intentional parser change. Property tests check
+//! the invariant that chunking never matters: any split of the byte
+//! stream - including mid-event and mid-UTF-8-character - must produce
+//! the same events, and arbitrary garbage must never panic the parser.
+
+use llminate::ai::client::{ContentBlock, ContentDelta, StreamEvent};
+use llminate::ai::streaming::SseParser;
+use llminate::error::Result;
+use proptest::prelude::*;
+use std::path::PathBuf;
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/streaming")
+}
+
+/// Stable one-line summary of a parsed event, used both for the golden
+/// files and for comparing differently-chunked parses
+fn summarize(event: &Result<StreamEvent>) -> String {
+    match event {
+        Ok(StreamEvent::MessageStart { .. }) => "message_start".to_string(),
+        Ok(StreamEvent::ContentBlockStart {
+            index,
+            content_block,
+        }) => match content_block {
+            ContentBlock::Text { text } => format!("block_start[{}] text {:?}", index, text),
+            ContentBlock::ToolUse { id, name, .. } => {
+                format!("block_start[{}] tool_use {} {}", index, name, id)
+            }
+            ContentBlock::Thinking { .. } => format!("block_start[{}] thinking", index),
+            ContentBlock::RedactedThinking { .. } => {
+                format!("block_start[{}] redacted_thinking", index)
+            }
+        },
+        Ok(StreamEvent::ContentBlockDelta { index, delta }) => match delta {
+            ContentDelta::TextDelta { text } => format!("delta[{}] text {:?}", index, text),
+            ContentDelta::InputJsonDelta { partial_json } => {
+                format!("delta[{}] input_json {:?}", index, partial_json)
+            }
+            ContentDelta::ThinkingDelta { thinking } => {
+                format!("delta[{}] thinking {:?}", index, thinking)
+            }
+            ContentDelta::SignatureDelta { .. } => format!("delta[{}] signature", index),
+            ContentDelta::CitationsDelta { .. } => format!("delta[{}] citations", index),
+        },
+        Ok(StreamEvent::ContentBlockStop { index }) => format!("block_stop[{}]", index),
+        Ok(StreamEvent::MessageDelta { usage, .. }) => {
+            format!("message_delta output_tokens={}", usage.output_tokens)
+        }
+        Ok(StreamEvent::MessageStop) => "message_stop".to_string(),
+        Ok(StreamEvent::Ping) => "ping".to_string(),
+        Ok(StreamEvent::Error(message)) => format!("stream_error {:?}", message),
+        Ok(other) => format!("other {:?}", other),
+        Err(e) => format!("ERR {}", e),
+    }
+}
+
+/// Parse a whole byte slice in one push and summarize every event
+fn parse_all(bytes: &[u8]) -> Vec<String> {
+    let mut parser = SseParser::new();
+    let mut events = parser.push(bytes);
+    events.extend(parser.finish());
+    events.iter().map(summarize).collect()
+}
+
+/// Parse the same bytes split into the given chunks
+fn parse_chunked(chunks: &[&[u8]]) -> Vec<String> {
+    let mut parser = SseParser::new();
+    let mut events = Vec::new();
+    for chunk in chunks {
+        events.extend(parser.push(chunk));
+    }
+    events.extend(parser.finish());
+    events.iter().map(summarize).collect()
+}
+
+/// Compare a fixture's parse against its golden expectation, or rewrite
+/// the expectation when UPDATE_GOLDEN is set
+fn run_golden(name: &str) {
+    let input = std::fs::read(fixture_dir().join(format!("{}.sse", name)))
+        .unwrap_or_else(|e| panic!("missing fixture {}.sse: {}", name, e));
+    let actual = parse_all(&input).join("\n") + "\n";
+
+    let golden_path = fixture_dir().join(format!("{}.events", name));
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        std::fs::write(&golden_path, &actual).expect("write golden file");
+        return;
+    }
+    let expected = std::fs::read_to_string(&golden_path)
+        .unwrap_or_else(|e| panic!("missing golden file {}.events (run with UPDATE_GOLDEN=1 to create it): {}", name, e));
+    assert_eq!(actual, expected, "golden mismatch for {}", name);
+}
+
+#[test]
+fn test_golden_text_simple() {
+    run_golden("text_simple");
+}
+
+#[test]
+fn test_golden_tool_use_interleaved() {
+    run_golden("tool_use_interleaved");
+}
+
+#[test]
+fn test_golden_malformed_chunks() {
+    run_golden("malformed_chunks");
+}
+
+#[test]
+fn test_every_split_point_of_multibyte_transcript() {
+    // The fixture contains "wörld 🌍": splitting inside those characters
+    // must not corrupt text or produce spurious errors
+    let input = std::fs::read(fixture_dir().join("text_simple.sse")).expect("fixture");
+    let whole = parse_all(&input);
+    for split in 1..input.len() {
+        let chunked = parse_chunked(&[&input[..split], &input[split..]]);
+        assert_eq!(whole, chunked, "mismatch when splitting at byte {}", split);
+    }
+}
+
+#[test]
+fn test_incomplete_event_reported_on_finish() {
+    let mut parser = SseParser::new();
+    let events = parser.push(b"data: {\"type\":\"ping\"}\n\ndata: {\"type\":\"trunc");
+    assert_eq!(events.len(), 1);
+    let trailing = parser.finish();
+    assert_eq!(trailing.len(), 1);
+    let err = trailing[0].as_ref().expect_err("incomplete event must error");
+    assert!(err.to_string().contains("incomplete event"));
+}
+
+#[test]
+fn test_invalid_utf8_does_not_kill_the_stream() {
+    let mut parser = SseParser::new();
+    // 0xFF can never start a UTF-8 sequence; the parser reports it and
+    // keeps decoding subsequent events
+    let mut input = b"data: {\"type\":\"ping\"}\n\n".to_vec();
+    input.push(0xFF);
+    input.extend_from_slice(b"data: {\"type\":\"message_stop\"}\n\n");
+    let mut events = parser.push(&input);
+    events.extend(parser.finish());
+    let summaries: Vec<String> = events.iter().map(|e| summarize(e)).collect();
+    assert!(summaries.iter().any(|s| s == "ping"));
+    assert!(summaries.iter().any(|s| s.starts_with("ERR")));
+    assert!(summaries.iter().any(|s| s == "message_stop"));
+}
+
+proptest! {
+    /// Chunking is meaningless: any partition of a valid transcript's
+    /// bytes parses to exactly the same events as one big push
+    #[test]
+    fn prop_chunking_never_changes_events(splits in proptest::collection::vec(any::<prop::sample::Index>(), 0..8)) {
+        let input = std::fs::read(fixture_dir().join("tool_use_interleaved.sse")).expect("fixture");
+        let whole = parse_all(&input);
+
+        let mut offsets: Vec<usize> = splits.iter().map(|i| i.index(input.len())).collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+
+        let mut chunks: Vec<&[u8]> = Vec::new();
+        let mut start = 0;
+        for offset in offsets {
+            chunks.push(&input[start..offset]);
+            start = offset;
+        }
+        chunks.push(&input[start..]);
+
+        prop_assert_eq!(whole, parse_chunked(&chunks));
+    }
+
+    /// Arbitrary garbage - random bytes in random chunkings - must never
+    /// panic the parser; at worst it yields error items
+    #[test]
+    fn prop_arbitrary_bytes_never_panic(chunks in proptest::collection::vec(proptest::collection::vec(any::<u8>(), 0..64), 0..8)) {
+        let mut parser = SseParser::new();
+        for chunk in &chunks {
+            let _ = parser.push(chunk);
+        }
+        let _ = parser.finish();
+    }
+}
@@ -0,0 +1,86 @@
+use llminate::ai::tools::ToolExecutor;
+use llminate::ai::ContentPart;
+use serde_json::json;
+use std::fs;
+use tempfile::NamedTempFile;
+use tokio;
+
+#[tokio::test]
+async fn test_missing_required_field_returns_validation_error() {
+    let executor = ToolExecutor::new();
+
+    // Read requires file_path; omit it entirely
+    let result = executor.execute("Read", json!({})).await;
+    assert!(result.is_ok(), "Validation failure should be a tool_result, not an Err");
+
+    match result.unwrap() {
+        ContentPart::ToolResult {
+            content, is_error, ..
+        } => {
+            assert_eq!(is_error, Some(true), "Validation failure should be an error result");
+            assert!(
+                content.contains("Input validation failed"),
+                "Message should be the structured validation report, got: {}",
+                content
+            );
+            assert!(
+                content.contains("file_path"),
+                "Message should name the offending field, got: {}",
+                content
+            );
+        }
+        other => panic!("Expected a ToolResult, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_wrong_type_field_reports_instance_path() {
+    let executor = ToolExecutor::new();
+
+    // offset must be a number, not a string
+    let result = executor
+        .execute("Read", json!({"file_path": "/tmp/whatever.txt", "offset": "ten"}))
+        .await;
+    assert!(result.is_ok());
+
+    match result.unwrap() {
+        ContentPart::ToolResult {
+            content, is_error, ..
+        } => {
+            assert_eq!(is_error, Some(true));
+            assert!(
+                content.contains("/offset"),
+                "Message should include the instance path of the bad field, got: {}",
+                content
+            );
+        }
+        other => panic!("Expected a ToolResult, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_valid_input_still_executes() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let temp_path = temp_file.path().to_str().unwrap().to_string();
+    fs::write(&temp_path, "hello validation\n").unwrap();
+
+    let executor = ToolExecutor::new();
+    let result = executor
+        .execute("Read", json!({"file_path": temp_path}))
+        .await;
+    assert!(result.is_ok());
+
+    match result.unwrap() {
+        ContentPart::ToolResult {
+            content, is_error, ..
+        } => {
+            assert!(is_error.is_none(), "Valid input should not produce an error result");
+            assert!(
+                content.contains("hello validation"),
+                "Tool should have executed normally, got: {}",
+                content
+            );
+        }
+        other => panic!("Expected a ToolResult, got: {:?}", other),
+    }
+}